@@ -0,0 +1,260 @@
+use crate::config::sequence_config::AuthConfig;
+use crate::utils::error::{EtlError, Result};
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Token 到期前提早刷新的緩衝時間
+const EXPIRY_SKEW: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    expires_at: Option<Instant>,
+}
+
+impl CachedToken {
+    fn is_valid(&self) -> bool {
+        self.expires_at
+            .map(|expires_at| Instant::now() < expires_at)
+            .unwrap_or(true)
+    }
+}
+
+/// OAuth2 client credentials 認證：取得並快取 access token，過期或收到 401 時重新取得
+#[derive(Debug)]
+pub struct OAuth2ClientCredentials {
+    client: Client,
+    config: AuthConfig,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+impl OAuth2ClientCredentials {
+    pub fn new(client: Client, config: AuthConfig) -> Self {
+        Self {
+            client,
+            config,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// 取得有效的 access token（優先使用快取）
+    pub async fn access_token(&self) -> Result<String> {
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref().filter(|token| token.is_valid()) {
+            return Ok(token.access_token.clone());
+        }
+
+        let token = self.fetch_token().await?;
+        let access_token = token.access_token.clone();
+        *cached = Some(token);
+        Ok(access_token)
+    }
+
+    /// 捨棄快取的 token，下次呼叫時重新取得
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    async fn fetch_token(&self) -> Result<CachedToken> {
+        let token_url = required(&self.config.token_url, "source.auth.token_url")?;
+        let client_id = required(&self.config.client_id, "source.auth.client_id")?;
+        let client_secret = required(&self.config.client_secret, "source.auth.client_secret")?;
+
+        let mut form = vec![
+            ("grant_type", "client_credentials".to_string()),
+            ("client_id", client_id.to_string()),
+            ("client_secret", client_secret.to_string()),
+        ];
+        if let Some(scopes) = self.config.scopes.as_ref().filter(|s| !s.is_empty()) {
+            form.push(("scope", scopes.join(" ")));
+        }
+
+        tracing::info!("🔑 Requesting OAuth2 token from {}", token_url);
+        let response = self.client.post(token_url).form(&form).send().await?;
+
+        if !response.status().is_success() {
+            return Err(EtlError::AuthenticationError {
+                details: format!(
+                    "Token endpoint {} returned status {}",
+                    token_url,
+                    response.status()
+                ),
+            });
+        }
+
+        let token: TokenResponse =
+            response
+                .json()
+                .await
+                .map_err(|e| EtlError::AuthenticationError {
+                    details: format!("Invalid token response: {}", e),
+                })?;
+
+        let expires_at = token.expires_in.map(|seconds| {
+            Instant::now() + Duration::from_secs(seconds).saturating_sub(EXPIRY_SKEW)
+        });
+        tracing::debug!(
+            "🔑 OAuth2 token acquired, expires in {:?}s",
+            token.expires_in
+        );
+
+        Ok(CachedToken {
+            access_token: token.access_token,
+            expires_at,
+        })
+    }
+}
+
+/// 來源請求的認證方式，依 source.auth 的 type 建立
+#[derive(Debug)]
+pub enum SourceAuth {
+    OAuth2(Box<OAuth2ClientCredentials>),
+    Basic {
+        username_env: String,
+        password_env: String,
+    },
+    ApiKey {
+        key_env: String,
+        in_query: bool,
+        name: String,
+    },
+}
+
+impl SourceAuth {
+    pub fn new(client: Client, config: AuthConfig) -> Self {
+        match config.r#type.as_str() {
+            "basic" => Self::Basic {
+                username_env: config.username_env.clone().unwrap_or_default(),
+                password_env: config.password_env.clone().unwrap_or_default(),
+            },
+            "api_key" => Self::ApiKey {
+                key_env: config.key_env.clone().unwrap_or_default(),
+                in_query: config.placement() == "query",
+                name: config.key_name().to_string(),
+            },
+            _ => Self::OAuth2(Box::new(OAuth2ClientCredentials::new(client, config))),
+        }
+    }
+
+    /// 在請求上套用憑證；環境變數每次請求時讀取，輪替後不必重新啟動
+    pub async fn apply(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+        match self {
+            Self::OAuth2(auth) => Ok(request.bearer_auth(auth.access_token().await?)),
+            Self::Basic {
+                username_env,
+                password_env,
+            } => Ok(request.basic_auth(env_value(username_env)?, Some(env_value(password_env)?))),
+            Self::ApiKey {
+                key_env,
+                in_query,
+                name,
+            } => {
+                let key = env_value(key_env)?;
+                Ok(if *in_query {
+                    request.query(&[(name, key)])
+                } else {
+                    request.header(name.as_str(), key)
+                })
+            }
+        }
+    }
+
+    /// 收到 401 時能否刷新憑證後重試；只有 OAuth2 的 token 可重新取得
+    pub fn is_refreshable(&self) -> bool {
+        matches!(self, Self::OAuth2(_))
+    }
+
+    /// 捨棄快取的憑證，下次套用時重新取得
+    pub async fn invalidate(&self) {
+        if let Self::OAuth2(auth) = self {
+            auth.invalidate().await;
+        }
+    }
+}
+
+fn env_value(name: &str) -> Result<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| EtlError::MissingConfigError {
+            field: format!("environment variable {}", name),
+        })
+}
+
+fn required<'a>(value: &'a Option<String>, field: &str) -> Result<&'a str> {
+    value
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| EtlError::MissingConfigError {
+            field: field.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    fn auth_config(token_url: String) -> AuthConfig {
+        AuthConfig {
+            r#type: "oauth2".to_string(),
+            token_url: Some(token_url),
+            client_id: Some("etl".to_string()),
+            client_secret: Some("secret".to_string()),
+            scopes: Some(vec!["read".to_string(), "write".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_is_cached_until_invalidated() {
+        let server = MockServer::start();
+        let mut first = server.mock(|when, then| {
+            when.method(POST)
+                .path("/token")
+                .body_contains("grant_type=client_credentials")
+                .body_contains("scope=read+write");
+            then.status(200)
+                .json_body(serde_json::json!({"access_token": "t1", "expires_in": 3600}));
+        });
+
+        let auth = OAuth2ClientCredentials::new(Client::new(), auth_config(server.url("/token")));
+        assert_eq!(auth.access_token().await.unwrap(), "t1");
+        assert_eq!(auth.access_token().await.unwrap(), "t1");
+        first.assert_hits(1);
+
+        first.delete();
+        let second = server.mock(|when, then| {
+            when.method(POST).path("/token");
+            then.status(200)
+                .json_body(serde_json::json!({"access_token": "t2"}));
+        });
+
+        auth.invalidate().await;
+        assert_eq!(auth.access_token().await.unwrap(), "t2");
+        second.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_token_endpoint_failure() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/token");
+            then.status(401);
+        });
+
+        let auth = OAuth2ClientCredentials::new(Client::new(), auth_config(server.url("/token")));
+        assert!(matches!(
+            auth.access_token().await,
+            Err(EtlError::AuthenticationError { .. })
+        ));
+    }
+}
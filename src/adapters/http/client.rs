@@ -0,0 +1,202 @@
+use crate::config::sequence_config::HttpConfig;
+use crate::utils::error::Result;
+use reqwest::redirect::{Attempt, Policy};
+use reqwest::{Certificate, Client, Proxy};
+use std::time::Duration;
+
+/// 依 HTTP 設定建立用戶端
+///
+/// `carries_credentials` 表示請求會帶認證資訊（auth 區塊或 Authorization 標頭），
+/// 此時預設禁止跨主機的重新導向，避免憑證外洩到其他主機。
+pub fn build_client(http: Option<&HttpConfig>, carries_credentials: bool) -> Result<Client> {
+    HttpClientBuilder::new(http)
+        .carries_credentials(carries_credentials)
+        .build()
+}
+
+/// 依 `[source.http]` 設定建立 reqwest 用戶端（代理、CA 憑證、TLS 驗證、連線池、逾時）
+#[derive(Debug, Clone, Default)]
+pub struct HttpClientBuilder {
+    http: HttpConfig,
+    carries_credentials: bool,
+    timeout: Option<Duration>,
+}
+
+impl HttpClientBuilder {
+    pub fn new(http: Option<&HttpConfig>) -> Self {
+        Self {
+            http: http.cloned().unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// 請求帶有認證資訊時，預設禁止跨主機的重新導向
+    pub fn carries_credentials(mut self, carries_credentials: bool) -> Self {
+        self.carries_credentials = carries_credentials;
+        self
+    }
+
+    /// 整個請求的逾時（各請求也可再以 `RequestBuilder::timeout` 覆寫）
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let http = &self.http;
+        let max_redirects = http.max_redirects();
+        let block_cross_host = self.carries_credentials && !http.allow_cross_host_auth_redirects();
+        let policy = Policy::custom(move |attempt| {
+            redirect_decision(attempt, max_redirects, block_cross_host)
+        });
+
+        let mut builder = Client::builder().redirect(policy);
+        if http.http2_prior_knowledge.unwrap_or(false) {
+            builder = builder.http2_prior_knowledge();
+        }
+        if http.http2_adaptive_window.unwrap_or(false) {
+            builder = builder.http2_adaptive_window(true);
+        }
+        if let Some(proxy) = &http.proxy {
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        if let Some(ca_cert) = &http.ca_cert {
+            builder = builder.add_root_certificate(load_certificate(ca_cert)?);
+        }
+        if !http.verify_tls() {
+            tracing::warn!("🔓 TLS certificate verification is disabled");
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        if let Some(pool_size) = http.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(pool_size);
+        }
+        if let Some(user_agent) = &http.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(timeout) = http.connect_timeout() {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = http.read_timeout() {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// 讀取 PEM 格式的 CA 憑證
+pub fn load_certificate(path: &str) -> Result<Certificate> {
+    let pem = std::fs::read(path)?;
+    Ok(Certificate::from_pem(&pem)?)
+}
+
+fn redirect_decision(
+    attempt: Attempt,
+    max_redirects: usize,
+    block_cross_host: bool,
+) -> reqwest::redirect::Action {
+    if max_redirects == 0 {
+        // 不追隨，直接返回 3xx 回應
+        return attempt.stop();
+    }
+    if attempt.previous().len() > max_redirects {
+        return attempt.error(format!("Too many redirects (max {})", max_redirects));
+    }
+    if block_cross_host {
+        if let Some(original) = attempt.previous().first() {
+            if original.origin() != attempt.url().origin() {
+                let message = format!(
+                    "Blocked cross-host redirect from {} to {} for a request carrying credentials",
+                    original.origin().ascii_serialization(),
+                    attempt.url().origin().ascii_serialization()
+                );
+                tracing::warn!("🔒 {}", message);
+                return attempt.error(message);
+            }
+        }
+    }
+    attempt.follow()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    fn redirecting_servers() -> (MockServer, MockServer) {
+        let target = MockServer::start();
+        target.mock(|when, then| {
+            when.method(GET).path("/target");
+            then.status(200).body("ok");
+        });
+        let origin = MockServer::start();
+        let location = target.url("/target");
+        origin.mock(move |when, then| {
+            when.method(GET).path("/start");
+            then.status(302).header("Location", location.as_str());
+        });
+        (origin, target)
+    }
+
+    #[tokio::test]
+    async fn test_cross_host_redirect_blocked_with_credentials() {
+        let (origin, _target) = redirecting_servers();
+
+        let client = build_client(None, true).unwrap();
+        assert!(client.get(origin.url("/start")).send().await.is_err());
+
+        let client = build_client(None, false).unwrap();
+        let response = client.get(origin.url("/start")).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_redirects_disabled() {
+        let (origin, _target) = redirecting_servers();
+        let http = HttpConfig {
+            max_redirects: Some(0),
+            ..Default::default()
+        };
+
+        let client = build_client(Some(&http), false).unwrap();
+        let response = client.get(origin.url("/start")).send().await.unwrap();
+        assert_eq!(response.status(), 302);
+    }
+
+    #[tokio::test]
+    async fn test_builder_applies_user_agent_and_validates_settings() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/data")
+                .header("user-agent", "small-etl/nightly");
+            then.status(200).body("ok");
+        });
+        let http = HttpConfig {
+            user_agent: Some("small-etl/nightly".to_string()),
+            pool_max_idle_per_host: Some(2),
+            connect_timeout_seconds: Some(5),
+            read_timeout_seconds: Some(30),
+            ..Default::default()
+        };
+
+        let client = HttpClientBuilder::new(Some(&http)).build().unwrap();
+        let response = client.get(server.url("/data")).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        mock.assert();
+
+        let invalid = HttpConfig {
+            ca_cert: Some("/nonexistent/ca.pem".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.validate("source.http").is_err());
+        let invalid = HttpConfig {
+            read_timeout_seconds: Some(0),
+            ..Default::default()
+        };
+        assert!(invalid.validate("source.http").is_err());
+    }
+}
@@ -0,0 +1,7 @@
+// HTTP 用戶端相關的適配器（認證等）
+
+pub mod auth;
+pub mod client;
+
+pub use auth::{OAuth2ClientCredentials, SourceAuth};
+pub use client::{build_client, HttpClientBuilder};
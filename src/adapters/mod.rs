@@ -5,10 +5,8 @@ pub mod config {
     // Placeholder for configuration providers (cli, lambda, toml, sequence)
 }
 
-pub mod storage {
-    // Placeholder for storage backends (local, s3)
-}
+pub mod storage;
 
-pub mod http {
-    // Placeholder for http client wrappers
-}
+pub mod http;
+
+pub mod queue;
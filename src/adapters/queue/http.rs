@@ -0,0 +1,174 @@
+use super::QueueMessage;
+use crate::adapters::http::build_client;
+use crate::config::sequence_config::QueueConfig;
+use crate::utils::error::{EtlError, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// 佇列端點返回的訊息
+#[derive(Debug, Deserialize)]
+struct HttpMessage {
+    id: String,
+    #[serde(default)]
+    body: serde_json::Value,
+    attempts: Option<u32>,
+}
+
+/// 以 HTTP 輪詢的通用佇列
+///
+/// - `GET {url}?wait_seconds=N`：返回訊息陣列 `[{"id": "...", "body": {...}, "attempts": 1}]`，
+///   沒有訊息時返回空陣列或 204
+/// - `DELETE {url}/{id}`：處理完成
+/// - `POST {url}/{id}/retry`：放回佇列，內容為 `{"delay_seconds": N}`
+pub struct HttpQueue {
+    client: Client,
+    url: String,
+    headers: HeaderMap,
+}
+
+impl HttpQueue {
+    pub fn new(config: &QueueConfig) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in config.headers.iter().flatten() {
+            let invalid = |reason: String| EtlError::InvalidConfigValueError {
+                field: format!("sequence.queue.headers.{}", name),
+                value: "(hidden)".to_string(),
+                reason,
+            };
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|e| invalid(e.to_string()))?,
+                HeaderValue::from_str(value).map_err(|e| invalid(e.to_string()))?,
+            );
+        }
+        Ok(Self {
+            client: build_client(None, !headers.is_empty())?,
+            url: config.url.trim_end_matches('/').to_string(),
+            headers,
+        })
+    }
+
+    fn message_url(&self, message: &QueueMessage) -> String {
+        format!("{}/{}", self.url, urlencode(&message.receipt))
+    }
+
+    pub async fn receive(&self, wait: Duration) -> Result<Vec<QueueMessage>> {
+        let response = self
+            .client
+            .get(&self.url)
+            .headers(self.headers.clone())
+            .query(&[("wait_seconds", wait.as_secs())])
+            .timeout(wait + Duration::from_secs(30))
+            .send()
+            .await?
+            .error_for_status()?;
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(Vec::new());
+        }
+        let messages: Vec<HttpMessage> = response.json().await?;
+        Ok(messages
+            .into_iter()
+            .map(|message| QueueMessage {
+                receipt: message.id.clone(),
+                id: message.id,
+                body: match message.body {
+                    serde_json::Value::String(body) => body,
+                    serde_json::Value::Null => String::new(),
+                    body => body.to_string(),
+                },
+                attempts: message.attempts.unwrap_or(1),
+            })
+            .collect())
+    }
+
+    pub async fn ack(&self, message: &QueueMessage) -> Result<()> {
+        self.client
+            .delete(self.message_url(message))
+            .headers(self.headers.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn retry(&self, message: &QueueMessage, delay: Duration) -> Result<()> {
+        self.client
+            .post(format!("{}/retry", self.message_url(message)))
+            .headers(self.headers.clone())
+            .json(&serde_json::json!({ "delay_seconds": delay.as_secs() }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// 訊息 ID 作為路徑段時的編碼
+fn urlencode(segment: &str) -> String {
+    url::form_urlencoded::byte_serialize(segment.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    fn queue(server: &MockServer) -> HttpQueue {
+        HttpQueue::new(&QueueConfig {
+            r#type: "http".to_string(),
+            url: server.url("/queue/"),
+            region: None,
+            headers: Some([("X-Token".to_string(), "secret".to_string())].into()),
+            max_attempts: None,
+            retry_delay_seconds: None,
+            poll_interval_seconds: None,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_receive_ack_and_retry() {
+        let server = MockServer::start();
+        let receive = server.mock(|when, then| {
+            when.method(GET)
+                .path("/queue")
+                .query_param("wait_seconds", "5")
+                .header("X-Token", "secret");
+            then.status(200).json_body(serde_json::json!([
+                {"id": "m 1", "body": {"variables": {"REGION": "eu"}}, "attempts": 2},
+                {"id": "m2", "body": "{\"only\": [\"users\"]}"},
+                {"id": "m3"}
+            ]));
+        });
+        let ack = server.mock(|when, then| {
+            when.method(DELETE).path("/queue/m%201");
+            then.status(204);
+        });
+        let retry = server.mock(|when, then| {
+            when.method(POST)
+                .path("/queue/m2/retry")
+                .json_body(serde_json::json!({"delay_seconds": 60}));
+            then.status(202);
+        });
+
+        let queue = queue(&server);
+        let messages = queue.receive(Duration::from_secs(5)).await.unwrap();
+        receive.assert();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].attempts, 2);
+        assert_eq!(messages[0].run_request().unwrap().variables["REGION"], "eu");
+        assert_eq!(messages[1].run_request().unwrap().only, ["users"]);
+        assert_eq!(messages[2].body, "");
+
+        queue.ack(&messages[0]).await.unwrap();
+        ack.assert();
+        queue
+            .retry(&messages[1], Duration::from_secs(60))
+            .await
+            .unwrap();
+        retry.assert();
+    }
+}
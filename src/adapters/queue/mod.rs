@@ -0,0 +1,160 @@
+// 佇列適配器：從 SQS 或 HTTP 佇列取得觸發序列執行的訊息
+
+pub mod http;
+pub mod sqs;
+
+pub use http::HttpQueue;
+#[cfg(feature = "sqs")]
+pub use sqs::SqsQueue;
+
+use crate::config::sequence_config::QueueConfig;
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 訊息內容：一次序列執行的變數與 Pipeline 篩選
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunRequest {
+    pub execution_id: Option<String>, // 未指定時以訊息 ID 產生
+    #[serde(default, alias = "parameters")]
+    pub variables: HashMap<String, String>, // 覆寫 [global.variables] 與 --var
+    #[serde(default)]
+    pub only: Vec<String>,
+    #[serde(default)]
+    pub skip: Vec<String>,
+}
+
+/// 從佇列收到的訊息
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueMessage {
+    pub id: String,
+    pub receipt: String, // 確認或重新排入時使用（SQS 的 receipt handle；HTTP 佇列為訊息 ID）
+    pub body: String,
+    pub attempts: u32, // 含這次在內的接收次數
+}
+
+impl QueueMessage {
+    /// 解析訊息內容；空白內容表示使用預設值執行
+    pub fn run_request(&self) -> Result<RunRequest> {
+        if self.body.trim().is_empty() {
+            return Ok(RunRequest::default());
+        }
+        serde_json::from_str(&self.body).map_err(|e| EtlError::DataValidationError {
+            message: format!("Invalid queue message {}: {}", self.id, e),
+        })
+    }
+
+    /// 執行 ID：訊息指定的 ID，否則為 `queue_{訊息 ID}`；重試時沿用同一個 ID 以便續跑
+    pub fn execution_id(&self, request: &RunRequest) -> String {
+        request.execution_id.clone().unwrap_or_else(|| {
+            let id: String = self
+                .id
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            format!("queue_{}", id)
+        })
+    }
+}
+
+/// 設定的佇列（sequence.queue）
+pub enum MessageQueue {
+    Http(HttpQueue),
+    #[cfg(feature = "sqs")]
+    Sqs(Box<SqsQueue>),
+}
+
+impl MessageQueue {
+    pub async fn from_config(config: &QueueConfig) -> Result<Self> {
+        match config.r#type.as_str() {
+            "http" => Ok(Self::Http(HttpQueue::new(config)?)),
+            #[cfg(feature = "sqs")]
+            "sqs" => Ok(Self::Sqs(Box::new(SqsQueue::new(config).await))),
+            #[cfg(not(feature = "sqs"))]
+            "sqs" => Err(EtlError::ConfigValidationError {
+                field: "sequence.queue.type".to_string(),
+                message: "SQS queues require building with --features sqs".to_string(),
+            }),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "sequence.queue.type".to_string(),
+                value: other.to_string(),
+                reason: format!(
+                    "Supported types: {}",
+                    QueueConfig::SUPPORTED_TYPES.join(", ")
+                ),
+            }),
+        }
+    }
+
+    /// 取得下一批訊息；沒有訊息時最多等待 `wait` 後返回空清單
+    pub async fn receive(&self, wait: Duration) -> Result<Vec<QueueMessage>> {
+        match self {
+            Self::Http(queue) => queue.receive(wait).await,
+            #[cfg(feature = "sqs")]
+            Self::Sqs(queue) => queue.receive(wait).await,
+        }
+    }
+
+    /// 處理完成，從佇列刪除
+    pub async fn ack(&self, message: &QueueMessage) -> Result<()> {
+        match self {
+            Self::Http(queue) => queue.ack(message).await,
+            #[cfg(feature = "sqs")]
+            Self::Sqs(queue) => queue.ack(message).await,
+        }
+    }
+
+    /// 放回佇列，`delay` 後可再次取得
+    pub async fn retry(&self, message: &QueueMessage, delay: Duration) -> Result<()> {
+        match self {
+            Self::Http(queue) => queue.retry(message, delay).await,
+            #[cfg(feature = "sqs")]
+            Self::Sqs(queue) => queue.retry(message, delay).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, body: &str) -> QueueMessage {
+        QueueMessage {
+            id: id.to_string(),
+            receipt: id.to_string(),
+            body: body.to_string(),
+            attempts: 1,
+        }
+    }
+
+    #[test]
+    fn test_run_request_from_message() {
+        let msg = message(
+            "4f1c/9a",
+            r#"{"parameters": {"REGION": "eu"}, "only": ["users"]}"#,
+        );
+        let request = msg.run_request().unwrap();
+        assert_eq!(request.variables["REGION"], "eu");
+        assert_eq!(request.only, ["users"]);
+        assert_eq!(msg.execution_id(&request), "queue_4f1c_9a");
+
+        let request = message("1", r#"{"execution_id": "daily_eu"}"#)
+            .run_request()
+            .unwrap();
+        assert_eq!(message("1", "").execution_id(&request), "daily_eu");
+
+        assert_eq!(
+            message("2", " ").run_request().unwrap(),
+            RunRequest::default()
+        );
+        assert!(message("3", r#"{"unknown": 1}"#).run_request().is_err());
+    }
+}
@@ -0,0 +1,103 @@
+// Amazon SQS 佇列（需以 --features sqs 編譯）
+
+#[cfg(feature = "sqs")]
+use super::QueueMessage;
+#[cfg(feature = "sqs")]
+use crate::config::sequence_config::QueueConfig;
+#[cfg(feature = "sqs")]
+use crate::utils::error::{EtlError, Result};
+#[cfg(feature = "sqs")]
+use aws_sdk_sqs::types::MessageSystemAttributeName;
+#[cfg(feature = "sqs")]
+use aws_sdk_sqs::Client;
+#[cfg(feature = "sqs")]
+use std::time::Duration;
+
+/// 以長輪詢讀取 SQS 佇列；重新排入以調整可見逾時實作
+///
+/// 接收次數取自 ApproximateReceiveCount，佇列本身的 redrive policy 仍然有效。
+#[cfg(feature = "sqs")]
+pub struct SqsQueue {
+    client: Client,
+    url: String,
+}
+
+#[cfg(feature = "sqs")]
+impl SqsQueue {
+    pub async fn new(config: &QueueConfig) -> Self {
+        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+        if let Some(region) = &config.region {
+            loader = loader.region(aws_config::Region::new(region.clone()));
+        }
+        Self {
+            client: Client::new(&loader.load().await),
+            url: config.url.clone(),
+        }
+    }
+
+    pub async fn receive(&self, wait: Duration) -> Result<Vec<QueueMessage>> {
+        let output = self
+            .client
+            .receive_message()
+            .queue_url(&self.url)
+            .max_number_of_messages(10)
+            .wait_time_seconds(wait.as_secs().min(20) as i32)
+            .message_system_attribute_names(MessageSystemAttributeName::ApproximateReceiveCount)
+            .send()
+            .await
+            .map_err(|e| sqs_error("receive messages", e))?;
+
+        Ok(output
+            .messages
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|message| {
+                let attempts = message
+                    .attributes
+                    .as_ref()
+                    .and_then(|attributes| {
+                        attributes.get(&MessageSystemAttributeName::ApproximateReceiveCount)
+                    })
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(1);
+                Some(QueueMessage {
+                    id: message.message_id?,
+                    receipt: message.receipt_handle?,
+                    body: message.body.unwrap_or_default(),
+                    attempts,
+                })
+            })
+            .collect())
+    }
+
+    pub async fn ack(&self, message: &QueueMessage) -> Result<()> {
+        self.client
+            .delete_message()
+            .queue_url(&self.url)
+            .receipt_handle(&message.receipt)
+            .send()
+            .await
+            .map_err(|e| sqs_error("delete message", e))?;
+        Ok(())
+    }
+
+    pub async fn retry(&self, message: &QueueMessage, delay: Duration) -> Result<()> {
+        // SQS 的可見逾時上限為 12 小時
+        self.client
+            .change_message_visibility()
+            .queue_url(&self.url)
+            .receipt_handle(&message.receipt)
+            .visibility_timeout(delay.as_secs().min(43_200) as i32)
+            .send()
+            .await
+            .map_err(|e| sqs_error("requeue message", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqs")]
+fn sqs_error(action: &str, error: impl std::fmt::Display) -> EtlError {
+    EtlError::ServiceUnavailableError {
+        service: format!("SQS ({}): {}", action, error),
+    }
+}
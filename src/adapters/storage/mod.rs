@@ -0,0 +1,75 @@
+// 輸出存儲的適配器：依 load.output_path 選擇本機目錄或 SFTP
+
+pub mod sftp;
+
+pub use sftp::{SftpStorage, SftpTarget};
+
+use crate::config::cli::LocalStorage;
+use crate::config::sequence_config::LoadConfig;
+use crate::core::Storage;
+use crate::utils::error::Result;
+
+/// Pipeline 的輸出存儲；`sftp://` 開頭的 output_path 寫到 SFTP，其餘寫到本機
+#[derive(Debug)]
+pub enum PipelineStorage {
+    Local(LocalStorage),
+    Sftp(Box<SftpStorage>),
+}
+
+impl PipelineStorage {
+    pub fn from_load_config(load: &LoadConfig) -> Result<Self> {
+        if SftpTarget::is_sftp(&load.output_path) {
+            let target = SftpTarget::parse(&load.output_path, "load.output_path")?;
+            Ok(Self::Sftp(Box::new(SftpStorage::new(
+                target,
+                load.sftp.clone().unwrap_or_default(),
+            ))))
+        } else {
+            Ok(Self::Local(LocalStorage::new(load.output_path.clone())))
+        }
+    }
+}
+
+impl Storage for PipelineStorage {
+    async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Local(storage) => storage.read_file(path).await,
+            Self::Sftp(storage) => storage.read_file(path).await,
+        }
+    }
+
+    async fn write_file(&self, path: &str, data: &[u8]) -> Result<()> {
+        match self {
+            Self::Local(storage) => storage.write_file(path, data).await,
+            Self::Sftp(storage) => storage.write_file(path, data).await,
+        }
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        match self {
+            Self::Local(storage) => storage.exists(path).await,
+            Self::Sftp(storage) => storage.exists(path).await,
+        }
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<()> {
+        match self {
+            Self::Local(storage) => storage.rename(from, to).await,
+            Self::Sftp(storage) => storage.rename(from, to).await,
+        }
+    }
+
+    async fn remove(&self, path: &str) -> Result<()> {
+        match self {
+            Self::Local(storage) => storage.remove(path).await,
+            Self::Sftp(storage) => storage.remove(path).await,
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        match self {
+            Self::Local(storage) => storage.list(prefix).await,
+            Self::Sftp(storage) => storage.list(prefix).await,
+        }
+    }
+}
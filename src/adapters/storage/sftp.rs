@@ -0,0 +1,499 @@
+use crate::config::sequence_config::SftpConfig;
+use crate::core::Storage;
+use crate::utils::error::{EtlError, Result};
+
+const SCHEME: &str = "sftp://";
+
+/// `sftp://user@host:port/dir` 形式的輸出位置；未指定埠號時為 22，未指定目錄時為登入目錄
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SftpTarget {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub base_dir: String,
+}
+
+impl SftpTarget {
+    pub fn is_sftp(path: &str) -> bool {
+        path.starts_with(SCHEME)
+    }
+
+    pub fn parse(url: &str, field: &str) -> Result<Self> {
+        let invalid = |reason: &str| EtlError::InvalidConfigValueError {
+            field: field.to_string(),
+            value: url.to_string(),
+            reason: reason.to_string(),
+        };
+        let parsed = url::Url::parse(url).map_err(|e| invalid(&e.to_string()))?;
+        if parsed.scheme() != "sftp" {
+            return Err(invalid("Expected an sftp:// URL"));
+        }
+        let host = parsed
+            .host_str()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| invalid("Missing host"))?;
+        let username = match parsed.username() {
+            "" => None,
+            username => Some(decode(username)),
+        };
+        Ok(Self {
+            host: host.to_string(),
+            port: parsed.port().unwrap_or(22),
+            username,
+            base_dir: match decode(parsed.path()).trim_end_matches('/') {
+                "" => ".".to_string(),
+                path => path.to_string(),
+            },
+        })
+    }
+
+    /// 存儲收到的路徑相對於 base_dir；完整的 sftp:// URL 則直接使用其中的路徑
+    pub fn remote_path(&self, path: &str) -> String {
+        if Self::is_sftp(path) {
+            if let Ok(target) = Self::parse(path, "path") {
+                return target.base_dir;
+            }
+        }
+        let path = path.trim_start_matches("./");
+        if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.base_dir, path)
+        }
+    }
+}
+
+/// 還原 URL 中的百分比編碼（例如使用者名稱中的 %40）
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| value.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// 將輸出寫到 SFTP 伺服器（需以 --features sftp 編譯）
+///
+/// 第一次讀寫時才連線並沿用同一個工作階段；寫入先寫到 `.part` 暫存檔再改名，
+/// 對方不會讀到寫到一半的檔案。主機金鑰必須已記錄在 known_hosts 中。
+pub struct SftpStorage {
+    target: SftpTarget,
+    config: SftpConfig,
+    #[cfg(feature = "sftp")]
+    connection: tokio::sync::Mutex<Option<connection::Connection>>,
+}
+
+impl std::fmt::Debug for SftpStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SftpStorage")
+            .field("target", &self.target)
+            .field(
+                "username",
+                &self
+                    .config
+                    .username
+                    .as_ref()
+                    .or(self.target.username.as_ref()),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl SftpStorage {
+    pub fn new(target: SftpTarget, config: SftpConfig) -> Self {
+        Self {
+            target,
+            config,
+            #[cfg(feature = "sftp")]
+            connection: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    pub fn target(&self) -> &SftpTarget {
+        &self.target
+    }
+
+    #[cfg(feature = "sftp")]
+    fn username(&self) -> Result<&str> {
+        self.config
+            .username
+            .as_deref()
+            .or(self.target.username.as_deref())
+            .ok_or_else(|| EtlError::MissingConfigError {
+                field: "load.sftp.username".to_string(),
+            })
+    }
+}
+
+#[cfg(feature = "sftp")]
+impl Storage for SftpStorage {
+    async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        let remote_path = self.target.remote_path(path);
+        let mut connection = self.connection.lock().await;
+        let sftp = self.session(&mut connection).await?;
+        match sftp.read(remote_path.as_str()).await {
+            Ok(data) => Ok(data),
+            // 檔案不存在時保留 NotFound，讓呼叫端與其他讀取錯誤區分
+            Err(russh_sftp::client::error::Error::Status(status))
+                if status.status_code == russh_sftp::protocol::StatusCode::NoSuchFile =>
+            {
+                Err(EtlError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("SFTP read '{}' failed: no such file", remote_path),
+                )))
+            }
+            Err(e) => {
+                // 連線中斷時下次重新連線
+                *connection = None;
+                Err(sftp_error("read", &remote_path, e))
+            }
+        }
+    }
+
+    async fn write_file(&self, path: &str, data: &[u8]) -> Result<()> {
+        let remote_path = self.target.remote_path(path);
+        let mut connection = self.connection.lock().await;
+        let sftp = self.session(&mut connection).await?;
+        let result = connection::write_atomic(sftp, &remote_path, data).await;
+        if result.is_err() {
+            *connection = None;
+        }
+        result?;
+        tracing::debug!(
+            "📤 Uploaded {} bytes to sftp://{}{}",
+            data.len(),
+            self.target.host,
+            remote_path
+        );
+        Ok(())
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        let remote_path = self.target.remote_path(path);
+        let mut connection = self.connection.lock().await;
+        let sftp = self.session(&mut connection).await?;
+        match sftp.try_exists(remote_path.as_str()).await {
+            Ok(exists) => Ok(exists),
+            Err(e) => {
+                *connection = None;
+                Err(sftp_error("stat", &remote_path, e))
+            }
+        }
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<()> {
+        let (from, to) = (self.target.remote_path(from), self.target.remote_path(to));
+        let mut connection = self.connection.lock().await;
+        let sftp = self.session(&mut connection).await?;
+        let result = connection::rename_replacing(sftp, &from, &to).await;
+        if result.is_err() {
+            *connection = None;
+        }
+        result
+    }
+
+    async fn remove(&self, path: &str) -> Result<()> {
+        let remote_path = self.target.remote_path(path);
+        let mut connection = self.connection.lock().await;
+        let sftp = self.session(&mut connection).await?;
+        if !sftp.try_exists(remote_path.as_str()).await.unwrap_or(false) {
+            return Ok(());
+        }
+        sftp.remove_file(remote_path.as_str())
+            .await
+            .map_err(|e| sftp_error("remove", &remote_path, e))
+    }
+}
+
+#[cfg(feature = "sftp")]
+impl SftpStorage {
+    async fn session<'a>(
+        &self,
+        connection: &'a mut Option<connection::Connection>,
+    ) -> Result<&'a russh_sftp::client::SftpSession> {
+        if connection.is_none() {
+            *connection = Some(
+                connection::Connection::open(&self.target, &self.config, self.username()?).await?,
+            );
+        }
+        Ok(&connection
+            .as_ref()
+            .expect("connection was just opened")
+            .sftp)
+    }
+}
+
+#[cfg(feature = "sftp")]
+fn sftp_error(operation: &str, path: &str, error: impl std::fmt::Display) -> EtlError {
+    EtlError::IoError(std::io::Error::other(format!(
+        "SFTP {} '{}' failed: {}",
+        operation, path, error
+    )))
+}
+
+/// 未啟用 sftp 功能時無法連線，設定 sftp:// output_path 會在驗證時失敗
+#[cfg(not(feature = "sftp"))]
+impl Storage for SftpStorage {
+    async fn read_file(&self, _path: &str) -> Result<Vec<u8>> {
+        Err(self.unsupported())
+    }
+
+    async fn write_file(&self, _path: &str, _data: &[u8]) -> Result<()> {
+        Err(self.unsupported())
+    }
+}
+
+#[cfg(not(feature = "sftp"))]
+impl SftpStorage {
+    fn unsupported(&self) -> EtlError {
+        EtlError::ConfigValidationError {
+            field: "load.output_path".to_string(),
+            message: "SFTP output requires building with --features sftp".to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "sftp")]
+mod connection {
+    use super::{sftp_error, SftpTarget};
+    use crate::config::sequence_config::SftpConfig;
+    use crate::utils::error::{EtlError, Result};
+    use russh::client::{self, Handle};
+    use russh::keys::{PrivateKeyWithHashAlg, PublicKeyOrCertificate};
+    use russh_sftp::client::SftpSession;
+    use std::sync::Arc;
+
+    pub(super) struct Connection {
+        pub(super) sftp: SftpSession,
+        _handle: Handle<HostKeyCheck>,
+    }
+
+    /// 只接受 known_hosts 中已記錄的主機金鑰
+    pub(super) struct HostKeyCheck {
+        host: String,
+        port: u16,
+        known_hosts_path: Option<String>,
+    }
+
+    impl client::Handler for HostKeyCheck {
+        type Error = russh::Error;
+
+        async fn check_server_key(
+            &mut self,
+            server_public_key: &PublicKeyOrCertificate,
+        ) -> std::result::Result<bool, Self::Error> {
+            let PublicKeyOrCertificate::PublicKey { key, .. } = server_public_key else {
+                tracing::warn!(
+                    "🔐 SFTP host {} presented a certificate, which is not supported",
+                    self.host
+                );
+                return Ok(false);
+            };
+            let known = match &self.known_hosts_path {
+                Some(path) => russh::keys::check_known_hosts_path(&self.host, self.port, key, path),
+                None => russh::keys::check_known_hosts(&self.host, self.port, key),
+            };
+            match known {
+                Ok(true) => Ok(true),
+                Ok(false) => {
+                    tracing::warn!(
+                        "🔐 SFTP host {}:{} is not in known_hosts; add its key with ssh-keyscan",
+                        self.host,
+                        self.port
+                    );
+                    Ok(false)
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "🔐 SFTP host key check for {}:{} failed: {}",
+                        self.host,
+                        self.port,
+                        e
+                    );
+                    Ok(false)
+                }
+            }
+        }
+    }
+
+    impl Connection {
+        pub(super) async fn open(
+            target: &SftpTarget,
+            config: &SftpConfig,
+            username: &str,
+        ) -> Result<Self> {
+            let address = format!("{}:{}", target.host, target.port);
+            tracing::info!("📤 Connecting to SFTP server {} as {}", address, username);
+            let handler = HostKeyCheck {
+                host: target.host.clone(),
+                port: target.port,
+                known_hosts_path: config.known_hosts_path.clone(),
+            };
+            let ssh_config = Arc::new(client::Config {
+                inactivity_timeout: Some(config.timeout()),
+                ..Default::default()
+            });
+            let mut handle = tokio::time::timeout(
+                config.timeout(),
+                client::connect(ssh_config, (target.host.as_str(), target.port), handler),
+            )
+            .await
+            .map_err(|_| EtlError::TimeoutError {
+                operation: format!("SFTP connect to {}", address),
+                timeout_seconds: config.timeout().as_secs(),
+            })?
+            .map_err(|e| sftp_error("connect", &address, e))?;
+
+            let authenticated = match (&config.private_key_path, &config.password) {
+                (Some(key_path), _) => {
+                    let key = russh::keys::load_secret_key(
+                        key_path,
+                        config.private_key_passphrase.as_deref(),
+                    )
+                    .map_err(|e| EtlError::InvalidConfigValueError {
+                        field: "load.sftp.private_key_path".to_string(),
+                        value: key_path.clone(),
+                        reason: format!("Cannot load private key: {}", e),
+                    })?;
+                    let hash_alg = handle
+                        .best_supported_rsa_hash()
+                        .await
+                        .map_err(|e| sftp_error("connect", &address, e))?
+                        .flatten();
+                    handle
+                        .authenticate_publickey(
+                            username,
+                            PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg),
+                        )
+                        .await
+                }
+                (None, Some(password)) => handle.authenticate_password(username, password).await,
+                (None, None) => {
+                    return Err(EtlError::MissingConfigError {
+                        field: "load.sftp.private_key_path".to_string(),
+                    })
+                }
+            }
+            .map_err(|e| sftp_error("authenticate", &address, e))?;
+            if !authenticated.success() {
+                return Err(EtlError::AuthenticationError {
+                    details: format!("SFTP login as {} to {} was rejected", username, address),
+                });
+            }
+
+            let channel = handle
+                .channel_open_session()
+                .await
+                .map_err(|e| sftp_error("open session", &address, e))?;
+            channel
+                .request_subsystem(true, "sftp")
+                .await
+                .map_err(|e| sftp_error("open session", &address, e))?;
+            let sftp = SftpSession::new(channel.into_stream())
+                .await
+                .map_err(|e| sftp_error("open session", &address, e))?;
+            sftp.set_timeout(config.timeout().as_secs());
+            Ok(Self {
+                sftp,
+                _handle: handle,
+            })
+        }
+    }
+
+    /// 建立上層目錄後寫入 `.part` 暫存檔，完成後改名為目標檔名
+    pub(super) async fn write_atomic(sftp: &SftpSession, path: &str, data: &[u8]) -> Result<()> {
+        if let Some((parent, _)) = path.rsplit_once('/') {
+            create_dir_all(sftp, parent).await?;
+        }
+        let part_path = format!("{}.part", path);
+        sftp.create(part_path.as_str())
+            .await
+            .map_err(|e| sftp_error("write", &part_path, e))?;
+        sftp.write(part_path.as_str(), data)
+            .await
+            .map_err(|e| sftp_error("write", &part_path, e))?;
+        rename_replacing(sftp, &part_path, path).await
+    }
+
+    /// SFTP v3 的改名不會覆蓋既有檔案，先刪除目的檔
+    pub(super) async fn rename_replacing(sftp: &SftpSession, from: &str, to: &str) -> Result<()> {
+        if sftp.try_exists(to).await.unwrap_or(false) {
+            sftp.remove_file(to)
+                .await
+                .map_err(|e| sftp_error("replace", to, e))?;
+        }
+        sftp.rename(from, to)
+            .await
+            .map_err(|e| sftp_error("rename", to, e))
+    }
+
+    async fn create_dir_all(sftp: &SftpSession, dir: &str) -> Result<()> {
+        let mut current = String::new();
+        for component in dir.split('/') {
+            if component.is_empty() {
+                current.push('/');
+                continue;
+            }
+            if !current.is_empty() && !current.ends_with('/') {
+                current.push('/');
+            }
+            current.push_str(component);
+            if component == "." || sftp.try_exists(current.as_str()).await.unwrap_or(false) {
+                continue;
+            }
+            sftp.create_dir(current.as_str())
+                .await
+                .map_err(|e| sftp_error("create directory", &current, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target_and_remote_paths() {
+        let target = SftpTarget::parse(
+            "sftp://etl%40corp@partner.example.com:2222/inbox/daily/",
+            "load.output_path",
+        )
+        .unwrap();
+        assert_eq!(target.host, "partner.example.com");
+        assert_eq!(target.port, 2222);
+        assert_eq!(target.username.as_deref(), Some("etl@corp"));
+        assert_eq!(target.base_dir, "/inbox/daily");
+        assert_eq!(
+            target.remote_path("users_output.zip"),
+            "/inbox/daily/users_output.zip"
+        );
+        assert_eq!(
+            target.remote_path("sftp://partner.example.com/inbox/daily/users_rejects.json"),
+            "/inbox/daily/users_rejects.json"
+        );
+
+        let home = SftpTarget::parse("sftp://partner.example.com", "load.output_path").unwrap();
+        assert_eq!(home.port, 22);
+        assert_eq!(home.username, None);
+        assert_eq!(home.remote_path("out.zip"), "./out.zip");
+
+        assert!(SftpTarget::parse("sftp:///inbox", "load.output_path").is_err());
+        assert!(SftpTarget::parse("ftp://host/inbox", "load.output_path").is_err());
+    }
+}
@@ -12,3 +12,6 @@ pub mod lambda {
 }
 
 pub mod pipelines;
+
+// 嵌入式 API：其他 Rust 服務（例如 Airflow/Dagster 的工作程序）直接執行序列
+pub use pipelines::sequence_runner::{run_sequence, RunOptions};
@@ -1,3 +1,14 @@
 pub mod mvp_pipeline;
+pub mod pipeline_builder;
+pub mod sequence_backfill;
+pub mod sequence_batch;
+pub mod sequence_dry_run;
+pub mod sequence_engine;
+pub mod sequence_foreach;
+pub mod sequence_init;
+pub mod sequence_lint;
 pub mod sequence_pipeline;
+pub mod sequence_runner;
+pub mod shared_data;
 pub mod simple_pipeline;
+pub mod stream_transform;
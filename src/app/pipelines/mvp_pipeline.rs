@@ -1,24 +1,38 @@
+use crate::adapters::http::build_client;
 use crate::config::toml_config::TomlConfig;
 use crate::core::{Pipeline, Record, Storage, TransformResult};
-use crate::utils::error::Result;
+use crate::utils::error::{EtlError, Result};
 use reqwest::Client;
 use std::collections::HashMap;
 use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
 use zip::write::{FileOptions, ZipWriter};
 
 /// MVP Pipeline 實現，專注於處理第一筆記錄
 pub struct MvpPipeline<S: Storage> {
     pub(crate) storage: S,
     pub(crate) config: TomlConfig,
-    pub(crate) client: Client,
+    /// 依 source.http 建立；失敗時保留原因，extract 時返回錯誤，不退回預設設定
+    pub(crate) client: std::result::Result<Client, String>,
+    bytes_written: AtomicU64,
 }
 
 impl<S: Storage> MvpPipeline<S> {
     pub fn new(storage: S, config: TomlConfig) -> Self {
+        let carries_credentials = config.source.headers.as_ref().is_some_and(|headers| {
+            headers
+                .keys()
+                .any(|key| key.eq_ignore_ascii_case("authorization"))
+        });
+        let client = build_client(config.source.http.as_ref(), carries_credentials).map_err(|e| {
+            tracing::error!("📡 Failed to build HTTP client: {}", e);
+            e.to_string()
+        });
         Self {
             storage,
             config,
-            client: Client::new(),
+            client,
+            bytes_written: AtomicU64::new(0),
         }
     }
 }
@@ -39,7 +53,10 @@ impl<S: Storage> Pipeline for MvpPipeline<S> {
         }
 
         // 構建請求
-        let mut request = self.client.get(&self.config.source.endpoint);
+        let client = self.client.as_ref().map_err(|e| EtlError::ConfigError {
+            message: format!("Failed to build HTTP client: {}", e),
+        })?;
+        let mut request = client.get(&self.config.source.endpoint);
 
         // 添加自定義標頭
         if let Some(headers) = &self.config.source.headers {
@@ -241,8 +258,14 @@ impl<S: Storage> Pipeline for MvpPipeline<S> {
         };
 
         self.storage.write_file("mvp_output.zip", &zip_data).await?;
+        self.bytes_written
+            .store(zip_data.len() as u64, Ordering::Relaxed);
 
         tracing::info!("📦 MVP output saved: {}", output_path);
         Ok(output_path)
     }
+
+    fn bytes_written(&self) -> Option<u64> {
+        Some(self.bytes_written.load(Ordering::Relaxed))
+    }
 }
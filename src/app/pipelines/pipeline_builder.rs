@@ -0,0 +1,464 @@
+use crate::config::sequence_config::{
+    AggregationConfig, DataEnrichment, DataSource, ExtractConfig, FieldMapping,
+    FieldTransformConfig, LoadConfig, PayloadConfig, PipelineDefinition, SequenceConfig,
+    SequenceInfo, SourceConfig, TransformConfig, TransformOperations, ValidationConfig,
+};
+use crate::utils::error::Result;
+use std::collections::HashMap;
+
+/// 以程式碼組出 Pipeline 設定，將本 crate 當作函式庫嵌入時不需要產生 TOML 字串
+///
+/// 例如 `PipelineBuilder::new("users").source_api(url).map_field("user.name", "name")
+/// .keep_only(["id", "name"]).output_json("./output").build()`；
+/// 未提供方法的設定可在 `build()` 後直接修改 `PipelineDefinition` 的欄位。
+#[derive(Debug, Clone)]
+pub struct PipelineBuilder {
+    definition: PipelineDefinition,
+}
+
+impl PipelineBuilder {
+    /// 預設為 GET 的 api 來源，輸出到 ./output，尚未指定輸出格式
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            definition: PipelineDefinition {
+                name: name.into(),
+                description: None,
+                enabled: None,
+                source: SourceConfig {
+                    r#type: "api".to_string(),
+                    endpoint: None,
+                    method: None,
+                    timeout_seconds: None,
+                    retry_attempts: None,
+                    retry_delay_seconds: None,
+                    headers: None,
+                    parameters: None,
+                    payload: None,
+                    data_source: None,
+                    batch_parameters: None,
+                    rate_limit: None,
+                    auth: None,
+                    http: None,
+                    encoding: None,
+                    response_format: None,
+                    join: None,
+                    combined: None,
+                    audit: None,
+                    follow_links: None,
+                    on_record_error: None,
+                    files: None,
+                    s3: None,
+                    fan_out_checkpoint_every: None,
+                    response_metadata: None,
+                    conditional: None,
+                },
+                extract: ExtractConfig {
+                    max_records: None,
+                    concurrent_requests: None,
+                    field_mapping: None,
+                    filters: None,
+                    data_processing: None,
+                    cache: None,
+                    data_path: None,
+                    records_from_object_keys: None,
+                    object_path: None,
+                    object_key_field: None,
+                    max_response_bytes: None,
+                    on_limit_exceeded: None,
+                },
+                transform: TransformConfig {
+                    operations: None,
+                    validation: None,
+                    intermediate: None,
+                    data_enrichment: None,
+                    field_transforms: None,
+                    record_timeout_ms: None,
+                    aggregation: None,
+                    script: None,
+                },
+                load: LoadConfig {
+                    output_path: "./output".to_string(),
+                    output_formats: Vec::new(),
+                    filename_pattern: None,
+                    file_pattern: None,
+                    compression: None,
+                    append_to_sequence: None,
+                    append: None,
+                    partition_by: None,
+                    partition_layout: None,
+                    max_records_per_file: None,
+                    columns: None,
+                    strict_columns: None,
+                    sftp: None,
+                    infer_schema: None,
+                    expected_schema: None,
+                    csv: None,
+                    null_policy: None,
+                    write_mode: None,
+                    transactional: None,
+                    manifest: None,
+                },
+                dependencies: None,
+                conditions: None,
+                checkpoint: None,
+                outputs: None,
+                dead_letter: None,
+                context_index: None,
+                on_success: None,
+                on_failure: None,
+                skip_on_empty_input: None,
+                quality: None,
+                reconciliation: None,
+                profile: None,
+            },
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.definition.description = Some(description.into());
+        self
+    }
+
+    // ---- source ----
+
+    pub fn source_api(mut self, endpoint: impl Into<String>) -> Self {
+        self.definition.source.r#type = "api".to_string();
+        self.definition.source.endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.definition.source.method = Some(method.into());
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.definition
+            .source
+            .headers
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value.into());
+        self
+    }
+
+    pub fn query(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.definition
+            .source
+            .parameters
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value.into());
+        self
+    }
+
+    /// 請求內容，可使用 `{{key}}` 模板
+    pub fn payload(mut self, body: impl Into<String>) -> Self {
+        self.definition
+            .source
+            .payload
+            .get_or_insert(PayloadConfig {
+                body: None,
+                template_params: None,
+                content_type: None,
+                use_previous_data_as_params: None,
+                form: None,
+                multipart: None,
+            })
+            .body = Some(body.into());
+        self
+    }
+
+    pub fn timeout_seconds(mut self, seconds: u64) -> Self {
+        self.definition.source.timeout_seconds = Some(seconds);
+        self
+    }
+
+    /// 以上游 Pipeline 的每筆記錄呼叫端點（端點中的 `{field}` 以記錄欄位替換），並設為依賴
+    pub fn from_pipeline(mut self, pipeline: impl Into<String>) -> Self {
+        let pipeline = pipeline.into();
+        self.definition.source.data_source = Some(DataSource {
+            use_previous_output: None,
+            from_pipeline: Some(pipeline.clone()),
+            merge_with_api: None,
+        });
+        self.depends_on(pipeline)
+    }
+
+    pub fn depends_on(mut self, pipeline: impl Into<String>) -> Self {
+        let pipeline = pipeline.into();
+        let dependencies = self.definition.dependencies.get_or_insert_with(Vec::new);
+        if !dependencies.contains(&pipeline) {
+            dependencies.push(pipeline);
+        }
+        self
+    }
+
+    // ---- extract ----
+
+    pub fn max_records(mut self, max_records: usize) -> Self {
+        self.definition.extract.max_records = Some(max_records);
+        self
+    }
+
+    /// 將來源欄位（可用 `user.name` 取巢狀欄位）輸出為 `to`
+    pub fn map_field(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.definition
+            .extract
+            .field_mapping
+            .get_or_insert_with(HashMap::new)
+            .insert(from.into(), FieldMapping::Rename(to.into()));
+        self
+    }
+
+    // ---- transform ----
+
+    fn operations(&mut self) -> &mut TransformOperations {
+        self.definition
+            .transform
+            .operations
+            .get_or_insert(TransformOperations {
+                clean_text: None,
+                trim_whitespace: None,
+                remove_html_tags: None,
+                normalize_fields: None,
+                keep_only_fields: None,
+                exclude_fields: None,
+                custom: None,
+                mask_fields: None,
+                hash_fields: None,
+                hash_salt: None,
+                tokenize_fields: None,
+            })
+    }
+
+    pub fn keep_only<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.operations().keep_only_fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn exclude<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.operations().exclude_fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// 加入以 `register_transform_step` 註冊的自訂轉換步驟
+    pub fn custom_step(mut self, name: impl Into<String>) -> Self {
+        self.operations()
+            .custom
+            .get_or_insert_with(Vec::new)
+            .push(name.into());
+        self
+    }
+
+    pub fn trim_whitespace(mut self) -> Self {
+        self.operations().trim_whitespace = Some(true);
+        self
+    }
+
+    pub fn require_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.definition
+            .transform
+            .validation
+            .get_or_insert(ValidationConfig {
+                required_fields: None,
+                field_types: None,
+                min_records: None,
+                max_records: None,
+                on_invalid: None,
+            })
+            .required_fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn transform_field(
+        mut self,
+        field: impl Into<String>,
+        transform: FieldTransformConfig,
+    ) -> Self {
+        self.definition
+            .transform
+            .field_transforms
+            .get_or_insert_with(HashMap::new)
+            .insert(field.into(), transform);
+        self
+    }
+
+    /// 計算欄位，運算式語法與 `data_enrichment.computed_fields` 相同
+    pub fn computed_field(
+        mut self,
+        field: impl Into<String>,
+        expression: impl Into<String>,
+    ) -> Self {
+        self.definition
+            .transform
+            .data_enrichment
+            .get_or_insert(DataEnrichment {
+                lookup_data: None,
+                lookup_tables: None,
+                computed_fields: None,
+            })
+            .computed_fields
+            .get_or_insert_with(HashMap::new)
+            .insert(field.into(), expression.into());
+        self
+    }
+
+    pub fn aggregate(mut self, aggregation: AggregationConfig) -> Self {
+        self.definition.transform.aggregation = Some(aggregation);
+        self
+    }
+
+    // ---- load ----
+
+    pub fn output_path(mut self, path: impl Into<String>) -> Self {
+        self.definition.load.output_path = path.into();
+        self
+    }
+
+    /// 加入輸出格式（csv、tsv 或 json）
+    pub fn output_format(mut self, format: impl Into<String>) -> Self {
+        let format = format.into();
+        if !self.definition.load.output_formats.contains(&format) {
+            self.definition.load.output_formats.push(format);
+        }
+        self
+    }
+
+    pub fn output_json(self, path: impl Into<String>) -> Self {
+        self.output_path(path).output_format("json")
+    }
+
+    pub fn output_csv(self, path: impl Into<String>) -> Self {
+        self.output_path(path).output_format("csv")
+    }
+
+    pub fn build(self) -> PipelineDefinition {
+        self.definition
+    }
+}
+
+/// 組出序列設定；未指定執行順序時依加入 Pipeline 的順序執行
+#[derive(Debug, Clone)]
+pub struct SequenceBuilder {
+    info: SequenceInfo,
+    pipelines: Vec<PipelineDefinition>,
+}
+
+impl SequenceBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            info: SequenceInfo {
+                description: name.clone(),
+                name,
+                version: "1.0.0".to_string(),
+                execution_order: Vec::new(),
+                schedule: None,
+                queue: None,
+                foreach: None,
+            },
+            pipelines: Vec::new(),
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.info.description = description.into();
+        self
+    }
+
+    pub fn pipeline(mut self, pipeline: impl Into<PipelineDefinition>) -> Self {
+        self.pipelines.push(pipeline.into());
+        self
+    }
+
+    pub fn execution_order<I, S>(mut self, order: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.info.execution_order = order.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 與從 TOML 載入的設定相同，經過 `SequenceConfig::validate` 驗證
+    pub fn build(mut self) -> Result<SequenceConfig> {
+        if self.info.execution_order.is_empty() {
+            self.info.execution_order = self
+                .pipelines
+                .iter()
+                .map(|pipeline| pipeline.name.clone())
+                .collect();
+        }
+        let config = SequenceConfig {
+            include: None,
+            sequence: self.info,
+            pipelines: self.pipelines,
+            global: None,
+            monitoring: None,
+            error_handling: None,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl From<PipelineBuilder> for PipelineDefinition {
+    fn from(builder: PipelineBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_matches_toml() {
+        let config = SequenceBuilder::new("users")
+            .pipeline(
+                PipelineBuilder::new("users")
+                    .source_api("https://api.example.com/users")
+                    .header("Accept", "application/json")
+                    .map_field("user.name", "name")
+                    .keep_only(["id", "name"])
+                    .output_json("./output"),
+            )
+            .pipeline(
+                PipelineBuilder::new("posts")
+                    .source_api("https://api.example.com/users/{id}/posts")
+                    .from_pipeline("users")
+                    .output_csv("./output"),
+            )
+            .build()
+            .unwrap();
+
+        let parsed = SequenceConfig::from_toml_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.sequence.execution_order, ["users", "posts"]);
+        let users = &parsed.pipelines[0];
+        assert_eq!(
+            users.extract.field_mapping.as_ref().unwrap()["user.name"].target(),
+            "name"
+        );
+        assert_eq!(users.load.output_formats, ["json"]);
+        assert_eq!(
+            parsed.pipelines[1].dependencies.as_deref(),
+            Some(&["users".to_string()][..])
+        );
+
+        let missing_endpoint = SequenceBuilder::new("broken")
+            .pipeline(PipelineBuilder::new("users").output_json("./output"))
+            .build();
+        assert!(missing_endpoint.is_err());
+    }
+}
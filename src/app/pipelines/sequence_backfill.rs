@@ -0,0 +1,412 @@
+use crate::app::pipelines::sequence_foreach::{ForeachEntry, ForeachItem, ForeachStatus};
+use crate::app::pipelines::sequence_runner::{write_run_report, RunOptions, SequenceRunner};
+use crate::config::sequence_config::SequenceConfig;
+use crate::utils::error::{EtlError, Result};
+use chrono::{Days, Months, NaiveDate};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// 時間窗在模板中的名稱：`{{window.start}}`、`{{window.end}}`、`{{window.last}}`
+pub const WINDOW_ITEM: &str = "window";
+
+/// 時間窗的長度，例如 `1d`、`2w`、`1mo`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackfillStep {
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+}
+
+impl BackfillStep {
+    pub const SUPPORTED: [&'static str; 3] = ["d", "w", "mo"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let split = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let (count, unit) = value.split_at(split);
+        let invalid = |reason: String| EtlError::InvalidConfigValueError {
+            field: "step".to_string(),
+            value: value.to_string(),
+            reason,
+        };
+        let count: u32 = count
+            .parse()
+            .ok()
+            .filter(|count| *count > 0)
+            .ok_or_else(|| invalid("Step must start with a positive number".to_string()))?;
+        match unit {
+            "d" => Ok(Self::Days(count)),
+            "w" => Ok(Self::Weeks(count)),
+            "mo" => Ok(Self::Months(count)),
+            _ => Err(invalid(format!(
+                "Supported units: {}",
+                Self::SUPPORTED.join(", ")
+            ))),
+        }
+    }
+
+    /// 下一個時間窗的開始日期；超出日期範圍時返回 None
+    pub fn advance(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Days(days) => date.checked_add_days(Days::new(u64::from(*days))),
+            Self::Weeks(weeks) => date.checked_add_days(Days::new(u64::from(*weeks) * 7)),
+            Self::Months(months) => date.checked_add_months(Months::new(*months)),
+        }
+    }
+}
+
+impl std::fmt::Display for BackfillStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Days(days) => write!(f, "{}d", days),
+            Self::Weeks(weeks) => write!(f, "{}w", weeks),
+            Self::Months(months) => write!(f, "{}mo", months),
+        }
+    }
+}
+
+/// 單一時間窗：`start` 含、`end` 不含；`last` 為窗內最後一天
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillWindow {
+    pub index: usize, // 從 1 開始
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl BackfillWindow {
+    /// 以逐項執行的項目提供給模板與共享數據，日期格式為 `%Y-%m-%d`
+    pub fn item(&self) -> ForeachItem {
+        let last = self.end.pred_opt().unwrap_or(self.start);
+        ForeachItem::new(
+            WINDOW_ITEM,
+            self.index,
+            serde_json::json!({
+                "start": self.start.to_string(),
+                "end": self.end.to_string(),
+                "last": last.to_string(),
+            }),
+        )
+    }
+}
+
+/// 回補的日期範圍與執行方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillPlan {
+    pub from: NaiveDate,
+    pub to: NaiveDate, // 含
+    pub step: BackfillStep,
+    pub concurrency: usize,      // 同時執行的時間窗數
+    pub continue_on_error: bool, // 時間窗失敗後是否繼續執行其餘時間窗
+}
+
+impl BackfillPlan {
+    /// 解析 `YYYY-MM-DD` 的起訖日期（皆含）與步長；預設逐一執行並在失敗時停止
+    pub fn parse(from: &str, to: &str, step: &str) -> Result<Self> {
+        let date = |field: &str, value: &str| {
+            NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+                EtlError::InvalidConfigValueError {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: "Expected a date like 2024-01-31".to_string(),
+                }
+            })
+        };
+        let (from, to) = (date("from", from)?, date("to", to)?);
+        if from > to {
+            return Err(EtlError::ConfigValidationError {
+                field: "from".to_string(),
+                message: format!("Start date {} is after end date {}", from, to),
+            });
+        }
+        Ok(Self {
+            from,
+            to,
+            step: BackfillStep::parse(step)?,
+            concurrency: 1,
+            continue_on_error: false,
+        })
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn with_continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.continue_on_error = continue_on_error;
+        self
+    }
+
+    /// 依步長切出的時間窗；最後一個時間窗截在 `to` 的隔天
+    pub fn windows(&self) -> Vec<BackfillWindow> {
+        let until = self.to.succ_opt().unwrap_or(self.to);
+        let mut windows = Vec::new();
+        let mut start = self.from;
+        while start < until {
+            let end = self
+                .step
+                .advance(start)
+                .map_or(until, |next| next.min(until));
+            windows.push(BackfillWindow {
+                index: windows.len() + 1,
+                start,
+                end,
+            });
+            start = end;
+        }
+        windows
+    }
+}
+
+/// 回補的彙整報告；各時間窗依開始日期排序，因失敗或中斷而未執行的時間窗列在 `not_run`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackfillReport {
+    pub sequence_name: String,
+    pub execution_id: String,
+    pub from: String,
+    pub to: String,
+    pub step: String,
+    pub windows: Vec<ForeachEntry>,
+    pub not_run: usize,
+    pub records: usize, // 成功時間窗的記錄總數
+}
+
+impl BackfillReport {
+    pub fn count(&self, status: ForeachStatus) -> usize {
+        self.windows.iter().filter(|w| w.status == status).count()
+    }
+
+    pub fn all_succeeded(&self) -> bool {
+        self.not_run == 0 && self.count(ForeachStatus::Succeeded) == self.windows.len()
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!(
+            "Backfill report for {} ({} to {}, step {}): {} windows - {} succeeded, {} failed, {} not run, {} records",
+            self.sequence_name,
+            self.from,
+            self.to,
+            self.step,
+            self.windows.len() + self.not_run,
+            self.count(ForeachStatus::Succeeded),
+            self.count(ForeachStatus::Failed),
+            self.not_run,
+            self.records
+        )];
+        for window in &self.windows {
+            let icon = match window.status {
+                ForeachStatus::Succeeded => "✅",
+                ForeachStatus::Failed => "❌",
+            };
+            let date = |field: &str| window.item[field].as_str().unwrap_or_default().to_string();
+            let mut line = format!("  {} {}..{}", icon, date("start"), date("last"));
+            if window.status == ForeachStatus::Succeeded {
+                line.push_str(&format!(
+                    " - {} pipelines, {} records in {}ms",
+                    window.pipelines, window.records, window.duration_ms
+                ));
+            }
+            line.push_str(&format!(" [{}]", window.execution_id));
+            lines.push(line);
+            if let Some(error) = &window.error {
+                lines.push(format!("      {}", error));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 依時間窗逐一（或在 concurrency 內並行）執行整個序列，返回彙整報告
+///
+/// 每個時間窗以 `{execution_id}_{開始日期}` 作為執行 ID，並各自寫入執行報告。
+/// 時間窗失敗（未設定 continue_on_error）或收到停止訊號後不再開始新的時間窗，執行中的時間窗會完成。
+pub async fn run_backfill(
+    config: &SequenceConfig,
+    options: &RunOptions,
+    plan: &BackfillPlan,
+) -> Result<BackfillReport> {
+    if config.sequence.foreach.is_some() {
+        // 兩者都以逐項執行的項目提供模板值，無法同時使用
+        return Err(EtlError::ConfigValidationError {
+            field: "sequence.foreach".to_string(),
+            message: "Backfill cannot be used with sequence.foreach".to_string(),
+        });
+    }
+    let execution_id = options.resolve_execution_id();
+    let windows = plan.windows();
+    tracing::info!(
+        "⏪ Backfilling sequence {} from {} to {} in {} windows of {} (concurrency {})",
+        config.sequence.name,
+        plan.from,
+        plan.to,
+        windows.len(),
+        plan.step,
+        plan.concurrency
+    );
+
+    let config = Arc::new(config.clone());
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(plan.concurrency));
+    let stop = Arc::new(AtomicBool::new(false));
+    let total = windows.len();
+    let mut tasks = tokio::task::JoinSet::new();
+    for window in windows {
+        let item = window.item();
+        let window_execution_id = format!("{}_{}", execution_id, item.suffix(Some("start")));
+        let window_options = RunOptions {
+            execution_id: Some(window_execution_id.clone()),
+            resume: None,
+            foreach_item: Some(item.clone()),
+            ..options.clone()
+        };
+        let (config, semaphore, stop) = (
+            Arc::clone(&config),
+            Arc::clone(&semaphore),
+            Arc::clone(&stop),
+        );
+        let continue_on_error = plan.continue_on_error;
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            if stop.load(Ordering::SeqCst) {
+                return None;
+            }
+            tracing::info!(
+                "▶️ Window {}/{}: {} to {} as {}",
+                window.index,
+                total,
+                window.start,
+                window.end,
+                window_execution_id
+            );
+
+            let started_at = Instant::now();
+            let outcome = match SequenceRunner::new(&config, &window_options) {
+                Ok(runner) => runner.run().await,
+                Err(e) => Err(e),
+            };
+            let exit_code = outcome.as_ref().err().map_or(0, EtlError::exit_code);
+            write_run_report(
+                &config,
+                &window_options,
+                &window_execution_id,
+                &outcome,
+                exit_code,
+                started_at.elapsed(),
+            );
+            if let Err(e) = &outcome {
+                tracing::error!("❌ Window starting {} failed: {}", window.start, e);
+                let interrupted = matches!(e.root(), EtlError::Interrupted { .. });
+                if interrupted || !continue_on_error {
+                    stop.store(true, Ordering::SeqCst);
+                }
+            }
+            Some(ForeachEntry::new(
+                &item,
+                &window_execution_id,
+                started_at.elapsed(),
+                &outcome,
+            ))
+        });
+    }
+
+    let mut report = BackfillReport {
+        sequence_name: config.sequence.name.clone(),
+        execution_id,
+        from: plan.from.to_string(),
+        to: plan.to.to_string(),
+        step: plan.step.to_string(),
+        ..Default::default()
+    };
+    while let Some(entry) = tasks.join_next().await {
+        match entry.map_err(|e| EtlError::ProcessingError {
+            message: format!("Backfill window task failed: {}", e),
+        })? {
+            Some(entry) => report.windows.push(entry),
+            None => report.not_run += 1,
+        }
+    }
+    report.windows.sort_by_key(|window| window.index);
+    report.records = report
+        .windows
+        .iter()
+        .filter(|window| window.status == ForeachStatus::Succeeded)
+        .map(|window| window.records)
+        .sum();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_parse() {
+        assert_eq!(BackfillStep::parse("1d").unwrap(), BackfillStep::Days(1));
+        assert_eq!(BackfillStep::parse("2w").unwrap(), BackfillStep::Weeks(2));
+        assert_eq!(BackfillStep::parse("3mo").unwrap(), BackfillStep::Months(3));
+        assert_eq!(BackfillStep::Months(3).to_string(), "3mo");
+        for invalid in ["d", "0d", "1h", "1m", "-1d"] {
+            assert!(BackfillStep::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_windows_cover_range() {
+        let plan = BackfillPlan::parse("2024-01-30", "2024-03-31", "1mo").unwrap();
+        let windows: Vec<(String, String)> = plan
+            .windows()
+            .iter()
+            .map(|w| (w.start.to_string(), w.end.to_string()))
+            .collect();
+        assert_eq!(
+            windows,
+            [
+                ("2024-01-30".to_string(), "2024-02-29".to_string()),
+                ("2024-02-29".to_string(), "2024-03-29".to_string()),
+                ("2024-03-29".to_string(), "2024-04-01".to_string()),
+            ]
+        );
+
+        let plan = BackfillPlan::parse("2024-01-01", "2024-01-10", "7d").unwrap();
+        let windows = plan.windows();
+        assert_eq!(windows.len(), 2);
+        let item = windows[1].item();
+        assert_eq!(item.name, "window");
+        assert_eq!(item.index, 2);
+        assert_eq!(
+            item.value,
+            serde_json::json!({"start": "2024-01-08", "end": "2024-01-11", "last": "2024-01-10"})
+        );
+        assert_eq!(item.suffix(Some("start")), "2024-01-08");
+
+        // 單日範圍
+        let plan = BackfillPlan::parse("2024-01-01", "2024-01-01", "1w").unwrap();
+        assert_eq!(plan.windows().len(), 1);
+    }
+
+    #[test]
+    fn test_plan_rejects_invalid_dates() {
+        assert!(BackfillPlan::parse("2024-02-01", "2024-01-01", "1d").is_err());
+        assert!(BackfillPlan::parse("2024/01/01", "2024-01-31", "1d").is_err());
+        assert!(BackfillPlan::parse("2024-01-01", "2024-02-30", "1d").is_err());
+        assert_eq!(
+            BackfillPlan::parse("2024-01-01", "2024-01-31", "1d")
+                .unwrap()
+                .with_concurrency(0)
+                .concurrency,
+            1
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use crate::config::sequence_config::SequenceConfig;
+use crate::utils::error::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 目錄中找到的序列設定檔；設定無法載入或驗證失敗時 `config` 為錯誤訊息
+#[derive(Debug)]
+pub struct DiscoveredSequence {
+    pub path: PathBuf,
+    pub config: std::result::Result<SequenceConfig, String>,
+}
+
+/// 找出目錄下所有序列設定檔（含 `[sequence]` 區塊的 .toml），依檔名排序並逐一驗證
+///
+/// 其他 TOML（例如單一 Pipeline 的設定檔）會被略過；不遞迴搜尋子目錄。
+pub fn discover_sequence_configs(
+    dir: &Path,
+    variables: &HashMap<String, String>,
+) -> Result<Vec<DiscoveredSequence>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    let mut discovered = Vec::new();
+    for path in paths {
+        let content = std::fs::read_to_string(&path)?;
+        if !content.lines().any(|line| line.trim() == "[sequence]") {
+            tracing::debug!("⏭️ {} is not a sequence config, skipping", path.display());
+            continue;
+        }
+
+        let config = SequenceConfig::from_file_with_variables(&path, variables)
+            .and_then(|config| config.validate().map(|_| config))
+            .map_err(|e| e.to_string());
+        discovered.push(DiscoveredSequence { path, config });
+    }
+    Ok(discovered)
+}
+
+/// 單一序列在批次中的結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Succeeded,
+    Failed,
+    /// 設定無法載入或驗證失敗，未執行
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchEntry {
+    pub config_path: String,
+    pub sequence_name: Option<String>,
+    pub status: BatchStatus,
+    pub execution_id: Option<String>,
+    pub pipelines: usize,
+    pub records: usize,
+    pub duration_ms: u128,
+    pub error: Option<String>,
+}
+
+impl BatchEntry {
+    pub fn invalid(path: &Path, error: String) -> Self {
+        Self {
+            config_path: path.display().to_string(),
+            sequence_name: None,
+            status: BatchStatus::Invalid,
+            execution_id: None,
+            pipelines: 0,
+            records: 0,
+            duration_ms: 0,
+            error: Some(error),
+        }
+    }
+
+    pub fn executed(
+        path: &Path,
+        sequence_name: &str,
+        execution_id: &str,
+        duration: Duration,
+        outcome: std::result::Result<(usize, usize), String>,
+    ) -> Self {
+        let (status, (pipelines, records), error) = match outcome {
+            Ok(counts) => (BatchStatus::Succeeded, counts, None),
+            Err(error) => (BatchStatus::Failed, (0, 0), Some(error)),
+        };
+        Self {
+            config_path: path.display().to_string(),
+            sequence_name: Some(sequence_name.to_string()),
+            status,
+            execution_id: Some(execution_id.to_string()),
+            pipelines,
+            records,
+            duration_ms: duration.as_millis(),
+            error,
+        }
+    }
+}
+
+/// 批次執行的彙整報告
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchReport {
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchReport {
+    /// 加入結果並維持依設定檔路徑排序（並行執行完成的順序不固定）
+    pub fn push(&mut self, entry: BatchEntry) {
+        self.entries.push(entry);
+        self.entries
+            .sort_by(|a, b| a.config_path.cmp(&b.config_path));
+    }
+
+    pub fn count(&self, status: BatchStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    pub fn all_succeeded(&self) -> bool {
+        self.count(BatchStatus::Succeeded) == self.entries.len()
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!(
+            "Batch report: {} sequences - {} succeeded, {} failed, {} invalid",
+            self.entries.len(),
+            self.count(BatchStatus::Succeeded),
+            self.count(BatchStatus::Failed),
+            self.count(BatchStatus::Invalid)
+        )];
+        for entry in &self.entries {
+            let icon = match entry.status {
+                BatchStatus::Succeeded => "✅",
+                BatchStatus::Failed => "❌",
+                BatchStatus::Invalid => "⚠️",
+            };
+            let mut line = format!(
+                "  {} {} ({})",
+                icon,
+                entry.sequence_name.as_deref().unwrap_or("-"),
+                entry.config_path
+            );
+            if entry.status == BatchStatus::Succeeded {
+                line.push_str(&format!(
+                    " - {} pipelines, {} records in {}ms",
+                    entry.pipelines, entry.records, entry.duration_ms
+                ));
+            }
+            if let Some(execution_id) = &entry.execution_id {
+                line.push_str(&format!(" [{}]", execution_id));
+            }
+            lines.push(line);
+            if let Some(error) = &entry.error {
+                lines.push(format!("      {}", error));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEQUENCE: &str = r#"
+[sequence]
+name = "NAME"
+description = "batch"
+version = "1.0.0"
+execution_order = ["p"]
+
+[[pipelines]]
+name = "p"
+
+[pipelines.source]
+type = "api"
+endpoint = "https://api.example.com/p"
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output"
+output_formats = ["FORMAT"]
+"#;
+
+    #[test]
+    fn test_discover_and_validate() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let write =
+            |name: &str, content: &str| std::fs::write(dir.path().join(name), content).unwrap();
+        write(
+            "b_nightly.toml",
+            &SEQUENCE.replace("NAME", "nightly").replace("FORMAT", "csv"),
+        );
+        write(
+            "a_broken.toml",
+            &SEQUENCE.replace("NAME", "broken").replace("FORMAT", "xls"),
+        );
+        write("single.toml", "[source]\nendpoint = \"https://x\"\n");
+        write("notes.txt", "[sequence]");
+
+        let discovered = discover_sequence_configs(dir.path(), &HashMap::new()).unwrap();
+        assert_eq!(discovered.len(), 2);
+        assert!(discovered[0].path.ends_with("a_broken.toml"));
+        assert!(discovered[0].config.as_ref().unwrap_err().contains("xls"));
+        assert_eq!(
+            discovered[1].config.as_ref().unwrap().sequence.name,
+            "nightly"
+        );
+    }
+
+    #[test]
+    fn test_report_counts_and_render() {
+        let mut report = BatchReport::default();
+        report.push(BatchEntry::executed(
+            Path::new("configs/b.toml"),
+            "b",
+            "b_1",
+            Duration::from_millis(20),
+            Err("API down".to_string()),
+        ));
+        report.push(BatchEntry::executed(
+            Path::new("configs/a.toml"),
+            "a",
+            "a_1",
+            Duration::from_millis(10),
+            Ok((2, 40)),
+        ));
+
+        assert!(!report.all_succeeded());
+        assert_eq!(report.count(BatchStatus::Failed), 1);
+        assert_eq!(report.entries[0].sequence_name.as_deref(), Some("a"));
+        let text = report.render();
+        assert!(text.contains("1 succeeded, 1 failed, 0 invalid"));
+        assert!(text.contains("2 pipelines, 40 records"));
+    }
+}
@@ -0,0 +1,535 @@
+use crate::adapters::http::HttpClientBuilder;
+use crate::adapters::storage::SftpTarget;
+use crate::config::sequence_config::PipelineDefinition;
+use crate::core::builtin_templates::{is_builtin, BuiltinValues};
+use crate::utils::error::Result;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::time::Duration;
+
+/// 端點檢查的預設逾時（未設定 source.timeout_seconds 時）
+const DEFAULT_ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 乾跑檢查項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DryRunCheck {
+    Template,
+    Endpoint,
+    OutputPath,
+    Dependency,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// 實際執行時一定會失敗
+    Error,
+    /// 可能有問題，或缺少樣本資料無法確認
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunProblem {
+    pub pipeline: String,
+    pub check: DryRunCheck,
+    pub severity: ProblemSeverity,
+    pub message: String,
+}
+
+/// 乾跑檢查結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DryRunReport {
+    pub problems: Vec<DryRunProblem>,
+}
+
+impl DryRunReport {
+    fn push(
+        &mut self,
+        pipeline: &str,
+        check: DryRunCheck,
+        severity: ProblemSeverity,
+        message: String,
+    ) {
+        self.problems.push(DryRunProblem {
+            pipeline: pipeline.to_string(),
+            check,
+            severity,
+            message,
+        });
+    }
+
+    pub fn count(&self, severity: ProblemSeverity) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(ProblemSeverity::Error) > 0
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!(
+            "Dry run checks: {} errors, {} warnings",
+            self.count(ProblemSeverity::Error),
+            self.count(ProblemSeverity::Warning)
+        )];
+        for problem in &self.problems {
+            let icon = match problem.severity {
+                ProblemSeverity::Error => "❌",
+                ProblemSeverity::Warning => "⚠️",
+            };
+            lines.push(format!(
+                "  {} [{}] {:?}: {}",
+                icon, problem.pipeline, problem.check, problem.message
+            ));
+        }
+        lines.join("\n")
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 依執行順序檢查即將執行的 Pipeline：模板、端點連線、輸出目錄與依賴順序
+///
+/// `sample` 是模板名稱（共享數據或前一個 Pipeline 的欄位）對應的樣本值；
+/// 未提供樣本時，無法在執行前確認的模板名稱只列為警告。
+pub async fn check_pipelines(
+    pipelines: &[&PipelineDefinition],
+    sample: Option<&HashMap<String, serde_json::Value>>,
+) -> DryRunReport {
+    let mut report = DryRunReport::default();
+    check_dependency_order(pipelines, &mut report);
+    for pipeline in pipelines {
+        let endpoint = check_templates(pipeline, sample, &mut report);
+        if let Some(endpoint) = endpoint {
+            check_endpoint(pipeline, &endpoint, &mut report).await;
+        }
+    }
+    check_output_paths(pipelines, &mut report);
+    report
+}
+
+/// 依賴與 data_source.from_pipeline 必須排在前面執行
+fn check_dependency_order(pipelines: &[&PipelineDefinition], report: &mut DryRunReport) {
+    let position: HashMap<&str, usize> = pipelines
+        .iter()
+        .enumerate()
+        .map(|(index, pipeline)| (pipeline.name.as_str(), index))
+        .collect();
+
+    for (index, pipeline) in pipelines.iter().enumerate() {
+        let from_pipeline = pipeline
+            .source
+            .data_source
+            .as_ref()
+            .and_then(|data_source| data_source.from_pipeline.as_ref());
+        let joined = pipeline
+            .source
+            .join
+            .iter()
+            .flat_map(|join| [&join.left, &join.right]);
+        let combined = pipeline
+            .source
+            .combined
+            .iter()
+            .flat_map(|combined| &combined.pipelines);
+        let upstream = pipeline
+            .dependencies
+            .iter()
+            .flatten()
+            .chain(from_pipeline)
+            .chain(joined)
+            .chain(combined);
+        for name in upstream {
+            match position.get(name.as_str()) {
+                Some(&upstream_index) if upstream_index > index => report.push(
+                    &pipeline.name,
+                    DryRunCheck::Dependency,
+                    ProblemSeverity::Error,
+                    format!("'{}' runs after this pipeline", name),
+                ),
+                Some(_) => {}
+                None => report.push(
+                    &pipeline.name,
+                    DryRunCheck::Dependency,
+                    ProblemSeverity::Warning,
+                    format!("'{}' is not part of this run", name),
+                ),
+            }
+        }
+    }
+}
+
+/// 檢查端點、標頭與 payload 中的佔位符，返回以樣本值替換後可連線檢查的端點
+fn check_templates(
+    pipeline: &PipelineDefinition,
+    sample: Option<&HashMap<String, serde_json::Value>>,
+    report: &mut DryRunReport,
+) -> Option<String> {
+    let source = &pipeline.source;
+    let payload = source.payload.as_ref();
+    let template_params = payload.and_then(|payload| payload.template_params.as_ref());
+    let watermarks: BTreeSet<String> = pipeline
+        .checkpoint
+        .iter()
+        .flat_map(|checkpoint| checkpoint.watermarks.keys())
+        .map(|key| format!("checkpoint.{}", key))
+        .collect();
+    let batch_placeholder = source
+        .batch_parameters
+        .as_ref()
+        .map(|batch| batch.placeholder.as_str());
+
+    let mut templates: Vec<(String, &str)> = Vec::new();
+    if let Some(endpoint) = &source.endpoint {
+        templates.push(("endpoint".to_string(), endpoint));
+    }
+    for (name, value) in source.headers.iter().flatten() {
+        templates.push((format!("header {}", name), value));
+    }
+    for (location, template) in payload.iter().flat_map(|payload| payload.templates()) {
+        templates.push((
+            match location.as_str() {
+                "body" => "payload".to_string(),
+                _ => format!("payload {}", location),
+            },
+            template,
+        ));
+    }
+
+    let mut unresolved_endpoint = false;
+    for (location, template) in &templates {
+        for variable in unresolved_env_vars(template) {
+            report.push(
+                &pipeline.name,
+                DryRunCheck::Template,
+                ProblemSeverity::Error,
+                format!(
+                    "{} references unset environment variable ${{{}}}",
+                    location, variable
+                ),
+            );
+            unresolved_endpoint |= location == "endpoint";
+        }
+
+        let mut names = template_names(template);
+        if location == "endpoint" {
+            names.extend(single_brace_names(template));
+        }
+        for name in names {
+            if name.starts_with("lookup:")
+                || is_builtin(&name)
+                || watermarks.contains(&name)
+                || batch_placeholder == Some(name.as_str())
+            {
+                continue;
+            }
+            let key = template_params
+                .and_then(|params| params.get(&name))
+                .unwrap_or(&name);
+            if sample.is_some_and(|sample| sample.contains_key(key)) {
+                continue;
+            }
+            unresolved_endpoint |= location == "endpoint";
+            let (severity, detail) = match sample {
+                Some(_) => (ProblemSeverity::Error, "has no value in the sample data"),
+                None => (
+                    ProblemSeverity::Warning,
+                    "cannot be verified without --sample-data",
+                ),
+            };
+            report.push(
+                &pipeline.name,
+                DryRunCheck::Template,
+                severity,
+                format!("{} placeholder '{}' {}", location, name, detail),
+            );
+        }
+    }
+
+    let endpoint = source.endpoint.as_ref()?;
+    if unresolved_endpoint {
+        report.push(
+            &pipeline.name,
+            DryRunCheck::Endpoint,
+            ProblemSeverity::Warning,
+            "Endpoint has unresolved placeholders, reachability not checked".to_string(),
+        );
+        return None;
+    }
+    // 內建佔位符以目前時間填入
+    let endpoint = BuiltinValues::new("dry_run", chrono::Utc::now()).apply(endpoint);
+    Some(fill_sample(&endpoint, sample))
+}
+
+/// 以 HEAD 確認端點可連線，伺服器不支援時改用 OPTIONS
+async fn check_endpoint(pipeline: &PipelineDefinition, endpoint: &str, report: &mut DryRunReport) {
+    if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+        report.push(
+            &pipeline.name,
+            DryRunCheck::Endpoint,
+            ProblemSeverity::Error,
+            format!("Endpoint is not an http(s) URL: {}", endpoint),
+        );
+        return;
+    }
+
+    let timeout = pipeline
+        .source
+        .timeout_seconds
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_ENDPOINT_TIMEOUT);
+    let client = match HttpClientBuilder::new(pipeline.source.http.as_ref())
+        .carries_credentials(pipeline.source.carries_credentials())
+        .timeout(timeout)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("⚠️ Could not build HTTP client for dry run: {}", e);
+            return;
+        }
+    };
+
+    let mut response = client.head(endpoint).send().await;
+    if let Ok(head) = &response {
+        let status = head.status();
+        if status == reqwest::StatusCode::METHOD_NOT_ALLOWED
+            || status == reqwest::StatusCode::NOT_IMPLEMENTED
+        {
+            response = client
+                .request(reqwest::Method::OPTIONS, endpoint)
+                .send()
+                .await;
+        }
+    }
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            tracing::debug!("🔍 {}: {} answered {}", pipeline.name, endpoint, status);
+            if status == reqwest::StatusCode::NOT_FOUND || status.is_server_error() {
+                report.push(
+                    &pipeline.name,
+                    DryRunCheck::Endpoint,
+                    ProblemSeverity::Warning,
+                    format!("{} answered HTTP {}", endpoint, status),
+                );
+            }
+        }
+        Err(e) => report.push(
+            &pipeline.name,
+            DryRunCheck::Endpoint,
+            ProblemSeverity::Error,
+            format!("{} is unreachable: {}", endpoint, e),
+        ),
+    }
+}
+
+/// 輸出目錄（或最近的既有上層目錄）必須可寫入；不會建立任何目錄
+fn check_output_paths(pipelines: &[&PipelineDefinition], report: &mut DryRunReport) {
+    let mut checked = BTreeSet::new();
+    for pipeline in pipelines {
+        let output_path = &pipeline.load.output_path;
+        // 遠端輸出無法在本機檢查，連線問題在執行時才會出現
+        if !checked.insert(output_path.as_str()) || SftpTarget::is_sftp(output_path) {
+            continue;
+        }
+        if let Err(message) = check_writable(Path::new(output_path)) {
+            report.push(
+                &pipeline.name,
+                DryRunCheck::OutputPath,
+                ProblemSeverity::Error,
+                format!("{}: {}", output_path, message),
+            );
+        }
+    }
+}
+
+fn check_writable(path: &Path) -> std::result::Result<(), String> {
+    if path.exists() && !path.is_dir() {
+        return Err("exists but is not a directory".to_string());
+    }
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.as_os_str().is_empty() || ancestor.is_dir())
+        .map(|ancestor| {
+            if ancestor.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                ancestor
+            }
+        })
+        .unwrap_or(Path::new("."));
+
+    let probe = existing.join(format!(".samll-etl-dry-run-{}", std::process::id()));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| format!("not writable ({})", e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// 模板中的 {{name}} 名稱（不含 `|upper` 等過濾器）
+pub(crate) fn template_names(template: &str) -> Vec<String> {
+    let re = regex::Regex::new(r"\{\{([^}]+)\}\}").unwrap();
+    re.captures_iter(template)
+        .map(|caps| {
+            caps[1]
+                .split('|')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// 端點中的 {name} 名稱（參數化端點以前一個 Pipeline 的欄位替換）
+pub(crate) fn single_brace_names(template: &str) -> Vec<String> {
+    let without_double = regex::Regex::new(r"\{\{[^}]*\}\}")
+        .unwrap()
+        .replace_all(template, "");
+    regex::Regex::new(r"\{([A-Za-z0-9_.\-]+)\}")
+        .unwrap()
+        .captures_iter(&without_double)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// 載入設定時找不到的環境變數會保留為 ${NAME}
+fn unresolved_env_vars(template: &str) -> Vec<String> {
+    regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}")
+        .unwrap()
+        .captures_iter(template)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// 以樣本值替換端點中的 {name} 與 {{name}}
+fn fill_sample(endpoint: &str, sample: Option<&HashMap<String, serde_json::Value>>) -> String {
+    let mut filled = endpoint.to_string();
+    for (key, value) in sample.into_iter().flatten() {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        filled = filled
+            .replace(&format!("{{{{{}}}}}", key), &value)
+            .replace(&format!("{{{}}}", key), &value);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sequence_config::SequenceConfig;
+
+    const CONFIG: &str = r#"
+[sequence]
+name = "dry-run"
+description = "dry run checks"
+version = "1.0.0"
+execution_order = ["details", "users"]
+
+[[pipelines]]
+name = "users"
+
+[pipelines.source]
+type = "api"
+endpoint = "not-a-url"
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "OUTPUT"
+output_formats = ["json"]
+
+[[pipelines]]
+name = "details"
+dependencies = ["users"]
+
+[pipelines.source]
+type = "api"
+endpoint = "not-a-url/{id}"
+
+[pipelines.source.headers]
+Authorization = "Bearer {{token}}"
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "OUTPUT/details"
+output_formats = ["json"]
+"#;
+
+    #[tokio::test]
+    async fn test_template_and_dependency_problems() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config =
+            SequenceConfig::from_toml_str(&CONFIG.replace("OUTPUT", dir.path().to_str().unwrap()))
+                .unwrap();
+        let pipelines = config.get_enabled_pipelines();
+
+        let report = check_pipelines(&pipelines, None).await;
+        let messages = |check: DryRunCheck| {
+            report
+                .problems
+                .iter()
+                .filter(|problem| problem.check == check)
+                .map(|problem| (problem.pipeline.as_str(), problem.severity))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(DryRunCheck::Dependency),
+            vec![("details", ProblemSeverity::Error)]
+        );
+        // 未提供樣本時 {id} 與 {{token}} 只是警告，端點因此不做連線檢查
+        assert_eq!(
+            messages(DryRunCheck::Template),
+            vec![
+                ("details", ProblemSeverity::Warning),
+                ("details", ProblemSeverity::Warning)
+            ]
+        );
+        assert!(messages(DryRunCheck::OutputPath).is_empty());
+
+        let sample = HashMap::from([("id".to_string(), serde_json::json!(1))]);
+        let report = check_pipelines(&pipelines, Some(&sample)).await;
+        let token = report
+            .problems
+            .iter()
+            .find(|problem| problem.message.contains("'token'"))
+            .unwrap();
+        assert_eq!(token.severity, ProblemSeverity::Error);
+        assert!(report.has_errors());
+        assert!(report.render().contains("not an http(s) URL: not-a-url"));
+    }
+
+    #[test]
+    fn test_output_path_must_be_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, "x").unwrap();
+        assert!(check_writable(&dir.path().join("new/nested")).is_ok());
+        assert!(check_writable(&file).is_err());
+        assert!(!dir.path().join("new").exists());
+    }
+}
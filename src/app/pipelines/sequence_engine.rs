@@ -0,0 +1,133 @@
+use crate::app::pipelines::sequence_pipeline::{PipelineResult, PipelineSequence};
+use crate::utils::budget::ExecutionBudget;
+use crate::utils::error::Result;
+use crate::utils::monitor::SystemMonitor;
+use std::sync::Arc;
+
+/// 多 Pipeline 序列的執行引擎，對應單一 Pipeline 的 `EtlEngine`
+///
+/// 監控、執行時間預算與結束時的統計都由引擎負責，讓單一 Pipeline 與序列的
+/// 執行行為一致；序列本身只會在每個 Pipeline 完成後透過共用的監控器記錄資源。
+pub struct SequenceEngine {
+    sequence: PipelineSequence,
+    monitor: Arc<SystemMonitor>,
+    budget: Option<ExecutionBudget>,
+}
+
+impl SequenceEngine {
+    pub fn new(sequence: PipelineSequence) -> Self {
+        Self::new_with_monitoring(sequence, false)
+    }
+
+    pub fn new_with_monitoring(sequence: PipelineSequence, enable_monitoring: bool) -> Self {
+        let monitor = Arc::new(SystemMonitor::new(enable_monitoring));
+        Self {
+            sequence: sequence.with_monitor(Arc::clone(&monitor)),
+            monitor,
+            budget: None,
+        }
+    }
+
+    /// 設定執行時間預算，於序列開始、每個 Pipeline 完成與結束時記錄剩餘時間
+    pub fn with_budget(mut self, budget: ExecutionBudget) -> Self {
+        self.sequence = self.sequence.with_budget(budget.clone());
+        self.budget = Some(budget);
+        self
+    }
+
+    fn budget_checkpoint(&self, label: &str) {
+        if let Some(budget) = &self.budget {
+            budget.checkpoint(label);
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<Vec<PipelineResult>> {
+        tracing::info!("Starting ETL sequence");
+        self.monitor.log_stats("Sequence Start");
+        self.budget_checkpoint("Sequence Start");
+
+        let results = self.sequence.execute_all().await?;
+
+        let executed: Vec<&PipelineResult> = results
+            .iter()
+            .filter(|result| !result.is_skipped())
+            .collect();
+        tracing::info!(
+            "🎉 ETL sequence completed: {} pipelines, {} records, {} skipped",
+            executed.len(),
+            executed
+                .iter()
+                .map(|result| result.record_count())
+                .sum::<usize>(),
+            results.len() - executed.len()
+        );
+        self.budget_checkpoint("Sequence End");
+        self.monitor.log_final_stats();
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::pipelines::sequence_pipeline::{ContextualPipeline, PipelineContext};
+    use crate::domain::model::{Record, TransformResult};
+    use std::time::Duration;
+
+    struct StaticPipeline;
+
+    #[async_trait::async_trait]
+    impl ContextualPipeline for StaticPipeline {
+        async fn extract_with_context(&self, _context: &PipelineContext) -> Result<Vec<Record>> {
+            Ok(vec![Record {
+                data: [("id".to_string(), serde_json::json!(1))].into(),
+            }])
+        }
+
+        async fn transform_with_context(
+            &self,
+            data: Vec<Record>,
+            _context: &mut PipelineContext,
+        ) -> Result<TransformResult> {
+            Ok(TransformResult {
+                processed_records: data,
+                csv_output: String::new(),
+                tsv_output: String::new(),
+                intermediate_data: Vec::new(),
+            })
+        }
+
+        async fn load_with_context(
+            &self,
+            _result: &TransformResult,
+            _context: &PipelineContext,
+        ) -> Result<String> {
+            Ok("/tmp/static_output.json".to_string())
+        }
+
+        fn get_name(&self) -> &str {
+            "static"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequence_engine_records_budget_checkpoints() {
+        let mut sequence = PipelineSequence::new("engine".to_string());
+        sequence.add_pipeline(Box::new(StaticPipeline));
+        let budget = ExecutionBudget::new(Duration::from_secs(60));
+        let mut engine =
+            SequenceEngine::new_with_monitoring(sequence, true).with_budget(budget.clone());
+
+        let results = engine.run().await.unwrap();
+        assert_eq!(results.len(), 1);
+        let labels: Vec<String> = budget
+            .checkpoints()
+            .into_iter()
+            .map(|checkpoint| checkpoint.label)
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["Sequence Start", "After static", "Sequence End"]
+        );
+    }
+}
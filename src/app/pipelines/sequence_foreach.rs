@@ -0,0 +1,342 @@
+use crate::app::pipelines::sequence_runner::{write_run_report, RunOptions, SequenceRunner};
+use crate::config::sequence_config::{ForeachConfig, SequenceConfig};
+use crate::core::pipeline_sequence::PipelineResult;
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// 逐項執行時目前的項目；以 `{{name}}`、`{{name.field}}` 提供給模板
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeachItem {
+    pub name: String,
+    pub index: usize, // 從 1 開始
+    pub value: serde_json::Value,
+}
+
+impl ForeachItem {
+    pub fn new(name: &str, index: usize, value: serde_json::Value) -> Self {
+        Self {
+            name: name.to_string(),
+            index,
+            value,
+        }
+    }
+
+    /// 寫入共享數據的鍵值：整個項目，以及物件的每個頂層欄位（`tenant.id`）
+    pub fn shared_data(&self) -> Vec<(String, serde_json::Value)> {
+        let mut entries = vec![(self.name.clone(), self.value.clone())];
+        if let serde_json::Value::Object(fields) = &self.value {
+            for (field, value) in fields {
+                entries.push((format!("{}.{}", self.name, field), value.clone()));
+            }
+        }
+        entries
+    }
+
+    /// 解析 `name` 或 `name.a.b` 佔位符的值；不是此項目的佔位符時返回 None
+    pub fn resolve(&self, key: &str) -> Option<&serde_json::Value> {
+        if key == self.name {
+            return Some(&self.value);
+        }
+        key.strip_prefix(self.name.as_str())?
+            .strip_prefix('.')?
+            .split('.')
+            .try_fold(&self.value, |value, field| match value {
+                serde_json::Value::Array(items) => items.get(field.parse::<usize>().ok()?),
+                _ => value.get(field),
+            })
+    }
+
+    /// 此項目的執行 ID 後綴：`key` 欄位的值（只保留安全字元），否則為序號
+    pub fn suffix(&self, key: Option<&str>) -> String {
+        let value = key
+            .and_then(|key| self.value.get(key))
+            .map(|value| match value {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            });
+        let suffix: String = value
+            .unwrap_or_else(|| self.index.to_string())
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if suffix.is_empty() {
+            self.index.to_string()
+        } else {
+            suffix
+        }
+    }
+}
+
+/// 讀取 items 或 file 中的項目（from_pipeline 需先執行該 Pipeline，見 `run_foreach`）
+pub fn load_items(config: &ForeachConfig) -> Result<Vec<serde_json::Value>> {
+    if let Some(items) = &config.items {
+        return Ok(items.clone());
+    }
+    let Some(file) = &config.file else {
+        return Ok(Vec::new());
+    };
+    let content = std::fs::read_to_string(file)?;
+    match serde_json::from_str(&content)? {
+        serde_json::Value::Array(items) => Ok(items),
+        _ => Err(EtlError::InvalidConfigValueError {
+            field: "sequence.foreach.file".to_string(),
+            value: file.clone(),
+            reason: "File must contain a JSON array".to_string(),
+        }),
+    }
+}
+
+/// 單一項目的執行結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForeachStatus {
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ForeachEntry {
+    pub index: usize,
+    pub item: serde_json::Value,
+    pub execution_id: String,
+    pub status: ForeachStatus,
+    pub pipelines: usize,
+    pub records: usize,
+    pub duration_ms: u128,
+    pub error: Option<String>,
+}
+
+impl ForeachEntry {
+    pub fn new(
+        item: &ForeachItem,
+        execution_id: &str,
+        duration: Duration,
+        outcome: &Result<Vec<PipelineResult>>,
+    ) -> Self {
+        let (status, pipelines, records, error) = match outcome {
+            Ok(results) => (
+                ForeachStatus::Succeeded,
+                results.iter().filter(|result| !result.is_skipped()).count(),
+                results.iter().map(PipelineResult::record_count).sum(),
+                None,
+            ),
+            Err(e) => (ForeachStatus::Failed, 0, 0, Some(e.to_string())),
+        };
+        Self {
+            index: item.index,
+            item: item.value.clone(),
+            execution_id: execution_id.to_string(),
+            status,
+            pipelines,
+            records,
+            duration_ms: duration.as_millis(),
+            error,
+        }
+    }
+}
+
+/// 逐項執行的彙整報告；因失敗而未執行的項目列在 `not_run`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ForeachReport {
+    pub sequence_name: String,
+    pub execution_id: String,
+    pub entries: Vec<ForeachEntry>,
+    pub not_run: usize,
+}
+
+impl ForeachReport {
+    pub fn count(&self, status: ForeachStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    pub fn all_succeeded(&self) -> bool {
+        self.not_run == 0 && self.count(ForeachStatus::Succeeded) == self.entries.len()
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!(
+            "Foreach report for {}: {} items - {} succeeded, {} failed, {} not run",
+            self.sequence_name,
+            self.entries.len() + self.not_run,
+            self.count(ForeachStatus::Succeeded),
+            self.count(ForeachStatus::Failed),
+            self.not_run
+        )];
+        for entry in &self.entries {
+            let icon = match entry.status {
+                ForeachStatus::Succeeded => "✅",
+                ForeachStatus::Failed => "❌",
+            };
+            let mut line = format!("  {} #{} {}", icon, entry.index, entry.item);
+            if entry.status == ForeachStatus::Succeeded {
+                line.push_str(&format!(
+                    " - {} pipelines, {} records in {}ms",
+                    entry.pipelines, entry.records, entry.duration_ms
+                ));
+            }
+            line.push_str(&format!(" [{}]", entry.execution_id));
+            lines.push(line);
+            if let Some(error) = &entry.error {
+                lines.push(format!("      {}", error));
+            }
+        }
+        lines.join("\n")
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 依 sequence.foreach 逐一執行整個序列，返回各項目的彙整報告
+///
+/// 每個項目使用 `{execution_id}_{後綴}` 作為執行 ID，並各自寫入執行報告。
+/// 設定 from_pipeline 時先以 `{execution_id}_{pipeline}` 執行該 Pipeline 一次，其輸出記錄即為項目，
+/// 之後逐項執行時略過該 Pipeline。該 Pipeline 失敗時返回錯誤。
+pub async fn run_foreach(config: &SequenceConfig, options: &RunOptions) -> Result<ForeachReport> {
+    let Some(foreach) = &config.sequence.foreach else {
+        return Err(EtlError::ConfigValidationError {
+            field: "sequence.foreach".to_string(),
+            message: "Sequence has no foreach section".to_string(),
+        });
+    };
+    let execution_id = options.resolve_execution_id();
+    let mut skip = options.skip.clone();
+
+    let items = match &foreach.from_pipeline {
+        Some(from_pipeline) => {
+            let list_options = RunOptions {
+                execution_id: Some(format!("{}_{}", execution_id, from_pipeline)),
+                resume: None,
+                only: vec![from_pipeline.clone()],
+                skip: Vec::new(),
+                foreach_item: None,
+                ..options.clone()
+            };
+            let results = SequenceRunner::new(config, &list_options)?.run().await?;
+            skip.push(from_pipeline.clone());
+            results
+                .iter()
+                .filter(|result| &result.pipeline_name == from_pipeline)
+                .flat_map(|result| result.records.iter())
+                .map(|record| serde_json::to_value(&record.data))
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        }
+        None => load_items(foreach)?,
+    };
+    tracing::info!(
+        "🔁 Running sequence {} for {} {} items",
+        config.sequence.name,
+        items.len(),
+        foreach.name()
+    );
+
+    let mut report = ForeachReport {
+        sequence_name: config.sequence.name.clone(),
+        execution_id: execution_id.clone(),
+        ..Default::default()
+    };
+    let total = items.len();
+    for (index, value) in items.into_iter().enumerate() {
+        let item = ForeachItem::new(foreach.name(), index + 1, value);
+        let item_execution_id = format!("{}_{}", execution_id, item.suffix(foreach.key.as_deref()));
+        tracing::info!(
+            "▶️ {} {}/{}: {} as {}",
+            foreach.name(),
+            item.index,
+            total,
+            item.value,
+            item_execution_id
+        );
+        let item_options = RunOptions {
+            execution_id: Some(item_execution_id.clone()),
+            resume: None,
+            skip: skip.clone(),
+            foreach_item: Some(item.clone()),
+            ..options.clone()
+        };
+
+        let started_at = Instant::now();
+        let outcome = match SequenceRunner::new(config, &item_options) {
+            Ok(runner) => runner.run().await,
+            Err(e) => Err(e),
+        };
+        let exit_code = outcome.as_ref().err().map_or(0, EtlError::exit_code);
+        write_run_report(
+            config,
+            &item_options,
+            &item_execution_id,
+            &outcome,
+            exit_code,
+            started_at.elapsed(),
+        );
+        let entry = ForeachEntry::new(&item, &item_execution_id, started_at.elapsed(), &outcome);
+        report.entries.push(entry);
+
+        if let Err(e) = outcome {
+            tracing::error!("❌ {} {} failed: {}", foreach.name(), item.index, e);
+            let interrupted = matches!(e.root(), EtlError::Interrupted { .. });
+            if interrupted || !foreach.continue_on_error() {
+                report.not_run = total - item.index;
+                break;
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_item_shared_data_and_resolve() {
+        let item = ForeachItem::new(
+            "tenant",
+            2,
+            json!({"id": "acme", "regions": ["eu", "us"], "plan": {"tier": "gold"}}),
+        );
+        let keys: Vec<String> = item.shared_data().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(
+            keys,
+            ["tenant", "tenant.id", "tenant.plan", "tenant.regions"]
+        );
+        assert_eq!(item.resolve("tenant.id"), Some(&json!("acme")));
+        assert_eq!(item.resolve("tenant.plan.tier"), Some(&json!("gold")));
+        assert_eq!(item.resolve("tenant.regions.1"), Some(&json!("us")));
+        assert_eq!(item.resolve("tenants.id"), None);
+        assert_eq!(item.resolve("tenant.missing"), None);
+
+        assert_eq!(item.suffix(Some("id")), "acme");
+        assert_eq!(item.suffix(Some("plan")), "__tier___gold__");
+        assert_eq!(item.suffix(None), "2");
+    }
+
+    #[test]
+    fn test_load_items_from_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tenants.json");
+        std::fs::write(&path, r#"[{"id": "a"}, {"id": "b"}]"#).unwrap();
+        let config = ForeachConfig {
+            file: Some(path.display().to_string()),
+            ..Default::default()
+        };
+        assert_eq!(load_items(&config).unwrap().len(), 2);
+
+        std::fs::write(&path, r#"{"id": "a"}"#).unwrap();
+        assert!(load_items(&config).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use crate::app::pipelines::pipeline_builder::{PipelineBuilder, SequenceBuilder};
+use crate::config::sequence_config::{AuthConfig, LoadConfig, SequenceConfig};
+use crate::utils::error::{EtlError, Result};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// `sequence-etl init` 產生的設定檔名稱
+pub const CONFIG_FILE: &str = "sequence.toml";
+
+/// 來源 API 的認證方式；機密一律從環境變數帶入，不寫進設定檔
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitAuth {
+    None,
+    /// `Authorization: Bearer ${API_TOKEN}`
+    Bearer,
+    /// `X-API-Key` 標頭，金鑰取自 API_KEY
+    ApiKey,
+    /// HTTP Basic，帳號密碼取自 API_USERNAME / API_PASSWORD
+    Basic,
+    /// client credentials，token_url 由使用者輸入
+    OAuth2 {
+        token_url: String,
+    },
+}
+
+impl InitAuth {
+    pub const SUPPORTED: [&'static str; 5] = ["none", "bearer", "api_key", "basic", "oauth2"];
+
+    /// 設定檔引用的環境變數
+    pub fn env_vars(&self) -> &'static [&'static str] {
+        match self {
+            Self::None => &[],
+            Self::Bearer => &["API_TOKEN"],
+            Self::ApiKey => &["API_KEY"],
+            Self::Basic => &["API_USERNAME", "API_PASSWORD"],
+            Self::OAuth2 { .. } => &["CLIENT_ID", "CLIENT_SECRET"],
+        }
+    }
+}
+
+/// init 精靈的回答
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitAnswers {
+    pub name: String,
+    pub endpoint: String,
+    pub auth: InitAuth,
+    pub output_formats: Vec<String>,
+    pub output_path: String,
+}
+
+impl InitAnswers {
+    /// 依回答組出單一 Pipeline 的序列設定（經 `SequenceConfig::validate` 驗證）
+    ///
+    /// Pipeline 名稱取自端點路徑的最後一段，例如 `/v1/users` 為 `users`。
+    pub fn to_config(&self) -> Result<SequenceConfig> {
+        let mut pipeline = PipelineBuilder::new(pipeline_name(&self.endpoint))
+            .description(format!("Fetch records from {}", self.endpoint))
+            .source_api(&self.endpoint)
+            .output_path(&self.output_path);
+        for format in &self.output_formats {
+            pipeline = pipeline.output_format(format);
+        }
+        if self.auth == InitAuth::Bearer {
+            pipeline = pipeline.header("Authorization", "Bearer ${API_TOKEN}");
+        }
+
+        let mut definition = pipeline.build();
+        definition.source.auth = match &self.auth {
+            InitAuth::None | InitAuth::Bearer => None,
+            InitAuth::ApiKey => Some(AuthConfig {
+                r#type: "api_key".to_string(),
+                key_env: Some("API_KEY".to_string()),
+                ..Default::default()
+            }),
+            InitAuth::Basic => Some(AuthConfig {
+                r#type: "basic".to_string(),
+                username_env: Some("API_USERNAME".to_string()),
+                password_env: Some("API_PASSWORD".to_string()),
+                ..Default::default()
+            }),
+            InitAuth::OAuth2 { token_url } => Some(AuthConfig {
+                r#type: "oauth2".to_string(),
+                token_url: Some(token_url.clone()),
+                client_id: Some("${CLIENT_ID}".to_string()),
+                client_secret: Some("${CLIENT_SECRET}".to_string()),
+                ..Default::default()
+            }),
+        };
+
+        SequenceBuilder::new(&self.name)
+            .description(format!("{} (generated by sequence-etl init)", self.name))
+            .pipeline(definition)
+            .build()
+    }
+
+    /// 產生的 TOML 內容，開頭附上執行方式與需要的環境變數
+    pub fn render(&self) -> Result<String> {
+        let config = self.to_config()?;
+        let body = toml::to_string(&config).map_err(|e| EtlError::ConfigError {
+            message: format!("Failed to render config: {}", e),
+        })?;
+
+        let mut header = format!(
+            "# Generated by `sequence-etl init`\n# Run: sequence-etl --config {}\n",
+            CONFIG_FILE
+        );
+        if !self.auth.env_vars().is_empty() {
+            header.push_str(&format!(
+                "# Required environment variables (see .env.example): {}\n",
+                self.auth.env_vars().join(", ")
+            ));
+        }
+        Ok(format!("{}\n{}", header, body))
+    }
+}
+
+/// 端點路徑最後一段中的英數字，沒有時為 `records`
+fn pipeline_name(endpoint: &str) -> String {
+    let path = url::Url::parse(endpoint)
+        .map(|url| url.path().to_string())
+        .unwrap_or_default();
+    let name: String = path
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let name = name.trim_matches('_').to_ascii_lowercase();
+    if name.is_empty() {
+        "records".to_string()
+    } else {
+        name
+    }
+}
+
+/// 逐一提問並讀取回答；空白回答採用預設值，無效的回答會重新詢問
+///
+/// 輸入結束（EOF）時返回錯誤，避免在非互動環境中無限重問。
+pub fn ask<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> Result<InitAnswers> {
+    let name = prompt(
+        input,
+        output,
+        "Sequence name",
+        Some("my-sequence"),
+        |answer| Ok(answer.to_string()),
+    )?;
+    let endpoint = prompt(input, output, "Source API URL", None, |answer| {
+        crate::utils::validation::validate_url("endpoint", answer)?;
+        Ok(answer.to_string())
+    })?;
+    let auth = prompt(
+        input,
+        output,
+        &format!("Auth type ({})", InitAuth::SUPPORTED.join(", ")),
+        Some("none"),
+        |answer| match answer {
+            "none" => Ok(Some(InitAuth::None)),
+            "bearer" => Ok(Some(InitAuth::Bearer)),
+            "api_key" => Ok(Some(InitAuth::ApiKey)),
+            "basic" => Ok(Some(InitAuth::Basic)),
+            "oauth2" => Ok(None),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "auth".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported types: {}", InitAuth::SUPPORTED.join(", ")),
+            }),
+        },
+    )?;
+    let auth = match auth {
+        Some(auth) => auth,
+        None => InitAuth::OAuth2 {
+            token_url: prompt(input, output, "OAuth2 token URL", None, |answer| {
+                crate::utils::validation::validate_url("token_url", answer)?;
+                Ok(answer.to_string())
+            })?,
+        },
+    };
+    let output_formats = prompt(
+        input,
+        output,
+        &format!(
+            "Output formats, comma separated ({})",
+            LoadConfig::OUTPUT_FORMATS.join(", ")
+        ),
+        Some("csv,json"),
+        |answer| {
+            let mut formats: Vec<String> = Vec::new();
+            for format in answer.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+                if !LoadConfig::OUTPUT_FORMATS.contains(&format) {
+                    return Err(EtlError::InvalidConfigValueError {
+                        field: "output_formats".to_string(),
+                        value: format.to_string(),
+                        reason: format!(
+                            "Supported formats: {}",
+                            LoadConfig::OUTPUT_FORMATS.join(", ")
+                        ),
+                    });
+                }
+                if !formats.iter().any(|f| f == format) {
+                    formats.push(format.to_string());
+                }
+            }
+            if formats.is_empty() {
+                return Err(EtlError::ConfigError {
+                    message: "At least one output format is required".to_string(),
+                });
+            }
+            Ok(formats)
+        },
+    )?;
+    let output_path = prompt(input, output, "Output path", Some("./output"), |answer| {
+        Ok(answer.to_string())
+    })?;
+
+    Ok(InitAnswers {
+        name,
+        endpoint,
+        auth,
+        output_formats,
+        output_path,
+    })
+}
+
+fn prompt<R, W, T, F>(
+    input: &mut R,
+    output: &mut W,
+    question: &str,
+    default: Option<&str>,
+    parse: F,
+) -> Result<T>
+where
+    R: BufRead,
+    W: Write,
+    F: Fn(&str) -> Result<T>,
+{
+    loop {
+        match default {
+            Some(default) => write!(output, "{} [{}]: ", question, default)?,
+            None => write!(output, "{}: ", question)?,
+        }
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Err(EtlError::ConfigError {
+                message: format!("No answer for '{}'", question),
+            });
+        }
+        let answer = match (line.trim(), default) {
+            ("", Some(default)) => default,
+            ("", None) => {
+                writeln!(output, "  An answer is required")?;
+                continue;
+            }
+            (answer, _) => answer,
+        };
+        match parse(answer) {
+            Ok(value) => return Ok(value),
+            Err(e) => writeln!(output, "  {}", e)?,
+        }
+    }
+}
+
+/// 在 dir 下寫出 sequence.toml、輸出目錄與 .env.example（需要認證時），返回建立的路徑
+///
+/// sequence.toml 已存在且未指定 force 時返回錯誤，不覆寫任何檔案。
+pub fn scaffold(dir: &Path, answers: &InitAnswers, force: bool) -> Result<Vec<PathBuf>> {
+    let config_path = dir.join(CONFIG_FILE);
+    if config_path.exists() && !force {
+        return Err(EtlError::ConfigError {
+            message: format!(
+                "{} already exists; use --force to overwrite",
+                config_path.display()
+            ),
+        });
+    }
+    let content = answers.render()?;
+
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&config_path, content)?;
+    let mut created = vec![config_path];
+
+    // 相對的輸出路徑以設定檔所在目錄為準建立，方便直接在 dir 中執行
+    let output_dir = dir.join(&answers.output_path);
+    std::fs::create_dir_all(&output_dir)?;
+    created.push(output_dir);
+
+    let env_vars = answers.auth.env_vars();
+    if !env_vars.is_empty() {
+        let env_path = dir.join(".env.example");
+        let content: String = env_vars.iter().map(|name| format!("{}=\n", name)).collect();
+        std::fs::write(&env_path, content)?;
+        created.push(env_path);
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(auth: InitAuth) -> InitAnswers {
+        InitAnswers {
+            name: "demo".to_string(),
+            endpoint: "https://api.example.com/v1/user-list?page=1".to_string(),
+            auth,
+            output_formats: vec!["csv".to_string(), "json".to_string()],
+            output_path: "./output".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_name_from_endpoint() {
+        assert_eq!(pipeline_name("https://api.example.com/v1/users/"), "users");
+        assert_eq!(
+            pipeline_name("https://api.example.com/v1/user-list"),
+            "user_list"
+        );
+        assert_eq!(pipeline_name("https://api.example.com"), "records");
+    }
+
+    #[test]
+    fn test_rendered_config_round_trips() {
+        for auth in [
+            InitAuth::None,
+            InitAuth::Bearer,
+            InitAuth::ApiKey,
+            InitAuth::Basic,
+            InitAuth::OAuth2 {
+                token_url: "https://auth.example.com/token".to_string(),
+            },
+        ] {
+            let rendered = answers(auth.clone()).render().unwrap();
+            let config = SequenceConfig::from_toml_str(&rendered).unwrap();
+            config.validate().unwrap();
+
+            let pipeline = &config.pipelines[0];
+            assert_eq!(pipeline.name, "user_list");
+            assert_eq!(config.sequence.execution_order, ["user_list"]);
+            assert_eq!(pipeline.load.output_formats, ["csv", "json"]);
+            assert_eq!(
+                pipeline.source.auth.is_some(),
+                !matches!(auth, InitAuth::None | InitAuth::Bearer)
+            );
+            for name in auth.env_vars() {
+                assert!(rendered.contains(name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ask_reprompts_invalid_answers() {
+        let mut input = "\nnot a url\nhttps://api.example.com/items\ndigest\noauth2\nhttps://auth.example.com/token\nxml, json\njson\n\n".as_bytes();
+        let mut output = Vec::new();
+        let answers = ask(&mut input, &mut output).unwrap();
+
+        assert_eq!(answers.name, "my-sequence");
+        assert_eq!(answers.endpoint, "https://api.example.com/items");
+        assert_eq!(
+            answers.auth,
+            InitAuth::OAuth2 {
+                token_url: "https://auth.example.com/token".to_string()
+            }
+        );
+        assert_eq!(answers.output_formats, ["json"]);
+        assert_eq!(answers.output_path, "./output");
+
+        let transcript = String::from_utf8(output).unwrap();
+        assert!(transcript.contains("endpoint = 'not a url'"));
+        assert!(transcript.contains("auth = 'digest'"));
+        assert!(transcript.contains("output_formats = 'xml'"));
+
+        // 輸入提前結束時不會卡住
+        assert!(ask(&mut "demo\n".as_bytes(), &mut Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,513 @@
+use crate::app::pipelines::sequence_backfill::WINDOW_ITEM;
+use crate::app::pipelines::sequence_dry_run::{single_brace_names, template_names};
+use crate::config::sequence_config::{PipelineDefinition, SequenceConfig};
+use crate::core::builtin_templates::is_builtin;
+use crate::utils::error::EtlError;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    /// 載入或執行時一定會失敗
+    Error,
+    /// 可能有問題，例如找不到來源的佔位符
+    Warning,
+}
+
+/// 單一檢查結果；`line`、`column` 從 1 開始，無法定位時省略
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+/// 設定檔檢查結果（`sequence-etl validate`）
+#[derive(Debug, Clone, Serialize)]
+pub struct LintReport {
+    pub file: String,
+    pub valid: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    fn new(file: &str, mut diagnostics: Vec<Diagnostic>) -> Self {
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line.unwrap_or(usize::MAX));
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        let errors = count(DiagnosticSeverity::Error);
+        let warnings = count(DiagnosticSeverity::Warning);
+        Self {
+            file: file.to_string(),
+            valid: errors == 0,
+            errors,
+            warnings,
+            diagnostics,
+        }
+    }
+
+    /// 類似編譯器的文字輸出：`檔案:行:欄: error[code]: 訊息`
+    pub fn render(&self) -> String {
+        let mut lines: Vec<String> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let location = match (diagnostic.line, diagnostic.column) {
+                    (Some(line), Some(column)) => format!("{}:{}:{}", self.file, line, column),
+                    (Some(line), None) => format!("{}:{}", self.file, line),
+                    _ => self.file.clone(),
+                };
+                let severity = match diagnostic.severity {
+                    DiagnosticSeverity::Error => "error",
+                    DiagnosticSeverity::Warning => "warning",
+                };
+                format!(
+                    "{}: {}[{}]: {}",
+                    location, severity, diagnostic.code, diagnostic.message
+                )
+            })
+            .collect();
+        lines.push(format!(
+            "{}: {} errors, {} warnings",
+            self.file, self.errors, self.warnings
+        ));
+        lines.join("\n")
+    }
+}
+
+/// 讀取並檢查設定檔
+pub fn lint_file(path: &Path) -> LintReport {
+    let file = path.display().to_string();
+    match std::fs::read_to_string(path) {
+        Ok(content) => lint_str(&file, &content),
+        Err(e) => LintReport::new(
+            &file,
+            vec![diagnostic(
+                DiagnosticSeverity::Error,
+                "io",
+                format!("Cannot read config file: {}", e),
+                None,
+                None,
+            )],
+        ),
+    }
+}
+
+/// 執行完整檢查：TOML 語法與未知欄位、未設定的環境變數、端點 URL、
+/// 沒有來源的模板佔位符，以及 `SequenceConfig::validate`
+pub fn lint_str(file: &str, content: &str) -> LintReport {
+    let mut diagnostics = Vec::new();
+    let processed = match SequenceConfig::substitute_all_vars(content) {
+        Ok(processed) => processed,
+        Err(e) => {
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                "parse",
+                e.to_string(),
+                None,
+                None,
+            ));
+            return LintReport::new(file, diagnostics);
+        }
+    };
+    check_env_vars(&processed, &mut diagnostics);
+
+    let mut config = match toml::from_str::<SequenceConfig>(&processed) {
+        Ok(config) => config,
+        Err(e) => {
+            let code = if e.message().starts_with("unknown field") {
+                "unknown_key"
+            } else {
+                "parse"
+            };
+            let position = e.span().map(|span| line_column(&processed, span.start));
+            let mut parse_error = diagnostic(
+                DiagnosticSeverity::Error,
+                code,
+                e.message().trim().to_string(),
+                None,
+                position.map(|(line, _)| line),
+            );
+            parse_error.column = position.map(|(_, column)| column);
+            diagnostics.push(parse_error);
+            return LintReport::new(file, diagnostics);
+        }
+    };
+
+    // include 的路徑相對於設定檔所在目錄
+    let base_dir = Path::new(file).parent().unwrap_or(Path::new("."));
+    if let Err(e) = config.resolve_includes(base_dir) {
+        diagnostics.push(diagnostic(
+            DiagnosticSeverity::Error,
+            "include",
+            e.to_string(),
+            Some("include".to_string()),
+            locate_field(content, "include"),
+        ));
+        return LintReport::new(file, diagnostics);
+    }
+
+    for pipeline in &config.pipelines {
+        check_endpoint(content, pipeline, &mut diagnostics);
+        check_placeholders(content, &config, pipeline, &mut diagnostics);
+    }
+
+    // validate 在第一個錯誤停止；與前面已列出的相同錯誤不重複
+    if let Err(e) = config.validate() {
+        let message = e.to_string();
+        if !diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message == message)
+        {
+            let field = error_field(&e);
+            let line = field
+                .as_deref()
+                .and_then(|field| locate_field(content, field));
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                "validation",
+                message,
+                field,
+                line,
+            ));
+        }
+    }
+
+    LintReport::new(file, diagnostics)
+}
+
+fn diagnostic(
+    severity: DiagnosticSeverity,
+    code: &'static str,
+    message: String,
+    field: Option<String>,
+    line: Option<usize>,
+) -> Diagnostic {
+    Diagnostic {
+        severity,
+        code,
+        message,
+        field,
+        line,
+        column: None,
+    }
+}
+
+/// 載入時找不到的環境變數會保留為 ${NAME}；
+/// 註解與 regex replacement（以 ${name} 參照擷取群組）不檢查
+fn check_env_vars(processed: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let pattern = regex::Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    for (index, line) in processed.lines().enumerate() {
+        let code = line.trim_start();
+        if code.starts_with('#') || code.starts_with("replacement") {
+            continue;
+        }
+        for caps in pattern.captures_iter(line) {
+            let mut unresolved = diagnostic(
+                DiagnosticSeverity::Error,
+                "unresolved_env_var",
+                format!("Environment variable ${{{}}} is not set", &caps[1]),
+                None,
+                Some(index + 1),
+            );
+            unresolved.column = Some(caps.get(0).unwrap().start() + 1);
+            diagnostics.push(unresolved);
+        }
+    }
+}
+
+/// 端點 URL 語法（佔位符與環境變數由其他檢查負責）
+fn check_endpoint(content: &str, pipeline: &PipelineDefinition, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(endpoint) = pipeline.source.endpoint.as_deref() else {
+        return;
+    };
+    if endpoint.contains("${") {
+        return;
+    }
+    let field = format!("pipelines.{}.source.endpoint", pipeline.name);
+    // 與 validate 使用相同欄位名稱，避免同一錯誤列出兩次
+    if let Err(e) = crate::utils::validation::validate_url("source.endpoint", endpoint) {
+        diagnostics.push(diagnostic(
+            DiagnosticSeverity::Error,
+            "invalid_url",
+            e.to_string(),
+            Some(field.clone()),
+            locate_field(content, &field),
+        ));
+    }
+}
+
+/// 沒有任何來源可提供值的 {{name}}：沒有上游 Pipeline、不是 checkpoint、查找參照、
+/// 批次參數或 template_params，也沒有 Pipeline 會寫入共享數據
+fn check_placeholders(
+    content: &str,
+    config: &SequenceConfig,
+    pipeline: &PipelineDefinition,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let source = &pipeline.source;
+    let data_source = source.data_source.as_ref();
+    let has_upstream = pipeline
+        .dependencies
+        .as_ref()
+        .is_some_and(|dependencies| !dependencies.is_empty())
+        || source.join.is_some()
+        || source.combined.is_some()
+        || data_source.is_some_and(|data_source| {
+            data_source.from_pipeline.is_some() || data_source.use_previous_output.unwrap_or(false)
+        });
+    if has_upstream {
+        return;
+    }
+    let shared_data_written = config.pipelines.iter().any(|pipeline| {
+        pipeline
+            .transform
+            .intermediate
+            .as_ref()
+            .is_some_and(|intermediate| intermediate.export_to_shared.unwrap_or(false))
+    });
+    let producers = config
+        .global
+        .as_ref()
+        .and_then(|global| global.shared_data.as_ref())
+        .and_then(|shared_data| shared_data.producers.as_ref());
+    let template_params = source
+        .payload
+        .as_ref()
+        .and_then(|payload| payload.template_params.as_ref());
+    let batch_placeholder = source
+        .batch_parameters
+        .as_ref()
+        .map(|batch| batch.placeholder.as_str());
+    let variables = config
+        .global
+        .as_ref()
+        .and_then(|global| global.variables.as_ref());
+    let foreach_name = config
+        .sequence
+        .foreach
+        .as_ref()
+        .map(|foreach| foreach.name());
+    let is_item = |name: &str, item: &str| {
+        name == item
+            || name
+                .strip_prefix(item)
+                .is_some_and(|rest| rest.starts_with('.'))
+    };
+    let has_source = |name: &str| {
+        name.starts_with("lookup:")
+            || is_builtin(name)
+            || foreach_name.is_some_and(|item| is_item(name, item))
+            // `sequence-etl backfill` 提供的時間窗
+            || is_item(name, WINDOW_ITEM)
+            || name
+                .strip_prefix("var.")
+                .is_some_and(|key| variables.is_some_and(|vars| vars.contains_key(key)))
+            || batch_placeholder == Some(name)
+            || template_params.is_some_and(|params| params.contains_key(name))
+            || producers.is_some_and(|producers| producers.contains_key(name))
+            || name.strip_prefix("checkpoint.").is_some_and(|key| {
+                pipeline
+                    .checkpoint
+                    .as_ref()
+                    .is_some_and(|checkpoint| checkpoint.watermarks.contains_key(key))
+            })
+            || (shared_data_written && !name.starts_with("checkpoint."))
+    };
+
+    let mut templates: Vec<(String, &str)> = Vec::new();
+    if let Some(endpoint) = &source.endpoint {
+        templates.push(("endpoint".to_string(), endpoint));
+    }
+    for (name, value) in source.headers.iter().flatten() {
+        templates.push((format!("headers.{}", name), value));
+    }
+    for (location, template) in source
+        .payload
+        .iter()
+        .flat_map(|payload| payload.templates())
+    {
+        templates.push((format!("payload.{}", location), template));
+    }
+
+    for (location, template) in templates {
+        let mut names = template_names(template);
+        if location == "endpoint" {
+            names.extend(single_brace_names(template));
+        }
+        for name in names.into_iter().filter(|name| !has_source(name)) {
+            let field = format!("pipelines.{}.source.{}", pipeline.name, location);
+            let line = locate_in_pipeline(content, &pipeline.name, |line| line.contains(&name))
+                .or_else(|| locate_field(content, &field));
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Warning,
+                "unresolved_placeholder",
+                format!(
+                    "Placeholder '{}' in source.{} has no source: the pipeline has no upstream data and nothing provides this value",
+                    name, location
+                ),
+                Some(field),
+                line,
+            ));
+        }
+    }
+}
+
+fn error_field(error: &EtlError) -> Option<String> {
+    match error {
+        EtlError::ConfigValidationError { field, .. }
+        | EtlError::InvalidConfigValueError { field, .. }
+        | EtlError::MissingConfigError { field } => Some(field.clone()),
+        _ => None,
+    }
+}
+
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
+/// 依欄位路徑找出設定所在行：`pipelines.NAME.…` 在該 Pipeline 區塊內找最後一段鍵，
+/// 其他欄位從檔案開頭找
+fn locate_field(content: &str, field: &str) -> Option<usize> {
+    let key = field.rsplit('.').next()?;
+    let matches_key = |line: &str| {
+        let line = line.trim_start().trim_start_matches('[');
+        let line = line.trim_start_matches('"');
+        line.starts_with(key)
+            && line[key.len()..]
+                .trim_start_matches('"')
+                .trim_start()
+                .starts_with(['=', ']', '.'])
+    };
+    if let Some(rest) = field.strip_prefix("pipelines.") {
+        let pipeline = content_pipeline_name(content, rest)?;
+        return locate_in_pipeline(content, pipeline, matches_key)
+            .or_else(|| locate_in_pipeline(content, pipeline, |_| true));
+    }
+    content
+        .lines()
+        .position(|line| !line.trim_start().starts_with('#') && matches_key(line))
+        .map(|index| index + 1)
+}
+
+/// `pipelines.` 後的路徑以 Pipeline 名稱開頭（名稱可能含有 `.`），取檔案中最長的相符名稱
+fn content_pipeline_name<'a>(content: &str, path: &'a str) -> Option<&'a str> {
+    path.match_indices('.')
+        .map(|(index, _)| &path[..index])
+        .chain([path])
+        .filter(|name| pipeline_start(content, name).is_some())
+        .max_by_key(|name| name.len())
+}
+
+fn pipeline_start(content: &str, name: &str) -> Option<usize> {
+    let pattern = regex::Regex::new(&format!(
+        r#"^\s*name\s*=\s*["']{}["']"#,
+        regex::escape(name)
+    ))
+    .ok()?;
+    let mut in_pipeline = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_pipeline = trimmed == "[[pipelines]]";
+        } else if in_pipeline && pattern.is_match(line) {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// 在 Pipeline 區塊（從 name 那一行到下一個 `[[pipelines]]`）內找第一個符合的行
+fn locate_in_pipeline(content: &str, name: &str, matches: impl Fn(&str) -> bool) -> Option<usize> {
+    let start = pipeline_start(content, name)?;
+    content
+        .lines()
+        .enumerate()
+        .skip(start)
+        .take_while(|(index, line)| *index == start || line.trim() != "[[pipelines]]")
+        .find(|(_, line)| !line.trim_start().starts_with('#') && matches(line))
+        .map(|(index, _)| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[sequence]
+name = "lint"
+description = "lint test"
+version = "1.0.0"
+execution_order = ["users"]
+
+[[pipelines]]
+name = "users"
+
+[pipelines.source]
+type = "api"
+endpoint = "ftp://example.com/users/{{tenant}}"
+
+[pipelines.source.headers]
+Authorization = "Bearer ${LINT_TEST_UNSET_TOKEN}"
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output"
+output_formats = ["json"]
+"#;
+
+    #[test]
+    fn test_lint_reports_lines() {
+        let report = lint_str("lint.toml", CONFIG);
+        assert!(!report.valid);
+        let codes: Vec<(&str, Option<usize>)> = report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.line))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                ("invalid_url", Some(13)),
+                ("unresolved_placeholder", Some(13)),
+                ("unresolved_env_var", Some(16)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_unknown_key() {
+        let config = CONFIG.replace("[pipelines.extract]", "[pipelines.extract]\nmax_record = 5");
+        let report = lint_str("lint.toml", &config);
+        let unknown = report
+            .diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.code == "unknown_key")
+            .unwrap();
+        assert_eq!(unknown.line, Some(19));
+        assert!(unknown.message.contains("max_record"));
+    }
+}
@@ -1,26 +1,137 @@
-use crate::core::{Record, TransformResult};
+use crate::app::pipelines::sequence_foreach::ForeachItem;
+use crate::app::pipelines::shared_data::{SharedDataPolicy, SharedDataStore, SharedDataWrite};
+use crate::core::context_spill::{ContextSpill, SpilledRecords};
+use crate::core::intermediate_output::{AggregateSink, IntermediateAggregate, IntermediateFormat};
+use crate::core::output_variables::evaluate_outputs;
+use crate::core::progress_file::{PipelineProgressStatus, PipelineStage, ProgressFile};
+use crate::core::sequence_state::{SequenceState, SequenceStateStore, SequenceStatus};
+use crate::core::warnings::Warning;
+use crate::core::{Record, Storage, TransformResult};
+use crate::domain::services::stage_runner::run_stage;
+use crate::utils::budget::ExecutionBudget;
 use crate::utils::error::{EtlError, Result};
-use crate::utils::monitor::SystemMonitor;
-use std::collections::HashMap;
-use std::time::Instant;
+use crate::utils::heartbeat::{ProgressSink, ProgressTracker};
+use crate::utils::metrics::{per_second, StageMetrics, StageThroughput, ThroughputReport};
+use crate::utils::monitor::{MemoryPressure, SystemMonitor};
+use crate::utils::prometheus;
+use crate::utils::shutdown::ShutdownSignal;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 
 /// Pipeline 執行結果
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineResult {
     pub pipeline_name: String,
-    pub records: Vec<Record>,
+    /// 以 Arc 共用：上下文、結果清單與重試前的上下文快照都指向同一份記錄；
+    /// 寫入暫存檔的結果不含記錄，筆數以 `record_count` 取得
+    pub records: Arc<[Record]>,
     pub output_path: String,
     pub duration: std::time::Duration,
     pub metadata: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+    #[serde(default)]
+    pub outputs: HashMap<String, serde_json::Value>,
+    /// 被略過時的原因；略過的 Pipeline 沒有記錄與輸出
+    #[serde(default)]
+    pub skipped: Option<SkipReason>,
+}
+
+impl PipelineResult {
+    /// 略過的 Pipeline 在結果中的紀錄
+    pub fn skipped(pipeline_name: &str, reason: SkipReason) -> Self {
+        Self {
+            pipeline_name: pipeline_name.to_string(),
+            records: Arc::from([]),
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: Some(reason),
+        }
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        self.skipped.is_some()
+    }
+
+    /// 記錄數，含已寫入暫存檔而不在記憶體中的記錄
+    pub fn record_count(&self) -> usize {
+        self.metadata
+            .get("spilled_records")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(self.records.len(), |records| records as usize)
+    }
+
+    /// 各階段耗時、寫入位元組與 HTTP 呼叫次數；略過或未回報時為 None
+    pub fn stage_metrics(&self) -> Option<StageMetrics> {
+        serde_json::from_value(self.metadata.get("stage_metrics")?.clone()).ok()
+    }
+
+    /// 將輸出記錄轉為使用者定義的結構
+    pub fn records_as<T: serde::de::DeserializeOwned>(&self) -> Result<Vec<T>> {
+        crate::core::deserialize_records(&self.records)
+    }
+}
+
+/// Pipeline 被略過的原因
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum SkipReason {
+    /// conditions 不符合
+    ConditionNotMet,
+    /// 依賴的 Pipeline 在本次執行中沒有成功完成
+    DependencyFailed { dependency: String },
+    /// 擷取結果為空且設定了 skip_on_empty_input
+    EmptyInput,
+    /// 所有條件式請求都收到 304 且 on_not_modified = "skip"
+    NotModified,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConditionNotMet => write!(f, "condition not met"),
+            Self::DependencyFailed { dependency } => {
+                write!(f, "dependency '{}' did not complete", dependency)
+            }
+            Self::EmptyInput => write!(f, "empty input"),
+            Self::NotModified => write!(f, "source not modified"),
+        }
+    }
 }
 
 /// Pipeline 執行上下文，用於在 Pipeline 間傳遞數據
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineContext {
     pub previous_results: Vec<PipelineResult>,
-    pub shared_data: HashMap<String, serde_json::Value>,
+    /// 只能透過 add_shared_data / write_shared_data 寫入，才會經過共享存放區傳給其他複本與後續 Pipeline
+    shared_data: HashMap<String, serde_json::Value>,
     pub execution_id: String,
+    /// 執行開始時間，供 {{run_start}}、{{today}} 等內建佔位符使用；續跑時沿用原本的時間
+    #[serde(default = "chrono::Utc::now")]
+    pub run_started_at: chrono::DateTime<chrono::Utc>,
+    /// sequence.foreach 目前的項目，供端點、參數與檔名中的 {{tenant.id}} 等佔位符使用
+    #[serde(default)]
+    pub foreach_item: Option<ForeachItem>,
+    #[serde(skip)]
     pipeline_data: HashMap<String, Vec<Record>>,
+    #[serde(default)]
+    shared_data_owners: HashMap<String, String>, // 共享數據鍵 -> 寫入的 Pipeline
+    #[serde(skip)]
+    shared_store: SharedDataStore,
+    #[serde(default)]
+    spilled_records: HashMap<String, SpilledRecords>, // Pipeline 名稱 -> 已寫入暫存檔的記錄
+    #[serde(skip)]
+    spill: Option<ContextSpill>,
+    #[serde(skip)]
+    memory_pressure: Option<Arc<MemoryPressure>>,
 }
 
 impl PipelineContext {
@@ -29,7 +140,53 @@ impl PipelineContext {
             previous_results: Vec::new(),
             shared_data: HashMap::new(),
             execution_id,
+            run_started_at: chrono::Utc::now(),
+            foreach_item: None,
             pipeline_data: HashMap::new(),
+            shared_data_owners: HashMap::new(),
+            shared_store: SharedDataStore::default(),
+            spilled_records: HashMap::new(),
+            spill: None,
+            memory_pressure: None,
+        }
+    }
+
+    /// 設定記憶體上限，之後加入的大型結果改存暫存檔
+    pub fn configure_spill(&mut self, spill: ContextSpill) {
+        self.spill = Some(spill);
+    }
+
+    /// 設定記憶體背壓：超過 RSS 上限時暫停擷取，並將新加入的結果提早寫入暫存檔
+    pub fn configure_memory_pressure(&mut self, memory_pressure: Arc<MemoryPressure>) {
+        self.memory_pressure = Some(memory_pressure);
+    }
+
+    pub fn memory_pressure(&self) -> Option<&MemoryPressure> {
+        self.memory_pressure.as_deref()
+    }
+
+    /// 取得結果的記錄；已寫入暫存檔的結果會重新讀取
+    pub fn records_of<'a>(&self, result: &'a PipelineResult) -> Result<Cow<'a, [Record]>> {
+        match self.spilled_records.get(&result.pipeline_name) {
+            Some(spilled) => Ok(Cow::Owned(
+                spilled.read(self.spill.as_ref().and_then(|spill| spill.cipher()))?,
+            )),
+            None => Ok(Cow::Borrowed(&result.records)),
+        }
+    }
+
+    /// 結果的記錄數（不需讀回暫存檔）
+    pub fn record_count(&self, result: &PipelineResult) -> usize {
+        self.spilled_records
+            .get(&result.pipeline_name)
+            .map(|spilled| spilled.records)
+            .unwrap_or(result.records.len())
+    }
+
+    /// 序列成功完成後移除暫存檔
+    pub fn cleanup_spill(&mut self) {
+        if let Some(spill) = &self.spill {
+            spill.cleanup();
         }
     }
 
@@ -46,11 +203,12 @@ impl PipelineContext {
     }
 
     /// 獲取所有之前處理的記錄
-    pub fn get_all_previous_records(&self) -> Vec<Record> {
-        self.previous_results
-            .iter()
-            .flat_map(|result| result.records.clone())
-            .collect()
+    pub fn get_all_previous_records(&self) -> Result<Vec<Record>> {
+        let mut records = Vec::new();
+        for result in &self.previous_results {
+            records.extend_from_slice(&self.records_of(result)?);
+        }
+        Ok(records)
     }
 
     /// 添加 Pipeline 數據
@@ -58,28 +216,92 @@ impl PipelineContext {
         self.pipeline_data.insert(pipeline_name, records);
     }
 
-    /// 獲取 Pipeline 數據
-    pub fn get_pipeline_data(&self, pipeline_name: &str) -> Option<&Vec<Record>> {
-        self.pipeline_data.get(pipeline_name)
+    /// 獲取 Pipeline 數據（未以 add_pipeline_data 加入時取該 Pipeline 的結果）
+    pub fn get_pipeline_data(&self, pipeline_name: &str) -> Result<Option<Cow<'_, [Record]>>> {
+        if let Some(records) = self.pipeline_data.get(pipeline_name) {
+            return Ok(Some(Cow::Borrowed(records)));
+        }
+        self.get_result_by_name(pipeline_name)
+            .map(|result| self.records_of(result))
+            .transpose()
     }
 
-    /// 添加共享數據
+    /// 添加共享數據（不具名寫入，不套用寫入策略）
     pub fn add_shared_data(&mut self, key: String, value: serde_json::Value) {
+        self.shared_store.write(None, &key, value.clone());
+        self.shared_data_owners.remove(&key);
         self.shared_data.insert(key, value);
     }
 
+    /// 設定逐項執行的項目，並將項目與其頂層欄位寫入共享數據
+    pub fn set_foreach_item(&mut self, item: ForeachItem) {
+        for (key, value) in item.shared_data() {
+            self.add_shared_data(key, value);
+        }
+        self.foreach_item = Some(item);
+    }
+
+    /// 以 Pipeline 名義寫入共享數據，依設定的策略處理與其他 Pipeline 的衝突
+    pub fn write_shared_data(
+        &mut self,
+        producer: &str,
+        key: String,
+        value: serde_json::Value,
+    ) -> SharedDataWrite {
+        let outcome = self.shared_store.write(Some(producer), &key, value.clone());
+        if !matches!(outcome, SharedDataWrite::Rejected { .. }) {
+            self.shared_data_owners
+                .insert(key.clone(), producer.to_string());
+            self.shared_data.insert(key, value);
+        }
+        outcome
+    }
+
+    /// 設定共享數據寫入策略；producers 為鍵到生產者 Pipeline 的宣告
+    pub fn configure_shared_data(
+        &self,
+        policy: SharedDataPolicy,
+        producers: HashMap<String, String>,
+    ) {
+        self.shared_store.configure(
+            policy,
+            producers,
+            &self.shared_data,
+            &self.shared_data_owners,
+        );
+    }
+
+    /// 以共享存放區的內容更新本上下文（取得其他複本的寫入）
+    pub fn sync_shared_data(&mut self) {
+        self.shared_data = self.shared_store.values();
+        self.shared_data_owners = self.shared_store.owners();
+    }
+
+    /// 將共享存放區還原為本上下文的內容，捨棄之後（例如失敗的嘗試）寫入的共享數據
+    ///
+    /// 存放區由所有複本共用，只還原上下文本身不會撤銷已寫入存放區的值。
+    pub fn rollback_shared_data(&self) {
+        self.shared_store
+            .reset(&self.shared_data, &self.shared_data_owners);
+    }
+
     /// 獲取共享數據
     pub fn get_shared_data(&self, key: &str) -> Option<&serde_json::Value> {
         self.shared_data.get(key)
     }
 
+    /// 所有共享數據
+    pub fn all_shared_data(&self) -> &HashMap<String, serde_json::Value> {
+        &self.shared_data
+    }
+
     /// 與前一個 Pipeline 的數據合併
     pub fn merge_with_previous(
         &self,
         pipeline_name: &str,
         api_records: Vec<Record>,
-    ) -> Vec<Record> {
-        if let Some(previous_records) = self.get_pipeline_data(pipeline_name) {
+    ) -> Result<Vec<Record>> {
+        if let Some(previous_records) = self.get_pipeline_data(pipeline_name)? {
             let mut merged = Vec::new();
 
             for api_record in api_records {
@@ -87,7 +309,7 @@ impl PipelineContext {
 
                 // 嘗試根據 ID 合併數據
                 if let Some(api_id) = api_record.data.get("id") {
-                    for prev_record in previous_records {
+                    for prev_record in previous_records.iter() {
                         if prev_record.data.get("id") == Some(api_id) {
                             // 合併數據，API 數據優先
                             for (key, value) in &prev_record.data {
@@ -101,16 +323,52 @@ impl PipelineContext {
                 merged.push(Record { data: merged_data });
             }
 
-            merged
+            Ok(merged)
         } else {
-            api_records
+            Ok(api_records)
         }
     }
 
-    /// 添加結果到上下文
-    pub fn add_result(&mut self, result: PipelineResult) {
-        // 同時添加到 pipeline_data 供後續使用
-        self.add_pipeline_data(result.pipeline_name.clone(), result.records.clone());
+    /// 添加結果到上下文；記錄數超過記憶體上限，或行程記憶體超過 RSS 上限時寫入暫存檔，
+    /// 上下文只保留檔案位置
+    pub fn add_result(&mut self, mut result: PipelineResult) {
+        self.pipeline_data.remove(&result.pipeline_name);
+        self.spilled_records.remove(&result.pipeline_name);
+        let under_pressure = !result.records.is_empty()
+            && self
+                .memory_pressure
+                .as_ref()
+                .is_some_and(|pressure| pressure.is_over_limit());
+        if let Some(spill) = self
+            .spill
+            .as_ref()
+            .filter(|spill| under_pressure || spill.should_spill(result.records.len()))
+        {
+            // 暫存檔只是節省記憶體，寫入失敗時保留在記憶體中
+            match spill.write(&result.pipeline_name, &result.records) {
+                Ok(spilled) => {
+                    tracing::info!(
+                        "💽 Spilled {} records of {} to {}",
+                        spilled.records,
+                        result.pipeline_name,
+                        spilled.path.display()
+                    );
+                    result.records = Arc::from([]);
+                    result
+                        .metadata
+                        .insert("spilled_records".to_string(), spilled.records.into());
+                    self.spilled_records
+                        .insert(result.pipeline_name.clone(), spilled);
+                }
+                Err(e) => tracing::warn!(
+                    "⚠️ Failed to spill records of {}, keeping them in memory: {}",
+                    result.pipeline_name,
+                    e
+                ),
+            }
+        }
+        self.previous_results
+            .retain(|r| r.pipeline_name != result.pipeline_name);
         self.previous_results.push(result);
     }
 }
@@ -119,6 +377,7 @@ impl PipelineContext {
 #[async_trait::async_trait]
 pub trait ContextualPipeline: Send + Sync {
     async fn extract_with_context(&self, context: &PipelineContext) -> Result<Vec<Record>>;
+    /// 共享數據以 `write_shared_data` 導出；Pipeline 成功後傳給後續 Pipeline，重試時捨棄失敗嘗試的導出
     async fn transform_with_context(
         &self,
         data: Vec<Record>,
@@ -126,7 +385,7 @@ pub trait ContextualPipeline: Send + Sync {
     ) -> Result<TransformResult>;
     async fn load_with_context(
         &self,
-        result: TransformResult,
+        result: &TransformResult,
         context: &PipelineContext,
     ) -> Result<String>;
 
@@ -137,14 +396,72 @@ pub trait ContextualPipeline: Send + Sync {
     fn should_execute(&self, _context: &PipelineContext) -> bool {
         true
     }
+
+    /// 取出本次執行期間收集的額外 metadata（例如 bytes_written）
+    fn take_execution_metadata(&self) -> HashMap<String, serde_json::Value> {
+        HashMap::new()
+    }
+
+    /// 取出本次執行期間收集的結構化警告
+    fn take_warnings(&self) -> Vec<Warning> {
+        Vec::new()
+    }
+
+    /// 是否為 view：只重新輸出先前結果，續跑時仍會執行
+    fn is_view(&self) -> bool {
+        false
+    }
+
+    /// 輸出變數定義（名稱 -> 表達式），完成後計算並放入結果
+    fn output_definitions(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// 成功後接著執行的 Pipeline
+    fn on_success(&self) -> Option<&str> {
+        None
+    }
+
+    /// 失敗後執行的 Pipeline（例如清理），序列仍以失敗結束
+    fn on_failure(&self) -> Option<&str> {
+        None
+    }
+
+    /// 依賴的 Pipeline；其中任一在本次執行被略過時，此 Pipeline 也會被略過
+    fn dependencies(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 擷取結果為空時是否略過 transform 與 load
+    fn skip_on_empty_input(&self) -> bool {
+        false
+    }
+
+    /// 來源未變更（條件式請求全部收到 304）且設定為略過 Pipeline
+    fn source_not_modified(&self) -> bool {
+        false
+    }
 }
 
 /// Pipeline 序列，負責順序執行多個帶上下文的 Pipeline
 pub struct PipelineSequence {
     pipelines: Vec<Box<dyn ContextualPipeline>>, // 使用 trait object 支持多態
-    monitor: Option<SystemMonitor>,
-    monitor_enabled: bool,
+    monitor: Option<Arc<SystemMonitor>>,
+    budget: Option<ExecutionBudget>,
     execution_id: String,
+    state_store: Option<SequenceStateStore>,
+    resume_state: Option<SequenceState>,
+    progress: Option<Arc<ProgressTracker>>,
+    shared_data_policy: SharedDataPolicy,
+    shared_data_producers: HashMap<String, String>,
+    intermediate_aggregate: Option<(Arc<dyn AggregateSink>, String, IntermediateFormat)>,
+    progress_file: Option<PathBuf>,
+    fallback_pipeline: Option<String>,
+    pipeline_retry: Option<(u32, Duration)>,
+    context_spill: Option<ContextSpill>,
+    memory_pressure: Option<Arc<MemoryPressure>>,
+    shutdown: Option<ShutdownSignal>,
+    foreach_item: Option<ForeachItem>,
 }
 
 impl PipelineSequence {
@@ -152,17 +469,167 @@ impl PipelineSequence {
         Self {
             pipelines: Vec::new(),
             monitor: None,
-            monitor_enabled: false,
+            budget: None,
             execution_id,
+            state_store: None,
+            resume_state: None,
+            progress: None,
+            shared_data_policy: SharedDataPolicy::default(),
+            shared_data_producers: HashMap::new(),
+            intermediate_aggregate: None,
+            progress_file: None,
+            fallback_pipeline: None,
+            pipeline_retry: None,
+            context_spill: None,
+            memory_pressure: None,
+            shutdown: None,
+            foreach_item: None,
+        }
+    }
+
+    /// 逐項執行（sequence.foreach）時的目前項目，執行前寫入上下文的共享數據
+    pub fn with_foreach_item(mut self, item: ForeachItem) -> Self {
+        self.foreach_item = Some(item);
+        self
+    }
+
+    /// 收到停止訊號後不再開始新的 Pipeline，保存狀態後以 Interrupted 結束
+    pub fn with_shutdown(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    fn is_interrupted(&self) -> bool {
+        self.shutdown
+            .as_ref()
+            .is_some_and(ShutdownSignal::is_requested)
+    }
+
+    /// 已收到停止訊號時保存狀態並返回 Interrupted，續跑時從未完成的 Pipeline 開始
+    fn stop_if_interrupted(&self, run: &mut SequenceRun) -> Result<()> {
+        if !self.is_interrupted() {
+            return Ok(());
+        }
+        run.state.status = SequenceStatus::Interrupted;
+        self.persist_state(&mut run.state, &run.context);
+        run.progress.finish(SequenceStatus::Interrupted);
+        tracing::warn!(
+            "🛑 Execution {} interrupted after {} completed pipelines",
+            run.state.execution_id,
+            run.state.completed_pipelines.len()
+        );
+        Err(EtlError::Interrupted {
+            details: format!(
+                "stopped after {} completed pipelines, resume with --resume {}",
+                run.state.completed_pipelines.len(),
+                run.state.execution_id
+            ),
+        })
+    }
+
+    /// 上下文中超過記憶體上限的結果寫入暫存檔，序列成功完成後移除
+    pub fn with_context_spill(mut self, spill: ContextSpill) -> Self {
+        self.context_spill = Some(spill);
+        self
+    }
+
+    /// 行程記憶體超過上限時暫停擷取，並將完成的結果提早寫入暫存檔（需同時設定 context spill）
+    pub fn with_memory_pressure(mut self, memory_pressure: MemoryPressure) -> Self {
+        self.memory_pressure = Some(Arc::new(memory_pressure));
+        self
+    }
+
+    /// Pipeline 失敗時整個重新執行，最多重試 `retries` 次，每次間隔 `delay`
+    pub fn with_pipeline_retry(mut self, retries: u32, delay: Duration) -> Self {
+        self.pipeline_retry = Some((retries, delay));
+        self
+    }
+
+    /// 未設定 on_failure 的 Pipeline 失敗時執行的 Pipeline（error_handling.fallback_pipeline）
+    pub fn with_fallback_pipeline(mut self, name: impl Into<String>) -> Self {
+        self.fallback_pipeline = Some(name.into());
+        self
+    }
+
+    /// 執行期間持續更新機器可讀的進度檔（各 Pipeline 的階段、筆數與時間）
+    pub fn with_progress_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.progress_file = Some(path.into());
+        self
+    }
+
+    /// 將本次執行的各 Pipeline 中繼結果彙整，經由 `storage` 寫入單一檔案（續跑時只含重新執行的 Pipeline）
+    pub fn with_intermediate_aggregate(
+        mut self,
+        storage: impl Storage + 'static,
+        path: impl Into<String>,
+        format: IntermediateFormat,
+    ) -> Self {
+        self.intermediate_aggregate = Some((Arc::new(storage), path.into(), format));
+        self
+    }
+
+    /// 設定多個 Pipeline 寫入同一共享數據鍵時的策略
+    pub fn with_shared_data_policy(
+        mut self,
+        policy: SharedDataPolicy,
+        producers: HashMap<String, String>,
+    ) -> Self {
+        self.shared_data_policy = policy;
+        self.shared_data_producers = producers;
+        self
+    }
+
+    /// 回報執行進度，供心跳與存活檔使用
+    pub fn with_progress(mut self, progress: Arc<ProgressTracker>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// 每個 Pipeline 完成後保存上下文，供失敗後續跑
+    pub fn with_state_store(mut self, state_store: SequenceStateStore) -> Self {
+        self.state_store = Some(state_store);
+        self
+    }
+
+    /// 從先前保存的狀態繼續執行，已完成的 Pipeline 會被跳過
+    pub fn resume_from(mut self, state: SequenceState) -> Self {
+        self.execution_id = state.execution_id.clone();
+        self.resume_state = Some(state);
+        self
+    }
+
+    fn persist_state(&self, state: &mut SequenceState, context: &PipelineContext) {
+        if let Some(store) = &self.state_store {
+            state.context = context.clone();
+            state.outputs = collect_outputs(&context.previous_results);
+            state.updated_at = chrono::Utc::now().to_rfc3339();
+            if let Err(e) = store.save(state) {
+                tracing::warn!("⚠️ Failed to save sequence state: {}", e);
+            }
         }
     }
 
     /// 啟用或禁用系統監控
-    pub fn with_monitoring(mut self, enabled: bool) -> Self {
-        self.monitor_enabled = enabled;
+    #[deprecated(
+        note = "use SequenceEngine::new_with_monitoring, which also logs start and final stats"
+    )]
+    pub fn with_monitoring(self, enabled: bool) -> Self {
         if enabled {
-            self.monitor = Some(SystemMonitor::new(enabled));
+            self.with_monitor(Arc::new(SystemMonitor::new(enabled)))
+        } else {
+            self
         }
+    }
+
+    /// 共用執行引擎的監控器，每個 Pipeline 完成後記錄資源使用
+    pub fn with_monitor(mut self, monitor: Arc<SystemMonitor>) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// 共用執行引擎的時間預算，每個 Pipeline 完成後記錄檢查點
+    pub fn with_budget(mut self, budget: ExecutionBudget) -> Self {
+        self.budget = Some(budget);
         self
     }
 
@@ -173,111 +640,480 @@ impl PipelineSequence {
 
     /// 執行所有 pipeline
     pub async fn execute_all(&mut self) -> Result<Vec<PipelineResult>> {
-        let mut results = Vec::new();
-        let mut context = PipelineContext::new(self.execution_id.clone());
-
-        if self.monitor_enabled {
-            if let Some(monitor) = &self.monitor {
-                monitor.log_stats("Pipeline execution started.");
+        let state = match self.resume_state.take() {
+            Some(mut state) => {
+                tracing::info!(
+                    "🔁 Resuming execution {} ({} pipelines already completed)",
+                    state.execution_id,
+                    state.completed_pipelines.len()
+                );
+                state.status = SequenceStatus::Running;
+                state.failed_pipeline = None;
+                state
             }
+            None => SequenceState::new(PipelineContext::new(self.execution_id.clone())),
+        };
+        let mut context = state.context.clone();
+        if let Some(spill) = &self.context_spill {
+            context.configure_spill(spill.clone());
+        }
+        if let Some(memory_pressure) = &self.memory_pressure {
+            context.configure_memory_pressure(Arc::clone(memory_pressure));
+        }
+        context.configure_shared_data(self.shared_data_policy, self.shared_data_producers.clone());
+        if let Some(item) = &self.foreach_item {
+            context.set_foreach_item(item.clone());
         }
+        // 進度檔是進度追蹤的 sink，與心跳讀取同一組事件
+        let names: Vec<&str> = self.pipelines.iter().map(|p| p.get_name()).collect();
+        let progress = self
+            .progress
+            .clone()
+            .unwrap_or_else(|| Arc::new(ProgressTracker::new(names.len())));
+        progress.set_sink(self.progress_file.as_ref().map(|path| {
+            Arc::new(ProgressFile::new(path, &state.execution_id, &names)) as Arc<dyn ProgressSink>
+        }));
+        let mut run = SequenceRun {
+            results: context.previous_results.clone(),
+            context,
+            state,
+            intermediates: IntermediateAggregate::default(),
+            progress,
+            skipped: HashSet::new(),
+        };
 
+        // 分支目標只在被觸發時執行，不參與一般的執行順序
+        let branch_targets = self.branch_targets();
         for pipeline in &self.pipelines {
-            let start_time = Instant::now();
+            self.stop_if_interrupted(&mut run)?;
+            if branch_targets.contains(pipeline.get_name()) {
+                continue;
+            }
 
-            // 根據上下文決定是否執行
-            if !pipeline.should_execute(&context) {
+            if run.state.is_completed(pipeline.get_name()) && !pipeline.is_view() {
                 tracing::info!(
-                    "⏭️ Skipping pipeline: {} (condition not met)",
+                    "⏩ Skipping pipeline: {} (completed in previous run)",
                     pipeline.get_name()
                 );
+                self.report_pipeline_skipped(&run, pipeline.get_name());
                 continue;
             }
 
-            // 執行單個 pipeline
-            match self.execute_pipeline(pipeline.as_ref(), &mut context).await {
-                Ok(execution_result) => {
-                    let end_time = Instant::now();
-                    let duration = end_time.duration_since(start_time);
-
-                    let result = PipelineResult {
-                        pipeline_name: pipeline.get_name().to_string(),
-                        records: execution_result.processed_records.clone(),
-                        output_path: execution_result.output_path.clone(),
-                        duration,
-                        metadata: execution_result.metadata.clone(),
-                    };
+            if let Some(dependency) = pipeline
+                .dependencies()
+                .into_iter()
+                .find(|dependency| run.skipped.contains(dependency))
+            {
+                self.record_skip(
+                    &mut run,
+                    pipeline.get_name(),
+                    SkipReason::DependencyFailed { dependency },
+                );
+                continue;
+            }
 
-                    tracing::info!(
-                        "✅ Pipeline executed: {} (records: {}, duration: {:?})",
-                        result.pipeline_name,
-                        result.records.len(),
-                        result.duration
-                    );
+            // 根據上下文決定是否執行
+            if !pipeline.should_execute(&run.context) {
+                self.record_skip(&mut run, pipeline.get_name(), SkipReason::ConditionNotMet);
+                continue;
+            }
 
-                    // 將結果添加到上下文
-                    context.add_result(result.clone());
-                    results.push(result);
-                }
+            self.execute_with_branches(pipeline.as_ref(), &mut run)
+                .await?;
+        }
+
+        self.stop_if_interrupted(&mut run)?;
+        run.state.status = SequenceStatus::Completed;
+        self.persist_state(&mut run.state, &run.context);
+        run.context.cleanup_spill();
+        run.progress.finish(SequenceStatus::Completed);
+
+        if let Some((storage, path, format)) = &self.intermediate_aggregate {
+            run.intermediates
+                .write(storage.as_ref(), path, *format)
+                .await?;
+            tracing::info!(
+                "🧾 Aggregated {} intermediate records into {}",
+                run.intermediates.len(),
+                path
+            );
+        }
+
+        Ok(run.results)
+    }
+
+    /// 被 on_success、on_failure 或 fallback_pipeline 指定的 Pipeline
+    fn branch_targets(&self) -> HashSet<String> {
+        self.pipelines
+            .iter()
+            .flat_map(|pipeline| [pipeline.on_success(), pipeline.on_failure()])
+            .chain([self.fallback_pipeline.as_deref()])
+            .flatten()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn find_pipeline(&self, name: &str) -> Option<&dyn ContextualPipeline> {
+        let pipeline = self
+            .pipelines
+            .iter()
+            .find(|pipeline| pipeline.get_name() == name)
+            .map(|pipeline| pipeline.as_ref());
+        if pipeline.is_none() {
+            tracing::warn!(
+                "⚠️ Branch target '{}' is not part of this run, skipping",
+                name
+            );
+        }
+        pipeline
+    }
+
+    /// 執行 Pipeline 並依結果跳到 on_success 或 on_failure 指定的 Pipeline
+    ///
+    /// 成功時沿 on_success 鏈繼續執行；失敗時先執行 on_failure（未設定時為
+    /// fallback_pipeline），序列仍以原本的錯誤結束。
+    async fn execute_with_branches(
+        &self,
+        pipeline: &dyn ContextualPipeline,
+        run: &mut SequenceRun,
+    ) -> Result<()> {
+        let mut current = pipeline;
+        let mut visited = HashSet::new();
+        loop {
+            visited.insert(current.get_name().to_string());
+            let executed = match self.run_pipeline(current, run).await {
+                Ok(executed) => executed,
                 Err(e) => {
-                    tracing::error!("❌ Pipeline execution failed: {}", e);
-                    return Err(EtlError::TransformationError {
-                        stage: pipeline.get_name().to_string(),
-                        details: format!("Pipeline execution failed: {}", e),
-                    });
+                    run.state.status = SequenceStatus::Failed;
+                    run.state.failed_pipeline = Some(current.get_name().to_string());
+                    self.persist_state(&mut run.state, &run.context);
+                    run.progress
+                        .fail_pipeline(current.get_name(), &e.to_string());
+
+                    let handler = current
+                        .on_failure()
+                        .or(self.fallback_pipeline.as_deref())
+                        .filter(|name| *name != current.get_name())
+                        .and_then(|name| self.find_pipeline(name));
+                    if let Some(handler) = handler {
+                        tracing::info!(
+                            "↪️ {} failed, running on_failure pipeline: {}",
+                            current.get_name(),
+                            handler.get_name()
+                        );
+                        if let Err(handler_error) = self.run_pipeline(handler, run).await {
+                            tracing::error!(
+                                "❌ on_failure pipeline {} failed: {}",
+                                handler.get_name(),
+                                handler_error
+                            );
+                            run.progress
+                                .fail_pipeline(handler.get_name(), &handler_error.to_string());
+                        }
+                    }
+
+                    run.progress.finish(SequenceStatus::Failed);
+                    return Err(e.in_pipeline(current.get_name()));
                 }
+            };
+
+            // 略過（例如輸入為空）不算成功，不觸發 on_success；收到停止訊號後也不再往下執行
+            if !executed || self.is_interrupted() {
+                return Ok(());
+            }
+            let Some(next) = current.on_success() else {
+                return Ok(());
+            };
+            if visited.contains(next) {
+                tracing::warn!(
+                    "⚠️ on_success of {} points back to {}, stopping branch",
+                    current.get_name(),
+                    next
+                );
+                return Ok(());
             }
+            let Some(next) = self.find_pipeline(next) else {
+                return Ok(());
+            };
+            tracing::info!(
+                "↪️ {} succeeded, running on_success pipeline: {}",
+                current.get_name(),
+                next.get_name()
+            );
+            current = next;
         }
+    }
+
+    /// 執行單一 Pipeline 並將結果寫回上下文、狀態檔與進度；返回 false 表示被略過
+    async fn run_pipeline(
+        &self,
+        pipeline: &dyn ContextualPipeline,
+        run: &mut SequenceRun,
+    ) -> Result<bool> {
+        let start_time = Instant::now();
+        let (retries, retry_delay) = self.pipeline_retry.unwrap_or((0, Duration::ZERO));
+        let mut attempt = 0;
+        let mut failed_attempts = FailedAttempts::default();
+        let mut execution_result = loop {
+            attempt += 1;
+            run.progress.begin_pipeline(pipeline.get_name());
 
-        if self.monitor_enabled {
-            if let Some(monitor) = &self.monitor {
-                monitor.log_stats("Pipeline execution completed.");
-                {
-                    if let Some(metrics) = monitor.get_stats() {
-                        tracing::info!("📊 System metrics during execution: {:?}", metrics)
+            // 失敗的嘗試可能已改動上下文與共享數據，重試前還原
+            run.context.sync_shared_data();
+            let context_before = run.context.clone();
+            match self
+                .execute_pipeline(pipeline, &mut run.context, &run.progress, &failed_attempts)
+                .await
+            {
+                Ok(Some(execution_result)) => break execution_result,
+                Ok(None) => {
+                    let reason = if pipeline.source_not_modified() {
+                        SkipReason::NotModified
+                    } else {
+                        SkipReason::EmptyInput
                     };
+                    self.record_skip(run, pipeline.get_name(), reason);
+                    return Ok(false);
+                }
+                Err(e) if attempt <= retries && !self.is_interrupted() => {
+                    tracing::warn!(
+                        "🔄 Pipeline {} failed (attempt {}/{}): {} - retrying in {:?}",
+                        pipeline.get_name(),
+                        attempt,
+                        retries + 1,
+                        e,
+                        retry_delay
+                    );
+                    prometheus::global().inc_counter(
+                        prometheus::HTTP_RETRIES,
+                        &[("pipeline", pipeline.get_name())],
+                        1.0,
+                    );
+                    run.context = context_before;
+                    run.context.rollback_shared_data();
+                    failed_attempts.record(&pipeline.take_execution_metadata());
+                    pipeline.take_warnings();
+                    tokio::time::sleep(retry_delay).await;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "❌ Pipeline execution failed after {} attempt(s): {}",
+                        attempt,
+                        e
+                    );
+                    return Err(e);
                 }
             }
+        };
+        execution_result
+            .metadata
+            .insert("attempts".to_string(), serde_json::json!(attempt));
+
+        let duration = start_time.elapsed();
+        // 因停止訊號提前結束的 Pipeline 只輸出部分結果，不放入上下文，續跑時需重新執行
+        let partial = execution_result.metadata.contains_key("partial");
+        run.intermediates
+            .extend(pipeline.get_name(), &execution_result.intermediate_data);
+
+        let mut result = PipelineResult {
+            pipeline_name: pipeline.get_name().to_string(),
+            records: execution_result.processed_records.into(),
+            output_path: execution_result.output_path,
+            duration,
+            metadata: execution_result.metadata,
+            warnings: execution_result.warnings,
+            outputs: execution_result.outputs,
+            skipped: None,
+        };
+
+        tracing::info!(
+            "✅ Pipeline executed: {} (records: {}, duration: {:?}, warnings: {})",
+            result.pipeline_name,
+            result.records.len(),
+            result.duration,
+            result.warnings.len()
+        );
+
+        // 將結果添加到上下文（續跑時重新執行的 view 會取代舊結果）；複製結果只增加記錄的參照計數
+        run.context.sync_shared_data();
+        if !partial {
+            run.context.add_result(result.clone());
+            // 寫入暫存檔的結果在上下文中只剩筆數，結果清單也改用這份，才能釋放記錄
+            if let Some(stored) = run.context.get_result_by_name(pipeline.get_name()) {
+                result = stored.clone();
+            }
+        }
+        run.results
+            .retain(|r| r.pipeline_name != result.pipeline_name);
+        run.results.push(result);
+
+        if partial {
+            tracing::warn!(
+                "🛑 Pipeline {} was interrupted, its output is partial",
+                pipeline.get_name()
+            );
+        } else if !run.state.is_completed(pipeline.get_name()) {
+            run.state
+                .completed_pipelines
+                .push(pipeline.get_name().to_string());
         }
+        self.persist_state(&mut run.state, &run.context);
+        if partial {
+            run.progress
+                .fail_pipeline(pipeline.get_name(), "interrupted, output is partial");
+        } else {
+            run.progress
+                .finish_pipeline(pipeline.get_name(), PipelineProgressStatus::Completed);
+        }
+        let label = format!("After {}", pipeline.get_name());
+        if let Some(monitor) = &self.monitor {
+            monitor.log_stats(&label);
+        }
+        if let Some(budget) = &self.budget {
+            budget.checkpoint(&label);
+        }
+        Ok(true)
+    }
+
+    /// 將略過的 Pipeline 與原因記入結果
+    fn record_skip(&self, run: &mut SequenceRun, name: &str, reason: SkipReason) {
+        tracing::info!("⏭️ Skipping pipeline: {} ({})", name, reason);
+        self.report_pipeline_skipped(run, name);
+        run.skipped.insert(name.to_string());
+        run.results.retain(|r| r.pipeline_name != name);
+        run.results.push(PipelineResult::skipped(name, reason));
+    }
 
-        Ok(results)
+    fn report_pipeline_skipped(&self, run: &SequenceRun, name: &str) {
+        run.progress
+            .finish_pipeline(name, PipelineProgressStatus::Skipped);
     }
 
     async fn execute_pipeline(
         &self,
         pipeline: &dyn ContextualPipeline,
         context: &mut PipelineContext,
-    ) -> Result<PipelineExecutionResult> {
+        progress: &ProgressTracker,
+        failed_attempts: &FailedAttempts,
+    ) -> Result<Option<PipelineExecutionResult>> {
+        // 每個階段一個 span，JSON 日誌以此帶出 execution_id、pipeline、stage 與筆數、耗時
+        let execution_id = context.execution_id.clone();
+        let stage_span = |stage: PipelineStage| {
+            tracing::info_span!(
+                "stage",
+                execution_id = %execution_id,
+                pipeline = pipeline.get_name(),
+                stage = stage.as_str(),
+                records = tracing::field::Empty,
+                duration_ms = tracing::field::Empty,
+            )
+        };
+        let stage_finished =
+            |stage: PipelineStage, span: &tracing::Span, throughput: &StageThroughput| {
+                span.record("records", throughput.records);
+                span.record("duration_ms", throughput.duration_ms);
+                progress.stage_finished(pipeline.get_name(), stage, throughput.records);
+            };
+        let monitor = self.monitor.as_deref();
+
         // Extract
-        let records = pipeline.extract_with_context(context).await?;
+        let span = stage_span(PipelineStage::Extract);
+        let (records, extract) = run_stage(
+            pipeline.get_name(),
+            PipelineStage::Extract,
+            monitor,
+            pipeline
+                .extract_with_context(context)
+                .instrument(span.clone()),
+            Vec::len,
+        )
+        .await?;
+        stage_finished(PipelineStage::Extract, &span, &extract);
         tracing::debug!("📥 Extracted {} records", records.len());
+        if records.is_empty() && (pipeline.skip_on_empty_input() || pipeline.source_not_modified())
+        {
+            return Ok(None);
+        }
 
         // Transform
-        let transform_result = pipeline.transform_with_context(records, context).await?;
+        let span = stage_span(PipelineStage::Transform);
+        let (transform_result, transform) = run_stage(
+            pipeline.get_name(),
+            PipelineStage::Transform,
+            monitor,
+            pipeline
+                .transform_with_context(records, context)
+                .instrument(span.clone()),
+            |result: &TransformResult| result.processed_records.len(),
+        )
+        .await?;
+        stage_finished(PipelineStage::Transform, &span, &transform);
         tracing::debug!(
             "🔄 Transformed {} records",
             transform_result.processed_records.len()
         );
 
         // Load
-        let output_path = pipeline
-            .load_with_context(transform_result.clone(), context)
-            .await?;
+        let span = stage_span(PipelineStage::Load);
+        let (output_path, load) = run_stage(
+            pipeline.get_name(),
+            PipelineStage::Load,
+            monitor,
+            pipeline
+                .load_with_context(&transform_result, context)
+                .instrument(span.clone()),
+            |_| transform_result.processed_records.len(),
+        )
+        .await?;
+        stage_finished(PipelineStage::Load, &span, &load);
         tracing::debug!("💾 Loaded data to: {}", output_path);
 
-        Ok(PipelineExecutionResult {
+        prometheus::global().record_stages(
+            pipeline.get_name(),
+            extract.records,
+            transform.records,
+            load.records,
+        );
+
+        let mut metadata = pipeline.take_execution_metadata();
+        failed_attempts.merge_into(&mut metadata);
+        let bytes_written = metadata
+            .get("bytes_written")
+            .and_then(|value| value.as_u64());
+        let throughput = ThroughputReport::new(extract, transform, load, bytes_written);
+        throughput.log(pipeline.get_name());
+        metadata.insert("throughput".to_string(), throughput.to_value());
+        let stage_metrics = StageMetrics::new(&throughput, &metadata);
+        metadata.insert("stage_metrics".to_string(), stage_metrics.to_value());
+
+        let outputs = evaluate_outputs(
+            &pipeline.output_definitions(),
+            &transform_result.processed_records,
+        )?;
+        for (name, value) in &outputs {
+            tracing::info!("📤 {}: Output {} = {}", pipeline.get_name(), name, value);
+        }
+
+        Ok(Some(PipelineExecutionResult {
             processed_records: transform_result.processed_records,
+            intermediate_data: transform_result.intermediate_data,
             output_path,
-            metadata: HashMap::new(),
-        })
+            metadata,
+            warnings: pipeline.take_warnings(),
+            outputs,
+        }))
     }
 
-    /// 獲取執行摘要
-    pub fn get_execution_summary(results: &[PipelineResult]) -> HashMap<String, serde_json::Value> {
+    /// 獲取執行摘要（略過的 Pipeline 另列於 skipped，不計入執行數）
+    pub fn get_execution_summary(
+        all_results: &[PipelineResult],
+    ) -> HashMap<String, serde_json::Value> {
         let mut summary = HashMap::new();
+        let (skipped, results): (Vec<&PipelineResult>, Vec<&PipelineResult>) =
+            all_results.iter().partition(|r| r.is_skipped());
 
         let total_pipelines = results.len();
-        let total_records: usize = results.iter().map(|r| r.records.len()).sum();
+        let total_records: usize = results.iter().map(|r| r.record_count()).sum();
         let total_duration: std::time::Duration = results.iter().map(|r| r.duration).sum();
 
         summary.insert(
@@ -302,13 +1138,123 @@ impl PipelineSequence {
             serde_json::Value::Array(pipeline_names),
         );
 
+        // 整體吞吐量
+        let total_bytes_written: u64 = results
+            .iter()
+            .filter_map(|r| r.metadata.get("bytes_written").and_then(|v| v.as_u64()))
+            .sum();
+        summary.insert(
+            "throughput".to_string(),
+            serde_json::json!({
+                "records_per_sec": per_second(total_records as f64, total_duration),
+                "bytes_written": total_bytes_written,
+                "mb_per_sec": per_second(total_bytes_written as f64 / (1024.0 * 1024.0), total_duration),
+            }),
+        );
+
+        // 各 Pipeline 的警告
+        let warnings: serde_json::Map<String, serde_json::Value> = results
+            .iter()
+            .filter(|r| !r.warnings.is_empty())
+            .map(|r| {
+                (
+                    r.pipeline_name.clone(),
+                    serde_json::to_value(&r.warnings).unwrap_or_default(),
+                )
+            })
+            .collect();
+        let total_warnings: usize = results
+            .iter()
+            .flat_map(|r| r.warnings.iter().map(|w| w.count))
+            .sum();
+        summary.insert(
+            "total_warnings".to_string(),
+            serde_json::Value::Number(total_warnings.into()),
+        );
+        summary.insert("warnings".to_string(), serde_json::Value::Object(warnings));
+        summary.insert("outputs".to_string(), collect_outputs(all_results));
+
+        summary.insert(
+            "skipped_pipelines".to_string(),
+            serde_json::Value::Number(skipped.len().into()),
+        );
+        summary.insert(
+            "skipped".to_string(),
+            serde_json::Value::Object(
+                skipped
+                    .iter()
+                    .filter_map(|r| {
+                        let reason = r.skipped.as_ref()?;
+                        Some((
+                            r.pipeline_name.clone(),
+                            serde_json::json!(reason.to_string()),
+                        ))
+                    })
+                    .collect(),
+            ),
+        );
+
         summary
     }
 }
 
+/// 依 Pipeline 名稱整理輸出變數：{ pipeline: { name: value } }
+pub fn collect_outputs(results: &[PipelineResult]) -> serde_json::Value {
+    serde_json::Value::Object(
+        results
+            .iter()
+            .filter(|r| !r.outputs.is_empty())
+            .map(|r| (r.pipeline_name.clone(), serde_json::json!(r.outputs)))
+            .collect(),
+    )
+}
+
 /// Pipeline 執行結果內部結構
+/// 單次 execute_all 的執行中狀態
+struct SequenceRun {
+    context: PipelineContext,
+    state: SequenceState,
+    results: Vec<PipelineResult>,
+    intermediates: IntermediateAggregate,
+    /// 心跳與進度檔共用的進度來源
+    progress: Arc<ProgressTracker>,
+    /// 本次執行中被略過的 Pipeline，用於判斷依賴是否完成
+    skipped: HashSet<String>,
+}
+
+/// 失敗嘗試累計的 HTTP 呼叫與重試次數，併入最終成功嘗試的階段指標
+#[derive(Default)]
+struct FailedAttempts {
+    http_calls: u64,
+    http_retries: u64,
+}
+
+impl FailedAttempts {
+    /// 記錄一次失敗的嘗試：保留其 HTTP 計數，並將這次 Pipeline 重試計為一次重試
+    fn record(&mut self, metadata: &HashMap<String, serde_json::Value>) {
+        let count = |key: &str| metadata.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        self.http_calls += count("http_calls");
+        self.http_retries += count("http_retries") + 1;
+    }
+
+    fn merge_into(&self, metadata: &mut HashMap<String, serde_json::Value>) {
+        for (key, failed) in [
+            ("http_calls", self.http_calls),
+            ("http_retries", self.http_retries),
+        ] {
+            if failed > 0 {
+                let total = metadata.get(key).and_then(|v| v.as_u64()).unwrap_or(0) + failed;
+                metadata.insert(key.to_string(), serde_json::json!(total));
+            }
+        }
+    }
+}
+
 struct PipelineExecutionResult {
     processed_records: Vec<Record>,
+    intermediate_data: Vec<Record>,
     output_path: String,
     metadata: HashMap<String, serde_json::Value>,
+    warnings: Vec<Warning>,
+    outputs: HashMap<String, serde_json::Value>,
 }
@@ -0,0 +1,466 @@
+use crate::adapters::storage::PipelineStorage;
+use crate::app::pipelines::sequence_foreach::ForeachItem;
+use crate::config::sequence_config::{PipelineDefinition, SequenceConfig};
+use crate::core::Storage;
+use crate::core::{
+    context_spill::ContextSpill,
+    contextual_pipeline::SequenceAwarePipeline,
+    etl::SequenceEngine,
+    pipeline_sequence::{PipelineResult, PipelineSequence},
+    run_report::RunReport,
+    sequence_state::{validate_execution_id, SequenceStateStore, DEFAULT_STATE_DIR},
+};
+use crate::utils::budget::ExecutionBudget;
+use crate::utils::encryption::StateCipher;
+use crate::utils::error::{EtlError, Result};
+use crate::utils::heartbeat::{Heartbeat, ProgressTracker};
+use crate::utils::monitor::MemoryPressure;
+use crate::utils::rate_limiter::RateLimiter;
+use crate::utils::redact;
+use crate::utils::shutdown::ShutdownSignal;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// 嵌入執行序列的選項，對應 sequence_etl 的命令列旗標
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub execution_id: Option<String>,      // 未指定時產生 seq_{時間}
+    pub resume: Option<String>,            // 續跑的執行 ID（沿用其 ID，略過已完成的 Pipeline）
+    pub only: Vec<String>,                 // 只執行這些 Pipeline；空白表示全部
+    pub skip: Vec<String>,                 // 略過這些 Pipeline
+    pub monitor: Option<bool>,             // 覆寫 monitoring.enabled
+    pub run_report: Option<PathBuf>,       // 覆寫 monitoring.run_report
+    pub shutdown: Option<ShutdownSignal>,  // 由呼叫端要求停止（例如排程器取消工作）
+    pub budget: Option<ExecutionBudget>,   // 執行時間預算（例如 Lambda 的呼叫期限）
+    pub foreach_item: Option<ForeachItem>, // sequence.foreach 目前的項目，寫入共享數據
+}
+
+impl RunOptions {
+    /// 續跑時沿用原本的執行 ID，否則使用指定的 ID 或產生新的 ID
+    pub fn resolve_execution_id(&self) -> String {
+        self.resume
+            .clone()
+            .or_else(|| self.execution_id.clone())
+            .unwrap_or_else(|| generate_execution_id(None))
+    }
+
+    /// 依 only / skip 篩選出要執行的已啟用 Pipeline
+    pub fn select_pipelines<'a>(&self, config: &'a SequenceConfig) -> Vec<&'a PipelineDefinition> {
+        let mut pipelines = config.get_enabled_pipelines();
+        if !self.only.is_empty() {
+            let only: HashSet<&str> = self.only.iter().map(|name| name.trim()).collect();
+            pipelines.retain(|p| only.contains(p.name.as_str()));
+        }
+        if !self.skip.is_empty() {
+            let skip: HashSet<&str> = self.skip.iter().map(|name| name.trim()).collect();
+            pipelines.retain(|p| !skip.contains(p.name.as_str()));
+        }
+        pipelines
+    }
+
+    /// 執行報告的路徑：選項優先於 monitoring.run_report，預設在狀態目錄
+    pub fn run_report_path(&self, config: &SequenceConfig, execution_id: &str) -> PathBuf {
+        self.run_report
+            .clone()
+            .or_else(|| {
+                config
+                    .monitoring
+                    .as_ref()
+                    .and_then(|monitoring| monitoring.run_report.as_deref())
+                    .map(|path| PathBuf::from(path.replace("{execution_id}", execution_id)))
+            })
+            .unwrap_or_else(|| state_store(config, None).run_report_path(execution_id))
+    }
+}
+
+/// 在其他 Rust 服務中執行一次序列，不必呼叫 sequence_etl 再解析輸出
+///
+/// 設定會先驗證；結果（含失敗與中斷）以 `RunReport` 返回，並與 CLI 相同寫入 run_report.json。
+pub async fn run_sequence(config: SequenceConfig, options: RunOptions) -> RunReport {
+    run_sequence_with_storage(config, options, |pipeline| {
+        PipelineStorage::from_load_config(&pipeline.load)
+    })
+    .await
+}
+
+/// 同 `run_sequence`，但各 Pipeline 的輸出存儲由 `storage_for` 建立（例如 Lambda 寫到 S3）
+pub async fn run_sequence_with_storage<S, F>(
+    config: SequenceConfig,
+    options: RunOptions,
+    storage_for: F,
+) -> RunReport
+where
+    S: Storage + 'static,
+    F: Fn(&PipelineDefinition) -> Result<S>,
+{
+    let started_at = Instant::now();
+    let execution_id = options.resolve_execution_id();
+    let options = RunOptions {
+        execution_id: Some(execution_id.clone()),
+        ..options
+    };
+    let span = tracing::info_span!(
+        "sequence",
+        execution_id = %execution_id,
+        sequence = %config.sequence.name
+    );
+    let outcome = async {
+        config.validate()?;
+        configure_redaction(&[&config]);
+        SequenceRunner::with_storage(&config, &options, storage_for)?
+            .run()
+            .await
+    }
+    .instrument(span)
+    .await;
+
+    let exit_code = outcome.as_ref().err().map_or(0, EtlError::exit_code);
+    write_run_report(
+        &config,
+        &options,
+        &execution_id,
+        &outcome,
+        exit_code,
+        started_at.elapsed(),
+    )
+}
+
+/// 依設定組好的序列；心跳在執行結束（或放棄執行）時停止
+pub struct SequenceRunner {
+    execution_id: String,
+    engine: SequenceEngine,
+    _heartbeat: Option<Heartbeat>,
+}
+
+impl SequenceRunner {
+    /// 建立共用元件（速率限制、狀態檔、進度、上下文暫存…）與各 Pipeline；續跑時讀回狀態
+    pub fn new(config: &SequenceConfig, options: &RunOptions) -> Result<Self> {
+        Self::with_storage(config, options, |pipeline| {
+            PipelineStorage::from_load_config(&pipeline.load)
+        })
+    }
+
+    /// 以 `storage_for` 建立各 Pipeline 的輸出存儲
+    pub fn with_storage<S, F>(
+        config: &SequenceConfig,
+        options: &RunOptions,
+        storage_for: F,
+    ) -> Result<Self>
+    where
+        S: Storage + 'static,
+        F: Fn(&PipelineDefinition) -> Result<S>,
+    {
+        let execution_id = options.resolve_execution_id();
+        validate_execution_id(&execution_id)?;
+        let monitor_enabled = options.monitor.unwrap_or_else(|| {
+            config
+                .monitoring
+                .as_ref()
+                .map(|m| m.enabled)
+                .unwrap_or(false)
+        });
+        let pipelines_to_execute = options.select_pipelines(config);
+
+        // 全域速率限制器由所有 Pipeline 共享
+        let shared_rate_limiter = config
+            .global
+            .as_ref()
+            .and_then(|global| global.rate_limit.as_ref())
+            .map(|rate_limit| {
+                tracing::info!(
+                    "🚦 Global rate limit: {} req/s (burst {})",
+                    rate_limit.requests_per_second,
+                    rate_limit.burst()
+                );
+                Arc::new(RateLimiter::from_config(rate_limit))
+            });
+
+        // 狀態檔加密器由所有 Pipeline 共享
+        let state_cipher = state_cipher(config)?;
+        if state_cipher.is_some() {
+            tracing::info!("🔐 State file encryption enabled");
+        }
+        let state_store = state_store(config, state_cipher.as_ref());
+
+        // 心跳與存活檔：長時間執行時讓外部監控分辨「慢」與「卡住」
+        let progress = Arc::new(ProgressTracker::new(pipelines_to_execute.len()));
+        let heartbeat = config.monitoring.as_ref().and_then(|monitoring| {
+            let interval = monitoring.heartbeat_interval()?;
+            tracing::info!("💓 Heartbeat every {:?}", interval);
+            Some(Heartbeat::spawn(
+                Arc::clone(&progress),
+                interval,
+                monitoring.liveness_file.as_ref().map(PathBuf::from),
+            ))
+        });
+
+        // 進度檔：外部儀表板可在執行期間讀取各 Pipeline 的階段與筆數
+        let progress_file = config
+            .monitoring
+            .as_ref()
+            .and_then(|monitoring| monitoring.progress_file.as_ref())
+            .map(|path| PathBuf::from(path.replace("{execution_id}", &execution_id)))
+            .unwrap_or_else(|| state_store.progress_path(&execution_id));
+        tracing::info!("📈 Progress file: {}", progress_file.display());
+
+        // 創建序列執行器
+        let mut sequence = PipelineSequence::new(execution_id.clone())
+            .with_state_store(state_store.clone())
+            .with_progress(Arc::clone(&progress))
+            .with_progress_file(progress_file);
+        if let Some(shutdown) = &options.shutdown {
+            sequence = sequence.with_shutdown(shutdown.clone());
+        }
+        if let Some(item) = &options.foreach_item {
+            sequence = sequence.with_foreach_item(item.clone());
+        }
+
+        if let Some(shared_data) = config
+            .global
+            .as_ref()
+            .and_then(|global| global.shared_data.as_ref())
+        {
+            sequence =
+                sequence.with_shared_data_policy(shared_data.policy()?, shared_data.producers());
+        }
+
+        if let Some(aggregate) = config
+            .global
+            .as_ref()
+            .and_then(|global| global.intermediate_aggregate.as_ref())
+        {
+            // 彙整檔沿用 Pipeline 的存儲建立方式（本機、SFTP 或呼叫端提供的存儲），根目錄為檔案所在目錄
+            let (directory, file_name) = aggregate.split_path();
+            if let Some(template) = config.pipelines.first() {
+                let mut definition = template.clone();
+                definition.load.output_path = directory;
+                sequence = sequence.with_intermediate_aggregate(
+                    storage_for(&definition)?,
+                    file_name,
+                    aggregate.format()?,
+                );
+            }
+        }
+
+        // 上下文記憶體上限：大型結果寫入暫存檔（與狀態檔使用同一把金鑰加密）
+        if let Some(context) = config
+            .global
+            .as_ref()
+            .and_then(|global| global.context.as_ref())
+        {
+            // 續跑時 execution_id 沿用原執行，因此會讀回同一批暫存檔
+            let spill_dir = context
+                .spill_dir
+                .as_ref()
+                .map(|dir| PathBuf::from(dir.replace("{execution_id}", &execution_id)))
+                .unwrap_or_else(|| state_store.spill_dir(&execution_id));
+            tracing::info!(
+                "💽 Context keeps up to {} records per pipeline in memory, spilling to {}",
+                context.max_records_in_memory,
+                spill_dir.display()
+            );
+            let mut spill = ContextSpill::new(context.max_records_in_memory, spill_dir);
+            if let Some(state_cipher) = &state_cipher {
+                spill = spill.with_cipher(Arc::clone(state_cipher));
+            }
+            sequence = sequence.with_context_spill(spill);
+        }
+
+        // 記憶體背壓：超過 RSS 上限時暫停擷取，並將結果提早寫入暫存檔
+        if let Some((monitoring, limit_mb)) = config.monitoring.as_ref().and_then(|monitoring| {
+            monitoring
+                .max_memory_mb
+                .map(|limit_mb| (monitoring, limit_mb))
+        }) {
+            tracing::info!(
+                "🧠 Memory limit {}MB: pausing extraction up to {:?} when exceeded",
+                limit_mb,
+                monitoring.max_memory_pause()
+            );
+            let has_context_spill = config
+                .global
+                .as_ref()
+                .is_some_and(|global| global.context.is_some());
+            if !has_context_spill {
+                // 未設定上下文上限時只在記憶體壓力下寫入暫存檔
+                let mut spill = ContextSpill::new(usize::MAX, state_store.spill_dir(&execution_id));
+                if let Some(state_cipher) = &state_cipher {
+                    spill = spill.with_cipher(Arc::clone(state_cipher));
+                }
+                sequence = sequence.with_context_spill(spill);
+            }
+            sequence = sequence
+                .with_memory_pressure(MemoryPressure::new(limit_mb, monitoring.max_memory_pause()));
+        }
+
+        if let Some(error_handling) = &config.error_handling {
+            if let Some(fallback) = &error_handling.fallback_pipeline {
+                sequence = sequence.with_fallback_pipeline(fallback);
+            }
+            if let Some((retries, delay)) = error_handling.pipeline_retry() {
+                tracing::info!("🔄 Pipeline retry: {} retries, {:?} apart", retries, delay);
+                sequence = sequence.with_pipeline_retry(retries, delay);
+            }
+        }
+
+        if let Some(resume_id) = &options.resume {
+            let state = state_store.load(resume_id)?;
+            tracing::info!(
+                "🔁 Resuming execution {} - completed pipelines: {:?}",
+                resume_id,
+                state.completed_pipelines
+            );
+            sequence = sequence.resume_from(state);
+        }
+
+        // 為每個要執行的 Pipeline 創建 SequenceAwarePipeline
+        for pipeline_def in pipelines_to_execute {
+            tracing::info!("📦 Setting up pipeline: {}", pipeline_def.name);
+
+            // 每個 Pipeline 使用獨立的存儲
+            let storage = storage_for(pipeline_def)?;
+
+            let mut contextual_pipeline = SequenceAwarePipeline::new(
+                pipeline_def.name.clone(),
+                storage,
+                pipeline_def.clone(),
+            )
+            .with_sequence_name(&config.sequence.name)
+            .with_cache_dir(state_store.cache_dir());
+            if let Some(rate_limiter) = &shared_rate_limiter {
+                contextual_pipeline =
+                    contextual_pipeline.with_rate_limiter(Arc::clone(rate_limiter));
+            }
+            if let Some(state_cipher) = &state_cipher {
+                contextual_pipeline =
+                    contextual_pipeline.with_state_cipher(Arc::clone(state_cipher));
+            }
+            contextual_pipeline = contextual_pipeline.with_progress(Arc::clone(&progress));
+            if let Some(shutdown) = &options.shutdown {
+                contextual_pipeline = contextual_pipeline.with_shutdown(shutdown.clone());
+            }
+            if let Some(budget) = &options.budget {
+                contextual_pipeline = contextual_pipeline.with_budget(budget.clone());
+            }
+
+            sequence.add_pipeline(Box::new(contextual_pipeline));
+        }
+
+        let mut engine = SequenceEngine::new_with_monitoring(sequence, monitor_enabled);
+        if let Some(budget) = &options.budget {
+            engine = engine.with_budget(budget.clone());
+        }
+
+        Ok(Self {
+            execution_id,
+            engine,
+            _heartbeat: heartbeat,
+        })
+    }
+
+    pub fn execution_id(&self) -> &str {
+        &self.execution_id
+    }
+
+    pub async fn run(mut self) -> Result<Vec<PipelineResult>> {
+        tracing::info!("🎬 Starting pipeline sequence execution");
+        self.engine.run().await
+    }
+}
+
+/// 建立並寫入執行報告；寫入失敗只記錄警告，不影響執行結果
+pub fn write_run_report(
+    config: &SequenceConfig,
+    options: &RunOptions,
+    execution_id: &str,
+    outcome: &Result<Vec<PipelineResult>>,
+    exit_code: i32,
+    duration: Duration,
+) -> RunReport {
+    let report = match outcome {
+        Ok(results) => RunReport::succeeded(execution_id, results),
+        Err(error) => {
+            let state = state_cipher(config)
+                .ok()
+                .and_then(|cipher| state_store(config, cipher.as_ref()).load(execution_id).ok());
+            let pipeline_order: Vec<String> = options
+                .select_pipelines(config)
+                .into_iter()
+                .map(|pipeline| pipeline.name.clone())
+                .collect();
+            RunReport::failed(
+                execution_id,
+                error,
+                exit_code,
+                state.as_ref(),
+                &pipeline_order,
+            )
+        }
+    }
+    .with_sequence(&config.sequence.name)
+    .with_duration(duration);
+
+    let path = options.run_report_path(config, execution_id);
+    match report.write(&path) {
+        Ok(()) => tracing::info!("🧾 Run report written to {}", path.display()),
+        Err(e) => tracing::warn!("⚠️ Failed to write run report {}: {}", path.display(), e),
+    }
+    report
+}
+
+/// 設定日誌、指標與 metadata.json 的遮蔽：設定檔額外列出的敏感欄位，以及標頭、參數中的憑證值
+pub fn configure_redaction(configs: &[&SequenceConfig]) {
+    let sensitive_fields: Vec<String> = configs
+        .iter()
+        .filter_map(|config| config.global.as_ref()?.redaction.as_ref())
+        .flat_map(|redaction| redaction.sensitive_fields().iter().cloned())
+        .collect();
+    redact::set_sensitive_fields(&sensitive_fields);
+    redact::set_known_secrets(
+        configs
+            .iter()
+            .flat_map(|config| config.sensitive_values())
+            .collect(),
+    );
+}
+
+/// 產生執行 ID，可指定前綴（排程模式下 --execution-id 作為前綴使用）
+pub fn generate_execution_id(prefix: Option<&str>) -> String {
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    match prefix {
+        Some(prefix) => format!("{}_{}", prefix, timestamp),
+        None => format!("seq_{}", timestamp),
+    }
+}
+
+pub fn state_cipher(config: &SequenceConfig) -> Result<Option<Arc<StateCipher>>> {
+    match config
+        .global
+        .as_ref()
+        .and_then(|global| global.state_encryption.as_ref())
+    {
+        Some(encryption) => Ok(encryption.cipher()?.map(Arc::new)),
+        None => Ok(None),
+    }
+}
+
+/// 序列狀態存放於工作目錄下，供失敗後 --resume 使用
+pub fn state_store(
+    config: &SequenceConfig,
+    cipher: Option<&Arc<StateCipher>>,
+) -> SequenceStateStore {
+    let state_dir = config
+        .global
+        .as_ref()
+        .and_then(|global| global.working_directory.as_deref())
+        .map(|dir| std::path::Path::new(dir).join(DEFAULT_STATE_DIR))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_STATE_DIR));
+    let store = SequenceStateStore::new(state_dir);
+    match cipher {
+        Some(cipher) => store.with_cipher(Arc::clone(cipher)),
+        None => store,
+    }
+}
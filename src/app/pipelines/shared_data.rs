@@ -0,0 +1,251 @@
+use crate::utils::error::{EtlError, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 多個 Pipeline 寫入同一個共享數據鍵時的處理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SharedDataPolicy {
+    /// 後寫入者覆蓋，覆蓋其他 Pipeline 的值時發出警告
+    #[default]
+    LastWriteWins,
+    /// 先寫入者保留，其他 Pipeline 的寫入被拒絕
+    FirstWriteWins,
+    /// 只有宣告為該鍵生產者的 Pipeline 可以寫入；未宣告的鍵視同後寫入者覆蓋
+    DeclaredProducers,
+}
+
+impl SharedDataPolicy {
+    pub const SUPPORTED: [&'static str; 3] =
+        ["last_write_wins", "first_write_wins", "declared_producers"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "last_write_wins" => Ok(Self::LastWriteWins),
+            "first_write_wins" => Ok(Self::FirstWriteWins),
+            "declared_producers" => Ok(Self::DeclaredProducers),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "global.shared_data.policy".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 單次共享數據寫入的結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum SharedDataWrite {
+    /// 新鍵，或同一個 Pipeline 更新自己寫入的值
+    Written,
+    /// 覆蓋了其他 Pipeline 寫入的值
+    Overwritten { previous_producer: String },
+    /// 依策略拒絕寫入，值維持不變
+    Rejected { owner: String },
+}
+
+#[derive(Debug, Default)]
+struct Ledger {
+    policy: SharedDataPolicy,
+    producers: HashMap<String, String>,
+    owners: HashMap<String, String>,
+    values: HashMap<String, serde_json::Value>,
+}
+
+/// 以鎖保護的共享數據存放區
+///
+/// 複製的 PipelineContext 共用同一個存放區，並行的轉換階段寫入時由鎖保證
+/// 判斷與寫入是原子操作，結果不受執行順序交錯影響。
+#[derive(Debug, Clone, Default)]
+pub struct SharedDataStore {
+    ledger: Arc<Mutex<Ledger>>,
+}
+
+impl SharedDataStore {
+    /// 設定寫入策略，並以既有的值與擁有者（例如續跑時載入的狀態）初始化
+    pub fn configure(
+        &self,
+        policy: SharedDataPolicy,
+        producers: HashMap<String, String>,
+        values: &HashMap<String, serde_json::Value>,
+        owners: &HashMap<String, String>,
+    ) {
+        if let Ok(mut ledger) = self.ledger.lock() {
+            ledger.policy = policy;
+            ledger.producers = producers;
+            for (key, value) in values {
+                ledger
+                    .values
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+            for (key, owner) in owners {
+                ledger
+                    .owners
+                    .entry(key.clone())
+                    .or_insert_with(|| owner.clone());
+            }
+        }
+    }
+
+    /// 依策略寫入；producer 為 None 時視為不具名寫入，直接覆蓋
+    pub fn write(
+        &self,
+        producer: Option<&str>,
+        key: &str,
+        value: serde_json::Value,
+    ) -> SharedDataWrite {
+        let Ok(mut ledger) = self.ledger.lock() else {
+            return SharedDataWrite::Written;
+        };
+
+        let outcome = match producer {
+            None => SharedDataWrite::Written,
+            Some(producer) => ledger.decide(producer, key),
+        };
+        if !matches!(outcome, SharedDataWrite::Rejected { .. }) {
+            ledger.values.insert(key.to_string(), value);
+            match producer {
+                Some(producer) => ledger.owners.insert(key.to_string(), producer.to_string()),
+                None => ledger.owners.remove(key),
+            };
+        }
+        outcome
+    }
+
+    /// 以給定的值與擁有者取代存放區內容（保留寫入策略），用於捨棄失敗嘗試的寫入
+    pub fn reset(
+        &self,
+        values: &HashMap<String, serde_json::Value>,
+        owners: &HashMap<String, String>,
+    ) {
+        if let Ok(mut ledger) = self.ledger.lock() {
+            ledger.values = values.clone();
+            ledger.owners = owners.clone();
+        }
+    }
+
+    pub fn values(&self) -> HashMap<String, serde_json::Value> {
+        self.ledger
+            .lock()
+            .map(|ledger| ledger.values.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn owners(&self) -> HashMap<String, String> {
+        self.ledger
+            .lock()
+            .map(|ledger| ledger.owners.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Ledger {
+    fn decide(&self, producer: &str, key: &str) -> SharedDataWrite {
+        let owner = self.owners.get(key).filter(|owner| *owner != producer);
+
+        if self.policy == SharedDataPolicy::DeclaredProducers {
+            if let Some(declared) = self.producers.get(key) {
+                return if declared == producer {
+                    SharedDataWrite::Written
+                } else {
+                    SharedDataWrite::Rejected {
+                        owner: declared.clone(),
+                    }
+                };
+            }
+        }
+
+        match owner {
+            None => SharedDataWrite::Written,
+            Some(owner) if self.policy == SharedDataPolicy::FirstWriteWins => {
+                SharedDataWrite::Rejected {
+                    owner: owner.clone(),
+                }
+            }
+            Some(owner) => SharedDataWrite::Overwritten {
+                previous_producer: owner.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn store(policy: SharedDataPolicy, producers: &[(&str, &str)]) -> SharedDataStore {
+        let store = SharedDataStore::default();
+        let producers = producers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        store.configure(policy, producers, &HashMap::new(), &HashMap::new());
+        store
+    }
+
+    #[test]
+    fn test_last_and_first_write_wins() {
+        let lww = store(SharedDataPolicy::LastWriteWins, &[]);
+        assert_eq!(
+            lww.write(Some("a"), "token", json!(1)),
+            SharedDataWrite::Written
+        );
+        assert_eq!(
+            lww.write(Some("a"), "token", json!(2)),
+            SharedDataWrite::Written
+        );
+        assert_eq!(
+            lww.write(Some("b"), "token", json!(3)),
+            SharedDataWrite::Overwritten {
+                previous_producer: "a".to_string()
+            }
+        );
+        assert_eq!(lww.values()["token"], json!(3));
+
+        let fww = store(SharedDataPolicy::FirstWriteWins, &[]);
+        fww.write(Some("a"), "token", json!(1));
+        assert_eq!(
+            fww.write(Some("b"), "token", json!(2)),
+            SharedDataWrite::Rejected {
+                owner: "a".to_string()
+            }
+        );
+        assert_eq!(fww.values()["token"], json!(1));
+    }
+
+    #[test]
+    fn test_declared_producers() {
+        let store = store(SharedDataPolicy::DeclaredProducers, &[("token", "auth")]);
+        assert!(matches!(
+            store.write(Some("users"), "token", json!("x")),
+            SharedDataWrite::Rejected { .. }
+        ));
+        assert_eq!(
+            store.write(Some("auth"), "token", json!("y")),
+            SharedDataWrite::Written
+        );
+        // 未宣告的鍵不受限制
+        assert_eq!(
+            store.write(Some("users"), "cursor", json!(5)),
+            SharedDataWrite::Written
+        );
+    }
+
+    #[test]
+    fn test_concurrent_writes_are_serialized() {
+        let store = store(SharedDataPolicy::FirstWriteWins, &[]);
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                std::thread::spawn(move || store.write(Some(&format!("p{}", i)), "key", json!(i)))
+            })
+            .collect();
+        let written = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|outcome| *outcome == SharedDataWrite::Written)
+            .count();
+        assert_eq!(written, 1);
+    }
+}
@@ -1,14 +1,17 @@
+use crate::core::lookup::LookupTable;
 use crate::core::{ConfigProvider, Pipeline, Record, Storage, TransformResult};
 use crate::utils::error::Result;
 use reqwest::Client;
 use std::collections::HashMap;
 use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
 use zip::write::{FileOptions, ZipWriter};
 
 pub struct SimplePipeline<S: Storage, C: ConfigProvider> {
     pub(crate) storage: S,
     pub(crate) config: C,
     pub(crate) client: Client,
+    bytes_written: AtomicU64,
 }
 
 impl<S: Storage, C: ConfigProvider> SimplePipeline<S, C> {
@@ -17,6 +20,7 @@ impl<S: Storage, C: ConfigProvider> SimplePipeline<S, C> {
             storage,
             config,
             client: Client::new(),
+            bytes_written: AtomicU64::new(0),
         }
     }
 }
@@ -81,9 +85,28 @@ impl<S: Storage, C: ConfigProvider> Pipeline for SimplePipeline<S, C> {
         let mut tsv_lines = vec!["id\tname\tvalue\tprocessed".to_string()];
         let mut intermediate_data = Vec::new();
 
+        // 載入 lookup 參照表
+        let lookup_key = self.config.lookup_key();
+        let lookup_tables = self
+            .config
+            .lookup_files()
+            .iter()
+            .map(|path| LookupTable::load(path, lookup_key))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut lookup_collisions = std::collections::BTreeSet::new();
         for record in data {
             let mut processed_record = record.clone();
 
+            // 以 lookup_key 關聯參照資料；與記錄既有欄位同名時保留記錄的值
+            for table in &lookup_tables {
+                if let Some(collisions) =
+                    table.enrich(&mut processed_record, lookup_key, None, "", false)
+                {
+                    lookup_collisions.extend(collisions);
+                }
+            }
+
             // 簡單的數據處理邏輯
             let id = record.data.get("id").and_then(|v| v.as_i64()).unwrap_or(0);
 
@@ -118,6 +141,13 @@ impl<S: Storage, C: ConfigProvider> Pipeline for SimplePipeline<S, C> {
             processed_records.push(processed_record);
         }
 
+        if !lookup_collisions.is_empty() {
+            tracing::warn!(
+                "📚 Lookup fields {:?} already exist in records, kept the record values",
+                lookup_collisions
+            );
+        }
+
         Ok(TransformResult {
             processed_records,
             csv_output: csv_lines.join("\n"),
@@ -165,8 +195,14 @@ impl<S: Storage, C: ConfigProvider> Pipeline for SimplePipeline<S, C> {
         // 保存ZIP文件
         tracing::debug!("Writing ZIP file ({} bytes) to storage", zip_data.len());
         self.storage.write_file("etl_output.zip", &zip_data).await?;
+        self.bytes_written
+            .store(zip_data.len() as u64, Ordering::Relaxed);
 
         tracing::debug!("ZIP file saved successfully");
         Ok(output_path)
     }
+
+    fn bytes_written(&self) -> Option<u64> {
+        Some(self.bytes_written.load(Ordering::Relaxed))
+    }
 }
@@ -0,0 +1,234 @@
+use crate::config::sequence_config::{LoadConfig, PipelineDefinition, SequenceConfig};
+use crate::core::contextual_pipeline::{render_output, SequenceAwarePipeline};
+use crate::core::csv_output::DelimitedFormat;
+use crate::core::lookup::read_delimited;
+use crate::core::pipeline_sequence::{ContextualPipeline, PipelineContext};
+use crate::core::response_limits::limit_records;
+use crate::core::{Record, Storage};
+use crate::utils::error::{EtlError, Result};
+
+/// transform 子命令從標準輸入讀取的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum StreamInputFormat {
+    /// JSON 陣列、單一物件或 JSON Lines
+    Json,
+    /// 第一列為欄位名稱，值皆為字串
+    Csv,
+}
+
+/// transform 子命令寫到標準輸出的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum StreamOutputFormat {
+    /// 與 load 的 CSV 輸出相同（遵循 load.columns、load.csv 與 load.null_policy）
+    Csv,
+    /// 每行一筆 JSON 物件
+    Jsonl,
+}
+
+/// 選擇要執行轉換的 Pipeline；設定中只有一個 Pipeline 時可省略名稱
+pub fn select_pipeline<'a>(
+    config: &'a SequenceConfig,
+    name: Option<&str>,
+) -> Result<&'a PipelineDefinition> {
+    match name {
+        Some(name) => config
+            .pipelines
+            .iter()
+            .find(|pipeline| pipeline.name == name)
+            .ok_or_else(|| EtlError::InvalidConfigValueError {
+                field: "pipeline".to_string(),
+                value: name.to_string(),
+                reason: "Pipeline not found in config".to_string(),
+            }),
+        None => match config.pipelines.as_slice() {
+            [pipeline] => Ok(pipeline),
+            _ => Err(EtlError::ConfigValidationError {
+                field: "pipeline".to_string(),
+                message: format!(
+                    "Config has {} pipelines, choose one with --pipeline",
+                    config.pipelines.len()
+                ),
+            }),
+        },
+    }
+}
+
+/// 將輸入解析為 JSON 值，之後與 API 回應相同地套用欄位映射
+pub fn parse_input(input: &[u8], format: StreamInputFormat) -> Result<serde_json::Value> {
+    match format {
+        StreamInputFormat::Csv => Ok(serde_json::Value::Array(
+            read_delimited(input, b',')?
+                .into_iter()
+                .map(|row| serde_json::Value::Object(row.into_iter().collect()))
+                .collect(),
+        )),
+        StreamInputFormat::Json => {
+            let text = std::str::from_utf8(input).map_err(|e| EtlError::DataValidationError {
+                message: format!("Input is not valid UTF-8: {}", e),
+            })?;
+            if text.trim().is_empty() {
+                return Ok(serde_json::Value::Array(Vec::new()));
+            }
+            // 整份文件不是單一 JSON 值時視為 JSON Lines
+            match serde_json::from_str(text) {
+                Ok(value) => Ok(value),
+                Err(_) => text
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(index, line)| {
+                        serde_json::from_str(line).map_err(|e| EtlError::DataValidationError {
+                            message: format!("Invalid JSON on line {}: {}", index + 1, e),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()
+                    .map(serde_json::Value::Array),
+            }
+        }
+    }
+}
+
+pub fn render_records(
+    records: &[Record],
+    format: StreamOutputFormat,
+    load: &LoadConfig,
+) -> Result<String> {
+    match format {
+        StreamOutputFormat::Csv => {
+            let mut output = render_output("csv", records, load)?;
+            if !output.is_empty() {
+                output.push_str(DelimitedFormat::csv(load.csv.as_ref())?.line_terminator());
+            }
+            Ok(output)
+        }
+        StreamOutputFormat::Jsonl => {
+            let mut output = String::new();
+            for record in records {
+                // 轉為 serde_json::Map 讓欄位依名稱排序，輸出穩定
+                let object: serde_json::Map<String, serde_json::Value> = record
+                    .data
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                output.push_str(&serde_json::to_string(&object)?);
+                output.push('\n');
+            }
+            Ok(output)
+        }
+    }
+}
+
+/// 以輸入取代 HTTP 來源、以輸出字串取代 ZIP 檔，只執行 Pipeline 的欄位映射與轉換
+///
+/// 轉換在空的上下文中執行，依賴其他 Pipeline 結果的查找或共享資料不會有值；
+/// `on_invalid = "reject"` 的 rejects 檔仍寫入 `storage`。
+pub async fn transform_stream<S: Storage>(
+    definition: &PipelineDefinition,
+    storage: S,
+    input: &[u8],
+    input_format: StreamInputFormat,
+    output_format: StreamOutputFormat,
+) -> Result<String> {
+    let pipeline = SequenceAwarePipeline::new(definition.name.clone(), storage, definition.clone());
+    let mut records = pipeline.records_from_json(parse_input(input, input_format)?)?;
+    if let Some(max_records) = definition.extract.max_records {
+        limit_records(
+            &mut records,
+            max_records,
+            definition.extract.limit_policy()?,
+            "stdin",
+        )?;
+    }
+    tracing::info!(
+        "📥 {}: Read {} records from stdin",
+        definition.name,
+        records.len()
+    );
+
+    let mut context = PipelineContext::new(format!("transform-{}", definition.name));
+    let result = pipeline
+        .transform_with_context(records, &mut context)
+        .await?;
+    tracing::info!(
+        "🔄 {}: Transformed {} records",
+        definition.name,
+        result.processed_records.len()
+    );
+    render_records(&result.processed_records, output_format, &definition.load)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::cli::LocalStorage;
+    use tempfile::TempDir;
+
+    const CONFIG: &str = r#"
+[sequence]
+name = "stream"
+description = "stdin transform"
+version = "1.0.0"
+execution_order = ["people"]
+
+[[pipelines]]
+name = "people"
+
+[pipelines.source]
+type = "stdin"
+
+[pipelines.extract]
+field_mapping = { "full_name" = "name", "address.city" = "city" }
+
+[pipelines.transform.operations]
+keep_only_fields = ["id", "name", "city"]
+
+[pipelines.load]
+output_path = "./output"
+output_formats = ["csv"]
+columns = ["id", "name", "city"]
+"#;
+
+    #[tokio::test]
+    async fn test_transform_stream_maps_and_renders() {
+        let config = SequenceConfig::from_toml_str(CONFIG).unwrap();
+        let definition = select_pipeline(&config, None).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let storage = || LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+
+        let input = br#"{"id": 1, "full_name": "Ada", "address": {"city": "London"}, "extra": true}
+{"id": 2, "full_name": "Linus", "address": {"city": "Helsinki"}}"#;
+        let jsonl = transform_stream(
+            definition,
+            storage(),
+            input,
+            StreamInputFormat::Json,
+            StreamOutputFormat::Jsonl,
+        )
+        .await
+        .unwrap();
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["name"], "Ada");
+        assert_eq!(lines[0]["city"], "London");
+        assert!(lines[0].get("extra").is_none());
+        assert_eq!(lines[1]["city"], "Helsinki");
+
+        let csv = transform_stream(
+            definition,
+            storage(),
+            b"id,full_name\n1,Ada\n",
+            StreamInputFormat::Csv,
+            StreamOutputFormat::Csv,
+        )
+        .await
+        .unwrap();
+        assert_eq!(csv.lines().collect::<Vec<_>>(), ["id,name,city", "1,Ada,"]);
+
+        assert!(select_pipeline(&config, Some("missing")).is_err());
+    }
+}
@@ -1,32 +1,75 @@
-use clap::Parser;
-use samll_etl::config::sequence_config::SequenceConfig;
+use clap::{Parser, Subcommand};
+use notify::Watcher;
+use samll_etl::adapters::queue::{MessageQueue, QueueMessage};
+use samll_etl::adapters::storage::PipelineStorage;
+use samll_etl::app::pipelines::sequence_backfill::{run_backfill, BackfillPlan};
+use samll_etl::app::pipelines::sequence_batch::{
+    discover_sequence_configs, BatchEntry, BatchReport,
+};
+use samll_etl::app::pipelines::sequence_foreach::run_foreach;
+use samll_etl::app::pipelines::sequence_runner::{
+    self, configure_redaction, generate_execution_id, state_cipher, state_store, SequenceRunner,
+};
+use samll_etl::app::pipelines::stream_transform::{self, StreamInputFormat, StreamOutputFormat};
+use samll_etl::app::pipelines::{sequence_dry_run, sequence_init, sequence_lint};
+use samll_etl::app::RunOptions;
+use samll_etl::config::sequence_config::{QueueConfig, SequenceConfig};
 use samll_etl::core::{
-    contextual_pipeline::SequenceAwarePipeline, pipeline_sequence::PipelineSequence,
+    file_ledger::FileLedger,
+    output_encryption,
+    pipeline_sequence::{PipelineResult, PipelineSequence},
+    resume_report::ResumeReport,
 };
-use samll_etl::utils::logger;
-use samll_etl::LocalStorage;
+use samll_etl::utils::error::EtlError;
+use samll_etl::utils::file_glob;
+use samll_etl::utils::logger::{self, LogFormat};
+use samll_etl::utils::schedule::CronSchedule;
+use samll_etl::utils::shutdown::{ShutdownSignal, INTERRUPTED_EXIT_CODE};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+use tracing::Instrument;
+
+/// SIGINT/SIGTERM 的停止旗標，所有執行中的序列與 Pipeline 共用
+static SHUTDOWN: LazyLock<ShutdownSignal> = LazyLock::new(ShutdownSignal::new);
 
-#[derive(Parser)]
+#[derive(Parser, Clone)]
 #[command(name = "sequence-etl")]
 #[command(about = "ETL tool with pipeline sequence support")]
 struct Args {
     /// Path to sequence configuration file
-    #[arg(short, long, default_value = "configs/sequence-example.toml")]
+    #[arg(
+        short,
+        long,
+        global = true,
+        default_value = "configs/sequence-example.toml"
+    )]
     config: String,
 
     /// Enable verbose output
     #[arg(short, long)]
     verbose: bool,
 
+    /// Log output format: human-readable text or structured JSON lines
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
     /// Override monitoring setting from config
     #[arg(long)]
     monitor: Option<bool>,
 
-    /// Dry run - show execution plan without executing
+    /// Dry run - show execution plan and check templates, endpoints, output paths and dependencies
     #[arg(long)]
     dry_run: bool,
 
+    /// JSON object of sample template values used by --dry-run to resolve placeholders
+    #[arg(long, value_name = "FILE", requires = "dry_run")]
+    sample_data: Option<String>,
+
+    /// Write the --dry-run problem list as JSON
+    #[arg(long, value_name = "FILE", requires = "dry_run")]
+    dry_run_report: Option<String>,
+
     /// Execution ID for this run
     #[arg(long)]
     execution_id: Option<String>,
@@ -38,20 +81,326 @@ struct Args {
     /// Skip specific pipelines (comma-separated)
     #[arg(long)]
     skip: Option<String>,
+
+    /// Write the machine-readable run report (status, exit code, per-pipeline results) to this path
+    #[arg(long, value_name = "PATH")]
+    run_report: Option<String>,
+
+    /// Resume a failed execution, skipping pipelines that already completed
+    #[arg(long, value_name = "EXECUTION_ID", conflicts_with = "execution_id")]
+    resume: Option<String>,
+
+    /// Template variable available as {{var.KEY}}, overriding [global.variables] (repeatable)
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable, global = true)]
+    vars: Vec<(String, String)>,
+
+    /// Keep running and re-run the sequence on a cron schedule (UTC), e.g. "0 */6 * * *"
+    #[arg(long, value_name = "CRON", conflicts_with = "resume")]
+    schedule: Option<String>,
+
+    /// Keep running and run the sequence whenever new files match a `files` source glob
+    #[arg(long, conflicts_with_all = ["schedule", "resume"])]
+    watch: bool,
+
+    /// Quiet period after the last file event before --watch runs the sequence
+    #[arg(long, value_name = "MS", default_value_t = 2000, requires = "watch")]
+    watch_debounce_ms: u64,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl Args {
+    /// --var 指定的變數，同名時後者優先
+    fn variables(&self) -> HashMap<String, String> {
+        self.vars.iter().cloned().collect()
+    }
+
+    /// 本次執行的選項；--only / --skip 以逗號分隔
+    fn run_options(&self, execution_id: &str) -> RunOptions {
+        let names = |list: &Option<String>| -> Vec<String> {
+            list.iter()
+                .flat_map(|list| list.split(','))
+                .map(|name| name.trim().to_string())
+                .collect()
+        };
+        RunOptions {
+            execution_id: Some(execution_id.to_string()),
+            resume: self.resume.clone(),
+            only: names(&self.only),
+            skip: names(&self.skip),
+            monitor: self.monitor,
+            run_report: self
+                .run_report
+                .as_ref()
+                .map(|path| std::path::PathBuf::from(path.replace("{execution_id}", execution_id))),
+            shutdown: Some(SHUTDOWN.clone()),
+            budget: None,
+            foreach_item: None,
+        }
+    }
+}
+
+#[derive(Subcommand, Clone)]
+enum Command {
+    /// Validate and run every sequence config in a directory
+    RunAll {
+        /// Directory containing sequence configuration files
+        #[arg(long)]
+        dir: String,
+
+        /// Maximum number of sequences running at the same time
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+
+        /// Write the consolidated report as JSON to this path
+        #[arg(long)]
+        report: Option<String>,
+    },
+    /// Run the sequence once per date window, exposing {{window.start}} and {{window.end}} to templates
+    Backfill {
+        /// First day to backfill (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+
+        /// Last day to backfill, inclusive (YYYY-MM-DD)
+        #[arg(long)]
+        to: String,
+
+        /// Window length: days (1d), weeks (1w) or months (1mo)
+        #[arg(long, default_value = "1d")]
+        step: String,
+
+        /// Maximum number of windows running at the same time
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+
+        /// Keep starting new windows after a window fails
+        #[arg(long)]
+        continue_on_error: bool,
+
+        /// Write the backfill summary report as JSON to this path
+        #[arg(long)]
+        report: Option<String>,
+    },
+    /// Check the config without running it and print diagnostics (exit code 1 on errors)
+    Validate {
+        /// Diagnostics output: compiler-style text or a JSON document for CI
+        #[arg(long, value_enum, default_value_t = DiagnosticFormat::Text)]
+        format: DiagnosticFormat,
+    },
+    /// Transform records read from stdin and write them to stdout (logs go to stderr)
+    Transform {
+        /// Pipeline whose field mapping and transform are applied (required with several pipelines)
+        #[arg(long)]
+        pipeline: Option<String>,
+
+        /// Input format on stdin
+        #[arg(long, value_enum, default_value_t = StreamInputFormat::Json)]
+        stdin_format: StreamInputFormat,
+
+        /// Output format on stdout
+        #[arg(long, value_enum, default_value_t = StreamOutputFormat::Csv)]
+        stdout_format: StreamOutputFormat,
+    },
+    /// Answer a few questions and generate a starter sequence config in a directory
+    Init {
+        /// Directory to write sequence.toml and the output directory into
+        #[arg(long, default_value = ".")]
+        dir: String,
+
+        /// Overwrite an existing sequence.toml
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the JSON Schema of the sequence config for editor autocomplete and validation
+    Schema {
+        /// Write the schema to this file instead of stdout
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Decrypt an output file written with load.compression.encryption method "aes_gcm"
+    Decrypt {
+        /// Encrypted output file (*.enc)
+        #[arg(long)]
+        input: String,
+
+        /// Where to write the decrypted file (default: input without .enc)
+        #[arg(long)]
+        output: Option<String>,
+
+        /// File containing the encryption key
+        #[arg(long, conflicts_with = "key_env", required_unless_present = "key_env")]
+        key_file: Option<String>,
+
+        /// Environment variable holding the encryption key
+        #[arg(long)]
+        key_env: Option<String>,
+    },
+}
+
+/// 解密 aes_gcm 加密的輸出檔，返回寫出的路徑
+fn decrypt_file(
+    input: &str,
+    output: Option<&str>,
+    key_file: Option<&str>,
+    key_env: Option<&str>,
+) -> Result<String, EtlError> {
+    let key = output_encryption::load_key(key_file, key_env)?;
+    let data = output_encryption::decrypt_output(&key, &std::fs::read(input)?)?;
+    let output = match output {
+        Some(output) => output.to_string(),
+        None => match input.strip_suffix(output_encryption::ENCRYPTED_EXTENSION) {
+            Some(stem) => stem.to_string(),
+            None => format!("{}.decrypted", input),
+        },
+    };
+    std::fs::write(&output, data)?;
+    Ok(output)
+}
+
+/// 互動式產生設定檔，完成後列出建立的檔案與下一步
+fn run_init(dir: &Path, force: bool) -> Result<(), EtlError> {
+    let config_path = dir.join(sequence_init::CONFIG_FILE);
+    if config_path.exists() && !force {
+        // 提問前先檢查，避免回答完才發現無法寫入
+        return Err(EtlError::ConfigError {
+            message: format!(
+                "{} already exists; use --force to overwrite",
+                config_path.display()
+            ),
+        });
+    }
+
+    let answers = sequence_init::ask(&mut std::io::stdin().lock(), &mut std::io::stdout())?;
+    for path in sequence_init::scaffold(dir, &answers, force)? {
+        println!("✅ Created {}", path.display());
+    }
+    println!(
+        "👉 Next: sequence-etl --config {} --dry-run",
+        config_path.display()
+    );
+    Ok(())
+}
+
+/// 解析 --var KEY=VALUE
+fn parse_variable(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", value)),
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+enum DiagnosticFormat {
+    Text,
+    Json,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
+    // validate 的輸出供 CI 解析，不初始化日誌以免混入其他輸出
+    if let Some(Command::Validate { format }) = &args.command {
+        let report = sequence_lint::lint_file(Path::new(&args.config));
+        match format {
+            DiagnosticFormat::Text => println!("{}", report.render()),
+            DiagnosticFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        }
+        std::process::exit(if report.valid { 0 } else { 1 });
+    }
+
+    if let Some(Command::Transform {
+        pipeline,
+        stdin_format,
+        stdout_format,
+    }) = &args.command
+    {
+        // stdout 只輸出資料，日誌改寫到 stderr
+        logger::init_stderr_logger(args.verbose, args.log_format);
+        if let Err(e) = run_transform(
+            &args.config,
+            &args.variables(),
+            pipeline.as_deref(),
+            *stdin_format,
+            *stdout_format,
+        )
+        .await
+        {
+            tracing::error!("❌ Transform failed: {}", e);
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Init { dir, force }) = &args.command {
+        if let Err(e) = run_init(Path::new(dir), *force) {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Schema { output }) = &args.command {
+        let schema = serde_json::to_string_pretty(&SequenceConfig::json_schema()?)?;
+        match output {
+            Some(path) => std::fs::write(path, schema + "\n")?,
+            None => println!("{}", schema),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Decrypt {
+        input,
+        output,
+        key_file,
+        key_env,
+    }) = &args.command
+    {
+        match decrypt_file(
+            input,
+            output.as_deref(),
+            key_file.as_deref(),
+            key_env.as_deref(),
+        ) {
+            Ok(path) => println!("🔓 Decrypted {} to {}", input, path),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     // 初始化日誌
-    logger::init_cli_logger(args.verbose);
+    logger::init_logger(args.verbose, args.log_format);
 
     tracing::info!("🚀 Starting Pipeline Sequence ETL tool");
+    SHUTDOWN.listen();
+
+    if let Some(Command::RunAll {
+        dir,
+        concurrency,
+        report,
+    }) = &args.command
+    {
+        return run_all(
+            &args,
+            Path::new(dir),
+            *concurrency as usize,
+            report.as_deref(),
+        )
+        .await;
+    }
+
     tracing::info!("📁 Loading sequence configuration from: {}", args.config);
 
     // 載入序列配置
-    let config = match SequenceConfig::from_file(&args.config) {
+    let config = match SequenceConfig::from_file_with_variables(&args.config, &args.variables()) {
         Ok(config) => config,
         Err(e) => {
             eprintln!(
@@ -71,12 +420,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     tracing::info!("✅ Sequence configuration loaded and validated successfully");
+    configure_redaction(&[&config]);
+
+    // 常駐排程：CLI 參數優先於設定檔
+    let schedule = match args.schedule.as_deref() {
+        Some(expression) => Some((CronSchedule::parse(expression)?, false)),
+        None => match &config.sequence.schedule {
+            Some(schedule) => Some((
+                CronSchedule::parse(&schedule.cron)?,
+                schedule.run_on_start(),
+            )),
+            None => None,
+        },
+    };
+    // 排程的每次執行各自產生執行 ID，沿用 --resume 的狀態會讓每次排程都續跑同一次執行
+    if schedule.is_some() && args.resume.is_some() {
+        eprintln!("❌ --resume cannot be used with a schedule (--schedule or sequence.schedule)");
+        std::process::exit(1);
+    }
 
-    // 生成執行 ID
-    let execution_id = args
-        .execution_id
-        .clone()
-        .unwrap_or_else(|| format!("seq_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S")));
+    // 生成執行 ID（續跑時沿用原本的 ID；排程與監看模式每次執行各自產生）
+    let execution_id = match &schedule {
+        _ if args.watch => "(generated per watch run)".to_string(),
+        _ if config.sequence.queue.is_some() => "(generated per queue message)".to_string(),
+        Some(_) => "(generated per scheduled run)".to_string(),
+        None => args
+            .resume
+            .clone()
+            .unwrap_or_else(|| generate_execution_id(args.execution_id.as_deref())),
+    };
 
     // 顯示序列摘要
     display_sequence_summary(&config, &args, &execution_id);
@@ -87,81 +459,854 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // 決定監控設定
-    let monitor_enabled = args.monitor.unwrap_or_else(|| {
-        config
-            .monitoring
-            .as_ref()
-            .map(|m| m.enabled)
-            .unwrap_or(false)
-    });
-
-    // 創建序列執行器
-    let mut sequence = PipelineSequence::new(execution_id.clone()).with_monitoring(monitor_enabled);
+    // Prometheus 指標端點：執行期間持續提供 /metrics 供監控系統抓取
+    let metrics_address = config
+        .monitoring
+        .as_ref()
+        .and_then(|monitoring| monitoring.metrics_address.clone());
+    #[cfg(feature = "metrics-server")]
+    let _metrics_server = match &metrics_address {
+        Some(address) => Some(
+            Arc::new(samll_etl::utils::monitor::SystemMonitor::new(true))
+                .serve_metrics(address)
+                .await?,
+        ),
+        None => None,
+    };
+    #[cfg(not(feature = "metrics-server"))]
+    if let Some(address) = &metrics_address {
+        tracing::warn!(
+            "📡 metrics_address {} ignored: rebuild with --features metrics-server",
+            address
+        );
+    }
 
-    // 獲取要執行的 Pipeline 列表
-    let pipelines_to_execute = determine_pipelines_to_execute(&config, &args);
+    if let Some(Command::Backfill {
+        from,
+        to,
+        step,
+        concurrency,
+        continue_on_error,
+        report,
+    }) = &args.command
+    {
+        if args.schedule.is_some() || args.watch || args.resume.is_some() {
+            eprintln!("❌ backfill cannot be used with --schedule, --watch or --resume");
+            std::process::exit(1);
+        }
+        let plan = match BackfillPlan::parse(from, to, step) {
+            Ok(plan) => plan
+                .with_concurrency(*concurrency as usize)
+                .with_continue_on_error(*continue_on_error),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        };
+        // 回補是一次性的執行：不使用設定檔中的排程與佇列
+        let execution_id = generate_execution_id(args.execution_id.as_deref());
+        return run_backfill_windows(&config, &args, &execution_id, &plan, report.as_deref()).await;
+    }
 
-    // 為每個要執行的 Pipeline 創建 ContextualPipeline
-    for pipeline_def in pipelines_to_execute {
-        tracing::info!("📦 Setting up pipeline: {}", pipeline_def.name);
+    if config.sequence.foreach.is_some() {
+        if schedule.is_some() || args.watch || args.resume.is_some() {
+            eprintln!("❌ sequence.foreach cannot be used with a schedule, --watch or --resume");
+            std::process::exit(1);
+        }
+        return run_foreach_items(&config, &args, &execution_id).await;
+    }
 
-        // 創建存儲（每個 Pipeline 使用獨立的存儲）
-        let storage = LocalStorage::new(pipeline_def.load.output_path.clone());
+    if args.watch {
+        return run_watch(&config, &args).await;
+    }
 
-        // 創建 SequenceAwarePipeline
-        let contextual_pipeline =
-            SequenceAwarePipeline::new(pipeline_def.name.clone(), storage, pipeline_def.clone());
+    if let Some(queue) = &config.sequence.queue {
+        if args.schedule.is_some() {
+            eprintln!("❌ --schedule cannot be used with sequence.queue");
+            std::process::exit(1);
+        }
+        return run_queue(&args, queue).await;
+    }
 
-        sequence.add_pipeline(Box::new(contextual_pipeline));
+    if let Some((schedule, run_on_start)) = schedule {
+        return run_scheduled(&config, &args, &schedule, run_on_start).await;
     }
 
-    // 執行序列
-    tracing::info!("🎬 Starting pipeline sequence execution");
-    match sequence.execute_all().await {
+    let started_at = std::time::Instant::now();
+    let outcome = run_sequence(&config, &args, &execution_id).await?;
+    match &outcome {
         Ok(results) => {
-            tracing::info!("🎉 Pipeline sequence completed successfully!");
+            report_success(&config, results, &execution_id).await?;
+            write_run_report(
+                &config,
+                &args,
+                &execution_id,
+                &outcome,
+                0,
+                started_at.elapsed(),
+            );
+        }
+        Err(e) => {
+            eprintln!("❌ Pipeline sequence failed: {}", e);
+            report_failure(&config, &args, &execution_id, e);
+
+            // 根據錯誤處理配置決定處理方式（中斷時一律以 130 結束）；
+            // 與單一 Pipeline 相同，依錯誤嚴重程度決定退出碼（預設是停止）
+            let interrupted = matches!(e.root(), EtlError::Interrupted { .. });
+            let continue_on_failure = !interrupted
+                && config
+                    .error_handling
+                    .as_ref()
+                    .and_then(|error_config| error_config.on_pipeline_failure.as_deref())
+                    == Some("continue");
+            let exit_code = if continue_on_failure {
+                0
+            } else {
+                e.exit_code()
+            };
+            write_run_report(
+                &config,
+                &args,
+                &execution_id,
+                &outcome,
+                exit_code,
+                started_at.elapsed(),
+            );
+
+            if continue_on_failure {
+                tracing::info!("⚠️ Continuing despite failure (configured behavior)");
+                return Ok(());
+            }
+            if exit_code > 0 {
+                std::process::exit(exit_code);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 批次模式：找出目錄下所有序列設定檔，在全域並行上限內執行並輸出彙整報告
+///
+/// 驗證失敗的設定不會執行，但會列入報告；任一序列失敗或無效時以非零狀態結束。
+async fn run_all(
+    args: &Args,
+    dir: &Path,
+    concurrency: usize,
+    report_path: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let discovered = discover_sequence_configs(dir, &args.variables())?;
+    if discovered.is_empty() {
+        eprintln!("❌ No sequence configs found in {}", dir.display());
+        std::process::exit(1);
+    }
+    tracing::info!(
+        "📚 Found {} sequence configs in {} (concurrency {})",
+        discovered.len(),
+        dir.display(),
+        concurrency
+    );
+
+    configure_redaction(
+        &discovered
+            .iter()
+            .filter_map(|sequence| sequence.config.as_ref().ok())
+            .collect::<Vec<_>>(),
+    );
+
+    let mut report = BatchReport::default();
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+    let mut tasks = tokio::task::JoinSet::new();
+    for sequence in discovered {
+        let config = match sequence.config {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::error!("❌ {} is invalid: {}", sequence.path.display(), e);
+                report.push(BatchEntry::invalid(&sequence.path, e));
+                continue;
+            }
+        };
+
+        // 各序列沿用共用旗標，但 Pipeline 篩選、續跑與排程只對單一序列有意義
+        let args = Args {
+            config: sequence.path.display().to_string(),
+            execution_id: None,
+            only: None,
+            skip: None,
+            resume: None,
+            run_report: None,
+            schedule: None,
+            watch: false,
+            command: None,
+            ..args.clone()
+        };
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            // 以檔名為前綴，避免同名序列在同一秒啟動時共用狀態檔
+            let prefix = sequence
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned());
+            let execution_id = generate_execution_id(prefix.as_deref());
+            tracing::info!("▶️ Running {} as {}", args.config, execution_id);
+
+            let start = std::time::Instant::now();
+            let result = run_sequence(&config, &args, &execution_id)
+                .await
+                .map_err(|e| e.to_string());
+            if let Ok(outcome) = &result {
+                let exit_code = outcome.as_ref().err().map_or(0, EtlError::exit_code);
+                write_run_report(
+                    &config,
+                    &args,
+                    &execution_id,
+                    outcome,
+                    exit_code,
+                    start.elapsed(),
+                );
+            }
+            let outcome = match result {
+                Ok(Ok(results)) => {
+                    if let Some(monitoring) = config
+                        .monitoring
+                        .as_ref()
+                        .filter(|m| m.export_metrics.unwrap_or(false))
+                    {
+                        if let Err(e) =
+                            export_execution_metrics(&results, &execution_id, monitoring).await
+                        {
+                            tracing::warn!("⚠️ Failed to export metrics: {}", e);
+                        }
+                    }
+                    let records = results.iter().map(|result| result.record_count()).sum();
+                    let executed = results.iter().filter(|result| !result.is_skipped()).count();
+                    Ok((executed, records))
+                }
+                Ok(Err(e)) => {
+                    report_failure(&config, &args, &execution_id, &e);
+                    Err(e.to_string())
+                }
+                Err(e) => Err(e),
+            };
+            BatchEntry::executed(
+                &sequence.path,
+                &config.sequence.name,
+                &execution_id,
+                start.elapsed(),
+                outcome,
+            )
+        });
+    }
+
+    while let Some(entry) = tasks.join_next().await {
+        report.push(entry?);
+    }
+
+    println!("\n{}", report.render());
+    if let Some(path) = report_path {
+        report.write_json(Path::new(path))?;
+        println!("📝 Batch report written to {}", path);
+    }
+
+    if SHUTDOWN.is_requested() {
+        std::process::exit(INTERRUPTED_EXIT_CODE);
+    }
+
+    if !report.all_succeeded() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 逐項執行：每個 sequence.foreach 項目執行一次整個序列，最後輸出彙整報告
+///
+/// 各項目的執行 ID 為 `{execution_id}_{後綴}`，執行報告各自寫入；任一項目失敗時以非零狀態結束。
+async fn run_foreach_items(
+    config: &SequenceConfig,
+    args: &Args,
+    execution_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // 執行報告依各項目的執行 ID 寫到預設位置，避免互相覆寫
+    let options = RunOptions {
+        run_report: None,
+        ..args.run_options(execution_id)
+    };
+    let report = run_foreach(config, &options).await?;
+
+    println!("\n{}", report.render());
+    if let Some(summary) = config
+        .sequence
+        .foreach
+        .as_ref()
+        .and_then(|foreach| foreach.summary.as_ref())
+    {
+        let path = summary.replace("{execution_id}", execution_id);
+        report.write_json(Path::new(&path))?;
+        println!("📝 Foreach report written to {}", path);
+    }
+
+    if SHUTDOWN.is_requested() {
+        std::process::exit(INTERRUPTED_EXIT_CODE);
+    }
+    if !report.all_succeeded() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 回補：每個時間窗執行一次整個序列，最後輸出彙整報告
+///
+/// 各時間窗的執行 ID 為 `{execution_id}_{開始日期}`，執行報告各自寫入；任一時間窗失敗時以非零狀態結束。
+async fn run_backfill_windows(
+    config: &SequenceConfig,
+    args: &Args,
+    execution_id: &str,
+    plan: &BackfillPlan,
+    report_path: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let options = RunOptions {
+        run_report: None,
+        ..args.run_options(execution_id)
+    };
+    let report = match run_backfill(config, &options, plan).await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!("\n{}", report.render());
+    if let Some(path) = report_path {
+        let path = path.replace("{execution_id}", execution_id);
+        report.write_json(Path::new(&path))?;
+        println!("📝 Backfill report written to {}", path);
+    }
+
+    if SHUTDOWN.is_requested() {
+        std::process::exit(INTERRUPTED_EXIT_CODE);
+    }
+    if !report.all_succeeded() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-            // 顯示執行結果摘要
-            display_execution_results(&results, &execution_id);
+/// 常駐模式：依 cron 排程重複執行序列，直到收到 Ctrl+C
+///
+/// 同一時間只會有一次執行；執行期間到期的排程直接略過，不會在結束後補跑。
+async fn run_scheduled(
+    config: &SequenceConfig,
+    args: &Args,
+    schedule: &CronSchedule,
+    run_on_start: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!(
+        "⏰ Scheduler started with '{}' (UTC)",
+        schedule.expression()
+    );
+    let mut run_now = run_on_start;
 
-            // 匯出執行摘要
-            if let Some(monitoring) = &config.monitoring {
-                if monitoring.export_metrics.unwrap_or(false) {
-                    export_execution_metrics(&results, &execution_id, monitoring).await?;
+    loop {
+        if !run_now {
+            let Some(next) = schedule.next_after(chrono::Utc::now()) else {
+                tracing::warn!("⏰ Schedule '{}' never fires again", schedule.expression());
+                return Ok(());
+            };
+            tracing::info!("⏰ Next run at {}", next.to_rfc3339());
+            let wait = (next - chrono::Utc::now()).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("🛑 Scheduler stopped");
+                    return Ok(());
                 }
             }
+        }
+        run_now = false;
+
+        let started_at = chrono::Utc::now();
+        let execution_id = generate_execution_id(args.execution_id.as_deref());
+        tracing::info!("⏰ Scheduled run {} starting", execution_id);
+        run_and_report(config, args, &execution_id, "Scheduled").await;
+        if SHUTDOWN.is_requested() {
+            tracing::info!("🛑 Scheduler stopped");
+            std::process::exit(INTERRUPTED_EXIT_CODE);
+        }
+
+        // 重疊保護：執行期間到期的排程不補跑
+        let now = chrono::Utc::now();
+        let mut missed = 0;
+        let mut cursor = started_at;
+        while let Some(next) = schedule.next_after(cursor).filter(|next| *next <= now) {
+            missed += 1;
+            cursor = next;
+        }
+        if missed > 0 {
+            tracing::warn!(
+                "⏭️ Skipped {} scheduled run(s) that fell due while {} was still running",
+                missed,
+                execution_id
+            );
+        }
+    }
+}
 
-            println!("✅ Pipeline sequence completed successfully!");
-            println!("🆔 Execution ID: {}", execution_id);
-            println!("📊 Pipelines executed: {}", results.len());
+/// 常駐模式的單次執行：失敗只記錄並回報，不中止常駐程序；返回是否成功
+async fn run_and_report(
+    config: &SequenceConfig,
+    args: &Args,
+    execution_id: &str,
+    mode: &str,
+) -> bool {
+    let started_at = std::time::Instant::now();
+    match run_sequence(config, args, execution_id).await {
+        Ok(outcome) => {
+            let exit_code = match &outcome {
+                Ok(results) => {
+                    if let Err(e) = report_success(config, results, execution_id).await {
+                        tracing::error!("❌ Failed to report run {}: {}", execution_id, e);
+                    }
+                    0
+                }
+                Err(e) => {
+                    eprintln!("❌ {} run {} failed: {}", mode, execution_id, e);
+                    report_failure(config, args, execution_id, e);
+                    e.exit_code()
+                }
+            };
+            write_run_report(
+                config,
+                args,
+                execution_id,
+                &outcome,
+                exit_code,
+                started_at.elapsed(),
+            );
+            outcome.is_ok()
         }
         Err(e) => {
-            tracing::error!("❌ Pipeline sequence failed: {}", e);
-            eprintln!("❌ Pipeline sequence failed: {}", e);
+            tracing::error!("❌ {} run {} could not start: {}", mode, execution_id, e);
+            false
+        }
+    }
+}
+
+/// 佇列模式：每則訊息執行一次序列，直到收到 Ctrl+C
+///
+/// 訊息的變數覆寫 --var 後重新載入設定。失敗的訊息在 retry_delay 後重新排入，
+/// 再次取得時以相同的 execution_id 從失敗的 Pipeline 續跑；執行 max_attempts 次仍失敗就刪除。
+async fn run_queue(
+    args: &Args,
+    queue_config: &QueueConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let queue = MessageQueue::from_config(queue_config).await?;
+    let poll_interval = queue_config.poll_interval();
+    tracing::info!(
+        "📬 Consuming {} queue {} (max {} attempts per message)",
+        queue_config.r#type,
+        queue_config.url,
+        queue_config.max_attempts()
+    );
+
+    loop {
+        let polled_at = std::time::Instant::now();
+        let received = tokio::select! {
+            received = queue.receive(poll_interval) => received,
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("🛑 Queue consumer stopped");
+                return Ok(());
+            }
+        };
+        let messages = received.unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Failed to receive queue messages: {}", e);
+            Vec::new()
+        });
+
+        if messages.is_empty() {
+            // 不支援長輪詢的佇列會立即返回，補足等待時間以免空轉
+            let remaining = poll_interval.saturating_sub(polled_at.elapsed());
+            tokio::select! {
+                _ = tokio::time::sleep(remaining) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("🛑 Queue consumer stopped");
+                    return Ok(());
+                }
+            }
+            continue;
+        }
+
+        for message in &messages {
+            handle_queue_message(args, &queue, queue_config, message).await;
+            if SHUTDOWN.is_requested() {
+                // 同一批尚未處理的訊息會在可見逾時後重新出現
+                tracing::info!("🛑 Queue consumer stopped");
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+        }
+    }
+}
+
+/// 執行一則佇列訊息，依結果刪除或重新排入；無法解析的訊息直接刪除
+async fn handle_queue_message(
+    args: &Args,
+    queue: &MessageQueue,
+    queue_config: &QueueConfig,
+    message: &QueueMessage,
+) {
+    let request = match message.run_request() {
+        Ok(request) => request,
+        Err(e) => {
+            tracing::error!("❌ Dropping queue message {}: {}", message.id, e);
+            if let Err(e) = queue.ack(message).await {
+                tracing::warn!("⚠️ Failed to delete queue message {}: {}", message.id, e);
+            }
+            return;
+        }
+    };
+    let execution_id = message.execution_id(&request);
 
-            // 根據錯誤處理配置決定處理方式
-            if let Some(error_config) = &config.error_handling {
-                match error_config.on_pipeline_failure.as_deref() {
-                    Some("continue") => {
-                        tracing::info!("⚠️ Continuing despite failure (configured behavior)");
+    let mut variables = args.variables();
+    variables.extend(request.variables.clone());
+    let config = match SequenceConfig::from_file_with_variables(&args.config, &variables)
+        .and_then(|config| config.validate().map(|_| config))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            // 設定錯誤重試也不會成功
+            tracing::error!(
+                "❌ Dropping queue message {}: sequence config is invalid with its variables: {}",
+                message.id,
+                e
+            );
+            if let Err(e) = queue.ack(message).await {
+                tracing::warn!("⚠️ Failed to delete queue message {}: {}", message.id, e);
+            }
+            return;
+        }
+    };
+    configure_redaction(&[&config]);
+
+    // 重新排入的訊息從上次失敗的 Pipeline 續跑
+    let resume = (message.attempts > 1)
+        .then(|| execution_id.clone())
+        .filter(|id| state_store(&config, None).path(id).exists());
+    let names = |names: &[String], fallback: &Option<String>| {
+        if names.is_empty() {
+            fallback.clone()
+        } else {
+            Some(names.join(","))
+        }
+    };
+    let run_args = Args {
+        only: names(&request.only, &args.only),
+        skip: names(&request.skip, &args.skip),
+        resume: resume.clone(),
+        ..args.clone()
+    };
+
+    tracing::info!(
+        "📬 Message {} (attempt {}/{}) {} as {}",
+        message.id,
+        message.attempts,
+        queue_config.max_attempts(),
+        if resume.is_some() {
+            "resuming"
+        } else {
+            "running"
+        },
+        execution_id
+    );
+    let succeeded = run_and_report(&config, &run_args, &execution_id, "Queue").await;
+
+    let updated = if succeeded {
+        queue.ack(message).await
+    } else if SHUTDOWN.is_requested() {
+        // 中斷的執行立即放回佇列，下次取得時續跑
+        queue.retry(message, std::time::Duration::ZERO).await
+    } else if message.attempts < queue_config.max_attempts() {
+        tracing::warn!(
+            "🔁 Requeueing message {} in {:?}",
+            message.id,
+            queue_config.retry_delay()
+        );
+        queue.retry(message, queue_config.retry_delay()).await
+    } else {
+        tracing::error!(
+            "❌ Message {} failed {} times, removing it from the queue (execution {})",
+            message.id,
+            message.attempts,
+            execution_id
+        );
+        queue.ack(message).await
+    };
+    if let Err(e) = updated {
+        tracing::warn!("⚠️ Failed to update queue message {}: {}", message.id, e);
+    }
+}
+
+/// 監看模式：files 來源的 glob 出現新檔案時執行序列，直到收到 Ctrl+C
+///
+/// 連續的檔案事件在 debounce 期間內合併為一次執行；是否有新檔案以各 Pipeline 的
+/// source.files.ledger 判斷，已處理的檔案不會重複觸發。
+async fn run_watch(config: &SequenceConfig, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let mut sources = Vec::new();
+    for pipeline in &config.pipelines {
+        let Some(files) = pipeline
+            .source
+            .files
+            .as_ref()
+            .filter(|_| pipeline.source.r#type == "files")
+        else {
+            continue;
+        };
+        let ledger = files
+            .ledger
+            .as_deref()
+            .ok_or_else(|| EtlError::ConfigValidationError {
+                field: format!("pipelines.{}.source.files.ledger", pipeline.name),
+                message: "--watch requires a ledger so each file is processed once".to_string(),
+            })?;
+        sources.push((files.pattern.as_str(), ledger));
+    }
+    if sources.is_empty() {
+        return Err(EtlError::ConfigValidationError {
+            field: "pipelines.source.type".to_string(),
+            message: "--watch requires at least one pipeline with source type \"files\""
+                .to_string(),
+        }
+        .into());
+    }
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.kind.is_create() || event.kind.is_modify() => {
+                let _ = sender.send(());
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("👀 File watch error: {}", e),
+        })?;
+    for (pattern, _) in &sources {
+        let dir = file_glob::base_dir(pattern);
+        watcher.watch(Path::new(dir), notify::RecursiveMode::Recursive)?;
+        tracing::info!("👀 Watching {} for '{}'", dir, pattern);
+    }
+    let debounce = std::time::Duration::from_millis(args.watch_debounce_ms);
+
+    // 啟動時先處理停止期間新增的檔案
+    let mut check_now = true;
+    loop {
+        if !check_now {
+            tokio::select! {
+                event = receiver.recv() => {
+                    if event.is_none() {
                         return Ok(());
                     }
-                    Some("retry") => {
-                        tracing::info!("🔄 Retry logic would be implemented here");
-                        // 這裡可以實作重試邏輯
-                    }
-                    _ => {
-                        // 預設是停止
-                        std::process::exit(1);
-                    }
                 }
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("🛑 Watcher stopped");
+                    return Ok(());
+                }
+            }
+            // 檔案仍在寫入時事件會持續出現，等到安靜一段時間再執行
+            loop {
+                tokio::select! {
+                    Some(()) = receiver.recv() => {}
+                    _ = tokio::time::sleep(debounce) => break,
+                }
+            }
+        }
+        check_now = false;
+
+        let mut new_files = 0;
+        for (pattern, ledger) in &sources {
+            match file_glob::expand(pattern)
+                .and_then(|paths| FileLedger::load(ledger)?.unprocessed(paths))
+            {
+                Ok(pending) => new_files += pending.len(),
+                Err(e) => tracing::warn!("👀 Could not check '{}' for new files: {}", pattern, e),
             }
+        }
+        if new_files == 0 {
+            tracing::debug!("👀 No new files to process");
+            continue;
+        }
 
-            std::process::exit(1);
+        let execution_id = generate_execution_id(args.execution_id.as_deref());
+        tracing::info!(
+            "👀 {} new files found, run {} starting",
+            new_files,
+            execution_id
+        );
+        run_and_report(config, args, &execution_id, "Watch").await;
+        if SHUTDOWN.is_requested() {
+            tracing::info!("🛑 Watcher stopped");
+            std::process::exit(INTERRUPTED_EXIT_CODE);
+        }
+    }
+}
+
+/// 序列失敗後輸出並寫入續跑報告；報告本身失敗時退回只印續跑指令
+fn report_failure(config: &SequenceConfig, args: &Args, execution_id: &str, error: &EtlError) {
+    tracing::error!(
+        "❌ ETL sequence failed: {} (Category: {}, Severity: {:?})",
+        error,
+        error.category().code(),
+        error.severity()
+    );
+    tracing::error!("💡 Recovery suggestion: {}", error.recovery_suggestion());
+    eprintln!("💡 建議: {}", error.recovery_suggestion());
+
+    let resume_command = resume_command(args, execution_id);
+    let report = state_cipher(config)
+        .map_err(|e| e.to_string())
+        .and_then(|cipher| {
+            let store = state_store(config, cipher.as_ref());
+            let state = store.load(execution_id).map_err(|e| e.to_string())?;
+            let report =
+                ResumeReport::build(&state, config, &error.to_string(), resume_command.clone());
+            let path = report.write(&store).map_err(|e| e.to_string())?;
+            Ok((report, path))
+        });
+
+    match report {
+        Ok((report, path)) => {
+            eprintln!("\n{}", report.render());
+            eprintln!("📝 Resume report written to {}", path.display());
+        }
+        Err(e) => {
+            tracing::warn!("⚠️ Could not build resume report: {}", e);
+            eprintln!(
+                "💡 Resume from the failed pipeline with: {}",
+                resume_command
+            );
+        }
+    }
+}
+
+/// 寫入機器可讀的執行報告（--run-report 或 monitoring.run_report，預設在狀態目錄）；
+/// 寫入失敗只記錄警告，不影響退出碼
+fn write_run_report(
+    config: &SequenceConfig,
+    args: &Args,
+    execution_id: &str,
+    outcome: &samll_etl::utils::error::Result<Vec<PipelineResult>>,
+    exit_code: i32,
+    duration: std::time::Duration,
+) {
+    sequence_runner::write_run_report(
+        config,
+        &args.run_options(execution_id),
+        execution_id,
+        outcome,
+        exit_code,
+        duration,
+    );
+}
+
+/// 續跑指令：沿用本次的設定檔與 Pipeline 篩選條件
+fn resume_command(args: &Args, execution_id: &str) -> String {
+    let program = std::env::args()
+        .next()
+        .unwrap_or_else(|| "sequence_etl".to_string());
+    let mut command = format!(
+        "{} --config {} --resume {}",
+        program, args.config, execution_id
+    );
+    if let Some(only) = &args.only {
+        command.push_str(&format!(" --only {}", only));
+    }
+    if let Some(skip) = &args.skip {
+        command.push_str(&format!(" --skip {}", skip));
+    }
+    command
+}
+
+/// 建立並執行一次序列；外層錯誤為設定錯誤，內層為序列執行結果
+/// 執行一次序列；期間的日誌都帶有 execution_id 與序列名稱（JSON 日誌格式下可直接篩選）
+async fn run_sequence(
+    config: &SequenceConfig,
+    args: &Args,
+    execution_id: &str,
+) -> Result<samll_etl::utils::error::Result<Vec<PipelineResult>>, Box<dyn std::error::Error>> {
+    let span = tracing::info_span!(
+        "sequence",
+        execution_id = execution_id,
+        sequence = %config.sequence.name
+    );
+    execute_sequence(config, args, execution_id)
+        .instrument(span)
+        .await
+}
+
+async fn execute_sequence(
+    config: &SequenceConfig,
+    args: &Args,
+    execution_id: &str,
+) -> Result<samll_etl::utils::error::Result<Vec<PipelineResult>>, Box<dyn std::error::Error>> {
+    let runner = SequenceRunner::new(config, &args.run_options(execution_id))?;
+    Ok(runner.run().await)
+}
+
+/// transform 子命令：略過 HTTP 來源與 ZIP 輸出，讀 stdin、寫 stdout
+async fn run_transform(
+    config_path: &str,
+    variables: &HashMap<String, String>,
+    pipeline: Option<&str>,
+    stdin_format: StreamInputFormat,
+    stdout_format: StreamOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{Read, Write};
+
+    let config = SequenceConfig::from_file_with_variables(config_path, variables)?;
+    config.validate()?;
+    configure_redaction(&[&config]);
+    let definition = stream_transform::select_pipeline(&config, pipeline)?;
+
+    let mut input = Vec::new();
+    std::io::stdin().lock().read_to_end(&mut input)?;
+    let output = stream_transform::transform_stream(
+        definition,
+        PipelineStorage::from_load_config(&definition.load)?,
+        &input,
+        stdin_format,
+        stdout_format,
+    )
+    .await?;
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(output.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// 顯示並匯出成功執行的結果
+async fn report_success(
+    config: &SequenceConfig,
+    results: &[PipelineResult],
+    execution_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::info!("🎉 Pipeline sequence completed successfully!");
+
+    // 顯示執行結果摘要
+    display_execution_results(results, execution_id);
+
+    // 匯出執行摘要
+    if let Some(monitoring) = &config.monitoring {
+        if monitoring.export_metrics.unwrap_or(false) {
+            export_execution_metrics(results, execution_id, monitoring).await?;
         }
     }
 
+    println!("✅ Pipeline sequence completed successfully!");
+    println!("🆔 Execution ID: {}", execution_id);
+    let skipped = results.iter().filter(|result| result.is_skipped()).count();
+    println!("📊 Pipelines executed: {}", results.len() - skipped);
+    if skipped > 0 {
+        println!("⏭️ Pipelines skipped: {}", skipped);
+    }
     Ok(())
 }
 
@@ -187,6 +1332,19 @@ fn display_sequence_summary(config: &SequenceConfig, args: &Args, execution_id:
         println!("  ⏭️ Skipping: {}", skip);
     }
 
+    let schedule = args.schedule.as_deref().or(config
+        .sequence
+        .schedule
+        .as_ref()
+        .map(|schedule| schedule.cron.as_str()));
+    if let Some(schedule) = schedule {
+        println!("  ⏰ Schedule: {} (UTC)", schedule);
+    }
+
+    if args.watch {
+        println!("  👀 Watching for new files");
+    }
+
     println!();
     println!("📝 Execution Order:");
     for (index, pipeline_name) in config.sequence.execution_order.iter().enumerate() {
@@ -207,32 +1365,29 @@ fn display_sequence_summary(config: &SequenceConfig, args: &Args, execution_id:
             if let Some(deps) = &pipeline.dependencies {
                 println!("     Dependencies: {}", deps.join(", "));
             }
+            if let Some(on_success) = &pipeline.on_success {
+                println!("     On success: {}", on_success);
+            }
+            if let Some(on_failure) = &pipeline.on_failure {
+                println!("     On failure: {}", on_failure);
+            }
         }
     }
+    let branch_targets = config.branch_targets();
+    if !branch_targets.is_empty() {
+        println!(
+            "  ↪️ Branch pipelines (run only when triggered): {}",
+            branch_targets.join(", ")
+        );
+    }
     println!();
 }
 
 fn determine_pipelines_to_execute<'a>(
     config: &'a SequenceConfig,
-    args: &'a Args,
+    args: &Args,
 ) -> Vec<&'a samll_etl::config::sequence_config::PipelineDefinition> {
-    let mut pipelines = config.get_enabled_pipelines();
-
-    // 處理 --only 參數
-    if let Some(only_list) = &args.only {
-        let only_names: std::collections::HashSet<&str> =
-            only_list.split(',').map(|s| s.trim()).collect();
-        pipelines.retain(|p| only_names.contains(p.name.as_str()));
-    }
-
-    // 處理 --skip 參數
-    if let Some(skip_list) = &args.skip {
-        let skip_names: std::collections::HashSet<&str> =
-            skip_list.split(',').map(|s| s.trim()).collect();
-        pipelines.retain(|p| !skip_names.contains(p.name.as_str()));
-    }
-
-    pipelines
+    args.run_options("").select_pipelines(config)
 }
 
 async fn perform_dry_run(
@@ -298,6 +1453,23 @@ async fn perform_dry_run(
     );
     println!("  Estimated total time: Variable (depends on data size and API response time)");
     println!();
+
+    let sample: Option<HashMap<String, serde_json::Value>> = match &args.sample_data {
+        Some(path) => Some(serde_json::from_str(&std::fs::read_to_string(path)?)?),
+        None => None,
+    };
+    let report = sequence_dry_run::check_pipelines(&pipelines_to_execute, sample.as_ref()).await;
+    println!("{}", report.render());
+    if let Some(path) = &args.dry_run_report {
+        report.write_json(Path::new(path))?;
+        println!("📝 Dry run report written to {}", path);
+    }
+    println!();
+
+    if report.has_errors() {
+        eprintln!("❌ Dry run found problems that would fail the run.");
+        std::process::exit(1);
+    }
     println!("✅ Dry run analysis complete.");
 
     Ok(())
@@ -310,9 +1482,11 @@ fn display_execution_results(
     println!();
     println!("📊 Execution Results Summary:");
     println!("  Execution ID: {}", execution_id);
-    println!("  Completed Pipelines: {}", results.len());
+    let skipped = results.iter().filter(|r| r.is_skipped()).count();
+    println!("  Completed Pipelines: {}", results.len() - skipped);
+    println!("  Skipped Pipelines: {}", skipped);
 
-    let total_records: usize = results.iter().map(|r| r.records.len()).sum();
+    let total_records: usize = results.iter().map(|r| r.record_count()).sum();
     let total_duration: std::time::Duration = results.iter().map(|r| r.duration).sum();
 
     println!("  Total Records Processed: {}", total_records);
@@ -321,14 +1495,49 @@ fn display_execution_results(
 
     println!("📝 Pipeline Details:");
     for (index, result) in results.iter().enumerate() {
+        if let Some(reason) = &result.skipped {
+            println!(
+                "  {}. {} - ⏭️ skipped ({})",
+                index + 1,
+                result.pipeline_name,
+                reason
+            );
+            continue;
+        }
         println!(
             "  {}. {} - {} records in {:?}",
             index + 1,
             result.pipeline_name,
-            result.records.len(),
+            result.record_count(),
             result.duration
         );
         println!("     Output: {}", result.output_path);
+
+        if let Some(throughput) = result.metadata.get("throughput") {
+            println!(
+                "     Throughput: extract {:.1} rec/s, transform {:.1} rec/s, write {:.2} MB/s",
+                throughput["extract"]["records_per_sec"]
+                    .as_f64()
+                    .unwrap_or(0.0),
+                throughput["transform"]["records_per_sec"]
+                    .as_f64()
+                    .unwrap_or(0.0),
+                throughput["write_mb_per_sec"].as_f64().unwrap_or(0.0)
+            );
+        }
+
+        let mut outputs: Vec<_> = result.outputs.iter().collect();
+        outputs.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, value) in outputs {
+            println!("     📤 {} = {}", name, value);
+        }
+
+        for warning in &result.warnings {
+            println!(
+                "     ⚠️ [{:?}] {} (x{})",
+                warning.code, warning.message, warning.count
+            );
+        }
     }
     println!();
 }
@@ -369,7 +1578,7 @@ async fn export_execution_metrics(
             );
             pipeline_data.insert(
                 "records_count".to_string(),
-                serde_json::Value::Number(result.records.len().into()),
+                serde_json::Value::Number(result.record_count().into()),
             );
             pipeline_data.insert(
                 "duration_ms".to_string(),
@@ -379,6 +1588,9 @@ async fn export_execution_metrics(
                 "output_path".to_string(),
                 serde_json::Value::String(result.output_path.clone()),
             );
+            if let Some(reason) = &result.skipped {
+                pipeline_data.insert("skipped".to_string(), serde_json::json!(reason));
+            }
 
             for (key, value) in &result.metadata {
                 pipeline_data.insert(key.clone(), value.clone());
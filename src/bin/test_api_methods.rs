@@ -4,6 +4,7 @@ use samll_etl::core::{
     contextual_pipeline::SequenceAwarePipeline, pipeline_sequence::PipelineSequence,
 };
 use samll_etl::utils::error::Result;
+use samll_etl::SequenceEngine;
 use std::path::Path;
 
 /// 基於檔案系統的存儲實現（用於測試）
@@ -78,7 +79,7 @@ async fn main() -> Result<()> {
     }
 
     // 創建 Pipeline 序列
-    let mut sequence = PipelineSequence::new("test-api-methods".to_string()).with_monitoring(true);
+    let mut sequence = PipelineSequence::new("test-api-methods".to_string());
 
     // 為每個 Pipeline 定義創建 SequenceAwarePipeline
     for pipeline_name in &config.sequence.execution_order {
@@ -97,7 +98,9 @@ async fn main() -> Result<()> {
 
     // 執行 Pipeline 序列
     println!("\n🔄 開始執行 Pipeline 序列...");
-    let results = sequence.execute_all().await?;
+    let results = SequenceEngine::new_with_monitoring(sequence, true)
+        .run()
+        .await?;
 
     // 顯示結果
     println!("\n✅ Pipeline 序列執行完成！");
@@ -107,7 +110,7 @@ async fn main() -> Result<()> {
         println!(
             "  - {}: {} 筆記錄, 耗時 {:?}",
             result.pipeline_name,
-            result.records.len(),
+            result.record_count(),
             result.duration
         );
     }
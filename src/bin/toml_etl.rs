@@ -1,7 +1,8 @@
 use clap::Parser;
 use samll_etl::config::toml_config::TomlConfig;
 use samll_etl::core::mvp_pipeline::MvpPipeline;
-use samll_etl::utils::{logger, validation::Validate};
+use samll_etl::utils::logger::{self, LogFormat};
+use samll_etl::utils::validation::Validate;
 use samll_etl::EtlEngine;
 use samll_etl::LocalStorage;
 
@@ -17,6 +18,10 @@ struct Args {
     #[arg(short, long)]
     verbose: bool,
 
+    /// Log output format: human-readable text or structured JSON lines
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
     /// Override monitoring setting from config
     #[arg(long)]
     monitor: Option<bool>,
@@ -28,6 +33,10 @@ struct Args {
     /// Dry run - show what would be processed without executing
     #[arg(long)]
     dry_run: bool,
+
+    /// Fail on unknown config keys (typos) instead of warning and ignoring them
+    #[arg(long)]
+    strict_config: bool,
 }
 
 #[tokio::main]
@@ -35,13 +44,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
     // 初始化日誌
-    logger::init_cli_logger(args.verbose);
+    logger::init_logger(args.verbose, args.log_format);
 
     tracing::info!("🚀 Starting TOML-based ETL tool ");
     tracing::info!("📁 Loading configuration from: {}", args.config);
 
     // 載入 TOML 配置
-    let mut config = match TomlConfig::from_file(&args.config) {
+    let mut config = match TomlConfig::from_file_checked(&args.config, args.strict_config) {
         Ok(config) => config,
         Err(e) => {
             eprintln!("❌ Failed to load config file '{}': {}", args.config, e);
@@ -99,9 +108,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => {
             // 記錄詳細錯誤信息
             tracing::error!(
-                "❌ ETL process failed: {} (Category: {:?}, Severity: {:?})",
+                "❌ ETL process failed: {} (Category: {}, Severity: {:?})",
                 e,
-                e.category(),
+                e.category().code(),
                 e.severity()
             );
             tracing::error!("💡 Recovery suggestion: {}", e.recovery_suggestion());
@@ -111,12 +120,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("💡 建議: {}", e.recovery_suggestion());
 
             // 根據錯誤嚴重程度決定退出碼
-            let exit_code = match e.severity() {
-                samll_etl::utils::error::ErrorSeverity::Low => 0, // 警告，但成功
-                samll_etl::utils::error::ErrorSeverity::Medium => 2, // 重試錯誤
-                samll_etl::utils::error::ErrorSeverity::High => 1, // 處理錯誤
-                samll_etl::utils::error::ErrorSeverity::Critical => 3, // 系統錯誤
-            };
+            let exit_code = e.severity().exit_code();
 
             if exit_code > 0 {
                 std::process::exit(exit_code);
@@ -28,7 +28,61 @@ impl Storage for LocalStorage {
             fs::create_dir_all(parent)?;
         }
 
-        fs::write(full_path, data)?;
+        // 先寫入暫存檔再改名，中斷時不會留下寫到一半的輸出
+        let mut partial_path = full_path.clone().into_os_string();
+        partial_path.push(".partial");
+        fs::write(&partial_path, data)?;
+        fs::rename(&partial_path, &full_path)?;
         Ok(())
     }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        Ok(Path::new(&self.base_path).join(path).try_exists()?)
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<()> {
+        let to = Path::new(&self.base_path).join(to);
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(Path::new(&self.base_path).join(from), to)?;
+        Ok(())
+    }
+
+    async fn remove(&self, path: &str) -> Result<()> {
+        match fs::remove_file(Path::new(&self.base_path).join(path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        fn walk(dir: &Path, relative: &str, files: &mut Vec<String>) -> Result<()> {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                let path = if relative.is_empty() {
+                    name
+                } else {
+                    format!("{}/{}", relative, name)
+                };
+                if entry.file_type()?.is_dir() {
+                    walk(&entry.path(), &path, files)?;
+                } else {
+                    files.push(path);
+                }
+            }
+            Ok(())
+        }
+
+        let base = Path::new(&self.base_path);
+        let mut files = Vec::new();
+        if base.is_dir() {
+            walk(base, "", &mut files)?;
+        }
+        let prefix = prefix.trim_start_matches("./");
+        files.retain(|path| path.starts_with(prefix));
+        files.sort();
+        Ok(files)
+    }
 }
@@ -1,14 +1,14 @@
 #[cfg(feature = "lambda")]
-use crate::core::{ConfigProvider, Storage};
-#[cfg(feature = "lambda")]
+use crate::core::ConfigProvider;
+#[cfg(feature = "s3")]
+use crate::core::Storage;
+#[cfg(feature = "s3")]
+use crate::utils::error::EtlError;
+#[cfg(feature = "s3")]
 use crate::utils::error::Result;
-#[cfg(feature = "lambda")]
-use aws_sdk_s3::error::ProvideErrorMetadata;
-#[cfg(feature = "lambda")]
-use aws_sdk_s3::operation::get_object::GetObjectError;
-#[cfg(feature = "lambda")]
-use aws_sdk_s3::operation::put_object::PutObjectError;
-#[cfg(feature = "lambda")]
+#[cfg(feature = "s3")]
+use aws_sdk_s3::error::DisplayErrorContext;
+#[cfg(feature = "s3")]
 use aws_sdk_s3::Client as S3Client;
 #[cfg(feature = "lambda")]
 use std::env;
@@ -155,92 +155,316 @@ fn validate_aws_region(field_name: &str, region: &str) -> crate::utils::error::R
     Ok(())
 }
 
-#[cfg(feature = "lambda")]
+#[cfg(feature = "s3")]
 #[derive(Debug, Clone)]
 pub struct S3Storage {
     client: S3Client,
     bucket: String,
+    prefix: String,
 }
 
-#[cfg(feature = "lambda")]
+#[cfg(feature = "s3")]
 impl S3Storage {
     pub fn new(client: S3Client, bucket: String) -> Self {
-        Self { client, bucket }
+        Self {
+            client,
+            bucket,
+            prefix: String::new(),
+        }
+    }
+
+    /// 所有物件鍵加上前綴（例如 `etl-output/users`）
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.trim_matches('/').to_string();
+        self
+    }
+
+    /// 存儲路徑對應的物件鍵：去掉本機路徑的 `./` 與開頭的 `/`，再加上前綴
+    pub fn key(&self, path: &str) -> String {
+        s3_key(&self.prefix, path)
+    }
+
+    /// 存儲路徑對應的 `s3://` 位置
+    pub fn uri(&self, path: &str) -> String {
+        format!("s3://{}/{}", self.bucket, self.key(path))
     }
 }
 
-#[cfg(feature = "lambda")]
+#[cfg(feature = "s3")]
+fn s3_key(prefix: &str, path: &str) -> String {
+    let path = path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>()
+        .join("/");
+    if prefix.is_empty() {
+        path
+    } else if path.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{}/{}", prefix, path)
+    }
+}
+
+#[cfg(feature = "s3")]
+fn s3_not_found(uri: &str) -> EtlError {
+    EtlError::IoError(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("S3 read '{}' failed: no such key", uri),
+    ))
+}
+
+#[cfg(feature = "s3")]
+fn s3_error(operation: &str, uri: &str, error: impl std::fmt::Display) -> EtlError {
+    EtlError::IoError(std::io::Error::other(format!(
+        "S3 {} '{}' failed: {}",
+        operation, uri, error
+    )))
+}
+
+/// 解析 `s3://bucket/key` 為 (bucket, key)
+#[cfg(feature = "s3")]
+pub fn parse_s3_uri(uri: &str) -> Result<(String, String)> {
+    uri.strip_prefix("s3://")
+        .and_then(|rest| rest.split_once('/'))
+        .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+        .map(|(bucket, key)| (bucket.to_string(), key.to_string()))
+        .ok_or_else(|| crate::utils::error::EtlError::InvalidConfigValueError {
+            field: "sequence_config_s3".to_string(),
+            value: uri.to_string(),
+            reason: "Expected s3://bucket/key".to_string(),
+        })
+}
+
+#[cfg(feature = "s3")]
 impl Storage for S3Storage {
     async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
-        let resp = self
+        let uri = self.uri(path);
+        let resp = match self
             .client
             .get_object()
             .bucket(&self.bucket)
-            .key(path)
+            .key(self.key(path))
             .send()
             .await
-            .map_err(|e| crate::utils::error::EtlError::ConfigError {
-                message: format!("Failed to read from S3: {}", e),
-            })?;
+        {
+            Ok(resp) => resp,
+            // 物件不存在時保留 NotFound，讓呼叫端與其他讀取錯誤區分
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => {
+                return Err(s3_not_found(&uri));
+            }
+            Err(e) if e.raw_response().map(|r| r.status().as_u16()) == Some(404) => {
+                return Err(s3_not_found(&uri));
+            }
+            Err(e) => return Err(s3_error("read", &uri, DisplayErrorContext(e))),
+        };
 
-        let data =
-            resp.body
-                .collect()
-                .await
-                .map_err(|e| crate::utils::error::EtlError::ConfigError {
-                    message: format!("Failed to collect S3 data: {}", e),
-                })?;
+        let data = resp
+            .body
+            .collect()
+            .await
+            .map_err(|e| s3_error("read", &uri, e))?;
 
         Ok(data.into_bytes().to_vec())
     }
 
     async fn write_file(&self, path: &str, data: &[u8]) -> Result<()> {
-        let result = self
-            .client
+        let uri = self.uri(path);
+        self.client
             .put_object()
             .bucket(&self.bucket)
-            .key(path)
+            .key(self.key(path))
             .body(data.to_vec().into())
             .send()
-            .await;
-        // .map_err(|e| crate::utils::error::EtlError::ConfigError {
-        //     message: format!("Failed to write to S3: {}", e),
-        // })?;
-
-        match result {
-            Ok(_output) => { /* Success. Do something with the output. */ }
-            Err(err) => match err.into_service_error() {
-                // GetObjectError::InvalidObjectState(value) => {
-                //     println!("invalid object state: {:?}", value);
-                // }
-                // GetObjectError::NoSuchKey(_) => {
-                //     println!("object didn't exist");
-                // }
-                // // err.code() returns the raw error code from the service and can be
-                // //     used as a last resort for handling unmodeled service errors.
-                // err if err.code() == Some("SomeUnmodeledError") => {}
-                // err => return Err(err.into()),
-                PutObjectError::EncryptionTypeMismatch(e) => {
-                    println!("encryption type mismatch: {:?}", e);
-                }
-                PutObjectError::InvalidRequest(e) => {
-                    println!("invalid request: {:?}", e);
-                }
-                PutObjectError::InvalidWriteOffset(e) => {
-                    println!("invalid write offset: {:?}", e);
-                }
-                PutObjectError::TooManyParts(e) => {
-                    println!("too many parts: {:?}", e);
-                }
-                PutObjectError::Unhandled(e) => {
-                    println!("unhandled error: {:?}", e);
-                }
-                err => {
-                    println!("{:?}", err);
-                }
-            },
-        };
+            .await
+            .map_err(|e| s3_error("write", &uri, DisplayErrorContext(e)))?;
+        tracing::debug!("📤 Uploaded {} bytes to {}", data.len(), uri);
+        Ok(())
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        // HeadObject 只取中繼資料；暫時性錯誤回報為錯誤，不當成「不存在」
+        let uri = self.uri(path);
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(e) if e.raw_response().map(|r| r.status().as_u16()) == Some(404) => Ok(false),
+            Err(e) => Err(s3_error("stat", &uri, DisplayErrorContext(e))),
+        }
+    }
 
+    async fn rename(&self, from: &str, to: &str) -> Result<()> {
+        // S3 沒有改名，以複製後刪除原物件實作；複製來源需 URL 編碼
+        let source = format!("{}/{}", self.bucket, self.key(from))
+            .split('/')
+            .map(|segment| url::form_urlencoded::byte_serialize(segment.as_bytes()).collect())
+            .collect::<Vec<String>>()
+            .join("/");
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .copy_source(source)
+            .key(self.key(to))
+            .send()
+            .await
+            .map_err(|e| crate::utils::error::EtlError::ConfigError {
+                message: format!("Failed to copy {} on S3: {}", self.uri(from), e),
+            })?;
+        self.remove(from).await
+    }
+
+    async fn remove(&self, path: &str) -> Result<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(self.key(path))
+            .send()
+            .await
+            .map_err(|e| crate::utils::error::EtlError::ConfigError {
+                message: format!("Failed to delete {} from S3: {}", self.uri(path), e),
+            })?;
         Ok(())
     }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        // 物件鍵去掉存儲前綴後即為存儲路徑
+        let root = self.key("");
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(self.key(prefix))
+            .into_paginator()
+            .send();
+        let mut paths = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| crate::utils::error::EtlError::ConfigError {
+                message: format!("Failed to list {} on S3: {}", self.uri(prefix), e),
+            })?;
+            paths.extend(page.contents().iter().filter_map(|object| {
+                let key = object.key()?;
+                let path = key.strip_prefix(&root)?.trim_start_matches('/');
+                (!path.is_empty() && !key.ends_with('/')).then(|| path.to_string())
+            }));
+        }
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+#[cfg(all(test, feature = "s3"))]
+mod tests {
+    use super::*;
+    use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
+    use httpmock::prelude::*;
+
+    /// 指向模擬端點的 S3Storage（path-style，不重試）
+    fn mock_storage(server: &MockServer) -> S3Storage {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new("key", "secret", None, None, "test"))
+            .endpoint_url(server.base_url())
+            .force_path_style(true)
+            .retry_config(aws_sdk_s3::config::retry::RetryConfig::disabled())
+            .build();
+        S3Storage::new(S3Client::from_conf(config), "bucket".to_string()).with_prefix("etl")
+    }
+
+    #[tokio::test]
+    async fn test_missing_object_is_not_found() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/bucket/etl/state.json");
+            then.status(404).body(
+                "<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message></Error>",
+            );
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::HEAD)
+                .path("/bucket/etl/state.json");
+            then.status(404);
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/bucket/etl/denied.json");
+            then.status(403)
+                .body("<Error><Code>AccessDenied</Code><Message>Access Denied</Message></Error>");
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::HEAD)
+                .path("/bucket/etl/denied.json");
+            then.status(503);
+        });
+        let storage = mock_storage(&server);
+
+        assert!(storage
+            .read_file("state.json")
+            .await
+            .unwrap_err()
+            .is_not_found());
+        assert!(!storage.exists("state.json").await.unwrap());
+        // 其他錯誤不當成不存在
+        let error = storage.read_file("denied.json").await.unwrap_err();
+        assert!(!error.is_not_found());
+        assert!(error.to_string().contains("AccessDenied"));
+        assert!(storage.exists("denied.json").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_put_is_an_error() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(PUT).path("/bucket/etl/out/users.json");
+            then.status(500).body(
+                "<Error><Code>InternalError</Code><Message>We encountered an internal error.</Message></Error>",
+            );
+        });
+        server.mock(|when, then| {
+            when.method(PUT).path("/bucket/etl/out/orders.json");
+            then.status(200);
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::HEAD)
+                .path("/bucket/etl/out/orders.json");
+            then.status(200);
+        });
+        let storage = mock_storage(&server);
+
+        let error = storage
+            .write_file("./out/users.json", b"[]")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("s3://bucket/etl/out/users.json"));
+        storage
+            .write_file("./out/orders.json", b"[]")
+            .await
+            .unwrap();
+        assert!(storage.exists("out/orders.json").await.unwrap());
+    }
+
+    #[test]
+    fn test_s3_keys() {
+        assert_eq!(s3_key("", "./out/users.zip"), "out/users.zip");
+        assert_eq!(
+            s3_key("etl-output", "./out/users.zip"),
+            "etl-output/out/users.zip"
+        );
+        assert_eq!(
+            s3_key("etl-output", "/tmp//out/./a.json"),
+            "etl-output/tmp/out/a.json"
+        );
+        assert_eq!(
+            parse_s3_uri("s3://configs/etl/sequence.toml").unwrap(),
+            ("configs".to_string(), "etl/sequence.toml".to_string())
+        );
+        assert!(parse_s3_uri("s3://configs").is_err());
+        assert!(parse_s3_uri("configs/sequence.toml").is_err());
+    }
 }
@@ -1,6 +1,6 @@
 pub mod cli;
 
-#[cfg(feature = "lambda")]
+#[cfg(feature = "s3")]
 pub mod lambda;
 
 pub mod sequence_config;
@@ -9,6 +9,8 @@ pub mod toml_config;
 #[cfg(feature = "cli")]
 use crate::core::ConfigProvider;
 #[cfg(feature = "cli")]
+use crate::utils::logger::LogFormat;
+#[cfg(feature = "cli")]
 use clap::Parser;
 #[cfg(feature = "cli")]
 use serde::{Deserialize, Serialize};
@@ -27,14 +29,35 @@ pub struct CliConfig {
     #[arg(long, value_delimiter = ',')]
     pub lookup_files: Vec<String>,
 
+    #[arg(
+        long,
+        default_value = "id",
+        help = "Field used to join records with lookup files"
+    )]
+    pub lookup_key: String,
+
     #[arg(long, default_value = "5")]
     pub concurrent_requests: usize,
 
     #[arg(long, help = "Enable verbose output")]
     pub verbose: bool,
 
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = LogFormat::Text,
+        help = "Log output format: human-readable text or structured JSON lines"
+    )]
+    pub log_format: LogFormat,
+
     #[arg(long, help = "Enable system resource monitoring (CPU/Memory)")]
     pub monitor: bool,
+
+    #[arg(
+        long,
+        help = "Path of the machine-readable run report (default: run_report.json in output_path)"
+    )]
+    pub run_report: Option<String>,
 }
 
 #[cfg(feature = "cli")]
@@ -51,6 +74,10 @@ impl ConfigProvider for CliConfig {
         &self.lookup_files
     }
 
+    fn lookup_key(&self) -> &str {
+        &self.lookup_key
+    }
+
     fn concurrent_requests(&self) -> usize {
         self.concurrent_requests
     }
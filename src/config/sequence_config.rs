@@ -1,28 +1,177 @@
+use crate::app::pipelines::shared_data::SharedDataPolicy;
+use crate::core::aggregation::Aggregator;
+use crate::core::context_index::LookupReference;
+use crate::core::field_transforms::{CastType, FieldTransformer};
+use crate::core::filename_template;
+use crate::core::intermediate_output::{self, IntermediateFormat};
+use crate::core::null_policy::NullPolicy;
+use crate::core::output_archive::ArchiveFormat;
+use crate::core::output_encryption::EncryptionMethod;
+use crate::core::output_manifest::ManifestLocation;
+use crate::core::partitioned_output::PartitionLayout;
+use crate::core::pipeline_join::JoinType;
+use crate::core::record_script::RecordScript;
+use crate::core::template_filters::validate_template;
+use crate::core::transform_steps::resolve_transform_steps;
+use crate::core::type_coercion::CoercionErrorPolicy;
+use crate::core::write_mode::WriteMode;
 use crate::core::ConfigProvider;
 use crate::utils::error::{EtlError, Result};
+use crate::utils::schedule::CronSchedule;
 use crate::utils::validation::Validate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SequenceConfig {
+    pub include: Option<Vec<String>>, // 從其他 TOML 檔載入 [[pipelines]]，路徑相對於此設定檔
     pub sequence: SequenceInfo,
+    #[serde(default)]
     pub pipelines: Vec<PipelineDefinition>,
     pub global: Option<GlobalConfig>,
     pub monitoring: Option<MonitoringConfig>,
     pub error_handling: Option<ErrorHandlingConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SequenceInfo {
     pub name: String,
     pub description: String,
     pub version: String,
-    pub execution_order: Vec<String>, // Pipeline 執行順序
+    pub execution_order: Vec<String>,     // Pipeline 執行順序
+    pub schedule: Option<ScheduleConfig>, // 常駐模式：依 cron 排程重複執行
+    pub queue: Option<QueueConfig>,       // 常駐模式：每則佇列訊息執行一次
+    pub foreach: Option<ForeachConfig>,   // 依項目清單（例如租戶）逐一執行整個序列
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 逐項執行：每個項目執行一次整個序列，項目以共享數據提供給模板，例如 {{tenant.id}}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ForeachConfig {
+    pub name: Option<String>,                  // 模板中項目的名稱，預設 "item"
+    pub items: Option<Vec<serde_json::Value>>, // 直接列出的項目
+    pub file: Option<String>,                  // 讀取項目的 JSON 陣列檔
+    pub from_pipeline: Option<String>,         // 先執行此 Pipeline 一次，以其輸出記錄為項目
+    pub key: Option<String>,                   // 項目中作為 execution_id 後綴的欄位，預設為序號
+    pub continue_on_error: Option<bool>,       // 某一項失敗時繼續執行其餘項目，預設 false
+    pub summary: Option<String>,               // 彙整報告（JSON）的路徑，可使用 {execution_id}
+}
+
+impl ForeachConfig {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("item")
+    }
+
+    pub fn continue_on_error(&self) -> bool {
+        self.continue_on_error.unwrap_or(false)
+    }
+
+    pub fn validate(&self, pipelines: &[PipelineDefinition]) -> Result<()> {
+        let name = self.name();
+        if name.is_empty()
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            || matches!(name, "var" | "checkpoint" | "lookup")
+            || crate::core::builtin_templates::is_builtin(name)
+        {
+            return Err(EtlError::InvalidConfigValueError {
+                field: "sequence.foreach.name".to_string(),
+                value: name.to_string(),
+                reason: "Use letters, digits and underscores, and not a reserved placeholder name"
+                    .to_string(),
+            });
+        }
+        let sources = [
+            self.items.is_some(),
+            self.file.is_some(),
+            self.from_pipeline.is_some(),
+        ];
+        if sources.iter().filter(|set| **set).count() != 1 {
+            return Err(EtlError::ConfigValidationError {
+                field: "sequence.foreach".to_string(),
+                message: "Set exactly one of items, file or from_pipeline".to_string(),
+            });
+        }
+        if let Some(from_pipeline) = &self.from_pipeline {
+            if !pipelines.iter().any(|p| &p.name == from_pipeline) {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: "sequence.foreach.from_pipeline".to_string(),
+                    value: from_pipeline.clone(),
+                    reason: "Pipeline not found in pipelines definition".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 佇列觸發設定：每則訊息攜帶一次執行的變數與 Pipeline 篩選
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueueConfig {
+    pub r#type: String,                           // "sqs" 或 "http"
+    pub url: String,                              // SQS 佇列 URL，或 HTTP 佇列的端點
+    pub region: Option<String>,                   // SQS 區域，預設取自 AWS 設定
+    pub headers: Option<HashMap<String, String>>, // HTTP 佇列的請求標頭（例如認證）
+    pub max_attempts: Option<u32>,                // 每則訊息最多執行次數，預設 3
+    pub retry_delay_seconds: Option<u64>,         // 失敗後重新排入的延遲，預設 60
+    pub poll_interval_seconds: Option<u64>,       // 沒有訊息時的等待（SQS 長輪詢上限 20），預設 20
+}
+
+impl QueueConfig {
+    pub const SUPPORTED_TYPES: [&'static str; 2] = ["sqs", "http"];
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts.unwrap_or(3)
+    }
+
+    pub fn retry_delay(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.retry_delay_seconds.unwrap_or(60))
+    }
+
+    pub fn poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.poll_interval_seconds.unwrap_or(20))
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if !Self::SUPPORTED_TYPES.contains(&self.r#type.as_str()) {
+            return Err(EtlError::InvalidConfigValueError {
+                field: format!("{}.type", field),
+                value: self.r#type.clone(),
+                reason: format!("Supported types: {}", Self::SUPPORTED_TYPES.join(", ")),
+            });
+        }
+        crate::utils::validation::validate_url(&format!("{}.url", field), &self.url)?;
+        if let Some(max_attempts) = self.max_attempts {
+            crate::utils::validation::validate_positive_number(
+                &format!("{}.max_attempts", field),
+                max_attempts as usize,
+                1,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 常駐排程設定
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    pub cron: String,               // 五欄位 cron 表達式（UTC），例如 "0 */6 * * *"
+    pub run_on_start: Option<bool>, // 啟動時先執行一次，預設 false
+}
+
+impl ScheduleConfig {
+    pub fn run_on_start(&self) -> bool {
+        self.run_on_start.unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct PipelineDefinition {
     pub name: String,
     pub description: Option<String>,
@@ -33,11 +182,169 @@ pub struct PipelineDefinition {
     pub load: LoadConfig,
     pub dependencies: Option<Vec<String>>, // 依賴的其他 Pipeline
     pub conditions: Option<ExecutionConditions>, // 執行條件
+    pub checkpoint: Option<CheckpointConfig>, // 增量擷取 checkpoint
+    pub outputs: Option<HashMap<String, String>>, // 輸出變數，例如 { last_run_max_id = "max(id)" }
+    pub dead_letter: Option<DeadLetterConfig>, // 失敗記錄改寫入 rejects 檔而非中止
+    pub context_index: Option<ContextIndexConfig>, // 供模板 {{lookup:...}} 逐筆查找先前結果
+    pub on_success: Option<String>,        // 成功後接著執行的 Pipeline（只在被觸發時執行）
+    pub on_failure: Option<String>,        // 失敗後執行的 Pipeline（例如清理），序列仍以失敗結束
+    pub skip_on_empty_input: Option<bool>, // 擷取結果為空時略過 transform/load，結果中記為 skipped
+    pub quality: Option<QualityConfig>,    // transform 後評估的資料品質規則
+    pub reconciliation: Option<ReconciliationConfig>, // 各階段記錄數對帳
+    pub profile: Option<ProfileConfig>,    // 統計輸出記錄的欄位，寫入輸出檔的 profile.json
+}
+
+/// 欄位統計（筆數、不重複值、null 比例、最小／最大值、平均、最常見值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    pub fields: Option<Vec<String>>, // 只統計這些欄位，預設為所有欄位
+    pub top_k: Option<usize>,        // 列出的最常見值數量，預設 5
+}
+
+impl ProfileConfig {
+    pub fn top_k(&self) -> usize {
+        self.top_k.unwrap_or(crate::core::profiling::DEFAULT_TOP_K)
+    }
+}
+
+/// 比對 extract、transform、load 的記錄數，找出未回報的遺失記錄
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ReconciliationConfig {
+    pub tolerance: Option<f64>, // 允許的差異比例（相對於前一階段），預設 0
+    pub on_mismatch: Option<String>, // "warn"（預設）或 "fail"
+}
+
+impl ReconciliationConfig {
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance.unwrap_or(0.0)
+    }
+
+    pub fn policy(&self) -> Result<crate::core::reconciliation::MismatchPolicy> {
+        crate::core::reconciliation::MismatchPolicy::parse(
+            self.on_mismatch.as_deref().unwrap_or("warn"),
+        )
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.tolerance()) {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.tolerance", field),
+                message: "Tolerance must be between 0 and 1".to_string(),
+            });
+        }
+        self.policy()?;
+        Ok(())
+    }
+}
+
+/// 資料品質規則：transform 後評估，結果寫入 quality_report.json
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QualityConfig {
+    pub rules: Vec<QualityRuleConfig>,
+    pub report_file: Option<String>, // 輸出檔中的報告檔名，預設 "quality_report.json"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QualityRuleConfig {
+    pub check: String, // 例如 "null_rate(email) < 0.05"、"unique(id)"、"min(price) >= 0"
+    pub name: Option<String>,
+    pub severity: Option<String>, // "error"（預設，中止 Pipeline）或 "warn"（記錄警告）
+}
+
+impl QualityConfig {
+    pub fn report_file(&self) -> &str {
+        self.report_file
+            .as_deref()
+            .unwrap_or(crate::core::quality_rules::REPORT_FILE_NAME)
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if self.rules.is_empty() {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.rules", field),
+                message: "At least one quality rule is required".to_string(),
+            });
+        }
+        for (index, rule) in self.rules.iter().enumerate() {
+            crate::core::quality_rules::QualityRule::parse(rule).map_err(|e| {
+                EtlError::ConfigValidationError {
+                    field: format!("{}.rules[{}]", field, index),
+                    message: e.to_string(),
+                }
+            })?;
+        }
+        if let Some(report_file) = &self.report_file {
+            crate::utils::validation::validate_path(
+                &format!("{}.report_file", field),
+                report_file,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 以指定欄位索引先前 Pipeline 的結果，模板中以
+/// `{{lookup:PIPELINE:KEY=SOURCE_FIELD:FIELD}}` 取出對應記錄的欄位
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContextIndexConfig {
+    pub pipeline: String,
+    pub key: String,
+}
+
+/// Dead-letter 設定：範本替換或參數化 API 呼叫失敗的記錄寫入 rejects 檔
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeadLetterConfig {
+    pub enabled: Option<bool>,
+    pub path: Option<String>, // 相對於 output_path，預設 "{pipeline_name}_rejects.json"
+    pub max_rejects: Option<usize>, // 超過時中止 Pipeline
+}
+
+impl DeadLetterConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn path(&self, pipeline_name: &str) -> String {
+        self.path
+            .as_deref()
+            .unwrap_or("{pipeline_name}_rejects.json")
+            .replace("{pipeline_name}", pipeline_name)
+    }
+}
+
+/// 增量擷取的 checkpoint 設定
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CheckpointConfig {
+    pub enabled: Option<bool>,
+    pub watermarks: HashMap<String, String>, // checkpoint key -> 記錄欄位（取最大值）
+    pub initial_values: Option<HashMap<String, serde_json::Value>>, // 首次執行的預設值
+    pub state_file: Option<String>,          // 狀態檔路徑，預設 ".checkpoints/{pipeline_name}.json"
+}
+
+impl CheckpointConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn state_file(&self, pipeline_name: &str) -> String {
+        self.state_file
+            .as_deref()
+            .unwrap_or(".checkpoints/{pipeline_name}.json")
+            .replace("{pipeline_name}", pipeline_name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SourceConfig {
-    pub r#type: String,
+    pub r#type: String, // "api"、"view"（重新輸出 data_source.from_pipeline 的結果，不重新擷取）、"join"（合併兩個 Pipeline 的輸出）、"combined"（依序聯集多個 Pipeline 的輸出）、"files"（讀取 glob 比對的本機檔案）、"s3"（讀取 S3 前綴下的物件）或 "stdin"（只供 transform 子命令）
     pub endpoint: Option<String>,
     pub method: Option<String>,
     pub timeout_seconds: Option<u64>,
@@ -47,33 +354,843 @@ pub struct SourceConfig {
     pub parameters: Option<HashMap<String, String>>,
     pub payload: Option<PayloadConfig>,  // API 請求負載設定
     pub data_source: Option<DataSource>, // 數據來源設定
+    pub batch_parameters: Option<BatchParameterConfig>, // 將多個參數值合併到單一 URL
+    pub rate_limit: Option<RateLimitConfig>, // 此 Pipeline 專用的請求速率限制
+    pub auth: Option<AuthConfig>,        // 內建認證（自動取得並快取 token）
+    pub http: Option<HttpConfig>, // HTTP 用戶端設定（代理、TLS、連線池、逾時、重新導向、HTTP/2）
+    pub encoding: Option<EncodingConfig>, // 來源字元編碼轉換
+    pub response_format: Option<String>, // 回應格式："json"（預設）或 "xml"
+    pub join: Option<JoinConfig>, // type = "join" 時合併的兩個上游 Pipeline
+    pub combined: Option<CombinedConfig>, // type = "combined" 時聯集的上游 Pipeline
+    pub audit: Option<bool>, // 記錄每個請求與回應狀態，寫入輸出檔的 http_audit.jsonl（憑證已遮蔽）
+    pub follow_links: Option<FollowLinksConfig>, // 依回應中的下一頁 URL 持續請求，合併所有頁面的記錄
+    pub on_record_error: Option<String>, // 參數化呼叫單筆失敗時："fail"、"skip" 或 "dead_letter"；預設依 dead_letter 是否啟用
+    pub files: Option<FilesSourceConfig>, // type = "files" 時讀取的檔案
+    pub s3: Option<S3SourceConfig>,      // type = "s3" 時讀取的 bucket 與前綴
+    pub fan_out_checkpoint_every: Option<usize>, // 參數化呼叫每完成 N 次保存一次進度，以 --resume 重新執行時略過已完成的呼叫
+    pub response_metadata: Option<ResponseMetadataConfig>, // 擷取回應狀態碼、標頭與延遲，供追蹤參數化呼叫
+    pub conditional: Option<ConditionalRequestConfig>, // 保存 ETag / Last-Modified，下次以條件式 GET 請求
+}
+
+/// 條件式請求：保存每個端點的 ETag / Last-Modified，下次帶上 If-None-Match / If-Modified-Since
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ConditionalRequestConfig {
+    pub enabled: Option<bool>,           // 預設 true
+    pub on_not_modified: Option<String>, // 收到 304 時："reuse"（預設，使用上次的回應內容）或 "skip"（略過 Pipeline）
+}
+
+impl ConditionalRequestConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn on_not_modified(&self) -> Result<crate::core::conditional_requests::NotModifiedPolicy> {
+        crate::core::conditional_requests::NotModifiedPolicy::parse(
+            self.on_not_modified.as_deref().unwrap_or("reuse"),
+        )
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 回應資訊（狀態碼、選取的標頭、延遲）加到記錄欄位或 Pipeline metadata
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseMetadataConfig {
+    pub headers: Option<Vec<String>>, // 要擷取的回應標頭，例如 ["ETag", "X-Request-Id"]
+    pub target: Option<String>,       // "record"（預設，加到每筆記錄）或 "metadata"
+    pub prefix: Option<String>,       // 記錄欄位前綴，預設 "_http_"
+}
+
+impl ResponseMetadataConfig {
+    pub fn header_names(&self) -> &[String] {
+        self.headers.as_deref().unwrap_or_default()
+    }
+
+    pub fn target(&self) -> Result<crate::core::response_metadata::ResponseMetadataTarget> {
+        crate::core::response_metadata::ResponseMetadataTarget::parse(
+            self.target.as_deref().unwrap_or("record"),
+        )
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.prefix
+            .as_deref()
+            .unwrap_or(crate::core::response_metadata::DEFAULT_PREFIX)
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        self.target()?;
+        for name in self.header_names() {
+            if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("{}.headers", field),
+                    value: name.clone(),
+                    reason: "Not a valid HTTP header name".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SourceConfig {
+    pub const RESPONSE_FORMATS: [&'static str; 2] = ["json", "xml"];
+
+    pub fn response_format(&self) -> &str {
+        self.response_format.as_deref().unwrap_or("json")
+    }
+
+    pub fn is_audited(&self) -> bool {
+        self.audit.unwrap_or(false)
+    }
+
+    /// 請求是否帶有認證資訊（auth 區塊或 Authorization 標頭）
+    pub fn carries_credentials(&self) -> bool {
+        self.auth.is_some()
+            || self.headers.as_ref().is_some_and(|headers| {
+                headers
+                    .keys()
+                    .any(|key| key.eq_ignore_ascii_case("authorization"))
+            })
+    }
+}
+
+/// HTTP 用戶端設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+    pub max_redirects: Option<usize>, // 最大重新導向次數，0 表示不追隨，預設 10
+    pub allow_cross_host_auth_redirects: Option<bool>, // 帶認證的請求是否允許跨主機導向，預設 false
+    pub http2_prior_knowledge: Option<bool>, // 直接使用 HTTP/2（不經協商）
+    pub http2_adaptive_window: Option<bool>, // HTTP/2 自適應流量控制視窗
+    pub proxy: Option<String>,        // 代理伺服器 URL，例如 "http://proxy.internal:3128"
+    pub ca_cert: Option<String>,      // 額外信任的 CA 憑證（PEM 檔路徑）
+    pub verify_tls: Option<bool>,     // 驗證伺服器憑證，預設 true；僅在測試環境關閉
+    pub pool_max_idle_per_host: Option<usize>, // 每個主機保留的閒置連線數上限
+    pub user_agent: Option<String>,   // User-Agent 標頭
+    pub connect_timeout_seconds: Option<u64>, // 建立連線的逾時
+    pub read_timeout_seconds: Option<u64>, // 讀取回應的逾時（每次讀取之間）
+}
+
+impl HttpConfig {
+    pub fn max_redirects(&self) -> usize {
+        self.max_redirects.unwrap_or(10)
+    }
+
+    pub fn allow_cross_host_auth_redirects(&self) -> bool {
+        self.allow_cross_host_auth_redirects.unwrap_or(false)
+    }
+
+    pub fn verify_tls(&self) -> bool {
+        self.verify_tls.unwrap_or(true)
+    }
+
+    pub fn connect_timeout(&self) -> Option<std::time::Duration> {
+        self.connect_timeout_seconds
+            .map(std::time::Duration::from_secs)
+    }
+
+    pub fn read_timeout(&self) -> Option<std::time::Duration> {
+        self.read_timeout_seconds
+            .map(std::time::Duration::from_secs)
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if let Some(proxy) = &self.proxy {
+            reqwest::Proxy::all(proxy).map_err(|e| EtlError::InvalidConfigValueError {
+                field: format!("{}.proxy", field),
+                value: proxy.clone(),
+                reason: format!("Invalid proxy URL: {}", e),
+            })?;
+        }
+        if let Some(ca_cert) = &self.ca_cert {
+            crate::adapters::http::client::load_certificate(ca_cert).map_err(|e| {
+                EtlError::InvalidConfigValueError {
+                    field: format!("{}.ca_cert", field),
+                    value: ca_cert.clone(),
+                    reason: e.to_string(),
+                }
+            })?;
+        }
+        if let Some(user_agent) = &self.user_agent {
+            if reqwest::header::HeaderValue::from_str(user_agent).is_err() {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("{}.user_agent", field),
+                    value: user_agent.clone(),
+                    reason: "Not a valid header value".to_string(),
+                });
+            }
+        }
+        for (name, value) in [
+            ("connect_timeout_seconds", self.connect_timeout_seconds),
+            ("read_timeout_seconds", self.read_timeout_seconds),
+        ] {
+            if let Some(value) = value {
+                crate::utils::validation::validate_positive_number(
+                    &format!("{}.{}", field, name),
+                    value as usize,
+                    1,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 來源字元編碼設定，回應內容會轉為 UTF-8 後再解析
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EncodingConfig {
+    pub charset: Option<String>, // 例如 "windows-1252"、"latin1"；未設定時依 Content-Type，否則 UTF-8
+    pub strict: Option<bool>,    // 遇到無效位元組序列時失敗，預設 false（以替換字元取代）
+}
+
+impl EncodingConfig {
+    pub fn is_strict(&self) -> bool {
+        self.strict.unwrap_or(false)
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if let Some(charset) = &self.charset {
+            crate::utils::encoding::lookup(charset).map_err(|_| {
+                EtlError::InvalidConfigValueError {
+                    field: format!("{}.charset", field),
+                    value: charset.clone(),
+                    reason: "Unknown character encoding".to_string(),
+                }
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// 下一頁 URL 分頁：持續請求回應中的下一頁連結直到沒有為止
+///
+/// 後續頁面以 GET 與相同標頭請求，不再附加 parameters 與 payload（下一頁 URL 通常已包含）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FollowLinksConfig {
+    pub next_path: Option<String>, // 回應中下一頁 URL 的路徑，預設 "paging.next"
+    pub next_header: Option<String>, // 改從標頭讀取，例如 "Link"（取 rel="next"）或 "X-Next-Page"
+    pub records_path: Option<String>, // 每頁記錄所在的路徑，例如 "data"；未設定時為整個回應
+    pub max_pages: Option<usize>,  // 頁數上限，預設 1000
+}
+
+impl FollowLinksConfig {
+    pub fn next_path(&self) -> &str {
+        self.next_path.as_deref().unwrap_or("paging.next")
+    }
+
+    pub fn max_pages(&self) -> usize {
+        self.max_pages.unwrap_or(1000)
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        crate::utils::validation::validate_non_empty_string(
+            &format!("{}.next_path", field),
+            self.next_path(),
+        )?;
+        if let Some(header) = &self.next_header {
+            reqwest::header::HeaderName::from_bytes(header.as_bytes()).map_err(|_| {
+                EtlError::InvalidConfigValueError {
+                    field: format!("{}.next_header", field),
+                    value: header.clone(),
+                    reason: "Invalid HTTP header name".to_string(),
+                }
+            })?;
+        }
+        crate::utils::validation::validate_positive_number(
+            &format!("{}.max_pages", field),
+            self.max_pages(),
+            1,
+        )
+    }
+}
+
+/// 檔案批次來源（type = "files"）：每個比對到的檔案各自解析後套用欄位映射，並加上檔名欄位
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FilesSourceConfig {
+    pub pattern: String,        // glob，例如 "input/**/*.csv"；`**/` 比對零或多層目錄
+    pub format: Option<String>, // "csv" 或 "json"（陣列、單一物件或 JSON Lines）；預設依副檔名，.csv 以外視為 json
+    pub filename_field: Option<String>, // 記錄中存放來源檔案路徑的欄位，預設 "source_file"
+    pub concurrency: Option<usize>, // 同時讀取與解析的檔案數，預設 1
+    pub ledger: Option<String>, // 已處理檔案清單（JSON）的路徑；設定後只讀取新檔案或內容已變更的檔案
+}
+
+impl FilesSourceConfig {
+    pub const FORMATS: [&'static str; 2] = ["csv", "json"];
+
+    pub fn filename_field(&self) -> &str {
+        self.filename_field.as_deref().unwrap_or("source_file")
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency.unwrap_or(1)
+    }
+
+    /// 檔案的解析格式：format 未設定時依副檔名判斷
+    pub fn format_for(&self, path: &Path) -> &str {
+        match self.format.as_deref() {
+            Some(format) => format,
+            None if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) =>
+            {
+                "csv"
+            }
+            None => "json",
+        }
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        crate::utils::validation::validate_non_empty_string(
+            &format!("{}.pattern", field),
+            &self.pattern,
+        )?;
+        if let Some(format) = &self.format {
+            if !Self::FORMATS.contains(&format.as_str()) {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("{}.format", field),
+                    value: format.clone(),
+                    reason: format!("Supported formats: {}", Self::FORMATS.join(", ")),
+                });
+            }
+        }
+        crate::utils::validation::validate_non_empty_string(
+            &format!("{}.filename_field", field),
+            self.filename_field(),
+        )?;
+        if let Some(ledger) = &self.ledger {
+            crate::utils::validation::validate_path(&format!("{}.ledger", field), ledger)?;
+        }
+        crate::utils::validation::validate_positive_number(
+            &format!("{}.concurrency", field),
+            self.concurrency(),
+            1,
+        )
+    }
+}
+
+/// 讀取 S3 前綴下的物件作為來源（source.type = "s3"，需以 --features s3 編譯）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct S3SourceConfig {
+    pub bucket: String,
+    pub prefix: Option<String>, // 物件鍵前綴，例如 "landing/orders/"；未設定時讀取整個 bucket
+    pub region: Option<String>, // 未設定時使用 AWS 預設設定
+    pub endpoint_url: Option<String>, // S3 相容服務（MinIO、LocalStack）的端點，使用 path-style 位址
+    pub format: Option<String>,       // "csv"、"json" 或 "ndjson"；預設依副檔名，無法判斷的物件略過
+    pub key_field: Option<String>,    // 記錄中存放物件鍵的欄位，預設 "source_key"
+}
+
+impl S3SourceConfig {
+    pub const FORMATS: [&'static str; 3] = ["csv", "json", "ndjson"];
+
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or("")
+    }
+
+    pub fn key_field(&self) -> &str {
+        self.key_field.as_deref().unwrap_or("source_key")
+    }
+
+    /// 物件的解析格式：format 未設定時依副檔名判斷，無法判斷時返回 None
+    pub fn format_for(&self, key: &str) -> Option<&str> {
+        if let Some(format) = &self.format {
+            return Some(format);
+        }
+        let extension = Path::new(key).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some("csv"),
+            "json" => Some("json"),
+            "ndjson" | "jsonl" => Some("ndjson"),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        crate::utils::validation::validate_non_empty_string(
+            &format!("{}.bucket", field),
+            &self.bucket,
+        )?;
+        if let Some(format) = &self.format {
+            if !Self::FORMATS.contains(&format.as_str()) {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("{}.format", field),
+                    value: format.clone(),
+                    reason: format!("Supported formats: {}", Self::FORMATS.join(", ")),
+                });
+            }
+        }
+        if let Some(endpoint_url) = &self.endpoint_url {
+            crate::utils::validation::validate_url(
+                &format!("{}.endpoint_url", field),
+                endpoint_url,
+            )?;
+        }
+        crate::utils::validation::validate_non_empty_string(
+            &format!("{}.key_field", field),
+            self.key_field(),
+        )?;
+        if !cfg!(feature = "s3") {
+            return Err(EtlError::ConfigValidationError {
+                field: field.to_string(),
+                message: "S3 sources require building with --features s3".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 來源認證設定：OAuth2 client credentials（type = "oauth2"）、HTTP Basic（"basic"）
+/// 或 API 金鑰（"api_key"）；basic 與 api_key 的憑證在發送請求時從環境變數讀取
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AuthConfig {
+    pub r#type: String,
+    pub token_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub scopes: Option<Vec<String>>,
+    pub username_env: Option<String>, // basic：存放使用者名稱的環境變數
+    pub password_env: Option<String>, // basic：存放密碼的環境變數
+    pub key_env: Option<String>,      // api_key：存放金鑰的環境變數
+    pub placement: Option<String>,    // api_key：放在 "header"（預設）或 "query"
+    pub name: Option<String>, // api_key：標頭名稱（預設 X-API-Key）或查詢參數名稱（預設 api_key）
+}
+
+impl AuthConfig {
+    pub const SUPPORTED_TYPES: [&'static str; 3] = ["oauth2", "basic", "api_key"];
+    pub const API_KEY_PLACEMENTS: [&'static str; 2] = ["header", "query"];
+
+    pub fn placement(&self) -> &str {
+        self.placement.as_deref().unwrap_or("header")
+    }
+
+    /// API 金鑰的標頭或查詢參數名稱
+    pub fn key_name(&self) -> &str {
+        match (self.name.as_deref(), self.placement()) {
+            (Some(name), _) => name,
+            (None, "query") => "api_key",
+            (None, _) => "X-API-Key",
+        }
+    }
+
+    /// 需要遮蔽的憑證值：client_secret，以及目前環境中 basic 密碼與 API 金鑰的值
+    pub fn secret_values(&self) -> Vec<String> {
+        let from_env = [&self.password_env, &self.key_env]
+            .into_iter()
+            .flatten()
+            .filter_map(|name| std::env::var(name).ok());
+        self.client_secret
+            .iter()
+            .cloned()
+            .chain(from_env)
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        let required = |name: &str, value: &Option<String>| {
+            crate::utils::validation::validate_required_field(&format!("{}.{}", field, name), value)
+                .map(|_| ())
+        };
+        match self.r#type.as_str() {
+            "oauth2" => {
+                let token_url = crate::utils::validation::validate_required_field(
+                    &format!("{}.token_url", field),
+                    &self.token_url,
+                )?;
+                crate::utils::validation::validate_url(&format!("{}.token_url", field), token_url)?;
+                required("client_id", &self.client_id)?;
+                required("client_secret", &self.client_secret)
+            }
+            "basic" => {
+                required("username_env", &self.username_env)?;
+                required("password_env", &self.password_env)
+            }
+            "api_key" => {
+                required("key_env", &self.key_env)?;
+                if !Self::API_KEY_PLACEMENTS.contains(&self.placement()) {
+                    return Err(EtlError::InvalidConfigValueError {
+                        field: format!("{}.placement", field),
+                        value: self.placement().to_string(),
+                        reason: format!(
+                            "Supported placements: {}",
+                            Self::API_KEY_PLACEMENTS.join(", ")
+                        ),
+                    });
+                }
+                if let Some(name) = &self.name {
+                    crate::utils::validation::validate_non_empty_string(
+                        &format!("{}.name", field),
+                        name,
+                    )?;
+                }
+                Ok(())
+            }
+            other => Err(EtlError::InvalidConfigValueError {
+                field: format!("{}.type", field),
+                value: other.to_string(),
+                reason: format!("Supported types: {}", Self::SUPPORTED_TYPES.join(", ")),
+            }),
+        }
+    }
+}
+
+/// Token bucket 速率限制設定
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub requests_per_second: f64, // 每秒補充的請求數
+    pub burst: Option<u32>,       // 可累積的最大請求數，預設為每秒請求數（至少 1）
+}
+
+impl RateLimitConfig {
+    pub fn burst(&self) -> u32 {
+        self.burst
+            .unwrap_or_else(|| self.requests_per_second.ceil() as u32)
+            .max(1)
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if self.requests_per_second.is_nan() || self.requests_per_second <= 0.0 {
+            return Err(EtlError::InvalidConfigValueError {
+                field: format!("{}.requests_per_second", field),
+                value: self.requests_per_second.to_string(),
+                reason: "Must be greater than 0".to_string(),
+            });
+        }
+        if let Some(burst) = self.burst {
+            crate::utils::validation::validate_positive_number(
+                &format!("{}.burst", field),
+                burst as usize,
+                1,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 參數批次設定：將前一個 Pipeline 的多筆值合併為清單填入端點，
+/// 並在 URL 超過長度上限時自動拆分為多次呼叫
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BatchParameterConfig {
+    pub placeholder: String,       // 端點中的佔位符名稱，例如 "ids" 對應 {ids}
+    pub field: String,             // 從前一個 Pipeline 記錄中取值的欄位
+    pub separator: Option<String>, // 值之間的分隔符，預設 ","
+    pub max_url_length: Option<usize>, // URL 最大長度，預設 2000
+}
+
+impl BatchParameterConfig {
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or(",")
+    }
+
+    pub fn max_url_length(&self) -> usize {
+        self.max_url_length.unwrap_or(2000)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct PayloadConfig {
     pub body: Option<String>,                             // JSON 字串或模板
     pub template_params: Option<HashMap<String, String>>, // 模板參數映射
     pub content_type: Option<String>,                     // Content-Type header
     pub use_previous_data_as_params: Option<bool>,        // 使用前一個 pipeline 的資料作為參數
+    pub form: Option<HashMap<String, String>>, // application/x-www-form-urlencoded 欄位，值支援模板
+    pub multipart: Option<MultipartConfig>,    // multipart/form-data 的文字欄位與檔案
+}
+
+impl PayloadConfig {
+    /// 可使用模板的內容：(設定位置, 模板)，例如 ("form.username", "{{user}}")
+    pub fn templates(&self) -> Vec<(String, &str)> {
+        let mut templates = Vec::new();
+        if let Some(body) = &self.body {
+            templates.push(("body".to_string(), body.as_str()));
+        }
+        let mut form: Vec<_> = self.form.iter().flatten().collect();
+        form.sort();
+        for (name, value) in form {
+            templates.push((format!("form.{}", name), value.as_str()));
+        }
+        if let Some(multipart) = &self.multipart {
+            let mut fields: Vec<_> = multipart.fields.iter().flatten().collect();
+            fields.sort();
+            for (name, value) in fields {
+                templates.push((format!("multipart.fields.{}", name), value.as_str()));
+            }
+            for (index, file) in multipart.files.iter().flatten().enumerate() {
+                templates.push((
+                    format!("multipart.files[{}].path", index),
+                    file.path.as_str(),
+                ));
+            }
+        }
+        templates
+    }
+
+    /// 所有可使用模板的內容（供變數替換）
+    pub fn templates_mut(&mut self) -> Vec<(String, &mut String)> {
+        let mut templates = Vec::new();
+        if let Some(body) = &mut self.body {
+            templates.push(("body".to_string(), body));
+        }
+        for (name, value) in self.form.iter_mut().flatten() {
+            templates.push((format!("form.{}", name), value));
+        }
+        if let Some(multipart) = &mut self.multipart {
+            for (name, value) in multipart.fields.iter_mut().flatten() {
+                templates.push((format!("multipart.fields.{}", name), value));
+            }
+            for (index, file) in multipart.files.iter_mut().flatten().enumerate() {
+                templates.push((format!("multipart.files[{}].path", index), &mut file.path));
+            }
+        }
+        templates
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        let bodies = [
+            self.body.is_some(),
+            self.form.is_some(),
+            self.multipart.is_some(),
+        ];
+        if bodies.iter().filter(|set| **set).count() > 1 {
+            return Err(EtlError::ConfigValidationError {
+                field: field.to_string(),
+                message: "Set only one of body, form or multipart".to_string(),
+            });
+        }
+        if self.content_type.is_some() && (self.form.is_some() || self.multipart.is_some()) {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.content_type", field),
+                message: "content_type is set automatically for form and multipart payloads"
+                    .to_string(),
+            });
+        }
+        for (index, file) in self
+            .multipart
+            .iter()
+            .flat_map(|multipart| multipart.files.iter().flatten())
+            .enumerate()
+        {
+            let field = format!("{}.multipart.files[{}]", field, index);
+            crate::utils::validation::validate_non_empty_string(
+                &format!("{}.name", field),
+                &file.name,
+            )?;
+            crate::utils::validation::validate_path(&format!("{}.path", field), &file.path)?;
+        }
+        for (location, template) in self.templates() {
+            validate_template(template, &format!("{}.{}", field, location))?;
+        }
+        Ok(())
+    }
+}
+
+/// multipart/form-data 內容；檔案從 Pipeline 的存儲（load.output_path）讀取
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MultipartConfig {
+    pub fields: Option<HashMap<String, String>>, // 文字欄位，值支援模板
+    pub files: Option<Vec<MultipartFileConfig>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MultipartFileConfig {
+    pub name: String,                 // 表單欄位名稱
+    pub path: String,                 // 相對於存儲的檔案路徑，支援模板
+    pub filename: Option<String>,     // 上傳的檔名，預設為 path 的最後一段
+    pub content_type: Option<String>, // 預設 "application/octet-stream"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DataSource {
     pub use_previous_output: Option<bool>, // 使用前一個 Pipeline 的輸出
     pub from_pipeline: Option<String>,     // 指定來源 Pipeline
     pub merge_with_api: Option<bool>,      // 是否與 API 數據合併
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 合併兩個上游 Pipeline 的輸出（source.type = "join"）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct JoinConfig {
+    pub left: String,
+    pub right: String,
+    pub on: Option<String>,           // 兩邊同名的鍵欄位
+    pub left_key: Option<String>,     // 左側鍵欄位，未設定時使用 on
+    pub right_key: Option<String>,    // 右側鍵欄位，未設定時使用 on
+    pub r#type: Option<String>,       // "inner"（預設）、"left" 或 "full"
+    pub right_prefix: Option<String>, // 右側同名欄位的前綴，預設為右側 Pipeline 名稱
+}
+
+impl JoinConfig {
+    pub fn left_key(&self) -> Option<&str> {
+        self.left_key.as_deref().or(self.on.as_deref())
+    }
+
+    pub fn right_key(&self) -> Option<&str> {
+        self.right_key.as_deref().or(self.on.as_deref())
+    }
+
+    pub fn join_type(&self, field: &str) -> Result<JoinType> {
+        JoinType::parse(self.r#type.as_deref().unwrap_or("inner"), field)
+    }
+
+    pub fn right_prefix(&self) -> &str {
+        self.right_prefix.as_deref().unwrap_or(&self.right)
+    }
+}
+
+/// 聯集多個上游 Pipeline 的輸出（source.type = "combined"）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CombinedConfig {
+    pub pipelines: Vec<String>,          // 依序合併，去重時保留先出現的記錄
+    pub dedup_keys: Option<Vec<String>>, // 鍵值相同的記錄只保留一筆
+    pub source_field: Option<String>,    // 記錄來源 Pipeline 名稱的欄位，例如 "_source"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ExtractConfig {
     pub max_records: Option<usize>,
     pub concurrent_requests: Option<usize>,
-    pub field_mapping: Option<HashMap<String, String>>,
+    pub field_mapping: Option<HashMap<String, FieldMapping>>, // 來源路徑 -> 輸出欄位名稱或 { field, type, on_error }
     pub filters: Option<HashMap<String, serde_json::Value>>,
     pub data_processing: Option<DataProcessing>,
+    pub cache: Option<ExtractCacheConfig>, // 快取完整擷取結果
+    pub data_path: Option<String>, // 回應中記錄所在的路徑，例如 "data.items"，預設為回應本身
+    pub records_from_object_keys: Option<bool>, // 將單一物件的每個鍵轉為一筆記錄（例如 日期 -> 指標）
+    pub object_path: Option<String>, // 要展開的物件路徑，例如 "data.daily"，預設為回應本身
+    pub object_key_field: Option<String>, // 存放原物件鍵的欄位名稱，預設 "key"
+    pub max_response_bytes: Option<u64>, // 單一回應的大小上限，邊讀取邊檢查
+    pub on_limit_exceeded: Option<String>, // "truncate"（預設，警告並截斷）或 "fail"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ExtractConfig {
+    pub fn object_key_field(&self) -> &str {
+        self.object_key_field.as_deref().unwrap_or("key")
+    }
+
+    pub fn limit_policy(&self) -> Result<crate::core::response_limits::LimitPolicy> {
+        crate::core::response_limits::LimitPolicy::parse(
+            self.on_limit_exceeded.as_deref().unwrap_or("truncate"),
+        )
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if let Some(path) = &self.data_path {
+            if path.split('.').any(|segment| segment.trim().is_empty()) {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("{}.data_path", field),
+                    value: path.clone(),
+                    reason: "Must be a dot-separated path such as \"data.items\"".to_string(),
+                });
+            }
+        }
+        for (path, mapping) in self.field_mapping.iter().flatten() {
+            mapping.validate(&format!("{}.field_mapping.{}", field, path))?;
+        }
+        if self.max_response_bytes == Some(0) {
+            return Err(EtlError::InvalidConfigValueError {
+                field: format!("{}.max_response_bytes", field),
+                value: "0".to_string(),
+                reason: "Must be greater than 0".to_string(),
+            });
+        }
+        if self.max_records == Some(0) {
+            return Err(EtlError::InvalidConfigValueError {
+                field: format!("{}.max_records", field),
+                value: "0".to_string(),
+                reason: "Must be greater than 0".to_string(),
+            });
+        }
+        self.limit_policy().map(|_| ())
+    }
+}
+
+/// extract.field_mapping 的值：輸出欄位名稱，或帶型別轉換的設定
+/// `{ field = "age", type = "int" }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum FieldMapping {
+    Rename(String),
+    Typed(TypedFieldMapping),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TypedFieldMapping {
+    pub field: String,
+    pub r#type: Option<String>, // "string"、"int"、"float"、"bool" 或 "date"，未設定時不轉型
+    pub date_format: Option<String>, // type = "date" 的來源格式（chrono 格式）
+    pub on_error: Option<String>, // 無法轉型時："null"（預設，警告）、"keep"（保留原值，警告）或 "fail"
+}
+
+impl FieldMapping {
+    /// 輸出欄位名稱
+    pub fn target(&self) -> &str {
+        match self {
+            Self::Rename(field) => field,
+            Self::Typed(typed) => &typed.field,
+        }
+    }
+
+    /// 目標型別與失敗處理方式（未指定型別時為 None）
+    pub fn coercion(&self, field: &str) -> Result<Option<(CastType, CoercionErrorPolicy)>> {
+        let Self::Typed(typed) = self else {
+            return Ok(None);
+        };
+        let Some(cast) = &typed.r#type else {
+            return Ok(None);
+        };
+        let cast = CastType::parse(cast, &format!("{}.type", field))?;
+        let policy = CoercionErrorPolicy::parse(
+            typed.on_error.as_deref().unwrap_or("null"),
+            &format!("{}.on_error", field),
+        )?;
+        Ok(Some((cast, policy)))
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        if self.target().trim().is_empty() {
+            return Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: self.target().to_string(),
+                reason: "Target field name must not be empty".to_string(),
+            });
+        }
+        self.coercion(field).map(|_| ())
+    }
+}
+
+/// 擷取結果快取設定，以已解析的端點與參數作為快取鍵
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExtractCacheConfig {
+    pub enabled: Option<bool>,    // 預設 true
+    pub ttl_minutes: Option<u64>, // 預設 60 分鐘
+}
+
+impl ExtractCacheConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn ttl_minutes(&self) -> u64 {
+        self.ttl_minutes.unwrap_or(60)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DataProcessing {
     pub deduplicate: Option<bool>,
     pub deduplicate_fields: Option<Vec<String>>,
@@ -81,15 +1198,68 @@ pub struct DataProcessing {
     pub sort_order: Option<String>, // "asc" or "desc"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TransformConfig {
     pub operations: Option<TransformOperations>,
     pub validation: Option<ValidationConfig>,
     pub intermediate: Option<IntermediateConfig>,
     pub data_enrichment: Option<DataEnrichment>,
+    pub field_transforms: Option<HashMap<String, FieldTransformConfig>>, // 欄位名稱 -> 欄位層級轉換
+    pub record_timeout_ms: Option<u64>, // 單筆欄位轉換的時間上限，逾時的記錄寫入 dead-letter
+    pub aggregation: Option<AggregationConfig>, // 轉換完成後分組彙總，輸出每組一筆摘要記錄
+    pub script: Option<ScriptConfig>, // 以 Rhai 腳本逐筆轉換記錄（需以 --features scripting 建置）
+}
+
+/// 記錄轉換腳本：腳本需定義 `fn transform(record)`，返回轉換後的記錄，返回 `()` 則略過該筆
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptConfig {
+    pub path: Option<String>,        // .rhai 腳本檔案路徑
+    pub code: Option<String>,        // 直接寫在設定中的腳本，與 path 擇一
+    pub max_operations: Option<u64>, // 每筆記錄的運算上限，避免無窮迴圈，預設 1000000
+}
+
+impl ScriptConfig {
+    pub fn max_operations(&self) -> u64 {
+        self.max_operations.unwrap_or(1_000_000)
+    }
+}
+
+/// 彙總設定，例如 group_by = ["userId"]、aggregates = { posts = "count", total = "sum(amount)" }
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AggregationConfig {
+    pub group_by: Option<Vec<String>>, // 未設定時所有記錄彙總為一筆
+    pub aggregates: HashMap<String, String>, // 輸出欄位 -> count、count(欄位)、sum/avg/min/max(欄位)
+}
+
+impl TransformConfig {
+    pub fn record_timeout(&self) -> Option<std::time::Duration> {
+        self.record_timeout_ms.map(std::time::Duration::from_millis)
+    }
+}
+
+/// 單一欄位的轉換，依序套用：default → regex_replace → cast → rename
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FieldTransformConfig {
+    pub rename: Option<String>,
+    pub cast: Option<String>, // "string"、"int"、"float"、"bool" 或 "date"（輸出 YYYY-MM-DD）
+    pub date_format: Option<String>, // cast = "date" 的來源格式（chrono 格式），未設定時自動辨識常見格式
+    pub default: Option<serde_json::Value>, // 欄位缺少或為 null 時使用的值
+    pub regex_replace: Option<RegexReplaceConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RegexReplaceConfig {
+    pub pattern: String,
+    pub replacement: String, // 可使用 $1、${name} 參照擷取群組
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TransformOperations {
     pub clean_text: Option<bool>,
     pub trim_whitespace: Option<bool>,
@@ -97,46 +1267,469 @@ pub struct TransformOperations {
     pub normalize_fields: Option<Vec<String>>,
     pub keep_only_fields: Option<Vec<String>>, // 只保留指定的欄位，移除其他所有欄位
     pub exclude_fields: Option<Vec<String>>,   // 排除指定的欄位，保留其他欄位
+    pub custom: Option<Vec<String>>, // 以 register_transform_step 註冊的自訂轉換步驟，依序執行
+    pub mask_fields: Option<Vec<String>>, // 值改為 "****"
+    pub hash_fields: Option<Vec<String>>, // 值改為加 hash_salt 的 SHA-256（十六進位）
+    pub hash_salt: Option<String>, // 雜湊與代碼化的 salt（未設定時會產生警告），建議使用 "${PII_SALT}"
+    pub tokenize_fields: Option<Vec<String>>, // 值改為 tok_ 代碼，對照表存於 .tokens/{pipeline}_tokens.json
+}
+
+impl TransformOperations {
+    /// 個資欄位不可為空白，同一欄位只能使用一種處理方式
+    pub fn validate_pii(&self, field: &str) -> Result<()> {
+        let mut seen = HashMap::new();
+        for (option, fields) in [
+            ("mask_fields", &self.mask_fields),
+            ("hash_fields", &self.hash_fields),
+            ("tokenize_fields", &self.tokenize_fields),
+        ] {
+            for name in fields.iter().flatten() {
+                crate::utils::validation::validate_non_empty_string(
+                    &format!("{}.{}", field, option),
+                    name,
+                )?;
+                if let Some(previous) = seen.insert(name.as_str(), option) {
+                    return Err(EtlError::InvalidConfigValueError {
+                        field: format!("{}.{}", field, option),
+                        value: name.clone(),
+                        reason: format!("Field is already listed in {}", previous),
+                    });
+                }
+            }
+        }
+        let digests = self
+            .hash_fields
+            .iter()
+            .chain(&self.tokenize_fields)
+            .any(|fields| !fields.is_empty());
+        if self.hash_salt.is_some() && !digests {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.hash_salt", field),
+                message: "hash_salt requires hash_fields or tokenize_fields".to_string(),
+            });
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ValidationConfig {
     pub required_fields: Option<Vec<String>>,
     pub field_types: Option<HashMap<String, String>>,
     pub min_records: Option<usize>,
     pub max_records: Option<usize>,
+    pub on_invalid: Option<String>, // "fail"（預設）、"drop" 或 "reject"（寫入 dead-letter rejects 檔）
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct IntermediateConfig {
     pub conditions: Option<HashMap<String, serde_json::Value>>,
     pub export_to_shared: Option<bool>, // 是否導出到共享數據
     pub shared_key: Option<String>,     // 共享數據的 key
+    pub output: Option<bool>,           // 是否將中繼結果寫入輸出檔，預設 true
+    pub output_name: Option<String>,    // 中繼結果檔名（不含副檔名），預設 "intermediate"
+    pub output_format: Option<String>,  // "json"（預設）、"jsonl" 或 "csv"
+}
+
+impl IntermediateConfig {
+    pub fn output_enabled(&self) -> bool {
+        self.output.unwrap_or(true)
+    }
+
+    pub fn output_format(&self) -> Result<IntermediateFormat> {
+        IntermediateFormat::parse(
+            self.output_format.as_deref().unwrap_or("json"),
+            "transform.intermediate.output_format",
+        )
+    }
+
+    /// 輸出檔名（含副檔名）
+    pub fn output_file_name(&self) -> Result<String> {
+        Ok(intermediate_output::file_name(
+            self.output_name
+                .as_deref()
+                .unwrap_or(intermediate_output::DEFAULT_INTERMEDIATE_NAME),
+            self.output_format()?,
+        ))
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DataEnrichment {
-    pub lookup_data: Option<HashMap<String, String>>,
+    pub lookup_data: Option<HashMap<String, String>>, // 記錄欄位 -> lookup_tables 中的參照表名稱
+    pub lookup_tables: Option<HashMap<String, LookupTableConfig>>,
     pub computed_fields: Option<HashMap<String, String>>, // 計算字段
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 參照表設定（CSV/TSV/JSON），以 key 欄位與記錄關聯
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LookupTableConfig {
+    pub path: String,
+    pub key: String,
+    pub fields: Option<Vec<String>>, // 要加入記錄的欄位，預設為 key 以外的全部欄位
+    pub prefix: Option<String>,      // 新欄位名稱前綴，預設為 "{表名}_"
+    pub overwrite: Option<bool>,     // 與記錄既有欄位同名時覆寫，預設保留記錄的值並警告
+}
+
+impl LookupTableConfig {
+    pub fn prefix(&self, table_name: &str) -> String {
+        self.prefix
+            .clone()
+            .unwrap_or_else(|| format!("{}_", table_name))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct LoadConfig {
     pub output_path: String,
     pub output_formats: Vec<String>,
-    pub filename_pattern: Option<String>, // 例如: "{pipeline_name}_{timestamp}"
+    pub filename_pattern: Option<String>, // 例如: "{pipeline_name}_{timestamp}"，另支援 {date}、{sequence_name}、{record_count} 與共享數據鍵
+    pub file_pattern: Option<String>, // 輸出檔內資料檔的檔名（不含副檔名），例如: "{pipeline_name}_{format}"，另支援 {partition}
     pub compression: Option<CompressionConfig>,
     pub append_to_sequence: Option<bool>, // 是否追加到序列輸出
+    pub append: Option<AppendConfig>,     // 跨次執行持續追加的輸出檔
+    pub partition_by: Option<String>,     // 依此欄位的值分別輸出檔案（例如 country、date）
+    pub partition_layout: Option<String>, // "flat"（預設，output_US.csv）或 "hive"（country=US/part-0.csv）
+    pub max_records_per_file: Option<usize>, // 每個輸出檔的筆數上限；設定後依序分檔為 output_0001.csv、output_0002.csv…
+    pub columns: Option<Vec<String>>,        // CSV/TSV 欄位與順序；缺少的值留空
+    pub strict_columns: Option<bool>, // 記錄含 columns 以外的欄位時失敗（預設 false，略過該欄位）
+    pub sftp: Option<SftpConfig>,     // output_path 為 sftp://user@host:port/dir 時的連線與認證
+    pub infer_schema: Option<bool>,   // 由輸出記錄推斷 schema，寫入輸出檔的 schema.json
+    pub expected_schema: Option<String>, // 先前產生的 schema.json 路徑；推斷結果不一致時中止執行
+    pub csv: Option<CsvOutputConfig>, // CSV 輸出的分隔字元、引號、換行與編碼
+    pub null_policy: Option<HashMap<String, String>>, // 依輸出格式處理缺值，例如 { csv = "null", json = "fail" }
+    pub write_mode: Option<String>, // "overwrite"（預設）、"error_if_exists" 或 "version"（檔名加上 _v2、_v3…）
+    pub transactional: Option<bool>, // 所有輸出先寫入暫存檔，load 階段全部成功後才改名為正式檔名（預設 false）
+    pub manifest: Option<ManifestConfig>, // 產生列出各檔案大小、SHA-256 與記錄數的 manifest.json
+}
+
+/// 輸出清單設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestConfig {
+    pub location: Option<String>, // "inside"（預設，輸出檔中的 manifest.json）或 "alongside"（輸出檔旁的 {輸出檔名}.manifest.json）
+}
+
+impl ManifestConfig {
+    pub fn location(&self) -> Result<ManifestLocation> {
+        ManifestLocation::parse(self.location.as_deref().unwrap_or("inside"))
+    }
+}
+
+/// CSV 輸出格式（只套用於 output_formats 中的 csv）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CsvOutputConfig {
+    pub delimiter: Option<String>,   // 單一字元，預設 ","，例如 ";"、"|"
+    pub quote_style: Option<String>, // "necessary"（預設）、"always"、"non_numeric"、"never"
+    pub line_terminator: Option<String>, // "lf"（預設）或 "crlf"
+    pub bom: Option<bool>,           // 檔案開頭加上 BOM（僅 UTF-8 與 UTF-16）
+    pub encoding: Option<String>,    // "utf-8"（預設）、"utf-16le" 或其他編碼，例如 "windows-1252"
+}
+
+impl CsvOutputConfig {
+    pub fn validate(&self) -> Result<()> {
+        crate::core::csv_output::DelimitedFormat::csv(Some(self))?;
+        crate::core::csv_output::output_encoding(self)?;
+        Ok(())
+    }
+}
+
+/// SFTP 輸出的認證設定；主機、埠號與遠端目錄取自 output_path
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SftpConfig {
+    pub username: Option<String>,         // 未設定時使用 URL 中的 user@
+    pub private_key_path: Option<String>, // 金鑰認證（優先於密碼）
+    pub private_key_passphrase: Option<String>,
+    pub password: Option<String>, // 密碼認證，建議使用 "${SFTP_PASSWORD}"
+    pub known_hosts_path: Option<String>, // 驗證主機金鑰，預設 ~/.ssh/known_hosts
+    pub timeout_seconds: Option<u64>, // 連線逾時（預設 30）
+}
+
+impl SftpConfig {
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_seconds.unwrap_or(30))
+    }
+}
+
+impl LoadConfig {
+    /// load 階段支援的輸出格式
+    pub const OUTPUT_FORMATS: [&'static str; 3] = ["csv", "tsv", "json"];
+
+    fn validate_sftp(&self, field: &str) -> Result<()> {
+        use crate::adapters::storage::sftp::SftpTarget;
+
+        if !SftpTarget::is_sftp(&self.output_path) {
+            return match self.sftp {
+                Some(_) => Err(EtlError::ConfigValidationError {
+                    field: format!("{}.sftp", field),
+                    message: "sftp settings require an sftp:// output_path".to_string(),
+                }),
+                None => Ok(()),
+            };
+        }
+        let target = SftpTarget::parse(&self.output_path, &format!("{}.output_path", field))?;
+        let sftp = self.sftp.clone().unwrap_or_default();
+        if sftp.username.is_none() && target.username.is_none() {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.sftp.username", field),
+                message: "Set sftp.username or include user@ in the output_path".to_string(),
+            });
+        }
+        if sftp.private_key_path.is_none() && sftp.password.is_none() {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.sftp", field),
+                message: "Set private_key_path or password for SFTP authentication".to_string(),
+            });
+        }
+        if sftp.timeout_seconds == Some(0) {
+            return Err(EtlError::InvalidConfigValueError {
+                field: format!("{}.sftp.timeout_seconds", field),
+                value: "0".to_string(),
+                reason: "Must be at least 1".to_string(),
+            });
+        }
+        if !cfg!(feature = "sftp") {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.output_path", field),
+                message: "SFTP output requires building with --features sftp".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn partition_layout(&self) -> Result<PartitionLayout> {
+        PartitionLayout::parse(self.partition_layout.as_deref().unwrap_or("flat"))
+    }
+
+    pub fn strict_columns(&self) -> bool {
+        self.strict_columns.unwrap_or(false)
+    }
+
+    pub fn write_mode(&self) -> Result<WriteMode> {
+        WriteMode::parse(self.write_mode.as_deref().unwrap_or("overwrite"))
+    }
+
+    /// 輸出格式的缺值處理方式（預設 empty）
+    pub fn null_policy(&self, output_format: &str) -> Result<NullPolicy> {
+        self.null_policy
+            .as_ref()
+            .and_then(|policies| policies.get(output_format))
+            .map_or(Ok(NullPolicy::Empty), |policy| NullPolicy::parse(policy))
+    }
+
+    pub fn is_transactional(&self) -> bool {
+        self.transactional.unwrap_or(false)
+    }
+
+    /// 設定 expected_schema 時也需要推斷 schema
+    pub fn infers_schema(&self) -> bool {
+        self.infer_schema.unwrap_or(false) || self.expected_schema.is_some()
+    }
+
+    /// 驗證輸出格式與各格式相關選項，讓錯誤在呼叫 API 之前就被發現
+    pub fn validate(&self, field: &str) -> Result<()> {
+        crate::utils::validation::validate_path(
+            &format!("{}.output_path", field),
+            &self.output_path,
+        )?;
+        self.validate_sftp(field)?;
+        if let Some(path) = &self.expected_schema {
+            crate::utils::validation::validate_path(&format!("{}.expected_schema", field), path)?;
+        }
+
+        for format in &self.output_formats {
+            if !Self::OUTPUT_FORMATS.contains(&format.as_str()) {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("{}.output_formats", field),
+                    value: format.clone(),
+                    reason: format!(
+                        "Unsupported format. Valid formats: {}",
+                        Self::OUTPUT_FORMATS.join(", ")
+                    ),
+                });
+            }
+        }
+
+        if let Some(compression) = &self.compression {
+            compression.validate(&format!("{}.compression", field))?;
+        }
+        if let Some(csv) = &self.csv {
+            csv.validate()?;
+        }
+        if let Some(manifest) = &self.manifest {
+            manifest.location()?;
+        }
+        self.write_mode()?;
+        for format in self.null_policy.iter().flat_map(|policies| policies.keys()) {
+            if !Self::OUTPUT_FORMATS.contains(&format.as_str()) {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("{}.null_policy", field),
+                    value: format.clone(),
+                    reason: format!(
+                        "Unsupported format. Valid formats: {}",
+                        Self::OUTPUT_FORMATS.join(", ")
+                    ),
+                });
+            }
+            self.null_policy(format)?;
+        }
+
+        match &self.partition_by {
+            Some(partition_by) => crate::utils::validation::validate_non_empty_string(
+                &format!("{}.partition_by", field),
+                partition_by,
+            )?,
+            None if self.partition_layout.is_some() => {
+                return Err(EtlError::ConfigValidationError {
+                    field: format!("{}.partition_layout", field),
+                    message: "partition_layout requires partition_by".to_string(),
+                });
+            }
+            None => {}
+        }
+        self.partition_layout()?;
+
+        if let Some(pattern) = &self.filename_pattern {
+            filename_template::validate_pattern(
+                pattern,
+                &format!("{}.filename_pattern", field),
+                false,
+            )?;
+        }
+        if let Some(pattern) = &self.file_pattern {
+            filename_template::validate_pattern(pattern, &format!("{}.file_pattern", field), true)?;
+        }
+
+        if self.max_records_per_file == Some(0) {
+            return Err(EtlError::InvalidConfigValueError {
+                field: format!("{}.max_records_per_file", field),
+                value: "0".to_string(),
+                reason: "Must be at least 1".to_string(),
+            });
+        }
+
+        match &self.columns {
+            Some(columns) => {
+                if columns.is_empty() {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!("{}.columns", field),
+                        message: "columns must list at least one column".to_string(),
+                    });
+                }
+                let mut seen = std::collections::HashSet::new();
+                for column in columns {
+                    crate::utils::validation::validate_non_empty_string(
+                        &format!("{}.columns", field),
+                        column,
+                    )?;
+                    if !seen.insert(column) {
+                        return Err(EtlError::InvalidConfigValueError {
+                            field: format!("{}.columns", field),
+                            value: column.clone(),
+                            reason: "Duplicate column".to_string(),
+                        });
+                    }
+                }
+            }
+            None if self.strict_columns.is_some() => {
+                return Err(EtlError::ConfigValidationError {
+                    field: format!("{}.strict_columns", field),
+                    message: "strict_columns requires columns".to_string(),
+                });
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// 追加輸出設定（目前支援 CSV）
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AppendConfig {
+    pub path: String,                     // 相對於 output_path 的檔案路徑
+    pub schema_evolution: Option<String>, // "add_columns"（預設）、"fail" 或 "ignore"
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CompressionConfig {
+    #[serde(default)]
     pub enabled: bool,
+    #[serde(default)]
     pub filename: String,
     pub include_metadata: Option<bool>,
+    pub format: Option<String>, // "zip"（預設）、"tar.gz" 或 "none"（未壓縮目錄）
+    pub encryption: Option<OutputEncryptionConfig>, // 輸出檔加密，避免含個資的輸出以明文存放
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl CompressionConfig {
+    pub fn archive_format(&self) -> Result<ArchiveFormat> {
+        ArchiveFormat::parse(self.format.as_deref().unwrap_or("zip"))
+    }
+
+    pub fn validate(&self, field: &str) -> Result<()> {
+        let format = self.archive_format()?;
+        if let Some(encryption) = &self.encryption {
+            encryption.validate(&format!("{}.encryption", field), format)?;
+        }
+        Ok(())
+    }
+}
+
+/// 輸出檔加密設定；金鑰取自 key_file 或 key_env（擇一）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OutputEncryptionConfig {
+    pub method: Option<String>, // "zip_aes"（預設，AES-256 加密的 ZIP）或 "aes_gcm"（整個輸出檔以 AES-256-GCM 加密，加上 .enc）
+    pub key_file: Option<String>, // 金鑰（密碼）檔路徑，首尾空白會被去除
+    pub key_env: Option<String>, // 金鑰環境變數名稱
+}
+
+impl OutputEncryptionConfig {
+    pub fn method(&self) -> Result<EncryptionMethod> {
+        EncryptionMethod::parse(self.method.as_deref().unwrap_or("zip_aes"))
+    }
+
+    pub fn validate(&self, field: &str, format: ArchiveFormat) -> Result<()> {
+        let method = self.method()?;
+        match (&self.key_file, &self.key_env) {
+            (Some(_), Some(_)) | (None, None) => {
+                return Err(EtlError::ConfigValidationError {
+                    field: field.to_string(),
+                    message: "Set exactly one of key_file or key_env".to_string(),
+                });
+            }
+            (Some(path), None) => {
+                crate::utils::validation::validate_path(&format!("{}.key_file", field), path)?
+            }
+            (None, Some(key_env)) => crate::utils::validation::validate_non_empty_string(
+                &format!("{}.key_env", field),
+                key_env,
+            )?,
+        }
+        if method == EncryptionMethod::ZipAes && format != ArchiveFormat::Zip {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("{}.method", field),
+                message:
+                    "zip_aes requires compression format \"zip\"; use \"aes_gcm\" for other formats"
+                        .to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ExecutionConditions {
     pub when_previous_succeeded: Option<bool>,
     pub when_records_count: Option<RecordCountCondition>,
@@ -144,56 +1737,332 @@ pub struct ExecutionConditions {
     pub skip_if_empty: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RecordCountCondition {
     pub min: Option<usize>,
     pub max: Option<usize>,
     pub from_pipeline: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct GlobalConfig {
     pub working_directory: Option<String>,
     pub shared_variables: Option<HashMap<String, String>>,
+    pub variables: Option<HashMap<String, String>>, // 模板中的 {{var.KEY}}，可由 --var KEY=VALUE 覆寫
     pub timeout_minutes: Option<u64>,
+    pub rate_limit: Option<RateLimitConfig>, // 序列內所有 Pipeline 共享的請求速率限制
+    pub state_encryption: Option<StateEncryptionConfig>, // 狀態檔（checkpoint 等）加密
+    pub shared_data: Option<SharedDataConfig>, // 多個 Pipeline 寫入同一共享數據鍵的策略
+    pub intermediate_aggregate: Option<IntermediateAggregateConfig>, // 將各 Pipeline 的中繼結果彙整為單一檔案
+    pub context: Option<ContextMemoryConfig>, // Pipeline 上下文的記憶體上限與暫存檔
+    pub redaction: Option<RedactionConfig>,   // 日誌、指標與 metadata.json 的敏感資料遮蔽
+}
+
+/// 敏感資料遮蔽設定；名稱含 auth、token、secret、password、key 等字詞的欄位一律遮蔽
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionConfig {
+    pub sensitive_fields: Option<Vec<String>>, // 額外視為敏感的欄位名稱字詞，例如 ["ssn", "phone"]
+}
+
+impl RedactionConfig {
+    pub fn sensitive_fields(&self) -> &[String] {
+        self.sensitive_fields.as_deref().unwrap_or(&[])
+    }
+}
+
+/// Pipeline 上下文記憶體設定：記錄數超過上限的結果寫入暫存檔，需要時再讀回
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContextMemoryConfig {
+    pub max_records_in_memory: usize,
+    pub spill_dir: Option<String>, // 支援 {execution_id}，預設為狀態目錄下的 "{execution_id}.spill"
+}
+
+/// 序列中繼結果彙整設定
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IntermediateAggregateConfig {
+    pub path: String, // 彙整檔路徑，與 load.output_path 相同方式解析（本機路徑或 sftp:// URL）
+    pub format: Option<String>, // "json"（預設）、"jsonl" 或 "csv"
+}
+
+impl IntermediateAggregateConfig {
+    /// 拆成彙整檔所在目錄（作為存儲根目錄）與檔名
+    pub fn split_path(&self) -> (String, String) {
+        match self.path.rsplit_once('/') {
+            Some((directory, file_name)) if !directory.is_empty() => {
+                (directory.to_string(), file_name.to_string())
+            }
+            Some((_, file_name)) => ("/".to_string(), file_name.to_string()),
+            None => (".".to_string(), self.path.clone()),
+        }
+    }
+
+    pub fn format(&self) -> Result<IntermediateFormat> {
+        IntermediateFormat::parse(
+            self.format.as_deref().unwrap_or("json"),
+            "global.intermediate_aggregate.format",
+        )
+    }
+}
+
+/// 共享數據寫入策略設定
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SharedDataConfig {
+    pub policy: Option<String>, // "last_write_wins"、"first_write_wins" 或 "declared_producers"
+    pub producers: Option<HashMap<String, String>>, // 共享數據鍵 -> 唯一可寫入的 Pipeline
+}
+
+impl SharedDataConfig {
+    /// 寫入策略；只宣告 producers 時預設為 declared_producers，否則為 last_write_wins
+    pub fn policy(&self) -> Result<SharedDataPolicy> {
+        match (&self.policy, &self.producers) {
+            (Some(policy), _) => SharedDataPolicy::parse(policy),
+            (None, Some(_)) => Ok(SharedDataPolicy::DeclaredProducers),
+            (None, None) => Ok(SharedDataPolicy::LastWriteWins),
+        }
+    }
+
+    pub fn producers(&self) -> HashMap<String, String> {
+        self.producers.clone().unwrap_or_default()
+    }
+}
+
+/// 狀態檔加密設定，金鑰從環境變數讀取，不寫在設定檔中
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StateEncryptionConfig {
+    pub enabled: Option<bool>,           // 預設 true
+    pub key_env: Option<String>,         // 金鑰環境變數名稱，預設 ETL_STATE_KEY
+    pub migrate_plaintext: Option<bool>, // 一次性遷移：接受未加密的舊狀態檔，遷移後移除
+}
+
+impl StateEncryptionConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn key_env(&self) -> &str {
+        self.key_env
+            .as_deref()
+            .unwrap_or(crate::utils::encryption::DEFAULT_KEY_ENV)
+    }
+
+    /// 建立加密器（未啟用時返回 None）
+    pub fn cipher(&self) -> Result<Option<crate::utils::encryption::StateCipher>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        let cipher = crate::utils::encryption::StateCipher::from_env(self.key_env())?;
+        if self.migrate_plaintext.unwrap_or(false) {
+            tracing::warn!(
+                "🔐 state_encryption.migrate_plaintext is set: unencrypted state files are accepted and encrypted on next save; remove it once migrated"
+            );
+            return Ok(Some(cipher.allowing_plaintext()));
+        }
+        Ok(Some(cipher))
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct MonitoringConfig {
     pub enabled: bool,
     pub log_level: Option<String>,
     pub export_metrics: Option<bool>,
     pub metrics_file: Option<String>,
+    pub heartbeat_interval_seconds: Option<u64>, // 心跳日誌間隔，未設定時不輸出
+    pub liveness_file: Option<String>,           // 每次心跳更新的存活檔（JSON 進度快照）
+    pub progress_file: Option<String>, // 依執行事件更新的進度檔，可用 {execution_id}；預設寫在狀態目錄
+    pub metrics_address: Option<String>, // Prometheus 指標端點，例如 "0.0.0.0:9464"（需 metrics-server feature）
+    pub max_memory_mb: Option<u64>,      // 行程 RSS 上限，超過時暫停擷取並提早將結果寫入暫存檔
+    pub max_memory_pause_seconds: Option<u64>, // 每個 Pipeline 擷取時最長暫停秒數（預設 60），逾時仍超過上限則 Pipeline 失敗
+    pub run_report: Option<String>, // 結束時寫入的 run_report.json，可用 {execution_id}；預設寫在狀態目錄
+}
+
+impl MonitoringConfig {
+    /// 心跳間隔；只設定 liveness_file 時預設 60 秒
+    pub fn heartbeat_interval(&self) -> Option<std::time::Duration> {
+        self.heartbeat_interval_seconds
+            .or(self.liveness_file.as_ref().map(|_| 60))
+            .filter(|secs| *secs > 0)
+            .map(std::time::Duration::from_secs)
+    }
+
+    pub fn max_memory_pause(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.max_memory_pause_seconds.unwrap_or(60))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(max_memory_mb) = self.max_memory_mb {
+            crate::utils::validation::validate_positive_number(
+                "monitoring.max_memory_mb",
+                max_memory_mb as usize,
+                1,
+            )?;
+        }
+        if let Some(pause) = self.max_memory_pause_seconds {
+            crate::utils::validation::validate_positive_number(
+                "monitoring.max_memory_pause_seconds",
+                pause as usize,
+                1,
+            )?;
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ErrorHandlingConfig {
     pub on_pipeline_failure: Option<String>, // "stop", "continue", "retry"
-    pub retry_attempts: Option<u32>,
-    pub retry_delay_seconds: Option<u64>,
-    pub fallback_pipeline: Option<String>,
+    pub retry_attempts: Option<u32>,         // "retry" 時失敗後重新執行的次數（預設 2）
+    pub retry_delay_seconds: Option<u64>,    // 每次重試前的等待秒數（預設 10）
+    pub fallback_pipeline: Option<String>,   // 未設定 on_failure 的 Pipeline 失敗時執行
+}
+
+impl ErrorHandlingConfig {
+    pub const FAILURE_POLICIES: [&'static str; 3] = ["stop", "continue", "retry"];
+
+    /// on_pipeline_failure = "retry" 時的（重試次數, 間隔）
+    pub fn pipeline_retry(&self) -> Option<(u32, std::time::Duration)> {
+        (self.on_pipeline_failure.as_deref() == Some("retry")).then(|| {
+            (
+                self.retry_attempts.unwrap_or(2),
+                std::time::Duration::from_secs(self.retry_delay_seconds.unwrap_or(10)),
+            )
+        })
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(policy) = &self.on_pipeline_failure {
+            if !Self::FAILURE_POLICIES.contains(&policy.as_str()) {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: "error_handling.on_pipeline_failure".to_string(),
+                    value: policy.clone(),
+                    reason: format!("Supported policies: {}", Self::FAILURE_POLICIES.join(", ")),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl SequenceConfig {
     /// 從 TOML 檔案載入序列配置
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_file_with_variables(path, &HashMap::new())
+    }
+
+    /// 從 TOML 檔案載入序列配置，`overrides` 覆寫 [global.variables] 中的同名變數
+    pub fn from_file_with_variables<P: AsRef<Path>>(
+        path: P,
+        overrides: &HashMap<String, String>,
+    ) -> Result<Self> {
         let content = std::fs::read_to_string(&path).map_err(EtlError::IoError)?;
-        Self::from_toml_str(&content)
+        let base_dir = path.as_ref().parent().unwrap_or(Path::new("."));
+        Self::parse(&content, base_dir, overrides)
     }
 
     /// 從 TOML 字串解析序列配置
     pub fn from_toml_str(content: &str) -> Result<Self> {
+        Self::from_toml_str_with_variables(content, &HashMap::new())
+    }
+
+    /// 設定檔的 JSON Schema，供編輯器自動完成與檢查；未知的鍵以 additionalProperties = false 標示
+    pub fn json_schema() -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(schemars::schema_for!(SequenceConfig))?)
+    }
+
+    /// 從 TOML 字串解析序列配置，並以 [global.variables] 與 `overrides` 替換 {{var.KEY}}
+    /// include 的路徑相對於目前工作目錄。
+    pub fn from_toml_str_with_variables(
+        content: &str,
+        overrides: &HashMap<String, String>,
+    ) -> Result<Self> {
+        Self::parse(content, Path::new("."), overrides)
+    }
+
+    fn parse(content: &str, base_dir: &Path, overrides: &HashMap<String, String>) -> Result<Self> {
         // 處理環境變數替換和共享變數替換
         let processed_content = Self::substitute_all_vars(content)?;
 
-        toml::from_str(&processed_content).map_err(|e| EtlError::ConfigValidationError {
-            field: "sequence_toml_parsing".to_string(),
-            message: format!("Sequence TOML parsing error: {}", e),
-        })
+        let mut config: Self =
+            toml::from_str(&processed_content).map_err(|e| EtlError::ConfigValidationError {
+                field: "sequence_toml_parsing".to_string(),
+                message: format!("Sequence TOML parsing error: {}", e),
+            })?;
+        config.resolve_includes(base_dir)?;
+        config.apply_variables(overrides)?;
+        Ok(config)
+    }
+
+    /// 替換各 Pipeline 端點、標頭、查詢參數與 payload 中的 {{var.KEY}}
+    ///
+    /// 在解析後替換（而非替換 TOML 文字），變數值不會改變設定結構；未定義的變數視為設定錯誤。
+    fn apply_variables(&mut self, overrides: &HashMap<String, String>) -> Result<()> {
+        let mut variables = self
+            .global
+            .as_ref()
+            .and_then(|global| global.variables.clone())
+            .unwrap_or_default();
+        variables.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        let pattern = regex::Regex::new(r"\{\{\s*var\.([A-Za-z0-9_.-]+)\s*\}\}").unwrap();
+        let substitute = |template: &mut String, field: String| -> Result<()> {
+            if !template.contains("var.") {
+                return Ok(());
+            }
+            if let Some(missing) = pattern
+                .captures_iter(template)
+                .map(|caps| caps[1].to_string())
+                .find(|name| !variables.contains_key(name))
+            {
+                return Err(EtlError::ConfigValidationError {
+                    field,
+                    message: format!(
+                        "Undefined variable '{}'; set it in [global.variables] or pass --var {}=VALUE",
+                        missing, missing
+                    ),
+                });
+            }
+            *template = pattern
+                .replace_all(template, |caps: &regex::Captures| {
+                    variables[&caps[1]].clone()
+                })
+                .into_owned();
+            Ok(())
+        };
+
+        for pipeline in &mut self.pipelines {
+            let field = format!("pipelines.{}.source", pipeline.name);
+            let source = &mut pipeline.source;
+            if let Some(endpoint) = &mut source.endpoint {
+                substitute(endpoint, format!("{}.endpoint", field))?;
+            }
+            for (name, value) in source.headers.iter_mut().flatten() {
+                substitute(value, format!("{}.headers.{}", field, name))?;
+            }
+            for (name, value) in source.parameters.iter_mut().flatten() {
+                substitute(value, format!("{}.parameters.{}", field, name))?;
+            }
+            for (location, template) in source
+                .payload
+                .iter_mut()
+                .flat_map(|payload| payload.templates_mut())
+            {
+                substitute(template, format!("{}.payload.{}", field, location))?;
+            }
+        }
+        Ok(())
     }
 
     /// 替換所有變數（環境變數和共享變數）
-    fn substitute_all_vars(content: &str) -> Result<String> {
+    pub(crate) fn substitute_all_vars(content: &str) -> Result<String> {
         // 首先進行環境變數替換
         let env_substituted = Self::substitute_env_vars(content)?;
 
@@ -216,26 +2085,11 @@ impl SequenceConfig {
 
     /// 替換共享變數
     fn substitute_shared_vars(content: &str) -> Result<String> {
-        use regex::Regex;
-
         // 首先嘗試解析部分配置來獲取共享變數
         if let Ok(partial_config) = Self::parse_partial_config(content) {
             if let Some(global) = partial_config.global {
                 if let Some(shared_vars) = global.shared_variables {
-                    let re = Regex::new(r"\$\{([^}]+)\}").unwrap();
-
-                    let result = re.replace_all(content, |caps: &regex::Captures| {
-                        let var_name = &caps[1];
-                        // 首先檢查共享變數
-                        if let Some(shared_value) = shared_vars.get(var_name) {
-                            shared_value.clone()
-                        } else {
-                            // 保持原樣，可能是環境變數已經處理過或未定義
-                            format!("${{{}}}", var_name)
-                        }
-                    });
-
-                    return Ok(result.to_string());
+                    return Ok(Self::replace_shared_vars(content, &shared_vars));
                 }
             }
         }
@@ -244,6 +2098,78 @@ impl SequenceConfig {
         Ok(content.to_string())
     }
 
+    fn replace_shared_vars(content: &str, shared_vars: &HashMap<String, String>) -> String {
+        let re = regex::Regex::new(r"\$\{([^}]+)\}").unwrap();
+        re.replace_all(content, |caps: &regex::Captures| {
+            let var_name = &caps[1];
+            // 首先檢查共享變數
+            if let Some(shared_value) = shared_vars.get(var_name) {
+                shared_value.clone()
+            } else {
+                // 保持原樣，可能是環境變數已經處理過或未定義
+                format!("${{{}}}", var_name)
+            }
+        })
+        .into_owned()
+    }
+
+    /// 載入 include 列出的 Pipeline 片段（只能包含 [[pipelines]]），附加在設定檔本身的 Pipeline 之後
+    ///
+    /// 片段同樣替換環境變數與主設定的 shared_variables；Pipeline 名稱重複時報錯。
+    pub(crate) fn resolve_includes(&mut self, base_dir: &Path) -> Result<()> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct PipelineFragment {
+            #[serde(default)]
+            pipelines: Vec<PipelineDefinition>,
+        }
+
+        let Some(includes) = self.include.take() else {
+            return Ok(());
+        };
+        let shared_vars = self
+            .global
+            .as_ref()
+            .and_then(|global| global.shared_variables.clone())
+            .unwrap_or_default();
+        let mut defined_in: HashMap<String, String> = self
+            .pipelines
+            .iter()
+            .map(|pipeline| (pipeline.name.clone(), "the sequence config".to_string()))
+            .collect();
+
+        for include in includes {
+            let path = base_dir.join(&include);
+            let content =
+                std::fs::read_to_string(&path).map_err(|e| EtlError::InvalidConfigValueError {
+                    field: "include".to_string(),
+                    value: include.clone(),
+                    reason: format!("Cannot read pipeline fragment: {}", e),
+                })?;
+            let content =
+                Self::replace_shared_vars(&Self::substitute_env_vars(&content)?, &shared_vars);
+            let fragment: PipelineFragment =
+                toml::from_str(&content).map_err(|e| EtlError::ConfigValidationError {
+                    field: format!("include.{}", include),
+                    message: format!("Pipeline fragment parsing error: {}", e),
+                })?;
+
+            for pipeline in fragment.pipelines {
+                if let Some(previous) = defined_in.insert(pipeline.name.clone(), include.clone()) {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!("include.{}", include),
+                        message: format!(
+                            "Pipeline '{}' is already defined in {}",
+                            pipeline.name, previous
+                        ),
+                    });
+                }
+                self.pipelines.push(pipeline);
+            }
+        }
+        Ok(())
+    }
+
     /// 部分解析配置以獲取全域設定
     fn parse_partial_config(content: &str) -> Result<SequenceConfig> {
         // 嘗試解析整個配置，但如果失敗則返回錯誤
@@ -256,11 +2182,15 @@ impl SequenceConfig {
         if let Ok(partial) = toml::from_str::<PartialConfig>(content) {
             // 創建一個最小的有效配置
             Ok(SequenceConfig {
+                include: None,
                 sequence: SequenceInfo {
                     name: "temp".to_string(),
                     description: "temp".to_string(),
                     version: "1.0.0".to_string(),
                     execution_order: vec![],
+                    schedule: None,
+                    queue: None,
+                    foreach: None,
                 },
                 pipelines: vec![],
                 global: partial.global,
@@ -281,54 +2211,521 @@ impl SequenceConfig {
         let pipeline_names: std::collections::HashSet<String> =
             self.pipelines.iter().map(|p| p.name.clone()).collect();
 
-        for pipeline_name in &self.sequence.execution_order {
-            if !pipeline_names.contains(pipeline_name) {
+        for pipeline_name in &self.sequence.execution_order {
+            if !pipeline_names.contains(pipeline_name) {
+                return Err(EtlError::ConfigValidationError {
+                    field: "sequence.execution_order".to_string(),
+                    message: format!(
+                        "Pipeline '{}' in execution order not found in pipelines definition",
+                        pipeline_name
+                    ),
+                });
+            }
+        }
+
+        if let Some(rate_limit) = self.global.as_ref().and_then(|g| g.rate_limit.as_ref()) {
+            rate_limit.validate("global.rate_limit")?;
+        }
+
+        if let Some(schedule) = &self.sequence.schedule {
+            CronSchedule::parse(&schedule.cron)?;
+        }
+
+        if let Some(queue) = &self.sequence.queue {
+            queue.validate("sequence.queue")?;
+            if self.sequence.schedule.is_some() {
+                return Err(EtlError::ConfigValidationError {
+                    field: "sequence.queue".to_string(),
+                    message: "sequence.schedule and sequence.queue cannot be used together"
+                        .to_string(),
+                });
+            }
+        }
+
+        if let Some(foreach) = &self.sequence.foreach {
+            foreach.validate(&self.pipelines)?;
+            if self.sequence.queue.is_some() {
+                return Err(EtlError::ConfigValidationError {
+                    field: "sequence.foreach".to_string(),
+                    message: "sequence.foreach and sequence.queue cannot be used together"
+                        .to_string(),
+                });
+            }
+        }
+
+        if let Some(error_handling) = &self.error_handling {
+            error_handling.validate()?;
+        }
+
+        if let Some(monitoring) = &self.monitoring {
+            monitoring.validate()?;
+        }
+
+        if let Some(shared_data) = self
+            .global
+            .as_ref()
+            .and_then(|global| global.shared_data.as_ref())
+        {
+            shared_data.policy()?;
+            for (key, producer) in shared_data.producers.iter().flatten() {
+                if !pipeline_names.contains(producer) {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!("global.shared_data.producers.{}", key),
+                        message: format!("Producer pipeline '{}' not found", producer),
+                    });
+                }
+            }
+        }
+
+        if let Some(aggregate) = self
+            .global
+            .as_ref()
+            .and_then(|global| global.intermediate_aggregate.as_ref())
+        {
+            aggregate.format()?;
+        }
+
+        if let Some(context) = self
+            .global
+            .as_ref()
+            .and_then(|global| global.context.as_ref())
+        {
+            crate::utils::validation::validate_positive_number(
+                "global.context.max_records_in_memory",
+                context.max_records_in_memory,
+                1,
+            )?;
+        }
+
+        if let Some(redaction) = self
+            .global
+            .as_ref()
+            .and_then(|global| global.redaction.as_ref())
+        {
+            if redaction
+                .sensitive_fields()
+                .iter()
+                .any(|field| field.trim().is_empty())
+            {
+                return Err(EtlError::ConfigValidationError {
+                    field: "global.redaction.sensitive_fields".to_string(),
+                    message: "Sensitive field patterns must not be empty".to_string(),
+                });
+            }
+        }
+
+        // 驗證每個 Pipeline 的配置
+        for pipeline in &self.pipelines {
+            self.validate_pipeline(pipeline)?;
+        }
+
+        // 驗證依賴關係
+        self.validate_dependencies()?;
+        self.validate_branches()?;
+
+        Ok(())
+    }
+
+    fn validate_pipeline(&self, pipeline: &PipelineDefinition) -> Result<()> {
+        // 驗證 API 端點 (只有 api 類型需要端點)
+        if pipeline.source.r#type == "api" {
+            if let Some(endpoint) = &pipeline.source.endpoint {
+                crate::utils::validation::validate_url("source.endpoint", endpoint)?;
+            } else {
+                return Err(EtlError::ConfigValidationError {
+                    field: "source.endpoint".to_string(),
+                    message: "API source type requires an endpoint".to_string(),
+                });
+            }
+        }
+
+        // View 必須指定存在的來源 Pipeline
+        if pipeline.source.r#type == "view" {
+            let from_pipeline = pipeline
+                .source
+                .data_source
+                .as_ref()
+                .and_then(|data_source| data_source.from_pipeline.as_ref());
+            match from_pipeline {
+                Some(from) if self.pipelines.iter().any(|p| &p.name == from) => {}
+                Some(from) => {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!(
+                            "pipelines.{}.source.data_source.from_pipeline",
+                            pipeline.name
+                        ),
+                        message: format!("View source pipeline '{}' not found", from),
+                    });
+                }
+                None => {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!(
+                            "pipelines.{}.source.data_source.from_pipeline",
+                            pipeline.name
+                        ),
+                        message: "View pipelines require data_source.from_pipeline".to_string(),
+                    });
+                }
+            }
+        }
+
+        // Join 必須指定存在的左右 Pipeline 與鍵欄位
+        if pipeline.source.r#type == "join" {
+            let field = format!("pipelines.{}.source.join", pipeline.name);
+            let join =
+                pipeline
+                    .source
+                    .join
+                    .as_ref()
+                    .ok_or_else(|| EtlError::ConfigValidationError {
+                        field: field.clone(),
+                        message: "Join pipelines require a [source.join] section".to_string(),
+                    })?;
+            for upstream in [&join.left, &join.right] {
+                if !self.pipelines.iter().any(|p| &p.name == upstream) {
+                    return Err(EtlError::ConfigValidationError {
+                        field: field.clone(),
+                        message: format!("Join source pipeline '{}' not found", upstream),
+                    });
+                }
+            }
+            if join.left_key().is_none() || join.right_key().is_none() {
+                return Err(EtlError::ConfigValidationError {
+                    field: field.clone(),
+                    message: "Join requires 'on' or both 'left_key' and 'right_key'".to_string(),
+                });
+            }
+            join.join_type(&format!("{}.type", field))?;
+        }
+
+        // Combined 必須列出存在的上游 Pipeline
+        if let Some(combined) = &pipeline.source.combined {
+            let field = format!("pipelines.{}.source.combined", pipeline.name);
+            if pipeline.source.r#type != "combined" {
+                return Err(EtlError::ConfigValidationError {
+                    field,
+                    message: "[source.combined] requires source.type = \"combined\"".to_string(),
+                });
+            }
+            if combined.pipelines.is_empty() {
+                return Err(EtlError::ConfigValidationError {
+                    field: format!("{}.pipelines", field),
+                    message: "At least one upstream pipeline is required".to_string(),
+                });
+            }
+            for upstream in &combined.pipelines {
+                if !self.pipelines.iter().any(|p| &p.name == upstream) {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!("{}.pipelines", field),
+                        message: format!("Combined source pipeline '{}' not found", upstream),
+                    });
+                }
+            }
+        }
+
+        // Files 必須指定 glob
+        if pipeline.source.r#type == "files" {
+            let field = format!("pipelines.{}.source.files", pipeline.name);
+            pipeline
+                .source
+                .files
+                .as_ref()
+                .ok_or_else(|| EtlError::ConfigValidationError {
+                    field: field.clone(),
+                    message: "Files pipelines require a [source.files] section".to_string(),
+                })?
+                .validate(&field)?;
+        }
+
+        // S3 必須指定 bucket
+        if pipeline.source.r#type == "s3" {
+            let field = format!("pipelines.{}.source.s3", pipeline.name);
+            pipeline
+                .source
+                .s3
+                .as_ref()
+                .ok_or_else(|| EtlError::ConfigValidationError {
+                    field: field.clone(),
+                    message: "S3 pipelines require a [source.s3] section".to_string(),
+                })?
+                .validate(&field)?;
+        }
+
+        self.validate_context_lookups(pipeline)?;
+
+        // 驗證 header 與 payload 模板中的過濾器，例如 {{name|upper}}
+        let field = format!("pipelines.{}.source", pipeline.name);
+        for (name, template) in pipeline.source.headers.iter().flatten() {
+            validate_template(template, &format!("{}.headers.{}", field, name))?;
+        }
+        if let Some(payload) = &pipeline.source.payload {
+            payload.validate(&format!("{}.payload", field))?;
+        }
+
+        // 驗證輸出路徑、輸出格式與格式相關選項
+        pipeline
+            .load
+            .validate(&format!("pipelines.{}.load", pipeline.name))?;
+
+        pipeline
+            .extract
+            .validate(&format!("pipelines.{}.extract", pipeline.name))?;
+
+        // 驗證並發請求數
+        if let Some(concurrent) = pipeline.extract.concurrent_requests {
+            crate::utils::validation::validate_positive_number(
+                "extract.concurrent_requests",
+                concurrent,
+                1,
+            )?;
+        }
+
+        // 驗證追加輸出設定
+        if let Some(append) = &pipeline.load.append {
+            crate::utils::validation::validate_path("load.append.path", &append.path)?;
+            if let Some(policy) = &append.schema_evolution {
+                crate::core::append_output::SchemaEvolutionPolicy::parse(policy)?;
+            }
+        }
+
+        // 驗證參數批次設定
+        if let Some(batch) = &pipeline.source.batch_parameters {
+            let placeholder = format!("{{{}}}", batch.placeholder);
+            let endpoint = pipeline.source.endpoint.as_deref().unwrap_or("");
+            if !endpoint.contains(&placeholder) {
                 return Err(EtlError::ConfigValidationError {
-                    field: "sequence.execution_order".to_string(),
-                    message: format!(
-                        "Pipeline '{}' in execution order not found in pipelines definition",
-                        pipeline_name
-                    ),
+                    field: format!("pipelines.{}.source.batch_parameters", pipeline.name),
+                    message: format!("Endpoint does not contain placeholder {}", placeholder),
                 });
             }
+            crate::utils::validation::validate_positive_number(
+                "source.batch_parameters.max_url_length",
+                batch.max_url_length(),
+                endpoint.len(),
+            )?;
         }
 
-        // 驗證每個 Pipeline 的配置
-        for pipeline in &self.pipelines {
-            self.validate_pipeline(pipeline)?;
+        // 驗證速率限制設定
+        if let Some(rate_limit) = &pipeline.source.rate_limit {
+            rate_limit.validate(&format!("pipelines.{}.source.rate_limit", pipeline.name))?;
         }
 
-        // 驗證依賴關係
-        self.validate_dependencies()?;
-
-        Ok(())
-    }
+        // 驗證 HTTP 用戶端設定
+        if let Some(http) = &pipeline.source.http {
+            http.validate(&format!("pipelines.{}.source.http", pipeline.name))?;
+        }
 
-    fn validate_pipeline(&self, pipeline: &PipelineDefinition) -> Result<()> {
-        // 驗證 API 端點 (只有 api 類型需要端點)
-        if pipeline.source.r#type == "api" {
-            if let Some(endpoint) = &pipeline.source.endpoint {
-                crate::utils::validation::validate_url("source.endpoint", endpoint)?;
-            } else {
-                return Err(EtlError::ConfigValidationError {
-                    field: "source.endpoint".to_string(),
-                    message: "API source type requires an endpoint".to_string(),
+        // 驗證字元編碼設定
+        if let Some(policy) = &pipeline.source.on_record_error {
+            crate::core::dead_letter::RecordErrorPolicy::parse(policy)?;
+        }
+        if let Some(every) = pipeline.source.fan_out_checkpoint_every {
+            crate::utils::validation::validate_positive_number(
+                &format!(
+                    "pipelines.{}.source.fan_out_checkpoint_every",
+                    pipeline.name
+                ),
+                every,
+                1,
+            )?;
+        }
+        if let Some(response_metadata) = &pipeline.source.response_metadata {
+            response_metadata.validate(&format!(
+                "pipelines.{}.source.response_metadata",
+                pipeline.name
+            ))?;
+        }
+        if let Some(conditional) = &pipeline.source.conditional {
+            conditional.on_not_modified()?;
+            if conditional.is_enabled() && pipeline.source.follow_links.is_some() {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: format!("pipelines.{}.source.conditional", pipeline.name),
+                    value: "follow_links".to_string(),
+                    reason: "Conditional requests cannot be combined with follow_links".to_string(),
                 });
             }
         }
 
-        // 驗證輸出路徑
-        crate::utils::validation::validate_path("load.output_path", &pipeline.load.output_path)?;
+        if let Some(follow_links) = &pipeline.source.follow_links {
+            follow_links.validate(&format!("pipelines.{}.source.follow_links", pipeline.name))?;
+        }
+        if let Some(encoding) = &pipeline.source.encoding {
+            encoding.validate(&format!("pipelines.{}.source.encoding", pipeline.name))?;
+        }
 
-        // 驗證並發請求數
-        if let Some(concurrent) = pipeline.extract.concurrent_requests {
+        // 驗證欄位轉換設定
+        if let Some(field_transforms) = &pipeline.transform.field_transforms {
+            FieldTransformer::compile(field_transforms)?;
+        }
+        if let Some(aggregation) = &pipeline.transform.aggregation {
+            Aggregator::compile(aggregation)?;
+        }
+        if let Some(script) = &pipeline.transform.script {
+            RecordScript::compile(
+                script,
+                &format!("pipelines.{}.transform.script", pipeline.name),
+            )?;
+        }
+        if let Some(operations) = &pipeline.transform.operations {
+            operations
+                .validate_pii(&format!("pipelines.{}.transform.operations", pipeline.name))?;
+        }
+        if let Some(custom) = pipeline
+            .transform
+            .operations
+            .as_ref()
+            .and_then(|operations| operations.custom.as_ref())
+        {
+            resolve_transform_steps(
+                custom,
+                &format!("pipelines.{}.transform.operations.custom", pipeline.name),
+            )?;
+        }
+        if let Some(record_timeout_ms) = pipeline.transform.record_timeout_ms {
             crate::utils::validation::validate_positive_number(
-                "extract.concurrent_requests",
-                concurrent,
+                &format!("pipelines.{}.transform.record_timeout_ms", pipeline.name),
+                record_timeout_ms as usize,
                 1,
             )?;
         }
 
+        // 驗證中繼結果輸出格式
+        if let Some(intermediate) = &pipeline.transform.intermediate {
+            intermediate.output_format()?;
+        }
+
+        // file_pattern 在各輸出格式下的檔名不能重複，也不能覆蓋 metadata.json 等輸出檔
+        if let Some(pattern) = &pipeline.load.file_pattern {
+            let mut reserved = vec![
+                "metadata.json".to_string(),
+                crate::core::schema_inference::SCHEMA_FILE_NAME.to_string(),
+                crate::core::profiling::PROFILE_FILE_NAME.to_string(),
+                crate::core::http_audit::AUDIT_FILE_NAME.to_string(),
+                crate::core::output_manifest::MANIFEST_FILE_NAME.to_string(),
+            ];
+            if let Some(quality) = &pipeline.quality {
+                reserved.push(quality.report_file().to_string());
+            }
+            if let Some(intermediate) = &pipeline.transform.intermediate {
+                reserved.push(intermediate.output_file_name()?);
+            }
+            let formats: Vec<(&str, &str)> = pipeline
+                .load
+                .output_formats
+                .iter()
+                .filter_map(|format| match format.as_str() {
+                    "csv" => Some(("csv", ".csv")),
+                    "tsv" => Some(("tsv", ".tsv")),
+                    "json" => Some(("json", ".json")),
+                    _ => None,
+                })
+                .collect();
+            filename_template::check_unique_entries(
+                pattern,
+                &formats,
+                &reserved,
+                &format!("pipelines.{}.load.file_pattern", pipeline.name),
+            )?;
+        }
+
+        // 驗證回應格式
+        if !SourceConfig::RESPONSE_FORMATS.contains(&pipeline.source.response_format()) {
+            return Err(EtlError::InvalidConfigValueError {
+                field: format!("pipelines.{}.source.response_format", pipeline.name),
+                value: pipeline.source.response_format().to_string(),
+                reason: format!(
+                    "Supported formats: {}",
+                    SourceConfig::RESPONSE_FORMATS.join(", ")
+                ),
+            });
+        }
+
+        // 驗證認證設定
+        if let Some(auth) = &pipeline.source.auth {
+            auth.validate(&format!("pipelines.{}.source.auth", pipeline.name))?;
+        }
+
+        // 驗證輸出變數表達式
+        for expression in pipeline.outputs.iter().flat_map(|outputs| outputs.values()) {
+            crate::core::output_variables::OutputExpression::parse(expression).map_err(|e| {
+                EtlError::ConfigValidationError {
+                    field: format!("pipelines.{}.outputs", pipeline.name),
+                    message: e.to_string(),
+                }
+            })?;
+        }
+
+        // 驗證資料品質規則
+        if let Some(quality) = &pipeline.quality {
+            quality.validate(&format!("pipelines.{}.quality", pipeline.name))?;
+        }
+
+        // 驗證記錄數對帳設定
+        if let Some(reconciliation) = &pipeline.reconciliation {
+            reconciliation.validate(&format!("pipelines.{}.reconciliation", pipeline.name))?;
+        }
+
+        // 驗證記錄驗證設定
+        if let Some(validation) = &pipeline.transform.validation {
+            if let Some(policy) = &validation.on_invalid {
+                crate::core::record_validation::InvalidRecordPolicy::parse(policy)?;
+            }
+            for (field, field_type) in validation.field_types.iter().flatten() {
+                if !crate::core::record_validation::FIELD_TYPES.contains(&field_type.as_str()) {
+                    return Err(EtlError::InvalidConfigValueError {
+                        field: format!(
+                            "pipelines.{}.transform.validation.field_types.{}",
+                            pipeline.name, field
+                        ),
+                        value: field_type.clone(),
+                        reason: format!(
+                            "Supported types: {}",
+                            crate::core::record_validation::FIELD_TYPES.join(", ")
+                        ),
+                    });
+                }
+            }
+            if let (Some(min), Some(max)) = (validation.min_records, validation.max_records) {
+                if min > max {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!("pipelines.{}.transform.validation", pipeline.name),
+                        message: format!("min_records ({}) exceeds max_records ({})", min, max),
+                    });
+                }
+            }
+        }
+
+        // 驗證參照表設定
+        if let Some(enrichment) = &pipeline.transform.data_enrichment {
+            let tables = enrichment.lookup_tables.clone().unwrap_or_default();
+            for (name, table) in &tables {
+                let field = format!(
+                    "pipelines.{}.transform.data_enrichment.lookup_tables.{}",
+                    pipeline.name, name
+                );
+                crate::utils::validation::validate_path(&format!("{}.path", field), &table.path)?;
+                crate::utils::validation::validate_file_extensions(
+                    &format!("{}.path", field),
+                    std::slice::from_ref(&table.path),
+                    &["csv", "tsv", "json"],
+                )?;
+            }
+            for (join_field, table_name) in enrichment.lookup_data.iter().flatten() {
+                if !tables.contains_key(table_name) {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!(
+                            "pipelines.{}.transform.data_enrichment.lookup_data.{}",
+                            pipeline.name, join_field
+                        ),
+                        message: format!(
+                            "Lookup table '{}' is not defined in lookup_tables",
+                            table_name
+                        ),
+                    });
+                }
+            }
+        }
+
         // 驗證依賴的 Pipeline 存在
         if let Some(dependencies) = &pipeline.dependencies {
             let pipeline_names: std::collections::HashSet<String> =
@@ -347,6 +2744,110 @@ impl SequenceConfig {
         Ok(())
     }
 
+    /// 驗證 context_index 指向存在的 Pipeline，且模板中的 {{lookup:...}} 與之相符
+    fn validate_context_lookups(&self, pipeline: &PipelineDefinition) -> Result<()> {
+        let field = format!("pipelines.{}.context_index", pipeline.name);
+        if let Some(index) = &pipeline.context_index {
+            if !self.pipelines.iter().any(|p| p.name == index.pipeline) {
+                return Err(EtlError::ConfigValidationError {
+                    field,
+                    message: format!("Context index pipeline '{}' not found", index.pipeline),
+                });
+            }
+        }
+
+        let source = &pipeline.source;
+        let templates = source
+            .endpoint
+            .iter()
+            .chain(source.headers.iter().flat_map(|headers| headers.values()))
+            .map(String::as_str)
+            .chain(
+                source
+                    .payload
+                    .iter()
+                    .flat_map(|payload| payload.templates())
+                    .map(|(_, template)| template),
+            );
+        for template in templates {
+            for reference in LookupReference::find_all(template) {
+                let declared = pipeline.context_index.as_ref().is_some_and(|index| {
+                    index.pipeline == reference.pipeline && index.key == reference.key
+                });
+                if !declared {
+                    return Err(EtlError::ConfigValidationError {
+                        field,
+                        message: format!(
+                            "Template lookup on {}.{} requires context_index = {{ pipeline = \"{}\", key = \"{}\" }}",
+                            reference.pipeline, reference.key, reference.pipeline, reference.key
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 驗證分支目標存在，且 on_success 不會形成迴圈
+    fn validate_branches(&self) -> Result<()> {
+        let fallback = self
+            .error_handling
+            .as_ref()
+            .and_then(|error_handling| error_handling.fallback_pipeline.as_ref())
+            .map(|target| ("error_handling.fallback_pipeline".to_string(), target));
+        let branches = self.pipelines.iter().flat_map(|pipeline| {
+            [
+                ("on_success", &pipeline.on_success),
+                ("on_failure", &pipeline.on_failure),
+            ]
+            .into_iter()
+            .filter_map(move |(kind, target)| {
+                target.as_ref().map(|target| {
+                    if target == &pipeline.name {
+                        Err(EtlError::ConfigValidationError {
+                            field: format!("pipelines.{}.{}", pipeline.name, kind),
+                            message: "A pipeline cannot branch to itself".to_string(),
+                        })
+                    } else {
+                        Ok((format!("pipelines.{}.{}", pipeline.name, kind), target))
+                    }
+                })
+            })
+        });
+        for branch in branches.chain(fallback.map(Ok)) {
+            let (field, target) = branch?;
+            if self.get_pipeline(target).is_none() {
+                return Err(EtlError::ConfigValidationError {
+                    field,
+                    message: format!("Branch target pipeline '{}' not found", target),
+                });
+            }
+        }
+
+        for pipeline in &self.pipelines {
+            let mut chain = vec![pipeline.name.as_str()];
+            let mut next = pipeline.on_success.as_deref();
+            while let Some(name) = next {
+                if chain.contains(&name) {
+                    return Err(EtlError::ConfigValidationError {
+                        field: format!("pipelines.{}.on_success", pipeline.name),
+                        message: format!(
+                            "on_success chain loops: {} -> {}",
+                            chain.join(" -> "),
+                            name
+                        ),
+                    });
+                }
+                chain.push(name);
+                next = self
+                    .get_pipeline(name)
+                    .and_then(|target| target.on_success.as_deref());
+            }
+        }
+
+        Ok(())
+    }
+
     fn validate_dependencies(&self) -> Result<()> {
         // 檢查循環依賴
         let mut visited = std::collections::HashSet::new();
@@ -399,14 +2900,67 @@ impl SequenceConfig {
     }
 
     /// 獲取啟用的 Pipeline 列表（按執行順序）
+    ///
+    /// 未列在 execution_order 的分支目標（on_success、on_failure、fallback_pipeline）
+    /// 附加在最後，由序列執行器在被觸發時執行。
     pub fn get_enabled_pipelines(&self) -> Vec<&PipelineDefinition> {
+        let branch_targets = self.branch_targets();
         self.sequence
             .execution_order
             .iter()
+            .map(String::as_str)
+            .chain(
+                branch_targets
+                    .into_iter()
+                    .filter(|name| !self.sequence.execution_order.iter().any(|n| n == name)),
+            )
             .filter_map(|name| self.get_pipeline(name))
             .filter(|pipeline| pipeline.enabled.unwrap_or(true))
             .collect()
     }
+
+    /// 被 on_success、on_failure 或 error_handling.fallback_pipeline 指定的 Pipeline（依出現順序、不重複）
+    pub fn branch_targets(&self) -> Vec<&str> {
+        let mut targets: Vec<&str> = Vec::new();
+        let candidates = self
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| {
+                [
+                    pipeline.on_success.as_deref(),
+                    pipeline.on_failure.as_deref(),
+                ]
+            })
+            .chain([self
+                .error_handling
+                .as_ref()
+                .and_then(|error_handling| error_handling.fallback_pipeline.as_deref())])
+            .flatten();
+        for target in candidates {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        targets
+    }
+
+    /// 設定中的憑證值（敏感名稱的標頭與參數、OAuth2 client_secret），供報告遮蔽用
+    pub fn sensitive_values(&self) -> Vec<String> {
+        let mut values = Vec::new();
+        for source in self.pipelines.iter().map(|pipeline| &pipeline.source) {
+            for map in [&source.headers, &source.parameters].into_iter().flatten() {
+                values.extend(
+                    map.iter()
+                        .filter(|(name, _)| crate::utils::redact::is_sensitive_name(name))
+                        .map(|(_, value)| value.clone()),
+                );
+            }
+            if let Some(auth) = &source.auth {
+                values.extend(auth.secret_values());
+            }
+        }
+        values
+    }
 }
 
 impl ConfigProvider for PipelineDefinition {
@@ -491,6 +3045,118 @@ output_formats = ["json"]
         assert_eq!(config.sequence.execution_order.len(), 2);
     }
 
+    #[test]
+    fn test_runtime_variables() {
+        let toml_content = r#"
+[sequence]
+name = "tenants"
+description = "Per-tenant extract"
+version = "1.0.0"
+execution_order = ["orders"]
+
+[global.variables]
+tenant = "acme"
+region = "eu"
+
+[[pipelines]]
+name = "orders"
+
+[pipelines.source]
+type = "api"
+endpoint = "https://{{var.region}}.api.example.com/tenants/{{var.tenant}}/orders"
+headers = { "X-Tenant" = "{{ var.tenant }}" }
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output"
+output_formats = ["csv"]
+"#;
+
+        let config = SequenceConfig::from_toml_str(toml_content).unwrap();
+        assert_eq!(
+            config.pipelines[0].source.endpoint.as_deref(),
+            Some("https://eu.api.example.com/tenants/acme/orders")
+        );
+
+        let overrides = HashMap::from([("tenant".to_string(), "globex".to_string())]);
+        let config =
+            SequenceConfig::from_toml_str_with_variables(toml_content, &overrides).unwrap();
+        let source = &config.pipelines[0].source;
+        assert_eq!(
+            source.endpoint.as_deref(),
+            Some("https://eu.api.example.com/tenants/globex/orders")
+        );
+        assert_eq!(source.headers.as_ref().unwrap()["X-Tenant"], "globex");
+
+        let error =
+            SequenceConfig::from_toml_str(&toml_content.replace("{{var.region}}", "{{var.zone}}"))
+                .unwrap_err();
+        assert!(error.to_string().contains("Undefined variable 'zone'"));
+    }
+
+    #[test]
+    fn test_include_pipeline_fragments() {
+        let pipeline = |name: &str| {
+            format!(
+                r#"
+[[pipelines]]
+name = "{name}"
+
+[pipelines.source]
+type = "api"
+endpoint = "${{api_base}}/{name}"
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output"
+output_formats = ["csv"]
+"#
+            )
+        };
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("pipelines")).unwrap();
+        std::fs::write(dir.path().join("pipelines/users.toml"), pipeline("users")).unwrap();
+        std::fs::write(dir.path().join("pipelines/orders.toml"), pipeline("orders")).unwrap();
+        let main = r#"
+include = ["pipelines/users.toml", "pipelines/orders.toml"]
+
+[sequence]
+name = "split"
+description = "Pipelines in separate files"
+version = "1.0.0"
+execution_order = ["users", "orders"]
+
+[global.shared_variables]
+api_base = "https://api.example.com"
+"#;
+        let path = dir.path().join("sequence.toml");
+        std::fs::write(&path, main).unwrap();
+
+        let config = SequenceConfig::from_file(&path).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.pipelines.len(), 2);
+        assert_eq!(
+            config.pipelines[1].source.endpoint.as_deref(),
+            Some("https://api.example.com/orders")
+        );
+
+        std::fs::write(
+            &path,
+            main.replace("pipelines/orders.toml", "pipelines/users.toml"),
+        )
+        .unwrap();
+        let error = SequenceConfig::from_file(&path).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Pipeline 'users' is already defined in pipelines/users.toml"));
+    }
+
     #[test]
     fn test_circular_dependency_detection() {
         let toml_content = r#"
@@ -536,4 +3202,232 @@ output_formats = ["csv"]
         let config = SequenceConfig::from_toml_str(toml_content).unwrap();
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_rate_limit_config() {
+        let toml_content = r#"
+[sequence]
+name = "rate-limit-test"
+description = "Test rate limit validation"
+version = "1.0.0"
+execution_order = ["pipeline1"]
+
+[global]
+rate_limit = { requests_per_second = 2.5 }
+
+[[pipelines]]
+name = "pipeline1"
+
+[pipelines.source]
+type = "api"
+endpoint = "https://api1.example.com"
+rate_limit = { requests_per_second = 0, burst = 5 }
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output1"
+output_formats = ["csv"]
+"#;
+
+        let mut config = SequenceConfig::from_toml_str(toml_content).unwrap();
+        let global_limit = config.global.as_ref().unwrap().rate_limit.as_ref().unwrap();
+        assert_eq!(global_limit.burst(), 3);
+        assert!(config.validate().is_err());
+
+        config.pipelines[0]
+            .source
+            .rate_limit
+            .as_mut()
+            .unwrap()
+            .requests_per_second = 10.0;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_branch_targets_validated_and_appended() {
+        let pipeline = |name: &str| {
+            format!(
+                r#"
+[[pipelines]]
+name = "{name}"
+
+[pipelines.source]
+type = "api"
+endpoint = "https://api.example.com/{name}"
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output"
+output_formats = ["json"]
+"#
+            )
+        };
+        let toml_content = format!(
+            r#"
+[sequence]
+name = "branch-test"
+description = "Test branches"
+version = "1.0.0"
+execution_order = ["extract", "report"]
+
+[error_handling]
+fallback_pipeline = "alert"
+{}{}{}{}"#,
+            pipeline("extract"),
+            pipeline("report"),
+            pipeline("cleanup"),
+            pipeline("alert")
+        );
+
+        let mut config = SequenceConfig::from_toml_str(&toml_content).unwrap();
+        config.pipelines[0].on_failure = Some("cleanup".to_string());
+        config.validate().unwrap();
+        let enabled: Vec<&str> = config
+            .get_enabled_pipelines()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(enabled, vec!["extract", "report", "cleanup", "alert"]);
+
+        config.pipelines[0].on_success = Some("missing".to_string());
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("pipelines.extract.on_success"));
+
+        config.pipelines[0].on_success = Some("report".to_string());
+        config.pipelines[1].on_success = Some("extract".to_string());
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("extract -> report -> extract"));
+    }
+
+    #[test]
+    fn test_json_schema_describes_config() {
+        let schema = SequenceConfig::json_schema().unwrap();
+        assert_eq!(schema["title"], "SequenceConfig");
+        assert_eq!(schema["additionalProperties"], false);
+        let extract = &schema["$defs"]["ExtractConfig"];
+        assert_eq!(extract["additionalProperties"], false);
+        assert!(extract["properties"]["data_path"].is_object());
+        assert!(schema["$defs"]["TransformOperations"]["properties"]["exclude_fields"].is_object());
+    }
+
+    #[test]
+    fn test_output_formats_validated_up_front() {
+        let toml_content = r#"
+[sequence]
+name = "output-format-test"
+description = "Test output format validation"
+version = "1.0.0"
+execution_order = ["pipeline1"]
+
+[[pipelines]]
+name = "pipeline1"
+
+[pipelines.source]
+type = "api"
+endpoint = "https://api1.example.com"
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output1"
+output_formats = ["csv", "parquet"]
+"#;
+
+        let mut config = SequenceConfig::from_toml_str(toml_content).unwrap();
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("pipelines.pipeline1.load.output_formats"));
+        assert!(error.contains("csv, tsv, json"));
+
+        config.pipelines[0].load.output_formats = vec!["csv".to_string()];
+        config.pipelines[0].load.partition_layout = Some("hive".to_string());
+        assert!(config.validate().is_err());
+
+        config.pipelines[0].load.partition_by = Some("country".to_string());
+        assert!(config.validate().is_ok());
+
+        config.pipelines[0].load.strict_columns = Some(true);
+        assert!(config.validate().is_err());
+        config.pipelines[0].load.columns = Some(vec!["id".to_string(), "id".to_string()]);
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("Duplicate"));
+        config.pipelines[0].load.columns = Some(vec!["id".to_string(), "name".to_string()]);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_auth_config_validation() {
+        let basic = AuthConfig {
+            r#type: "basic".to_string(),
+            username_env: Some("API_USER".to_string()),
+            ..Default::default()
+        };
+        assert!(basic
+            .validate("source.auth")
+            .unwrap_err()
+            .to_string()
+            .contains("password_env"));
+        assert!(AuthConfig {
+            password_env: Some("API_PASSWORD".to_string()),
+            ..basic
+        }
+        .validate("source.auth")
+        .is_ok());
+
+        let api_key = AuthConfig {
+            r#type: "api_key".to_string(),
+            key_env: Some("API_KEY".to_string()),
+            ..Default::default()
+        };
+        assert!(api_key.validate("source.auth").is_ok());
+        assert_eq!(api_key.key_name(), "X-API-Key");
+        let query = AuthConfig {
+            placement: Some("query".to_string()),
+            ..api_key.clone()
+        };
+        assert_eq!(query.key_name(), "api_key");
+        assert!(AuthConfig {
+            placement: Some("cookie".to_string()),
+            ..api_key
+        }
+        .validate("source.auth")
+        .is_err());
+        assert!(AuthConfig {
+            r#type: "digest".to_string(),
+            ..Default::default()
+        }
+        .validate("source.auth")
+        .is_err());
+    }
+
+    #[test]
+    fn test_intermediate_aggregate_split_path() {
+        let split = |path: &str| {
+            IntermediateAggregateConfig {
+                path: path.to_string(),
+                format: None,
+            }
+            .split_path()
+        };
+        assert_eq!(
+            split("./sequence-output/all.jsonl"),
+            ("./sequence-output".to_string(), "all.jsonl".to_string())
+        );
+        assert_eq!(
+            split("sftp://host/exports/all.json"),
+            ("sftp://host/exports".to_string(), "all.json".to_string())
+        );
+        assert_eq!(split("all.csv"), (".".to_string(), "all.csv".to_string()));
+        assert_eq!(split("/all.csv"), ("/".to_string(), "all.csv".to_string()));
+    }
 }
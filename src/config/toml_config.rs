@@ -1,3 +1,4 @@
+use crate::config::sequence_config::HttpConfig;
 use crate::core::ConfigProvider;
 use crate::utils::error::{EtlError, Result};
 use crate::utils::validation::Validate;
@@ -35,6 +36,7 @@ pub struct SourceConfig {
     pub retry_delay_seconds: Option<u64>,
     pub headers: Option<HashMap<String, String>>,
     pub parameters: Option<HashMap<String, String>>,
+    pub http: Option<HttpConfig>, // HTTP 用戶端設定（代理、TLS、連線池、逾時）
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,22 +118,68 @@ pub struct PerformanceConfig {
     pub disk_cache_enabled: Option<bool>,
 }
 
+/// 以點號連接的設定鍵路徑，例如 `transform.operations.clean_txt`
+fn key_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    let join = |parent: &Path, segment: &str| match key_path(parent) {
+        parent if parent.is_empty() => segment.to_string(),
+        parent => format!("{}.{}", parent, segment),
+    };
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => join(parent, &index.to_string()),
+        Path::Map { parent, key } => join(parent, key),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => key_path(parent),
+    }
+}
+
 impl TomlConfig {
     /// 從 TOML 檔案載入配置
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_file_checked(path, false)
+    }
+
+    /// 從 TOML 檔案載入配置；strict 時未知的設定鍵視為錯誤
+    pub fn from_file_checked<P: AsRef<Path>>(path: P, strict: bool) -> Result<Self> {
         let content = std::fs::read_to_string(&path).map_err(EtlError::IoError)?;
-        Self::from_toml_str(&content)
+        Self::from_toml_str_checked(&content, strict)
     }
 
-    /// 從 TOML 字串解析配置
+    /// 從 TOML 字串解析配置；未知的設定鍵（多半是拼錯）只記錄警告
     pub fn from_toml_str(content: &str) -> Result<Self> {
+        Self::from_toml_str_checked(content, false)
+    }
+
+    /// 從 TOML 字串解析配置；strict 時未知的設定鍵視為錯誤，否則記錄警告後忽略
+    pub fn from_toml_str_checked(content: &str, strict: bool) -> Result<Self> {
         // 處理環境變數替換
         let processed_content = Self::substitute_env_vars(content)?;
-
-        toml::from_str(&processed_content).map_err(|e| EtlError::ConfigValidationError {
+        let parse_error = |e: toml::de::Error| EtlError::ConfigValidationError {
             field: "toml_parsing".to_string(),
             message: format!("TOML parsing error: {}", e),
+        };
+
+        let mut unknown_keys = Vec::new();
+        let deserializer = toml::Deserializer::parse(&processed_content).map_err(parse_error)?;
+        let config: Self = serde_ignored::deserialize(deserializer, |path| {
+            unknown_keys.push(key_path(&path));
         })
+        .map_err(parse_error)?;
+
+        if let Some(first) = unknown_keys.first() {
+            if strict {
+                return Err(EtlError::ConfigValidationError {
+                    field: first.clone(),
+                    message: format!("Unknown config keys: {}", unknown_keys.join(", ")),
+                });
+            }
+            for key in &unknown_keys {
+                tracing::warn!("⚠️ Ignoring unknown config key '{}'", key);
+            }
+        }
+        Ok(config)
     }
 
     /// 替換環境變數 (例如 ${API_KEY})
@@ -156,6 +204,11 @@ impl TomlConfig {
         // 驗證輸出路徑
         crate::utils::validation::validate_path("load.output_path", &self.load.output_path)?;
 
+        // 驗證 HTTP 用戶端設定
+        if let Some(http) = &self.source.http {
+            http.validate("source.http")?;
+        }
+
         // 驗證並發請求數
         if let Some(concurrent) = self.extract.concurrent_requests {
             crate::utils::validation::validate_positive_number(
@@ -276,6 +329,39 @@ output_formats = ["csv", "json"]
         assert_eq!(config.concurrent_requests(), 1);
     }
 
+    #[test]
+    fn test_strict_config_rejects_unknown_keys() {
+        let toml_content = r#"
+[pipeline]
+name = "test"
+description = "test"
+version = "1.0"
+
+[source]
+type = "api"
+endpoint = "https://api.example.com/data"
+
+[extract]
+max_record = 10
+
+[transform.operations]
+clean_txt = true
+
+[load]
+output_path = "./test-output"
+output_formats = ["json"]
+"#;
+
+        let config = TomlConfig::from_toml_str(toml_content).unwrap();
+        assert_eq!(config.extract.max_records, None);
+
+        let error = TomlConfig::from_toml_str_checked(toml_content, true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("extract.max_record"));
+        assert!(error.contains("transform.operations.clean_txt"));
+    }
+
     #[test]
     fn test_env_var_substitution() {
         std::env::set_var("TEST_API_ENDPOINT", "https://test.api.com");
@@ -0,0 +1,349 @@
+use crate::config::sequence_config::AggregationConfig;
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 彙總函數
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunction {
+    const SUPPORTED: [&'static str; 5] = ["count", "sum", "avg", "min", "max"];
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "count" => Some(Self::Count),
+            "sum" => Some(Self::Sum),
+            "avg" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+}
+
+/// 單一輸出欄位的彙總運算式，例如 `sum(amount)`；`count` 可不指定欄位
+#[derive(Debug, Clone)]
+struct Aggregate {
+    output: String,
+    function: AggregateFunction,
+    field: Option<String>,
+}
+
+fn expression_pattern() -> &'static regex::Regex {
+    static PATTERN: OnceLock<regex::Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        regex::Regex::new(r"^\s*([A-Za-z]+)\s*(?:\(\s*([^()]*?)\s*\))?\s*$").unwrap()
+    })
+}
+
+impl Aggregate {
+    fn parse(output: &str, expression: &str) -> Result<Self> {
+        let invalid = |reason: String| EtlError::InvalidConfigValueError {
+            field: format!("transform.aggregation.aggregates.{}", output),
+            value: expression.to_string(),
+            reason,
+        };
+        let captures = expression_pattern().captures(expression).ok_or_else(|| {
+            invalid("Expected an expression like \"count\" or \"sum(field)\"".to_string())
+        })?;
+        let function = AggregateFunction::parse(&captures[1]).ok_or_else(|| {
+            invalid(format!(
+                "Supported functions: {}",
+                AggregateFunction::SUPPORTED.join(", ")
+            ))
+        })?;
+        let field = captures
+            .get(2)
+            .map(|field| field.as_str().to_string())
+            .filter(|field| !field.is_empty() && field != "*");
+        if field.is_none() && function != AggregateFunction::Count {
+            return Err(invalid(format!("{} requires a field", &captures[1])));
+        }
+        Ok(Self {
+            output: output.to_string(),
+            function,
+            field,
+        })
+    }
+}
+
+/// 每組、每個彙總運算式的累計值
+#[derive(Debug, Clone)]
+enum Accumulator {
+    Count(u64),
+    Sum {
+        total: f64,
+        integral: bool,
+        seen: bool,
+    },
+    Avg {
+        total: f64,
+        count: u64,
+    },
+    Min(Option<Value>),
+    Max(Option<Value>),
+}
+
+impl Accumulator {
+    fn new(function: AggregateFunction) -> Self {
+        match function {
+            AggregateFunction::Count => Self::Count(0),
+            AggregateFunction::Sum => Self::Sum {
+                total: 0.0,
+                integral: true,
+                seen: false,
+            },
+            AggregateFunction::Avg => Self::Avg {
+                total: 0.0,
+                count: 0,
+            },
+            AggregateFunction::Min => Self::Min(None),
+            AggregateFunction::Max => Self::Max(None),
+        }
+    }
+
+    /// `value` 為 None 表示 `count` 不指定欄位（每筆記錄都計入）
+    fn add(&mut self, value: Option<&Value>) {
+        let value = match value {
+            Some(Value::Null) => return,
+            Some(value) => value,
+            None => {
+                if let Self::Count(count) = self {
+                    *count += 1;
+                }
+                return;
+            }
+        };
+        match self {
+            Self::Count(count) => *count += 1,
+            Self::Sum {
+                total,
+                integral,
+                seen,
+            } => {
+                if let Some(number) = as_number(value) {
+                    *total += number;
+                    *integral &= is_integral(value);
+                    *seen = true;
+                }
+            }
+            Self::Avg { total, count } => {
+                if let Some(number) = as_number(value) {
+                    *total += number;
+                    *count += 1;
+                }
+            }
+            Self::Min(current) => {
+                if current
+                    .as_ref()
+                    .is_none_or(|current| compare(value, current) == Ordering::Less)
+                {
+                    *current = Some(value.clone());
+                }
+            }
+            Self::Max(current) => {
+                if current
+                    .as_ref()
+                    .is_none_or(|current| compare(value, current) == Ordering::Greater)
+                {
+                    *current = Some(value.clone());
+                }
+            }
+        }
+    }
+
+    fn finish(self) -> Value {
+        match self {
+            Self::Count(count) => Value::from(count),
+            Self::Sum { seen: false, .. } => Value::Null,
+            Self::Sum {
+                total,
+                integral: true,
+                ..
+            } if total.abs() < i64::MAX as f64 => Value::from(total as i64),
+            Self::Sum { total, .. } => Value::from(total),
+            Self::Avg { count: 0, .. } => Value::Null,
+            Self::Avg { total, count } => Value::from(total / count as f64),
+            Self::Min(value) | Self::Max(value) => value.unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// 數字或可解析為數字的字串
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn is_integral(value: &Value) -> bool {
+    match value {
+        Value::Number(number) => number.is_i64() || number.is_u64(),
+        Value::String(text) => text.trim().parse::<i64>().is_ok(),
+        _ => false,
+    }
+}
+
+/// 兩邊都是數字時比較數值，否則比較字串（ISO 日期可正確排序）
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (as_number(a), as_number(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => display(a).cmp(&display(b)),
+    }
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 依 group_by 欄位分組並計算彙總值，每組輸出一筆記錄
+#[derive(Debug, Clone)]
+pub struct Aggregator {
+    group_by: Vec<String>,
+    aggregates: Vec<Aggregate>,
+}
+
+impl Aggregator {
+    pub fn compile(config: &AggregationConfig) -> Result<Self> {
+        if config.aggregates.is_empty() {
+            return Err(EtlError::ConfigValidationError {
+                field: "transform.aggregation.aggregates".to_string(),
+                message: "At least one aggregate expression is required".to_string(),
+            });
+        }
+        let mut aggregates = config
+            .aggregates
+            .iter()
+            .map(|(output, expression)| Aggregate::parse(output, expression))
+            .collect::<Result<Vec<_>>>()?;
+        aggregates.sort_by(|a, b| a.output.cmp(&b.output));
+        let group_by = config.group_by.clone().unwrap_or_default();
+        if let Some(aggregate) = aggregates
+            .iter()
+            .find(|aggregate| group_by.contains(&aggregate.output))
+        {
+            return Err(EtlError::ConfigValidationError {
+                field: format!("transform.aggregation.aggregates.{}", aggregate.output),
+                message: "Aggregate output field conflicts with a group_by field".to_string(),
+            });
+        }
+        Ok(Self {
+            group_by,
+            aggregates,
+        })
+    }
+
+    /// 各組依第一次出現的順序輸出；未設定 group_by 時整體彙總為一筆
+    pub fn apply(&self, records: &[Record]) -> Vec<Record> {
+        let mut groups: Vec<(Vec<Value>, Vec<Accumulator>)> = Vec::new();
+        let mut positions: HashMap<Vec<String>, usize> = HashMap::new();
+
+        for record in records {
+            let keys: Vec<Value> = self
+                .group_by
+                .iter()
+                .map(|field| record.data.get(field).cloned().unwrap_or(Value::Null))
+                .collect();
+            let position = *positions
+                .entry(keys.iter().map(display).collect())
+                .or_insert_with(|| {
+                    groups.push((
+                        keys,
+                        self.aggregates
+                            .iter()
+                            .map(|aggregate| Accumulator::new(aggregate.function))
+                            .collect(),
+                    ));
+                    groups.len() - 1
+                });
+            for (aggregate, accumulator) in self.aggregates.iter().zip(&mut groups[position].1) {
+                accumulator.add(
+                    aggregate
+                        .field
+                        .as_ref()
+                        .map(|field| record.data.get(field).unwrap_or(&Value::Null)),
+                );
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(keys, accumulators)| {
+                let mut data: HashMap<String, Value> =
+                    self.group_by.iter().cloned().zip(keys).collect();
+                for (aggregate, accumulator) in self.aggregates.iter().zip(accumulators) {
+                    data.insert(aggregate.output.clone(), accumulator.finish());
+                }
+                Record { data }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(value: Value) -> Record {
+        Record {
+            data: serde_json::from_value(value).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_with_aggregates() {
+        let config = AggregationConfig {
+            group_by: Some(vec!["userId".to_string()]),
+            aggregates: HashMap::from([
+                ("posts".to_string(), "count".to_string()),
+                ("total".to_string(), "sum(amount)".to_string()),
+                ("average".to_string(), "avg(amount)".to_string()),
+                ("first".to_string(), "min(date)".to_string()),
+                ("largest".to_string(), "max(amount)".to_string()),
+            ]),
+        };
+        let aggregator = Aggregator::compile(&config).unwrap();
+        let summary = aggregator.apply(&[
+            record(json!({"userId": 1, "amount": 10, "date": "2024-03-01"})),
+            record(json!({"userId": 2, "amount": "2.5", "date": "2024-01-01"})),
+            record(json!({"userId": 1, "amount": 5, "date": "2024-02-01"})),
+            record(json!({"userId": 1, "amount": null})),
+        ]);
+
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].data["userId"], 1);
+        assert_eq!(summary[0].data["posts"], 3);
+        assert_eq!(summary[0].data["total"], 15);
+        assert_eq!(summary[0].data["average"], 7.5);
+        assert_eq!(summary[0].data["first"], "2024-02-01");
+        assert_eq!(summary[0].data["largest"], 10);
+        assert_eq!(summary[1].data["total"], 2.5);
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        let config = |expression: &str| AggregationConfig {
+            group_by: None,
+            aggregates: HashMap::from([("value".to_string(), expression.to_string())]),
+        };
+        assert!(Aggregator::compile(&config("median(amount)")).is_err());
+        assert!(Aggregator::compile(&config("sum")).is_err());
+        assert!(Aggregator::compile(&config("count(*)")).is_ok());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use std::collections::BTreeSet;
+
+/// 追加輸出時欄位變動的處理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaEvolutionPolicy {
+    /// 新增欄位加到表頭，既有資料列補空值
+    AddColumns,
+    /// 出現新欄位時中止
+    Fail,
+    /// 忽略新欄位，維持既有表頭
+    Ignore,
+}
+
+impl SchemaEvolutionPolicy {
+    pub const SUPPORTED: [&'static str; 3] = ["add_columns", "fail", "ignore"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "add_columns" => Ok(Self::AddColumns),
+            "fail" => Ok(Self::Fail),
+            "ignore" => Ok(Self::Ignore),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "load.append.schema_evolution".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 追加結果摘要
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AppendReport {
+    pub columns: Vec<String>,
+    pub added_columns: Vec<String>,
+    pub ignored_fields: Vec<String>,
+    pub appended_rows: usize,
+}
+
+/// 將記錄追加到既有 CSV 內容，依策略處理欄位變動
+///
+/// `existing` 為 None 時建立新檔，表頭為所有記錄欄位的排序聯集。
+pub fn append_csv(
+    existing: Option<&[u8]>,
+    records: &[Record],
+    policy: SchemaEvolutionPolicy,
+) -> Result<(Vec<u8>, AppendReport)> {
+    let record_fields: BTreeSet<String> = records
+        .iter()
+        .flat_map(|record| record.data.keys().cloned())
+        .collect();
+
+    let (mut columns, existing_rows) = match existing {
+        Some(bytes) if !bytes.is_empty() => read_csv(bytes)?,
+        _ => (Vec::new(), Vec::new()),
+    };
+
+    let mut report = AppendReport::default();
+    let new_fields: Vec<String> = record_fields
+        .iter()
+        .filter(|field| !columns.contains(field))
+        .cloned()
+        .collect();
+
+    if columns.is_empty() {
+        columns = record_fields.into_iter().collect();
+    } else if !new_fields.is_empty() {
+        match policy {
+            SchemaEvolutionPolicy::AddColumns => {
+                columns.extend(new_fields.iter().cloned());
+                report.added_columns = new_fields;
+            }
+            SchemaEvolutionPolicy::Fail => {
+                return Err(EtlError::DataValidationError {
+                    message: format!(
+                        "Schema changed for appended output: new fields {:?} not in existing columns {:?}",
+                        new_fields, columns
+                    ),
+                });
+            }
+            SchemaEvolutionPolicy::Ignore => {
+                report.ignored_fields = new_fields;
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&columns)?;
+    for row in existing_rows {
+        let padded: Vec<String> = (0..columns.len())
+            .map(|i| row.get(i).cloned().unwrap_or_default())
+            .collect();
+        writer.write_record(&padded)?;
+    }
+    for record in records {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|column| record.data.get(column).map(csv_value).unwrap_or_default())
+            .collect();
+        writer.write_record(&row)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| EtlError::ProcessingError {
+        message: format!("Failed to finalize appended CSV: {}", e),
+    })?;
+
+    report.columns = columns;
+    report.appended_rows = records.len();
+    Ok((bytes, report))
+}
+
+fn read_csv(bytes: &[u8]) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(bytes);
+    let columns = reader.headers()?.iter().map(str::to_string).collect();
+    let mut rows = Vec::new();
+    for row in reader.records() {
+        rows.push(row?.iter().map(str::to_string).collect());
+    }
+    Ok((columns, rows))
+}
+
+fn csv_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn record(fields: &[(&str, serde_json::Value)]) -> Record {
+        Record {
+            data: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_append_creates_new_file() {
+        let records = vec![record(&[("id", json!(1)), ("name", json!("a"))])];
+        let (bytes, report) =
+            append_csv(None, &records, SchemaEvolutionPolicy::AddColumns).unwrap();
+
+        assert_eq!(String::from_utf8(bytes).unwrap(), "id,name\n1,a\n");
+        assert_eq!(report.appended_rows, 1);
+    }
+
+    #[test]
+    fn test_add_columns_pads_existing_rows() {
+        let existing = b"id,name\n1,a\n";
+        let records = vec![record(&[("id", json!(2)), ("email", json!("b@x"))])];
+        let (bytes, report) =
+            append_csv(Some(existing), &records, SchemaEvolutionPolicy::AddColumns).unwrap();
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "id,name,email\n1,a,\n2,,b@x\n"
+        );
+        assert_eq!(report.added_columns, vec!["email".to_string()]);
+    }
+
+    #[test]
+    fn test_fail_and_ignore_policies() {
+        let existing = b"id\n1\n";
+        let records = vec![record(&[("id", json!(2)), ("extra", json!(true))])];
+
+        assert!(append_csv(Some(existing), &records, SchemaEvolutionPolicy::Fail).is_err());
+
+        let (bytes, report) =
+            append_csv(Some(existing), &records, SchemaEvolutionPolicy::Ignore).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "id\n1\n2\n");
+        assert_eq!(report.ignored_fields, vec!["extra".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(
+            SchemaEvolutionPolicy::parse("ignore").unwrap(),
+            SchemaEvolutionPolicy::Ignore
+        );
+        assert!(SchemaEvolutionPolicy::parse("merge").is_err());
+    }
+}
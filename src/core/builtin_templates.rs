@@ -0,0 +1,167 @@
+use crate::app::pipelines::sequence_foreach::ForeachItem;
+use crate::core::template_filters::render_template;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::OnceLock;
+
+/// 內建佔位符名稱；名稱後可接 `:chrono 格式`，例如 `{{now:%Y-%m-%d}}`
+pub const BUILTIN_NAMES: [&str; 6] = [
+    "now",
+    "today",
+    "yesterday",
+    "run_start",
+    "run_start_ts",
+    "execution_id",
+];
+
+/// 內建佔位符的值來源：本次執行的 ID 與開始時間（UTC），以及 sequence.foreach 的目前項目
+#[derive(Debug, Clone)]
+pub struct BuiltinValues {
+    pub execution_id: String,
+    pub run_start: DateTime<Utc>,
+    pub item: Option<ForeachItem>,
+}
+
+impl BuiltinValues {
+    pub fn new(execution_id: &str, run_start: DateTime<Utc>) -> Self {
+        Self {
+            execution_id: execution_id.to_string(),
+            run_start,
+            item: None,
+        }
+    }
+
+    /// 一併替換逐項執行的 `{{tenant}}`、`{{tenant.id}}` 佔位符（可使用過濾器）
+    pub fn with_item(mut self, item: Option<ForeachItem>) -> Self {
+        self.item = item;
+        self
+    }
+
+    /// 取得單一內建佔位符的值；名稱不是內建佔位符或格式無效時返回 None
+    ///
+    /// - `now`、`run_start`：RFC 3339，指定格式時依格式輸出
+    /// - `today`、`yesterday`：`%Y-%m-%d`，以執行開始的日期計算
+    /// - `run_start_ts`：執行開始的 Unix 秒數
+    /// - `execution_id`：執行 ID
+    pub fn value(&self, name: &str, format: Option<&str>) -> Option<String> {
+        let render = |time: DateTime<Utc>, default: &str| match format {
+            Some(format) => format_time(time, format),
+            None if default.is_empty() => Some(time.to_rfc3339()),
+            None => format_time(time, default),
+        };
+        match name {
+            "now" => render(Utc::now(), ""),
+            "run_start" => render(self.run_start, ""),
+            "today" => render(self.run_start, "%Y-%m-%d"),
+            "yesterday" => render(self.run_start - Duration::days(1), "%Y-%m-%d"),
+            "run_start_ts" => Some(self.run_start.timestamp().to_string()),
+            "execution_id" => Some(self.execution_id.clone()),
+            _ => None,
+        }
+    }
+
+    /// 替換模板中的內建佔位符與項目佔位符，其他佔位符與格式無效的內建佔位符原樣保留
+    pub fn apply(&self, template: &str) -> String {
+        if !template.contains("{{") {
+            return template.to_string();
+        }
+        let rendered = builtin_pattern()
+            .replace_all(template, |captures: &regex::Captures| {
+                self.value(&captures[1], captures.get(2).map(|m| m.as_str().trim()))
+                    .unwrap_or_else(|| captures[0].to_string())
+            })
+            .into_owned();
+        match &self.item {
+            Some(item) if rendered.contains(&format!("{{{{{}", item.name)) => {
+                render_template(&rendered, |key| item.resolve(key)).unwrap_or(rendered)
+            }
+            _ => rendered,
+        }
+    }
+}
+
+/// 依 chrono 格式輸出時間；格式無效時返回 None（直接呼叫 to_string 會 panic）
+fn format_time(time: DateTime<Utc>, format: &str) -> Option<String> {
+    use std::fmt::Write;
+    let mut text = String::new();
+    write!(text, "{}", time.format(format)).ok()?;
+    Some(text)
+}
+
+fn builtin_pattern() -> &'static regex::Regex {
+    static PATTERN: OnceLock<regex::Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        regex::Regex::new(&format!(
+            r"\{{\{{\s*({})(?::([^}}]+))?\s*\}}\}}",
+            BUILTIN_NAMES.join("|")
+        ))
+        .unwrap()
+    })
+}
+
+/// 佔位符名稱（可含 `:格式`）是否為內建佔位符
+pub fn is_builtin(name: &str) -> bool {
+    let name = name.split_once(':').map_or(name, |(name, _)| name).trim();
+    BUILTIN_NAMES.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn values() -> BuiltinValues {
+        BuiltinValues::new(
+            "exec-42",
+            Utc.with_ymd_and_hms(2024, 3, 1, 6, 30, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_apply_builtins() {
+        let values = values();
+        assert_eq!(
+            values.apply("/orders?from={{yesterday}}&to={{today}}&run={{execution_id}}"),
+            "/orders?from=2024-02-29&to=2024-03-01&run=exec-42"
+        );
+        assert_eq!(values.apply("{{run_start_ts}}"), "1709274600");
+        assert_eq!(
+            values.apply("{{ run_start:%Y%m%d_%H%M }}.json"),
+            "20240301_0630.json"
+        );
+        assert_eq!(values.apply("{{yesterday:%d/%m}}"), "29/02");
+        // 無效的格式保留原樣
+        assert_eq!(values.apply("{{today:%Q}}"), "{{today:%Q}}");
+        assert_eq!(
+            values.apply("{{now:%Y}}"),
+            Utc::now().format("%Y").to_string()
+        );
+    }
+
+    #[test]
+    fn test_apply_foreach_item() {
+        let values = values().with_item(Some(ForeachItem::new(
+            "tenant",
+            1,
+            serde_json::json!({"id": "acme", "name": "Acme Corp"}),
+        )));
+        assert_eq!(
+            values.apply(
+                "/tenants/{{tenant.id}}/orders?from={{yesterday}}&q={{tenant.name|urlencode}}"
+            ),
+            "/tenants/acme/orders?from=2024-02-29&q=Acme%20Corp"
+        );
+        assert_eq!(values.apply("{{tenant.missing}}"), "{{tenant.missing}}");
+    }
+
+    #[test]
+    fn test_other_placeholders_are_kept() {
+        let values = values();
+        assert_eq!(
+            values.apply("{{user_id}} {{nowhere}} {{checkpoint.last_id}}"),
+            "{{user_id}} {{nowhere}} {{checkpoint.last_id}}"
+        );
+        assert!(is_builtin("now:%Y-%m-%d"));
+        assert!(is_builtin("execution_id"));
+        assert!(!is_builtin("user_id"));
+    }
+}
@@ -0,0 +1,250 @@
+use crate::config::sequence_config::CheckpointConfig;
+use crate::core::{Record, Storage};
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 模板中引用 checkpoint 值的前綴，例如 {{checkpoint.last_id}}
+pub const CHECKPOINT_TEMPLATE_PREFIX: &str = "checkpoint.";
+
+/// 持久化的 checkpoint 狀態（每個 Pipeline 一份）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckpointState {
+    pub pipeline_name: String,
+    pub values: HashMap<String, serde_json::Value>,
+    pub updated_at: Option<String>,
+    pub execution_id: Option<String>,
+}
+
+impl CheckpointState {
+    /// 從存儲讀取 checkpoint，不存在時回傳帶有初始值的狀態
+    pub async fn load<S: Storage>(
+        storage: &S,
+        pipeline_name: &str,
+        config: &CheckpointConfig,
+        cipher: Option<&StateCipher>,
+    ) -> Result<Self> {
+        let path = config.state_file(pipeline_name);
+        let mut state = match storage.read_file(&path).await {
+            Ok(bytes) => {
+                serde_json::from_slice::<CheckpointState>(&open_state(cipher, &bytes, &path)?)?
+            }
+            Err(e) if e.is_not_found() => {
+                tracing::info!(
+                    "📍 {}: No checkpoint found at '{}', starting from initial values",
+                    pipeline_name,
+                    path
+                );
+                CheckpointState {
+                    pipeline_name: pipeline_name.to_string(),
+                    ..Default::default()
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(initial_values) = &config.initial_values {
+            for (key, value) in initial_values {
+                state
+                    .values
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// 將 checkpoint 寫回存儲
+    pub async fn save<S: Storage>(
+        &self,
+        storage: &S,
+        config: &CheckpointConfig,
+        cipher: Option<&StateCipher>,
+    ) -> Result<()> {
+        let path = config.state_file(&self.pipeline_name);
+        let json = serde_json::to_string_pretty(self)?;
+        storage
+            .write_file(&path, &seal_state(cipher, json.as_bytes(), &path)?)
+            .await?;
+        tracing::info!(
+            "📍 {}: Checkpoint saved to '{}': {:?}",
+            self.pipeline_name,
+            path,
+            self.values
+        );
+        Ok(())
+    }
+
+    /// 依據記錄計算新的 watermark（取每個欄位的最大值），沒有資料時保留原值
+    pub fn advance(&mut self, records: &[Record], config: &CheckpointConfig) -> bool {
+        let mut changed = false;
+        for (key, field) in &config.watermarks {
+            let max_value = records
+                .iter()
+                .filter_map(|record| record.data.get(field))
+                .filter(|value| !value.is_null())
+                .max_by(|a, b| compare_watermark(a, b));
+
+            if let Some(max_value) = max_value {
+                let is_newer = self
+                    .values
+                    .get(key)
+                    .map(|current| compare_watermark(max_value, current) == Ordering::Greater)
+                    .unwrap_or(true);
+                if is_newer {
+                    self.values.insert(key.clone(), max_value.clone());
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// 替換模板中的 {{checkpoint.KEY}} 佔位符
+    pub fn apply_template(&self, template: &str) -> String {
+        if !template.contains(CHECKPOINT_TEMPLATE_PREFIX) {
+            return template.to_string();
+        }
+
+        let mut processed = template.to_string();
+        for (key, value) in &self.values {
+            let placeholder = format!("{{{{{}{}}}}}", CHECKPOINT_TEMPLATE_PREFIX, key);
+            let value_str = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            processed = processed.replace(&placeholder, &value_str);
+        }
+        processed
+    }
+}
+
+/// 比較兩個 watermark 值：數字以數值比較，其他以字串比較（適用 ISO 日期）
+fn compare_watermark(a: &serde_json::Value, b: &serde_json::Value) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => {
+            let a_str = a
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| a.to_string());
+            let b_str = b
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| b.to_string());
+            a_str.cmp(&b_str)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(id: i64, updated_at: &str) -> Record {
+        let mut data = HashMap::new();
+        data.insert("id".to_string(), json!(id));
+        data.insert("updated_at".to_string(), json!(updated_at));
+        Record { data }
+    }
+
+    fn config() -> CheckpointConfig {
+        CheckpointConfig {
+            enabled: Some(true),
+            watermarks: HashMap::from([
+                ("last_id".to_string(), "id".to_string()),
+                ("last_updated".to_string(), "updated_at".to_string()),
+            ]),
+            initial_values: Some(HashMap::from([("last_id".to_string(), json!(0))])),
+            state_file: None,
+        }
+    }
+
+    #[test]
+    fn test_advance_takes_max_values() {
+        let mut state = CheckpointState::default();
+        let records = vec![
+            record(9, "2024-01-02T00:00:00Z"),
+            record(12, "2024-01-01T00:00:00Z"),
+        ];
+
+        assert!(state.advance(&records, &config()));
+        assert_eq!(state.values["last_id"], json!(12));
+        assert_eq!(state.values["last_updated"], json!("2024-01-02T00:00:00Z"));
+
+        // 較舊的資料不應倒退 watermark
+        assert!(!state.advance(&[record(3, "2023-12-31T00:00:00Z")], &config()));
+        assert_eq!(state.values["last_id"], json!(12));
+    }
+
+    #[test]
+    fn test_apply_template() {
+        let mut state = CheckpointState::default();
+        state.values.insert("last_id".to_string(), json!(42));
+
+        assert_eq!(
+            state.apply_template("https://api.test/items?since={{checkpoint.last_id}}"),
+            "https://api.test/items?since=42"
+        );
+        assert_eq!(state.apply_template("{{token}}"), "{{token}}");
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_checkpoint_roundtrip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = crate::LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        let cipher = StateCipher::from_key_material("state-key").unwrap();
+
+        let mut state = CheckpointState {
+            pipeline_name: "items".to_string(),
+            ..Default::default()
+        };
+        state.values.insert("last_id".to_string(), json!(42));
+        state
+            .save(&storage, &config(), Some(&cipher))
+            .await
+            .unwrap();
+
+        let raw = std::fs::read(temp_dir.path().join(".checkpoints/items.json")).unwrap();
+        assert!(StateCipher::is_encrypted(&raw));
+
+        let loaded = CheckpointState::load(&storage, "items", &config(), Some(&cipher))
+            .await
+            .unwrap();
+        assert_eq!(loaded.values["last_id"], json!(42));
+        assert!(CheckpointState::load(&storage, "items", &config(), None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_propagates_read_errors_other_than_not_found() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = crate::LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+
+        let missing = CheckpointState::load(&storage, "items", &config(), None)
+            .await
+            .unwrap();
+        assert_eq!(missing.values["last_id"], json!(0));
+
+        // 無法讀取（例如路徑為目錄）時不應默默從初始值重新開始
+        std::fs::create_dir_all(temp_dir.path().join(".checkpoints/items.json")).unwrap();
+        let error = CheckpointState::load(&storage, "items", &config(), None)
+            .await
+            .unwrap_err();
+        assert!(!error.is_not_found());
+    }
+
+    #[test]
+    fn test_numeric_watermark_comparison() {
+        assert_eq!(compare_watermark(&json!(10), &json!(9)), Ordering::Greater);
+        assert_eq!(
+            compare_watermark(&json!("b"), &json!("a")),
+            Ordering::Greater
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use crate::core::Storage;
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
+use crate::utils::error::{EtlError, Result};
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 收到 304 Not Modified 時的處理方式（source.conditional.on_not_modified）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotModifiedPolicy {
+    /// 使用上次保存的回應內容（預設）
+    #[default]
+    Reuse,
+    /// 所有請求都未變更時略過整個 Pipeline
+    Skip,
+}
+
+impl NotModifiedPolicy {
+    pub const SUPPORTED: [&'static str; 2] = ["reuse", "skip"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "reuse" => Ok(Self::Reuse),
+            "skip" => Ok(Self::Skip),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "source.conditional.on_not_modified".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 單一端點上次回應的 ETag / Last-Modified，reuse 模式下另存回應內容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalEntry {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub updated_at: String,
+    pub body: Option<serde_json::Value>,
+}
+
+impl ConditionalEntry {
+    /// 從回應標頭建立；回應沒有 ETag 也沒有 Last-Modified 時返回 None
+    pub fn from_headers(url: &str, headers: &HeaderMap) -> Option<Self> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+        Some(Self {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            body: None,
+        })
+    }
+
+    /// 下次請求要加上的 If-None-Match / If-Modified-Since 標頭
+    pub fn request_headers(&self) -> Vec<(reqwest::header::HeaderName, &str)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &self.etag {
+            headers.push((IF_NONE_MATCH, etag.as_str()));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.push((IF_MODIFIED_SINCE, last_modified.as_str()));
+        }
+        headers
+    }
+}
+
+/// 保存檔路徑，以完整請求 URL 的 sha256 區分端點
+pub fn entry_path(pipeline_name: &str, url: &str) -> String {
+    let digest = Sha256::digest(url.as_bytes());
+    let key: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(".conditional/{}/{}.json", pipeline_name, key)
+}
+
+/// 讀取端點上次的驗證資訊，不存在或損毀時返回 None
+pub async fn load<S: Storage>(
+    storage: &S,
+    pipeline_name: &str,
+    url: &str,
+    cipher: Option<&StateCipher>,
+) -> Option<ConditionalEntry> {
+    let path = entry_path(pipeline_name, url);
+    let bytes = storage.read_file(&path).await.ok()?;
+    let entry = open_state(cipher, &bytes, &path)
+        .and_then(|json| Ok(serde_json::from_slice::<ConditionalEntry>(&json)?));
+
+    match entry {
+        Ok(entry) if entry.url == url => Some(entry),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!(
+                "🏷️ {}: Ignoring unreadable conditional request state: {}",
+                pipeline_name,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// 寫入端點的驗證資訊
+pub async fn save<S: Storage>(
+    storage: &S,
+    pipeline_name: &str,
+    entry: &ConditionalEntry,
+    cipher: Option<&StateCipher>,
+) -> Result<()> {
+    let path = entry_path(pipeline_name, &entry.url);
+    let json = serde_json::to_vec(entry)?;
+    storage
+        .write_file(&path, &seal_state(cipher, &json, &path)?)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_entry_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(ConditionalEntry::from_headers("http://a/users", &headers).is_none());
+
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        let entry = ConditionalEntry::from_headers("http://a/users", &headers).unwrap();
+        assert_eq!(
+            entry.request_headers(),
+            vec![
+                (IF_NONE_MATCH, "\"v1\""),
+                (IF_MODIFIED_SINCE, "Wed, 21 Oct 2015 07:28:00 GMT"),
+            ]
+        );
+        assert_ne!(
+            entry_path("users", "http://a/users?page=1"),
+            entry_path("users", "http://a/users?page=2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = crate::LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        let mut entry = ConditionalEntry::from_headers("http://a/users", &headers).unwrap();
+        entry.body = Some(serde_json::json!([{"id": 1}]));
+
+        save(&storage, "users", &entry, None).await.unwrap();
+        assert_eq!(
+            load(&storage, "users", "http://a/users", None).await,
+            Some(entry)
+        );
+        assert!(load(&storage, "users", "http://a/other", None)
+            .await
+            .is_none());
+        assert!(NotModifiedPolicy::parse("ignore").is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::core::pipeline_sequence::PipelineContext;
+use crate::utils::error::{EtlError, Result};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+type Row = HashMap<String, serde_json::Value>;
+
+/// 以指定欄位索引先前 Pipeline 的結果，供模板逐筆查找
+#[derive(Debug, Clone, Default)]
+pub struct ContextIndex {
+    pub pipeline: String,
+    pub key: String,
+    rows: HashMap<String, Row>,
+}
+
+impl ContextIndex {
+    /// 從上下文中的 Pipeline 結果建立索引；鍵重複時保留第一筆
+    pub fn build(context: &PipelineContext, pipeline: &str, key: &str) -> Result<Self> {
+        let result = context.get_result_by_name(pipeline).ok_or_else(|| {
+            EtlError::ConfigValidationError {
+                field: "context_index.pipeline".to_string(),
+                message: format!("No result for pipeline '{}' in context", pipeline),
+            }
+        })?;
+
+        let mut rows = HashMap::new();
+        for record in context.records_of(result)?.iter() {
+            if let Some(key_value) = record.data.get(key).and_then(index_key) {
+                rows.entry(key_value).or_insert_with(|| record.data.clone());
+            }
+        }
+
+        tracing::info!(
+            "🗂️ Indexed {} records from '{}' by '{}'",
+            rows.len(),
+            pipeline,
+            key
+        );
+        Ok(Self {
+            pipeline: pipeline.to_string(),
+            key: key.to_string(),
+            rows,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn get(&self, value: &serde_json::Value) -> Option<&Row> {
+        index_key(value).and_then(|key| self.rows.get(&key))
+    }
+}
+
+pub(crate) fn index_key(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// 模板中的查找參照：`{{lookup:PIPELINE:KEY=SOURCE_FIELD:FIELD}}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupReference {
+    pub pipeline: String,
+    pub key: String,
+    pub source_field: String,
+    pub field: String,
+}
+
+fn lookup_pattern() -> &'static regex::Regex {
+    static PATTERN: OnceLock<regex::Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        regex::Regex::new(r"\{\{\s*lookup:([^:}]+):([^=:}]+)=([^:}]+):([^}]+?)\s*\}\}").unwrap()
+    })
+}
+
+impl LookupReference {
+    /// 找出模板中所有查找參照
+    pub fn find_all(template: &str) -> Vec<Self> {
+        lookup_pattern()
+            .captures_iter(template)
+            .map(|caps| Self::from_captures(&caps))
+            .collect()
+    }
+
+    fn from_captures(caps: &regex::Captures) -> Self {
+        Self {
+            pipeline: caps[1].trim().to_string(),
+            key: caps[2].trim().to_string(),
+            source_field: caps[3].trim().to_string(),
+            field: caps[4].trim().to_string(),
+        }
+    }
+}
+
+/// 將模板中的查找參照替換為索引中對應記錄的欄位值
+///
+/// 記錄缺少來源欄位、索引查無記錄或欄位不存在時返回錯誤。
+pub fn resolve_lookups(
+    template: &str,
+    record: Option<&Row>,
+    index: &ContextIndex,
+) -> Result<String> {
+    let mut error = None;
+    let resolved = lookup_pattern().replace_all(template, |caps: &regex::Captures| {
+        match resolve_reference(&LookupReference::from_captures(caps), record, index) {
+            Ok(value) => value,
+            Err(e) => {
+                error.get_or_insert(e);
+                caps[0].to_string()
+            }
+        }
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(resolved.into_owned()),
+    }
+}
+
+fn resolve_reference(
+    reference: &LookupReference,
+    record: Option<&Row>,
+    index: &ContextIndex,
+) -> Result<String> {
+    if reference.pipeline != index.pipeline || reference.key != index.key {
+        return Err(EtlError::ConfigValidationError {
+            field: "context_index".to_string(),
+            message: format!(
+                "Lookup on {}.{} requires context_index = {{ pipeline = \"{}\", key = \"{}\" }}",
+                reference.pipeline, reference.key, reference.pipeline, reference.key
+            ),
+        });
+    }
+
+    let source_value = record
+        .and_then(|record| record.get(&reference.source_field))
+        .ok_or_else(|| EtlError::ProcessingError {
+            message: format!(
+                "Lookup source field '{}' missing from record",
+                reference.source_field
+            ),
+        })?;
+    let row = index
+        .get(source_value)
+        .ok_or_else(|| EtlError::ProcessingError {
+            message: format!(
+                "No '{}' record with {} = {}",
+                index.pipeline, index.key, source_value
+            ),
+        })?;
+    let value = row
+        .get(&reference.field)
+        .ok_or_else(|| EtlError::ProcessingError {
+            message: format!(
+                "Field '{}' not found in '{}' record with {} = {}",
+                reference.field, index.pipeline, index.key, source_value
+            ),
+        })?;
+
+    Ok(match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::pipeline_sequence::PipelineResult;
+    use crate::core::Record;
+
+    fn users_context() -> PipelineContext {
+        let mut context = PipelineContext::new("test".to_string());
+        let records = vec![
+            serde_json::json!({"id": 1, "email": "alice@example.com"}),
+            serde_json::json!({"id": 2, "email": "bob@example.com"}),
+        ]
+        .into_iter()
+        .map(|value| Record {
+            data: serde_json::from_value(value).unwrap(),
+        })
+        .collect();
+        context.add_result(PipelineResult {
+            pipeline_name: "users".to_string(),
+            records,
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+        context
+    }
+
+    #[test]
+    fn test_resolve_lookup_template() {
+        let index = ContextIndex::build(&users_context(), "users", "id").unwrap();
+        assert_eq!(index.len(), 2);
+
+        let record: Row = serde_json::from_value(serde_json::json!({"link_user_id": "2"})).unwrap();
+        let resolved = resolve_lookups(
+            "/notify?to={{lookup:users:id=link_user_id:email}}",
+            Some(&record),
+            &index,
+        )
+        .unwrap();
+        assert_eq!(resolved, "/notify?to=bob@example.com");
+    }
+
+    #[test]
+    fn test_lookup_errors() {
+        let index = ContextIndex::build(&users_context(), "users", "id").unwrap();
+        let record: Row = serde_json::from_value(serde_json::json!({"link_user_id": 9})).unwrap();
+
+        assert!(resolve_lookups(
+            "{{lookup:users:id=link_user_id:email}}",
+            Some(&record),
+            &index
+        )
+        .is_err());
+        assert!(resolve_lookups(
+            "{{lookup:orders:id=link_user_id:email}}",
+            Some(&record),
+            &index
+        )
+        .is_err());
+        assert!(ContextIndex::build(&users_context(), "missing", "id").is_err());
+        assert_eq!(
+            LookupReference::find_all("{{lookup:users:id=uid:email}} {{id}}").len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::core::Record;
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 上下文記憶體上限：超過 `max_records_in_memory` 的結果寫入暫存檔，需要時再讀回
+#[derive(Debug, Clone)]
+pub struct ContextSpill {
+    max_records_in_memory: usize,
+    dir: PathBuf,
+    cipher: Option<Arc<StateCipher>>,
+}
+
+impl ContextSpill {
+    pub fn new(max_records_in_memory: usize, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            max_records_in_memory,
+            dir: dir.into(),
+            cipher: None,
+        }
+    }
+
+    /// 與狀態檔使用同一把金鑰加密暫存檔
+    pub fn with_cipher(mut self, cipher: Arc<StateCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    pub fn should_spill(&self, records: usize) -> bool {
+        records > self.max_records_in_memory
+    }
+
+    pub fn cipher(&self) -> Option<&StateCipher> {
+        self.cipher.as_deref()
+    }
+
+    /// 將記錄以 JSON Lines 寫入 `{dir}/{pipeline_name}.jsonl`
+    pub fn write(&self, pipeline_name: &str, records: &[Record]) -> Result<SpilledRecords> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.jsonl", pipeline_name));
+        let mut lines = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut lines, record)?;
+            lines.push(b'\n');
+        }
+        let data = seal_state(self.cipher(), &lines, &path.to_string_lossy())?;
+        std::fs::write(&path, data)?;
+        Ok(SpilledRecords {
+            path,
+            records: records.len(),
+        })
+    }
+
+    /// 移除暫存目錄（序列成功完成後不再需要續跑）
+    pub fn cleanup(&self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(
+                    "⚠️ Failed to remove spill directory {}: {}",
+                    self.dir.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// 已寫入暫存檔的 Pipeline 結果（保存在序列狀態中，續跑時仍可讀回）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpilledRecords {
+    pub path: PathBuf,
+    pub records: usize,
+}
+
+impl SpilledRecords {
+    pub fn read(&self, cipher: Option<&StateCipher>) -> Result<Vec<Record>> {
+        let data = std::fs::read(&self.path)?;
+        let lines = open_state(cipher, &data, &self.path.to_string_lossy())?;
+        lines
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| Ok(serde_json::from_slice(line)?))
+            .collect()
+    }
+}
@@ -1,35 +1,1195 @@
-use crate::config::sequence_config::PipelineDefinition;
+use crate::adapters::http::{build_client, SourceAuth};
+use crate::app::pipelines::shared_data::SharedDataWrite;
+use crate::app::pipelines::stream_transform::{parse_input, StreamInputFormat};
+use crate::config::sequence_config::{
+    CombinedConfig, FieldMapping, FilesSourceConfig, FollowLinksConfig, JoinConfig, LoadConfig,
+    LookupTableConfig, MultipartConfig, PipelineDefinition, S3SourceConfig, ValidationConfig,
+};
 use crate::core::{
+    aggregation::Aggregator,
+    append_output::{append_csv, SchemaEvolutionPolicy},
+    builtin_templates::BuiltinValues,
+    checkpoint::CheckpointState,
+    conditional_requests::{self, ConditionalEntry, NotModifiedPolicy},
+    context_index::{resolve_lookups, ContextIndex},
+    csv_output::{encode_csv, DelimitedFormat},
+    dead_letter::{DeadLetterQueue, RecordErrorPolicy},
+    extract_cache,
+    fan_out_progress::{call_key, FanOutProgress},
+    field_transforms::FieldTransformer,
+    file_ledger::{FileLedger, LedgerEntry},
+    filename_template::FilenameValues,
+    http_audit::{self, HttpAuditEntry, HttpAuditLog},
+    link_pagination,
+    lookup::LookupTable,
+    null_policy::{missing_field, output_fields, render_json, NullPolicy},
+    object_source,
+    output_archive::{ArchiveFormat, OutputArchive},
+    output_encryption::{EncryptionMethod, OutputEncryption, ENCRYPTED_EXTENSION},
+    output_manifest::OutputManifest,
+    partitioned_output::{chunk_entry_name, chunk_records, partition_records},
+    pii::PiiProtector,
+    pipeline_join::join_records,
     pipeline_sequence::{ContextualPipeline, PipelineContext},
+    pipeline_union::union_records,
+    profiling::{DataProfile, PROFILE_FILE_NAME},
+    quality_rules::{QualityReport, Severity},
+    reconciliation::{Discrepancy, MismatchPolicy, Reconciliation},
+    record_script::RecordScript,
+    record_validation::{validate_record, validate_record_count, InvalidRecordPolicy},
+    response_limits::{self, LimitPolicy},
+    response_metadata::{ResponseMetadata, ResponseMetadataTarget},
+    schema_inference::{InferredSchema, SCHEMA_FILE_NAME},
+    sequence_state,
+    staged_storage::StagedStorage,
+    template_filters::{render_template, url_encode},
+    transform_steps::{apply_transform_steps, resolve_transform_steps},
+    type_coercion::{coerce, CoercionErrorPolicy},
+    warnings::{Warning, WarningCode, WarningCollector},
+    write_mode::{resolve_output_name, WriteMode},
     Record, Storage, TransformResult,
 };
+use crate::utils::budget::ExecutionBudget;
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
 use crate::utils::error::{EtlError, Result};
-use reqwest::Client;
-use std::collections::HashMap;
-use std::io::Write;
-use zip::write::{FileOptions, ZipWriter};
+use crate::utils::heartbeat::ProgressTracker;
+use crate::utils::prometheus;
+use crate::utils::rate_limiter::RateLimiter;
+use crate::utils::shutdown::ShutdownSignal;
+use crate::utils::{encoding, file_glob, redact, xml};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 因停止訊號或接近截止時間而未發出的參數化呼叫，下次執行時接續處理
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RemainingParameters {
+    execution_id: String,
+    parameters: Vec<HashMap<String, serde_json::Value>>,
+}
+
+/// 比對參數記錄是否相同：欄位排序後序列化
+fn parameter_key(data: &HashMap<String, serde_json::Value>) -> String {
+    let sorted: BTreeMap<&String, &serde_json::Value> = data.iter().collect();
+    serde_json::to_string(&sorted).unwrap_or_default()
+}
+
+/// 基於序列配置的上下文感知 Pipeline
+pub struct SequenceAwarePipeline<S: Storage> {
+    name: String,
+    storage: S,
+    config: PipelineDefinition,
+    /// 依 source.http 建立；失敗時保留原因，發出請求時返回錯誤，不退回預設設定
+    client: std::result::Result<Client, String>,
+    execution_metadata: Mutex<HashMap<String, serde_json::Value>>,
+    checkpoint_state: Mutex<Option<CheckpointState>>,
+    context_index: Mutex<Option<ContextIndex>>,
+    budget: Option<ExecutionBudget>,
+    rate_limiter: Option<RateLimiter>,
+    shared_rate_limiter: Option<Arc<RateLimiter>>,
+    auth: Option<SourceAuth>,
+    state_cipher: Option<Arc<StateCipher>>,
+    cache_dir: PathBuf,
+    warnings: WarningCollector,
+    dead_letters: DeadLetterQueue,
+    progress: Option<Arc<ProgressTracker>>,
+    http_audit: Option<HttpAuditLog>,
+    pending_files: Mutex<Vec<(PathBuf, LedgerEntry)>>,
+    shutdown: Option<ShutdownSignal>,
+    http_calls: AtomicU64,
+    http_retries: AtomicU64,
+    reconciliation: Mutex<Option<Reconciliation>>,
+    builtins: Mutex<Option<BuiltinValues>>,
+    pending_validators: Mutex<Vec<ConditionalEntry>>,
+    conditional_requests: AtomicUsize,
+    not_modified_responses: AtomicUsize,
+    sequence_name: Option<String>,
+}
+
+impl<S: Storage> SequenceAwarePipeline<S> {
+    pub fn new(name: String, storage: S, config: PipelineDefinition) -> Self {
+        let rate_limiter = config
+            .source
+            .rate_limit
+            .as_ref()
+            .map(RateLimiter::from_config);
+        let client = build_client(
+            config.source.http.as_ref(),
+            config.source.carries_credentials(),
+        )
+        .map_err(|e| {
+            tracing::error!("📡 {}: Failed to build HTTP client: {}", name, e);
+            e.to_string()
+        });
+        let auth = config.source.auth.clone().and_then(|auth| {
+            let client = client.as_ref().ok()?;
+            Some(SourceAuth::new(client.clone(), auth))
+        });
+        let http_audit = config.source.is_audited().then(HttpAuditLog::new);
+
+        Self {
+            name,
+            storage,
+            config,
+            client,
+            execution_metadata: Mutex::new(HashMap::new()),
+            checkpoint_state: Mutex::new(None),
+            context_index: Mutex::new(None),
+            budget: None,
+            rate_limiter,
+            shared_rate_limiter: None,
+            auth,
+            state_cipher: None,
+            cache_dir: sequence_state::default_cache_dir(),
+            warnings: WarningCollector::new(),
+            dead_letters: DeadLetterQueue::new(),
+            progress: None,
+            http_audit,
+            pending_files: Mutex::new(Vec::new()),
+            shutdown: None,
+            http_calls: AtomicU64::new(0),
+            http_retries: AtomicU64::new(0),
+            reconciliation: Mutex::new(None),
+            builtins: Mutex::new(None),
+            pending_validators: Mutex::new(Vec::new()),
+            conditional_requests: AtomicUsize::new(0),
+            not_modified_responses: AtomicUsize::new(0),
+            sequence_name: None,
+        }
+    }
+
+    /// 所屬序列的名稱，供檔名模板的 {sequence_name} 使用
+    pub fn with_sequence_name(mut self, sequence_name: impl Into<String>) -> Self {
+        self.sequence_name = Some(sequence_name.into());
+        self
+    }
+
+    /// 設定狀態檔（checkpoint、剩餘工作）的加密器
+    pub fn with_state_cipher(mut self, state_cipher: Arc<StateCipher>) -> Self {
+        self.state_cipher = Some(state_cipher);
+        self
+    }
+
+    /// 擷取結果快取（extract.cache）的目錄，位於序列的狀態目錄下
+    pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    /// 回報參數化呼叫進度，供心跳估算 ETA
+    pub fn with_progress(mut self, progress: Arc<ProgressTracker>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// 設定序列共享的速率限制器（與 source.rate_limit 同時生效）
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.shared_rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    fn has_rate_limit(&self) -> bool {
+        self.rate_limiter.is_some() || self.shared_rate_limiter.is_some()
+    }
+
+    /// 套用認證後發送請求，OAuth2 收到 401 時刷新 token 並重試一次；每次送出都計入請求數與稽核日誌
+    async fn send_request(&self, request: RequestBuilder) -> Result<Response> {
+        let Some(auth) = &self.auth else {
+            return self.send_counted(request).await;
+        };
+
+        let retry_request = auth.is_refreshable().then(|| request.try_clone()).flatten();
+        let request = auth.apply(request).await?;
+        let response = self.send_counted(request).await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            if let Some(retry_request) = retry_request {
+                tracing::warn!(
+                    "🔑 {}: Received 401, refreshing OAuth2 token and retrying",
+                    self.name
+                );
+                self.http_retries.fetch_add(1, Ordering::Relaxed);
+                prometheus::global().inc_counter(
+                    prometheus::HTTP_RETRIES,
+                    &[("pipeline", &self.name)],
+                    1.0,
+                );
+                auth.invalidate().await;
+                let retry_request = auth.apply(retry_request).await?;
+                return self.send_counted(retry_request).await;
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// 發送單一請求並記錄請求數、延遲指標與稽核日誌
+    async fn send_counted(&self, request: RequestBuilder) -> Result<Response> {
+        let audit_entry = self.http_audit.as_ref().and_then(|_| {
+            request
+                .try_clone()
+                .and_then(|request| request.build().ok())
+                .map(|request| HttpAuditEntry::from_request(&request))
+        });
+        self.wait_for_rate_limit().await;
+        let started = std::time::Instant::now();
+        let result = request.send().await.map_err(EtlError::from);
+
+        if let (Some(audit), Some(mut entry)) = (&self.http_audit, audit_entry) {
+            entry.duration_ms = started.elapsed().as_millis() as u64;
+            match &result {
+                Ok(response) => entry.status = Some(response.status().as_u16()),
+                Err(e) => entry.error = Some(redact::redact_sensitive(&e.to_string())),
+            }
+            audit.push(entry);
+        }
+
+        self.http_calls.fetch_add(1, Ordering::Relaxed);
+        let registry = prometheus::global();
+        let status = match &result {
+            Ok(response) => response.status().as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        registry.inc_counter(
+            prometheus::HTTP_REQUESTS,
+            &[("pipeline", &self.name), ("status", &status)],
+            1.0,
+        );
+        registry.observe(
+            prometheus::HTTP_REQUEST_DURATION,
+            &[("pipeline", &self.name)],
+            started.elapsed().as_secs_f64(),
+        );
+        result
+    }
+
+    /// 發送請求前等待速率限制器放行
+    async fn wait_for_rate_limit(&self) {
+        if let Some(limiter) = &self.shared_rate_limiter {
+            limiter.acquire().await;
+        }
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+    }
+
+    /// 設定執行時間預算，接近截止時間時停止排程新的參數化呼叫
+    pub fn with_budget(mut self, budget: ExecutionBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// HTTP 用戶端；依 source.http 建立失敗（例如 CA 憑證或代理設定錯誤）時返回錯誤
+    fn client(&self) -> Result<&Client> {
+        self.client.as_ref().map_err(|e| EtlError::ConfigError {
+            message: format!("{}: failed to build HTTP client: {}", self.name, e),
+        })
+    }
+
+    fn is_near_deadline(&self) -> bool {
+        self.budget
+            .as_ref()
+            .map(|budget| budget.is_near_deadline())
+            .unwrap_or(false)
+    }
+
+    /// 收到停止訊號後不再發出新的參數化呼叫，已擷取的記錄照常輸出並標記為 partial
+    pub fn with_shutdown(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    fn is_interrupted(&self) -> bool {
+        self.shutdown
+            .as_ref()
+            .is_some_and(ShutdownSignal::is_requested)
+    }
+
+    /// 載入 data_enrichment 中設定的參照表
+    fn load_lookup_tables(&self) -> Result<HashMap<String, (LookupTable, LookupTableConfig)>> {
+        let Some(tables) = self
+            .config
+            .transform
+            .data_enrichment
+            .as_ref()
+            .and_then(|enrichment| enrichment.lookup_tables.as_ref())
+        else {
+            return Ok(HashMap::new());
+        };
+
+        tables
+            .iter()
+            .map(|(name, table_config)| {
+                let table = LookupTable::load(&table_config.path, &table_config.key)?;
+                Ok((name.clone(), (table, table_config.clone())))
+            })
+            .collect()
+    }
+
+    /// 檢查記錄數範圍
+    fn finish_validation(
+        &self,
+        validation: &ValidationConfig,
+        policy: InvalidRecordPolicy,
+        valid_count: usize,
+        dropped_count: usize,
+    ) -> Result<()> {
+        if let Some(message) = validate_record_count(valid_count, validation) {
+            if policy == InvalidRecordPolicy::Fail {
+                return Err(EtlError::DataValidationError {
+                    message: format!("{}: {}", self.name, message),
+                });
+            }
+            tracing::warn!("🔶 {}: {}", self.name, message);
+            self.warnings
+                .add(WarningCode::RecordCountOutOfRange, message);
+        }
+
+        if dropped_count > 0 {
+            tracing::warn!(
+                "🗑️ {}: Dropped {} invalid records",
+                self.name,
+                dropped_count
+            );
+            self.record_metadata("dropped_records", serde_json::json!(dropped_count));
+        }
+
+        Ok(())
+    }
+
+    /// 讀取回應並解析為 JSON 值（source.response_format = "xml" 時轉換 XML）；設定 source.encoding 時先轉為 UTF-8
+    async fn read_response_json(&self, response: Response) -> Result<serde_json::Value> {
+        let is_xml = self.config.source.response_format() == "xml";
+        let max_bytes = self.config.extract.max_response_bytes;
+        let Some(encoding_config) = &self.config.source.encoding else {
+            if let Some(limit) = max_bytes {
+                let Some(bytes) = self.read_limited_body(response, limit).await? else {
+                    return Ok(serde_json::Value::Array(Vec::new()));
+                };
+                let text = String::from_utf8_lossy(&bytes);
+                if is_xml {
+                    return xml::parse_document(&text);
+                }
+                return Ok(serde_json::from_str(&text)?);
+            }
+            if is_xml {
+                return xml::parse_document(&response.text().await?);
+            }
+            return Ok(response.json().await?);
+        };
+
+        let header_charset = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(encoding::charset_from_content_type);
+        let label = encoding_config
+            .charset
+            .clone()
+            .or(header_charset)
+            .unwrap_or_else(|| "utf-8".to_string());
+        let source_encoding = encoding::lookup(&label)?;
+
+        let bytes = match max_bytes {
+            Some(limit) => match self.read_limited_body(response, limit).await? {
+                Some(bytes) => bytes,
+                None => return Ok(serde_json::Value::Array(Vec::new())),
+            },
+            None => response.bytes().await?.to_vec(),
+        };
+        let text = encoding::decode_to_utf8(&bytes, source_encoding, encoding_config.is_strict())?;
+        if is_xml {
+            return xml::parse_document(&text);
+        }
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// 依 extract.max_response_bytes 讀取回應；超過上限時 fail 返回錯誤，truncate 略過該回應（返回 None）
+    ///
+    /// 截斷的 JSON 無法解析，因此 truncate 不保留部分內容。
+    async fn read_limited_body(&self, response: Response, limit: u64) -> Result<Option<Vec<u8>>> {
+        let endpoint = redact::redact(response.url().as_str(), &[]);
+        if let Some(bytes) = response_limits::read_limited_body(response, limit).await? {
+            return Ok(Some(bytes));
+        }
+        if self.config.extract.limit_policy()? == LimitPolicy::Fail {
+            return Err(EtlError::ResponseTooLarge {
+                endpoint,
+                limit: format!("{} bytes", limit),
+            });
+        }
+        tracing::warn!(
+            "✂️ {}: Response from {} exceeded {} bytes, skipped",
+            self.name,
+            endpoint,
+            limit
+        );
+        self.warnings.add(
+            WarningCode::ResponseLimitExceeded,
+            format!("Response from {} exceeded {} bytes", endpoint, limit),
+        );
+        Ok(None)
+    }
+
+    /// View 的記錄直接取自來源 Pipeline 的結果，不重新呼叫 API
+    fn view_records(&self, context: &PipelineContext) -> Result<Vec<Record>> {
+        let from_pipeline = self
+            .config
+            .source
+            .data_source
+            .as_ref()
+            .and_then(|data_source| data_source.from_pipeline.as_deref())
+            .ok_or_else(|| EtlError::ConfigValidationError {
+                field: "source.data_source.from_pipeline".to_string(),
+                message: "View pipelines require a source pipeline".to_string(),
+            })?;
+
+        let records = context
+            .get_pipeline_data(from_pipeline)?
+            .map(Cow::into_owned)
+            .ok_or_else(|| EtlError::ProcessingError {
+                message: format!(
+                    "{}: Source pipeline '{}' has no results to view",
+                    self.name, from_pipeline
+                ),
+            })?;
+        tracing::info!(
+            "🪞 {}: Re-loading {} records from '{}'",
+            self.name,
+            records.len(),
+            from_pipeline
+        );
+        Ok(records)
+    }
+
+    /// 合併 source.join 指定的兩個 Pipeline 輸出
+    fn join_sources(&self, context: &PipelineContext, join: &JoinConfig) -> Result<Vec<Record>> {
+        let upstream = |name: &str| {
+            context
+                .get_pipeline_data(name)?
+                .ok_or_else(|| EtlError::ProcessingError {
+                    message: format!(
+                        "{}: Join source pipeline '{}' has no results",
+                        self.name, name
+                    ),
+                })
+        };
+        let (Some(left_key), Some(right_key)) = (join.left_key(), join.right_key()) else {
+            return Err(EtlError::ConfigValidationError {
+                field: "source.join".to_string(),
+                message: "Join requires 'on' or both 'left_key' and 'right_key'".to_string(),
+            });
+        };
+        let join_type = join.join_type("source.join.type")?;
+        let left = upstream(&join.left)?;
+        let right = upstream(&join.right)?;
+
+        let records = join_records(
+            &left,
+            &right,
+            left_key,
+            right_key,
+            join_type,
+            join.right_prefix(),
+        );
+        tracing::info!(
+            "🔗 {}: {:?} join of '{}' ({} records) and '{}' ({} records) produced {} records",
+            self.name,
+            join_type,
+            join.left,
+            left.len(),
+            join.right,
+            right.len(),
+            records.len()
+        );
+        Ok(records)
+    }
+
+    /// 依序聯集 source.combined 指定的 Pipeline 輸出，可依鍵去除重複並標記來源
+    fn combine_sources(
+        &self,
+        context: &PipelineContext,
+        combined: &CombinedConfig,
+    ) -> Result<Vec<Record>> {
+        let upstream = combined
+            .pipelines
+            .iter()
+            .map(|name| {
+                let records =
+                    context
+                        .get_pipeline_data(name)?
+                        .ok_or_else(|| EtlError::ProcessingError {
+                            message: format!(
+                                "{}: Combined source pipeline '{}' has no results",
+                                self.name, name
+                            ),
+                        })?;
+                Ok((name.as_str(), records))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let sources: Vec<(&str, &[Record])> = upstream
+            .iter()
+            .map(|(name, records)| (*name, &records[..]))
+            .collect();
+
+        let (records, duplicates) = union_records(
+            &sources,
+            combined.dedup_keys.as_deref().unwrap_or_default(),
+            combined.source_field.as_deref(),
+        );
+        tracing::info!(
+            "🧩 {}: Combined {} pipelines into {} records ({} duplicates removed)",
+            self.name,
+            sources.len(),
+            records.len(),
+            duplicates
+        );
+        if combined.dedup_keys.is_some() {
+            self.record_metadata("duplicates_removed", serde_json::json!(duplicates));
+        }
+        Ok(records)
+    }
+
+    /// 是否將範本替換與參數化 API 呼叫失敗的記錄寫入 dead-letter，而非中止
+    fn dead_letter_enabled(&self) -> bool {
+        self.config
+            .dead_letter
+            .as_ref()
+            .map(|dead_letter| dead_letter.is_enabled())
+            .unwrap_or(false)
+    }
+
+    /// 參數化呼叫單筆失敗的處理方式；未設定 on_record_error 時，啟用 dead_letter 則寫入 rejects，否則中止
+    fn record_error_policy(&self) -> Result<RecordErrorPolicy> {
+        match &self.config.source.on_record_error {
+            Some(policy) => RecordErrorPolicy::parse(policy),
+            None if self.dead_letter_enabled() => Ok(RecordErrorPolicy::DeadLetter),
+            None => Ok(RecordErrorPolicy::Fail),
+        }
+    }
+
+    /// 依 on_record_error 處理單筆參數的失敗，返回 Err 時中止 Pipeline
+    fn handle_record_error(
+        &self,
+        policy: RecordErrorPolicy,
+        stage: &str,
+        error: EtlError,
+        record: &HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        match policy {
+            RecordErrorPolicy::Fail => Err(error),
+            RecordErrorPolicy::DeadLetter => self.reject_record(stage, error.to_string(), record),
+            RecordErrorPolicy::Skip => {
+                let mut parameters = serde_json::to_value(record)?;
+                redact::redact_json(&mut parameters);
+                let reason = redact::redact_sensitive(&error.to_string());
+                tracing::warn!(
+                    "⏭️ {}: Skipped parameterized call at {} with parameters {}: {}",
+                    self.name,
+                    stage,
+                    parameters,
+                    reason
+                );
+                self.warnings.add(
+                    WarningCode::RecordCallFailed,
+                    format!("Skipped call with parameters {}: {}", parameters, reason),
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// 將記錄加入 dead-letter，超過 max_rejects 時返回錯誤
+    fn reject_record(
+        &self,
+        stage: &str,
+        reason: String,
+        record: &HashMap<String, serde_json::Value>,
+    ) -> Result<()> {
+        tracing::warn!("🚫 {}: Rejected record at {}: {}", self.name, stage, reason);
+        let count = self.dead_letters.push(stage, reason, record.clone());
+
+        let max_rejects = self
+            .config
+            .dead_letter
+            .as_ref()
+            .and_then(|dead_letter| dead_letter.max_rejects);
+        if let Some(max_rejects) = max_rejects {
+            if count > max_rejects {
+                return Err(EtlError::ProcessingError {
+                    message: format!(
+                        "{}: Rejected records exceeded max_rejects ({})",
+                        self.name, max_rejects
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// 依共享數據寫入策略導出，衝突時記錄警告；返回是否已寫入
+    fn export_shared_data(
+        &self,
+        context: &mut PipelineContext,
+        key: String,
+        value: serde_json::Value,
+    ) -> bool {
+        match context.write_shared_data(&self.name, key.clone(), value) {
+            SharedDataWrite::Written => true,
+            SharedDataWrite::Overwritten { previous_producer } => {
+                tracing::warn!(
+                    "📤 {}: Overwrote shared data '{}' written by '{}'",
+                    self.name,
+                    key,
+                    previous_producer
+                );
+                self.warnings.add(
+                    WarningCode::SharedDataConflict,
+                    format!(
+                        "Overwrote shared data '{}' written by '{}'",
+                        key, previous_producer
+                    ),
+                );
+                true
+            }
+            SharedDataWrite::Rejected { owner } => {
+                tracing::warn!(
+                    "📤 {}: Shared data '{}' is owned by '{}', write ignored",
+                    self.name,
+                    key,
+                    owner
+                );
+                self.warnings.add(
+                    WarningCode::SharedDataConflict,
+                    format!(
+                        "Shared data '{}' is owned by '{}', write ignored",
+                        key, owner
+                    ),
+                );
+                false
+            }
+        }
+    }
+
+    /// 寫出 dead-letter rejects 檔（每筆附失敗原因）
+    async fn write_dead_letters(&self) -> Result<()> {
+        let dead_letters = self.dead_letters.take();
+        if dead_letters.is_empty() {
+            return Ok(());
+        }
+
+        let relative_path = match &self.config.dead_letter {
+            Some(dead_letter) => dead_letter.path(&self.name),
+            None => format!("{}_rejects.json", self.name),
+        };
+        // 存儲已以 output_path 為根目錄，寫入相對路徑；回報時才組出完整路徑
+        let json = serde_json::to_string_pretty(&dead_letters)?;
+        self.storage
+            .write_file(&relative_path, json.as_bytes())
+            .await?;
+        let path = format!("{}/{}", self.config.load.output_path, relative_path);
+
+        tracing::warn!(
+            "🚫 {}: Wrote {} rejected records to {}",
+            self.name,
+            dead_letters.len(),
+            path
+        );
+        self.record_metadata("rejected_records", serde_json::json!(dead_letters.len()));
+        self.record_metadata("rejects_path", serde_json::json!(path));
+        Ok(())
+    }
+
+    fn remaining_parameters_path(&self) -> String {
+        format!(".checkpoints/{}_remaining.json", self.name)
+    }
+
+    /// 將尚未處理的參數記錄寫入存儲，供下次執行接續
+    async fn save_remaining_parameters(
+        &self,
+        remaining: &[Record],
+        context: &PipelineContext,
+    ) -> Result<String> {
+        let path = self.remaining_parameters_path();
+        let remaining = RemainingParameters {
+            execution_id: context.execution_id.clone(),
+            parameters: remaining.iter().map(|record| record.data.clone()).collect(),
+        };
+        let json = serde_json::to_string_pretty(&remaining)?;
+        let data = seal_state(self.state_cipher.as_deref(), json.as_bytes(), &path)?;
+        self.storage.write_file(&path, &data).await?;
+        Ok(path)
+    }
+
+    /// 讀取上次執行留下的參數記錄，不存在時為 None
+    async fn load_remaining_parameters(&self) -> Result<Option<RemainingParameters>> {
+        let path = self.remaining_parameters_path();
+        let bytes = match self.storage.read_file(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.is_not_found() => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let data = open_state(self.state_cipher.as_deref(), &bytes, &path)?;
+        Ok(Some(serde_json::from_slice(&data)?))
+    }
+
+    /// 組合決定擷取結果的請求內容，作為快取鍵的來源：API 來源使用實際送出的請求
+    /// （含查詢參數的 URL、方法、自訂標頭與 body），參數化呼叫時每筆參數記錄各一個請求
+    fn extract_cache_request(&self, context: &PipelineContext) -> Result<serde_json::Value> {
+        let source = &self.config.source;
+        let parameter_records = self.parameter_source_records(context)?;
+        let requests = match self.source_endpoint() {
+            Some(endpoint)
+                if !["previous", "combined", "join", "files", "s3"]
+                    .contains(&source.r#type.as_str()) =>
+            {
+                if source.batch_parameters.is_none() && endpoint.contains('{') {
+                    parameter_records
+                        .iter()
+                        .map(|record| {
+                            match self.build_parameterized_endpoint(&record.data) {
+                                Ok(endpoint) => {
+                                    self.rendered_request(&endpoint, Some(&record.data), context)
+                                }
+                                // 無法組出端點的記錄不會發出請求，以錯誤內容代表
+                                Err(e) => Ok(serde_json::json!({ "error": e.to_string() })),
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                } else {
+                    vec![self.rendered_request(&endpoint, None, context)?]
+                }
+            }
+            _ => Vec::new(),
+        };
+        let parameter_records: Vec<_> = parameter_records
+            .iter()
+            .map(|record| {
+                record
+                    .data
+                    .iter()
+                    .collect::<std::collections::BTreeMap<_, _>>()
+            })
+            .collect();
+
+        Ok(serde_json::json!({
+            "type": source.r#type,
+            "requests": requests,
+            "parameter_records": parameter_records,
+        }))
+    }
+
+    /// 依設定組出實際送出的請求內容（不發出請求、不記錄警告）
+    fn rendered_request(
+        &self,
+        endpoint: &str,
+        record_data: Option<&HashMap<String, serde_json::Value>>,
+        context: &PipelineContext,
+    ) -> Result<serde_json::Value> {
+        let source = &self.config.source;
+        let method = source.method.as_deref().unwrap_or("GET").to_uppercase();
+
+        // 查詢參數與送出時相同，由 reqwest 編碼到 URL
+        let mut request = self.client()?.get(endpoint);
+        for (key, value) in source.parameters.iter().flatten() {
+            request = request.query(&[(key, self.apply_checkpoint_template(value))]);
+        }
+        let url = request
+            .build()
+            .map(|request| request.url().to_string())
+            .unwrap_or_else(|_| endpoint.to_string());
+
+        let headers = source
+            .headers
+            .iter()
+            .flatten()
+            .map(|(name, template)| {
+                Ok((
+                    name.to_ascii_lowercase(),
+                    self.render_header_template(template, record_data, context)?,
+                ))
+            })
+            .collect::<Result<std::collections::BTreeMap<_, _>>>()?;
+
+        let render_fields = |fields: Option<&HashMap<String, String>>| {
+            fields
+                .into_iter()
+                .flatten()
+                .map(|(name, template)| {
+                    Ok((
+                        name.clone(),
+                        self.render_payload_template(template, record_data, context)?,
+                    ))
+                })
+                .collect::<Result<std::collections::BTreeMap<_, _>>>()
+        };
+        let body = match &source.payload {
+            Some(payload) if payload.form.is_some() => {
+                serde_json::json!({ "form": render_fields(payload.form.as_ref())? })
+            }
+            Some(payload) if payload.multipart.is_some() => {
+                let multipart = payload.multipart.as_ref();
+                let files = multipart
+                    .and_then(|multipart| multipart.files.as_ref())
+                    .into_iter()
+                    .flatten()
+                    .map(|file| {
+                        Ok((
+                            file.name.clone(),
+                            self.render_payload_template(&file.path, record_data, context)?,
+                        ))
+                    })
+                    .collect::<Result<std::collections::BTreeMap<_, _>>>()?;
+                serde_json::json!({
+                    "multipart": render_fields(multipart.and_then(|m| m.fields.as_ref()))?,
+                    "files": files,
+                })
+            }
+            Some(payload) => match &payload.body {
+                Some(body) => serde_json::json!({
+                    "content_type": payload.content_type,
+                    "body": self.render_payload_template(body, record_data, context)?,
+                }),
+                None => serde_json::Value::Null,
+            },
+            None => serde_json::Value::Null,
+        };
+
+        Ok(serde_json::json!({
+            "method": method,
+            "url": url,
+            "headers": headers,
+            "body": body,
+        }))
+    }
+
+    /// 替換模板中的內建佔位符（{{now}}、{{execution_id}}…）與 checkpoint 佔位符
+    fn apply_checkpoint_template(&self, template: &str) -> String {
+        let template = match self.builtins.lock() {
+            Ok(builtins) => match builtins.as_ref() {
+                Some(builtins) => builtins.apply(template),
+                None => template.to_string(),
+            },
+            Err(_) => template.to_string(),
+        };
+        match self.checkpoint_state.lock() {
+            Ok(state) => match state.as_ref() {
+                Some(state) => state.apply_template(&template),
+                None => template,
+            },
+            Err(_) => template,
+        }
+    }
+
+    /// 替換模板中的 {{lookup:...}} 參照（未設定 context_index 時原樣返回）
+    fn apply_context_lookups(
+        &self,
+        template: &str,
+        record_data: Option<&HashMap<String, serde_json::Value>>,
+    ) -> Result<String> {
+        match self.context_index.lock() {
+            Ok(index) => match index.as_ref() {
+                Some(index) => resolve_lookups(template, record_data, index),
+                None => Ok(template.to_string()),
+            },
+            Err(_) => Ok(template.to_string()),
+        }
+    }
+
+    /// 取得已套用 checkpoint 值的來源端點
+    /// 擷取失敗時附上遮蔽憑證後的來源端點
+    fn endpoint_context(&self, error: EtlError) -> EtlError {
+        match self.source_endpoint() {
+            Some(endpoint) => error.at_endpoint(redact::redact(&endpoint, &[])),
+            None => error,
+        }
+    }
+
+    fn source_endpoint(&self) -> Option<String> {
+        self.config
+            .source
+            .endpoint
+            .as_deref()
+            .map(|endpoint| self.apply_checkpoint_template(endpoint))
+    }
+
+    /// 輸出檔內容；csv 依 load.csv 的 encoding 與 bom 轉換，其他格式為 UTF-8
+    fn output_bytes(&self, output_format: &str, text: String) -> Result<Vec<u8>> {
+        match (output_format, &self.config.load.csv) {
+            ("csv", Some(csv)) => encode_csv(&text, csv),
+            _ => Ok(text.into_bytes()),
+        }
+    }
+
+    /// load.compression.encryption 的加密器（讀取金鑰檔或環境變數）
+    fn output_encryption(&self) -> Result<Option<OutputEncryption>> {
+        self.config
+            .load
+            .compression
+            .as_ref()
+            .and_then(|compression| compression.encryption.as_ref())
+            .map(OutputEncryption::from_config)
+            .transpose()
+    }
+
+    fn archive_format(&self) -> Result<ArchiveFormat> {
+        Ok(self
+            .config
+            .load
+            .compression
+            .as_ref()
+            .map(|compression| compression.archive_format())
+            .transpose()?
+            .unwrap_or(ArchiveFormat::Zip))
+    }
+
+    /// 檔名模板的值（pipeline_name、execution_id、sequence_name 與共享數據）
+    fn filename_values<'a>(&'a self, context: &'a PipelineContext) -> FilenameValues<'a> {
+        FilenameValues {
+            sequence_name: self.sequence_name.as_deref(),
+            shared: Some(context.all_shared_data()),
+            ..FilenameValues::new(&self.name, &context.execution_id, context.run_started_at)
+        }
+    }
+
+    /// 依 filename_pattern 與 load.write_mode 決定輸出檔名；error_if_exists 時輸出已存在即返回錯誤
+    ///
+    /// `record_count` 為 None（extract 前的檢查）時 {record_count} 保留原樣。
+    async fn resolve_output_filename(
+        &self,
+        context: &PipelineContext,
+        format: ArchiveFormat,
+        record_count: Option<usize>,
+    ) -> Result<String> {
+        let filename = if let Some(pattern) = &self.config.load.filename_pattern {
+            // 先處理 {{now:%Y%m%d}} 等內建佔位符，再替換 {pipeline_name} 等檔名變數
+            let pattern = BuiltinValues::new(&context.execution_id, context.run_started_at)
+                .with_item(context.foreach_item.clone())
+                .apply(pattern);
+            FilenameValues {
+                record_count,
+                ..self.filename_values(context)
+            }
+            .render(&pattern)
+        } else {
+            format!("{}_output{}", self.name, format.extension())
+        };
+        // aes_gcm 加密的封裝檔加上 .enc（未壓縮目錄改為逐檔加上）
+        let encrypted = format != ArchiveFormat::None
+            && self
+                .config
+                .load
+                .compression
+                .as_ref()
+                .and_then(|compression| compression.encryption.as_ref())
+                .map(|encryption| encryption.method())
+                .transpose()?
+                == Some(EncryptionMethod::AesGcm);
+        let (filename, extension) = if encrypted && !filename.ends_with(ENCRYPTED_EXTENSION) {
+            (
+                format!("{}{}", filename, ENCRYPTED_EXTENSION),
+                format!("{}{}", format.extension(), ENCRYPTED_EXTENSION),
+            )
+        } else {
+            (filename, format.extension().to_string())
+        };
+        resolve_output_name(
+            &self.storage,
+            &filename,
+            &extension,
+            self.config.load.write_mode()?,
+        )
+        .await
+    }
+
+    /// null_policy 為 skip_record 時，記錄該輸出格式略過的筆數
+    fn report_null_skips(&self, output_format: &str, records: &[Record]) -> Result<usize> {
+        let skipped = self.null_skipped(output_format, records)?;
+        if skipped > 0 {
+            tracing::warn!(
+                "🕳️ {}: Skipped {} records with missing values in {} output",
+                self.name,
+                skipped,
+                output_format
+            );
+            self.warnings.add(
+                WarningCode::NullRecordsSkipped,
+                format!(
+                    "Skipped {} records with missing values in {} output",
+                    skipped, output_format
+                ),
+            );
+            self.record_metadata(
+                &format!("null_skipped_{}", output_format),
+                serde_json::json!(skipped),
+            );
+        }
+        Ok(skipped)
+    }
+
+    /// null_policy 為 skip_record 時，該輸出格式會略過的筆數
+    fn null_skipped(&self, output_format: &str, records: &[Record]) -> Result<usize> {
+        if self.config.load.null_policy(output_format)? != NullPolicy::SkipRecord {
+            return Ok(0);
+        }
+        let columns = match output_format {
+            "json" => None,
+            _ => self.config.load.columns.as_deref(),
+        };
+        let fields = output_fields(records, columns);
+        Ok(records
+            .iter()
+            .filter(|record| missing_field(record, &fields).is_some())
+            .count())
+    }
+
+    /// 寫出追加輸出（load.append）與封裝後的輸出檔，返回封裝檔寫入的位元組數
+    async fn write_outputs<T: Storage>(
+        &self,
+        storage: &T,
+        archive: OutputArchive,
+        filename: &str,
+        result: &TransformResult,
+    ) -> Result<u64> {
+        // 追加輸出（依 schema_evolution 策略處理欄位變動）
+        if let Some(append) = &self.config.load.append {
+            let policy = SchemaEvolutionPolicy::parse(
+                append.schema_evolution.as_deref().unwrap_or("add_columns"),
+            )?;
+            // 只有檔案不存在才視為新檔；其他讀取錯誤若當作空檔會覆蓋既有資料
+            let existing = match storage.read_file(&append.path).await {
+                Ok(bytes) => Some(bytes),
+                Err(e) if e.is_not_found() => None,
+                Err(e) => return Err(e),
+            };
+            let (bytes, report) =
+                append_csv(existing.as_deref(), &result.processed_records, policy)?;
+            storage.write_file(&append.path, &bytes).await?;
+
+            if !report.added_columns.is_empty() {
+                tracing::info!(
+                    "🧩 {}: Added columns {:?} to appended output '{}'",
+                    self.name,
+                    report.added_columns,
+                    append.path
+                );
+            }
+            if !report.ignored_fields.is_empty() {
+                tracing::warn!(
+                    "🧩 {}: Ignored new fields {:?} for appended output '{}'",
+                    self.name,
+                    report.ignored_fields,
+                    append.path
+                );
+                self.warnings.add(
+                    WarningCode::IgnoredFields,
+                    format!(
+                        "Ignored new fields {:?} for appended output '{}'",
+                        report.ignored_fields, append.path
+                    ),
+                );
+            }
+            self.record_metadata(
+                "append_output",
+                serde_json::json!({
+                    "path": append.path,
+                    "columns": report.columns,
+                    "added_columns": report.added_columns,
+                    "ignored_fields": report.ignored_fields,
+                    "appended_rows": report.appended_rows,
+                }),
+            );
+        }
+
+        // 依壓縮格式寫出（ZIP、tar.gz 或未壓縮目錄）
+        archive.write_to(storage, filename).await
+    }
+
+    /// 記錄本次執行的 metadata，供 PipelineSequence 取出
+    fn record_metadata(&self, key: &str, value: serde_json::Value) {
+        if let Ok(mut metadata) = self.execution_metadata.lock() {
+            metadata.insert(key.to_string(), value);
+        }
+    }
+
+    /// 記錄數對帳：結果放入 metadata，差異依 on_mismatch 記錄警告或中止 Pipeline
+    fn reconcile(
+        &self,
+        check: impl FnOnce(&mut Reconciliation) -> Option<Discrepancy>,
+    ) -> Result<()> {
+        let Some(config) = &self.config.reconciliation else {
+            return Ok(());
+        };
+        let Ok(mut state) = self.reconciliation.lock() else {
+            return Ok(());
+        };
+        let reconciliation = state.get_or_insert_with(|| Reconciliation::new(config.tolerance()));
+        let discrepancy = check(reconciliation);
+        self.record_metadata("reconciliation", reconciliation.to_value());
 
-/// 基於序列配置的上下文感知 Pipeline
-pub struct SequenceAwarePipeline<S: Storage> {
-    name: String,
-    storage: S,
-    config: PipelineDefinition,
-    client: Client,
-}
+        let Some(discrepancy) = discrepancy else {
+            return Ok(());
+        };
+        let message = format!("Record count mismatch: {}", discrepancy);
+        if config.policy()? == MismatchPolicy::Fail {
+            tracing::error!("🧮 {}: {}", self.name, message);
+            return Err(EtlError::DataValidationError {
+                message: format!("{}: {}", self.name, message),
+            });
+        }
+        tracing::warn!("🧮 {}: {}", self.name, message);
+        self.warnings.add(WarningCode::RecordCountMismatch, message);
+        Ok(())
+    }
 
-impl<S: Storage> SequenceAwarePipeline<S> {
-    pub fn new(name: String, storage: S, config: PipelineDefinition) -> Self {
-        Self {
-            name,
-            storage,
-            config,
-            client: Client::new(),
+    /// 擷取是否因停止訊號提前結束
+    fn is_partial(&self) -> bool {
+        self.execution_metadata
+            .lock()
+            .is_ok_and(|metadata| metadata.contains_key("partial"))
+    }
+
+    /// 輸出 CSV 與 TSV 內容；strict_columns 開啟時記錄含 load.columns 未列出的欄位即返回錯誤
+    fn render_delimited(&self, records: &[Record]) -> Result<(String, String)> {
+        if let Some(columns) = self.config.load.columns.as_deref() {
+            if self.config.load.strict_columns() {
+                check_unknown_columns(records, columns)?;
+            }
         }
+        Ok((
+            render_output("csv", records, &self.config.load)?,
+            render_output("tsv", records, &self.config.load)?,
+        ))
     }
 
     /// 決定數據來源：API、前一個 Pipeline 或合併
     async fn determine_data_source(&self, context: &PipelineContext) -> Result<Vec<Record>> {
-        let mut records = Vec::new();
+        if self.config.source.r#type == "join" {
+            if let Some(join) = &self.config.source.join {
+                return self.join_sources(context, join);
+            }
+        }
+        if self.config.source.r#type == "combined" {
+            if let Some(combined) = &self.config.source.combined {
+                return self.combine_sources(context, combined);
+            }
+        }
+        if self.config.source.r#type == "files" {
+            if let Some(files) = &self.config.source.files {
+                return self.read_files(files).await;
+            }
+        }
+        if self.config.source.r#type == "s3" {
+            if let Some(s3) = &self.config.source.s3 {
+                return self.read_s3(s3).await;
+            }
+        }
+
+        // 前一個 Pipeline 的記錄先以參照取得，確定要輸出時才複製（參數化呼叫只讀取參數）
+        let mut upstream: Cow<'_, [Record]> = Cow::Borrowed(&[]);
 
         // 檢查是否使用前一個 Pipeline 的輸出
         if let Some(data_source) = &self.config.source.data_source {
@@ -37,84 +1197,350 @@ impl<S: Storage> SequenceAwarePipeline<S> {
                 if let Some(from_pipeline) = &data_source.from_pipeline {
                     // 使用指定 Pipeline 的輸出
                     if let Some(pipeline_result) = context.get_result_by_name(from_pipeline) {
-                        records.extend(pipeline_result.records.clone());
+                        upstream = context.records_of(pipeline_result)?;
                         tracing::info!(
                             "📂 {}: Using {} records from pipeline '{}'",
                             self.name,
-                            records.len(),
+                            upstream.len(),
                             from_pipeline
                         );
                     }
                 } else {
                     // 使用前一個 Pipeline 的輸出
                     if let Some(previous_result) = context.get_previous_result() {
-                        records.extend(previous_result.records.clone());
+                        upstream = context.records_of(previous_result)?;
                         tracing::info!(
                             "📂 {}: Using {} records from previous pipeline",
                             self.name,
-                            records.len()
+                            upstream.len()
                         );
                     }
                 }
 
                 // 如果設定為合併，還需要獲取 API 數據
                 // 但對於參數化 API（含 {param}），即使 merge_with_api = false 也需要執行 API 呼叫
-                let endpoint = self.config.source.endpoint.as_deref().unwrap_or("");
+                let endpoint = self.source_endpoint().unwrap_or_default();
                 if !data_source.merge_with_api.unwrap_or(false) && !endpoint.contains("{") {
-                    return Ok(records);
+                    return Ok(upstream.into_owned());
                 }
             }
         }
 
         // 獲取 API 數據 - 檢查是否需要參數化呼叫
-        let endpoint = self.config.source.endpoint.as_deref().unwrap_or("");
+        let endpoint = self.source_endpoint().unwrap_or_default();
 
         // 對於 "previous" 和 "combined" 類型，不進行 API 呼叫
         if self.config.source.r#type == "previous" || self.config.source.r#type == "combined" {
-            return Ok(records);
+            return Ok(upstream.into_owned());
         }
 
         // 如果沒有端點，也不進行 API 呼叫
         if endpoint.is_empty() {
-            return Ok(records);
+            return Ok(upstream.into_owned());
         }
 
-        let api_records = if endpoint.contains("{") {
+        let api_records = if self.config.source.batch_parameters.is_some() {
+            // 批次參數化 API 呼叫 - 多個值合併至同一 URL
+            return self.fetch_batched_parameterized_api(context).await;
+        } else if endpoint.contains("{") {
             // 參數化 API 呼叫 - 替換前一個 pipeline 的數據
             return self.fetch_parameterized_api(context).await;
         } else {
             // 標準 API 呼叫
             self.fetch_api_data(context).await?
         };
+        let mut records = upstream.into_owned();
         records.extend(api_records);
 
         Ok(records)
     }
 
-    /// 處理參數化 API 呼叫（為每個前一個記錄分別呼叫）
-    async fn fetch_parameterized_api(&self, context: &PipelineContext) -> Result<Vec<Record>> {
-        let mut all_records = Vec::new();
+    /// 讀取 glob 比對到的檔案：依 concurrency 分批在背景執行緒讀取與解析，依檔名順序合併記錄
+    async fn read_files(&self, files: &FilesSourceConfig) -> Result<Vec<Record>> {
+        let mut paths = file_glob::expand(&files.pattern)?;
+        tracing::info!(
+            "📂 {}: Matched {} files for '{}'",
+            self.name,
+            paths.len(),
+            files.pattern
+        );
+
+        // 略過 ledger 中已處理的檔案；成功載入後才記入 ledger
+        if let Some(ledger) = &files.ledger {
+            let pending = FileLedger::load(ledger)?.unprocessed(paths)?;
+            tracing::info!(
+                "📒 {}: {} files not yet processed according to {}",
+                self.name,
+                pending.len(),
+                ledger
+            );
+            paths = pending.iter().map(|(path, _)| path.clone()).collect();
+            if let Ok(mut current) = self.pending_files.lock() {
+                *current = pending;
+            }
+        }
+
+        let mut records = Vec::new();
+        for batch in paths.chunks(files.concurrency()) {
+            let mut tasks = tokio::task::JoinSet::new();
+            for (index, path) in batch.iter().enumerate() {
+                let path = path.clone();
+                let format = match files.format_for(&path) {
+                    "csv" => StreamInputFormat::Csv,
+                    _ => StreamInputFormat::Json,
+                };
+                tasks.spawn_blocking(move || {
+                    let parsed = std::fs::read(&path)
+                        .map_err(EtlError::from)
+                        .and_then(|bytes| parse_input(&bytes, format))
+                        .map_err(|e| EtlError::DataValidationError {
+                            message: format!("Failed to read '{}': {}", path.display(), e),
+                        });
+                    (index, parsed)
+                });
+            }
+
+            let mut parsed = Vec::with_capacity(batch.len());
+            while let Some(result) = tasks.join_next().await {
+                parsed.push(result.map_err(|e| EtlError::ProcessingError {
+                    message: format!("File reader task failed: {}", e),
+                })?);
+            }
+            parsed.sort_by_key(|(index, _)| *index);
+
+            for (index, value) in parsed {
+                let source_file = batch[index].to_string_lossy().to_string();
+                let mut file_records = self.records_from_json(value?)?;
+                for record in &mut file_records {
+                    record.data.insert(
+                        files.filename_field().to_string(),
+                        serde_json::Value::String(source_file.clone()),
+                    );
+                }
+                tracing::debug!(
+                    "📄 {}: Read {} records from {}",
+                    self.name,
+                    file_records.len(),
+                    source_file
+                );
+                records.extend(file_records);
+            }
+        }
+        self.record_metadata("files", serde_json::json!(paths.len()));
+        Ok(records)
+    }
+
+    #[cfg(feature = "s3")]
+    async fn read_s3(&self, config: &S3SourceConfig) -> Result<Vec<Record>> {
+        self.read_objects(&object_source::s3_storage(config).await, config)
+            .await
+    }
+
+    #[cfg(not(feature = "s3"))]
+    async fn read_s3(&self, _config: &S3SourceConfig) -> Result<Vec<Record>> {
+        Err(EtlError::ConfigValidationError {
+            field: "source.s3".to_string(),
+            message: "S3 sources require building with --features s3".to_string(),
+        })
+    }
 
-        // 獲取前一個 Pipeline 的記錄作為參數源
-        let param_records = if let Some(data_source) = &self.config.source.data_source {
+    /// 讀取存儲中 source.s3 前綴下的物件：每個物件的記錄套用欄位映射並加上物件鍵
+    pub async fn read_objects<T: Storage>(
+        &self,
+        storage: &T,
+        config: &S3SourceConfig,
+    ) -> Result<Vec<Record>> {
+        let objects = object_source::read_objects(storage, config).await?;
+        tracing::info!(
+            "🪣 {}: Read {} objects under s3://{}/{}",
+            self.name,
+            objects.len(),
+            config.bucket,
+            config.prefix()
+        );
+
+        let mut records = Vec::new();
+        for (key, value) in &objects {
+            let mut object_records = self.records_from_json(value.clone())?;
+            for record in &mut object_records {
+                record.data.insert(
+                    config.key_field().to_string(),
+                    serde_json::Value::String(key.clone()),
+                );
+            }
+            records.extend(object_records);
+        }
+        self.record_metadata("objects", serde_json::json!(objects.len()));
+        Ok(records)
+    }
+
+    /// 獲取前一個 Pipeline 的記錄作為參數源
+    fn parameter_source_records<'a>(
+        &self,
+        context: &'a PipelineContext,
+    ) -> Result<Cow<'a, [Record]>> {
+        if let Some(data_source) = &self.config.source.data_source {
             if data_source.use_previous_output.unwrap_or(false) {
-                if let Some(from_pipeline) = &data_source.from_pipeline {
-                    context
-                        .get_result_by_name(from_pipeline)
-                        .map(|r| r.records.clone())
-                        .unwrap_or_default()
-                } else {
-                    context
-                        .get_previous_result()
-                        .map(|r| r.records.clone())
-                        .unwrap_or_default()
+                let result = match &data_source.from_pipeline {
+                    Some(from_pipeline) => context.get_result_by_name(from_pipeline),
+                    None => context.get_previous_result(),
+                };
+                if let Some(result) = result {
+                    return context.records_of(result);
                 }
-            } else {
-                Vec::new()
             }
-        } else {
-            Vec::new()
+        }
+        Ok(Cow::Borrowed(&[]))
+    }
+
+    /// 擷取期間的記憶體背壓：每個 Pipeline 只在第一次超過上限時暫停等待回落，
+    /// 之後仍超過上限就中止，不在每次呼叫前都等待最長暫停時間
+    async fn check_memory_headroom(
+        &self,
+        context: &PipelineContext,
+        paused: &mut bool,
+    ) -> Result<()> {
+        let Some(memory_pressure) = context.memory_pressure() else {
+            return Ok(());
         };
+        if !memory_pressure.is_over_limit() {
+            return Ok(());
+        }
+        if !*paused {
+            *paused = true;
+            memory_pressure.wait_for_headroom(&self.name).await;
+            if !memory_pressure.is_over_limit() {
+                return Ok(());
+            }
+        }
+        Err(EtlError::ResourceExhaustedError {
+            resource: "memory".to_string(),
+            details: format!(
+                "{}: process memory {}MB still exceeds the {}MB limit after pausing extraction",
+                self.name,
+                memory_pressure.current_mb().unwrap_or_default(),
+                memory_pressure.limit_mb()
+            ),
+        })
+    }
+
+    /// 處理批次參數化 API 呼叫：將參數值合併到 URL，超過長度上限時拆分成多次呼叫
+    async fn fetch_batched_parameterized_api(
+        &self,
+        context: &PipelineContext,
+    ) -> Result<Vec<Record>> {
+        let batch = self
+            .config
+            .source
+            .batch_parameters
+            .as_ref()
+            .ok_or_else(|| EtlError::ConfigValidationError {
+                field: "source.batch_parameters".to_string(),
+                message: "Batch parameters are required for batched API calls".to_string(),
+            })?;
+        let endpoint = self
+            .source_endpoint()
+            .ok_or_else(|| EtlError::ConfigValidationError {
+                field: "source.endpoint".to_string(),
+                message: "Endpoint is required for batched API calls".to_string(),
+            })?;
+
+        let values: Vec<String> = self
+            .parameter_source_records(context)?
+            .iter()
+            .filter_map(|record| record.data.get(&batch.field))
+            .map(|value| match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            // 值可能放在路徑片段中，空白編碼為 %20 而不是表單編碼的 +
+            .map(|value| url_encode(&value))
+            .collect();
+
+        let placeholder = format!("{{{}}}", batch.placeholder);
+        let endpoints = split_batched_endpoints(
+            &endpoint,
+            &placeholder,
+            &values,
+            batch.separator(),
+            batch.max_url_length(),
+        );
+
+        tracing::info!(
+            "📡 {}: Making {} batched API calls for {} values (max URL length: {})",
+            self.name,
+            endpoints.len(),
+            values.len(),
+            batch.max_url_length()
+        );
+
+        let mut all_records = Vec::new();
+        let mut memory_paused = false;
+        for (index, batch_endpoint) in endpoints.iter().enumerate() {
+            if batch_endpoint.len() > batch.max_url_length() {
+                tracing::warn!(
+                    "📡 {}: Single value exceeds max URL length ({} > {})",
+                    self.name,
+                    batch_endpoint.len(),
+                    batch.max_url_length()
+                );
+            }
+            tracing::debug!(
+                "📡 {}: Batch call {}/{}: {}",
+                self.name,
+                index + 1,
+                endpoints.len(),
+                batch_endpoint
+            );
+            self.check_memory_headroom(context, &mut memory_paused)
+                .await?;
+            let api_records = self
+                .fetch_single_api_call_with_data(batch_endpoint, None, context)
+                .await?;
+            all_records.extend(api_records);
+        }
+
+        tracing::info!(
+            "📡 {}: Total records fetched from batched APIs: {}",
+            self.name,
+            all_records.len()
+        );
+        Ok(all_records)
+    }
+
+    /// 處理參數化 API 呼叫（為每個前一個記錄分別呼叫）
+    async fn fetch_parameterized_api(&self, context: &PipelineContext) -> Result<Vec<Record>> {
+        let mut all_records = Vec::new();
+
+        // 上次執行中斷時留下的參數先處理，再接上前一個 Pipeline 這次的記錄（已在剩餘參數中的略過）；
+        // 以相同 execution_id 續跑（--resume）時整個 Pipeline 重新執行，不使用剩餘參數
+        let previous_run = self.load_remaining_parameters().await?;
+        let had_remaining = previous_run.is_some();
+        let upstream_records = self.parameter_source_records(context)?;
+        let param_records: Cow<'_, [Record]> =
+            match previous_run.filter(|remaining| remaining.execution_id != context.execution_id) {
+                Some(remaining) => {
+                    tracing::info!(
+                        "📍 {}: Continuing {} parameterized calls left by execution {}",
+                        self.name,
+                        remaining.parameters.len(),
+                        remaining.execution_id
+                    );
+                    self.record_metadata(
+                        "carried_over_calls",
+                        serde_json::json!(remaining.parameters.len()),
+                    );
+                    let mut seen = HashSet::new();
+                    remaining
+                        .parameters
+                        .into_iter()
+                        .map(|data| Record { data })
+                        .chain(upstream_records.iter().cloned())
+                        .filter(|record| seen.insert(parameter_key(&record.data)))
+                        .collect()
+                }
+                None => upstream_records,
+            };
 
         tracing::info!(
             "📡 {}: Making parameterized API calls for {} records",
@@ -122,9 +1548,141 @@ impl<S: Storage> SequenceAwarePipeline<S> {
             param_records.len()
         );
 
+        // 以相同 execution_id 重新執行時，略過先前已完成的呼叫
+        let cipher = self.state_cipher.as_deref();
+        let mut fan_out = match self.config.source.fan_out_checkpoint_every {
+            Some(every) => {
+                let progress = FanOutProgress::load(
+                    &self.storage,
+                    &self.name,
+                    &context.execution_id,
+                    every,
+                    cipher,
+                )
+                .await?;
+                if progress.completed_calls() > 0 {
+                    tracing::info!(
+                        "📍 {}: Resuming fan-out, {} calls already completed",
+                        self.name,
+                        progress.completed_calls()
+                    );
+                }
+                Some(progress)
+            }
+            None => None,
+        };
+
         // 為每個記錄構建並呼叫 API
+        let on_record_error = self.record_error_policy()?;
+        let mut failed_calls = 0;
+        let mut resumed_calls = 0;
+        let mut stopped = false;
+        let mut memory_paused = false;
         for (index, record) in param_records.iter().enumerate() {
-            let endpoint = self.build_parameterized_endpoint(&record.data)?;
+            if self.is_interrupted() {
+                if let Some(fan_out) = &mut fan_out {
+                    fan_out.flush(&self.storage, cipher).await?;
+                }
+                let remaining = &param_records[index..];
+                let path = self.save_remaining_parameters(remaining, context).await?;
+                tracing::warn!(
+                    "🛑 {}: Shutdown requested, stopped after {}/{} calls; {} remaining saved to '{}'",
+                    self.name,
+                    index,
+                    param_records.len(),
+                    remaining.len(),
+                    path
+                );
+                self.warnings.add(
+                    WarningCode::Interrupted,
+                    format!(
+                        "Stopped parameterized calls on shutdown, {} remaining",
+                        remaining.len()
+                    ),
+                );
+                self.record_metadata("partial", serde_json::json!(true));
+                self.record_metadata(
+                    "interrupted",
+                    serde_json::json!({
+                        "completed_calls": index,
+                        "remaining_calls": remaining.len(),
+                        "remaining_file": path,
+                    }),
+                );
+                stopped = true;
+                break;
+            }
+            if self.is_near_deadline() {
+                if let Some(fan_out) = &mut fan_out {
+                    fan_out.flush(&self.storage, cipher).await?;
+                }
+                let remaining = &param_records[index..];
+                let path = self.save_remaining_parameters(remaining, context).await?;
+                tracing::warn!(
+                    "⏱️ {}: Approaching invocation deadline, stopped after {}/{} calls; {} remaining saved to '{}'",
+                    self.name,
+                    index,
+                    param_records.len(),
+                    remaining.len(),
+                    path
+                );
+                self.warnings.add(
+                    WarningCode::DeadlineStop,
+                    format!(
+                        "Stopped parameterized calls near deadline, {} remaining",
+                        remaining.len()
+                    ),
+                );
+                self.record_metadata(
+                    "deadline_stop",
+                    serde_json::json!({
+                        "completed_calls": index,
+                        "remaining_calls": remaining.len(),
+                        "remaining_file": path,
+                    }),
+                );
+                stopped = true;
+                break;
+            }
+
+            if let Some(progress) = &self.progress {
+                progress.set_units(index, param_records.len());
+            }
+            if let Err(e) = self
+                .check_memory_headroom(context, &mut memory_paused)
+                .await
+            {
+                if let Some(fan_out) = &mut fan_out {
+                    fan_out.flush(&self.storage, cipher).await?;
+                }
+                return Err(e);
+            }
+
+            let endpoint = match self.build_parameterized_endpoint(&record.data) {
+                Ok(endpoint) => endpoint,
+                Err(e) => {
+                    if let Err(e) =
+                        self.handle_record_error(on_record_error, "template", e, &record.data)
+                    {
+                        if let Some(fan_out) = &mut fan_out {
+                            fan_out.flush(&self.storage, cipher).await?;
+                        }
+                        return Err(e);
+                    }
+                    failed_calls += 1;
+                    continue;
+                }
+            };
+            let key = fan_out.as_ref().map(|_| call_key(&endpoint, &record.data));
+            if let Some(records) = fan_out
+                .as_mut()
+                .zip(key.as_deref())
+                .and_then(|(fan_out, key)| fan_out.take(key))
+            {
+                all_records.extend(records);
+                resumed_calls += 1;
+                continue;
+            }
             tracing::debug!(
                 "📡 {}: API call {}/{}: {}",
                 self.name,
@@ -133,21 +1691,57 @@ impl<S: Storage> SequenceAwarePipeline<S> {
                 endpoint
             );
 
-            let api_records = self
+            match self
                 .fetch_single_api_call_with_data(&endpoint, Some(&record.data), context)
-                .await?;
-            all_records.extend(api_records);
+                .await
+            {
+                Ok(api_records) => {
+                    if let Some((fan_out, key)) = fan_out.as_mut().zip(key) {
+                        fan_out
+                            .record(&self.storage, key, &api_records, cipher)
+                            .await?;
+                    }
+                    all_records.extend(api_records);
+                }
+                Err(e) => {
+                    if let Err(e) =
+                        self.handle_record_error(on_record_error, "extract", e, &record.data)
+                    {
+                        if let Some(fan_out) = &mut fan_out {
+                            fan_out.flush(&self.storage, cipher).await?;
+                        }
+                        return Err(e);
+                    }
+                    failed_calls += 1;
+                }
+            }
 
-            // 可選：添加延遲避免請求過於頻繁
-            if index < param_records.len() - 1 {
+            // 未設定速率限制時，添加延遲避免請求過於頻繁
+            if !self.has_rate_limit() && index < param_records.len() - 1 {
                 tokio::time::sleep(std::time::Duration::from_millis(100)).await;
             }
         }
 
+        if let Some(fan_out) = &mut fan_out {
+            fan_out.flush(&self.storage, cipher).await?;
+        }
+        // 全部呼叫完成後移除剩餘參數檔，避免下次執行重複處理
+        if had_remaining && !stopped {
+            self.storage
+                .remove(&self.remaining_parameters_path())
+                .await?;
+        }
+        if resumed_calls > 0 {
+            self.record_metadata("resumed_calls", serde_json::json!(resumed_calls));
+        }
+        if failed_calls > 0 {
+            self.record_metadata("failed_calls", serde_json::json!(failed_calls));
+        }
         tracing::info!(
-            "📡 {}: Total records fetched from parameterized APIs: {}",
+            "📡 {}: Total records fetched from parameterized APIs: {} ({} failed calls)",
             self.name,
-            all_records.len()
+            all_records.len(),
+            failed_calls
         );
         Ok(all_records)
     }
@@ -159,49 +1753,7 @@ impl<S: Storage> SequenceAwarePipeline<S> {
         record_data: Option<&HashMap<String, serde_json::Value>>,
         context: &PipelineContext,
     ) -> Result<String> {
-        let mut processed = template.to_string();
-
-        // 替換共享數據中的參數 {{key}}
-        if processed.contains("{{") && processed.contains("}}") {
-            let re = regex::Regex::new(r"\{\{([^}]+)\}\}").unwrap();
-            processed = re
-                .replace_all(&processed, |caps: &regex::Captures| {
-                    let key = &caps[1];
-                    if let Some(shared_value) = context.get_shared_data(key) {
-                        match shared_value {
-                            serde_json::Value::String(s) => s.clone(),
-                            serde_json::Value::Number(n) => n.to_string(),
-                            serde_json::Value::Bool(b) => b.to_string(),
-                            serde_json::Value::Null => "null".to_string(),
-                            _ => serde_json::to_string(shared_value)
-                                .unwrap_or_default()
-                                .trim_matches('"')
-                                .to_string(),
-                        }
-                    } else {
-                        // 嘗試從記錄數據中查找
-                        if let Some(record_data) = record_data {
-                            if let Some(record_value) = record_data.get(key) {
-                                match record_value {
-                                    serde_json::Value::String(s) => s.clone(),
-                                    serde_json::Value::Number(n) => n.to_string(),
-                                    serde_json::Value::Bool(b) => b.to_string(),
-                                    serde_json::Value::Null => "null".to_string(),
-                                    _ => serde_json::to_string(record_value)
-                                        .unwrap_or_default()
-                                        .trim_matches('"')
-                                        .to_string(),
-                                }
-                            } else {
-                                format!("{{{{{}}}}}", key) // 保持原樣，未找到替換值
-                            }
-                        } else {
-                            format!("{{{{{}}}}}", key) // 保持原樣，未找到替換值
-                        }
-                    }
-                })
-                .to_string();
-        }
+        let processed = self.render_header_template(template, record_data, context)?;
 
         // 檢查是否還有未替換的參數
         if processed.contains("{{") && processed.contains("}}") {
@@ -210,121 +1762,79 @@ impl<S: Storage> SequenceAwarePipeline<S> {
                 self.name,
                 processed
             );
+            self.warnings.add(
+                WarningCode::UnresolvedTemplate,
+                format!(
+                    "Unresolved template parameters in header: {:?}",
+                    unresolved_template_names(&processed)
+                ),
+            );
         }
 
         Ok(processed)
     }
 
-    /// 處理 payload 模板，替換參數 (支援 shared data 和 record data)
-    fn process_payload_template(
+    /// 替換 header 值中的模板：先查共享數據，再查記錄數據
+    fn render_header_template(
         &self,
         template: &str,
         record_data: Option<&HashMap<String, serde_json::Value>>,
         context: &PipelineContext,
     ) -> Result<String> {
-        let mut processed = template.to_string();
+        let processed =
+            self.apply_context_lookups(&self.apply_checkpoint_template(template), record_data)?;
 
-        // 替換共享數據中的參數 {{key}}
+        // 替換 {{key|filter...}}
         if processed.contains("{{") && processed.contains("}}") {
-            let re = regex::Regex::new(r"\{\{([^}]+)\}\}").unwrap();
-            processed = re
-                .replace_all(&processed, |caps: &regex::Captures| {
-                    let key = &caps[1];
-                    if let Some(shared_value) = context.get_shared_data(key) {
-                        match shared_value {
-                            serde_json::Value::String(s) => s.clone(),
-                            serde_json::Value::Number(n) => n.to_string(),
-                            serde_json::Value::Bool(b) => b.to_string(),
-                            serde_json::Value::Null => "null".to_string(),
-                            _ => serde_json::to_string(shared_value)
-                                .unwrap_or_default()
-                                .trim_matches('"')
-                                .to_string(),
-                        }
-                    } else {
-                        // 嘗試從記錄數據中查找
-                        if let Some(record_data) = record_data {
-                            if let Some(record_value) = record_data.get(key) {
-                                match record_value {
-                                    serde_json::Value::String(s) => s.clone(),
-                                    serde_json::Value::Number(n) => n.to_string(),
-                                    serde_json::Value::Bool(b) => b.to_string(),
-                                    serde_json::Value::Null => "null".to_string(),
-                                    _ => serde_json::to_string(record_value)
-                                        .unwrap_or_default()
-                                        .trim_matches('"')
-                                        .to_string(),
-                                }
-                            } else {
-                                caps[0].to_string() // 保持原樣如果找不到
-                            }
-                        } else {
-                            caps[0].to_string() // 保持原樣如果找不到
-                        }
-                    }
-                })
-                .to_string();
-        }
-
-        // 如果配置了模板參數映射
-        if let Some(payload_config) = &self.config.source.payload {
-            if let Some(template_params) = &payload_config.template_params {
-                for (template_key, data_key) in template_params {
-                    let placeholder = format!("{{{{{}}}}}", template_key);
-                    if processed.contains(&placeholder) {
-                        if let Some(record_data) = record_data {
-                            if let Some(value) = record_data.get(data_key) {
-                                let value_str = match value {
-                                    serde_json::Value::String(s) => s.clone(),
-                                    serde_json::Value::Number(n) => n.to_string(),
-                                    serde_json::Value::Bool(b) => b.to_string(),
-                                    serde_json::Value::Null => "null".to_string(),
-                                    _ => serde_json::to_string(value)
-                                        .unwrap_or_default()
-                                        .trim_matches('"')
-                                        .to_string(),
-                                };
-                                processed = processed.replace(&placeholder, &value_str);
-                                tracing::debug!(
-                                    "📡 {}: Replaced payload template {} with {}",
-                                    self.name,
-                                    placeholder,
-                                    value_str
-                                );
-                            }
-                        }
-                    }
-                }
-            }
+            return render_template(&processed, |key| {
+                context
+                    .get_shared_data(key)
+                    .or_else(|| record_data.and_then(|data| data.get(key)))
+            });
+        }
+        Ok(processed)
+    }
 
-            // 如果啟用了使用前一個 pipeline 資料作為參數
-            if payload_config.use_previous_data_as_params.unwrap_or(false) {
-                if let Some(record_data) = record_data {
-                    for (key, value) in record_data {
-                        let placeholder = format!("{{{{{}}}}}", key);
-                        if processed.contains(&placeholder) {
-                            let value_str = match value {
-                                serde_json::Value::String(s) => s.clone(),
-                                serde_json::Value::Number(n) => n.to_string(),
-                                serde_json::Value::Bool(b) => b.to_string(),
-                                serde_json::Value::Null => "null".to_string(),
-                                _ => serde_json::to_string(value)
-                                    .unwrap_or_default()
-                                    .trim_matches('"')
-                                    .to_string(),
-                            };
-                            processed = processed.replace(&placeholder, &value_str);
-                            tracing::debug!(
-                                "📡 {}: Replaced payload parameter {} with {}",
-                                self.name,
-                                placeholder,
-                                value_str
-                            );
-                        }
-                    }
-                }
-            }
+    /// 替換 payload 中的模板：依序查共享數據、記錄數據、template_params 對應的記錄欄位
+    fn render_payload_template(
+        &self,
+        template: &str,
+        record_data: Option<&HashMap<String, serde_json::Value>>,
+        context: &PipelineContext,
+    ) -> Result<String> {
+        let processed =
+            self.apply_context_lookups(&self.apply_checkpoint_template(template), record_data)?;
+
+        // 替換 {{key|filter...}}
+        if processed.contains("{{") && processed.contains("}}") {
+            let template_params = self
+                .config
+                .source
+                .payload
+                .as_ref()
+                .and_then(|payload| payload.template_params.as_ref());
+            return render_template(&processed, |key| {
+                context.get_shared_data(key).or_else(|| {
+                    let record_data = record_data?;
+                    record_data.get(key).or_else(|| {
+                        template_params
+                            .and_then(|params| params.get(key))
+                            .and_then(|data_key| record_data.get(data_key))
+                    })
+                })
+            });
         }
+        Ok(processed)
+    }
+
+    /// 處理 payload 模板，替換參數 (支援 shared data 和 record data)
+    fn process_payload_template(
+        &self,
+        template: &str,
+        record_data: Option<&HashMap<String, serde_json::Value>>,
+        context: &PipelineContext,
+    ) -> Result<String> {
+        let processed = self.render_payload_template(template, record_data, context)?;
 
         // 檢查是否還有未替換的參數
         if processed.contains("{{") && processed.contains("}}") {
@@ -333,26 +1843,95 @@ impl<S: Storage> SequenceAwarePipeline<S> {
                 self.name,
                 processed
             );
+            self.warnings.add(
+                WarningCode::UnresolvedTemplate,
+                format!(
+                    "Unresolved template parameters in payload: {:?}",
+                    unresolved_template_names(&processed)
+                ),
+            );
         }
 
         Ok(processed)
     }
 
+    /// payload.form 的欄位（依名稱排序），值套用與 payload.body 相同的模板替換
+    fn payload_form_fields(
+        &self,
+        fields: &HashMap<String, String>,
+        record_data: Option<&HashMap<String, serde_json::Value>>,
+        context: &PipelineContext,
+    ) -> Result<Vec<(String, String)>> {
+        let mut fields = fields
+            .iter()
+            .map(|(name, value)| {
+                Ok((
+                    name.clone(),
+                    self.process_payload_template(value, record_data, context)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        fields.sort();
+        Ok(fields)
+    }
+
+    /// payload.multipart 的表單：文字欄位套用模板替換，檔案從存儲讀取
+    async fn build_multipart(
+        &self,
+        multipart: &MultipartConfig,
+        record_data: Option<&HashMap<String, serde_json::Value>>,
+        context: &PipelineContext,
+    ) -> Result<reqwest::multipart::Form> {
+        let mut form = reqwest::multipart::Form::new();
+        if let Some(fields) = &multipart.fields {
+            for (name, value) in self.payload_form_fields(fields, record_data, context)? {
+                form = form.text(name, value);
+            }
+        }
+        for file in multipart.files.iter().flatten() {
+            let path = self.process_payload_template(&file.path, record_data, context)?;
+            let data =
+                self.storage
+                    .read_file(&path)
+                    .await
+                    .map_err(|e| EtlError::ProcessingError {
+                        message: format!("Failed to read multipart file '{}': {}", path, e),
+                    })?;
+            let filename = file
+                .filename
+                .clone()
+                .unwrap_or_else(|| path.rsplit('/').next().unwrap_or(&path).to_string());
+            tracing::debug!(
+                "📡 {}: Attaching {} ({} bytes) as multipart field '{}'",
+                self.name,
+                filename,
+                data.len(),
+                file.name
+            );
+            let part = reqwest::multipart::Part::bytes(data)
+                .file_name(filename)
+                .mime_str(
+                    file.content_type
+                        .as_deref()
+                        .unwrap_or("application/octet-stream"),
+                )?;
+            form = form.part(file.name.clone(), part);
+        }
+        Ok(form)
+    }
+
     /// 構建參數化端點 URL
     fn build_parameterized_endpoint(
         &self,
         data: &HashMap<String, serde_json::Value>,
     ) -> Result<String> {
-        let mut endpoint = self
-            .config
-            .source
-            .endpoint
-            .as_ref()
+        let endpoint = self
+            .source_endpoint()
             .ok_or_else(|| EtlError::ConfigValidationError {
                 field: "source.endpoint".to_string(),
                 message: "Endpoint is required for parameterized API calls".to_string(),
-            })?
-            .clone();
+            })?;
+        let mut endpoint = self.apply_context_lookups(&endpoint, Some(data))?;
 
         tracing::debug!(
             "📡 {}: Building endpoint from template: {}",
@@ -450,35 +2029,75 @@ impl<S: Storage> SequenceAwarePipeline<S> {
 
         // 構建請求
         let mut request = match method.as_str() {
-            "GET" => self.client.get(endpoint),
-            "POST" => self.client.post(endpoint),
-            "PUT" => self.client.put(endpoint),
-            "DELETE" => self.client.delete(endpoint),
-            "PATCH" => self.client.patch(endpoint),
-            "HEAD" => self.client.head(endpoint),
+            "GET" => self.client()?.get(endpoint),
+            "POST" => self.client()?.post(endpoint),
+            "PUT" => self.client()?.put(endpoint),
+            "DELETE" => self.client()?.delete(endpoint),
+            "PATCH" => self.client()?.patch(endpoint),
+            "HEAD" => self.client()?.head(endpoint),
             _ => {
                 tracing::warn!(
                     "📡 {}: Unsupported HTTP method '{}', falling back to GET",
                     self.name,
                     method
                 );
-                self.client.get(endpoint)
+                self.warnings.add(
+                    WarningCode::UnsupportedHttpMethod,
+                    format!("Unsupported HTTP method '{}', fell back to GET", method),
+                );
+                self.client()?.get(endpoint)
             }
         };
 
         // 添加自定義標頭（支援模板替換）
+        let mut header_values = Vec::new();
         if let Some(headers) = &self.config.source.headers {
             for (key, value_template) in headers {
                 // 替換 header 值中的模板參數
                 let processed_value =
                     self.process_header_template(value_template, record_data, context)?;
                 request = request.header(key, &processed_value);
-                tracing::debug!("📡 {}: Set header {} = {}", self.name, key, processed_value);
+                tracing::debug!(
+                    "📡 {}: Set header {} = {}",
+                    self.name,
+                    key,
+                    if redact::is_sensitive_name(key) {
+                        redact::REDACTED
+                    } else {
+                        processed_value.as_str()
+                    }
+                );
+                header_values.push((key.clone(), processed_value));
             }
         }
 
-        // 處理 payload
-        if let Some(payload_config) = &self.config.source.payload {
+        // 處理 payload（表單與 multipart 由 reqwest 設定 Content-Type）
+        if let Some(form) = self
+            .config
+            .source
+            .payload
+            .as_ref()
+            .and_then(|payload| payload.form.as_ref())
+        {
+            let fields = self.payload_form_fields(form, record_data, context)?;
+            tracing::debug!(
+                "📡 {}: Form fields: {:?}",
+                self.name,
+                fields.iter().map(|(name, _)| name).collect::<Vec<_>>()
+            );
+            request = request.form(&fields);
+        } else if let Some(multipart) = self
+            .config
+            .source
+            .payload
+            .as_ref()
+            .and_then(|payload| payload.multipart.as_ref())
+        {
+            request = request.multipart(
+                self.build_multipart(multipart, record_data, context)
+                    .await?,
+            );
+        } else if let Some(payload_config) = &self.config.source.payload {
             // 設定 Content-Type
             if let Some(content_type) = &payload_config.content_type {
                 request = request.header("Content-Type", content_type);
@@ -500,7 +2119,7 @@ impl<S: Storage> SequenceAwarePipeline<S> {
         // 添加查詢參數
         if let Some(params) = &self.config.source.parameters {
             for (key, value) in params {
-                request = request.query(&[(key, value)]);
+                request = request.query(&[(key, self.apply_checkpoint_template(value))]);
             }
         }
 
@@ -516,92 +2135,361 @@ impl<S: Storage> SequenceAwarePipeline<S> {
             endpoint
         );
 
+        // 條件式請求：帶上此端點上次回應的 ETag / Last-Modified
+        let conditional = match self.conditional_config() {
+            Some(policy) if method == "GET" || method == "HEAD" => request
+                .try_clone()
+                .and_then(|request| request.build().ok())
+                .map(|built| (policy, built.url().to_string())),
+            _ => None,
+        };
+        let mut previous = None;
+        if let Some((_, url)) = &conditional {
+            self.conditional_requests.fetch_add(1, Ordering::Relaxed);
+            previous = conditional_requests::load(
+                &self.storage,
+                &self.name,
+                url,
+                self.state_cipher.as_deref(),
+            )
+            .await;
+            if let Some(previous) = &previous {
+                for (name, value) in previous.request_headers() {
+                    request = request.header(name, value);
+                }
+            }
+        }
+
         // 執行請求
-        let response = request.send().await?;
+        let started = std::time::Instant::now();
+        let response = self.send_request(request).await?;
+        let latency = started.elapsed();
 
-        if response.status().is_success() {
-            let json_data: serde_json::Value = response.json().await?;
-
-            // 處理 API 回應（支持單一物件回應）
-            if let serde_json::Value::Object(obj) = json_data {
-                let mut data = HashMap::new();
-
-                // 應用字段映射（支援多階層路徑）
-                if let Some(field_mapping) = &self.config.extract.field_mapping {
-                    // 先處理簡單的頂層映射
-                    for (original_key, value) in &obj {
-                        let mapped_key = field_mapping.get(original_key).unwrap_or(original_key);
-                        data.insert(mapped_key.clone(), value.clone());
-                    }
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let (Some((policy, _)), Some(previous)) = (&conditional, previous) {
+                self.not_modified_responses.fetch_add(1, Ordering::Relaxed);
+                tracing::info!(
+                    "🏷️ {}: {} not modified since {}",
+                    self.name,
+                    redact::redact_sensitive(&previous.url),
+                    previous.updated_at
+                );
+                if let (NotModifiedPolicy::Reuse, Some(body)) = (policy, previous.body) {
+                    let response_metadata = self.capture_response_metadata(&response, latency);
+                    records.extend(self.records_from_json(body)?);
+                    self.attach_response_metadata(response_metadata, &mut records)?;
+                }
+                return Ok(records);
+            }
+        }
 
-                    // 再處理多階層路徑映射（如 "user.profile.name" = "user_name"）
-                    for (path, mapped_key) in field_mapping {
-                        if path.contains('.') {
-                            if let Some(nested_value) = self.extract_nested_value(&obj, path) {
-                                data.insert(mapped_key.clone(), nested_value);
-                            }
+        if response.status().is_success() {
+            match &self.config.source.follow_links {
+                Some(follow_links) => {
+                    records = self
+                        .follow_next_links(follow_links, response, latency, &header_values)
+                        .await?;
+                }
+                None => {
+                    let response_metadata = self.capture_response_metadata(&response, latency);
+                    let validators = conditional.as_ref().and_then(|(policy, url)| {
+                        ConditionalEntry::from_headers(url, response.headers())
+                            .map(|entry| (*policy, entry))
+                    });
+                    let json_data = self.read_response_json(response).await?;
+                    if let Some((policy, mut entry)) = validators {
+                        if policy == NotModifiedPolicy::Reuse {
+                            entry.body = Some(json_data.clone());
+                        }
+                        if let Ok(mut pending) = self.pending_validators.lock() {
+                            pending.push(entry);
                         }
                     }
-                } else {
-                    // 沒有映射就直接使用原始字段
-                    for (key, value) in obj {
-                        data.insert(key, value);
-                    }
+                    records.extend(self.records_from_json(json_data)?);
+                    self.attach_response_metadata(response_metadata, &mut records)?;
                 }
+            }
+        } else {
+            let error_msg = format!(
+                "API request failed with status: {} ({} {})",
+                response.status(),
+                method,
+                redact::redact(response.url().as_str(), &[])
+            );
+            return Err(EtlError::http_status(response.status().as_u16(), error_msg));
+        }
 
-                records.push(Record { data });
-            } else if let serde_json::Value::Array(items) = json_data {
-                // 處理陣列回應
-                for item in items {
-                    if let serde_json::Value::Object(obj) = item {
-                        let mut data = HashMap::new();
-
-                        if let Some(field_mapping) = &self.config.extract.field_mapping {
-                            // 先處理簡單的頂層映射
-                            for (original_key, value) in &obj {
-                                let mapped_key =
-                                    field_mapping.get(original_key).unwrap_or(original_key);
-                                data.insert(mapped_key.clone(), value.clone());
-                            }
+        Ok(records)
+    }
 
-                            // 再處理多階層路徑映射
-                            for (path, mapped_key) in field_mapping {
-                                if path.contains('.') {
-                                    if let Some(nested_value) =
-                                        self.extract_nested_value(&obj, path)
-                                    {
-                                        data.insert(mapped_key.clone(), nested_value);
-                                    }
-                                }
-                            }
-                        } else {
-                            for (key, value) in &obj {
-                                data.insert(key.clone(), value.clone());
-                            }
-                        }
+    /// 依 source.follow_links 持續請求下一頁，合併所有頁面的記錄
+    async fn follow_next_links(
+        &self,
+        config: &FollowLinksConfig,
+        mut response: Response,
+        mut latency: std::time::Duration,
+        headers: &[(String, String)],
+    ) -> Result<Vec<Record>> {
+        let mut records = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pages = 0;
+        // 下一頁 URL 來自回應內容，只跟隨同源連結，避免把標頭與認證送到其他主機
+        let origin = response.url().origin();
+        loop {
+            pages += 1;
+            let current = response.url().clone();
+            let response_headers = response.headers().clone();
+            let response_metadata = self.capture_response_metadata(&response, latency);
+            visited.insert(current.clone());
+            let body = self.read_response_json(response).await?;
+            let next = link_pagination::next_url(config, &current, &response_headers, &body)?;
+            let mut page_records =
+                self.records_from_json(link_pagination::page_records(config, body))?;
+            self.attach_response_metadata(response_metadata, &mut page_records)?;
+            records.extend(page_records);
+
+            let Some(next) = next else {
+                break;
+            };
+            let stop_reason = if next.origin() != origin {
+                Some(format!(
+                    "Next page URL {} is on a different origin than {}",
+                    redact::redact_sensitive(next.as_str()),
+                    origin.ascii_serialization()
+                ))
+            } else if visited.contains(&next) {
+                Some(format!(
+                    "Next page URL {} was already requested",
+                    redact::redact_sensitive(next.as_str())
+                ))
+            } else if pages >= config.max_pages() {
+                Some(format!(
+                    "Reached follow_links.max_pages ({})",
+                    config.max_pages()
+                ))
+            } else {
+                None
+            };
+            if let Some(reason) = stop_reason {
+                tracing::warn!("📄 {}: {}, stopping pagination", self.name, reason);
+                self.warnings.add(WarningCode::PaginationStopped, reason);
+                break;
+            }
+
+            tracing::debug!(
+                "📄 {}: Following next page {}",
+                self.name,
+                redact::redact_sensitive(next.as_str())
+            );
+            let mut request = self.client()?.get(next);
+            for (key, value) in headers {
+                request = request.header(key, value);
+            }
+            if let Some(timeout) = self.config.source.timeout_seconds {
+                request = request.timeout(std::time::Duration::from_secs(timeout));
+            }
+            let started = std::time::Instant::now();
+            response = self.send_request(request).await?;
+            latency = started.elapsed();
+            if !response.status().is_success() {
+                return Err(EtlError::http_status(
+                    response.status().as_u16(),
+                    format!(
+                        "Next page request failed with status: {} (GET {})",
+                        response.status(),
+                        redact::redact(response.url().as_str(), &[])
+                    ),
+                ));
+            }
+        }
+
+        tracing::info!(
+            "📄 {}: Fetched {} records from {} pages",
+            self.name,
+            records.len(),
+            pages
+        );
+        self.record_metadata("pages", serde_json::json!(pages));
+        Ok(records)
+    }
 
-                        records.push(Record { data });
+    /// 啟用 source.conditional 時返回 304 的處理方式（已在設定驗證時檢查）
+    fn conditional_config(&self) -> Option<NotModifiedPolicy> {
+        self.config
+            .source
+            .conditional
+            .as_ref()
+            .filter(|conditional| conditional.is_enabled())
+            .and_then(|conditional| conditional.on_not_modified().ok())
+    }
+
+    /// 設定 source.response_metadata 時，在讀取回應內容之前擷取狀態碼、標頭與延遲
+    fn capture_response_metadata(
+        &self,
+        response: &Response,
+        latency: std::time::Duration,
+    ) -> Option<ResponseMetadata> {
+        let config = self.config.source.response_metadata.as_ref()?;
+        Some(ResponseMetadata::capture(
+            response,
+            latency,
+            config.header_names(),
+        ))
+    }
+
+    /// 將回應資訊加到該回應產生的記錄，或附加到 metadata 的 responses 清單
+    fn attach_response_metadata(
+        &self,
+        response_metadata: Option<ResponseMetadata>,
+        records: &mut [Record],
+    ) -> Result<()> {
+        let (Some(response_metadata), Some(config)) =
+            (response_metadata, &self.config.source.response_metadata)
+        else {
+            return Ok(());
+        };
+        match config.target()? {
+            ResponseMetadataTarget::Record => response_metadata.attach(records, config.prefix()),
+            ResponseMetadataTarget::Metadata => {
+                let mut entry = serde_json::to_value(&response_metadata)?;
+                entry["records"] = serde_json::json!(records.len());
+                if let Ok(mut metadata) = self.execution_metadata.lock() {
+                    match metadata
+                        .entry("responses".to_string())
+                        .or_insert_with(|| serde_json::json!([]))
+                    {
+                        serde_json::Value::Array(responses) => responses.push(entry),
+                        other => *other = serde_json::json!([entry]),
                     }
                 }
             }
-        } else {
-            let error_msg = format!("API request failed with status: {}", response.status());
-            return Err(crate::utils::error::EtlError::ProcessingError { message: error_msg });
+        }
+        Ok(())
+    }
+
+    /// 將回應（或 transform 子命令的輸入）轉為記錄：取出 data_path、展開物件鍵並套用 extract.field_mapping
+    pub fn records_from_json(&self, mut json_data: serde_json::Value) -> Result<Vec<Record>> {
+        let extract = &self.config.extract;
+        if let Some(path) = &extract.data_path {
+            json_data = self.unwrap_data_path(json_data, path)?;
         }
 
-        Ok(records)
+        // 將單一物件的鍵展開為多筆記錄
+        if extract.records_from_object_keys.unwrap_or(false) {
+            json_data = pivot_object_keys(
+                json_data,
+                extract.object_path.as_deref(),
+                extract.object_key_field(),
+            )?;
+        }
+
+        // 支持單一物件與陣列回應
+        Ok(match json_data {
+            serde_json::Value::Object(obj) => vec![self.map_fields(obj)?],
+            serde_json::Value::Array(items) => items
+                .into_iter()
+                .filter_map(|item| match item {
+                    serde_json::Value::Object(obj) => Some(self.map_fields(obj)),
+                    _ => None,
+                })
+                .collect::<Result<_>>()?,
+            _ => Vec::new(),
+        })
+    }
+
+    /// 依 extract.data_path 取出包在回應中的記錄（支援與 field_mapping 相同的巢狀路徑）；
+    /// 值為 null 時視為沒有記錄，找不到路徑則回報錯誤
+    fn unwrap_data_path(
+        &self,
+        json_data: serde_json::Value,
+        path: &str,
+    ) -> Result<serde_json::Value> {
+        let value = match &json_data {
+            serde_json::Value::Object(obj) => self.extract_nested_value(obj, path),
+            _ => None,
+        };
+        match value {
+            Some(serde_json::Value::Null) => Ok(serde_json::Value::Array(Vec::new())),
+            Some(value) => Ok(value),
+            None => Err(EtlError::DataValidationError {
+                message: format!("extract.data_path '{}' not found in response", path),
+            }),
+        }
+    }
+
+    /// 應用字段映射（支援多階層路徑與型別轉換）
+    fn map_fields(&self, obj: serde_json::Map<String, serde_json::Value>) -> Result<Record> {
+        let Some(field_mapping) = &self.config.extract.field_mapping else {
+            // 沒有映射就直接使用原始字段
+            return Ok(Record {
+                data: obj.into_iter().collect(),
+            });
+        };
+
+        // 先處理簡單的頂層映射
+        let mut data = HashMap::new();
+        for (original_key, value) in &obj {
+            let mapped_key = field_mapping
+                .get(original_key)
+                .map_or(original_key.as_str(), FieldMapping::target);
+            data.insert(mapped_key.to_string(), value.clone());
+        }
+
+        // 再處理多階層路徑映射（如 "user.profile.name" = "user_name"）
+        for (path, mapping) in field_mapping {
+            if path.contains('.') {
+                if let Some(nested_value) = self.extract_nested_value(&obj, path) {
+                    data.insert(mapping.target().to_string(), nested_value);
+                }
+            }
+        }
+
+        // 最後依指定型別轉換映射後的欄位
+        for (path, mapping) in field_mapping {
+            let Some((cast, policy)) =
+                mapping.coercion(&format!("extract.field_mapping.{}", path))?
+            else {
+                continue;
+            };
+            let FieldMapping::Typed(typed) = mapping else {
+                continue;
+            };
+            let Some(value) = data.get_mut(&typed.field) else {
+                continue;
+            };
+            match coerce(value, cast, typed.date_format.as_deref()) {
+                Some(coerced) => *value = coerced,
+                None => {
+                    let message = format!(
+                        "Field '{}' (from '{}') cannot be coerced to {:?}",
+                        typed.field, path, cast
+                    );
+                    if policy == CoercionErrorPolicy::Fail {
+                        return Err(EtlError::DataValidationError {
+                            message: format!("{}: {}, value: {}", self.name, message, value),
+                        });
+                    }
+                    tracing::debug!("🔶 {}: {}, value: {}", self.name, message, value);
+                    if policy == CoercionErrorPolicy::Null {
+                        *value = serde_json::Value::Null;
+                    }
+                    self.warnings.add(WarningCode::TypeCoercionFailed, message);
+                }
+            }
+        }
+        Ok(Record { data })
     }
 
     /// 從 API 獲取數據
     async fn fetch_api_data(&self, context: &PipelineContext) -> Result<Vec<Record>> {
-        let endpoint = self.config.source.endpoint.as_ref().ok_or_else(|| {
-            EtlError::ConfigValidationError {
+        let endpoint = self
+            .source_endpoint()
+            .ok_or_else(|| EtlError::ConfigValidationError {
                 field: "source.endpoint".to_string(),
                 message: "Endpoint is required for API calls".to_string(),
-            }
-        })?;
+            })?;
 
-        self.fetch_single_api_call_with_data(endpoint, None, context)
+        self.fetch_single_api_call_with_data(&endpoint, None, context)
             .await
     }
 
@@ -836,6 +2724,137 @@ impl<S: Storage> SequenceAwarePipeline<S> {
     }
 }
 
+/// 將物件（或 path 指向的巢狀物件）的每個鍵轉為一筆記錄
+///
+/// 物件值會展開為記錄欄位並加上 key_field；非物件值存放在 "value" 欄位。
+fn pivot_object_keys(
+    json_data: serde_json::Value,
+    path: Option<&str>,
+    key_field: &str,
+) -> Result<serde_json::Value> {
+    let mut target = &json_data;
+    for segment in path.into_iter().flat_map(|path| path.split('.')) {
+        target = target
+            .get(segment)
+            .ok_or_else(|| EtlError::DataValidationError {
+                message: format!("Object path segment '{}' not found in response", segment),
+            })?;
+    }
+    let serde_json::Value::Object(entries) = target else {
+        return Err(EtlError::DataValidationError {
+            message: format!(
+                "records_from_object_keys requires an object at '{}'",
+                path.unwrap_or("$")
+            ),
+        });
+    };
+
+    let records = entries
+        .iter()
+        .map(|(key, value)| {
+            let mut record = match value {
+                serde_json::Value::Object(fields) => fields.clone(),
+                other => {
+                    let mut fields = serde_json::Map::new();
+                    fields.insert("value".to_string(), other.clone());
+                    fields
+                }
+            };
+            record.insert(
+                key_field.to_string(),
+                serde_json::Value::String(key.clone()),
+            );
+            serde_json::Value::Object(record)
+        })
+        .collect();
+    Ok(serde_json::Value::Array(records))
+}
+
+/// 依輸出格式（csv/tsv/json）輸出一組記錄，套用 load.columns、load.csv 與 load.null_policy
+pub(crate) fn render_output(
+    output_format: &str,
+    records: &[Record],
+    load: &LoadConfig,
+) -> Result<String> {
+    let columns = load.columns.as_deref();
+    let null_policy = load.null_policy(output_format)?;
+    match output_format {
+        "csv" => DelimitedFormat::csv(load.csv.as_ref())?
+            .with_null_policy(null_policy)
+            .render(records, columns),
+        "tsv" => DelimitedFormat::tsv()
+            .with_null_policy(null_policy)
+            .render(records, columns),
+        _ => render_json(records, null_policy),
+    }
+}
+
+/// 轉換階段為每筆記錄加上的處理標記欄位
+const MARKER_FIELDS: [&str; 2] = ["processed", "processed_by"];
+
+/// 找出記錄中不在 `columns` 內的欄位（strict_columns 模式下視為錯誤；處理標記欄位不列入檢查）
+fn check_unknown_columns(records: &[Record], columns: &[String]) -> Result<()> {
+    let unknown: BTreeSet<&String> = records
+        .iter()
+        .flat_map(|record| record.data.keys())
+        .filter(|field| !columns.contains(field) && !MARKER_FIELDS.contains(&field.as_str()))
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(EtlError::DataValidationError {
+        message: format!(
+            "Records contain fields not listed in load.columns: {:?}",
+            unknown
+        ),
+    })
+}
+
+/// 找出模板中尚未替換的 {{key}} 名稱
+fn unresolved_template_names(template: &str) -> Vec<String> {
+    let re = regex::Regex::new(r"\{\{([^}]+)\}\}").unwrap();
+    re.captures_iter(template)
+        .map(|caps| {
+            caps[1]
+                .split('|')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// 將參數值依序填入端點佔位符，當 URL 長度超過上限時拆分為多個端點
+/// 單一值本身就超過上限時仍會獨立成一個端點
+fn split_batched_endpoints(
+    endpoint: &str,
+    placeholder: &str,
+    values: &[String],
+    separator: &str,
+    max_url_length: usize,
+) -> Vec<String> {
+    // 以組出的 URL 計算長度：佔位符可能出現不只一次
+    let render = |values: &[&str]| endpoint.replace(placeholder, &values.join(separator));
+    let mut endpoints = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for value in values {
+        current.push(value);
+        if current.len() > 1 && render(&current).len() > max_url_length {
+            current.pop();
+            endpoints.push(render(&current));
+            current = vec![value];
+        }
+    }
+
+    if !current.is_empty() {
+        endpoints.push(render(&current));
+    }
+
+    endpoints
+}
+
 #[async_trait::async_trait]
 impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
     fn get_name(&self) -> &str {
@@ -843,14 +2862,143 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
     }
 
     async fn extract_with_context(&self, context: &PipelineContext) -> Result<Vec<Record>> {
+        // 輸出已存在時在呼叫 API 之前就失敗
+        // 檔名含 {record_count} 時在 extract 前無法得知，留待 load 時檢查
+        let counted = self
+            .config
+            .load
+            .filename_pattern
+            .as_ref()
+            .is_some_and(|pattern| pattern.contains("{record_count}"));
+        if self.config.load.write_mode()? == WriteMode::ErrorIfExists && !counted {
+            self.resolve_output_filename(context, self.archive_format()?, None)
+                .await?;
+        }
+
+        if self.is_view() {
+            return self.view_records(context);
+        }
+
         tracing::info!("📥 {}: Starting contextual extract", self.name);
 
-        // 決定數據來源並獲取原始數據
-        let raw_records = self.determine_data_source(context).await?;
+        // 端點、標頭、payload 中的 {{now}}、{{yesterday}}、{{execution_id}} 等內建佔位符與逐項執行的項目
+        if let Ok(mut builtins) = self.builtins.lock() {
+            *builtins = Some(
+                BuiltinValues::new(&context.execution_id, context.run_started_at)
+                    .with_item(context.foreach_item.clone()),
+            );
+        }
+        self.conditional_requests.store(0, Ordering::Relaxed);
+        self.not_modified_responses.store(0, Ordering::Relaxed);
+
+        // 載入 checkpoint，供模板中的 {{checkpoint.KEY}} 使用
+        if let Some(checkpoint) = self.config.checkpoint.as_ref().filter(|c| c.is_enabled()) {
+            let state = CheckpointState::load(
+                &self.storage,
+                &self.name,
+                checkpoint,
+                self.state_cipher.as_deref(),
+            )
+            .await?;
+            tracing::info!("📍 {}: Loaded checkpoint {:?}", self.name, state.values);
+            if let Ok(mut current) = self.checkpoint_state.lock() {
+                *current = Some(state);
+            }
+        }
+
+        // 建立先前結果的索引，供模板中的 {{lookup:...}} 使用
+        if let Some(index_config) = &self.config.context_index {
+            let index = ContextIndex::build(context, &index_config.pipeline, &index_config.key)?;
+            if let Ok(mut current) = self.context_index.lock() {
+                *current = Some(index);
+            }
+        }
+
+        // 決定數據來源並獲取原始數據（啟用快取時優先使用 TTL 內的結果）
+        let raw_records = match self
+            .config
+            .extract
+            .cache
+            .as_ref()
+            .filter(|c| c.is_enabled())
+        {
+            Some(cache) => {
+                let key = extract_cache::cache_key(&self.extract_cache_request(context)?);
+                let cipher = self.state_cipher.as_deref();
+                let cache_storage =
+                    crate::LocalStorage::new(self.cache_dir.to_string_lossy().into_owned());
+                match extract_cache::load(&cache_storage, &self.name, &key, cache, cipher).await {
+                    Some(records) => {
+                        tracing::info!(
+                            "🗄️ {}: Using {} cached records (ttl {} min)",
+                            self.name,
+                            records.len(),
+                            cache.ttl_minutes()
+                        );
+                        self.record_metadata("extract_cache", serde_json::json!("hit"));
+                        records
+                    }
+                    None => {
+                        let records = self
+                            .determine_data_source(context)
+                            .await
+                            .map_err(|e| self.endpoint_context(e))?;
+                        extract_cache::save(&cache_storage, &self.name, &key, &records, cipher)
+                            .await?;
+                        self.record_metadata("extract_cache", serde_json::json!("miss"));
+                        records
+                    }
+                }
+            }
+            None => self
+                .determine_data_source(context)
+                .await
+                .map_err(|e| self.endpoint_context(e))?,
+        };
+
+        // 套用 extract.max_records
+        let mut raw_records = raw_records;
+        if let Some(max_records) = self.config.extract.max_records {
+            let endpoint = self
+                .source_endpoint()
+                .map(|endpoint| redact::redact(&endpoint, &[]))
+                .unwrap_or_else(|| self.name.clone());
+            let truncated = response_limits::limit_records(
+                &mut raw_records,
+                max_records,
+                self.config.extract.limit_policy()?,
+                &endpoint,
+            )?;
+            if truncated > 0 {
+                tracing::warn!(
+                    "✂️ {}: Truncated {} records over extract.max_records ({})",
+                    self.name,
+                    truncated,
+                    max_records
+                );
+                self.warnings.add(
+                    WarningCode::ResponseLimitExceeded,
+                    format!(
+                        "Truncated {} records over max_records {}",
+                        truncated, max_records
+                    ),
+                );
+                self.record_metadata("truncated_records", serde_json::json!(truncated));
+            }
+        }
 
         // 應用數據處理操作
         let processed_records = self.apply_data_processing(raw_records);
 
+        // 計算新的 watermark，待 load 成功後才持久化
+        if let Some(checkpoint) = self.config.checkpoint.as_ref().filter(|c| c.is_enabled()) {
+            if let Ok(mut current) = self.checkpoint_state.lock() {
+                if let Some(state) = current.as_mut() {
+                    state.advance(&processed_records, checkpoint);
+                }
+            }
+        }
+
         tracing::info!(
             "📥 {}: Extracted {} records",
             self.name,
@@ -864,11 +3012,56 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
         data: Vec<Record>,
         context: &mut PipelineContext,
     ) -> Result<TransformResult> {
+        // View 不做轉換，只重新輸出來源 Pipeline 的記錄
+        if self.is_view() {
+            let (csv_output, tsv_output) = self.render_delimited(&data)?;
+            return Ok(TransformResult {
+                csv_output,
+                tsv_output,
+                processed_records: data,
+                intermediate_data: Vec::new(),
+            });
+        }
+
         let mut processed_records = Vec::new();
-        let mut csv_lines = Vec::new();
-        let mut tsv_lines = Vec::new();
         let mut intermediate_data = Vec::new();
-        let mut headers_generated = false;
+        let lookup_tables = self.load_lookup_tables()?;
+        let validation = self.config.transform.validation.as_ref();
+        let invalid_policy = InvalidRecordPolicy::parse(
+            validation
+                .and_then(|v| v.on_invalid.as_deref())
+                .unwrap_or("fail"),
+        )?;
+        let field_transformer = Arc::new(match &self.config.transform.field_transforms {
+            Some(field_transforms) => FieldTransformer::compile(field_transforms)?,
+            None => FieldTransformer::default(),
+        });
+        let record_timeout = self.config.transform.record_timeout();
+        let script = self
+            .config
+            .transform
+            .script
+            .as_ref()
+            .map(|script| RecordScript::compile(script, "transform.script"))
+            .transpose()?;
+        let pii = self
+            .config
+            .transform
+            .operations
+            .as_ref()
+            .and_then(PiiProtector::from_operations);
+        if pii.as_ref().is_some_and(PiiProtector::is_unsalted) {
+            self.warnings.add(
+                WarningCode::UnsaltedHash,
+                "hash_fields/tokenize_fields without hash_salt can be reversed with a dictionary",
+            );
+        }
+        let mut dropped_count = 0;
+        let mut rejected_count = 0;
+        let extracted_count = data.len();
+        if let Ok(mut reconciliation) = self.reconciliation.lock() {
+            *reconciliation = None;
+        }
 
         tracing::info!(
             "🔄 {}: Starting contextual transform for {} records",
@@ -909,6 +3102,13 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
                                 self.name,
                                 field
                             );
+                            self.warnings.add(
+                                WarningCode::MissingField,
+                                format!(
+                                    "Field '{}' specified in keep_only_fields not found",
+                                    field
+                                ),
+                            );
                         }
                     }
 
@@ -949,13 +3149,35 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
             if let Some(enrichment) = &self.config.transform.data_enrichment {
                 // 查找數據
                 if let Some(lookup_data) = &enrichment.lookup_data {
-                    for (lookup_field, target_field) in lookup_data {
-                        if let Some(lookup_value) = record.data.get(lookup_field) {
-                            // 這裡可以實作更複雜的查找邏輯
-                            record.data.insert(
-                                target_field.clone(),
-                                serde_json::Value::String(format!("enriched_{}", lookup_value)),
-                            );
+                    for (join_field, table_name) in lookup_data {
+                        let Some((table, table_config)) = lookup_tables.get(table_name) else {
+                            continue;
+                        };
+                        let overwrite = table_config.overwrite.unwrap_or(false);
+                        match table.enrich(
+                            &mut record,
+                            join_field,
+                            table_config.fields.as_deref(),
+                            &table_config.prefix(table_name),
+                            overwrite,
+                        ) {
+                            None => self.warnings.add(
+                                WarningCode::LookupMiss,
+                                format!(
+                                    "No match in lookup table '{}' for field '{}'",
+                                    table_name, join_field
+                                ),
+                            ),
+                            Some(collisions) if !collisions.is_empty() => self.warnings.add(
+                                WarningCode::LookupFieldCollision,
+                                format!(
+                                    "Lookup table '{}' fields {:?} already exist in records ({})",
+                                    table_name,
+                                    collisions,
+                                    if overwrite { "overwritten" } else { "kept" }
+                                ),
+                            ),
+                            Some(_) => {}
                         }
                     }
                 }
@@ -977,88 +3199,94 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
                 }
             }
 
+            // 欄位層級轉換（重新命名、轉型、預設值、正規表示式替換）
+            // 設定 record_timeout_ms 時逾時的記錄改寫入 dead-letter，避免單筆卡住整個 Pipeline
+            let mut violations = match record_timeout {
+                Some(timeout) => {
+                    let original = record.data.clone();
+                    match field_transformer.apply_with_timeout(record, timeout).await {
+                        Some((transformed, violations)) => {
+                            record = transformed;
+                            violations
+                        }
+                        None => {
+                            self.reject_record(
+                                "timeout",
+                                format!(
+                                    "Record {} exceeded record_timeout_ms ({}ms)",
+                                    index,
+                                    timeout.as_millis()
+                                ),
+                                &original,
+                            )?;
+                            rejected_count += 1;
+                            continue;
+                        }
+                    }
+                }
+                None => field_transformer.apply(&mut record),
+            };
+
+            // 記錄轉換腳本：返回 () 的記錄略過，腳本錯誤依 on_invalid 處理
+            if let Some(script) = &script {
+                match script.apply(&record) {
+                    Ok(Some(transformed)) => record = transformed,
+                    Ok(None) => {
+                        tracing::debug!("📜 {}: Script skipped record {}", self.name, index);
+                        continue;
+                    }
+                    Err(e) => violations.push(format!("Record {}: {}", index, e)),
+                }
+            }
+
+            // 個資欄位遮罩、雜湊與代碼化（在驗證前套用，rejects 檔也不含原始值）
+            if let Some(pii) = &pii {
+                pii.apply(&mut record);
+            }
+
             // 添加處理標記
+            let [processed, processed_by] = MARKER_FIELDS;
             record
                 .data
-                .insert("processed".to_string(), serde_json::Value::Bool(true));
+                .insert(processed.to_string(), serde_json::Value::Bool(true));
             record.data.insert(
-                "processed_by".to_string(),
+                processed_by.to_string(),
                 serde_json::Value::String(self.name.clone()),
             );
 
-            // 生成動態 CSV/TSV 輸出格式
-            if !headers_generated {
-                // 根據第一筆記錄生成標頭
-                let mut field_names: Vec<String> = record.data.keys().cloned().collect();
-                field_names.sort(); // 確保一致的欄位順序
-
-                // 生成 CSV 標頭
-                csv_lines.push(field_names.join(","));
-
-                // 生成 TSV 標頭
-                tsv_lines.push(field_names.join("\t"));
-
-                headers_generated = true;
-
-                tracing::debug!(
-                    "🔄 {}: Generated headers for {} fields: {:?}",
-                    self.name,
-                    field_names.len(),
-                    field_names
-                );
+            // 記錄驗證（必要欄位與欄位型別）；轉型失敗與驗證違規依 on_invalid 一併處理
+            if let Some(validation) = validation {
+                violations.extend(validate_record(&record, validation));
             }
-
-            // 根據欄位順序生成資料行
-            if headers_generated {
-                let header_line = csv_lines[0].clone(); // 複製標頭行避免借用衝突
-                let field_names: Vec<&str> = header_line.split(',').collect();
-
-                // 生成 CSV 資料行
-                let csv_values: Vec<String> = field_names
-                    .iter()
-                    .map(|field_name| {
-                        record
-                            .data
-                            .get(*field_name)
-                            .map(|value| match value {
-                                serde_json::Value::String(s) => {
-                                    // CSV 欄位轉義：包含逗號、引號或換行的字串用引號包圍
-                                    if s.contains(',') || s.contains('"') || s.contains('\n') {
-                                        format!("\"{}\"", s.replace('"', "\"\""))
-                                    } else {
-                                        s.clone()
-                                    }
-                                }
-                                serde_json::Value::Number(n) => n.to_string(),
-                                serde_json::Value::Bool(b) => b.to_string(),
-                                serde_json::Value::Null => "".to_string(),
-                                _ => serde_json::to_string(value).unwrap_or_default().trim_matches('"').to_string(),
-                            })
-                            .unwrap_or_else(|| "".to_string())
-                    })
-                    .collect();
-
-                csv_lines.push(csv_values.join(","));
-
-                // 生成 TSV 資料行
-                let tsv_values: Vec<String> = field_names
-                    .iter()
-                    .map(|field_name| {
-                        record
-                            .data
-                            .get(*field_name)
-                            .map(|value| match value {
-                                serde_json::Value::String(s) => s.replace('\t', " ").replace('\n', " "),
-                                serde_json::Value::Number(n) => n.to_string(),
-                                serde_json::Value::Bool(b) => b.to_string(),
-                                serde_json::Value::Null => "".to_string(),
-                                _ => serde_json::to_string(value).unwrap_or_default().trim_matches('"').to_string().replace('\t', " ").replace('\n', " "),
-                            })
-                            .unwrap_or_else(|| "".to_string())
-                    })
-                    .collect();
-
-                tsv_lines.push(tsv_values.join("\t"));
+            if !violations.is_empty() {
+                match invalid_policy {
+                    InvalidRecordPolicy::Fail => {
+                        return Err(EtlError::DataValidationError {
+                            message: format!(
+                                "{}: Record {} is invalid: {}",
+                                self.name,
+                                index,
+                                violations.join("; ")
+                            ),
+                        });
+                    }
+                    InvalidRecordPolicy::Drop => {
+                        for violation in &violations {
+                            self.warnings
+                                .add(WarningCode::InvalidRecord, violation.clone());
+                        }
+                        dropped_count += 1;
+                    }
+                    InvalidRecordPolicy::Reject => {
+                        for violation in &violations {
+                            self.warnings
+                                .add(WarningCode::InvalidRecord, violation.clone());
+                        }
+                        self.reject_record("validate", violations.join("; "), &record.data)?;
+                        rejected_count += 1;
+                    }
+                }
+                continue;
             }
 
             // 檢查中繼數據條件
@@ -1095,21 +3323,27 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
 
                                 // 特殊處理 token 字段
                                 if key == "token" || key == "access_token" {
-                                    context.add_shared_data("token".to_string(), value.clone());
-                                    tracing::info!(
-                                        "📤 {}: Exported {} to shared data as 'token'",
-                                        self.name,
-                                        key
-                                    );
+                                    if self.export_shared_data(
+                                        context,
+                                        "token".to_string(),
+                                        value.clone(),
+                                    ) {
+                                        tracing::info!(
+                                            "📤 {}: Exported {} to shared data as 'token'",
+                                            self.name,
+                                            key
+                                        );
+                                    }
                                 } else {
                                     let full_key_clone = full_key.clone();
-                                    context.add_shared_data(full_key, value.clone());
-                                    tracing::debug!(
-                                        "📤 {}: Exported {} to shared data as '{}'",
-                                        self.name,
-                                        key,
-                                        full_key_clone
-                                    );
+                                    if self.export_shared_data(context, full_key, value.clone()) {
+                                        tracing::debug!(
+                                            "📤 {}: Exported {} to shared data as '{}'",
+                                            self.name,
+                                            key,
+                                            full_key_clone
+                                        );
+                                    }
                                 }
                             }
                         }
@@ -1120,6 +3354,55 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
             processed_records.push(record);
         }
 
+        if let Some(validation) = validation {
+            self.finish_validation(
+                validation,
+                invalid_policy,
+                processed_records.len(),
+                dropped_count,
+            )?;
+        }
+        self.write_dead_letters().await?;
+        if let Some(pii) = &pii {
+            if let Some((path, added)) = pii
+                .save_tokens(&self.storage, &self.name, self.state_cipher.as_deref())
+                .await?
+            {
+                tracing::info!("🔏 {}: Saved {} new tokens to {}", self.name, added, path);
+                self.record_metadata("token_vault", serde_json::json!(path));
+            }
+        }
+
+        if let Some(custom) = self
+            .config
+            .transform
+            .operations
+            .as_ref()
+            .and_then(|operations| operations.custom.as_ref())
+        {
+            let steps = resolve_transform_steps(custom, "transform.operations.custom")?;
+            let input_count = processed_records.len();
+            processed_records = apply_transform_steps(&steps, processed_records)?;
+            tracing::info!(
+                "🧩 {}: Applied custom steps {:?}: {} -> {} records",
+                self.name,
+                custom,
+                input_count,
+                processed_records.len()
+            );
+        }
+
+        if let Some(aggregation) = &self.config.transform.aggregation {
+            let aggregated = Aggregator::compile(aggregation)?.apply(&processed_records);
+            tracing::info!(
+                "🧮 {}: Aggregated {} records into {} groups",
+                self.name,
+                processed_records.len(),
+                aggregated.len()
+            );
+            processed_records = aggregated;
+        }
+
         tracing::info!(
             "🔄 {}: Transform complete: {} processed, {} intermediate",
             self.name,
@@ -1127,32 +3410,44 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
             intermediate_data.len()
         );
 
+        // 彙總會刻意改變筆數，只記錄不比較
+        let transformed_count = processed_records.len();
+        if self.config.transform.aggregation.is_some() {
+            self.reconcile(|reconciliation| {
+                reconciliation.extracted = extracted_count;
+                reconciliation.transformed = transformed_count;
+                None
+            })?;
+        } else {
+            self.reconcile(|reconciliation| {
+                reconciliation
+                    .check_transform(
+                        extracted_count,
+                        transformed_count,
+                        dropped_count + rejected_count,
+                    )
+                    .cloned()
+            })?;
+        }
+
+        let (csv_output, tsv_output) = self.render_delimited(&processed_records)?;
         Ok(TransformResult {
+            csv_output,
+            tsv_output,
             processed_records,
-            csv_output: csv_lines.join("\n"),
-            tsv_output: tsv_lines.join("\n"),
             intermediate_data,
         })
     }
 
     async fn load_with_context(
         &self,
-        result: TransformResult,
+        result: &TransformResult,
         context: &PipelineContext,
     ) -> Result<String> {
-        let filename = if let Some(pattern) = &self.config.load.filename_pattern {
-            // 簡單的模板替換
-            pattern
-                .replace("{pipeline_name}", &self.name)
-                .replace("{execution_id}", &context.execution_id)
-                .replace(
-                    "{timestamp}",
-                    &chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string(),
-                )
-        } else {
-            format!("{}_output.zip", self.name)
-        };
-
+        let format = self.archive_format()?;
+        let filename = self
+            .resolve_output_filename(context, format, Some(result.processed_records.len()))
+            .await?;
         let output_path = format!("{}/{}", self.config.load.output_path, filename);
 
         tracing::info!(
@@ -1161,68 +3456,384 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
             output_path
         );
 
-        // 創建 ZIP 文件
-        let zip_data = {
-            let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let encryption = self.output_encryption()?;
+        if let Some(encryption) = &encryption {
+            tracing::info!(
+                "🔐 {}: Encrypting output with {}",
+                self.name,
+                encryption.method().as_str()
+            );
+            self.record_metadata(
+                "output_encryption",
+                serde_json::json!(encryption.method().as_str()),
+            );
+        }
+        let mut archive = OutputArchive::new(format).with_encryption(encryption);
+        if let Some(manifest) = &self.config.load.manifest {
+            archive = archive.with_manifest(
+                manifest.location()?,
+                OutputManifest::new(&self.name, &context.execution_id),
+            );
+        }
 
-            // 根據配置的輸出格式添加文件
-            for format in &self.config.load.output_formats {
-                match format.as_str() {
-                    "csv" => {
-                        zip.start_file::<_, ()>("output.csv", FileOptions::default())?;
-                        zip.write_all(result.csv_output.as_bytes())?;
-                    }
-                    "tsv" => {
-                        zip.start_file::<_, ()>("output.tsv", FileOptions::default())?;
-                        zip.write_all(result.tsv_output.as_bytes())?;
-                    }
-                    "json" => {
-                        zip.start_file::<_, ()>("processed_data.json", FileOptions::default())?;
-                        let json_data = serde_json::to_string_pretty(&result.processed_records)?;
-                        zip.write_all(json_data.as_bytes())?;
-                    }
-                    _ => {
-                        tracing::warn!("🔶 {}: Unsupported output format: {}", self.name, format);
-                    }
+        // 推斷 schema 並檢查是否偏離預期（在寫出任何檔案前失敗）
+        if self.config.load.infers_schema() {
+            let schema = InferredSchema::infer(&result.processed_records);
+            if let Some(path) = &self.config.load.expected_schema {
+                let changes = schema.drift(&InferredSchema::load(path)?);
+                if !changes.is_empty() {
+                    tracing::error!(
+                        "📐 {}: Schema drifted from {}: {:?}",
+                        self.name,
+                        path,
+                        changes
+                    );
+                    return Err(EtlError::DataValidationError {
+                        message: format!(
+                            "Output schema drifted from {}: {}",
+                            path,
+                            changes.join("; ")
+                        ),
+                    });
                 }
+                tracing::info!("📐 {}: Schema matches {}", self.name, path);
             }
+            archive.add(SCHEMA_FILE_NAME, serde_json::to_string_pretty(&schema)?);
+            self.record_metadata("schema_fields", serde_json::json!(schema.fields.len()));
+        }
 
-            // 添加中繼結果 JSON
-            if !result.intermediate_data.is_empty() {
-                zip.start_file::<_, ()>("intermediate.json", FileOptions::default())?;
-                let json_data = serde_json::to_string_pretty(&result.intermediate_data)?;
-                zip.write_all(json_data.as_bytes())?;
-            }
-
-            // 添加元數據
-            if let Some(compression) = &self.config.load.compression {
-                if compression.include_metadata.unwrap_or(false) {
-                    zip.start_file::<_, ()>("metadata.json", FileOptions::default())?;
-                    let mut metadata = HashMap::new();
-                    metadata.insert(
-                        "pipeline_name".to_string(),
-                        serde_json::Value::String(self.name.clone()),
+        // 評估資料品質規則；error 等級的規則失敗時只寫出報告，不寫出輸出
+        if let Some(quality) = &self.config.quality {
+            let report = QualityReport::evaluate(&self.name, quality, &result.processed_records)?;
+            for rule in report.failed(Severity::Warn) {
+                tracing::warn!("🩺 {}: Quality rule failed: {}", self.name, rule.describe());
+                self.warnings.add(
+                    WarningCode::QualityRuleFailed,
+                    format!("Quality rule failed: {}", rule.check),
+                );
+            }
+            let failed: Vec<String> = report
+                .failed(Severity::Error)
+                .map(|rule| rule.describe())
+                .collect();
+            self.record_metadata(
+                "quality",
+                serde_json::json!({
+                    "passed": report.passed,
+                    "rules": report.rules.len(),
+                    "failed": report.rules.iter().filter(|rule| !rule.passed).count(),
+                }),
+            );
+            let report_json = serde_json::to_string_pretty(&report)?;
+            if !report.passed {
+                let report_path = format!("{}_{}", self.name, quality.report_file());
+                self.storage
+                    .write_file(&report_path, report_json.as_bytes())
+                    .await?;
+                tracing::error!(
+                    "🩺 {}: {} quality rules failed (report: {}): {}",
+                    self.name,
+                    failed.len(),
+                    report_path,
+                    failed.join("; ")
+                );
+                return Err(EtlError::DataValidationError {
+                    message: format!("Quality rules failed: {}", failed.join("; ")),
+                });
+            }
+            tracing::info!(
+                "🩺 {}: {} quality rules evaluated",
+                self.name,
+                report.rules.len()
+            );
+            archive.add(quality.report_file(), report_json);
+        }
+
+        // 欄位統計，供檢查新來源的資料是否合理
+        if let Some(profile) = &self.config.profile {
+            let profile = DataProfile::profile(
+                &result.processed_records,
+                profile.fields.as_deref(),
+                profile.top_k(),
+            );
+            tracing::info!(
+                "📊 {}: Profiled {} fields over {} records",
+                self.name,
+                profile.fields.len(),
+                profile.records
+            );
+            archive.add(PROFILE_FILE_NAME, serde_json::to_string_pretty(&profile)?);
+            self.record_metadata("profiled_fields", serde_json::json!(profile.fields.len()));
+        }
+
+        // 根據配置的輸出格式添加文件（設定 partition_by 時每個分區各一組）
+        let partitions = match &self.config.load.partition_by {
+            Some(field) => {
+                let partitions = partition_records(&result.processed_records, field);
+                tracing::info!(
+                    "🗃️ {}: Writing {} partitions by '{}'",
+                    self.name,
+                    partitions.len(),
+                    field
+                );
+                self.record_metadata("partitions", serde_json::json!(partitions.len()));
+                Some((field, self.config.load.partition_layout()?, partitions))
+            }
+            None => None,
+        };
+        let max_per_file = self.config.load.max_records_per_file;
+        // 各格式實際寫出的筆數（扣除 null_policy 略過的記錄），取最少者對帳
+        let mut loaded: Option<(usize, usize)> = None;
+        for output_format in &self.config.load.output_formats {
+            let (default_base, extension) = match output_format.as_str() {
+                "csv" => ("output", ".csv"),
+                "tsv" => ("output", ".tsv"),
+                "json" => ("processed_data", ".json"),
+                _ => {
+                    tracing::warn!(
+                        "🔶 {}: Unsupported output format: {}",
+                        self.name,
+                        output_format
                     );
-                    metadata.insert(
-                        "execution_id".to_string(),
-                        serde_json::Value::String(context.execution_id.clone()),
+                    self.warnings.add(
+                        WarningCode::UnsupportedOutputFormat,
+                        format!("Unsupported output format: {}", output_format),
                     );
-                    metadata.insert(
-                        "timestamp".to_string(),
-                        serde_json::Value::String(chrono::Utc::now().to_rfc3339()),
+                    continue;
+                }
+            };
+
+            // load.file_pattern 取代預設的 output/processed_data，{partition} 依分區替換
+            let entry_base = |partition: Option<&str>| match &self.config.load.file_pattern {
+                Some(pattern) => FilenameValues {
+                    record_count: Some(result.processed_records.len()),
+                    ..self.filename_values(context)
+                }
+                .with_format(output_format)
+                .with_partition(partition)
+                .render(pattern),
+                None => default_base.to_string(),
+            };
+            let base = entry_base(None);
+            let base = base.as_str();
+
+            let skipped = self.report_null_skips(output_format, &result.processed_records)?;
+            let mut rendered = 0;
+            match &partitions {
+                Some((field, layout, partitions)) => {
+                    for (value, records) in partitions {
+                        let base = entry_base(Some(value));
+                        for (part, chunk) in chunk_records(records, max_per_file) {
+                            rendered += chunk.len();
+                            archive.add_records(
+                                &layout.entry_name(field, value, &base, extension, part),
+                                self.output_bytes(
+                                    output_format,
+                                    render_output(output_format, chunk, &self.config.load)?,
+                                )?,
+                                chunk.len() - self.null_skipped(output_format, chunk)?,
+                            );
+                        }
+                    }
+                }
+                None if max_per_file.is_some() => {
+                    for (part, chunk) in chunk_records(&result.processed_records, max_per_file) {
+                        rendered += chunk.len();
+                        archive.add_records(
+                            &chunk_entry_name(base, extension, part),
+                            self.output_bytes(
+                                output_format,
+                                render_output(output_format, chunk, &self.config.load)?,
+                            )?,
+                            chunk.len() - self.null_skipped(output_format, chunk)?,
+                        );
+                    }
+                }
+                None => {
+                    rendered = result.processed_records.len();
+                    let data = match output_format.as_str() {
+                        "csv" => result.csv_output.clone(),
+                        "tsv" => result.tsv_output.clone(),
+                        _ => render_output(
+                            output_format,
+                            &result.processed_records,
+                            &self.config.load,
+                        )?,
+                    };
+                    archive.add_records(
+                        &format!("{}{}", base, extension),
+                        self.output_bytes(output_format, data)?,
+                        rendered - skipped,
                     );
-                    let metadata_json = serde_json::to_string_pretty(&metadata)?;
-                    zip.write_all(metadata_json.as_bytes())?;
                 }
             }
+            let written = rendered.saturating_sub(skipped);
+            if loaded.is_none_or(|(count, _)| written < count) {
+                loaded = Some((written, skipped));
+            }
+        }
+        if let Some((written, skipped)) = loaded {
+            let has_transform_counts = self
+                .reconciliation
+                .lock()
+                .is_ok_and(|reconciliation| reconciliation.is_some());
+            if has_transform_counts {
+                self.reconcile(|reconciliation| {
+                    reconciliation.check_load(written, skipped).cloned()
+                })?;
+            }
+        }
+
+        // 添加中繼結果（檔名與格式可設定，也可關閉）
+        if let Some(intermediate) = self
+            .config
+            .transform
+            .intermediate
+            .as_ref()
+            .filter(|intermediate| intermediate.output_enabled())
+        {
+            if !result.intermediate_data.is_empty() {
+                archive.add_records(
+                    &intermediate.output_file_name()?,
+                    intermediate
+                        .output_format()?
+                        .render(&result.intermediate_data)?,
+                    result.intermediate_data.len(),
+                );
+            }
+        }
+
+        // 添加元數據
+        if let Some(compression) = &self.config.load.compression {
+            if compression.include_metadata.unwrap_or(false) {
+                let mut metadata = HashMap::new();
+                metadata.insert(
+                    "pipeline_name".to_string(),
+                    serde_json::Value::String(self.name.clone()),
+                );
+                metadata.insert(
+                    "execution_id".to_string(),
+                    serde_json::Value::String(context.execution_id.clone()),
+                );
+                metadata.insert(
+                    "timestamp".to_string(),
+                    serde_json::Value::String(chrono::Utc::now().to_rfc3339()),
+                );
+                if self.is_partial() {
+                    // 收到停止訊號時輸出的只是部分結果
+                    metadata.insert("partial".to_string(), serde_json::Value::Bool(true));
+                }
+                let mut metadata = serde_json::to_value(metadata)?;
+                redact::redact_json(&mut metadata);
+                archive.add("metadata.json", serde_json::to_string_pretty(&metadata)?);
+            }
+        }
+
+        // 添加 HTTP 稽核紀錄
+        if let Some(audit) = &self.http_audit {
+            let entries = audit.take();
+            tracing::info!(
+                "🧾 {}: Writing {} audited requests to {}",
+                self.name,
+                entries.len(),
+                http_audit::AUDIT_FILE_NAME
+            );
+            archive.add(
+                http_audit::AUDIT_FILE_NAME,
+                http_audit::render_jsonl(&entries)?,
+            );
+        }
 
-            // 完成並取回底層 Vec<u8>
-            let cursor = zip.finish()?;
-            cursor.into_inner()
+        // 寫出追加輸出與封裝檔；交易式寫入時先暫存，全部成功後才一起改名為正式檔名
+        let bytes_written = if self.config.load.is_transactional() {
+            let staged = StagedStorage::new(&self.storage, &context.execution_id);
+            match self
+                .write_outputs(&staged, archive, &filename, result)
+                .await
+            {
+                Ok(bytes_written) => {
+                    let files = staged.commit().await?;
+                    tracing::info!("🔒 {}: Committed {} staged outputs", self.name, files);
+                    self.record_metadata("committed_files", serde_json::json!(files));
+                    bytes_written
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "🔒 {}: Rolling back staged outputs {:?} after load failure",
+                        self.name,
+                        staged.staged_targets()
+                    );
+                    staged.rollback().await;
+                    return Err(e);
+                }
+            }
+        } else {
+            self.write_outputs(&self.storage, archive, &filename, result)
+                .await?
         };
+        self.record_metadata("bytes_written", serde_json::json!(bytes_written));
+
+        // 記錄已處理的檔案（只在整個 Pipeline 成功載入後）
+        if let Some(ledger_path) = self
+            .config
+            .source
+            .files
+            .as_ref()
+            .and_then(|files| files.ledger.as_ref())
+        {
+            let processed = self
+                .pending_files
+                .lock()
+                .map(|mut pending| std::mem::take(&mut *pending))
+                .unwrap_or_default();
+            if !processed.is_empty() {
+                let mut ledger = FileLedger::load(ledger_path)?;
+                for (path, entry) in processed {
+                    ledger.record(&path, entry);
+                }
+                ledger.save()?;
+                tracing::info!(
+                    "📒 {}: Ledger {} now lists {} processed files",
+                    self.name,
+                    ledger_path,
+                    ledger.len()
+                );
+            }
+        }
 
-        // 保存 ZIP 文件
-        self.storage.write_file(&filename, &zip_data).await?;
+        // 保存條件式請求的 ETag / Last-Modified（只在整個 Pipeline 成功載入後）
+        let validators = self
+            .pending_validators
+            .lock()
+            .map(|mut pending| std::mem::take(&mut *pending))
+            .unwrap_or_default();
+        for entry in &validators {
+            conditional_requests::save(
+                &self.storage,
+                &self.name,
+                entry,
+                self.state_cipher.as_deref(),
+            )
+            .await?;
+        }
+
+        // 持久化 checkpoint（只在整個 Pipeline 成功載入後推進 watermark）
+        if let Some(checkpoint) = self.config.checkpoint.as_ref().filter(|c| c.is_enabled()) {
+            let state = self
+                .checkpoint_state
+                .lock()
+                .ok()
+                .and_then(|current| current.clone());
+            if let Some(mut state) = state {
+                state.pipeline_name = self.name.clone();
+                state.execution_id = Some(context.execution_id.clone());
+                state.updated_at = Some(chrono::Utc::now().to_rfc3339());
+                state
+                    .save(&self.storage, checkpoint, self.state_cipher.as_deref())
+                    .await?;
+                self.record_metadata("checkpoint", serde_json::json!(state.values));
+            }
+        }
 
         tracing::info!("💾 {}: Load completed successfully", self.name);
         Ok(output_path)
@@ -1248,12 +3859,12 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
                 let record_count = if let Some(from_pipeline) = &record_condition.from_pipeline {
                     context
                         .get_result_by_name(from_pipeline)
-                        .map(|r| r.records.len())
+                        .map(|r| context.record_count(r))
                         .unwrap_or(0)
                 } else {
                     context
                         .get_previous_result()
-                        .map(|r| r.records.len())
+                        .map(|r| context.record_count(r))
                         .unwrap_or(0)
                 };
 
@@ -1286,6 +3897,59 @@ impl<S: Storage> ContextualPipeline for SequenceAwarePipeline<S> {
 
         true
     }
+
+    fn take_execution_metadata(&self) -> HashMap<String, serde_json::Value> {
+        let mut metadata = self
+            .execution_metadata
+            .lock()
+            .map(|mut metadata| std::mem::take(&mut *metadata))
+            .unwrap_or_default();
+        // HTTP 呼叫計數隨 metadata 一併取出歸零，重試前的嘗試不會累計到下一次
+        metadata.insert(
+            "http_calls".to_string(),
+            serde_json::json!(self.http_calls.swap(0, Ordering::Relaxed)),
+        );
+        metadata.insert(
+            "http_retries".to_string(),
+            serde_json::json!(self.http_retries.swap(0, Ordering::Relaxed)),
+        );
+        metadata
+    }
+
+    fn take_warnings(&self) -> Vec<Warning> {
+        self.warnings.take()
+    }
+
+    fn is_view(&self) -> bool {
+        self.config.source.r#type == "view"
+    }
+
+    fn output_definitions(&self) -> HashMap<String, String> {
+        self.config.outputs.clone().unwrap_or_default()
+    }
+
+    fn on_success(&self) -> Option<&str> {
+        self.config.on_success.as_deref()
+    }
+
+    fn on_failure(&self) -> Option<&str> {
+        self.config.on_failure.as_deref()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.config.dependencies.clone().unwrap_or_default()
+    }
+
+    fn skip_on_empty_input(&self) -> bool {
+        self.config.skip_on_empty_input.unwrap_or(false)
+    }
+
+    fn source_not_modified(&self) -> bool {
+        let requests = self.conditional_requests.load(Ordering::Relaxed);
+        self.conditional_config() == Some(NotModifiedPolicy::Skip)
+            && requests > 0
+            && self.not_modified_responses.load(Ordering::Relaxed) == requests
+    }
 }
 
 #[cfg(test)]
@@ -1315,6 +3979,22 @@ mod tests {
                 parameters: None,
                 payload: None,
                 data_source: None,
+                batch_parameters: None,
+                rate_limit: None,
+                auth: None,
+                http: None,
+                encoding: None,
+                response_format: None,
+                join: None,
+                combined: None,
+                audit: None,
+                follow_links: None,
+                on_record_error: None,
+                files: None,
+                s3: None,
+                fan_out_checkpoint_every: None,
+                response_metadata: None,
+                conditional: None,
             },
             extract: crate::config::sequence_config::ExtractConfig {
                 max_records: None,
@@ -1322,27 +4002,651 @@ mod tests {
                 field_mapping: None,
                 filters: None,
                 data_processing: None,
+                cache: None,
+                data_path: None,
+                records_from_object_keys: None,
+                object_path: None,
+                object_key_field: None,
+                max_response_bytes: None,
+                on_limit_exceeded: None,
             },
             transform: crate::config::sequence_config::TransformConfig {
                 operations: None,
                 validation: None,
                 intermediate: None,
                 data_enrichment: None,
+                field_transforms: None,
+                record_timeout_ms: None,
+                aggregation: None,
+                script: None,
             },
             load: crate::config::sequence_config::LoadConfig {
                 output_path: temp_dir.path().to_str().unwrap().to_string(),
                 output_formats: vec!["json".to_string()],
                 filename_pattern: None,
+                file_pattern: None,
                 compression: None,
                 append_to_sequence: None,
+                append: None,
+                partition_by: None,
+                partition_layout: None,
+                max_records_per_file: None,
+                columns: None,
+                strict_columns: None,
+                sftp: None,
+                infer_schema: None,
+                expected_schema: None,
+                csv: None,
+                null_policy: None,
+                write_mode: None,
+                transactional: None,
+                manifest: None,
             },
             dependencies: None,
             conditions: None,
+            checkpoint: None,
+            outputs: None,
+            dead_letter: None,
+            context_index: None,
+            on_success: None,
+            on_failure: None,
+            skip_on_empty_input: None,
+            quality: None,
+            reconciliation: None,
+            profile: None,
         };
 
         SequenceAwarePipeline::new("test_pipeline".to_string(), storage, config)
     }
 
+    #[tokio::test]
+    async fn test_missing_keep_only_fields_are_reported_as_warnings() {
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.transform.operations =
+            Some(crate::config::sequence_config::TransformOperations {
+                clean_text: None,
+                trim_whitespace: None,
+                remove_html_tags: None,
+                normalize_fields: None,
+                keep_only_fields: Some(vec!["id".to_string(), "email".to_string()]),
+                exclude_fields: None,
+                custom: None,
+                mask_fields: None,
+                hash_fields: None,
+                hash_salt: None,
+                tokenize_fields: None,
+            });
+
+        let records = vec![
+            Record {
+                data: HashMap::from([("id".to_string(), json!(1))]),
+            },
+            Record {
+                data: HashMap::from([("id".to_string(), json!(2))]),
+            },
+        ];
+        let mut context = PipelineContext::new("test".to_string());
+        pipeline
+            .transform_with_context(records, &mut context)
+            .await
+            .unwrap();
+
+        let warnings = pipeline.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, WarningCode::MissingField);
+        assert_eq!(warnings[0].count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_records_routed_to_rejects_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut pipeline = create_test_pipeline();
+        let output_path = temp_dir.path().join("out").to_str().unwrap().to_string();
+        pipeline.storage = LocalStorage::new(output_path.clone());
+        pipeline.config.transform.validation = Some(ValidationConfig {
+            required_fields: Some(vec!["id".to_string()]),
+            field_types: Some(HashMap::from([("id".to_string(), "integer".to_string())])),
+            min_records: Some(1),
+            max_records: None,
+            on_invalid: Some("reject".to_string()),
+        });
+        pipeline.config.load.output_path = output_path;
+
+        let records = vec![
+            Record {
+                data: HashMap::from([("id".to_string(), json!(1))]),
+            },
+            Record {
+                data: HashMap::from([("id".to_string(), json!("x"))]),
+            },
+        ];
+        let mut context = PipelineContext::new("test".to_string());
+        let result = pipeline
+            .transform_with_context(records.clone(), &mut context)
+            .await
+            .unwrap();
+        assert_eq!(result.processed_records.len(), 1);
+
+        let rejects_path = temp_dir.path().join("out/test_pipeline_rejects.json");
+        let rejects: Vec<crate::core::dead_letter::DeadLetter> =
+            serde_json::from_slice(&std::fs::read(rejects_path).unwrap()).unwrap();
+        assert_eq!(rejects.len(), 1);
+        assert_eq!(rejects[0].stage, "validate");
+        assert_eq!(
+            rejects[0].reason,
+            "Field 'id' expected integer but got string"
+        );
+        assert_eq!(pipeline.take_warnings()[0].code, WarningCode::InvalidRecord);
+
+        // 預設策略為 fail
+        pipeline
+            .config
+            .transform
+            .validation
+            .as_mut()
+            .unwrap()
+            .on_invalid = None;
+        assert!(pipeline
+            .transform_with_context(records, &mut context)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_file_written_under_relative_output_path() {
+        let temp_dir = TempDir::new_in(".").unwrap();
+        let name = temp_dir.path().file_name().unwrap().to_str().unwrap();
+        let output_path = format!("./{}", name);
+
+        let mut pipeline = create_test_pipeline();
+        pipeline.storage = LocalStorage::new(output_path.clone());
+        pipeline.config.load.output_path = output_path.clone();
+        pipeline.config.transform.validation = Some(ValidationConfig {
+            required_fields: Some(vec!["id".to_string()]),
+            field_types: None,
+            min_records: None,
+            max_records: None,
+            on_invalid: Some("reject".to_string()),
+        });
+
+        let records = vec![Record {
+            data: HashMap::from([("name".to_string(), json!("x"))]),
+        }];
+        let mut context = PipelineContext::new("test".to_string());
+        pipeline
+            .transform_with_context(records, &mut context)
+            .await
+            .unwrap();
+
+        assert!(temp_dir.path().join("test_pipeline_rejects.json").exists());
+        assert!(!temp_dir.path().join(name).exists());
+        assert_eq!(
+            pipeline.take_execution_metadata()["rejects_path"],
+            json!(format!("{}/test_pipeline_rejects.json", output_path))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_append_output_propagates_read_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut pipeline = create_test_pipeline();
+        pipeline.storage = LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        pipeline.config.load.output_path = temp_dir.path().to_str().unwrap().to_string();
+        pipeline.config.load.append = Some(crate::config::sequence_config::AppendConfig {
+            path: "history.csv".to_string(),
+            schema_evolution: None,
+        });
+        let result = TransformResult {
+            processed_records: vec![Record {
+                data: HashMap::from([("id".to_string(), json!(1))]),
+            }],
+            csv_output: String::new(),
+            tsv_output: String::new(),
+            intermediate_data: Vec::new(),
+        };
+        let context = PipelineContext::new("test".to_string());
+
+        // 檔案不存在時建立新檔
+        pipeline.load_with_context(&result, &context).await.unwrap();
+        let appended = std::fs::read_to_string(temp_dir.path().join("history.csv")).unwrap();
+        assert_eq!(appended.lines().count(), 2);
+
+        // 其他讀取錯誤不可當作空檔而覆寫既有資料
+        struct UnreadableStorage(LocalStorage);
+        impl Storage for UnreadableStorage {
+            async fn read_file(&self, _path: &str) -> Result<Vec<u8>> {
+                Err(EtlError::IoError(std::io::Error::from(
+                    std::io::ErrorKind::PermissionDenied,
+                )))
+            }
+
+            async fn write_file(&self, path: &str, data: &[u8]) -> Result<()> {
+                self.0.write_file(path, data).await
+            }
+        }
+        let storage = UnreadableStorage(LocalStorage::new(
+            temp_dir.path().to_str().unwrap().to_string(),
+        ));
+        let archive = OutputArchive::new(pipeline.archive_format().unwrap());
+        assert!(pipeline
+            .write_outputs(&storage, archive, "items.json", &result)
+            .await
+            .is_err());
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("history.csv")).unwrap(),
+            appended
+        );
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_retry_counted_and_audited() {
+        let server = httpmock::MockServer::start();
+        let token = server.mock(|when, then| {
+            when.method(httpmock::Method::POST).path("/token");
+            then.status(200)
+                .json_body(json!({"access_token": "t1", "expires_in": 3600}));
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/items");
+            then.status(401);
+        });
+
+        let mut config = create_test_pipeline().config;
+        config.source.audit = Some(true);
+        config.source.auth = Some(crate::config::sequence_config::AuthConfig {
+            r#type: "oauth2".to_string(),
+            token_url: Some(server.url("/token")),
+            client_id: Some("etl".to_string()),
+            client_secret: Some("secret".to_string()),
+            ..Default::default()
+        });
+        let pipeline = SequenceAwarePipeline::new(
+            "test_pipeline".to_string(),
+            create_test_pipeline().storage,
+            config,
+        );
+
+        let response = pipeline
+            .send_request(pipeline.client().unwrap().get(server.url("/items")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        token.assert_hits(2);
+
+        // 刷新 token 後的重送同樣計入請求數與稽核日誌
+        let metadata = pipeline.take_execution_metadata();
+        assert_eq!(metadata["http_calls"], 2);
+        assert_eq!(metadata["http_retries"], 1);
+        let entries = pipeline.http_audit.as_ref().unwrap().take();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.status == Some(401)));
+    }
+
+    #[tokio::test]
+    async fn test_extract_cache_keyed_by_rendered_request_in_state_dir() {
+        let server = httpmock::MockServer::start();
+        let acme = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/items")
+                .query_param("limit", "10")
+                .header("x-tenant", "acme");
+            then.status(200).json_body(json!([{"id": 1}]));
+        });
+        let globex = server.mock(|when, then| {
+            when.method(httpmock::Method::POST)
+                .path("/items")
+                .header("x-tenant", "globex");
+            then.status(200).json_body(json!([{"id": 2}]));
+        });
+
+        let state_dir = TempDir::new().unwrap();
+        let mut pipeline = create_test_pipeline().with_cache_dir(state_dir.path().join("cache"));
+        let output_dir = TempDir::new().unwrap();
+        pipeline.storage = LocalStorage::new(output_dir.path().to_str().unwrap().to_string());
+        pipeline.config.source.endpoint = Some(server.url("/items"));
+        pipeline.config.source.method = Some("POST".to_string());
+        pipeline.config.source.parameters =
+            Some(HashMap::from([("limit".to_string(), "10".to_string())]));
+        pipeline.config.source.headers = Some(HashMap::from([(
+            "X-Tenant".to_string(),
+            "{{tenant}}".to_string(),
+        )]));
+        pipeline.config.extract.cache = Some(crate::config::sequence_config::ExtractCacheConfig {
+            enabled: Some(true),
+            ttl_minutes: Some(60),
+        });
+
+        let mut context = PipelineContext::new("test".to_string());
+        context.add_shared_data("tenant".to_string(), json!("acme"));
+        let first = pipeline.extract_cache_request(&context).unwrap();
+        assert_eq!(first["requests"][0]["headers"]["x-tenant"], "acme");
+        assert!(first["requests"][0]["url"]
+            .as_str()
+            .unwrap()
+            .ends_with("/items?limit=10"));
+
+        // 相同的請求重複使用快取，標頭值改變時重新擷取
+        for _ in 0..2 {
+            pipeline.extract_with_context(&context).await.unwrap();
+        }
+        acme.assert_hits(1);
+        context.add_shared_data("tenant".to_string(), json!("globex"));
+        assert_ne!(pipeline.extract_cache_request(&context).unwrap(), first);
+        let records = pipeline.extract_with_context(&context).await.unwrap();
+        assert_eq!(records[0].data["id"], json!(2));
+        globex.assert_hits(1);
+
+        // 快取寫在狀態目錄，不在輸出目錄
+        let cached = std::fs::read_dir(state_dir.path().join("cache/test_pipeline")).unwrap();
+        assert_eq!(cached.count(), 2);
+        assert!(!output_dir.path().join(".cache").exists());
+    }
+
+    #[tokio::test]
+    async fn test_follow_links_concatenates_pages() {
+        let server = httpmock::MockServer::start();
+        let first = server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/items")
+                .query_param("page", "1");
+            then.status(200).json_body(json!({
+                "data": [{"id": 1}, {"id": 2}],
+                "paging": {"next": "/items?page=2"}
+            }));
+        });
+        let second = server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/items")
+                .query_param("page", "2");
+            then.status(200)
+                .json_body(json!({"data": [{"id": 3}], "paging": {}}));
+        });
+
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.source.endpoint = Some(server.url("/items?page=1"));
+        pipeline.config.source.follow_links = Some(FollowLinksConfig {
+            records_path: Some("data".to_string()),
+            ..Default::default()
+        });
+
+        let context = PipelineContext::new("test".to_string());
+        let records = pipeline.fetch_api_data(&context).await.unwrap();
+        first.assert();
+        second.assert();
+        let ids: Vec<_> = records.iter().map(|r| r.data["id"].clone()).collect();
+        assert_eq!(ids, [json!(1), json!(2), json!(3)]);
+        assert_eq!(pipeline.take_execution_metadata()["pages"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_follow_links_stops_at_other_origin() {
+        let server = httpmock::MockServer::start();
+        let other = httpmock::MockServer::start();
+        let leaked = other.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/items");
+            then.status(200).json_body(json!({"data": [{"id": 3}]}));
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/items")
+                .header("Authorization", "Bearer secret");
+            then.status(200).json_body(json!({
+                "data": [{"id": 1}],
+                "paging": {"next": other.url("/items?page=2")}
+            }));
+        });
+
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.source.endpoint = Some(server.url("/items"));
+        pipeline.config.source.headers = Some(HashMap::from([(
+            "Authorization".to_string(),
+            "Bearer secret".to_string(),
+        )]));
+        pipeline.config.source.follow_links = Some(FollowLinksConfig {
+            records_path: Some("data".to_string()),
+            ..Default::default()
+        });
+
+        let context = PipelineContext::new("test".to_string());
+        let records = pipeline.fetch_api_data(&context).await.unwrap();
+        leaked.assert_hits(0);
+        assert_eq!(records.len(), 1);
+        let warnings = pipeline.take_warnings();
+        assert_eq!(warnings[0].code, WarningCode::PaginationStopped);
+        assert!(warnings[0].message.contains("different origin"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_http_config_fails_instead_of_using_defaults() {
+        let server = httpmock::MockServer::start();
+        let items = server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/items");
+            then.status(200).json_body(json!([{"id": 1}]));
+        });
+        let template = create_test_pipeline();
+        let mut config = template.config;
+        config.source.endpoint = Some(server.url("/items"));
+        config.source.http = Some(crate::config::sequence_config::HttpConfig {
+            ca_cert: Some("/nonexistent/ca.pem".to_string()),
+            ..Default::default()
+        });
+        let pipeline =
+            SequenceAwarePipeline::new("test_pipeline".to_string(), template.storage, config);
+
+        // CA 憑證無法讀取時不可改用預設設定送出請求
+        let context = PipelineContext::new("test".to_string());
+        let error = pipeline.fetch_api_data(&context).await.unwrap_err();
+        assert!(error.to_string().contains("failed to build HTTP client"));
+        items.assert_hits(0);
+    }
+
+    #[tokio::test]
+    async fn test_parameterized_call_errors_are_skipped() {
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/users/2");
+            then.status(500);
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path_matches(regex::Regex::new("^/users/[13]$").unwrap());
+            then.status(200).json_body(json!({"ok": true}));
+        });
+
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.source.endpoint = Some(server.url("/users/{id}"));
+        pipeline.config.source.on_record_error = Some("skip".to_string());
+        pipeline.config.source.data_source = Some(crate::config::sequence_config::DataSource {
+            use_previous_output: Some(true),
+            from_pipeline: None,
+            merge_with_api: None,
+        });
+
+        let mut context = PipelineContext::new("test".to_string());
+        context.add_result(crate::core::pipeline_sequence::PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: (1..=3)
+                .map(|id| Record {
+                    data: HashMap::from([("id".to_string(), json!(id))]),
+                })
+                .collect::<Vec<_>>()
+                .into(),
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+
+        let records = pipeline.fetch_parameterized_api(&context).await.unwrap();
+        assert_eq!(records.len(), 2);
+        let warnings = pipeline.take_warnings();
+        assert_eq!(warnings[0].code, WarningCode::RecordCallFailed);
+        assert!(warnings[0].message.contains("\"id\":2"));
+        assert_eq!(pipeline.take_execution_metadata()["failed_calls"], 1);
+
+        pipeline.config.source.on_record_error = Some("fail".to_string());
+        assert!(pipeline.fetch_parameterized_api(&context).await.is_err());
+    }
+
+    #[test]
+    fn test_parameter_records_are_borrowed_from_context() {
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.source.data_source = Some(crate::config::sequence_config::DataSource {
+            use_previous_output: Some(true),
+            from_pipeline: Some("users".to_string()),
+            merge_with_api: None,
+        });
+        let mut context = PipelineContext::new("test".to_string());
+        context.add_result(crate::core::pipeline_sequence::PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: (1..=3)
+                .map(|id| Record {
+                    data: HashMap::from([("id".to_string(), json!(id))]),
+                })
+                .collect::<Vec<_>>()
+                .into(),
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+
+        // 參數化呼叫直接讀取上下文中的記錄，不複製
+        let records = pipeline.parameter_source_records(&context).unwrap();
+        assert!(matches!(records, Cow::Borrowed(_)));
+        let stored = &context.get_result_by_name("users").unwrap().records;
+        assert!(std::ptr::eq(records.as_ptr(), stored.as_ptr()));
+    }
+
+    #[tokio::test]
+    async fn test_parameterized_calls_stop_near_deadline() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut pipeline = create_test_pipeline();
+        pipeline.storage = LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        pipeline.config.source.data_source = Some(crate::config::sequence_config::DataSource {
+            use_previous_output: Some(true),
+            from_pipeline: None,
+            merge_with_api: None,
+        });
+        let pipeline = pipeline.with_budget(ExecutionBudget::new(std::time::Duration::ZERO));
+
+        let mut context = PipelineContext::new("test".to_string());
+        context.add_result(crate::core::pipeline_sequence::PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: vec![Record {
+                data: HashMap::from([("id".to_string(), json!(1))]),
+            }]
+            .into(),
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+
+        let records = pipeline.fetch_parameterized_api(&context).await.unwrap();
+        assert!(records.is_empty());
+        assert_eq!(pipeline.take_warnings()[0].code, WarningCode::DeadlineStop);
+
+        let metadata = pipeline.take_execution_metadata();
+        assert_eq!(metadata["deadline_stop"]["remaining_calls"], 1);
+        let remaining = temp_dir
+            .path()
+            .join(".checkpoints/test_pipeline_remaining.json");
+        assert!(remaining.exists());
+    }
+
+    #[tokio::test]
+    async fn test_parameterized_calls_fail_when_memory_stays_over_limit() {
+        let server = httpmock::MockServer::start();
+        let calls = server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path_contains("/users/");
+            then.status(200).json_body(json!({"ok": true}));
+        });
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.source.endpoint = Some(server.url("/users/{id}"));
+        pipeline.config.source.data_source = Some(crate::config::sequence_config::DataSource {
+            use_previous_output: Some(true),
+            from_pipeline: None,
+            merge_with_api: None,
+        });
+
+        let mut context = PipelineContext::new("test".to_string());
+        let pressure = Arc::new(
+            crate::utils::monitor::MemoryPressure::new(100, std::time::Duration::from_millis(5))
+                .with_sampler(|| Some(500), std::time::Duration::from_millis(1)),
+        );
+        context.configure_memory_pressure(Arc::clone(&pressure));
+        context.add_result(crate::core::pipeline_sequence::PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: (1..=3)
+                .map(|id| Record {
+                    data: HashMap::from([("id".to_string(), json!(id))]),
+                })
+                .collect::<Vec<_>>()
+                .into(),
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+
+        // 只暫停一次，記憶體仍未回落即中止，不再逐次呼叫等待
+        let error = pipeline
+            .fetch_parameterized_api(&context)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, EtlError::ResourceExhaustedError { .. }));
+        assert_eq!(pressure.pauses(), 1);
+        calls.assert_hits(0);
+    }
+
+    #[tokio::test]
+    async fn test_parameterized_calls_stop_on_shutdown() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut pipeline = create_test_pipeline();
+        pipeline.storage = LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        pipeline.config.source.data_source = Some(crate::config::sequence_config::DataSource {
+            use_previous_output: Some(true),
+            from_pipeline: None,
+            merge_with_api: None,
+        });
+        let shutdown = ShutdownSignal::new();
+        shutdown.request();
+        let pipeline = pipeline.with_shutdown(shutdown);
+
+        let mut context = PipelineContext::new("test".to_string());
+        context.add_result(crate::core::pipeline_sequence::PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: vec![Record {
+                data: HashMap::from([("id".to_string(), json!(1))]),
+            }]
+            .into(),
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+
+        let records = pipeline.fetch_parameterized_api(&context).await.unwrap();
+        assert!(records.is_empty());
+        assert_eq!(pipeline.take_warnings()[0].code, WarningCode::Interrupted);
+        assert!(pipeline.is_partial());
+
+        let metadata = pipeline.take_execution_metadata();
+        assert_eq!(metadata["partial"], true);
+        assert_eq!(metadata["interrupted"]["remaining_calls"], 1);
+    }
+
     #[test]
     fn test_extract_nested_value_simple_path() {
         let pipeline = create_test_pipeline();
@@ -1409,6 +4713,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_extract_nested_value_different_types() {
         let pipeline = create_test_pipeline();
 
@@ -1943,6 +5248,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pivot_object_keys() {
+        let response = serde_json::json!({
+            "meta": {"source": "stats"},
+            "data": {"daily": {
+                "2024-01-01": {"visits": 10},
+                "2024-01-02": {"visits": 12},
+                "total": 22
+            }}
+        });
+
+        let pivoted = pivot_object_keys(response.clone(), Some("data.daily"), "date").unwrap();
+        assert_eq!(
+            pivoted,
+            serde_json::json!([
+                {"date": "2024-01-01", "visits": 10},
+                {"date": "2024-01-02", "visits": 12},
+                {"date": "total", "value": 22}
+            ])
+        );
+        assert!(pivot_object_keys(response.clone(), Some("data.missing"), "date").is_err());
+        assert!(pivot_object_keys(serde_json::json!([1]), None, "key").is_err());
+    }
+
+    #[test]
+    fn test_records_from_data_path() {
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.extract.data_path = Some("data.items".to_string());
+        pipeline.config.extract.field_mapping = Some(HashMap::from([(
+            "user.name".to_string(),
+            FieldMapping::Rename("user_name".to_string()),
+        )]));
+
+        let records = pipeline
+            .records_from_json(serde_json::json!({
+                "data": {"items": [{"id": 1, "user": {"name": "Ann"}}, {"id": 2}]},
+                "meta": {"page": 1}
+            }))
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].data["user_name"], "Ann");
+        assert!(!records[0].data.contains_key("meta"));
+
+        let empty = serde_json::json!({"data": {"items": null}});
+        assert!(pipeline.records_from_json(empty).unwrap().is_empty());
+        let missing = serde_json::json!({"data": {}});
+        assert!(pipeline.records_from_json(missing).is_err());
+    }
+
     #[test]
     fn test_process_payload_template_with_shared_data() {
         let pipeline = create_test_pipeline();
@@ -2013,4 +5367,99 @@ mod tests {
 
         println!("Processed payload (priority test): {}", processed);
     }
+
+    #[test]
+    fn test_split_batched_endpoints_respects_max_length() {
+        let values: Vec<String> = (1..=10).map(|i| i.to_string()).collect();
+        let endpoint = "http://api.test/users?ids={ids}";
+
+        let endpoints = split_batched_endpoints(endpoint, "{ids}", &values, ",", 35);
+
+        assert!(endpoints.len() > 1);
+        for url in &endpoints {
+            assert!(url.len() <= 35, "URL too long: {}", url);
+        }
+        let joined: Vec<String> = endpoints
+            .iter()
+            .map(|url| {
+                url.trim_start_matches("http://api.test/users?ids=")
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(joined.join(","), "1,2,3,4,5,6,7,8,9,10");
+    }
+
+    #[test]
+    fn test_split_batched_endpoints_counts_every_placeholder() {
+        let values: Vec<String> = ["1", "2", "3"].iter().map(|v| v.to_string()).collect();
+        let endpoint = "http://x/{ids}?check={ids}";
+
+        // 兩個佔位符都會展開，"1,2" 的 URL 長度為 25
+        let endpoints = split_batched_endpoints(endpoint, "{ids}", &values, ",", 25);
+        assert_eq!(endpoints, ["http://x/1,2?check=1,2", "http://x/3?check=3"]);
+        for url in &endpoints {
+            assert!(url.len() <= 25, "URL too long: {}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batched_values_are_path_encoded() {
+        let server = httpmock::MockServer::start();
+        let batch = server.mock(|when, then| {
+            when.method(httpmock::Method::GET)
+                .path("/users/a%20b,c%2Fd");
+            then.status(200).json_body(json!([{"ok": true}]));
+        });
+        let mut pipeline = create_test_pipeline();
+        pipeline.config.source.endpoint = Some(server.url("/users/{names}"));
+        pipeline.config.source.batch_parameters =
+            Some(crate::config::sequence_config::BatchParameterConfig {
+                placeholder: "names".to_string(),
+                field: "name".to_string(),
+                separator: None,
+                max_url_length: None,
+            });
+        pipeline.config.source.data_source = Some(crate::config::sequence_config::DataSource {
+            use_previous_output: Some(true),
+            from_pipeline: None,
+            merge_with_api: None,
+        });
+
+        let mut context = PipelineContext::new("test".to_string());
+        context.add_result(crate::core::pipeline_sequence::PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: ["a b", "c/d"]
+                .iter()
+                .map(|name| Record {
+                    data: HashMap::from([("name".to_string(), json!(name))]),
+                })
+                .collect::<Vec<_>>()
+                .into(),
+            output_path: String::new(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+
+        let records = pipeline
+            .fetch_batched_parameterized_api(&context)
+            .await
+            .unwrap();
+        batch.assert();
+        assert_eq!(records.len(), 1);
+    }
+
+    #[test]
+    fn test_split_batched_endpoints_single_batch_and_oversized_value() {
+        let values = vec!["a".to_string(), "b".to_string()];
+        let endpoints = split_batched_endpoints("http://x/{v}", "{v}", &values, "|", 100);
+        assert_eq!(endpoints, vec!["http://x/a|b".to_string()]);
+
+        let values = vec!["a".repeat(50), "b".to_string()];
+        let endpoints = split_batched_endpoints("http://x/{v}", "{v}", &values, ",", 20);
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[1], "http://x/b");
+    }
 }
@@ -0,0 +1,260 @@
+use crate::config::sequence_config::CsvOutputConfig;
+use crate::core::null_policy::{output_fields, NullPolicy};
+use crate::core::Record;
+use crate::utils::encoding;
+use crate::utils::error::{EtlError, Result};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// CSV/TSV 的分隔字元、引號與換行設定
+#[derive(Debug, Clone, Copy)]
+pub struct DelimitedFormat {
+    delimiter: u8,
+    quote_style: csv::QuoteStyle,
+    crlf: bool,
+    tsv: bool,
+    null_policy: NullPolicy,
+}
+
+impl DelimitedFormat {
+    pub const QUOTE_STYLES: [&'static str; 4] = ["necessary", "always", "non_numeric", "never"];
+    pub const LINE_TERMINATORS: [&'static str; 2] = ["lf", "crlf"];
+
+    /// 依 load.csv 設定建立 CSV 格式；未設定時為逗號分隔、必要時加引號、LF 換行
+    pub fn csv(config: Option<&CsvOutputConfig>) -> Result<Self> {
+        let Some(config) = config else {
+            return Ok(Self {
+                delimiter: b',',
+                quote_style: csv::QuoteStyle::Necessary,
+                crlf: false,
+                tsv: false,
+                null_policy: NullPolicy::Empty,
+            });
+        };
+
+        let delimiter = config.delimiter.as_deref().unwrap_or(",");
+        let delimiter = match delimiter.as_bytes() {
+            [byte] if byte.is_ascii() && !matches!(byte, b'"' | b'\n' | b'\r') => *byte,
+            _ => {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: "load.csv.delimiter".to_string(),
+                    value: delimiter.to_string(),
+                    reason: "Must be a single ASCII character other than a quote or newline"
+                        .to_string(),
+                })
+            }
+        };
+
+        let quote_style = match config.quote_style.as_deref().unwrap_or("necessary") {
+            "necessary" => csv::QuoteStyle::Necessary,
+            "always" => csv::QuoteStyle::Always,
+            "non_numeric" => csv::QuoteStyle::NonNumeric,
+            "never" => csv::QuoteStyle::Never,
+            other => {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: "load.csv.quote_style".to_string(),
+                    value: other.to_string(),
+                    reason: format!("Supported styles: {}", Self::QUOTE_STYLES.join(", ")),
+                })
+            }
+        };
+
+        let crlf = match config.line_terminator.as_deref().unwrap_or("lf") {
+            "lf" => false,
+            "crlf" => true,
+            other => {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: "load.csv.line_terminator".to_string(),
+                    value: other.to_string(),
+                    reason: format!(
+                        "Supported terminators: {}",
+                        Self::LINE_TERMINATORS.join(", ")
+                    ),
+                })
+            }
+        };
+
+        Ok(Self {
+            delimiter,
+            quote_style,
+            crlf,
+            tsv: false,
+            null_policy: NullPolicy::Empty,
+        })
+    }
+
+    /// TSV：不加引號，值中的 tab 與換行以空白取代
+    pub fn tsv() -> Self {
+        Self {
+            delimiter: b'\t',
+            quote_style: csv::QuoteStyle::Never,
+            crlf: false,
+            tsv: true,
+            null_policy: NullPolicy::Empty,
+        }
+    }
+
+    /// 缺少或為 null 的值依 load.null_policy 處理
+    pub fn with_null_policy(mut self, null_policy: NullPolicy) -> Self {
+        self.null_policy = null_policy;
+        self
+    }
+
+    pub fn line_terminator(&self) -> &'static str {
+        if self.crlf {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+
+    /// 將記錄輸出為分隔字元格式，最後一行不含換行
+    ///
+    /// 指定 `columns` 時依其順序輸出，記錄缺少的欄位留空、未列出的欄位略過；
+    /// 未指定時以所有記錄欄位的聯集（排序後）為標頭。
+    pub fn render(&self, records: &[Record], columns: Option<&[String]>) -> Result<String> {
+        if records.is_empty() {
+            return Ok(String::new());
+        }
+        let field_names = output_fields(records, columns);
+        let format = if self.tsv { "tsv" } else { "csv" };
+
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .quote_style(self.quote_style)
+            .terminator(if self.crlf {
+                csv::Terminator::CRLF
+            } else {
+                csv::Terminator::Any(b'\n')
+            })
+            .from_writer(Vec::new());
+        writer.write_record(&field_names)?;
+        for (index, record) in records.iter().enumerate() {
+            if !self.null_policy.keep(record, &field_names, index, format)? {
+                continue;
+            }
+            writer.write_record(
+                field_names
+                    .iter()
+                    .map(|field_name| self.render_value(record.data.get(*field_name))),
+            )?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| EtlError::ProcessingError {
+            message: format!("Failed to render delimited output: {}", e),
+        })?;
+        let mut output = String::from_utf8(bytes).map_err(|e| EtlError::ProcessingError {
+            message: format!("Failed to render delimited output: {}", e),
+        })?;
+        output.truncate(output.trim_end_matches(self.line_terminator()).len());
+        Ok(output)
+    }
+
+    fn render_value(&self, value: Option<&serde_json::Value>) -> String {
+        let text = match value {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            None | Some(serde_json::Value::Null) if self.null_policy == NullPolicy::Null => {
+                NullPolicy::NULL_TEXT.to_string()
+            }
+            None | Some(serde_json::Value::Null) => String::new(),
+            Some(other) => other.to_string(),
+        };
+        if self.tsv {
+            // TSV：移除會破壞欄位的 tab 與換行
+            text.replace(['\t', '\n'], " ")
+        } else {
+            text
+        }
+    }
+}
+
+/// 取得 load.csv.encoding 指定的輸出編碼，並檢查 BOM 設定
+pub fn output_encoding(config: &CsvOutputConfig) -> Result<&'static Encoding> {
+    let label = config.encoding.as_deref().unwrap_or("utf-8");
+    let output = encoding::lookup(label).map_err(|_| EtlError::InvalidConfigValueError {
+        field: "load.csv.encoding".to_string(),
+        value: label.to_string(),
+        reason: "Unknown character encoding".to_string(),
+    })?;
+    if config.bom.unwrap_or(false) && ![UTF_8, UTF_16LE, UTF_16BE].contains(&output) {
+        return Err(EtlError::InvalidConfigValueError {
+            field: "load.csv.bom".to_string(),
+            value: "true".to_string(),
+            reason: format!("{} does not use a byte order mark", output.name()),
+        });
+    }
+    Ok(output)
+}
+
+/// 依 load.csv 的 encoding 與 bom 將 CSV 轉為輸出位元組
+pub fn encode_csv(text: &str, config: &CsvOutputConfig) -> Result<Vec<u8>> {
+    let output = output_encoding(config)?;
+    let mut bytes = Vec::new();
+    if config.bom.unwrap_or(false) {
+        bytes.extend_from_slice(match output.name() {
+            "UTF-16LE" => &[0xFF, 0xFE],
+            "UTF-16BE" => &[0xFE, 0xFF],
+            _ => &[0xEF, 0xBB, 0xBF],
+        });
+    }
+    bytes.extend(encoding::encode_from_utf8(text, output)?);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn record(fields: serde_json::Value) -> Record {
+        Record {
+            data: serde_json::from_value::<HashMap<_, _>>(fields).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_with_options_and_encode() {
+        let records = [
+            record(json!({"id": 1, "name": "Café; Bar", "note": "say \"hi\""})),
+            record(json!({"id": 2, "name": "Plain", "note": null})),
+        ];
+        assert_eq!(
+            DelimitedFormat::csv(None)
+                .unwrap()
+                .render(&records, None)
+                .unwrap(),
+            "id,name,note\n1,Café; Bar,\"say \"\"hi\"\"\"\n2,Plain,"
+        );
+
+        let config = CsvOutputConfig {
+            delimiter: Some(";".to_string()),
+            quote_style: Some("non_numeric".to_string()),
+            line_terminator: Some("crlf".to_string()),
+            encoding: Some("windows-1252".to_string()),
+            bom: None,
+        };
+        let csv = DelimitedFormat::csv(Some(&config))
+            .unwrap()
+            .render(&records, Some(&["id".to_string(), "name".to_string()]))
+            .unwrap();
+        assert_eq!(csv, "\"id\";\"name\"\r\n1;\"Café; Bar\"\r\n2;\"Plain\"");
+        assert_eq!(encode_csv("Café", &config).unwrap(), b"Caf\xe9".to_vec());
+        assert!(encode_csv("€ 日本", &config).is_err());
+
+        let utf16 = CsvOutputConfig {
+            encoding: Some("utf-16le".to_string()),
+            bom: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            encode_csv("a,é", &utf16).unwrap(),
+            vec![0xFF, 0xFE, b'a', 0, b',', 0, 0xE9, 0]
+        );
+
+        let invalid = CsvOutputConfig {
+            delimiter: Some("::".to_string()),
+            ..Default::default()
+        };
+        assert!(DelimitedFormat::csv(Some(&invalid)).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 參數化 API 呼叫中單筆參數失敗（範本替換或請求失敗）時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordErrorPolicy {
+    /// 中止 Pipeline
+    Fail,
+    /// 記錄警告與參數後繼續其他呼叫
+    Skip,
+    /// 將參數記錄寫入 rejects 檔後繼續
+    DeadLetter,
+}
+
+impl RecordErrorPolicy {
+    pub const SUPPORTED: [&'static str; 3] = ["fail", "skip", "dead_letter"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "fail" => Ok(Self::Fail),
+            "skip" => Ok(Self::Skip),
+            "dead_letter" => Ok(Self::DeadLetter),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "source.on_record_error".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 無法處理的記錄與失敗原因
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub stage: String, // "template"、"extract"、"validate" 或 "timeout"
+    pub reason: String,
+    pub record: HashMap<String, serde_json::Value>,
+}
+
+/// 收集被拒絕的記錄（可在 &self 方法中使用）
+#[derive(Debug, Default)]
+pub struct DeadLetterQueue {
+    entries: Mutex<Vec<DeadLetter>>,
+}
+
+impl DeadLetterQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入被拒絕的記錄，返回目前累計數量
+    pub fn push(
+        &self,
+        stage: &str,
+        reason: impl Into<String>,
+        record: HashMap<String, serde_json::Value>,
+    ) -> usize {
+        match self.entries.lock() {
+            Ok(mut entries) => {
+                entries.push(DeadLetter {
+                    stage: stage.to_string(),
+                    reason: reason.into(),
+                    record,
+                });
+                entries.len()
+            }
+            Err(_) => 0,
+        }
+    }
+
+    /// 取出並清空目前收集的記錄
+    pub fn take(&self) -> Vec<DeadLetter> {
+        self.entries
+            .lock()
+            .map(|mut entries| std::mem::take(&mut *entries))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_push_and_take() {
+        let queue = DeadLetterQueue::new();
+        let record = HashMap::from([("id".to_string(), serde_json::json!(1))]);
+        assert_eq!(queue.push("extract", "HTTP 500", record.clone()), 1);
+        assert_eq!(queue.push("validate", "missing id", record), 2);
+
+        let entries = queue.take();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].stage, "extract");
+        assert!(queue.take().is_empty());
+    }
+
+    #[test]
+    fn test_record_error_policy_parse() {
+        assert_eq!(
+            RecordErrorPolicy::parse("dead_letter").unwrap(),
+            RecordErrorPolicy::DeadLetter
+        );
+        assert_eq!(
+            RecordErrorPolicy::parse("skip").unwrap(),
+            RecordErrorPolicy::Skip
+        );
+        assert!(RecordErrorPolicy::parse("retry").is_err());
+    }
+}
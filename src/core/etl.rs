@@ -1,3 +1,4 @@
+pub use crate::app::pipelines::sequence_engine::SequenceEngine;
 pub use crate::domain::services::etl_engine::EtlEngine;
 
 #[cfg(test)]
@@ -174,7 +175,10 @@ mod tests {
         let result = engine.run().await;
 
         assert!(result.is_err());
-        match result.unwrap_err() {
+        // 與序列相同：保留原始錯誤，另附上失敗的階段
+        let error = result.unwrap_err();
+        assert_eq!(error.context().unwrap().stage.as_deref(), Some("extract"));
+        match error.root() {
             EtlError::DataValidationError { message } => {
                 assert_eq!(message, "Mock extract failure");
             }
@@ -190,7 +194,9 @@ mod tests {
         let result = engine.run().await;
 
         assert!(result.is_err());
-        match result.unwrap_err() {
+        let error = result.unwrap_err();
+        assert_eq!(error.context().unwrap().stage.as_deref(), Some("transform"));
+        match error.root() {
             EtlError::TransformationError { stage, details } => {
                 assert_eq!(stage, "test");
                 assert_eq!(details, "Mock transformation failure");
@@ -207,7 +213,9 @@ mod tests {
         let result = engine.run().await;
 
         assert!(result.is_err());
-        match result.unwrap_err() {
+        let error = result.unwrap_err();
+        assert_eq!(error.context().unwrap().stage.as_deref(), Some("load"));
+        match error.root() {
             EtlError::IoError(_) => {} // Expected error type
             other => panic!("Expected IoError, got: {:?}", other),
         }
@@ -0,0 +1,140 @@
+use crate::config::sequence_config::ExtractCacheConfig;
+use crate::core::{Record, Storage};
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 快取的完整擷取結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractCacheEntry {
+    pub key: String,
+    pub created_at: String,
+    pub records: Vec<Record>,
+}
+
+impl ExtractCacheEntry {
+    pub fn new(key: String, records: Vec<Record>) -> Self {
+        Self {
+            key,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            records,
+        }
+    }
+
+    /// 是否仍在 TTL 內
+    pub fn is_fresh(&self, ttl_minutes: u64) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.created_at)
+            .map(|created_at| {
+                let age = chrono::Utc::now().signed_duration_since(created_at);
+                age < chrono::Duration::minutes(ttl_minutes as i64)
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// 以請求內容（已解析的端點、參數等）計算快取鍵
+pub fn cache_key(request: &serde_json::Value) -> String {
+    let digest = Sha256::digest(request.to_string().as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 快取檔路徑（相對於狀態目錄下的快取目錄）
+pub fn cache_path(pipeline_name: &str, key: &str) -> String {
+    format!("{}/{}.json", pipeline_name, key)
+}
+
+/// 讀取仍有效的快取記錄，不存在、過期或損毀時返回 None
+pub async fn load<S: Storage>(
+    storage: &S,
+    pipeline_name: &str,
+    key: &str,
+    config: &ExtractCacheConfig,
+    cipher: Option<&StateCipher>,
+) -> Option<Vec<Record>> {
+    let path = cache_path(pipeline_name, key);
+    let bytes = storage.read_file(&path).await.ok()?;
+    let entry = open_state(cipher, &bytes, &path)
+        .and_then(|json| Ok(serde_json::from_slice::<ExtractCacheEntry>(&json)?));
+
+    match entry {
+        Ok(entry) if entry.key == key && entry.is_fresh(config.ttl_minutes()) => {
+            Some(entry.records)
+        }
+        Ok(_) => {
+            tracing::info!("🗄️ {}: Extract cache expired", pipeline_name);
+            None
+        }
+        Err(e) => {
+            tracing::warn!(
+                "🗄️ {}: Ignoring unreadable extract cache: {}",
+                pipeline_name,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// 寫入擷取結果快取
+pub async fn save<S: Storage>(
+    storage: &S,
+    pipeline_name: &str,
+    key: &str,
+    records: &[Record],
+    cipher: Option<&StateCipher>,
+) -> Result<()> {
+    let path = cache_path(pipeline_name, key);
+    let entry = ExtractCacheEntry::new(key.to_string(), records.to_vec());
+    let json = serde_json::to_vec(&entry)?;
+    storage
+        .write_file(&path, &seal_state(cipher, &json, &path)?)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cache_key_is_stable() {
+        let a = cache_key(&json!({"endpoint": "http://a", "params": {"x": 1}}));
+        let b = cache_key(&json!({"endpoint": "http://a", "params": {"x": 1}}));
+        let c = cache_key(&json!({"endpoint": "http://b", "params": {"x": 1}}));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 64);
+    }
+
+    #[test]
+    fn test_entry_freshness() {
+        let mut entry = ExtractCacheEntry::new("k".to_string(), Vec::new());
+        assert!(entry.is_fresh(60));
+
+        entry.created_at = (chrono::Utc::now() - chrono::Duration::minutes(61)).to_rfc3339();
+        assert!(!entry.is_fresh(60));
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = crate::LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        let config = ExtractCacheConfig {
+            enabled: Some(true),
+            ttl_minutes: Some(5),
+        };
+        let records = vec![Record {
+            data: std::collections::HashMap::from([("id".to_string(), json!(1))]),
+        }];
+
+        save(&storage, "users", "abc", &records, None)
+            .await
+            .unwrap();
+        let cached = load(&storage, "users", "abc", &config, None).await.unwrap();
+        assert_eq!(cached.len(), 1);
+        assert!(load(&storage, "users", "other", &config, None)
+            .await
+            .is_none());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::core::extract_cache::cache_key;
+use crate::core::{Record, Storage};
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 一次已完成的參數化呼叫與其擷取的記錄
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanOutCall {
+    pub key: String,
+    pub records: Vec<Record>,
+}
+
+/// 參數化呼叫的鍵：以端點與參數記錄計算，同一筆參數在重新執行時得到相同的鍵
+pub fn call_key(endpoint: &str, parameters: &HashMap<String, serde_json::Value>) -> String {
+    cache_key(&serde_json::json!({
+        "endpoint": endpoint,
+        "parameters": parameters,
+    }))
+}
+
+/// 參數化呼叫的進度（source.fan_out_checkpoint_every）
+///
+/// 每完成 `every` 次呼叫就將這批結果寫成一個分段檔
+/// （`.checkpoints/{pipeline}/{execution_id}/fan_out_000001.json`…），
+/// 以相同 execution_id 重新執行時讀回已完成的呼叫，只補上尚未完成的部分。
+pub struct FanOutProgress {
+    dir: String,
+    every: usize,
+    segments: usize,
+    completed: HashMap<String, Vec<Record>>,
+    pending: Vec<FanOutCall>,
+}
+
+impl FanOutProgress {
+    /// 讀取此執行已保存的分段；遇到不存在或損毀（寫入中斷）的分段即停止
+    pub async fn load<S: Storage>(
+        storage: &S,
+        pipeline_name: &str,
+        execution_id: &str,
+        every: usize,
+        cipher: Option<&StateCipher>,
+    ) -> Result<Self> {
+        let mut progress = Self {
+            dir: format!(".checkpoints/{}/{}", pipeline_name, execution_id),
+            every: every.max(1),
+            segments: 0,
+            completed: HashMap::new(),
+            pending: Vec::new(),
+        };
+        loop {
+            let path = progress.segment_path(progress.segments + 1);
+            let Ok(bytes) = storage.read_file(&path).await else {
+                break;
+            };
+            let calls = open_state(cipher, &bytes, &path)
+                .and_then(|json| Ok(serde_json::from_slice::<Vec<FanOutCall>>(&json)?));
+            match calls {
+                Ok(calls) => {
+                    progress
+                        .completed
+                        .extend(calls.into_iter().map(|call| (call.key, call.records)));
+                    progress.segments += 1;
+                }
+                Err(e) => {
+                    // 之後的寫入會覆蓋這個分段
+                    tracing::warn!(
+                        "📍 {}: Ignoring unreadable fan-out progress '{}': {}",
+                        pipeline_name,
+                        path,
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    fn segment_path(&self, segment: usize) -> String {
+        format!("{}/fan_out_{:06}.json", self.dir, segment)
+    }
+
+    /// 先前執行已完成的呼叫數
+    pub fn completed_calls(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// 取出先前執行已完成的呼叫結果
+    pub fn take(&mut self, key: &str) -> Option<Vec<Record>> {
+        self.completed.remove(key)
+    }
+
+    /// 記錄完成的呼叫，累積 `every` 次後寫入分段
+    pub async fn record<S: Storage>(
+        &mut self,
+        storage: &S,
+        key: String,
+        records: &[Record],
+        cipher: Option<&StateCipher>,
+    ) -> Result<()> {
+        self.pending.push(FanOutCall {
+            key,
+            records: records.to_vec(),
+        });
+        if self.pending.len() >= self.every {
+            self.flush(storage, cipher).await?;
+        }
+        Ok(())
+    }
+
+    /// 將尚未寫入的呼叫寫成新的分段
+    pub async fn flush<S: Storage>(
+        &mut self,
+        storage: &S,
+        cipher: Option<&StateCipher>,
+    ) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let path = self.segment_path(self.segments + 1);
+        let json = serde_json::to_vec(&self.pending)?;
+        storage
+            .write_file(&path, &seal_state(cipher, &json, &path)?)
+            .await?;
+        self.segments += 1;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::cli::LocalStorage;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn record(id: i64) -> Record {
+        Record {
+            data: HashMap::from([("id".to_string(), json!(id))]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_completed_calls() {
+        let dir = TempDir::new().unwrap();
+        let storage = LocalStorage::new(dir.path().to_string_lossy().to_string());
+        let key = |id: i64| call_key(&format!("http://api/users/{}", id), &record(id).data);
+
+        let mut progress = FanOutProgress::load(&storage, "users", "exec1", 2, None)
+            .await
+            .unwrap();
+        for id in 1..=3 {
+            progress
+                .record(&storage, key(id), &[record(id)], None)
+                .await
+                .unwrap();
+        }
+        // 第三次呼叫尚未累積到 2 次，模擬中斷時不會保存
+
+        let mut resumed = FanOutProgress::load(&storage, "users", "exec1", 2, None)
+            .await
+            .unwrap();
+        assert_eq!(resumed.completed_calls(), 2);
+        assert_eq!(resumed.take(&key(2)).unwrap()[0].data["id"], json!(2));
+        assert!(resumed.take(&key(3)).is_none());
+
+        resumed
+            .record(&storage, key(3), &[record(3)], None)
+            .await
+            .unwrap();
+        resumed.flush(&storage, None).await.unwrap();
+        let resumed = FanOutProgress::load(&storage, "users", "exec1", 2, None)
+            .await
+            .unwrap();
+        assert_eq!(resumed.completed_calls(), 3);
+
+        let other = FanOutProgress::load(&storage, "users", "exec2", 2, None)
+            .await
+            .unwrap();
+        assert_eq!(other.completed_calls(), 0);
+    }
+}
@@ -0,0 +1,395 @@
+use crate::config::sequence_config::FieldTransformConfig;
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 未指定 date_format 時依序嘗試的日期格式
+pub(crate) const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%Y%m%d"];
+pub(crate) const DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"];
+
+/// 型別轉換目標
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastType {
+    String,
+    Int,
+    Float,
+    Bool,
+    /// 轉為 ISO 日期字串（YYYY-MM-DD）
+    Date,
+}
+
+impl CastType {
+    pub const SUPPORTED: [&'static str; 5] = ["string", "int", "float", "bool", "date"];
+
+    pub fn parse(value: &str, field: &str) -> Result<Self> {
+        match value {
+            "string" => Ok(Self::String),
+            "int" | "integer" => Ok(Self::Int),
+            "float" | "number" => Ok(Self::Float),
+            "bool" | "boolean" => Ok(Self::Bool),
+            "date" => Ok(Self::Date),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: other.to_string(),
+                reason: format!("Supported casts: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledTransform {
+    field: String,
+    rename: Option<String>,
+    cast: Option<CastType>,
+    date_format: Option<String>,
+    default: Option<Value>,
+    regex_replace: Option<(regex::Regex, String)>,
+}
+
+/// 編譯後的欄位轉換，每個欄位依序套用 default → regex_replace → cast → rename
+#[derive(Debug, Clone, Default)]
+pub struct FieldTransformer {
+    transforms: Vec<CompiledTransform>,
+}
+
+impl FieldTransformer {
+    /// 編譯設定；型別、正規表示式或重新命名衝突有誤時返回錯誤
+    pub fn compile(config: &HashMap<String, FieldTransformConfig>) -> Result<Self> {
+        let mut fields: Vec<&String> = config.keys().collect();
+        fields.sort();
+
+        let mut targets = HashSet::new();
+        let mut transforms = Vec::with_capacity(fields.len());
+        for field in fields {
+            let transform = &config[field];
+            let prefix = format!("transform.field_transforms.{}", field);
+
+            let target = transform.rename.as_ref().unwrap_or(field);
+            if !targets.insert(target.clone()) {
+                return Err(EtlError::ConfigValidationError {
+                    field: format!("{}.rename", prefix),
+                    message: format!("More than one field would be written to '{}'", target),
+                });
+            }
+
+            let regex_replace = match &transform.regex_replace {
+                Some(replace) => Some((
+                    regex::Regex::new(&replace.pattern).map_err(|e| {
+                        EtlError::InvalidConfigValueError {
+                            field: format!("{}.regex_replace.pattern", prefix),
+                            value: replace.pattern.clone(),
+                            reason: e.to_string(),
+                        }
+                    })?,
+                    replace.replacement.clone(),
+                )),
+                None => None,
+            };
+
+            transforms.push(CompiledTransform {
+                field: field.clone(),
+                rename: transform.rename.clone(),
+                cast: transform
+                    .cast
+                    .as_deref()
+                    .map(|cast| CastType::parse(cast, &format!("{}.cast", prefix)))
+                    .transpose()?,
+                date_format: transform.date_format.clone(),
+                default: transform.default.clone(),
+                regex_replace,
+            });
+        }
+        Ok(Self { transforms })
+    }
+
+    /// 在獨立執行緒中套用並限制時間，逾時返回 None
+    ///
+    /// 逾時的執行緒無法中斷，會在背景跑完後被丟棄；使用一般執行緒而非
+    /// `spawn_blocking`，避免卡住的記錄拖住 runtime 關閉。
+    pub async fn apply_with_timeout(
+        self: &Arc<Self>,
+        mut record: Record,
+        timeout: Duration,
+    ) -> Option<(Record, Vec<String>)> {
+        let transformer = Arc::clone(self);
+        run_detached(timeout, move || {
+            let violations = transformer.apply(&mut record);
+            (record, violations)
+        })
+        .await
+    }
+
+    /// 套用到單筆記錄，返回無法轉型的違規描述（空表示全部成功）
+    pub fn apply(&self, record: &mut Record) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        // 先取出所有欄位再寫回，避免 a→b、b→a 這類互換被前一次重新命名覆蓋
+        let mut updates = Vec::new();
+        for transform in &self.transforms {
+            let mut value = record.data.remove(&transform.field);
+            if matches!(value, None | Some(Value::Null)) {
+                if let Some(default) = &transform.default {
+                    value = Some(default.clone());
+                }
+            }
+
+            if let Some((pattern, replacement)) = &transform.regex_replace {
+                if let Some(Value::String(text)) = &mut value {
+                    *text = pattern.replace_all(text, replacement.as_str()).into_owned();
+                }
+            }
+
+            if let (Some(cast), Some(current)) = (transform.cast, &value) {
+                if !current.is_null() {
+                    match cast_value(current, cast, transform.date_format.as_deref()) {
+                        Some(cast_value) => value = Some(cast_value),
+                        None => violations.push(format!(
+                            "Field '{}' value {} cannot be cast to {:?}",
+                            transform.field, current, cast
+                        )),
+                    }
+                }
+            }
+
+            if let Some(value) = value {
+                let target = transform.rename.as_ref().unwrap_or(&transform.field);
+                updates.push((target.clone(), value));
+            }
+        }
+        record.data.extend(updates);
+
+        violations
+    }
+}
+
+pub(crate) fn cast_value(
+    value: &Value,
+    cast: CastType,
+    date_format: Option<&str>,
+) -> Option<Value> {
+    match cast {
+        CastType::String => Some(Value::String(match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })),
+        CastType::Int => match value {
+            Value::Number(n) => n.as_i64().or_else(|| {
+                n.as_f64()
+                    .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+                    .map(|f| f as i64)
+            }),
+            Value::String(s) => s.trim().parse::<i64>().ok(),
+            Value::Bool(b) => Some(*b as i64),
+            _ => None,
+        }
+        .map(Value::from),
+        CastType::Float => match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse::<f64>().ok().filter(|f| f.is_finite()),
+            Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+        .and_then(|f| serde_json::Number::from_f64(f).map(Value::Number)),
+        CastType::Bool => match value {
+            Value::Bool(b) => Some(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(0) => Some(false),
+                Some(1) => Some(true),
+                _ => None,
+            },
+            Value::String(s) => match s.trim().to_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Some(true),
+                "false" | "no" | "n" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+        .map(Value::Bool),
+        CastType::Date => match value {
+            Value::String(s) => parse_date(s.trim(), date_format),
+            Value::Number(n) => n
+                .as_i64()
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+                .map(|time| time.date_naive()),
+            _ => None,
+        }
+        .map(|date| Value::String(date.format("%Y-%m-%d").to_string())),
+    }
+}
+
+fn parse_date(text: &str, format: Option<&str>) -> Option<NaiveDate> {
+    if let Some(format) = format {
+        return NaiveDate::parse_from_str(text, format).ok().or_else(|| {
+            NaiveDateTime::parse_from_str(text, format)
+                .ok()
+                .map(|time| time.date())
+        });
+    }
+
+    DateTime::parse_from_rfc3339(text)
+        .ok()
+        .map(|time| time.date_naive())
+        .or_else(|| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+        })
+        .or_else(|| {
+            DATETIME_FORMATS.iter().find_map(|format| {
+                NaiveDateTime::parse_from_str(text, format)
+                    .ok()
+                    .map(|time| time.date())
+            })
+        })
+}
+
+async fn run_detached<T: Send + 'static>(
+    timeout: Duration,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(work());
+    });
+    tokio::time::timeout(timeout, receiver).await.ok()?.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::sequence_config::RegexReplaceConfig;
+    use serde_json::json;
+
+    fn transformer(config: &[(&str, FieldTransformConfig)]) -> Result<FieldTransformer> {
+        FieldTransformer::compile(
+            &config
+                .iter()
+                .map(|(field, transform)| (field.to_string(), transform.clone()))
+                .collect(),
+        )
+    }
+
+    fn record(value: Value) -> Record {
+        Record {
+            data: serde_json::from_value(value).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_rename_cast_default_and_replace() {
+        let transformer = transformer(&[
+            (
+                "userId",
+                FieldTransformConfig {
+                    rename: Some("user_id".to_string()),
+                    cast: Some("int".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "price",
+                FieldTransformConfig {
+                    regex_replace: Some(RegexReplaceConfig {
+                        pattern: r"[^0-9.]".to_string(),
+                        replacement: String::new(),
+                    }),
+                    cast: Some("float".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "active",
+                FieldTransformConfig {
+                    cast: Some("bool".to_string()),
+                    default: Some(json!("no")),
+                    ..Default::default()
+                },
+            ),
+            (
+                "created",
+                FieldTransformConfig {
+                    cast: Some("date".to_string()),
+                    date_format: Some("%d/%m/%Y".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ])
+        .unwrap();
+
+        let mut record = record(json!({
+            "userId": "42", "price": "NT$1,299.50", "created": "31/01/2024"
+        }));
+        assert!(transformer.apply(&mut record).is_empty());
+        assert_eq!(
+            Value::Object(record.data.into_iter().collect()),
+            json!({"user_id": 42, "price": 1299.5, "active": false, "created": "2024-01-31"})
+        );
+    }
+
+    #[test]
+    fn test_cast_failures_and_swaps() {
+        let transformer = transformer(&[
+            (
+                "a",
+                FieldTransformConfig {
+                    rename: Some("b".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "b",
+                FieldTransformConfig {
+                    rename: Some("a".to_string()),
+                    cast: Some("int".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ])
+        .unwrap();
+
+        let mut record = record(json!({"a": 1, "b": "x"}));
+        let violations = transformer.apply(&mut record);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(record.data["b"], json!(1));
+        assert_eq!(record.data["a"], json!("x"));
+
+        assert_eq!(
+            cast_value(&json!("2024-03-01T10:00:00Z"), CastType::Date, None),
+            Some(json!("2024-03-01"))
+        );
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let rename = |target: &str| FieldTransformConfig {
+            rename: Some(target.to_string()),
+            ..Default::default()
+        };
+        assert!(transformer(&[("a", rename("c")), ("b", rename("c"))]).is_err());
+        assert!(transformer(&[(
+            "a",
+            FieldTransformConfig {
+                cast: Some("decimal".to_string()),
+                ..Default::default()
+            }
+        )])
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_detached_times_out() {
+        let finished = run_detached(Duration::from_secs(5), || 42).await;
+        assert_eq!(finished, Some(42));
+
+        let stalled = run_detached(Duration::from_millis(10), || {
+            std::thread::sleep(Duration::from_millis(500));
+        })
+        .await;
+        assert!(stalled.is_none());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::utils::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 已處理檔案的識別：相同路徑但大小或修改時間改變時視為新檔案
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub size: u64,
+    pub modified: i64, // Unix 秒數
+    pub processed_at: String,
+}
+
+impl LedgerEntry {
+    /// 讀取檔案目前的大小與修改時間
+    pub fn for_file(path: &Path) -> Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+        Ok(Self {
+            size: metadata.len(),
+            modified,
+            processed_at: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+
+    fn same_file(&self, other: &LedgerEntry) -> bool {
+        self.size == other.size && self.modified == other.modified
+    }
+}
+
+/// files 來源的已處理檔案清單（source.files.ledger），避免重複處理同一個檔案
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FileLedger {
+    #[serde(skip)]
+    path: PathBuf,
+    files: BTreeMap<String, LedgerEntry>,
+}
+
+impl FileLedger {
+    /// 讀取清單；檔案不存在時為空清單
+    pub fn load(path: &str) -> Result<Self> {
+        let mut ledger = match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(e.into()),
+        };
+        ledger.path = PathBuf::from(path);
+        Ok(ledger)
+    }
+
+    /// 尚未處理（或處理後已變更）的檔案與其目前的識別
+    pub fn unprocessed(&self, paths: Vec<PathBuf>) -> Result<Vec<(PathBuf, LedgerEntry)>> {
+        let mut pending = Vec::new();
+        for path in paths {
+            let entry = LedgerEntry::for_file(&path)?;
+            let processed = self
+                .files
+                .get(path.to_string_lossy().as_ref())
+                .is_some_and(|previous| previous.same_file(&entry));
+            if !processed {
+                pending.push((path, entry));
+            }
+        }
+        Ok(pending)
+    }
+
+    pub fn record(&mut self, path: &Path, entry: LedgerEntry) {
+        self.files.insert(path.to_string_lossy().to_string(), entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// 寫入暫存檔後改名，中斷時不會留下損毀的清單
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ledger_skips_processed_files() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("a.csv");
+        let other = dir.path().join("b.csv");
+        std::fs::write(&file, "id\n1\n").unwrap();
+        std::fs::write(&other, "id\n2\n").unwrap();
+        let ledger_path = dir.path().join("state/ledger.json");
+        let ledger_path = ledger_path.to_str().unwrap();
+
+        let mut ledger = FileLedger::load(ledger_path).unwrap();
+        let pending = ledger.unprocessed(vec![file.clone()]).unwrap();
+        assert_eq!(pending.len(), 1);
+        for (path, entry) in pending {
+            ledger.record(&path, entry);
+        }
+        ledger.save().unwrap();
+
+        let ledger = FileLedger::load(ledger_path).unwrap();
+        assert_eq!(ledger.len(), 1);
+        let pending = ledger
+            .unprocessed(vec![file.clone(), other.clone()])
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, other);
+
+        // 內容改變（大小不同）時重新處理
+        std::fs::write(&file, "id\n1\n3\n").unwrap();
+        assert_eq!(ledger.unprocessed(vec![file]).unwrap().len(), 1);
+    }
+}
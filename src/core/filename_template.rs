@@ -0,0 +1,231 @@
+use crate::utils::error::{EtlError, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// 只有輸出檔內的資料檔（load.file_pattern）才有值的變數
+pub const ENTRY_VARIABLES: [&str; 2] = ["format", "partition"];
+
+/// 檔名模板的值：{pipeline_name}、{execution_id}、{timestamp}、{date}、{sequence_name}、
+/// {record_count}、{format}、{partition}，其他 `{name}` 以共享數據中的同名鍵替換
+#[derive(Debug, Clone)]
+pub struct FilenameValues<'a> {
+    pub pipeline_name: &'a str,
+    pub execution_id: &'a str,
+    pub run_start: DateTime<Utc>,
+    pub sequence_name: Option<&'a str>,
+    pub record_count: Option<usize>,
+    pub format: Option<&'a str>,
+    pub partition: Option<&'a str>,
+    pub shared: Option<&'a HashMap<String, serde_json::Value>>,
+}
+
+impl<'a> FilenameValues<'a> {
+    pub fn new(pipeline_name: &'a str, execution_id: &'a str, run_start: DateTime<Utc>) -> Self {
+        Self {
+            pipeline_name,
+            execution_id,
+            run_start,
+            sequence_name: None,
+            record_count: None,
+            format: None,
+            partition: None,
+            shared: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: &'a str) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn with_partition(mut self, partition: Option<&'a str>) -> Self {
+        self.partition = partition;
+        self
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        match name {
+            "pipeline_name" => Some(self.pipeline_name.to_string()),
+            "execution_id" => Some(self.execution_id.to_string()),
+            "timestamp" => Some(Utc::now().format("%Y%m%d_%H%M%S").to_string()),
+            "date" => Some(self.run_start.format("%Y-%m-%d").to_string()),
+            "sequence_name" => self.sequence_name.map(str::to_string),
+            "record_count" => self.record_count.map(|count| count.to_string()),
+            "format" => self.format.map(str::to_string),
+            "partition" => self.partition.map(str::to_string),
+            _ => self.shared?.get(name).map(|value| match value {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            }),
+        }
+    }
+
+    /// 替換 `{name}` 變數；值中不能用於檔名的字元改為 `_`，找不到值的變數保留原樣
+    ///
+    /// `{{...}}` 佔位符不受影響（已由內建佔位符處理）。
+    pub fn render(&self, pattern: &str) -> String {
+        let mut rendered = String::with_capacity(pattern.len());
+        let mut last = 0;
+        for (start, end, name) in variables(pattern) {
+            let Some(value) = self.value(name) else {
+                continue;
+            };
+            rendered.push_str(&pattern[last..start]);
+            rendered.push_str(&sanitize(&value));
+            last = end;
+        }
+        rendered.push_str(&pattern[last..]);
+        rendered
+    }
+}
+
+fn variable_pattern() -> &'static regex::Regex {
+    static PATTERN: OnceLock<regex::Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| regex::Regex::new(r"\{([A-Za-z_][A-Za-z0-9_.\-]*)\}").unwrap())
+}
+
+/// 模板中的 `{name}` 變數（位置與名稱），略過 `{{...}}` 佔位符內的部分
+fn variables(pattern: &str) -> Vec<(usize, usize, &str)> {
+    let bytes = pattern.as_bytes();
+    variable_pattern()
+        .captures_iter(pattern)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            let name = captures.get(1)?.as_str();
+            let inside_braces = (whole.start() > 0 && bytes[whole.start() - 1] == b'{')
+                || bytes.get(whole.end()) == Some(&b'}');
+            (!inside_braces).then_some((whole.start(), whole.end(), name))
+        })
+        .collect()
+}
+
+/// 將值中的路徑分隔字元與其他不能用於檔名的字元改為 `_`
+pub fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_whitespace() || c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// 檢查模板：filename_pattern（`entry = false`）不能使用只對資料檔有意義的 `{format}`、`{partition}`
+pub fn validate_pattern(pattern: &str, field: &str, entry: bool) -> Result<()> {
+    if pattern.trim().is_empty() {
+        return Err(EtlError::InvalidConfigValueError {
+            field: field.to_string(),
+            value: pattern.to_string(),
+            reason: "Pattern must not be empty".to_string(),
+        });
+    }
+    if !entry {
+        if let Some((_, _, name)) = variables(pattern)
+            .into_iter()
+            .find(|(_, _, name)| ENTRY_VARIABLES.contains(name))
+        {
+            return Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: pattern.to_string(),
+                reason: format!("{{{}}} is only available in load.file_pattern", name),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// 檢查 file_pattern 在各輸出格式下產生的檔名互不相同，也不與 `reserved`（metadata.json 等）衝突
+///
+/// `formats` 為（格式, 副檔名）；所有格式使用相同的其他變數值，只比較格式造成的差異。
+pub fn check_unique_entries(
+    pattern: &str,
+    formats: &[(&str, &str)],
+    reserved: &[String],
+    field: &str,
+) -> Result<()> {
+    let values = FilenameValues {
+        sequence_name: Some("sequence"),
+        record_count: Some(0),
+        ..FilenameValues::new("pipeline", "execution", Utc::now())
+    };
+    let mut seen = HashSet::new();
+    for (format, extension) in formats {
+        let name = format!(
+            "{}{}",
+            values.clone().with_format(format).render(pattern),
+            extension
+        );
+        if reserved.contains(&name) || !seen.insert(name.clone()) {
+            return Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: pattern.to_string(),
+                reason: format!(
+                    "Pattern yields '{}' more than once or overwrites another output file",
+                    name
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render_variables() {
+        let shared = HashMap::from([
+            ("region".to_string(), serde_json::json!("eu/west")),
+            ("batch".to_string(), serde_json::json!(7)),
+        ]);
+        let values = FilenameValues {
+            sequence_name: Some("nightly"),
+            record_count: Some(42),
+            shared: Some(&shared),
+            ..FilenameValues::new(
+                "orders",
+                "exec_1",
+                Utc.with_ymd_and_hms(2024, 3, 1, 6, 30, 0).unwrap(),
+            )
+        };
+        assert_eq!(
+            values.render("{sequence_name}_{pipeline_name}_{date}_{record_count}_{region}_{batch}"),
+            "nightly_orders_2024-03-01_42_eu_west_7"
+        );
+        assert_eq!(
+            values
+                .clone()
+                .with_format("csv")
+                .with_partition(Some("US"))
+                .render("{pipeline_name}_{format}_{partition}"),
+            "orders_csv_US"
+        );
+        // 沒有值的變數與 {{...}} 佔位符保留原樣
+        assert_eq!(
+            values.render("{format}_{unknown}_{{today}}"),
+            "{format}_{unknown}_{{today}}"
+        );
+    }
+
+    #[test]
+    fn test_validate_patterns() {
+        assert!(validate_pattern("{pipeline_name}_{date}", "load.filename_pattern", false).is_ok());
+        assert!(
+            validate_pattern("{pipeline_name}_{format}", "load.filename_pattern", false).is_err()
+        );
+        assert!(validate_pattern("{pipeline_name}_{format}", "load.file_pattern", true).is_ok());
+
+        let formats = [("csv", ".csv"), ("tsv", ".tsv"), ("json", ".json")];
+        let reserved = vec!["metadata.json".to_string()];
+        assert!(check_unique_entries("{pipeline_name}", &formats, &reserved, "f").is_ok());
+        assert!(check_unique_entries("metadata", &formats, &reserved, "f").is_err());
+        assert!(
+            check_unique_entries("data", &[("csv", ".csv"), ("csv", ".csv")], &reserved, "f")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::utils::error::Result;
+use crate::utils::redact;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// 輸出 ZIP 中的稽核檔名
+pub const AUDIT_FILE_NAME: &str = "http_audit.jsonl";
+
+/// 單一 HTTP 請求的稽核紀錄；URL、標頭與負載皆已遮蔽憑證
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpAuditEntry {
+    pub timestamp: String,
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub payload: Option<serde_json::Value>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl HttpAuditEntry {
+    /// 由實際送出的請求建立紀錄（認證 token 在送出時才加入，不會出現在這裡）
+    pub fn from_request(request: &reqwest::Request) -> Self {
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = if redact::is_sensitive_name(name.as_str()) {
+                    redact::REDACTED.to_string()
+                } else {
+                    redact::redact_sensitive(&String::from_utf8_lossy(value.as_bytes()))
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+        let payload = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|bytes| match serde_json::from_slice(bytes) {
+                Ok(mut json) => {
+                    redact::redact_json(&mut json);
+                    json
+                }
+                Err(_) => serde_json::Value::String(redact::redact_sensitive(
+                    &String::from_utf8_lossy(bytes),
+                )),
+            });
+
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            method: request.method().to_string(),
+            url: redact::redact_sensitive(request.url().as_str()),
+            headers,
+            payload,
+            status: None,
+            error: None,
+            duration_ms: 0,
+        }
+    }
+}
+
+/// 收集 Pipeline 執行期間的 HTTP 稽核紀錄（可在 &self 方法中使用）
+#[derive(Debug, Default)]
+pub struct HttpAuditLog {
+    entries: Mutex<Vec<HttpAuditEntry>>,
+}
+
+impl HttpAuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, entry: HttpAuditEntry) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(entry);
+        }
+    }
+
+    /// 取出並清空目前的紀錄
+    pub fn take(&self) -> Vec<HttpAuditEntry> {
+        self.entries
+            .lock()
+            .map(|mut entries| std::mem::take(&mut *entries))
+            .unwrap_or_default()
+    }
+}
+
+/// 每行一筆 JSON 紀錄
+pub fn render_jsonl(entries: &[HttpAuditEntry]) -> Result<String> {
+    let mut output = String::new();
+    for entry in entries {
+        output.push_str(&serde_json::to_string(entry)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_redacts_secrets() {
+        let request = reqwest::Client::new()
+            .post("https://api.example.com/orders?page=2&api_key=abc123")
+            .header("X-Api-Key", "abc123")
+            .header("Accept", "application/json")
+            .json(&serde_json::json!({"customer": 7, "password": "hunter2"}))
+            .build()
+            .unwrap();
+
+        let mut entry = HttpAuditEntry::from_request(&request);
+        assert_eq!(entry.method, "POST");
+        assert_eq!(
+            entry.url,
+            "https://api.example.com/orders?page=2&api_key=***"
+        );
+        assert_eq!(entry.headers["x-api-key"], redact::REDACTED);
+        assert_eq!(entry.headers["accept"], "application/json");
+        let payload = entry.payload.as_ref().unwrap();
+        assert_eq!(payload["customer"], 7);
+        assert_eq!(payload["password"], redact::REDACTED);
+
+        entry.status = Some(201);
+        let log = HttpAuditLog::new();
+        log.push(entry);
+        let jsonl = render_jsonl(&log.take()).unwrap();
+        assert_eq!(jsonl.lines().count(), 1);
+        assert!(!jsonl.contains("hunter2") && !jsonl.contains("abc123"));
+        assert!(log.take().is_empty());
+    }
+}
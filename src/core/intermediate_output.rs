@@ -0,0 +1,198 @@
+use crate::core::{Record, Storage};
+use crate::utils::error::{EtlError, Result};
+use std::collections::BTreeSet;
+
+/// 預設的中繼結果檔名（不含副檔名）
+pub const DEFAULT_INTERMEDIATE_NAME: &str = "intermediate";
+/// 彙整檔中標示記錄來源 Pipeline 的欄位
+pub const PIPELINE_FIELD: &str = "_pipeline";
+
+/// 中繼結果的輸出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntermediateFormat {
+    #[default]
+    Json,
+    Jsonl,
+    Csv,
+}
+
+impl IntermediateFormat {
+    pub const SUPPORTED: [&'static str; 3] = ["json", "jsonl", "csv"];
+
+    pub fn parse(value: &str, field: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "csv" => Ok(Self::Csv),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: other.to_string(),
+                reason: format!("Supported formats: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => ".json",
+            Self::Jsonl => ".jsonl",
+            Self::Csv => ".csv",
+        }
+    }
+
+    /// 依格式輸出記錄；JSON 與 JSONL 的每筆記錄同為 `{"data": {...}}`，CSV 的欄位為所有記錄欄位的聯集（依名稱排序）
+    pub fn render(&self, records: &[Record]) -> Result<String> {
+        match self {
+            Self::Json => Ok(serde_json::to_string_pretty(records)?),
+            Self::Jsonl => {
+                let mut lines = String::new();
+                for record in records {
+                    lines.push_str(&serde_json::to_string(record)?);
+                    lines.push('\n');
+                }
+                Ok(lines)
+            }
+            Self::Csv => render_csv(records),
+        }
+    }
+}
+
+/// 檔名加上格式副檔名（已帶副檔名時不重複）
+pub fn file_name(name: &str, format: IntermediateFormat) -> String {
+    if name.ends_with(format.extension()) {
+        name.to_string()
+    } else {
+        format!("{}{}", name, format.extension())
+    }
+}
+
+fn render_csv(records: &[Record]) -> Result<String> {
+    let columns: BTreeSet<&String> = records.iter().flat_map(|r| r.data.keys()).collect();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&columns)?;
+    for record in records {
+        writer.write_record(columns.iter().map(|column| match record.data.get(*column) {
+            None | Some(serde_json::Value::Null) => String::new(),
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        }))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| EtlError::ProcessingError {
+        message: format!("Failed to render intermediate CSV: {}", e),
+    })?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 彙整檔的寫出目標；PipelineSequence 不帶存儲型別，以此包裝任一 `Storage`
+#[async_trait::async_trait]
+pub trait AggregateSink: Send + Sync {
+    async fn write_aggregate(&self, path: &str, data: &[u8]) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl<S: Storage> AggregateSink for S {
+    async fn write_aggregate(&self, path: &str, data: &[u8]) -> Result<()> {
+        self.write_file(path, data).await
+    }
+}
+
+/// 序列層級的中繼結果彙整：各 Pipeline 的中繼記錄加上來源欄位後寫成單一檔案
+#[derive(Debug, Default)]
+pub struct IntermediateAggregate {
+    records: Vec<Record>,
+}
+
+impl IntermediateAggregate {
+    pub fn extend(&mut self, pipeline_name: &str, records: &[Record]) {
+        self.records.extend(records.iter().map(|record| {
+            let mut record = record.clone();
+            record.data.insert(
+                PIPELINE_FIELD.to_string(),
+                serde_json::Value::String(pipeline_name.to_string()),
+            );
+            record
+        }));
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// 經由存儲寫出彙整檔（路徑相對於存儲根目錄）
+    pub async fn write(
+        &self,
+        sink: &dyn AggregateSink,
+        path: &str,
+        format: IntermediateFormat,
+    ) -> Result<()> {
+        sink.write_aggregate(path, format.render(&self.records)?.as_bytes())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn record(value: serde_json::Value) -> Record {
+        Record {
+            data: serde_json::from_value::<HashMap<_, _>>(value).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let records = vec![
+            record(serde_json::json!({"id": 1, "name": "Alice, A."})),
+            record(serde_json::json!({"id": 2, "score": 9.5})),
+        ];
+        assert_eq!(
+            IntermediateFormat::Csv.render(&records).unwrap(),
+            "id,name,score\n1,\"Alice, A.\",\n2,,9.5\n"
+        );
+        let jsonl = IntermediateFormat::Jsonl.render(&records).unwrap();
+        assert_eq!(jsonl.lines().count(), 2);
+        // JSON 與 JSONL 使用相同的記錄形狀
+        let json: Vec<serde_json::Value> =
+            serde_json::from_str(&IntermediateFormat::Json.render(&records).unwrap()).unwrap();
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(json, lines);
+        assert_eq!(
+            file_name("users_stage", IntermediateFormat::Jsonl),
+            "users_stage.jsonl"
+        );
+        assert_eq!(file_name("x.csv", IntermediateFormat::Csv), "x.csv");
+        assert!(IntermediateFormat::parse("parquet", "f").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_tags_pipeline() {
+        let mut aggregate = IntermediateAggregate::default();
+        aggregate.extend("users", &[record(serde_json::json!({"id": 1}))]);
+        aggregate.extend("orders", &[record(serde_json::json!({"id": 7}))]);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = crate::LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        aggregate
+            .write(
+                &storage,
+                "all/intermediate.jsonl",
+                IntermediateFormat::Jsonl,
+            )
+            .await
+            .unwrap();
+
+        let content =
+            std::fs::read_to_string(temp_dir.path().join("all/intermediate.jsonl")).unwrap();
+        assert!(content.contains(r#""_pipeline":"orders""#));
+        assert_eq!(aggregate.len(), 2);
+    }
+}
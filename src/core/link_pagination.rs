@@ -0,0 +1,123 @@
+use crate::config::sequence_config::FollowLinksConfig;
+use crate::utils::error::{EtlError, Result};
+use reqwest::header::HeaderMap;
+use reqwest::Url;
+
+/// 依點號路徑取得 JSON 值，例如 "paging.next"
+pub fn value_at_path<'a>(
+    value: &'a serde_json::Value,
+    path: &str,
+) -> Option<&'a serde_json::Value> {
+    path.split('.')
+        .try_fold(value, |current, segment| current.get(segment))
+}
+
+/// 解析 Link 標頭（RFC 8288）中 rel="next" 的 URL
+pub fn next_from_link_header(value: &str) -> Option<String> {
+    value.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params.split(';').any(|param| {
+            param.trim().strip_prefix("rel=").is_some_and(|rel| {
+                rel.trim_matches('"')
+                    .split_whitespace()
+                    .any(|r| r == "next")
+            })
+        });
+        is_next.then(|| {
+            target
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+/// 從標頭或回應內容取得下一頁 URL；相對路徑以目前頁面的 URL 解析，空字串與 null 表示沒有下一頁
+pub fn next_url(
+    config: &FollowLinksConfig,
+    current: &Url,
+    headers: &HeaderMap,
+    body: &serde_json::Value,
+) -> Result<Option<Url>> {
+    let next = match &config.next_header {
+        Some(header) => headers
+            .get(header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                if header.eq_ignore_ascii_case("link") {
+                    next_from_link_header(value)
+                } else {
+                    Some(value.trim().to_string())
+                }
+            }),
+        None => value_at_path(body, config.next_path())
+            .and_then(|value| value.as_str())
+            .map(str::to_string),
+    };
+
+    next.filter(|next| !next.is_empty())
+        .map(|next| {
+            current
+                .join(&next)
+                .map_err(|e| EtlError::DataValidationError {
+                    message: format!("Invalid next page URL '{}': {}", next, e),
+                })
+        })
+        .transpose()
+}
+
+/// 取出每頁的記錄（records_path 未設定時為整個回應）；最後一頁常省略記錄欄位，找不到時視為沒有記錄
+pub fn page_records(config: &FollowLinksConfig, mut body: serde_json::Value) -> serde_json::Value {
+    let Some(path) = &config.records_path else {
+        return body;
+    };
+    path.split('.')
+        .try_fold(&mut body, |current, segment| current.get_mut(segment))
+        .map(serde_json::Value::take)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_next_url_from_body_and_link_header() {
+        let current = Url::parse("https://api.example.com/v1/items?page=1").unwrap();
+        let config = FollowLinksConfig {
+            records_path: Some("data".to_string()),
+            ..Default::default()
+        };
+        let body = json!({"data": [{"id": 1}], "paging": {"next": "/v1/items?page=2"}});
+        let next = next_url(&config, &current, &HeaderMap::new(), &body).unwrap();
+        assert_eq!(
+            next.unwrap().as_str(),
+            "https://api.example.com/v1/items?page=2"
+        );
+        assert_eq!(page_records(&config, body), json!([{"id": 1}]));
+
+        let last = json!({"data": [], "paging": {"next": null}});
+        assert!(next_url(&config, &current, &HeaderMap::new(), &last)
+            .unwrap()
+            .is_none());
+
+        let config = FollowLinksConfig {
+            next_header: Some("Link".to_string()),
+            ..Default::default()
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "link",
+            r#"<https://api.example.com/v1/items?page=1>; rel="prev", <https://api.example.com/v1/items?page=3>; rel="next""#
+                .parse()
+                .unwrap(),
+        );
+        let next = next_url(&config, &current, &headers, &json!([])).unwrap();
+        assert_eq!(
+            next.unwrap().as_str(),
+            "https://api.example.com/v1/items?page=3"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 載入記憶體的參照表（CSV/TSV/JSON），以指定欄位作為索引鍵
+#[derive(Debug, Clone, Default)]
+pub struct LookupTable {
+    pub key: String,
+    rows: HashMap<String, HashMap<String, serde_json::Value>>,
+}
+
+impl LookupTable {
+    /// 從本地檔案載入，格式依副檔名判斷
+    pub fn load(path: &str, key: &str) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let table = Self::from_bytes(&bytes, path, key)?;
+        tracing::info!(
+            "📚 Loaded lookup table '{}' with {} rows (key: {})",
+            path,
+            table.len(),
+            key
+        );
+        Ok(table)
+    }
+
+    pub fn from_bytes(bytes: &[u8], path: &str, key: &str) -> Result<Self> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let rows = match extension.as_str() {
+            "csv" => read_delimited(bytes, b',')?,
+            "tsv" => read_delimited(bytes, b'\t')?,
+            "json" => read_json(bytes, path)?,
+            other => {
+                return Err(EtlError::InvalidConfigValueError {
+                    field: "lookup_file".to_string(),
+                    value: path.to_string(),
+                    reason: format!("Unsupported lookup file extension '{}'", other),
+                })
+            }
+        };
+
+        let mut table = Self {
+            key: key.to_string(),
+            rows: HashMap::new(),
+        };
+        for row in rows {
+            if let Some(key_value) = row.get(key).and_then(lookup_key) {
+                table.rows.insert(key_value, row);
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// 以值查找對應的資料列（數字與字串以字串形式比對）
+    pub fn get(&self, value: &serde_json::Value) -> Option<&HashMap<String, serde_json::Value>> {
+        lookup_key(value).and_then(|key| self.rows.get(&key))
+    }
+
+    /// 以記錄的 join_field 查找並把參照欄位加入記錄；沒有匹配時返回 None，
+    /// 否則返回與記錄既有欄位同名的參照欄位
+    ///
+    /// `fields` 為 None 時複製索引鍵以外的所有欄位；`prefix` 會加在新欄位名稱前。
+    /// 同名欄位預設保留記錄原本的值，`overwrite` 為 true 時改用參照表的值。
+    pub fn enrich(
+        &self,
+        record: &mut Record,
+        join_field: &str,
+        fields: Option<&[String]>,
+        prefix: &str,
+        overwrite: bool,
+    ) -> Option<Vec<String>> {
+        let row = record.data.get(join_field).and_then(|v| self.get(v))?;
+
+        let row = row.clone();
+        let mut collisions = Vec::new();
+        for (column, value) in row {
+            let selected = match fields {
+                Some(fields) => fields.contains(&column),
+                None => column != self.key,
+            };
+            if !selected {
+                continue;
+            }
+            let field = format!("{}{}", prefix, column);
+            if record.data.contains_key(&field) {
+                if !overwrite {
+                    collisions.push(field);
+                    continue;
+                }
+                collisions.push(field.clone());
+            }
+            record.data.insert(field, value);
+        }
+        collisions.sort();
+        Some(collisions)
+    }
+}
+
+fn lookup_key(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+pub(crate) fn read_delimited(
+    bytes: &[u8],
+    delimiter: u8,
+) -> Result<Vec<HashMap<String, serde_json::Value>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(bytes);
+    let headers = reader.headers()?.clone();
+
+    let mut rows = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        rows.push(
+            headers
+                .iter()
+                .zip(row.iter())
+                .map(|(header, value)| {
+                    (
+                        header.to_string(),
+                        serde_json::Value::String(value.to_string()),
+                    )
+                })
+                .collect(),
+        );
+    }
+    Ok(rows)
+}
+
+fn read_json(bytes: &[u8], path: &str) -> Result<Vec<HashMap<String, serde_json::Value>>> {
+    match serde_json::from_slice::<serde_json::Value>(bytes)? {
+        serde_json::Value::Array(items) => Ok(items
+            .into_iter()
+            .filter_map(|item| match item {
+                serde_json::Value::Object(obj) => Some(obj.into_iter().collect()),
+                _ => None,
+            })
+            .collect()),
+        _ => Err(EtlError::DataValidationError {
+            message: format!(
+                "Lookup file '{}' must contain a JSON array of objects",
+                path
+            ),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(user_id: serde_json::Value) -> Record {
+        Record {
+            data: HashMap::from([("user_id".to_string(), user_id)]),
+        }
+    }
+
+    #[test]
+    fn test_csv_lookup_joins_numeric_key() {
+        let table = LookupTable::from_bytes(
+            b"id,name,team\n1,Alice,red\n2,Bob,blue\n",
+            "users.csv",
+            "id",
+        )
+        .unwrap();
+        assert_eq!(table.len(), 2);
+
+        let mut rec = record(json!(2));
+        assert_eq!(
+            table.enrich(&mut rec, "user_id", None, "user_", false),
+            Some(Vec::new())
+        );
+        assert_eq!(rec.data["user_name"], "Bob");
+        assert_eq!(rec.data["user_team"], "blue");
+        assert!(!rec.data.contains_key("user_id_1"));
+
+        let mut missing = record(json!(9));
+        assert!(table
+            .enrich(&mut missing, "user_id", None, "", false)
+            .is_none());
+    }
+
+    #[test]
+    fn test_collisions_keep_record_values_unless_overwrite() {
+        let table =
+            LookupTable::from_bytes(b"id,name,team\n1,Alice,red\n", "users.csv", "id").unwrap();
+        let mut rec = Record {
+            data: HashMap::from([
+                ("id".to_string(), json!(1)),
+                ("name".to_string(), json!("al")),
+            ]),
+        };
+
+        // 無前綴時參照欄位與記錄欄位同名，預設保留記錄的值
+        let collisions = table.enrich(&mut rec, "id", None, "", false).unwrap();
+        assert_eq!(collisions, ["name"]);
+        assert_eq!(rec.data["name"], "al");
+        assert_eq!(rec.data["team"], "red");
+
+        let collisions = table.enrich(&mut rec, "id", None, "", true).unwrap();
+        assert_eq!(collisions, ["name", "team"]);
+        assert_eq!(rec.data["name"], "Alice");
+    }
+
+    #[test]
+    fn test_json_lookup_with_selected_fields() {
+        let json = br#"[{"code": "TW", "name": "Taiwan", "region": "Asia"}]"#;
+        let table = LookupTable::from_bytes(json, "countries.json", "code").unwrap();
+
+        let mut rec = Record {
+            data: HashMap::from([("country".to_string(), json!("TW"))]),
+        };
+        let fields = vec!["name".to_string()];
+        assert!(table
+            .enrich(&mut rec, "country", Some(&fields), "country_", false)
+            .is_some());
+        assert_eq!(rec.data["country_name"], "Taiwan");
+        assert!(!rec.data.contains_key("country_region"));
+    }
+
+    #[test]
+    fn test_unsupported_extension() {
+        assert!(LookupTable::from_bytes(b"", "data.xml", "id").is_err());
+    }
+}
@@ -1,9 +1,55 @@
+pub mod aggregation;
+pub mod append_output;
+pub mod builtin_templates;
+pub mod checkpoint;
+pub mod conditional_requests;
+pub mod context_index;
+pub mod context_spill;
 pub mod contextual_pipeline;
+pub mod csv_output;
+pub mod dead_letter;
 pub mod etl;
+pub mod extract_cache;
+pub mod fan_out_progress;
+pub mod field_transforms;
+pub mod file_ledger;
+pub mod filename_template;
+pub mod http_audit;
+pub mod intermediate_output;
+pub mod link_pagination;
+pub mod lookup;
 pub mod mvp_pipeline;
+pub mod null_policy;
+pub mod object_source;
+pub mod output_archive;
+pub mod output_encryption;
+pub mod output_manifest;
+pub mod output_variables;
+pub mod partitioned_output;
+pub mod pii;
 pub mod pipeline;
+pub mod pipeline_join;
 pub mod pipeline_sequence;
+pub mod pipeline_union;
+pub mod profiling;
+pub mod progress_file;
+pub mod quality_rules;
+pub mod reconciliation;
+pub mod record_script;
+pub mod record_validation;
+pub mod response_limits;
+pub mod response_metadata;
+pub mod resume_report;
+pub mod run_report;
+pub mod schema_inference;
+pub mod sequence_state;
+pub mod staged_storage;
+pub mod template_filters;
+pub mod transform_steps;
+pub mod type_coercion;
+pub mod warnings;
+pub mod write_mode;
 
-pub use crate::domain::model::{Record, TransformResult};
+pub use crate::domain::model::{deserialize_records, Record, TransformResult};
 pub use crate::domain::ports::{ConfigProvider, Pipeline, Storage};
 pub use crate::utils::error::Result;
@@ -0,0 +1,151 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use std::collections::BTreeSet;
+
+/// 輸出檔中缺少或為 null 的欄位如何處理（load.null_policy，依輸出格式設定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullPolicy {
+    /// CSV/TSV 留空、JSON 維持原樣（預設）
+    #[default]
+    Empty,
+    /// CSV/TSV 寫入 "NULL"、JSON 補上 null
+    Null,
+    /// 略過有缺值的記錄
+    SkipRecord,
+    /// 以 DataValidationError 中止
+    Fail,
+}
+
+impl NullPolicy {
+    pub const SUPPORTED: [&'static str; 4] = ["empty", "null", "skip_record", "fail"];
+
+    /// CSV/TSV 中代表 null 的文字
+    pub const NULL_TEXT: &'static str = "NULL";
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "empty" => Ok(Self::Empty),
+            "null" => Ok(Self::Null),
+            "skip_record" => Ok(Self::SkipRecord),
+            "fail" => Ok(Self::Fail),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "load.null_policy".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+
+    /// 是否輸出此記錄：skip_record 略過有缺值的記錄，fail 返回指出欄位的錯誤
+    pub fn keep(
+        &self,
+        record: &Record,
+        fields: &[&String],
+        index: usize,
+        output_format: &str,
+    ) -> Result<bool> {
+        let missing = || missing_field(record, fields);
+        match self {
+            Self::Empty | Self::Null => Ok(true),
+            Self::SkipRecord => Ok(missing().is_none()),
+            Self::Fail => match missing() {
+                Some(field) => Err(EtlError::DataValidationError {
+                    message: format!(
+                        "Record {} has no value for '{}' in {} output (load.null_policy = \"fail\")",
+                        index, field, output_format
+                    ),
+                }),
+                None => Ok(true),
+            },
+        }
+    }
+}
+
+/// 輸出欄位：指定 `columns` 時依其順序，否則為所有記錄欄位的聯集（排序後）
+pub fn output_fields<'a>(records: &'a [Record], columns: Option<&'a [String]>) -> Vec<&'a String> {
+    match columns {
+        Some(columns) => columns.iter().collect(),
+        None => records
+            .iter()
+            .flat_map(|record| record.data.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// 記錄在輸出欄位中第一個缺少或為 null 的欄位
+pub fn missing_field<'a>(record: &Record, fields: &[&'a String]) -> Option<&'a String> {
+    fields.iter().copied().find(|field| {
+        record
+            .data
+            .get(field.as_str())
+            .is_none_or(serde_json::Value::is_null)
+    })
+}
+
+/// 依 null 政策輸出 JSON；null 政策下補上缺少的欄位
+pub fn render_json(records: &[Record], policy: NullPolicy) -> Result<String> {
+    if policy == NullPolicy::Empty {
+        return Ok(serde_json::to_string_pretty(records)?);
+    }
+    let fields = output_fields(records, None);
+    let mut output = Vec::with_capacity(records.len());
+    for (index, record) in records.iter().enumerate() {
+        if !policy.keep(record, &fields, index, "json")? {
+            continue;
+        }
+        let mut record = record.clone();
+        for field in &fields {
+            record
+                .data
+                .entry(field.to_string())
+                .or_insert(serde_json::Value::Null);
+        }
+        output.push(record);
+    }
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(fields: serde_json::Value) -> Record {
+        Record {
+            data: serde_json::from_value(fields).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_null_policies() {
+        let records = [
+            record(json!({"id": 1, "name": "Ada"})),
+            record(json!({"id": 2, "name": null})),
+            record(json!({"id": 3})),
+        ];
+        let fields = output_fields(&records, None);
+        assert_eq!(missing_field(&records[0], &fields), None);
+        assert_eq!(missing_field(&records[2], &fields).unwrap(), "name");
+
+        let kept: Vec<bool> = records
+            .iter()
+            .enumerate()
+            .map(|(i, r)| NullPolicy::SkipRecord.keep(r, &fields, i, "csv").unwrap())
+            .collect();
+        assert_eq!(kept, [true, false, false]);
+
+        let error = NullPolicy::Fail
+            .keep(&records[2], &fields, 2, "csv")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Record 2") && error.contains("'name'"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&records, NullPolicy::Null).unwrap()).unwrap();
+        assert_eq!(json[2]["data"]["name"], serde_json::Value::Null);
+        assert!(json[2]["data"].as_object().unwrap().contains_key("name"));
+        assert!(NullPolicy::parse("drop").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::app::pipelines::stream_transform::{parse_input, StreamInputFormat};
+use crate::config::sequence_config::S3SourceConfig;
+use crate::core::Storage;
+use crate::utils::error::{EtlError, Result};
+
+/// 列出 prefix 下的物件並解析內容，返回依物件鍵排序的 (物件鍵, JSON 值)
+///
+/// 未設定 format 且無法依副檔名判斷格式的物件（例如 `_SUCCESS`）會略過。
+pub async fn read_objects<S: Storage>(
+    storage: &S,
+    config: &S3SourceConfig,
+) -> Result<Vec<(String, serde_json::Value)>> {
+    let mut objects = Vec::new();
+    for key in storage.list(config.prefix()).await? {
+        let Some(format) = config.format_for(&key) else {
+            tracing::debug!("⏭️ Skipping object '{}' with unknown format", key);
+            continue;
+        };
+        let format = match format {
+            "csv" => StreamInputFormat::Csv,
+            _ => StreamInputFormat::Json,
+        };
+        let data = storage.read_file(&key).await?;
+        let value = parse_input(&data, format).map_err(|e| EtlError::DataValidationError {
+            message: format!("Failed to parse object '{}': {}", key, e),
+        })?;
+        objects.push((key, value));
+    }
+    Ok(objects)
+}
+
+/// 依來源設定建立 S3 存儲（使用 AWS 預設的憑證來源）
+#[cfg(feature = "s3")]
+pub async fn s3_storage(config: &S3SourceConfig) -> crate::config::lambda::S3Storage {
+    let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
+    if let Some(region) = &config.region {
+        loader = loader.region(aws_config::Region::new(region.clone()));
+    }
+    let mut builder = aws_sdk_s3::config::Builder::from(&loader.load().await);
+    if let Some(endpoint_url) = &config.endpoint_url {
+        builder = builder.endpoint_url(endpoint_url).force_path_style(true);
+    }
+    crate::config::lambda::S3Storage::new(
+        aws_sdk_s3::Client::from_conf(builder.build()),
+        config.bucket.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalStorage;
+    use tempfile::TempDir;
+
+    fn config(prefix: &str, format: Option<&str>) -> S3SourceConfig {
+        S3SourceConfig {
+            bucket: "landing".to_string(),
+            prefix: Some(prefix.to_string()),
+            region: None,
+            endpoint_url: None,
+            format: format.map(str::to_string),
+            key_field: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_objects_by_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        storage
+            .write_file("orders/2024/a.csv", b"id,total\n1,10\n")
+            .await
+            .unwrap();
+        storage
+            .write_file("orders/2024/b.ndjson", b"{\"id\":2}\n{\"id\":3}\n")
+            .await
+            .unwrap();
+        storage.write_file("orders/_SUCCESS", b"").await.unwrap();
+        storage.write_file("other/c.json", b"[]").await.unwrap();
+
+        let objects = read_objects(&storage, &config("orders/", None))
+            .await
+            .unwrap();
+        let keys: Vec<&str> = objects.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["orders/2024/a.csv", "orders/2024/b.ndjson"]);
+        assert_eq!(
+            objects[0].1,
+            serde_json::json!([{"id": "1", "total": "10"}])
+        );
+        assert_eq!(objects[1].1, serde_json::json!([{"id": 2}, {"id": 3}]));
+
+        // 指定格式時不再依副檔名略過
+        assert!(read_objects(&storage, &config("orders/", Some("json")))
+            .await
+            .is_err());
+    }
+}
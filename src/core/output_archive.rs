@@ -0,0 +1,255 @@
+use crate::core::output_encryption::OutputEncryption;
+use crate::core::output_manifest::{
+    ManifestFile, ManifestLocation, OutputManifest, MANIFEST_FILE_NAME,
+};
+use crate::core::Storage;
+use crate::utils::error::{EtlError, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use zip::write::{FileOptions, ZipWriter};
+use zip::AesMode;
+
+/// 輸出封裝格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+    /// 不壓縮，各檔案直接寫入以輸出名稱命名的目錄
+    None,
+}
+
+impl ArchiveFormat {
+    pub const SUPPORTED: [&'static str; 3] = ["zip", "tar.gz", "none"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "zip" => Ok(Self::Zip),
+            "tar.gz" | "tgz" => Ok(Self::TarGz),
+            "none" => Ok(Self::None),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "load.compression.format".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported formats: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+
+    /// 預設輸出名稱使用的副檔名（目錄輸出沒有副檔名）
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Zip => ".zip",
+            Self::TarGz => ".tar.gz",
+            Self::None => "",
+        }
+    }
+}
+
+/// 收集 load 階段的輸出檔案，再依格式封裝寫入存儲
+#[derive(Debug)]
+pub struct OutputArchive {
+    format: ArchiveFormat,
+    entries: Vec<(String, Vec<u8>)>,
+    records: HashMap<String, usize>, // 資料檔名稱 -> 記錄數，列入清單
+    encryption: Option<OutputEncryption>,
+    manifest: Option<(ManifestLocation, OutputManifest)>,
+}
+
+impl OutputArchive {
+    pub fn new(format: ArchiveFormat) -> Self {
+        Self {
+            format,
+            entries: Vec::new(),
+            records: HashMap::new(),
+            encryption: None,
+            manifest: None,
+        }
+    }
+
+    /// 寫出時產生列出各檔案大小、SHA-256 與記錄數的清單
+    pub fn with_manifest(mut self, location: ManifestLocation, manifest: OutputManifest) -> Self {
+        self.manifest = Some((location, manifest));
+        self
+    }
+
+    /// 加密輸出：zip_aes 加密各 ZIP 項目；aes_gcm 加密整個封裝檔（未壓縮目錄則逐檔加密並加上 .enc）
+    pub fn with_encryption(mut self, encryption: Option<OutputEncryption>) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    pub fn add(&mut self, name: &str, data: impl Into<Vec<u8>>) {
+        self.entries.push((name.to_string(), data.into()));
+    }
+
+    /// 加入資料檔，並記錄其記錄數供清單使用
+    pub fn add_records(&mut self, name: &str, data: impl Into<Vec<u8>>, records: usize) {
+        self.records.insert(name.to_string(), records);
+        self.add(name, data);
+    }
+
+    /// 寫入存儲，返回寫入的位元組數
+    ///
+    /// ZIP 與 tar.gz 寫成單一檔案 `name`；none 則把各檔案寫入 `name/` 目錄下。
+    /// aes_gcm 加密時 `name` 應已含 .enc（見 `OutputEncryption::file_name`）。
+    /// 清單列出的是解壓縮、解密後的各檔案；放在輸出檔旁時另列出輸出檔本身。
+    pub async fn write_to<S: Storage>(mut self, storage: &S, name: &str) -> Result<u64> {
+        let manifest = self.manifest.take().map(|(location, mut manifest)| {
+            for (entry, data) in &self.entries {
+                manifest.add(entry, data, self.records.get(entry).copied());
+            }
+            (location, manifest)
+        });
+        if let Some((ManifestLocation::Inside, manifest)) = &manifest {
+            self.add(MANIFEST_FILE_NAME, manifest.to_json()?);
+        }
+
+        let (bytes_written, output) = self.write_entries(storage, name).await?;
+        if let Some((ManifestLocation::Alongside, mut manifest)) = manifest {
+            manifest.output = output;
+            storage
+                .write_file(
+                    &ManifestLocation::alongside_path(name),
+                    manifest.to_json()?.as_bytes(),
+                )
+                .await?;
+        }
+        Ok(bytes_written)
+    }
+
+    /// 依格式寫出，返回寫入的位元組數與封裝檔的清單項目（目錄輸出沒有）
+    async fn write_entries<S: Storage>(
+        &self,
+        storage: &S,
+        name: &str,
+    ) -> Result<(u64, Option<ManifestFile>)> {
+        match self.format {
+            ArchiveFormat::Zip | ArchiveFormat::TarGz => {
+                let data = match self.format {
+                    ArchiveFormat::Zip => self.to_zip()?,
+                    _ => self.to_tar_gz()?,
+                };
+                let data = self.seal(data)?;
+                storage.write_file(name, &data).await?;
+                Ok((
+                    data.len() as u64,
+                    Some(ManifestFile::new(name, &data, None)),
+                ))
+            }
+            ArchiveFormat::None => {
+                let mut bytes_written = 0;
+                for (entry, data) in &self.entries {
+                    let (entry, data) = match &self.encryption {
+                        Some(encryption) => (encryption.file_name(entry), self.seal(data.clone())?),
+                        None => (entry.clone(), data.clone()),
+                    };
+                    storage
+                        .write_file(&format!("{}/{}", name, entry), &data)
+                        .await?;
+                    bytes_written += data.len() as u64;
+                }
+                Ok((bytes_written, None))
+            }
+        }
+    }
+
+    fn seal(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        match &self.encryption {
+            Some(encryption) => encryption.seal(data),
+            None => Ok(data),
+        }
+    }
+
+    fn to_zip(&self) -> Result<Vec<u8>> {
+        let password = self
+            .encryption
+            .as_ref()
+            .and_then(|encryption| encryption.zip_password());
+        let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (entry, data) in &self.entries {
+            let options = FileOptions::<()>::default();
+            let options = match password {
+                Some(password) => options.with_aes_encryption(AesMode::Aes256, password),
+                None => options,
+            };
+            zip.start_file(entry.as_str(), options)?;
+            zip.write_all(data)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+
+    fn to_tar_gz(&self) -> Result<Vec<u8>> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+
+        for (entry, data) in &self.entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            builder.append_data(&mut header, entry, data.as_slice())?;
+        }
+        Ok(builder.into_inner()?.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::output_encryption::EncryptionMethod;
+    use std::io::Read;
+
+    fn sample_archive(format: ArchiveFormat) -> OutputArchive {
+        let mut archive = OutputArchive::new(format);
+        archive.add("output.csv", "id,name\n1,Alice\n");
+        archive.add("processed_data.json", r#"[{"id":1}]"#);
+        archive
+    }
+
+    #[test]
+    fn test_tar_gz_contains_entries() {
+        let data = sample_archive(ArchiveFormat::TarGz).to_tar_gz().unwrap();
+        let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(data.as_slice()));
+
+        let mut entries = Vec::new();
+        for entry in tar.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            entries.push((entry.path().unwrap().display().to_string(), content));
+        }
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, "output.csv");
+        assert_eq!(entries[0].1, "id,name\n1,Alice\n");
+    }
+
+    #[test]
+    fn test_zip_aes_requires_password() {
+        let encryption = OutputEncryption::new(EncryptionMethod::ZipAes, "secret").unwrap();
+        let data = sample_archive(ArchiveFormat::Zip)
+            .with_encryption(Some(encryption))
+            .to_zip()
+            .unwrap();
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+
+        assert!(zip.by_name("output.csv").is_err());
+        assert!(zip.by_name_decrypt("output.csv", b"wrong").is_err());
+        let mut content = String::new();
+        zip.by_name_decrypt("output.csv", b"secret")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "id,name\n1,Alice\n");
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(
+            ArchiveFormat::parse("tar.gz").unwrap(),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(ArchiveFormat::parse("ZIP").unwrap(), ArchiveFormat::Zip);
+        assert_eq!(ArchiveFormat::None.extension(), "");
+        assert!(ArchiveFormat::parse("rar").is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::config::sequence_config::OutputEncryptionConfig;
+use crate::utils::encryption::StateCipher;
+use crate::utils::error::{EtlError, Result};
+
+/// aes_gcm 加密後的輸出檔附加的副檔名
+pub const ENCRYPTED_EXTENSION: &str = ".enc";
+
+/// 輸出檔加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionMethod {
+    /// AES-256 加密的 ZIP 項目（WinZip AE-2），一般解壓縮工具輸入密碼即可開啟
+    ZipAes,
+    /// 整個輸出檔以 AES-256-GCM 加密，需以 `decrypt` 指令或 `decrypt_output` 解密
+    AesGcm,
+}
+
+impl EncryptionMethod {
+    pub const SUPPORTED: [&'static str; 2] = ["zip_aes", "aes_gcm"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "zip_aes" => Ok(Self::ZipAes),
+            "aes_gcm" => Ok(Self::AesGcm),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "load.compression.encryption.method".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported methods: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ZipAes => "zip_aes",
+            Self::AesGcm => "aes_gcm",
+        }
+    }
+}
+
+/// 讀取 key_file 或 key_env 中的金鑰
+pub fn load_key(key_file: Option<&str>, key_env: Option<&str>) -> Result<String> {
+    let key = match (key_file, key_env) {
+        (Some(path), _) => std::fs::read_to_string(path)
+            .map_err(|e| EtlError::ConfigError {
+                message: format!("Failed to read encryption key file '{}': {}", path, e),
+            })?
+            .trim()
+            .to_string(),
+        (None, Some(key_env)) => {
+            std::env::var(key_env).map_err(|_| EtlError::MissingConfigError {
+                field: format!("environment variable {}", key_env),
+            })?
+        }
+        (None, None) => String::new(),
+    };
+    if key.is_empty() {
+        return Err(EtlError::MissingConfigError {
+            field: "load.compression.encryption key".to_string(),
+        });
+    }
+    Ok(key)
+}
+
+/// 輸出檔的加密器
+#[derive(Clone)]
+pub struct OutputEncryption {
+    method: EncryptionMethod,
+    key: String,
+}
+
+impl std::fmt::Debug for OutputEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutputEncryption")
+            .field("method", &self.method)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OutputEncryption {
+    pub fn new(method: EncryptionMethod, key: &str) -> Result<Self> {
+        if key.is_empty() {
+            return Err(EtlError::MissingConfigError {
+                field: "load.compression.encryption key".to_string(),
+            });
+        }
+        Ok(Self {
+            method,
+            key: key.to_string(),
+        })
+    }
+
+    pub fn from_config(config: &OutputEncryptionConfig) -> Result<Self> {
+        let key = load_key(config.key_file.as_deref(), config.key_env.as_deref())?;
+        Self::new(config.method()?, &key)
+    }
+
+    pub fn method(&self) -> EncryptionMethod {
+        self.method
+    }
+
+    /// ZIP 項目的密碼（只用於 zip_aes）
+    pub fn zip_password(&self) -> Option<&str> {
+        (self.method == EncryptionMethod::ZipAes).then_some(self.key.as_str())
+    }
+
+    /// 寫入存儲的檔名：aes_gcm 加上 `.enc`
+    pub fn file_name(&self, name: &str) -> String {
+        match self.method {
+            EncryptionMethod::AesGcm if !name.ends_with(ENCRYPTED_EXTENSION) => {
+                format!("{}{}", name, ENCRYPTED_EXTENSION)
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    /// aes_gcm 時加密整個檔案，其他方式原樣返回
+    pub fn seal(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        match self.method {
+            EncryptionMethod::AesGcm => StateCipher::from_key_material(&self.key)?.seal(&data, ""),
+            EncryptionMethod::ZipAes => Ok(data),
+        }
+    }
+}
+
+/// 解密 aes_gcm 加密的輸出檔
+pub fn decrypt_output(key: &str, data: &[u8]) -> Result<Vec<u8>> {
+    if !StateCipher::is_encrypted(data) {
+        return Err(EtlError::DataValidationError {
+            message: "Input is not an encrypted output file".to_string(),
+        });
+    }
+    StateCipher::from_key_material(key)?
+        .open(data, "")
+        .map_err(|_| EtlError::DataValidationError {
+            message: "Failed to decrypt output (wrong key or tampered content)".to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aes_gcm_roundtrip() {
+        let encryption = OutputEncryption::new(EncryptionMethod::AesGcm, "secret").unwrap();
+        let sealed = encryption
+            .seal(b"id,ssn\n1,123-45-6789\n".to_vec())
+            .unwrap();
+
+        assert!(!sealed.windows(11).any(|w| w == b"123-45-6789"));
+        assert_eq!(
+            decrypt_output("secret", &sealed).unwrap(),
+            b"id,ssn\n1,123-45-6789\n"
+        );
+        assert!(decrypt_output("wrong", &sealed).is_err());
+        assert!(decrypt_output("secret", b"plain").is_err());
+        assert_eq!(encryption.file_name("out.zip"), "out.zip.enc");
+        assert_eq!(encryption.file_name("out.zip.enc"), "out.zip.enc");
+        assert_eq!(encryption.zip_password(), None);
+    }
+
+    #[test]
+    fn test_load_key_and_parse() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("output.key");
+        std::fs::write(&path, "  secret\n").unwrap();
+        let path = path.display().to_string();
+        assert_eq!(load_key(Some(&path), None).unwrap(), "secret");
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(load_key(Some(&path), None).is_err());
+        assert!(load_key(None, Some("ETL_OUTPUT_KEY_NOT_SET")).is_err());
+
+        assert_eq!(
+            EncryptionMethod::parse("zip_aes").unwrap(),
+            EncryptionMethod::ZipAes
+        );
+        assert!(EncryptionMethod::parse("pgp").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 輸出檔中的清單檔名；放在輸出檔旁時為 `{輸出檔名}.manifest.json`
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// 清單的位置（load.manifest.location）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ManifestLocation {
+    /// 寫入輸出檔（壓縮檔或目錄）中的 manifest.json（預設）
+    #[default]
+    Inside,
+    /// 寫在輸出檔旁，另列出輸出檔本身的大小與 checksum
+    Alongside,
+}
+
+impl ManifestLocation {
+    pub const SUPPORTED: [&'static str; 2] = ["inside", "alongside"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "inside" => Ok(Self::Inside),
+            "alongside" => Ok(Self::Alongside),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "load.manifest.location".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported locations: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+
+    /// 放在輸出檔旁時的清單路徑
+    pub fn alongside_path(output_name: &str) -> String {
+        format!("{}.{}", output_name, MANIFEST_FILE_NAME)
+    }
+}
+
+/// 清單中的單一檔案
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub records: Option<usize>, // 資料檔的記錄數
+}
+
+impl ManifestFile {
+    pub fn new(path: &str, data: &[u8], records: Option<usize>) -> Self {
+        Self {
+            path: path.to_string(),
+            size: data.len() as u64,
+            sha256: sha256_hex(data),
+            records,
+        }
+    }
+
+    /// 檢查內容與清單記錄的大小、checksum 是否相符
+    pub fn verify(&self, data: &[u8]) -> bool {
+        self.size == data.len() as u64 && self.sha256 == sha256_hex(data)
+    }
+}
+
+/// 輸出清單：列出產生的每個檔案，供下游在匯入前驗證完整性
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputManifest {
+    pub pipeline: String,
+    pub execution_id: String,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<ManifestFile>, // 輸出檔本身（只在清單放在輸出檔旁時）
+    pub files: Vec<ManifestFile>,
+}
+
+impl OutputManifest {
+    pub fn new(pipeline: &str, execution_id: &str) -> Self {
+        Self {
+            pipeline: pipeline.to_string(),
+            execution_id: execution_id.to_string(),
+            generated_at: chrono::Utc::now(),
+            output: None,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, path: &str, data: &[u8], records: Option<usize>) {
+        self.files.push(ManifestFile::new(path, data, records));
+    }
+
+    /// 資料檔的記錄數總和
+    pub fn total_records(&self) -> usize {
+        self.files.iter().filter_map(|file| file.records).sum()
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_entries() {
+        let mut manifest = OutputManifest::new("orders", "exec_1");
+        manifest.add("output.csv", b"id\n1\n2\n", Some(2));
+        manifest.add("schema.json", b"{}", None);
+
+        assert_eq!(manifest.total_records(), 2);
+        assert_eq!(
+            manifest.files[1].sha256,
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        assert!(manifest.files[0].verify(b"id\n1\n2\n"));
+        assert!(!manifest.files[0].verify(b"id\n1\n3\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&manifest.to_json().unwrap()).unwrap();
+        assert_eq!(json["files"][0]["records"], 2);
+        assert!(json["files"][1].get("records").is_none());
+        assert!(json.get("output").is_none());
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(
+            ManifestLocation::parse("alongside").unwrap(),
+            ManifestLocation::Alongside
+        );
+        assert!(ManifestLocation::parse("beside").is_err());
+        assert_eq!(
+            ManifestLocation::alongside_path("orders_output.zip"),
+            "orders_output.zip.manifest.json"
+        );
+    }
+}
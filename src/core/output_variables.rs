@@ -0,0 +1,195 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use std::collections::HashMap;
+
+/// 支援的彙總函式
+pub const FUNCTIONS: [&str; 7] = ["count", "sum", "min", "max", "avg", "first", "last"];
+
+/// 解析後的輸出變數表達式，例如 `max(id)`、`count()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputExpression {
+    pub function: String,
+    pub field: Option<String>,
+}
+
+impl OutputExpression {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let invalid = |reason: String| EtlError::InvalidConfigValueError {
+            field: "outputs".to_string(),
+            value: expression.to_string(),
+            reason,
+        };
+
+        let expression = expression.trim();
+        let (function, rest) = expression
+            .split_once('(')
+            .ok_or_else(|| invalid("Expected the form function(field)".to_string()))?;
+        let argument = rest
+            .strip_suffix(')')
+            .ok_or_else(|| invalid("Missing closing parenthesis".to_string()))?
+            .trim();
+
+        let function = function.trim().to_lowercase();
+        if !FUNCTIONS.contains(&function.as_str()) {
+            return Err(invalid(format!(
+                "Supported functions: {}",
+                FUNCTIONS.join(", ")
+            )));
+        }
+
+        let field = match argument {
+            "" | "*" => None,
+            field => Some(field.to_string()),
+        };
+        if field.is_none() && function != "count" {
+            return Err(invalid(format!("{}() requires a field", function)));
+        }
+
+        Ok(Self { function, field })
+    }
+
+    /// 對記錄計算結果；沒有可用值時返回 null
+    pub fn evaluate(&self, records: &[Record]) -> serde_json::Value {
+        let values: Vec<&serde_json::Value> = match &self.field {
+            Some(field) => records
+                .iter()
+                .filter_map(|record| record.data.get(field))
+                .filter(|value| !value.is_null())
+                .collect(),
+            None => return serde_json::json!(records.len()),
+        };
+
+        match self.function.as_str() {
+            "count" => serde_json::json!(values.len()),
+            "first" => values.first().cloned().cloned().unwrap_or_default(),
+            "last" => values.last().cloned().cloned().unwrap_or_default(),
+            "min" | "max" => {
+                let numbers: Vec<&serde_json::Value> =
+                    values.iter().copied().filter(|v| v.is_number()).collect();
+                let candidates = if numbers.is_empty() { values } else { numbers };
+                let pick = candidates.into_iter().reduce(|best, value| {
+                    let ordering = compare(value, best);
+                    let better = if self.function == "max" {
+                        ordering.is_gt()
+                    } else {
+                        ordering.is_lt()
+                    };
+                    if better {
+                        value
+                    } else {
+                        best
+                    }
+                });
+                pick.cloned().unwrap_or_default()
+            }
+            "sum" | "avg" => {
+                let numbers: Vec<f64> = values.iter().filter_map(|v| as_number(v)).collect();
+                if numbers.is_empty() {
+                    return serde_json::Value::Null;
+                }
+                let sum: f64 = numbers.iter().sum();
+                let result = if self.function == "sum" {
+                    sum
+                } else {
+                    sum / numbers.len() as f64
+                };
+                number_value(result)
+            }
+            _ => serde_json::Value::Null,
+        }
+    }
+}
+
+/// 依設定計算所有輸出變數
+pub fn evaluate_outputs(
+    outputs: &HashMap<String, String>,
+    records: &[Record],
+) -> Result<HashMap<String, serde_json::Value>> {
+    outputs
+        .iter()
+        .map(|(name, expression)| {
+            let value = OutputExpression::parse(expression)?.evaluate(records);
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+fn as_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn compare(a: &serde_json::Value, b: &serde_json::Value) -> std::cmp::Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+fn number_value(value: f64) -> serde_json::Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        serde_json::json!(value as i64)
+    } else {
+        serde_json::json!(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn records() -> Vec<Record> {
+        [
+            json!({"id": 3, "price": "1.5"}),
+            json!({"id": 10, "price": 2.5}),
+            json!({"id": 7}),
+        ]
+        .into_iter()
+        .map(|value| Record {
+            data: serde_json::from_value(value).unwrap(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_evaluate_outputs() {
+        let outputs = HashMap::from([
+            ("last_run_max_id".to_string(), "max(id)".to_string()),
+            ("min_id".to_string(), "min(id)".to_string()),
+            ("total".to_string(), "count()".to_string()),
+            ("revenue".to_string(), "sum(price)".to_string()),
+            ("last_id".to_string(), "last(id)".to_string()),
+        ]);
+        let values = evaluate_outputs(&outputs, &records()).unwrap();
+        assert_eq!(values["last_run_max_id"], json!(10));
+        assert_eq!(values["min_id"], json!(3));
+        assert_eq!(values["total"], json!(3));
+        assert_eq!(values["revenue"], json!(4));
+        assert_eq!(values["last_id"], json!(7));
+
+        assert_eq!(
+            OutputExpression::parse("max(missing)")
+                .unwrap()
+                .evaluate(&records()),
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(OutputExpression::parse("median(id)").is_err());
+        assert!(OutputExpression::parse("max()").is_err());
+        assert!(OutputExpression::parse("max(id").is_err());
+        assert_eq!(
+            OutputExpression::parse("COUNT(*)").unwrap(),
+            OutputExpression {
+                function: "count".to_string(),
+                field: None
+            }
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use std::collections::BTreeMap;
+
+/// 分區欄位缺少或為 null 時使用的分區值
+pub const NULL_PARTITION: &str = "__null__";
+
+/// 分區輸出的檔案配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartitionLayout {
+    /// 每個分區一組檔案，例如 `output_US.csv`、`processed_data_US.json`
+    #[default]
+    Flat,
+    /// 目錄階層，例如 `country=US/part-0.csv`
+    Hive,
+}
+
+impl PartitionLayout {
+    pub const SUPPORTED: [&'static str; 2] = ["flat", "hive"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "flat" => Ok(Self::Flat),
+            "hive" => Ok(Self::Hive),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "load.partition_layout".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported layouts: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+
+    /// 分區內檔案的名稱；`base` 為未分區時的檔名主體（如 "output"），`extension` 含點
+    ///
+    /// 設定 max_records_per_file 時 `part` 為分檔編號（從 1 開始）。
+    pub fn entry_name(
+        &self,
+        field: &str,
+        value: &str,
+        base: &str,
+        extension: &str,
+        part: Option<usize>,
+    ) -> String {
+        match (self, part) {
+            (Self::Flat, None) => format!("{}_{}{}", base, value, extension),
+            (Self::Flat, Some(part)) => format!("{}_{}_{:04}{}", base, value, part, extension),
+            (Self::Hive, None) => format!("{}={}/part-0{}", field, value, extension),
+            (Self::Hive, Some(part)) => {
+                format!("{}={}/part-{:04}{}", field, value, part, extension)
+            }
+        }
+    }
+}
+
+/// 依每檔筆數上限切分記錄，返回（分檔編號, 記錄）；未設定上限時不切分也不編號
+///
+/// 沒有記錄時仍返回一個空的第 1 檔，讓下游看到固定的檔名。
+pub fn chunk_records(
+    records: &[Record],
+    max_per_file: Option<usize>,
+) -> Vec<(Option<usize>, &[Record])> {
+    match max_per_file {
+        None => vec![(None, records)],
+        Some(_) if records.is_empty() => vec![(Some(1), records)],
+        Some(max) => records
+            .chunks(max.max(1))
+            .enumerate()
+            .map(|(index, chunk)| (Some(index + 1), chunk))
+            .collect(),
+    }
+}
+
+/// 未分區輸出的檔名，例如 `output.csv` 或分檔時的 `output_0001.csv`
+pub fn chunk_entry_name(base: &str, extension: &str, part: Option<usize>) -> String {
+    match part {
+        Some(part) => format!("{}_{:04}{}", base, part, extension),
+        None => format!("{}{}", base, extension),
+    }
+}
+
+/// 依欄位值分組（依分區值排序，組內維持原順序）
+pub fn partition_records(records: &[Record], field: &str) -> BTreeMap<String, Vec<Record>> {
+    let mut partitions: BTreeMap<String, Vec<Record>> = BTreeMap::new();
+    for record in records {
+        partitions
+            .entry(partition_value(record.data.get(field)))
+            .or_default()
+            .push(record.clone());
+    }
+    partitions
+}
+
+/// 將欄位值轉為可安全用於檔名與路徑的分區值
+fn partition_value(value: Option<&serde_json::Value>) -> String {
+    let raw = match value {
+        None | Some(serde_json::Value::Null) => return NULL_PARTITION.to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+    let sanitized: String = raw
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '=' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match sanitized.as_str() {
+        "" => NULL_PARTITION.to_string(),
+        "." | ".." => sanitized.replace('.', "_"),
+        _ => sanitized,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn record(value: serde_json::Value) -> Record {
+        Record {
+            data: serde_json::from_value::<HashMap<_, _>>(value).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_partition_records() {
+        let records = vec![
+            record(serde_json::json!({"id": 1, "country": "US"})),
+            record(serde_json::json!({"id": 2, "country": "TW"})),
+            record(serde_json::json!({"id": 3, "country": "US"})),
+            record(serde_json::json!({"id": 4})),
+            record(serde_json::json!({"id": 5, "country": "../etc"})),
+        ];
+        let partitions = partition_records(&records, "country");
+        let keys: Vec<&str> = partitions.keys().map(|key| key.as_str()).collect();
+        assert_eq!(keys, vec![".._etc", "TW", "US", "__null__"]);
+        assert_eq!(partitions["US"].len(), 2);
+    }
+
+    #[test]
+    fn test_entry_names() {
+        assert_eq!(
+            PartitionLayout::Hive.entry_name("country", "US", "output", ".csv", None),
+            "country=US/part-0.csv"
+        );
+        assert_eq!(
+            PartitionLayout::Flat.entry_name("country", "US", "processed_data", ".json", None),
+            "processed_data_US.json"
+        );
+        assert_eq!(
+            PartitionLayout::Hive.entry_name("country", "US", "output", ".csv", Some(2)),
+            "country=US/part-0002.csv"
+        );
+        assert!(PartitionLayout::parse("nested").is_err());
+    }
+
+    #[test]
+    fn test_chunk_records() {
+        let records: Vec<Record> = (0..5)
+            .map(|id| record(serde_json::json!({ "id": id })))
+            .collect();
+        let chunks = chunk_records(&records, Some(2));
+        let sizes: Vec<(Option<usize>, usize)> = chunks
+            .iter()
+            .map(|(part, chunk)| (*part, chunk.len()))
+            .collect();
+        assert_eq!(sizes, vec![(Some(1), 2), (Some(2), 2), (Some(3), 1)]);
+        assert_eq!(chunk_records(&[], Some(2)).len(), 1);
+        assert_eq!(chunk_records(&records, None)[0].1.len(), 5);
+        assert_eq!(
+            chunk_entry_name("output", ".csv", Some(12)),
+            "output_0012.csv"
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use crate::config::sequence_config::TransformOperations;
+use crate::core::{Record, Storage};
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
+use crate::utils::error::{EtlError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+pub const MASK: &str = "****";
+
+/// 欄位層級的個資處理：遮罩、雜湊與代碼化
+///
+/// 在 keep_only_fields/exclude_fields 之後、驗證之前套用，rejects 檔也不會含原始值；
+/// null 與不存在的欄位保持不變。代碼化的對照表（代碼 -> 原始值）另存於存儲中，
+/// 不會隨輸出檔交付，設定 state_encryption 時加密。
+#[derive(Debug, Default)]
+pub struct PiiProtector {
+    mask_fields: Vec<String>,
+    hash_fields: Vec<String>,
+    tokenize_fields: Vec<String>,
+    salt: String,
+    tokens: Mutex<BTreeMap<String, TokenEntry>>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TokenEntry {
+    pub field: String,
+    pub value: serde_json::Value,
+}
+
+impl PiiProtector {
+    /// 未設定任何個資欄位時返回 None
+    pub fn from_operations(operations: &TransformOperations) -> Option<Self> {
+        let fields = |fields: &Option<Vec<String>>| fields.clone().unwrap_or_default();
+        let protector = Self {
+            mask_fields: fields(&operations.mask_fields),
+            hash_fields: fields(&operations.hash_fields),
+            tokenize_fields: fields(&operations.tokenize_fields),
+            salt: operations.hash_salt.clone().unwrap_or_default(),
+            tokens: Mutex::new(BTreeMap::new()),
+        };
+        (!protector.mask_fields.is_empty()
+            || !protector.hash_fields.is_empty()
+            || !protector.tokenize_fields.is_empty())
+        .then_some(protector)
+    }
+
+    /// 有雜湊或代碼化欄位卻未設定 salt：常見值（email、電話）可用字典反查
+    pub fn is_unsalted(&self) -> bool {
+        self.salt.is_empty() && (!self.hash_fields.is_empty() || !self.tokenize_fields.is_empty())
+    }
+
+    pub fn apply(&self, record: &mut Record) {
+        for field in &self.mask_fields {
+            if let Some(value) = record.data.get_mut(field).filter(|value| !value.is_null()) {
+                *value = serde_json::Value::String(MASK.to_string());
+            }
+        }
+        for field in &self.hash_fields {
+            if let Some(value) = record.data.get_mut(field).filter(|value| !value.is_null()) {
+                *value = serde_json::Value::String(self.digest(&[&plain_text(value)]));
+            }
+        }
+        for field in &self.tokenize_fields {
+            if let Some(value) = record.data.get_mut(field).filter(|value| !value.is_null()) {
+                // 同一欄位的相同值（與相同 salt）得到相同代碼，輸出之間仍可關聯
+                let token = format!("tok_{}", &self.digest(&[field, &plain_text(value)])[..16]);
+                let original = std::mem::replace(value, serde_json::Value::String(token.clone()));
+                if let Ok(mut tokens) = self.tokens.lock() {
+                    tokens.entry(token).or_insert_with(|| TokenEntry {
+                        field: field.clone(),
+                        value: original,
+                    });
+                }
+            }
+        }
+    }
+
+    /// salt 與各部分前都加上長度，避免 ("ab", "c") 與 ("a", "bc") 得到相同的雜湊
+    fn digest(&self, parts: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        for part in std::iter::once(self.salt.as_str()).chain(parts.iter().copied()) {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// 將本次產生的代碼併入對照表；返回對照表路徑與新增的代碼數
+    pub async fn save_tokens<S: Storage>(
+        &self,
+        storage: &S,
+        pipeline_name: &str,
+        cipher: Option<&StateCipher>,
+    ) -> Result<Option<(String, usize)>> {
+        let new_tokens = match self.tokens.lock() {
+            Ok(mut tokens) => std::mem::take(&mut *tokens),
+            Err(_) => {
+                return Err(EtlError::ProcessingError {
+                    message: "Token vault lock is poisoned".to_string(),
+                })
+            }
+        };
+        if new_tokens.is_empty() {
+            return Ok(None);
+        }
+
+        let path = token_vault_path(pipeline_name);
+        let mut vault: BTreeMap<String, TokenEntry> = match storage.read_file(&path).await {
+            Ok(bytes) => serde_json::from_slice(&open_state(cipher, &bytes, &path)?)?,
+            // 只有對照表不存在才從空白開始，其他讀取錯誤若照樣覆寫會遺失既有的代碼對照
+            Err(e) if e.is_not_found() => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+        let before = vault.len();
+        vault.extend(new_tokens);
+        let added = vault.len() - before;
+
+        let json = serde_json::to_vec_pretty(&vault)?;
+        storage
+            .write_file(&path, &seal_state(cipher, &json, &path)?)
+            .await?;
+        Ok(Some((path, added)))
+    }
+}
+
+/// 代碼化對照表的路徑（相對於存儲，與 checkpoint 一樣不在輸出檔內）
+pub fn token_vault_path(pipeline_name: &str) -> String {
+    format!(".tokens/{}_tokens.json", pipeline_name)
+}
+
+fn plain_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_mask_hash_and_tokenize() {
+        let operations: TransformOperations = toml::from_str(
+            r#"
+            mask_fields = ["phone"]
+            hash_fields = ["email"]
+            hash_salt = "pepper"
+            tokenize_fields = ["customer_id"]
+            "#,
+        )
+        .unwrap();
+        let protector = PiiProtector::from_operations(&operations).unwrap();
+        let record = || Record {
+            data: HashMap::from([
+                ("phone".to_string(), json!("0912345678")),
+                ("email".to_string(), json!("ada@example.com")),
+                ("customer_id".to_string(), json!(42)),
+                ("nickname".to_string(), serde_json::Value::Null),
+            ]),
+        };
+
+        let mut first = record();
+        let mut second = record();
+        protector.apply(&mut first);
+        protector.apply(&mut second);
+        assert_eq!(first.data["phone"], MASK);
+        let hashed = first.data["email"].as_str().unwrap();
+        assert_eq!(hashed.len(), 64);
+        assert_ne!(hashed, "ada@example.com");
+        let token = first.data["customer_id"].as_str().unwrap().to_string();
+        assert!(token.starts_with("tok_"));
+        assert_eq!(first.data, second.data);
+        assert!(first.data["nickname"].is_null());
+
+        let temp_dir = TempDir::new().unwrap();
+        let storage = crate::LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        let (path, added) = protector
+            .save_tokens(&storage, "customers", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(added, 1);
+        let vault: BTreeMap<String, TokenEntry> =
+            serde_json::from_slice(&std::fs::read(temp_dir.path().join(path)).unwrap()).unwrap();
+        assert_eq!(vault[&token].value, json!(42));
+    }
+
+    #[test]
+    fn test_digest_separates_parts() {
+        let protector = PiiProtector {
+            salt: "pepper".to_string(),
+            ..Default::default()
+        };
+        assert_ne!(
+            protector.digest(&["ab", "c"]),
+            protector.digest(&["a", "bc"])
+        );
+        let unsalted = PiiProtector::default();
+        assert_ne!(protector.digest(&["x"]), unsalted.digest(&["x"]));
+    }
+
+    #[tokio::test]
+    async fn test_save_tokens_keeps_vault_on_read_error() {
+        let operations: TransformOperations = toml::from_str(
+            r#"
+            tokenize_fields = ["customer_id"]
+            hash_salt = "pepper"
+            "#,
+        )
+        .unwrap();
+        let protector = PiiProtector::from_operations(&operations).unwrap();
+        let mut record = Record {
+            data: HashMap::from([("customer_id".to_string(), json!(42))]),
+        };
+        protector.apply(&mut record);
+
+        // 對照表路徑是目錄而非檔案：讀取失敗但不是不存在，不可覆寫
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join(token_vault_path("customers"))).unwrap();
+        let storage = crate::LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        assert!(protector
+            .save_tokens(&storage, "customers", None)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_unsalted_hashing_is_allowed() {
+        let parse = |toml_str: &str| toml::from_str::<TransformOperations>(toml_str).unwrap();
+        let unsalted = parse(r#"hash_fields = ["email"]"#);
+        assert!(unsalted.validate_pii("transform.operations").is_ok());
+        assert!(PiiProtector::from_operations(&unsalted)
+            .unwrap()
+            .is_unsalted());
+
+        let empty_salt = parse(
+            r#"tokenize_fields = ["id"]
+            hash_salt = """#,
+        );
+        assert!(empty_salt.validate_pii("transform.operations").is_ok());
+        assert!(PiiProtector::from_operations(&empty_salt)
+            .unwrap()
+            .is_unsalted());
+
+        let salted = parse(
+            r#"hash_fields = ["email"]
+            hash_salt = "pepper""#,
+        );
+        assert!(!PiiProtector::from_operations(&salted)
+            .unwrap()
+            .is_unsalted());
+        let masked = parse(r#"mask_fields = ["phone"]"#);
+        assert!(!PiiProtector::from_operations(&masked)
+            .unwrap()
+            .is_unsalted());
+    }
+}
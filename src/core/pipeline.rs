@@ -176,6 +176,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[allow(clippy::bool_assert_comparison)]
     async fn test_transform_with_valid_data() {
         let mut input_data = Vec::new();
 
@@ -374,6 +375,27 @@ mod tests {
         assert!(!zip_data.unwrap().is_empty());
     }
 
+    #[tokio::test]
+    async fn test_load_reports_bytes_written() {
+        let storage = MockStorage::new();
+        let pipeline = SimplePipeline::new(
+            storage.clone(),
+            MockConfig::new("http://test.com".to_string()),
+        );
+        let transform_result = TransformResult {
+            processed_records: Vec::new(),
+            csv_output: "id\n1".to_string(),
+            tsv_output: "id\n1".to_string(),
+            intermediate_data: Vec::new(),
+        };
+
+        pipeline.load(transform_result).await.unwrap();
+
+        // 寫入大小取自實際寫入存儲的內容，不依賴本地檔案
+        let zip_data = storage.get_file("etl_output.zip").await.unwrap();
+        assert_eq!(pipeline.bytes_written(), Some(zip_data.len() as u64));
+    }
+
     #[tokio::test]
     async fn test_load_without_intermediate_data() {
         let storage = MockStorage::new();
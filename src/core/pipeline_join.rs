@@ -0,0 +1,149 @@
+use crate::core::context_index::index_key;
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use std::collections::{HashMap, HashSet};
+
+/// 兩個 Pipeline 輸出的合併方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinType {
+    /// 只保留兩邊都有對應的記錄
+    #[default]
+    Inner,
+    /// 保留所有左側記錄，沒有對應時只有左側欄位
+    Left,
+    /// 保留兩邊所有記錄
+    Full,
+}
+
+impl JoinType {
+    pub const SUPPORTED: [&'static str; 3] = ["inner", "left", "full"];
+
+    pub fn parse(value: &str, field: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "inner" => Ok(Self::Inner),
+            "left" => Ok(Self::Left),
+            "full" | "outer" => Ok(Self::Full),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: other.to_string(),
+                reason: format!("Supported join types: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 依鍵欄位合併左右兩組記錄
+///
+/// 鍵值以字串比較（數字 1 與字串 "1" 視為相同），缺少鍵或為 null 的記錄不會對應。
+/// 一筆左側記錄對應多筆右側記錄時各產生一筆結果；右側與左側同名的欄位以
+/// `{right_prefix}_{欄位}` 保留，不覆寫左側的值。
+pub fn join_records(
+    left: &[Record],
+    right: &[Record],
+    left_key: &str,
+    right_key: &str,
+    join_type: JoinType,
+    right_prefix: &str,
+) -> Vec<Record> {
+    let mut right_index: HashMap<String, Vec<usize>> = HashMap::new();
+    for (position, record) in right.iter().enumerate() {
+        if let Some(key) = record.data.get(right_key).and_then(index_key) {
+            right_index.entry(key).or_default().push(position);
+        }
+    }
+
+    let mut matched_right = HashSet::new();
+    let mut joined = Vec::new();
+    for left_record in left {
+        let matches = left_record
+            .data
+            .get(left_key)
+            .and_then(index_key)
+            .and_then(|key| right_index.get(&key));
+        match matches {
+            Some(positions) => {
+                for &position in positions {
+                    matched_right.insert(position);
+                    joined.push(merge(
+                        left_record,
+                        &right[position],
+                        right_key,
+                        right_prefix,
+                    ));
+                }
+            }
+            None if join_type != JoinType::Inner => joined.push(left_record.clone()),
+            None => {}
+        }
+    }
+
+    if join_type == JoinType::Full {
+        joined.extend(
+            right
+                .iter()
+                .enumerate()
+                .filter(|(position, _)| !matched_right.contains(position))
+                .map(|(_, record)| record.clone()),
+        );
+    }
+    joined
+}
+
+fn merge(left: &Record, right: &Record, right_key: &str, right_prefix: &str) -> Record {
+    let mut data = left.data.clone();
+    for (field, value) in &right.data {
+        match data.get(field) {
+            None => {
+                data.insert(field.clone(), value.clone());
+            }
+            // 鍵欄位同名時兩邊的值相同，不需另存
+            Some(_) if field == right_key => {}
+            Some(_) => {
+                data.insert(format!("{}_{}", right_prefix, field), value.clone());
+            }
+        }
+    }
+    Record { data }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(value: serde_json::Value) -> Record {
+        Record {
+            data: serde_json::from_value(value).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_join_types() {
+        let users = vec![
+            record(json!({"id": 1, "name": "Amy"})),
+            record(json!({"id": 2, "name": "Bob"})),
+        ];
+        let orders = vec![
+            record(json!({"user_id": "1", "order": "A", "name": "gift"})),
+            record(json!({"user_id": 1, "order": "B"})),
+            record(json!({"user_id": 3, "order": "C"})),
+        ];
+
+        let inner = join_records(&users, &orders, "id", "user_id", JoinType::Inner, "orders");
+        assert_eq!(inner.len(), 2);
+        assert_eq!(inner[0].data["name"], "Amy");
+        assert_eq!(inner[0].data["orders_name"], "gift");
+        assert_eq!(inner[1].data["order"], "B");
+
+        let left = join_records(&users, &orders, "id", "user_id", JoinType::Left, "orders");
+        assert_eq!(left.len(), 3);
+        assert_eq!(left[2].data["name"], "Bob");
+        assert!(!left[2].data.contains_key("order"));
+
+        let full = join_records(&users, &orders, "id", "user_id", JoinType::Full, "orders");
+        assert_eq!(full.len(), 4);
+        assert_eq!(full[3].data["order"], "C");
+
+        assert!(JoinType::parse("cross", "source.join.type").is_err());
+    }
+}
@@ -1,6 +1,9 @@
 /// Pipeline 執行結果
 pub use crate::app::pipelines::sequence_pipeline::PipelineResult;
 
+/// Pipeline 被略過的原因
+pub use crate::app::pipelines::sequence_pipeline::SkipReason;
+
 pub use crate::app::pipelines::sequence_pipeline::PipelineContext;
 
 /// 上下文感知的 Pipeline trait
@@ -13,14 +16,24 @@ pub use crate::app::pipelines::sequence_pipeline::PipelineSequence;
 mod tests {
     use super::*;
     use crate::domain::model::{Record, TransformResult};
-    use crate::utils::error::Result;
+    use crate::utils::error::{EtlError, Result};
     use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     struct MockPipeline {
         name: String,
         should_execute: bool,
         extract_records: Vec<Record>,
         use_previous_data: bool,
+        should_fail: bool,
+        outputs: HashMap<String, String>,
+        on_success: Option<String>,
+        on_failure: Option<String>,
+        dependencies: Vec<String>,
+        skip_on_empty: bool,
+        transient_failures: AtomicUsize,
+        exports: Vec<(String, serde_json::Value)>,
+        load_failures: AtomicUsize,
     }
 
     impl MockPipeline {
@@ -30,9 +43,61 @@ mod tests {
                 should_execute: true,
                 extract_records: Vec::new(),
                 use_previous_data: false,
+                should_fail: false,
+                outputs: HashMap::new(),
+                on_success: None,
+                on_failure: None,
+                dependencies: Vec::new(),
+                skip_on_empty: false,
+                transient_failures: AtomicUsize::new(0),
+                exports: Vec::new(),
+                load_failures: AtomicUsize::new(0),
             }
         }
 
+        fn with_export(mut self, key: &str, value: serde_json::Value) -> Self {
+            self.exports.push((key.to_string(), value));
+            self
+        }
+
+        /// 前幾次 load 失敗；失敗的嘗試在轉換時另外寫入 failed_attempt
+        fn with_load_failures(self, failures: usize) -> Self {
+            self.load_failures.store(failures, Ordering::SeqCst);
+            self
+        }
+
+        fn with_transient_failures(self, failures: usize) -> Self {
+            self.transient_failures.store(failures, Ordering::SeqCst);
+            self
+        }
+
+        fn with_dependencies(mut self, dependencies: &[&str]) -> Self {
+            self.dependencies = dependencies.iter().map(|d| d.to_string()).collect();
+            self
+        }
+
+        fn with_skip_on_empty(mut self, skip_on_empty: bool) -> Self {
+            self.skip_on_empty = skip_on_empty;
+            self
+        }
+
+        fn with_branches(mut self, on_success: Option<&str>, on_failure: Option<&str>) -> Self {
+            self.on_success = on_success.map(str::to_string);
+            self.on_failure = on_failure.map(str::to_string);
+            self
+        }
+
+        fn with_output(mut self, name: &str, expression: &str) -> Self {
+            self.outputs
+                .insert(name.to_string(), expression.to_string());
+            self
+        }
+
+        fn with_failure(mut self, should_fail: bool) -> Self {
+            self.should_fail = should_fail;
+            self
+        }
+
         fn with_records(mut self, records: Vec<Record>) -> Self {
             self.extract_records = records;
             self
@@ -52,8 +117,17 @@ mod tests {
     #[async_trait::async_trait]
     impl ContextualPipeline for MockPipeline {
         async fn extract_with_context(&self, context: &PipelineContext) -> Result<Vec<Record>> {
+            let transient = self
+                .transient_failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if self.should_fail || transient {
+                return Err(crate::utils::error::EtlError::ProcessingError {
+                    message: format!("{} failed", self.name),
+                });
+            }
             if self.use_previous_data {
-                Ok(context.get_all_previous_records())
+                context.get_all_previous_records()
             } else {
                 Ok(self.extract_records.clone())
             }
@@ -62,8 +136,18 @@ mod tests {
         async fn transform_with_context(
             &self,
             data: Vec<Record>,
-            _context: &mut PipelineContext,
+            context: &mut PipelineContext,
         ) -> Result<TransformResult> {
+            for (key, value) in &self.exports {
+                context.write_shared_data(&self.name, key.clone(), value.clone());
+            }
+            if self.load_failures.load(Ordering::SeqCst) > 0 {
+                context.write_shared_data(
+                    &self.name,
+                    "failed_attempt".to_string(),
+                    serde_json::json!(true),
+                );
+            }
             Ok(TransformResult {
                 processed_records: data,
                 csv_output: String::new(),
@@ -74,9 +158,18 @@ mod tests {
 
         async fn load_with_context(
             &self,
-            _result: TransformResult,
+            _result: &TransformResult,
             _context: &PipelineContext,
         ) -> Result<String> {
+            if self
+                .load_failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Err(EtlError::ProcessingError {
+                    message: format!("{} load failed", self.name),
+                });
+            }
             Ok(format!("/tmp/{}_output.json", self.name))
         }
 
@@ -87,6 +180,26 @@ mod tests {
         fn should_execute(&self, _context: &PipelineContext) -> bool {
             self.should_execute
         }
+
+        fn output_definitions(&self) -> HashMap<String, String> {
+            self.outputs.clone()
+        }
+
+        fn on_success(&self) -> Option<&str> {
+            self.on_success.as_deref()
+        }
+
+        fn on_failure(&self) -> Option<&str> {
+            self.on_failure.as_deref()
+        }
+
+        fn dependencies(&self) -> Vec<String> {
+            self.dependencies.clone()
+        }
+
+        fn skip_on_empty_input(&self) -> bool {
+            self.skip_on_empty
+        }
     }
 
     fn create_test_record(id: i64, title: &str) -> Record {
@@ -102,12 +215,123 @@ mod tests {
         Record { data }
     }
 
+    #[tokio::test]
+    async fn test_on_success_and_on_failure_branches() {
+        let executed = |results: &[PipelineResult]| {
+            results
+                .iter()
+                .map(|r| r.pipeline_name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        // 分支目標不參與一般順序，成功時沿 on_success 執行後再繼續
+        let mut sequence = PipelineSequence::new("branches".to_string());
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("extract").with_branches(Some("notify"), Some("cleanup")),
+        ));
+        sequence.add_pipeline(Box::new(MockPipeline::new("report")));
+        sequence.add_pipeline(Box::new(MockPipeline::new("notify")));
+        sequence.add_pipeline(Box::new(MockPipeline::new("cleanup")));
+        let results = sequence.execute_all().await.unwrap();
+        assert_eq!(executed(&results), vec!["extract", "notify", "report"]);
+
+        // 失敗時執行 on_failure，序列仍以失敗結束；未設定時改用 fallback_pipeline
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = crate::core::sequence_state::SequenceStateStore::new(temp_dir.path());
+        let mut sequence = PipelineSequence::new("branch_failure".to_string())
+            .with_state_store(store.clone())
+            .with_fallback_pipeline("alert");
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("extract")
+                .with_failure(true)
+                .with_branches(Some("notify"), Some("cleanup")),
+        ));
+        sequence.add_pipeline(Box::new(MockPipeline::new("notify")));
+        sequence.add_pipeline(Box::new(MockPipeline::new("cleanup")));
+        sequence.add_pipeline(Box::new(MockPipeline::new("alert")));
+        assert!(sequence.execute_all().await.is_err());
+
+        let state = store.load("branch_failure").unwrap();
+        assert_eq!(state.failed_pipeline.as_deref(), Some("extract"));
+        assert_eq!(state.completed_pipelines, vec!["cleanup".to_string()]);
+
+        let mut sequence =
+            PipelineSequence::new("fallback".to_string()).with_fallback_pipeline("alert");
+        sequence.add_pipeline(Box::new(MockPipeline::new("extract").with_failure(true)));
+        sequence.add_pipeline(Box::new(MockPipeline::new("alert")));
+        let error = sequence
+            .execute_all()
+            .await
+            .expect_err("fallback does not recover the sequence");
+
+        // 序列的錯誤保留 Pipeline 原本的錯誤種類，另附上失敗的位置
+        assert!(matches!(error.root(), EtlError::ProcessingError { .. }));
+        let context = error.context().unwrap();
+        assert_eq!(context.pipeline.as_deref(), Some("extract"));
+        assert_eq!(context.stage.as_deref(), Some("extract"));
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_retry_records_attempts() {
+        let mut sequence = PipelineSequence::new("retry".to_string())
+            .with_pipeline_retry(2, std::time::Duration::ZERO);
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("flaky_counted")
+                .with_records(vec![create_test_record(1, "a")])
+                .with_transient_failures(2),
+        ));
+        let results = sequence.execute_all().await.unwrap();
+        assert_eq!(results[0].records.len(), 1);
+        assert_eq!(results[0].metadata["attempts"], 3);
+        // 每次 Pipeline 重試都計入重試指標
+        let metrics = crate::utils::prometheus::global().render();
+        assert!(metrics.contains("etl_http_retries_total{pipeline=\"flaky_counted\"} 2"));
+        assert_eq!(results[0].metadata["stage_metrics"]["http_retries"], 2);
+
+        // 重試用盡仍失敗則整個序列失敗
+        let mut sequence = PipelineSequence::new("retry_exhausted".to_string())
+            .with_pipeline_retry(1, std::time::Duration::ZERO);
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("flaky").with_transient_failures(2),
+        ));
+        assert!(sequence.execute_all().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_discards_shared_data_of_failed_attempt() {
+        use crate::core::sequence_state::SequenceStateStore;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = SequenceStateStore::new(temp_dir.path());
+        let mut sequence = PipelineSequence::new("shared_retry".to_string())
+            .with_state_store(store.clone())
+            .with_pipeline_retry(1, std::time::Duration::ZERO);
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("auth")
+                .with_records(vec![create_test_record(1, "a")])
+                .with_export("token", serde_json::json!("abc"))
+                .with_load_failures(1),
+        ));
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("users").with_records(vec![create_test_record(2, "b")]),
+        ));
+        sequence.execute_all().await.unwrap();
+
+        // 成功的嘗試導出的值傳給後續 Pipeline，失敗嘗試的寫入不會殘留在共享存放區
+        let context = store.load("shared_retry").unwrap().context;
+        assert_eq!(
+            context.get_shared_data("token"),
+            Some(&serde_json::json!("abc"))
+        );
+        assert!(context.get_shared_data("failed_attempt").is_none());
+    }
+
     #[tokio::test]
     async fn test_pipeline_context_new() {
         let context = PipelineContext::new("test_execution".to_string());
         assert_eq!(context.execution_id, "test_execution");
         assert!(context.previous_results.is_empty());
-        assert!(context.shared_data.is_empty());
+        assert!(context.all_shared_data().is_empty());
     }
 
     #[tokio::test]
@@ -117,10 +341,11 @@ mod tests {
         let records = vec![create_test_record(1, "Test")];
         context.add_pipeline_data("pipeline1".to_string(), records.clone());
 
-        let retrieved = context.get_pipeline_data("pipeline1");
+        let retrieved = context.get_pipeline_data("pipeline1").unwrap();
         assert!(retrieved.is_some());
-        assert_eq!(retrieved.unwrap().len(), 1);
-        assert_eq!(retrieved.unwrap()[0].data.get("title").unwrap(), "Test");
+        let retrieved = retrieved.unwrap();
+        assert_eq!(retrieved.len(), 1);
+        assert_eq!(retrieved[0].data.get("title").unwrap(), "Test");
     }
 
     #[tokio::test]
@@ -147,6 +372,33 @@ mod tests {
         assert!(context.get_shared_data("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_shared_data_policy_across_context_copies() {
+        use crate::app::pipelines::shared_data::{SharedDataPolicy, SharedDataWrite};
+
+        let mut context = PipelineContext::new("test".to_string());
+        context.configure_shared_data(SharedDataPolicy::FirstWriteWins, HashMap::new());
+        let mut branch = context.clone();
+
+        assert_eq!(
+            branch.write_shared_data("auth", "token".to_string(), serde_json::json!("a")),
+            SharedDataWrite::Written
+        );
+        // 另一個複本寫入同一鍵時，由共享存放區判斷衝突
+        assert_eq!(
+            context.write_shared_data("users", "token".to_string(), serde_json::json!("b")),
+            SharedDataWrite::Rejected {
+                owner: "auth".to_string()
+            }
+        );
+
+        context.sync_shared_data();
+        assert_eq!(
+            context.get_shared_data("token"),
+            Some(&serde_json::json!("a"))
+        );
+    }
+
     #[tokio::test]
     async fn test_pipeline_context_merge_with_previous() {
         let mut context = PipelineContext::new("test".to_string());
@@ -172,7 +424,9 @@ mod tests {
             data: api_record_data,
         }];
 
-        let merged = context.merge_with_previous("previous", api_records);
+        let merged = context
+            .merge_with_previous("previous", api_records)
+            .unwrap();
 
         assert_eq!(merged.len(), 1);
         assert_eq!(
@@ -228,9 +482,47 @@ mod tests {
 
         let results = sequence.execute_all().await.unwrap();
 
-        assert_eq!(results.len(), 2); // 只有 pipeline1 和 pipeline3 執行
+        // 只有 pipeline1 和 pipeline3 執行，pipeline2 以略過紀錄出現在結果中
+        assert_eq!(results.len(), 3);
         assert_eq!(results[0].pipeline_name, "pipeline1");
-        assert_eq!(results[1].pipeline_name, "pipeline3");
+        assert_eq!(results[1].pipeline_name, "pipeline2");
+        assert_eq!(results[1].skipped, Some(SkipReason::ConditionNotMet));
+        assert_eq!(results[2].pipeline_name, "pipeline3");
+        assert!(!results[2].is_skipped());
+    }
+
+    #[tokio::test]
+    async fn test_skipped_results_for_dependencies_and_empty_input() {
+        let mut sequence = PipelineSequence::new("skips".to_string());
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("users").with_skip_on_empty(true),
+        ));
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("orders")
+                .with_records(vec![create_test_record(1, "Order")])
+                .with_dependencies(&["users"]),
+        ));
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("report").with_records(vec![create_test_record(2, "Report")]),
+        ));
+
+        let results = sequence.execute_all().await.unwrap();
+        let reasons: Vec<Option<SkipReason>> = results.iter().map(|r| r.skipped.clone()).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                Some(SkipReason::EmptyInput),
+                Some(SkipReason::DependencyFailed {
+                    dependency: "users".to_string()
+                }),
+                None
+            ]
+        );
+
+        let summary = PipelineSequence::get_execution_summary(&results);
+        assert_eq!(summary["total_pipelines"], 1);
+        assert_eq!(summary["skipped_pipelines"], 2);
+        assert_eq!(summary["skipped"]["users"], "empty input");
     }
 
     #[tokio::test]
@@ -238,17 +530,23 @@ mod tests {
         let results = vec![
             PipelineResult {
                 pipeline_name: "pipeline1".to_string(),
-                records: vec![create_test_record(1, "Test")],
+                records: vec![create_test_record(1, "Test")].into(),
                 output_path: "/tmp/output1.json".to_string(),
                 duration: std::time::Duration::from_millis(100),
                 metadata: HashMap::new(),
+                warnings: Vec::new(),
+                outputs: HashMap::new(),
+                skipped: None,
             },
             PipelineResult {
                 pipeline_name: "pipeline2".to_string(),
-                records: vec![create_test_record(2, "Test"), create_test_record(3, "Test")],
+                records: vec![create_test_record(2, "Test"), create_test_record(3, "Test")].into(),
                 output_path: "/tmp/output2.json".to_string(),
                 duration: std::time::Duration::from_millis(200),
                 metadata: HashMap::new(),
+                warnings: Vec::new(),
+                outputs: HashMap::new(),
+                skipped: None,
             },
         ];
 
@@ -283,24 +581,118 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_pipeline_sequence_resume_skips_completed() {
+        use crate::core::sequence_state::{SequenceStateStore, SequenceStatus};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = SequenceStateStore::new(temp_dir.path());
+
+        let mut sequence =
+            PipelineSequence::new("resume_run".to_string()).with_state_store(store.clone());
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("first").with_records(vec![create_test_record(1, "First")]),
+        ));
+        sequence.add_pipeline(Box::new(MockPipeline::new("second").with_failure(true)));
+        assert!(sequence.execute_all().await.is_err());
+
+        let state = store.load("resume_run").unwrap();
+        assert_eq!(state.status, SequenceStatus::Failed);
+        assert_eq!(state.completed_pipelines, vec!["first".to_string()]);
+
+        // 第一個 Pipeline 若再次執行會失敗，確保續跑時被跳過
+        let mut resumed = PipelineSequence::new("ignored".to_string())
+            .with_state_store(store.clone())
+            .resume_from(state);
+        resumed.add_pipeline(Box::new(MockPipeline::new("first").with_failure(true)));
+        resumed.add_pipeline(Box::new(
+            MockPipeline::new("second").with_previous_data(true),
+        ));
+
+        let results = resumed.execute_all().await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].records.len(), 1);
+        assert_eq!(
+            store.load("resume_run").unwrap().status,
+            SequenceStatus::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_sequence_stops_on_shutdown() {
+        use crate::core::sequence_state::{SequenceStateStore, SequenceStatus};
+        use crate::utils::shutdown::ShutdownSignal;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = SequenceStateStore::new(temp_dir.path());
+        let shutdown = ShutdownSignal::new();
+        shutdown.request();
+
+        let mut sequence = PipelineSequence::new("stopped_run".to_string())
+            .with_state_store(store.clone())
+            .with_shutdown(shutdown);
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("first").with_records(vec![create_test_record(1, "First")]),
+        ));
+        let error = sequence.execute_all().await.unwrap_err();
+        assert!(matches!(error, EtlError::Interrupted { .. }));
+        assert_eq!(error.exit_code(), 130);
+
+        let state = store.load("stopped_run").unwrap();
+        assert_eq!(state.status, SequenceStatus::Interrupted);
+        assert!(state.completed_pipelines.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_outputs_in_summary_and_state() {
+        use crate::core::sequence_state::SequenceStateStore;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = SequenceStateStore::new(temp_dir.path());
+
+        let mut sequence =
+            PipelineSequence::new("outputs_run".to_string()).with_state_store(store.clone());
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("posts")
+                .with_records(vec![create_test_record(4, "a"), create_test_record(9, "b")])
+                .with_output("last_run_max_id", "max(id)"),
+        ));
+
+        let results = sequence.execute_all().await.unwrap();
+        assert_eq!(results[0].outputs["last_run_max_id"], 9);
+
+        let summary = PipelineSequence::get_execution_summary(&results);
+        assert_eq!(summary["outputs"]["posts"]["last_run_max_id"], 9);
+        assert_eq!(
+            store.load("outputs_run").unwrap().outputs["posts"]["last_run_max_id"],
+            9
+        );
+    }
+
     #[test]
     fn test_pipeline_context_get_result_by_name() {
         let mut context = PipelineContext::new("test".to_string());
 
         let result1 = PipelineResult {
             pipeline_name: "pipeline1".to_string(),
-            records: vec![create_test_record(1, "Test")],
+            records: vec![create_test_record(1, "Test")].into(),
             output_path: "/tmp/output1.json".to_string(),
             duration: std::time::Duration::from_millis(100),
             metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
         };
 
         let result2 = PipelineResult {
             pipeline_name: "pipeline2".to_string(),
-            records: vec![create_test_record(2, "Test")],
+            records: vec![create_test_record(2, "Test")].into(),
             output_path: "/tmp/output2.json".to_string(),
             duration: std::time::Duration::from_millis(200),
             metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
         };
 
         context.add_result(result1.clone());
@@ -316,4 +708,79 @@ mod tests {
 
         assert!(context.get_result_by_name("nonexistent").is_none());
     }
+
+    #[tokio::test]
+    async fn test_large_results_spill_to_disk_and_reload() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let spill_dir = temp_dir.path().join("spill");
+        let store = crate::core::sequence_state::SequenceStateStore::new(temp_dir.path());
+
+        let mut sequence = PipelineSequence::new("spill".to_string())
+            .with_state_store(store.clone())
+            .with_context_spill(crate::core::context_spill::ContextSpill::new(1, &spill_dir));
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("small").with_records(vec![create_test_record(1, "a")]),
+        ));
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("large")
+                .with_records(vec![create_test_record(2, "b"), create_test_record(3, "c")]),
+        ));
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("reader").with_previous_data(true),
+        ));
+        sequence.add_pipeline(Box::new(MockPipeline::new("broken").with_failure(true)));
+        assert!(sequence.execute_all().await.is_err());
+
+        // 只有超過上限的結果寫入暫存檔，後續 Pipeline 仍讀得到完整記錄
+        let state = store.load("spill").unwrap();
+        let large = state.context.get_result_by_name("large").unwrap();
+        assert!(large.records.is_empty());
+        assert_eq!(state.context.record_count(large), 2);
+        assert!(spill_dir.join("large.jsonl").exists());
+        assert!(!spill_dir.join("small.jsonl").exists());
+        assert_eq!(
+            state
+                .context
+                .get_pipeline_data("large")
+                .unwrap()
+                .unwrap()
+                .len(),
+            2
+        );
+        let reader = state.context.get_result_by_name("reader").unwrap();
+        assert_eq!(state.context.records_of(reader).unwrap().len(), 3);
+
+        // 續跑成功後移除暫存目錄
+        let mut resumed = PipelineSequence::new("spill".to_string())
+            .with_state_store(store.clone())
+            .with_context_spill(crate::core::context_spill::ContextSpill::new(1, &spill_dir))
+            .resume_from(state);
+        for name in ["small", "large", "reader", "broken"] {
+            resumed.add_pipeline(Box::new(MockPipeline::new(name)));
+        }
+        resumed.execute_all().await.unwrap();
+        assert!(!spill_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_spilled_results_keep_no_records_in_memory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut sequence = PipelineSequence::new("spill".to_string()).with_context_spill(
+            crate::core::context_spill::ContextSpill::new(1, temp_dir.path().join("spill")),
+        );
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("small").with_records(vec![create_test_record(1, "a")]),
+        ));
+        sequence.add_pipeline(Box::new(
+            MockPipeline::new("large")
+                .with_records(vec![create_test_record(2, "b"), create_test_record(3, "c")]),
+        ));
+        let results = sequence.execute_all().await.unwrap();
+
+        // 返回的結果清單也不保留寫入暫存檔的記錄，只留筆數
+        assert_eq!(results[0].records.len(), 1);
+        assert_eq!(results[0].record_count(), 1);
+        assert!(results[1].records.is_empty());
+        assert_eq!(results[1].record_count(), 2);
+    }
 }
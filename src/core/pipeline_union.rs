@@ -0,0 +1,80 @@
+use crate::core::context_index::index_key;
+use crate::core::Record;
+use std::collections::HashSet;
+
+/// 依序合併多個 Pipeline 的輸出
+///
+/// 指定 dedup_keys 時，鍵值（以字串比較）相同的記錄只保留最先出現的一筆，
+/// 因此 `sources` 的順序即為優先順序；缺少任一鍵或鍵為 null 的記錄一律保留。
+/// 指定 source_field 時，每筆記錄加上來源 Pipeline 名稱。
+pub fn union_records(
+    sources: &[(&str, &[Record])],
+    dedup_keys: &[String],
+    source_field: Option<&str>,
+) -> (Vec<Record>, usize) {
+    let mut seen = HashSet::new();
+    let mut records = Vec::new();
+    let mut duplicates = 0;
+    for (name, source_records) in sources {
+        for record in *source_records {
+            if !dedup_keys.is_empty() {
+                let key: Option<Vec<String>> = dedup_keys
+                    .iter()
+                    .map(|field| record.data.get(field).and_then(index_key))
+                    .collect();
+                if let Some(key) = key {
+                    if !seen.insert(key) {
+                        duplicates += 1;
+                        continue;
+                    }
+                }
+            }
+
+            let mut record = record.clone();
+            if let Some(field) = source_field {
+                record.data.insert(
+                    field.to_string(),
+                    serde_json::Value::String(name.to_string()),
+                );
+            }
+            records.push(record);
+        }
+    }
+    (records, duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(value: serde_json::Value) -> Record {
+        Record {
+            data: serde_json::from_value(value).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_union_with_dedup_and_source_tag() {
+        let crm = vec![
+            record(json!({"email": "a@x.com", "name": "Amy"})),
+            record(json!({"email": "b@x.com", "name": "Bob"})),
+        ];
+        let shop = vec![
+            record(json!({"email": "a@x.com", "name": "Amy S."})),
+            record(json!({"name": "Guest"})),
+            record(json!({"name": "Guest"})),
+        ];
+        let sources = [("crm", crm.as_slice()), ("shop", shop.as_slice())];
+
+        let (all, duplicates) = union_records(&sources, &[], None);
+        assert_eq!((all.len(), duplicates), (5, 0));
+
+        let (unique, duplicates) = union_records(&sources, &["email".to_string()], Some("source"));
+        assert_eq!((unique.len(), duplicates), (4, 1));
+        assert_eq!(unique[0].data["name"], "Amy");
+        assert_eq!(unique[0].data["source"], "crm");
+        assert_eq!(unique[2].data["source"], "shop");
+        assert!(!crm[0].data.contains_key("source"));
+    }
+}
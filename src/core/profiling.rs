@@ -0,0 +1,233 @@
+use crate::core::Record;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// 輸出檔中的欄位統計檔名
+pub const PROFILE_FILE_NAME: &str = "profile.json";
+
+/// 預設列出的最常見值數量
+pub const DEFAULT_TOP_K: usize = 5;
+
+/// profile.json 的內容：每個欄位的統計，欄位依名稱排序
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataProfile {
+    pub records: usize,
+    pub fields: Vec<FieldProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldProfile {
+    pub name: String,
+    pub count: usize, // 非 null 的值數量
+    pub nulls: usize, // 值為 null 或缺少此欄位的記錄數
+    pub null_percent: f64,
+    pub distinct: usize,
+    pub min: serde_json::Value, // 有數值時為最小數值，否則為字串的字典序最小值
+    pub max: serde_json::Value,
+    pub mean: Option<f64>, // 只計算數值
+    pub top_values: Vec<ValueCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueCount {
+    pub value: serde_json::Value,
+    pub count: usize,
+}
+
+/// 單一欄位統計的累計值
+#[derive(Default)]
+struct FieldStats<'a> {
+    count: usize,
+    values: HashMap<String, (&'a serde_json::Value, usize)>,
+    numbers: Vec<f64>,
+    min_text: Option<&'a str>,
+    max_text: Option<&'a str>,
+}
+
+impl DataProfile {
+    /// 統計記錄的欄位；`fields` 為 None 時統計所有出現過的欄位
+    pub fn profile(records: &[Record], fields: Option<&[String]>, top_k: usize) -> Self {
+        let mut stats: BTreeMap<&str, FieldStats> = BTreeMap::new();
+        if let Some(fields) = fields {
+            for field in fields {
+                stats.entry(field.as_str()).or_default();
+            }
+        }
+        for record in records {
+            for (name, value) in &record.data {
+                let field = match fields {
+                    Some(_) => match stats.get_mut(name.as_str()) {
+                        Some(field) => field,
+                        None => continue,
+                    },
+                    None => stats.entry(name).or_default(),
+                };
+                if value.is_null() {
+                    continue;
+                }
+                field.count += 1;
+                field
+                    .values
+                    .entry(value.to_string())
+                    .or_insert((value, 0))
+                    .1 += 1;
+                match value {
+                    serde_json::Value::Number(n) => field.numbers.extend(n.as_f64()),
+                    serde_json::Value::String(s) => {
+                        if field.min_text.is_none_or(|min| s.as_str() < min) {
+                            field.min_text = Some(s);
+                        }
+                        if field.max_text.is_none_or(|max| s.as_str() > max) {
+                            field.max_text = Some(s);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let fields = stats
+            .into_iter()
+            .map(|(name, stats)| stats.finish(name, records.len(), top_k))
+            .collect();
+        Self {
+            records: records.len(),
+            fields,
+        }
+    }
+}
+
+impl FieldStats<'_> {
+    fn finish(self, name: &str, records: usize, top_k: usize) -> FieldProfile {
+        let nulls = records - self.count;
+        let (min, max, mean) = if self.numbers.is_empty() {
+            (
+                self.min_text
+                    .map(serde_json::Value::from)
+                    .unwrap_or_default(),
+                self.max_text
+                    .map(serde_json::Value::from)
+                    .unwrap_or_default(),
+                None,
+            )
+        } else {
+            let min = self.numbers.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = self
+                .numbers
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            let mean = self.numbers.iter().sum::<f64>() / self.numbers.len() as f64;
+            (number(min), number(max), Some(mean))
+        };
+
+        // 出現次數相同時依值排序，讓輸出穩定
+        let mut top_values: Vec<(String, &serde_json::Value, usize)> = self
+            .values
+            .iter()
+            .map(|(key, (value, count))| (key.clone(), *value, *count))
+            .collect();
+        top_values.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+        FieldProfile {
+            name: name.to_string(),
+            count: self.count,
+            nulls,
+            null_percent: if records == 0 {
+                0.0
+            } else {
+                nulls as f64 * 100.0 / records as f64
+            },
+            distinct: self.values.len(),
+            min,
+            max,
+            mean,
+            top_values: top_values
+                .into_iter()
+                .take(top_k)
+                .map(|(_, value, count)| ValueCount {
+                    value: value.clone(),
+                    count,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 整數值以整數輸出
+fn number(value: f64) -> serde_json::Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        serde_json::json!(value as i64)
+    } else {
+        serde_json::json!(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn records() -> Vec<Record> {
+        [
+            json!({"id": 1, "country": "TW", "price": 10.5}),
+            json!({"id": 2, "country": "US", "price": null}),
+            json!({"id": 3, "country": "TW"}),
+            json!({"id": 4, "country": "JP", "price": 2}),
+        ]
+        .into_iter()
+        .map(|data| Record {
+            data: serde_json::from_value(data).unwrap(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_profile_fields() {
+        let profile = DataProfile::profile(&records(), None, 2);
+        assert_eq!(profile.records, 4);
+        let names: Vec<&str> = profile.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["country", "id", "price"]);
+
+        let country = &profile.fields[0];
+        assert_eq!(country.distinct, 3);
+        assert_eq!(country.min, json!("JP"));
+        assert_eq!(country.max, json!("US"));
+        assert_eq!(country.mean, None);
+        assert_eq!(
+            country.top_values,
+            vec![
+                ValueCount {
+                    value: json!("TW"),
+                    count: 2
+                },
+                ValueCount {
+                    value: json!("JP"),
+                    count: 1
+                },
+            ]
+        );
+
+        let id = &profile.fields[1];
+        assert_eq!((id.min.clone(), id.max.clone()), (json!(1), json!(4)));
+        assert_eq!(id.mean, Some(2.5));
+
+        let price = &profile.fields[2];
+        assert_eq!(price.count, 2);
+        assert_eq!(price.nulls, 2);
+        assert_eq!(price.null_percent, 50.0);
+        assert_eq!(price.min, json!(2));
+        assert_eq!(price.max, json!(10.5));
+    }
+
+    #[test]
+    fn test_profile_selected_fields() {
+        let fields = vec!["price".to_string(), "missing".to_string()];
+        let profile = DataProfile::profile(&records(), Some(&fields), DEFAULT_TOP_K);
+        let names: Vec<&str> = profile.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["missing", "price"]);
+        assert_eq!(profile.fields[0].nulls, 4);
+        assert_eq!(profile.fields[0].null_percent, 100.0);
+        assert_eq!(profile.fields[0].min, serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::core::sequence_state::SequenceStatus;
+use crate::utils::heartbeat::ProgressSink;
+use crate::utils::redact::redact;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 單一 Pipeline 在本次執行中的狀態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineProgressStatus {
+    Pending,
+    Running,
+    Completed,
+    /// 條件不符或先前執行已完成（續跑）
+    Skipped,
+    Failed,
+}
+
+/// 執行中的 Pipeline 所在階段
+pub use crate::domain::model::PipelineStage;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineProgress {
+    pub name: String,
+    pub status: PipelineProgressStatus,
+    pub stage: Option<PipelineStage>,
+    pub records_extracted: Option<usize>,
+    pub records_transformed: Option<usize>,
+    pub records_loaded: Option<usize>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub error: Option<String>,
+}
+
+/// progress.json 的內容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressDocument {
+    pub execution_id: String,
+    pub status: SequenceStatus,
+    pub current_pipeline: Option<String>,
+    pub started_at: String,
+    pub updated_at: String,
+    pub finished_at: Option<String>,
+    pub pipelines: Vec<PipelineProgress>,
+}
+
+/// 執行期間持續更新的進度檔，每次事件後以暫存檔加改名的方式整份重寫
+///
+/// 作為 `ProgressTracker` 的 sink 接收執行事件（階段切換、Pipeline 完成或失敗）即時更新，
+/// 讓外部儀表板不需解析日誌就能得知每個 Pipeline 的階段與筆數。
+#[derive(Debug)]
+pub struct ProgressFile {
+    path: PathBuf,
+    document: Mutex<ProgressDocument>,
+}
+
+impl ProgressFile {
+    pub fn new(path: impl Into<PathBuf>, execution_id: &str, pipelines: &[&str]) -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Self {
+            path: path.into(),
+            document: Mutex::new(ProgressDocument {
+                execution_id: execution_id.to_string(),
+                status: SequenceStatus::Running,
+                current_pipeline: None,
+                started_at: now.clone(),
+                updated_at: now,
+                finished_at: None,
+                pipelines: pipelines
+                    .iter()
+                    .map(|name| PipelineProgress {
+                        name: name.to_string(),
+                        status: PipelineProgressStatus::Pending,
+                        stage: None,
+                        records_extracted: None,
+                        records_transformed: None,
+                        records_loaded: None,
+                        started_at: None,
+                        finished_at: None,
+                        error: None,
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
+    pub fn snapshot(&self) -> Option<ProgressDocument> {
+        self.document.lock().ok().map(|document| document.clone())
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut ProgressDocument)) {
+        let Ok(mut document) = self.document.lock() else {
+            return;
+        };
+        apply(&mut document);
+        document.updated_at = chrono::Utc::now().to_rfc3339();
+        // 進度檔只供觀察，寫入失敗不影響執行
+        if let Err(e) = write_atomic(&self.path, &document) {
+            tracing::warn!(
+                "📈 Failed to update progress file {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+impl ProgressSink for ProgressFile {
+    fn begin_pipeline(&self, name: &str) {
+        self.update(|document| {
+            document.current_pipeline = Some(name.to_string());
+            if let Some(pipeline) = pipeline_mut(document, name) {
+                pipeline.status = PipelineProgressStatus::Running;
+                pipeline.stage = Some(PipelineStage::Extract);
+                pipeline.started_at = Some(chrono::Utc::now().to_rfc3339());
+            }
+        });
+    }
+
+    /// 階段完成：記錄該階段的筆數並進入下一階段
+    fn stage_finished(&self, name: &str, stage: PipelineStage, records: usize) {
+        self.update(|document| {
+            if let Some(pipeline) = pipeline_mut(document, name) {
+                match stage {
+                    PipelineStage::Extract => {
+                        pipeline.records_extracted = Some(records);
+                        pipeline.stage = Some(PipelineStage::Transform);
+                    }
+                    PipelineStage::Transform => {
+                        pipeline.records_transformed = Some(records);
+                        pipeline.stage = Some(PipelineStage::Load);
+                    }
+                    PipelineStage::Load => pipeline.records_loaded = Some(records),
+                }
+            }
+        });
+    }
+
+    fn finish_pipeline(&self, name: &str, status: PipelineProgressStatus) {
+        self.update(|document| {
+            if document.current_pipeline.as_deref() == Some(name) {
+                document.current_pipeline = None;
+            }
+            if let Some(pipeline) = pipeline_mut(document, name) {
+                pipeline.status = status;
+                if status == PipelineProgressStatus::Completed {
+                    pipeline.stage = None;
+                }
+                pipeline.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            }
+        });
+    }
+
+    /// 失敗時保留所在階段，錯誤訊息先遮蔽憑證
+    fn fail_pipeline(&self, name: &str, error: &str) {
+        self.update(|document| {
+            if let Some(pipeline) = pipeline_mut(document, name) {
+                pipeline.error = Some(redact(error, &[]));
+            }
+        });
+        self.finish_pipeline(name, PipelineProgressStatus::Failed);
+    }
+
+    /// 序列結束；成功時未執行的 Pipeline（例如未觸發的分支）標為 skipped
+    fn finish(&self, status: SequenceStatus) {
+        self.update(|document| {
+            if status == SequenceStatus::Completed {
+                for pipeline in &mut document.pipelines {
+                    if pipeline.status == PipelineProgressStatus::Pending {
+                        pipeline.status = PipelineProgressStatus::Skipped;
+                    }
+                }
+            }
+            document.status = status;
+            document.current_pipeline = None;
+            document.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        });
+    }
+}
+
+fn pipeline_mut<'a>(
+    document: &'a mut ProgressDocument,
+    name: &str,
+) -> Option<&'a mut PipelineProgress> {
+    document
+        .pipelines
+        .iter_mut()
+        .find(|pipeline| pipeline.name == name)
+}
+
+/// 讀取進度檔（供外部工具與續跑邏輯使用）
+pub fn read_progress_file(path: &Path) -> crate::utils::error::Result<ProgressDocument> {
+    Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+}
+
+fn write_atomic(path: &Path, document: &ProgressDocument) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(document)?)?;
+    std::fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_file_tracks_stages() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("run/progress.json");
+        let progress = ProgressFile::new(&path, "exec_1", &["users", "orders"]);
+
+        progress.begin_pipeline("users");
+        progress.stage_finished("users", PipelineStage::Extract, 10);
+        let document = read_progress_file(&path).unwrap();
+        assert_eq!(document.current_pipeline.as_deref(), Some("users"));
+        assert_eq!(document.pipelines[0].stage, Some(PipelineStage::Transform));
+        assert_eq!(document.pipelines[0].records_extracted, Some(10));
+        assert_eq!(
+            document.pipelines[1].status,
+            PipelineProgressStatus::Pending
+        );
+
+        progress.stage_finished("users", PipelineStage::Transform, 8);
+        progress.stage_finished("users", PipelineStage::Load, 8);
+        progress.finish_pipeline("users", PipelineProgressStatus::Completed);
+        progress.begin_pipeline("orders");
+        progress.fail_pipeline(
+            "orders",
+            "GET https://api.example.com/orders?token=abc failed",
+        );
+        progress.finish(SequenceStatus::Failed);
+
+        let document = read_progress_file(&path).unwrap();
+        assert_eq!(document.status, SequenceStatus::Failed);
+        assert!(document.finished_at.is_some());
+        assert_eq!(document.pipelines[0].records_loaded, Some(8));
+        let orders = &document.pipelines[1];
+        assert_eq!(orders.status, PipelineProgressStatus::Failed);
+        assert_eq!(orders.stage, Some(PipelineStage::Extract));
+        assert!(orders.error.as_deref().unwrap().contains("token=***"));
+        assert!(!temp_dir.path().join("run/progress.json.tmp").exists());
+    }
+}
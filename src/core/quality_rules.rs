@@ -0,0 +1,374 @@
+use crate::config::sequence_config::{QualityConfig, QualityRuleConfig};
+use crate::core::output_variables::OutputExpression;
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 品質報告預設檔名（寫入輸出檔中）
+pub const REPORT_FILE_NAME: &str = "quality_report.json";
+
+/// 品質規則支援的函式；count、sum、min、max、avg 與 outputs 相同
+pub const FUNCTIONS: [&str; 11] = [
+    "null_rate",
+    "null_count",
+    "distinct_count",
+    "duplicate_count",
+    "unique",
+    "not_null",
+    "count",
+    "sum",
+    "min",
+    "max",
+    "avg",
+];
+
+/// 規則未通過時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// 中止 Pipeline，不寫出輸出
+    Error,
+    /// 記錄警告後繼續
+    Warn,
+}
+
+impl Severity {
+    pub const SUPPORTED: [&'static str; 2] = ["error", "warn"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "error" => Ok(Self::Error),
+            "warn" | "warning" => Ok(Self::Warn),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "quality.rules.severity".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported severities: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 比較運算子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    /// 依長度排列，避免 `<=` 被解析成 `<`
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("==", Comparison::Eq),
+        ("!=", Comparison::Ne),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ];
+
+    fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Lt => value < threshold,
+            Self::Le => value <= threshold,
+            Self::Gt => value > threshold,
+            Self::Ge => value >= threshold,
+            Self::Eq => value == threshold,
+            Self::Ne => value != threshold,
+        }
+    }
+}
+
+/// 解析後的品質規則，例如 `null_rate(email) < 0.05`、`unique(id)`、`min(price) >= 0`
+#[derive(Debug, Clone)]
+pub struct QualityRule {
+    pub check: String,
+    pub name: Option<String>,
+    pub severity: Severity,
+    metric: String,
+    field: Option<String>,
+    comparison: Comparison,
+    threshold: f64,
+}
+
+impl QualityRule {
+    pub fn parse(config: &QualityRuleConfig) -> Result<Self> {
+        let check = config.check.trim();
+        let invalid = |reason: String| EtlError::InvalidConfigValueError {
+            field: "quality.rules.check".to_string(),
+            value: check.to_string(),
+            reason,
+        };
+
+        let close = check
+            .rfind(')')
+            .ok_or_else(|| invalid("Expected the form function(field) [op value]".to_string()))?;
+        let (call, condition) = (&check[..=close], check[close + 1..].trim());
+        let (function, argument) = call[..call.len() - 1]
+            .split_once('(')
+            .ok_or_else(|| invalid("Expected the form function(field) [op value]".to_string()))?;
+        let function = function.trim().to_lowercase();
+        let field = match argument.trim() {
+            "" | "*" => None,
+            field => Some(field.to_string()),
+        };
+        if !FUNCTIONS.contains(&function.as_str()) {
+            return Err(invalid(format!(
+                "Supported functions: {}",
+                FUNCTIONS.join(", ")
+            )));
+        }
+        if field.is_none() && function != "count" {
+            return Err(invalid(format!("{}() requires a field", function)));
+        }
+
+        // unique 與 not_null 是不需比較的斷言
+        let (metric, comparison, threshold) = match function.as_str() {
+            "unique" | "not_null" => {
+                if !condition.is_empty() {
+                    return Err(invalid(format!(
+                        "{}() does not take a comparison",
+                        function
+                    )));
+                }
+                let metric = if function == "unique" {
+                    "duplicate_count"
+                } else {
+                    "null_count"
+                };
+                (metric.to_string(), Comparison::Eq, 0.0)
+            }
+            _ => {
+                let (operator, comparison) = Comparison::OPERATORS
+                    .iter()
+                    .find(|(operator, _)| condition.starts_with(operator))
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            "{}() requires a comparison such as '< 0.05' or '>= 0'",
+                            function
+                        ))
+                    })?;
+                let threshold = condition[operator.len()..]
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| invalid("Comparison value must be a number".to_string()))?;
+                (function, *comparison, threshold)
+            }
+        };
+        if let "count" | "sum" | "min" | "max" | "avg" = metric.as_str() {
+            OutputExpression::parse(call)?;
+        }
+
+        Ok(Self {
+            check: check.to_string(),
+            name: config.name.clone(),
+            severity: Severity::parse(config.severity.as_deref().unwrap_or("error"))?,
+            metric,
+            field,
+            comparison,
+            threshold,
+        })
+    }
+
+    /// 計算規則的量測值；沒有可比較的值時返回 null
+    pub fn measure(&self, records: &[Record]) -> serde_json::Value {
+        let Some(field) = &self.field else {
+            return serde_json::json!(records.len());
+        };
+        let values = || {
+            records
+                .iter()
+                .filter_map(|record| record.data.get(field))
+                .filter(|value| !value.is_null())
+        };
+        let nulls = records.len() - values().count();
+        match self.metric.as_str() {
+            "null_count" => serde_json::json!(nulls),
+            "null_rate" if records.is_empty() => serde_json::json!(0.0),
+            "null_rate" => serde_json::json!(nulls as f64 / records.len() as f64),
+            "distinct_count" | "duplicate_count" => {
+                let distinct: HashSet<String> = values().map(|value| value.to_string()).collect();
+                if self.metric == "distinct_count" {
+                    serde_json::json!(distinct.len())
+                } else {
+                    serde_json::json!(values().count() - distinct.len())
+                }
+            }
+            function => OutputExpression {
+                function: function.to_string(),
+                field: Some(field.clone()),
+            }
+            .evaluate(records),
+        }
+    }
+
+    pub fn evaluate(&self, records: &[Record]) -> RuleOutcome {
+        let value = self.measure(records);
+        let number = match &value {
+            serde_json::Value::Number(n) => n.as_f64(),
+            serde_json::Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        };
+        RuleOutcome {
+            name: self.name.clone(),
+            check: self.check.clone(),
+            severity: self.severity,
+            passed: number.is_some_and(|n| self.comparison.holds(n, self.threshold)),
+            value,
+        }
+    }
+}
+
+/// 單一規則的結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleOutcome {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub check: String,
+    pub severity: Severity,
+    pub passed: bool,
+    pub value: serde_json::Value,
+}
+
+impl RuleOutcome {
+    /// 日誌與錯誤訊息中的規則描述
+    pub fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} ({}; actual {})", name, self.check, self.value),
+            None => format!("{} (actual {})", self.check, self.value),
+        }
+    }
+}
+
+/// quality_report.json 的內容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityReport {
+    pub pipeline: String,
+    pub generated_at: String,
+    pub records: usize,
+    pub passed: bool, // 沒有 error 等級的規則失敗
+    pub rules: Vec<RuleOutcome>,
+}
+
+impl QualityReport {
+    pub fn evaluate(pipeline: &str, config: &QualityConfig, records: &[Record]) -> Result<Self> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| Ok(QualityRule::parse(rule)?.evaluate(records)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            pipeline: pipeline.to_string(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            records: records.len(),
+            passed: !rules
+                .iter()
+                .any(|rule| !rule.passed && rule.severity == Severity::Error),
+            rules,
+        })
+    }
+
+    /// 指定等級中未通過的規則
+    pub fn failed(&self, severity: Severity) -> impl Iterator<Item = &RuleOutcome> {
+        self.rules
+            .iter()
+            .filter(move |rule| !rule.passed && rule.severity == severity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(check: &str, severity: Option<&str>) -> QualityRuleConfig {
+        QualityRuleConfig {
+            check: check.to_string(),
+            name: None,
+            severity: severity.map(str::to_string),
+        }
+    }
+
+    fn records() -> Vec<Record> {
+        [
+            json!({"id": 1, "email": "a@example.com", "price": 10}),
+            json!({"id": 2, "email": null, "price": "2.5"}),
+            json!({"id": 2, "price": 0}),
+            json!({"id": 4, "email": "d@example.com", "price": 7}),
+        ]
+        .into_iter()
+        .map(|data| Record {
+            data: serde_json::from_value(data).unwrap(),
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_rules_evaluate_against_records() {
+        let records = records();
+        let outcome = |check: &str| {
+            QualityRule::parse(&rule(check, None))
+                .unwrap()
+                .evaluate(&records)
+        };
+
+        let null_rate = outcome("null_rate(email) < 0.05");
+        assert_eq!(null_rate.value, json!(0.5));
+        assert!(!null_rate.passed);
+        assert!(outcome("null_rate(email) <= 0.5").passed);
+        assert!(!outcome("unique(id)").passed);
+        assert_eq!(outcome("unique(id)").value, json!(1));
+        assert!(outcome("unique(email)").passed);
+        assert!(outcome("not_null(price)").passed);
+        assert!(outcome("min(price) >= 0").passed);
+        assert!(!outcome("max(price) < 10").passed);
+        assert!(outcome("count() == 4").passed);
+        assert!(outcome("distinct_count(id) == 3").passed);
+        // 沒有可比較的值時視為未通過
+        assert!(!outcome("avg(missing) > 0").passed);
+    }
+
+    #[test]
+    fn test_report_and_invalid_rules() {
+        let config = QualityConfig {
+            rules: vec![
+                rule("unique(id)", Some("warn")),
+                rule("min(price)>=0", None),
+            ],
+            report_file: None,
+        };
+        let report = QualityReport::evaluate("products", &config, &records()).unwrap();
+        assert!(report.passed);
+        assert_eq!(report.failed(Severity::Warn).count(), 1);
+
+        let config = QualityConfig {
+            rules: vec![rule("null_rate(email) < 0.05", Some("error"))],
+            report_file: None,
+        };
+        assert!(
+            !QualityReport::evaluate("products", &config, &records())
+                .unwrap()
+                .passed
+        );
+
+        for invalid in [
+            "null_rate(email)",
+            "unique(id) > 1",
+            "median(price) > 1",
+            "min(price) >= low",
+            "null_rate() < 1",
+            "null_rate(email",
+        ] {
+            assert!(
+                QualityRule::parse(&rule(invalid, None)).is_err(),
+                "{}",
+                invalid
+            );
+        }
+        assert!(QualityRule::parse(&rule("unique(id)", Some("fatal"))).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+
+/// 記錄數不一致時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MismatchPolicy {
+    /// 記錄警告後繼續（預設）
+    #[default]
+    Warn,
+    /// 中止 Pipeline
+    Fail,
+}
+
+impl MismatchPolicy {
+    pub const SUPPORTED: [&'static str; 2] = ["warn", "fail"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "warn" => Ok(Self::Warn),
+            "fail" => Ok(Self::Fail),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "reconciliation.on_mismatch".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 兩個階段之間的記錄數差異
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Discrepancy {
+    pub from: String,
+    pub to: String,
+    /// 前一階段的筆數扣除已回報的丟棄筆數
+    pub expected: usize,
+    pub actual: usize,
+    pub missing: i64,
+}
+
+impl std::fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {}: expected {} records, got {}",
+            self.from, self.to, self.expected, self.actual
+        )
+    }
+}
+
+/// 各階段記錄數對帳；已回報的丟棄（驗證 drop/reject、null_policy 略過）不算差異
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reconciliation {
+    pub extracted: usize,
+    pub transformed: usize,
+    pub loaded: Option<usize>,
+    /// transform 階段已回報的丟棄筆數
+    pub transform_dropped: usize,
+    /// load 階段因 null_policy = "skip_record" 略過的筆數
+    pub load_skipped: usize,
+    pub tolerance: f64,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl Reconciliation {
+    pub fn new(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            ..Self::default()
+        }
+    }
+
+    /// 比較 extract 與 transform 的筆數，返回新發現的差異
+    pub fn check_transform(
+        &mut self,
+        extracted: usize,
+        transformed: usize,
+        dropped: usize,
+    ) -> Option<&Discrepancy> {
+        self.extracted = extracted;
+        self.transformed = transformed;
+        self.transform_dropped = dropped;
+        let expected = extracted.saturating_sub(dropped);
+        self.check("extract", "transform", expected, transformed)
+    }
+
+    /// 比較 transform 與 load 的筆數，返回新發現的差異
+    pub fn check_load(&mut self, loaded: usize, skipped: usize) -> Option<&Discrepancy> {
+        self.loaded = Some(loaded);
+        self.load_skipped = skipped;
+        let expected = self.transformed.saturating_sub(skipped);
+        self.check("transform", "load", expected, loaded)
+    }
+
+    fn check(
+        &mut self,
+        from: &str,
+        to: &str,
+        expected: usize,
+        actual: usize,
+    ) -> Option<&Discrepancy> {
+        let difference = expected.abs_diff(actual);
+        if difference as f64 <= expected as f64 * self.tolerance {
+            return None;
+        }
+        self.discrepancies.push(Discrepancy {
+            from: from.to_string(),
+            to: to.to_string(),
+            expected,
+            actual,
+            missing: expected as i64 - actual as i64,
+        });
+        self.discrepancies.last()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        value["passed"] = serde_json::Value::Bool(self.passed());
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reported_drops_are_not_discrepancies() {
+        let mut reconciliation = Reconciliation::new(0.0);
+        assert!(reconciliation.check_transform(10, 8, 2).is_none());
+        assert!(reconciliation.check_load(7, 1).is_none());
+        assert!(reconciliation.passed());
+        assert_eq!(reconciliation.to_value()["loaded"], 7);
+    }
+
+    #[test]
+    fn test_silent_drops_beyond_tolerance() {
+        let mut reconciliation = Reconciliation::new(0.1);
+        // 10% 以內視為一致
+        assert!(reconciliation.check_transform(100, 90, 0).is_none());
+        let discrepancy = reconciliation.check_load(80, 0).cloned().unwrap();
+        assert_eq!(discrepancy.expected, 90);
+        assert_eq!(discrepancy.missing, 10);
+        assert_eq!(
+            discrepancy.to_string(),
+            "transform -> load: expected 90 records, got 80"
+        );
+        assert!(!reconciliation.passed());
+        assert_eq!(reconciliation.to_value()["passed"], false);
+    }
+
+    #[test]
+    fn test_mismatch_policy() {
+        assert_eq!(MismatchPolicy::parse("fail").unwrap(), MismatchPolicy::Fail);
+        assert!(MismatchPolicy::parse("ignore").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::config::sequence_config::ScriptConfig;
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+
+/// 已編譯的記錄轉換腳本（Rhai）
+///
+/// 每筆記錄以物件傳入腳本的 `transform(record)`，返回的物件取代原記錄；
+/// 返回 `()` 表示略過該筆。腳本錯誤或返回其他型別時視為該筆記錄無效。
+#[cfg(feature = "scripting")]
+pub struct RecordScript {
+    engine: rhai::Engine,
+    ast: rhai::AST,
+}
+
+#[cfg(feature = "scripting")]
+impl RecordScript {
+    const ENTRY: &'static str = "transform";
+
+    pub fn compile(config: &ScriptConfig, field: &str) -> Result<Self> {
+        let source = script_source(config, field)?;
+        let mut engine = rhai::Engine::new();
+        engine.set_max_operations(config.max_operations());
+        let ast = engine
+            .compile(&source)
+            .map_err(|e| EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: config.path.clone().unwrap_or_else(|| "code".to_string()),
+                reason: format!("Script does not compile: {}", e),
+            })?;
+        if !ast
+            .iter_functions()
+            .any(|function| function.name == Self::ENTRY && function.params.len() == 1)
+        {
+            return Err(EtlError::ConfigValidationError {
+                field: field.to_string(),
+                message: format!("Script must define fn {}(record)", Self::ENTRY),
+            });
+        }
+        Ok(Self { engine, ast })
+    }
+
+    /// 返回 None 表示腳本略過此筆記錄；Err 為錯誤說明
+    pub fn apply(&self, record: &Record) -> std::result::Result<Option<Record>, String> {
+        let input = rhai::serde::to_dynamic(&record.data).map_err(|e| e.to_string())?;
+        let output: rhai::Dynamic = self
+            .engine
+            .call_fn(&mut rhai::Scope::new(), &self.ast, Self::ENTRY, (input,))
+            .map_err(|e| format!("Script error: {}", e))?;
+        if output.is_unit() {
+            return Ok(None);
+        }
+        if !output.is_map() {
+            return Err(format!(
+                "Script must return a record or (), got {}",
+                output.type_name()
+            ));
+        }
+        let data = rhai::serde::from_dynamic(&output).map_err(|e| e.to_string())?;
+        Ok(Some(Record { data }))
+    }
+}
+
+/// 未啟用 scripting 功能時無法編譯腳本，設定 transform.script 會在驗證時失敗
+#[cfg(not(feature = "scripting"))]
+pub struct RecordScript {
+    _private: (),
+}
+
+#[cfg(not(feature = "scripting"))]
+impl RecordScript {
+    pub fn compile(config: &ScriptConfig, field: &str) -> Result<Self> {
+        script_source(config, field)?;
+        Err(EtlError::ConfigValidationError {
+            field: field.to_string(),
+            message: "Record scripts require building with --features scripting".to_string(),
+        })
+    }
+
+    pub fn apply(&self, record: &Record) -> std::result::Result<Option<Record>, String> {
+        Ok(Some(record.clone()))
+    }
+}
+
+fn script_source(config: &ScriptConfig, field: &str) -> Result<String> {
+    match (&config.path, &config.code) {
+        (Some(path), None) => {
+            std::fs::read_to_string(path).map_err(|e| EtlError::InvalidConfigValueError {
+                field: format!("{}.path", field),
+                value: path.clone(),
+                reason: format!("Cannot read script: {}", e),
+            })
+        }
+        (None, Some(code)) => Ok(code.clone()),
+        _ => Err(EtlError::ConfigValidationError {
+            field: field.to_string(),
+            message: "Set exactly one of 'path' or 'code'".to_string(),
+        }),
+    }
+}
+
+#[cfg(all(test, feature = "scripting"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_script_transforms_and_drops_records() {
+        let config = ScriptConfig {
+            code: Some(
+                r#"
+                fn transform(record) {
+                    if record.status == "deleted" { return (); }
+                    record.full_name = record.first + " " + record.last;
+                    record.score = record.score * 2;
+                    record
+                }
+                "#
+                .to_string(),
+            ),
+            ..Default::default()
+        };
+        let script = RecordScript::compile(&config, "transform.script").unwrap();
+        let record = |value: serde_json::Value| Record {
+            data: serde_json::from_value(value).unwrap(),
+        };
+
+        let transformed = script
+            .apply(&record(
+                json!({"first": "Ada", "last": "Lovelace", "score": 21, "status": "active"}),
+            ))
+            .unwrap()
+            .unwrap();
+        assert_eq!(transformed.data["full_name"], "Ada Lovelace");
+        assert_eq!(transformed.data["score"], 42);
+
+        let dropped = script.apply(&record(json!({"status": "deleted"}))).unwrap();
+        assert!(dropped.is_none());
+
+        assert!(script.apply(&record(json!({"status": "active"}))).is_err());
+
+        let missing_entry = ScriptConfig {
+            code: Some("fn other(x) { x }".to_string()),
+            ..Default::default()
+        };
+        assert!(RecordScript::compile(&missing_entry, "transform.script").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::config::sequence_config::ValidationConfig;
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+
+/// 支援的欄位型別名稱
+pub const FIELD_TYPES: [&str; 7] = [
+    "string", "number", "integer", "boolean", "array", "object", "null",
+];
+
+/// 無效記錄的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidRecordPolicy {
+    /// 出現無效記錄時中止 Pipeline
+    Fail,
+    /// 丟棄無效記錄並記錄警告
+    Drop,
+    /// 將無效記錄寫入 rejects 檔
+    Reject,
+}
+
+impl InvalidRecordPolicy {
+    pub const SUPPORTED: [&'static str; 3] = ["fail", "drop", "reject"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "fail" => Ok(Self::Fail),
+            "drop" => Ok(Self::Drop),
+            "reject" => Ok(Self::Reject),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "transform.validation.on_invalid".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 檢查單筆記錄，返回所有違規描述（空表示通過）
+pub fn validate_record(record: &Record, config: &ValidationConfig) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    for field in config.required_fields.iter().flatten() {
+        match record.data.get(field) {
+            None | Some(serde_json::Value::Null) => {
+                violations.push(format!("Required field '{}' is missing", field))
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (field, expected) in config.field_types.iter().flatten() {
+        if let Some(value) = record.data.get(field) {
+            if !matches_type(value, expected) {
+                violations.push(format!(
+                    "Field '{}' expected {} but got {}",
+                    field,
+                    expected,
+                    type_name(value)
+                ));
+            }
+        }
+    }
+
+    violations
+}
+
+/// 檢查記錄數是否落在 min_records/max_records 範圍內
+pub fn validate_record_count(count: usize, config: &ValidationConfig) -> Option<String> {
+    if let Some(min) = config.min_records {
+        if count < min {
+            return Some(format!("Expected at least {} records, got {}", min, count));
+        }
+    }
+    if let Some(max) = config.max_records {
+        if count > max {
+            return Some(format!("Expected at most {} records, got {}", max, count));
+        }
+    }
+    None
+}
+
+fn matches_type(value: &serde_json::Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+pub(crate) fn type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(n) if n.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn config() -> ValidationConfig {
+        ValidationConfig {
+            required_fields: Some(vec!["id".to_string(), "title".to_string()]),
+            field_types: Some(HashMap::from([
+                ("id".to_string(), "integer".to_string()),
+                ("score".to_string(), "number".to_string()),
+            ])),
+            min_records: Some(1),
+            max_records: Some(2),
+            on_invalid: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_record() {
+        let valid = Record {
+            data: HashMap::from([
+                ("id".to_string(), json!(1)),
+                ("title".to_string(), json!("a")),
+                ("score".to_string(), json!(1.5)),
+            ]),
+        };
+        assert!(validate_record(&valid, &config()).is_empty());
+
+        let invalid = Record {
+            data: HashMap::from([
+                ("id".to_string(), json!("1")),
+                ("title".to_string(), serde_json::Value::Null),
+            ]),
+        };
+        let violations = validate_record(&invalid, &config());
+        assert_eq!(violations.len(), 2);
+        assert!(violations
+            .iter()
+            .any(|v| v == "Field 'id' expected integer but got string"));
+    }
+
+    #[test]
+    fn test_validate_record_count_and_policy() {
+        assert!(validate_record_count(0, &config()).is_some());
+        assert!(validate_record_count(2, &config()).is_none());
+        assert!(validate_record_count(3, &config()).is_some());
+
+        assert_eq!(
+            InvalidRecordPolicy::parse("reject").unwrap(),
+            InvalidRecordPolicy::Reject
+        );
+        assert!(InvalidRecordPolicy::parse("skip").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use reqwest::Response;
+
+/// 超過回應大小或筆數上限時的處理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitPolicy {
+    /// 截斷到上限並記錄警告（預設）
+    Truncate,
+    /// 以 ResponseTooLarge 中止 Pipeline
+    Fail,
+}
+
+impl LimitPolicy {
+    pub const SUPPORTED: [&'static str; 2] = ["truncate", "fail"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "truncate" => Ok(Self::Truncate),
+            "fail" => Ok(Self::Fail),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "extract.on_limit_exceeded".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 逐塊讀取回應，超過 `limit` 位元組時立即停止；返回 None 表示超過上限
+///
+/// Content-Length 已超過上限時不讀取內容。
+pub async fn read_limited_body(mut response: Response, limit: u64) -> Result<Option<Vec<u8>>> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit)
+    {
+        return Ok(None);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(body))
+}
+
+/// 套用 extract.max_records；返回被截斷的筆數
+pub fn limit_records(
+    records: &mut Vec<Record>,
+    max_records: usize,
+    policy: LimitPolicy,
+    endpoint: &str,
+) -> Result<usize> {
+    if records.len() <= max_records {
+        return Ok(0);
+    }
+    match policy {
+        LimitPolicy::Fail => Err(EtlError::ResponseTooLarge {
+            endpoint: endpoint.to_string(),
+            limit: format!("{} records (got {})", max_records, records.len()),
+        }),
+        LimitPolicy::Truncate => {
+            let truncated = records.len() - max_records;
+            records.truncate(max_records);
+            Ok(truncated)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_read_limited_body() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/items");
+            then.status(200).body("[1,2,3]");
+        });
+        let fetch = || reqwest::get(server.url("/items"));
+
+        let body = read_limited_body(fetch().await.unwrap(), 7).await.unwrap();
+        assert_eq!(body.as_deref(), Some(&b"[1,2,3]"[..]));
+        assert!(read_limited_body(fetch().await.unwrap(), 6)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_limit_records() {
+        let records = || {
+            (0..5)
+                .map(|id| Record {
+                    data: HashMap::from([("id".to_string(), json!(id))]),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut truncated = records();
+        assert_eq!(
+            limit_records(&mut truncated, 3, LimitPolicy::Truncate, "api").unwrap(),
+            2
+        );
+        assert_eq!(truncated.len(), 3);
+
+        let error = limit_records(&mut records(), 3, LimitPolicy::Fail, "api").unwrap_err();
+        assert!(matches!(error, EtlError::ResponseTooLarge { .. }));
+        assert!(LimitPolicy::parse("skip").is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use crate::utils::redact;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// 記錄欄位的預設前綴，例如 `_http_status`、`_http_etag`
+pub const DEFAULT_PREFIX: &str = "_http_";
+
+/// 回應資訊放置的位置（source.response_metadata.target）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseMetadataTarget {
+    /// 加到該回應產生的每筆記錄（預設）
+    #[default]
+    Record,
+    /// 放入 PipelineResult.metadata["responses"]，每個回應一筆
+    Metadata,
+}
+
+impl ResponseMetadataTarget {
+    pub const SUPPORTED: [&'static str; 2] = ["record", "metadata"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "record" => Ok(Self::Record),
+            "metadata" => Ok(Self::Metadata),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "source.response_metadata.target".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported targets: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 單一回應的狀態碼、選取的標頭與延遲；URL 與敏感標頭已遮蔽
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    pub url: String,
+    pub status: u16,
+    pub latency_ms: u64,
+    pub headers: BTreeMap<String, String>,
+}
+
+impl ResponseMetadata {
+    /// 在讀取回應內容之前擷取；`header_names` 不分大小寫，回應中沒有的標頭不列出
+    pub fn capture(
+        response: &reqwest::Response,
+        latency: Duration,
+        header_names: &[String],
+    ) -> Self {
+        let headers = header_names
+            .iter()
+            .filter_map(|name| {
+                let value = response.headers().get(name.as_str())?;
+                let value = if redact::is_sensitive_name(name) {
+                    redact::REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                Some((name.to_lowercase(), value))
+            })
+            .collect();
+        Self {
+            url: redact::redact_sensitive(response.url().as_str()),
+            status: response.status().as_u16(),
+            latency_ms: latency.as_millis() as u64,
+            headers,
+        }
+    }
+
+    /// 將狀態碼、延遲與標頭加到每筆記錄；標頭欄位名稱轉為小寫並以 `_` 取代 `-`
+    pub fn attach(&self, records: &mut [Record], prefix: &str) {
+        for record in records {
+            record
+                .data
+                .insert(format!("{}status", prefix), serde_json::json!(self.status));
+            record.data.insert(
+                format!("{}latency_ms", prefix),
+                serde_json::json!(self.latency_ms),
+            );
+            for (name, value) in &self.headers {
+                record.data.insert(
+                    format!("{}{}", prefix, name.replace('-', "_")),
+                    serde_json::json!(value),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach_to_records() {
+        let metadata = ResponseMetadata {
+            url: "https://api.example.com/users/1".to_string(),
+            status: 200,
+            latency_ms: 42,
+            headers: BTreeMap::from([
+                ("etag".to_string(), "\"abc\"".to_string()),
+                ("x-request-id".to_string(), "req-1".to_string()),
+            ]),
+        };
+        let mut records = vec![Record {
+            data: [("id".to_string(), serde_json::json!(1))].into(),
+        }];
+
+        metadata.attach(&mut records, DEFAULT_PREFIX);
+        let data = &records[0].data;
+        assert_eq!(data["_http_status"], 200);
+        assert_eq!(data["_http_latency_ms"], 42);
+        assert_eq!(data["_http_etag"], "\"abc\"");
+        assert_eq!(data["_http_x_request_id"], "req-1");
+        assert_eq!(data["id"], 1);
+    }
+
+    #[test]
+    fn test_target_parse() {
+        assert_eq!(
+            ResponseMetadataTarget::parse("metadata").unwrap(),
+            ResponseMetadataTarget::Metadata
+        );
+        assert!(ResponseMetadataTarget::parse("headers").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::config::sequence_config::SequenceConfig;
+use crate::core::sequence_state::{SequenceState, SequenceStateStore};
+use crate::utils::error::Result;
+use crate::utils::redact;
+use std::path::PathBuf;
+
+/// 失敗前已完成的 Pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedPipeline {
+    pub name: String,
+    pub records: usize,
+    pub output_path: String,
+}
+
+/// 序列失敗後給值班人員的續跑報告：哪些已完成、哪些輸出可用、如何續跑、失敗原因
+#[derive(Debug, Clone)]
+pub struct ResumeReport {
+    pub execution_id: String,
+    pub completed: Vec<CompletedPipeline>,
+    pub failed_pipeline: Option<String>,
+    /// 失敗 Pipeline 的輸出位置，內容可能不完整
+    pub partial_output: Option<String>,
+    /// 尚未執行的 Pipeline（依執行順序）
+    pub pending: Vec<String>,
+    /// 已遮蔽憑證的失敗原因
+    pub error: String,
+    pub resume_command: String,
+    pub generated_at: String,
+}
+
+impl ResumeReport {
+    pub fn build(
+        state: &SequenceState,
+        config: &SequenceConfig,
+        error: &str,
+        resume_command: String,
+    ) -> Self {
+        let completed = state
+            .completed_pipelines
+            .iter()
+            .map(|name| {
+                let result = state.context.get_result_by_name(name);
+                CompletedPipeline {
+                    name: name.clone(),
+                    records: result.map(|r| state.context.record_count(r)).unwrap_or(0),
+                    output_path: result.map(|r| r.output_path.clone()).unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let partial_output = state
+            .failed_pipeline
+            .as_deref()
+            .and_then(|name| config.get_pipeline(name))
+            .map(|pipeline| pipeline.load.output_path.clone());
+
+        let pending = config
+            .get_enabled_pipelines()
+            .into_iter()
+            .map(|pipeline| pipeline.name.clone())
+            .filter(|name| {
+                !state.is_completed(name) && state.failed_pipeline.as_ref() != Some(name)
+            })
+            .collect();
+
+        Self {
+            execution_id: state.execution_id.clone(),
+            completed,
+            failed_pipeline: state.failed_pipeline.clone(),
+            partial_output,
+            pending,
+            error: redact::redact(error, &config.sensitive_values()),
+            resume_command,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// 純文字報告，同時用於終端輸出與寫檔
+    pub fn render(&self) -> String {
+        let mut lines = vec![
+            format!("Resume report for execution {}", self.execution_id),
+            format!("Generated at: {}", self.generated_at),
+            String::new(),
+        ];
+
+        lines.push(format!(
+            "Failed pipeline: {}",
+            self.failed_pipeline.as_deref().unwrap_or("(unknown)")
+        ));
+        lines.push(format!("Error: {}", self.error));
+        lines.push(String::new());
+
+        if self.completed.is_empty() {
+            lines.push("Completed pipelines: none".to_string());
+        } else {
+            lines.push("Completed pipelines (outputs are safe to consume):".to_string());
+            for pipeline in &self.completed {
+                lines.push(format!(
+                    "  ✅ {} - {} records -> {}",
+                    pipeline.name, pipeline.records, pipeline.output_path
+                ));
+            }
+        }
+        if let (Some(failed), Some(output)) = (&self.failed_pipeline, &self.partial_output) {
+            lines.push(format!(
+                "Do NOT consume output of '{}' under {} (may be partial)",
+                failed, output
+            ));
+        }
+        if !self.pending.is_empty() {
+            lines.push(format!("Not yet run: {}", self.pending.join(", ")));
+        }
+
+        lines.push(String::new());
+        lines.push("Next step: fix the cause above, then resume with:".to_string());
+        lines.push(format!("  {}", self.resume_command));
+        lines.join("\n") + "\n"
+    }
+
+    /// 寫入狀態目錄，返回報告路徑
+    pub fn write(&self, store: &SequenceStateStore) -> Result<PathBuf> {
+        let path = store.report_path(&self.execution_id);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, self.render())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::pipeline_sequence::{PipelineContext, PipelineResult};
+    use crate::core::sequence_state::SequenceStatus;
+    use std::collections::HashMap;
+
+    fn config() -> SequenceConfig {
+        let pipeline = |name: &str| {
+            format!(
+                r#"
+[[pipelines]]
+name = "{name}"
+
+[pipelines.source]
+type = "api"
+endpoint = "https://api.example.com/{name}"
+headers = {{ "X-Api-Key" = "k-123456" }}
+
+[pipelines.extract]
+
+[pipelines.transform]
+
+[pipelines.load]
+output_path = "./output/{name}"
+output_formats = ["json"]
+"#
+            )
+        };
+        SequenceConfig::from_toml_str(&format!(
+            r#"
+[sequence]
+name = "report"
+description = "Resume report"
+version = "1.0.0"
+execution_order = ["users", "orders", "invoices"]
+{}{}{}"#,
+            pipeline("users"),
+            pipeline("orders"),
+            pipeline("invoices")
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_report_lists_completed_failed_and_pending() {
+        let mut context = PipelineContext::new("run_42".to_string());
+        context.add_result(PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: Vec::new().into(),
+            output_path: "./output/users/users_output.zip".to_string(),
+            duration: std::time::Duration::ZERO,
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+        let mut state = SequenceState::new(context);
+        state.completed_pipelines.push("users".to_string());
+        state.status = SequenceStatus::Failed;
+        state.failed_pipeline = Some("orders".to_string());
+
+        let report = ResumeReport::build(
+            &state,
+            &config(),
+            "API request failed with status: 500 (GET https://api.example.com/orders?key=k-123456)",
+            "sequence_etl --config c.toml --resume run_42".to_string(),
+        );
+        assert_eq!(report.pending, vec!["invoices".to_string()]);
+        assert_eq!(report.partial_output.as_deref(), Some("./output/orders"));
+
+        let text = report.render();
+        assert!(text.contains("users - 0 records -> ./output/users/users_output.zip"));
+        assert!(text.contains("--resume run_42"));
+        assert!(!text.contains("k-123456"));
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = report
+            .write(&SequenceStateStore::new(temp_dir.path()))
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), text);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::core::pipeline_sequence::PipelineResult;
+use crate::core::sequence_state::SequenceState;
+use crate::utils::error::{ErrorContext, EtlError, Result};
+use crate::utils::redact;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 執行結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Succeeded,
+    /// 成功，但有 Pipeline 接近截止時間而將部分參數化呼叫留待下次執行
+    Deferred,
+    Failed,
+    /// 收到 SIGINT/SIGTERM 後停止
+    Interrupted,
+}
+
+/// 失敗原因（已遮蔽憑證）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunError {
+    pub message: String,
+    pub category: String,
+    /// 穩定的分類代碼，例如 network、configuration
+    #[serde(default)]
+    pub code: String,
+    pub severity: String,
+    pub recovery_suggestion: String,
+    /// 失敗的 Pipeline、階段與端點
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<ErrorContext>,
+}
+
+impl RunError {
+    pub fn from_error(error: &EtlError) -> Self {
+        Self {
+            message: redact::redact_sensitive(&error.to_string()),
+            category: format!("{:?}", error.category()),
+            code: error.category().code().to_string(),
+            severity: format!("{:?}", error.severity()),
+            recovery_suggestion: error.recovery_suggestion().to_string(),
+            context: error.context().cloned(),
+        }
+    }
+}
+
+/// 單一 Pipeline 的執行結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineRunReport {
+    pub name: String,
+    pub status: String, // "completed"、"deferred"、"skipped"、"failed" 或 "not_run"
+    pub records: Option<usize>, // 單一 Pipeline 的 CLI 不回報筆數
+    pub duration_ms: Option<u64>,
+    pub output_path: Option<String>,
+    pub skip_reason: Option<String>,
+    pub warnings: usize,
+    /// 留待下次執行的參數化呼叫數（.checkpoints/{pipeline}_remaining.json）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deferred_calls: Option<usize>,
+}
+
+impl PipelineRunReport {
+    fn from_result(result: &PipelineResult, records: usize) -> Self {
+        let deferred_calls = result
+            .metadata
+            .get("deadline_stop")
+            .and_then(|stop| stop["remaining_calls"].as_u64())
+            .map(|calls| calls as usize);
+        Self {
+            name: result.pipeline_name.clone(),
+            status: if result.is_skipped() {
+                "skipped"
+            } else if deferred_calls.is_some() {
+                "deferred"
+            } else {
+                "completed"
+            }
+            .to_string(),
+            records: Some(records),
+            duration_ms: Some(result.duration.as_millis() as u64),
+            output_path: Some(result.output_path.clone()).filter(|path| !path.is_empty()),
+            skip_reason: result.skipped.as_ref().map(ToString::to_string),
+            warnings: result.warnings.iter().map(|warning| warning.count).sum(),
+            deferred_calls,
+        }
+    }
+
+    fn without_result(name: &str, status: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: status.to_string(),
+            records: None,
+            duration_ms: None,
+            output_path: None,
+            skip_reason: None,
+            warnings: 0,
+            deferred_calls: None,
+        }
+    }
+}
+
+/// 機器可讀的執行報告（run_report.json），供 Airflow 等排程器判斷執行結果而不必解析日誌
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    pub execution_id: String,
+    pub sequence: Option<String>,
+    pub status: RunStatus,
+    pub exit_code: i32,
+    pub error: Option<RunError>,
+    pub pipelines: Vec<PipelineRunReport>,
+    pub output_paths: Vec<String>,
+    pub duration_ms: u64,
+    pub finished_at: String,
+}
+
+impl RunReport {
+    fn new(execution_id: &str, status: RunStatus, exit_code: i32) -> Self {
+        Self {
+            execution_id: execution_id.to_string(),
+            sequence: None,
+            status,
+            exit_code,
+            error: None,
+            pipelines: Vec::new(),
+            output_paths: Vec::new(),
+            duration_ms: 0,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// 成功的執行，依序列出各 Pipeline 的結果；有呼叫留待下次執行時狀態為 Deferred
+    pub fn succeeded(execution_id: &str, results: &[PipelineResult]) -> Self {
+        let mut report = Self::new(execution_id, RunStatus::Succeeded, 0);
+        report.pipelines = results
+            .iter()
+            .map(|result| PipelineRunReport::from_result(result, result.record_count()))
+            .collect();
+        if report
+            .pipelines
+            .iter()
+            .any(|pipeline| pipeline.deferred_calls.is_some())
+        {
+            report.status = RunStatus::Deferred;
+        }
+        report.collect_output_paths();
+        report
+    }
+
+    /// 失敗或中斷的執行；Pipeline 結果取自序列狀態，`pipeline_order` 中未完成的標記為 not_run
+    pub fn failed(
+        execution_id: &str,
+        error: &EtlError,
+        exit_code: i32,
+        state: Option<&SequenceState>,
+        pipeline_order: &[String],
+    ) -> Self {
+        let status = match error.root() {
+            EtlError::Interrupted { .. } => RunStatus::Interrupted,
+            _ => RunStatus::Failed,
+        };
+        let mut report = Self::new(execution_id, status, exit_code);
+        report.error = Some(RunError::from_error(error));
+        report.pipelines = pipeline_order
+            .iter()
+            .map(|name| {
+                let Some(state) = state else {
+                    return PipelineRunReport::without_result(name, "not_run");
+                };
+                if state.failed_pipeline.as_ref() == Some(name) {
+                    return PipelineRunReport::without_result(name, "failed");
+                }
+                match state
+                    .context
+                    .get_result_by_name(name)
+                    .filter(|_| state.is_completed(name))
+                {
+                    Some(result) => {
+                        PipelineRunReport::from_result(result, state.context.record_count(result))
+                    }
+                    None => PipelineRunReport::without_result(name, "not_run"),
+                }
+            })
+            .collect();
+        report.collect_output_paths();
+        report
+    }
+
+    /// 單一 Pipeline 的執行（samll-etl CLI）；`outcome` 成功時為輸出路徑
+    pub fn for_pipeline(
+        execution_id: &str,
+        name: &str,
+        outcome: std::result::Result<&str, &EtlError>,
+        exit_code: i32,
+    ) -> Self {
+        let mut pipeline = PipelineRunReport::without_result(name, "completed");
+        let mut report = match outcome {
+            Ok(output_path) => {
+                pipeline.output_path = Some(output_path.to_string());
+                Self::new(execution_id, RunStatus::Succeeded, exit_code)
+            }
+            Err(error) => {
+                pipeline.status = "failed".to_string();
+                let mut report = Self::new(execution_id, RunStatus::Failed, exit_code);
+                report.error = Some(RunError::from_error(error));
+                report
+            }
+        };
+        report.pipelines.push(pipeline);
+        report.collect_output_paths();
+        report
+    }
+
+    pub fn with_sequence(mut self, name: impl Into<String>) -> Self {
+        self.sequence = Some(name.into());
+        self
+    }
+
+    pub fn with_duration(mut self, duration: std::time::Duration) -> Self {
+        self.duration_ms = duration.as_millis() as u64;
+        self
+    }
+
+    fn collect_output_paths(&mut self) {
+        self.output_paths = self
+            .pipelines
+            .iter()
+            .filter(|pipeline| ["completed", "deferred"].contains(&pipeline.status.as_str()))
+            .filter_map(|pipeline| pipeline.output_path.clone())
+            .collect();
+    }
+
+    /// 寫入暫存檔後改名，排程器不會讀到寫到一半的報告
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::pipeline_sequence::PipelineContext;
+    use crate::core::Record;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn result(name: &str, output_path: &str) -> PipelineResult {
+        PipelineResult {
+            pipeline_name: name.to_string(),
+            records: vec![Record {
+                data: HashMap::new(),
+            }]
+            .into(),
+            output_path: output_path.to_string(),
+            duration: Duration::from_millis(1500),
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        }
+    }
+
+    #[test]
+    fn test_failed_report_from_state() {
+        let mut context = PipelineContext::new("run1".to_string());
+        context.add_result(result("users", "out/users.zip"));
+        let mut state = SequenceState::new(context);
+        state.completed_pipelines.push("users".to_string());
+        state.failed_pipeline = Some("posts".to_string());
+
+        let error = EtlError::ProcessingError {
+            message: "boom".to_string(),
+        }
+        .at_stage("transform")
+        .in_pipeline("posts");
+        let order = ["users", "posts", "comments"].map(String::from);
+        let report = RunReport::failed("run1", &error, 1, Some(&state), &order)
+            .with_sequence("blog")
+            .with_duration(Duration::from_secs(2));
+
+        assert_eq!(report.status, RunStatus::Failed);
+        assert_eq!(report.exit_code, 1);
+        let run_error = report.error.as_ref().unwrap();
+        assert_eq!(run_error.category, "DataProcessing");
+        assert_eq!(run_error.code, "data_processing");
+        let context = run_error.context.as_ref().unwrap();
+        assert_eq!(context.pipeline.as_deref(), Some("posts"));
+        assert_eq!(context.stage.as_deref(), Some("transform"));
+        let statuses: Vec<&str> = report.pipelines.iter().map(|p| p.status.as_str()).collect();
+        assert_eq!(statuses, ["completed", "failed", "not_run"]);
+        assert_eq!(report.pipelines[0].records, Some(1));
+        assert_eq!(report.output_paths, ["out/users.zip"]);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("reports/run_report.json");
+        report.write(&path).unwrap();
+        let written: RunReport = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, report);
+
+        let succeeded = RunReport::succeeded("run2", &[result("users", "out/users.zip")]);
+        assert_eq!(succeeded.status, RunStatus::Succeeded);
+        assert_eq!(succeeded.pipelines[0].duration_ms, Some(1500));
+    }
+
+    #[test]
+    fn test_deferred_calls_are_reported() {
+        let mut orders = result("orders", "out/orders.zip");
+        orders.metadata.insert(
+            "deadline_stop".to_string(),
+            serde_json::json!({ "completed_calls": 1, "remaining_calls": 2 }),
+        );
+        let report = RunReport::succeeded("run1", &[result("users", "out/users.zip"), orders]);
+        assert_eq!(report.status, RunStatus::Deferred);
+        assert_eq!(report.exit_code, 0);
+        assert_eq!(report.pipelines[0].status, "completed");
+        assert_eq!(report.pipelines[1].status, "deferred");
+        assert_eq!(report.pipelines[1].deferred_calls, Some(2));
+        assert_eq!(report.output_paths, ["out/users.zip", "out/orders.zip"]);
+    }
+}
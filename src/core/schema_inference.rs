@@ -0,0 +1,159 @@
+use crate::core::record_validation::type_name;
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 輸出檔中的 schema 檔名
+pub const SCHEMA_FILE_NAME: &str = "schema.json";
+
+/// 由輸出記錄推斷的 schema，欄位依名稱排序
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InferredSchema {
+    pub fields: Vec<SchemaField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String, // record_validation 的型別名稱；integer 與 number 混合為 number，其他混合為 "mixed"
+    pub nullable: bool, // 有記錄的值為 null 或缺少此欄位
+    #[serde(default)]
+    pub example: serde_json::Value, // 第一個非 null 的值
+}
+
+impl InferredSchema {
+    pub fn infer(records: &[Record]) -> Self {
+        let mut types: BTreeMap<&str, BTreeSet<&'static str>> = BTreeMap::new();
+        let mut present: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut examples: BTreeMap<&str, &serde_json::Value> = BTreeMap::new();
+        let mut nulls: BTreeSet<&str> = BTreeSet::new();
+        for record in records {
+            for (name, value) in &record.data {
+                *present.entry(name).or_default() += 1;
+                let field_types = types.entry(name).or_default();
+                if value.is_null() {
+                    nulls.insert(name);
+                } else {
+                    field_types.insert(type_name(value));
+                    examples.entry(name).or_insert(value);
+                }
+            }
+        }
+
+        let fields = types
+            .into_iter()
+            .map(|(name, field_types)| SchemaField {
+                name: name.to_string(),
+                field_type: merge_types(&field_types).to_string(),
+                nullable: nulls.contains(name) || present[name] < records.len(),
+                example: examples.get(name).map(|v| (*v).clone()).unwrap_or_default(),
+            })
+            .collect();
+        Self { fields }
+    }
+
+    /// 與預期的 schema 比較：缺少或新增的欄位、型別改變、原本不可為 null 的欄位出現 null
+    pub fn drift(&self, expected: &InferredSchema) -> Vec<String> {
+        let actual: BTreeMap<&str, &SchemaField> =
+            self.fields.iter().map(|f| (f.name.as_str(), f)).collect();
+        let mut changes = Vec::new();
+        for field in &expected.fields {
+            match actual.get(field.name.as_str()) {
+                None => changes.push(format!("Field '{}' is missing", field.name)),
+                Some(found) => {
+                    // 全為 null 時無法判斷型別，不視為漂移
+                    if found.field_type != field.field_type && found.field_type != "null" {
+                        changes.push(format!(
+                            "Field '{}' changed type from {} to {}",
+                            field.name, field.field_type, found.field_type
+                        ));
+                    }
+                    if found.nullable && !field.nullable {
+                        changes.push(format!("Field '{}' became nullable", field.name));
+                    }
+                }
+            }
+        }
+        let expected_names: BTreeSet<&str> =
+            expected.fields.iter().map(|f| f.name.as_str()).collect();
+        for field in &self.fields {
+            if !expected_names.contains(field.name.as_str()) {
+                changes.push(format!("Unexpected field '{}'", field.name));
+            }
+        }
+        changes
+    }
+
+    /// 讀取 load.expected_schema 指定的 schema.json
+    pub fn load(path: &str) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| EtlError::InvalidConfigValueError {
+                field: "load.expected_schema".to_string(),
+                value: path.to_string(),
+                reason: format!("Failed to read schema file: {}", e),
+            })?;
+        serde_json::from_str(&content).map_err(|e| EtlError::InvalidConfigValueError {
+            field: "load.expected_schema".to_string(),
+            value: path.to_string(),
+            reason: format!("Invalid schema file: {}", e),
+        })
+    }
+}
+
+fn merge_types(types: &BTreeSet<&'static str>) -> &'static str {
+    match types.len() {
+        0 => "null",
+        1 => types.iter().next().copied().unwrap_or("null"),
+        2 if types.contains("integer") && types.contains("number") => "number",
+        _ => "mixed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn record(fields: serde_json::Value) -> Record {
+        Record {
+            data: fields
+                .as_object()
+                .unwrap()
+                .clone()
+                .into_iter()
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_infer_and_drift() {
+        let records = [
+            record(json!({"id": 1, "score": 1, "name": "Ada", "tag": null})),
+            record(json!({"id": 2, "score": 2.5, "tag": "x"})),
+        ];
+        let schema = InferredSchema::infer(&records);
+        let field = |name: &str| schema.fields.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(field("id").field_type, "integer");
+        assert!(!field("id").nullable);
+        assert_eq!(field("score").field_type, "number");
+        assert!(field("name").nullable);
+        assert_eq!(field("tag").example, json!("x"));
+        assert!(schema.drift(&schema).is_empty());
+
+        let drifted = InferredSchema::infer(&[record(
+            json!({"id": "3", "score": null, "tag": "y", "extra": true}),
+        )]);
+        assert_eq!(
+            drifted.drift(&schema),
+            [
+                "Field 'id' changed type from integer to string",
+                "Field 'name' is missing",
+                "Field 'score' became nullable",
+                "Unexpected field 'extra'",
+            ]
+        );
+    }
+}
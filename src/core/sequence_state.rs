@@ -0,0 +1,238 @@
+use crate::core::pipeline_sequence::PipelineContext;
+use crate::utils::encryption::{open_state, seal_state, StateCipher};
+use crate::utils::error::{EtlError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 預設的序列狀態目錄
+pub const DEFAULT_STATE_DIR: &str = ".sequence_state";
+
+/// 未指定狀態目錄時擷取結果快取的目錄
+pub fn default_cache_dir() -> PathBuf {
+    Path::new(DEFAULT_STATE_DIR).join("cache")
+}
+
+/// 執行 ID 會成為狀態目錄下的檔名，不可為空白，也不可含路徑分隔符或 ".."
+pub fn validate_execution_id(execution_id: &str) -> Result<()> {
+    if execution_id.is_empty()
+        || execution_id.contains(['/', '\\', '\0'])
+        || execution_id.contains("..")
+    {
+        return Err(EtlError::InvalidConfigValueError {
+            field: "execution_id".to_string(),
+            value: execution_id.to_string(),
+            reason: "Execution ID must not be empty or contain path separators or '..'".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// 序列執行狀態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceStatus {
+    Running,
+    Failed,
+    Completed,
+    /// 收到 SIGINT/SIGTERM 後停止，可用 --resume 續跑
+    Interrupted,
+}
+
+/// 序列執行的持久化快照，用於 `--resume` 從中斷處繼續
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceState {
+    pub execution_id: String,
+    pub status: SequenceStatus,
+    pub completed_pipelines: Vec<String>,
+    pub failed_pipeline: Option<String>,
+    pub context: PipelineContext,
+    #[serde(default)]
+    pub outputs: serde_json::Value, // 各 Pipeline 的輸出變數，供外部腳本讀取
+    pub updated_at: String,
+}
+
+impl SequenceState {
+    pub fn new(context: PipelineContext) -> Self {
+        Self {
+            execution_id: context.execution_id.clone(),
+            status: SequenceStatus::Running,
+            completed_pipelines: Vec::new(),
+            failed_pipeline: None,
+            context,
+            outputs: serde_json::Value::Object(Default::default()),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn is_completed(&self, pipeline_name: &str) -> bool {
+        self.completed_pipelines.iter().any(|p| p == pipeline_name)
+    }
+}
+
+/// 將序列狀態保存在本地目錄（每個 execution_id 一個檔案）
+#[derive(Debug, Clone)]
+pub struct SequenceStateStore {
+    dir: PathBuf,
+    cipher: Option<Arc<StateCipher>>,
+}
+
+impl SequenceStateStore {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            cipher: None,
+        }
+    }
+
+    pub fn with_cipher(mut self, cipher: Arc<StateCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// 狀態目錄下以執行 ID 命名的檔案；不合法的 ID 以底線取代 `/`、`\\` 與 `.`，不會指向目錄以外
+    fn file(&self, execution_id: &str, suffix: &str) -> PathBuf {
+        let name = if validate_execution_id(execution_id).is_ok() {
+            execution_id.to_string()
+        } else {
+            execution_id
+                .chars()
+                .map(|c| {
+                    if matches!(c, '/' | '\\' | '.' | '\0') {
+                        '_'
+                    } else {
+                        c
+                    }
+                })
+                .collect()
+        };
+        self.dir.join(format!("{}{}", name, suffix))
+    }
+
+    pub fn path(&self, execution_id: &str) -> PathBuf {
+        self.file(execution_id, ".json")
+    }
+
+    /// 執行期間持續更新的進度檔
+    pub fn progress_path(&self, execution_id: &str) -> PathBuf {
+        self.file(execution_id, ".progress.json")
+    }
+
+    /// 上下文暫存檔的預設目錄
+    pub fn spill_dir(&self, execution_id: &str) -> PathBuf {
+        self.file(execution_id, ".spill")
+    }
+
+    /// 擷取結果快取（extract.cache）的目錄，跨執行共用
+    pub fn cache_dir(&self) -> PathBuf {
+        self.dir.join("cache")
+    }
+
+    /// 失敗後的續跑報告（純文字，已遮蔽憑證，不加密）
+    pub fn report_path(&self, execution_id: &str) -> PathBuf {
+        self.file(execution_id, ".resume.txt")
+    }
+
+    /// 結束時寫入的機器可讀執行報告
+    pub fn run_report_path(&self, execution_id: &str) -> PathBuf {
+        self.file(execution_id, ".run_report.json")
+    }
+
+    pub fn save(&self, state: &SequenceState) -> Result<()> {
+        validate_execution_id(&state.execution_id)?;
+        let path = self.path(&state.execution_id);
+        std::fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_vec_pretty(state)?;
+        let data = seal_state(self.cipher.as_deref(), &json, &path.to_string_lossy())?;
+        std::fs::write(&path, data)?;
+        tracing::debug!("💾 Sequence state saved to {}", path.display());
+        Ok(())
+    }
+
+    pub fn load(&self, execution_id: &str) -> Result<SequenceState> {
+        validate_execution_id(execution_id)?;
+        let path = self.path(execution_id);
+        let data = std::fs::read(&path).map_err(|e| EtlError::ConfigValidationError {
+            field: "resume".to_string(),
+            message: format!(
+                "No saved state for execution '{}' at {}: {}",
+                execution_id,
+                path.display(),
+                e
+            ),
+        })?;
+        let json = open_state(self.cipher.as_deref(), &data, &path.to_string_lossy())?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::pipeline_sequence::PipelineResult;
+    use crate::core::Record;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_save_and_load_state() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cipher = Arc::new(StateCipher::from_key_material("key").unwrap());
+        let store = SequenceStateStore::new(temp_dir.path()).with_cipher(cipher);
+
+        let mut context = PipelineContext::new("run_1".to_string());
+        context.add_shared_data("token".to_string(), serde_json::json!("abc"));
+        context.add_result(PipelineResult {
+            pipeline_name: "users".to_string(),
+            records: vec![Record {
+                data: HashMap::from([("id".to_string(), serde_json::json!(1))]),
+            }]
+            .into(),
+            output_path: "out.zip".to_string(),
+            duration: std::time::Duration::from_millis(5),
+            metadata: HashMap::new(),
+            warnings: Vec::new(),
+            outputs: HashMap::new(),
+            skipped: None,
+        });
+
+        let mut state = SequenceState::new(context);
+        state.completed_pipelines.push("users".to_string());
+        store.save(&state).unwrap();
+
+        let loaded = store.load("run_1").unwrap();
+        assert!(loaded.is_completed("users"));
+        assert_eq!(loaded.context.get_shared_data("token").unwrap(), "abc");
+        assert_eq!(
+            loaded
+                .context
+                .get_pipeline_data("users")
+                .unwrap()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(store.load("missing").is_err());
+    }
+
+    #[test]
+    fn test_execution_id_cannot_escape_state_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = SequenceStateStore::new(temp_dir.path().join("state"));
+
+        for id in ["../escape", "a/b", "a\\b", "..", ""] {
+            assert!(validate_execution_id(id).is_err(), "{:?}", id);
+            assert!(store.load(id).is_err());
+            let state = SequenceState::new(PipelineContext::new(id.to_string()));
+            assert!(store.save(&state).is_err());
+            assert_eq!(
+                store.run_report_path(id).parent(),
+                Some(temp_dir.path().join("state").as_path())
+            );
+        }
+        assert!(!temp_dir.path().join("escape.json").exists());
+        assert_eq!(
+            store.path("run-2024.01"),
+            temp_dir.path().join("state/run-2024.01.json")
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::core::Storage;
+use crate::utils::error::Result;
+use std::sync::Mutex;
+
+/// 交易式寫入（load.transactional）：先寫入暫存檔，整個 load 階段成功後才一次改名為正式檔名
+///
+/// 暫存檔與目標位於同一目錄（`.{檔名}.{token}.staged`），本機與 SFTP 的改名不需跨裝置複製；
+/// S3 以複製後刪除實作。
+///
+/// 保證範圍：`commit` 成功時所有目標都是本次內容；途中失敗時已改名的目標會還原為提交前的內容
+/// （原本不存在的目標會刪除），不會留下一部分新、一部分舊的輸出。提交期間逐檔改名，
+/// 同時讀取的程式仍可能短暫看到新舊混合或缺少的檔案；還原本身也失敗時（例如存儲已無法連線）
+/// 記錄錯誤並保留備份檔（`.{檔名}.{token}.backup`）供人工處理。
+/// 以上保證的前提是底層存儲在寫入、改名或檢查存在失敗時回報錯誤，而不是當成成功或不存在。
+pub struct StagedStorage<'a, S: Storage> {
+    storage: &'a S,
+    token: String,
+    staged: Mutex<Vec<(String, String)>>, // (暫存路徑, 目標路徑)
+}
+
+impl<'a, S: Storage> StagedStorage<'a, S> {
+    pub fn new(storage: &'a S, token: &str) -> Self {
+        let token = token
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        Self {
+            storage,
+            token,
+            staged: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 目標路徑對應的暫存路徑
+    pub fn staged_path(&self, path: &str) -> String {
+        self.sibling_path(path, "staged")
+    }
+
+    /// 提交期間保存既有目標的備份路徑
+    fn backup_path(&self, path: &str) -> String {
+        self.sibling_path(path, "backup")
+    }
+
+    fn sibling_path(&self, path: &str, suffix: &str) -> String {
+        match path.rsplit_once('/') {
+            Some((parent, name)) => format!("{}/.{}.{}.{}", parent, name, self.token, suffix),
+            None => format!(".{}.{}.{}", path, self.token, suffix),
+        }
+    }
+
+    /// 已暫存的目標路徑
+    pub fn staged_targets(&self) -> Vec<String> {
+        self.staged
+            .lock()
+            .map(|staged| staged.iter().map(|(_, target)| target.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    fn is_staged(&self, path: &str) -> bool {
+        self.staged
+            .lock()
+            .map(|staged| staged.iter().any(|(_, target)| target == path))
+            .unwrap_or(false)
+    }
+
+    fn take_staged(&self) -> Vec<(String, String)> {
+        self.staged
+            .lock()
+            .map(|mut staged| std::mem::take(&mut *staged))
+            .unwrap_or_default()
+    }
+
+    /// 將所有暫存檔改名為正式檔名，返回提交的檔案數
+    ///
+    /// 先將既有目標改名為備份，再逐一提交暫存檔；任一步失敗時還原已提交的目標與備份、
+    /// 刪除暫存檔並返回錯誤。全部成功後才刪除備份。
+    pub async fn commit(self) -> Result<usize> {
+        let staged = self.take_staged();
+        let mut backups = Vec::new(); // (目標路徑, 備份路徑)
+        let mut promoted = Vec::new();
+
+        let result = async {
+            for (_, target) in &staged {
+                if self.storage.exists(target).await? {
+                    let backup = self.backup_path(target);
+                    self.storage.rename(target, &backup).await?;
+                    backups.push((target.clone(), backup));
+                }
+            }
+            for (staged_path, target) in &staged {
+                self.storage.rename(staged_path, target).await?;
+                promoted.push(target.clone());
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            self.restore(&staged, &promoted, &backups).await;
+            return Err(e);
+        }
+        for (_, backup) in &backups {
+            if let Err(e) = self.storage.remove(backup).await {
+                tracing::warn!("🧹 Failed to remove backup file {}: {}", backup, e);
+            }
+        }
+        Ok(staged.len())
+    }
+
+    /// 提交失敗：刪除已提交的新檔案與剩餘暫存檔，備份改名回原目標
+    async fn restore(
+        &self,
+        staged: &[(String, String)],
+        promoted: &[String],
+        backups: &[(String, String)],
+    ) {
+        for target in promoted {
+            if let Err(e) = self.storage.remove(target).await {
+                tracing::error!(
+                    "🧹 Failed to remove partially committed file {}: {}",
+                    target,
+                    e
+                );
+            }
+        }
+        for (staged_path, target) in staged {
+            if !promoted.contains(target) {
+                let _ = self.storage.remove(staged_path).await;
+            }
+        }
+        for (target, backup) in backups {
+            if let Err(e) = self.storage.rename(backup, target).await {
+                tracing::error!(
+                    "🧹 Failed to restore {} from backup {}: {}",
+                    target,
+                    backup,
+                    e
+                );
+            }
+        }
+    }
+
+    /// 刪除所有暫存檔，正式檔案保持不變
+    pub async fn rollback(self) {
+        for (staged_path, _) in self.take_staged() {
+            if let Err(e) = self.storage.remove(&staged_path).await {
+                tracing::warn!("🧹 Failed to remove staged file {}: {}", staged_path, e);
+            }
+        }
+    }
+}
+
+impl<S: Storage> Storage for StagedStorage<'_, S> {
+    async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        if self.is_staged(path) {
+            return self.storage.read_file(&self.staged_path(path)).await;
+        }
+        self.storage.read_file(path).await
+    }
+
+    async fn write_file(&self, path: &str, data: &[u8]) -> Result<()> {
+        let staged_path = self.staged_path(path);
+        self.storage.write_file(&staged_path, data).await?;
+        if !self.is_staged(path) {
+            if let Ok(mut staged) = self.staged.lock() {
+                staged.push((staged_path, path.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        Ok(self.is_staged(path) || self.storage.exists(path).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LocalStorage;
+    use tempfile::TempDir;
+
+    fn files(dir: &TempDir) -> Vec<String> {
+        let mut files: Vec<String> = walk(dir.path())
+            .into_iter()
+            .map(|path| {
+                path.strip_prefix(dir.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn walk(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .flat_map(|entry| {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    walk(&path)
+                } else {
+                    vec![path]
+                }
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_commit_and_rollback() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = LocalStorage::new(temp_dir.path().to_str().unwrap().to_string());
+        storage.write_file("history.csv", b"id\n1\n").await.unwrap();
+
+        // 回滾：正式檔案不變，暫存檔刪除
+        let staged = StagedStorage::new(&storage, "exec:1");
+        staged
+            .write_file("history.csv", b"id\n1\n2\n")
+            .await
+            .unwrap();
+        staged.write_file("out/data.json", b"[]").await.unwrap();
+        assert_eq!(
+            staged.read_file("history.csv").await.unwrap(),
+            b"id\n1\n2\n"
+        );
+        assert!(staged.exists("out/data.json").await.unwrap());
+        assert!(!storage.exists("out/data.json").await.unwrap());
+        assert_eq!(
+            staged.staged_path("out/data.json"),
+            "out/.data.json.exec_1.staged"
+        );
+        staged.rollback().await;
+        assert_eq!(files(&temp_dir), ["history.csv"]);
+        assert_eq!(storage.read_file("history.csv").await.unwrap(), b"id\n1\n");
+
+        // 提交：所有檔案一起出現
+        let staged = StagedStorage::new(&storage, "exec_2");
+        staged
+            .write_file("history.csv", b"id\n1\n3\n")
+            .await
+            .unwrap();
+        staged.write_file("out/data.json", b"[]").await.unwrap();
+        assert_eq!(staged.staged_targets(), ["history.csv", "out/data.json"]);
+        assert_eq!(staged.commit().await.unwrap(), 2);
+        assert_eq!(files(&temp_dir), ["history.csv", "out/data.json"]);
+        assert_eq!(
+            storage.read_file("history.csv").await.unwrap(),
+            b"id\n1\n3\n"
+        );
+    }
+
+    /// 暫存寫入或提交到指定目標時失敗的存儲
+    struct FailingStorage {
+        inner: LocalStorage,
+        fail_write: &'static str,
+        fail_target: &'static str,
+    }
+
+    impl Storage for FailingStorage {
+        async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+            self.inner.read_file(path).await
+        }
+
+        async fn write_file(&self, path: &str, data: &[u8]) -> Result<()> {
+            if !self.fail_write.is_empty() && path.contains(self.fail_write) {
+                return Err(crate::utils::error::EtlError::ProcessingError {
+                    message: format!("write to {} failed", path),
+                });
+            }
+            self.inner.write_file(path, data).await
+        }
+
+        async fn exists(&self, path: &str) -> Result<bool> {
+            self.inner.exists(path).await
+        }
+
+        async fn rename(&self, from: &str, to: &str) -> Result<()> {
+            if to == self.fail_target && from.ends_with(".staged") {
+                return Err(crate::utils::error::EtlError::ProcessingError {
+                    message: format!("rename to {} failed", to),
+                });
+            }
+            self.inner.rename(from, to).await
+        }
+
+        async fn remove(&self, path: &str) -> Result<()> {
+            self.inner.remove(path).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_commit_restores_committed_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FailingStorage {
+            inner: LocalStorage::new(temp_dir.path().to_str().unwrap().to_string()),
+            fail_write: "",
+            fail_target: "out/data.json",
+        };
+        storage.write_file("history.csv", b"id\n1\n").await.unwrap();
+        storage.write_file("out/data.json", b"[1]").await.unwrap();
+
+        let staged = StagedStorage::new(&storage, "exec_3");
+        staged
+            .write_file("history.csv", b"id\n1\n2\n")
+            .await
+            .unwrap();
+        staged.write_file("out/data.json", b"[1,2]").await.unwrap();
+        staged
+            .write_file("out/data.csv", b"id\n1\n2\n")
+            .await
+            .unwrap();
+        assert!(staged.commit().await.is_err());
+
+        // 已提交的 history.csv 還原，未提交的目標不變，也不留下暫存檔與備份
+        assert_eq!(files(&temp_dir), ["history.csv", "out/data.json"]);
+        assert_eq!(storage.read_file("history.csv").await.unwrap(), b"id\n1\n");
+        assert_eq!(storage.read_file("out/data.json").await.unwrap(), b"[1]");
+    }
+
+    #[tokio::test]
+    async fn test_failed_staged_write_keeps_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FailingStorage {
+            inner: LocalStorage::new(temp_dir.path().to_str().unwrap().to_string()),
+            fail_write: ".data.json.",
+            fail_target: "",
+        };
+        storage.write_file("history.csv", b"id\n1\n").await.unwrap();
+        storage.write_file("out/data.json", b"[1]").await.unwrap();
+
+        // 底層存儲寫入失敗時錯誤傳回呼叫端，失敗的檔案不列入提交
+        let staged = StagedStorage::new(&storage, "exec_4");
+        staged
+            .write_file("history.csv", b"id\n1\n2\n")
+            .await
+            .unwrap();
+        assert!(staged.write_file("out/data.json", b"[1,2]").await.is_err());
+        assert!(!staged.is_staged("out/data.json"));
+        staged.rollback().await;
+
+        assert_eq!(files(&temp_dir), ["history.csv", "out/data.json"]);
+        assert_eq!(storage.read_file("history.csv").await.unwrap(), b"id\n1\n");
+        assert_eq!(storage.read_file("out/data.json").await.unwrap(), b"[1]");
+    }
+}
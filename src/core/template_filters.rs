@@ -0,0 +1,217 @@
+use crate::core::field_transforms::{DATETIME_FORMATS, DATE_FORMATS};
+use crate::utils::error::{EtlError, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+/// 佔位符中的過濾器：`{{name|upper}}`、`{{date|format:%Y-%m-%d}}`、`{{id|urlencode}}`、`{{obj|json}}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateFilter {
+    Upper,
+    Lower,
+    Trim,
+    UrlEncode,
+    Json,
+    /// 將日期或時間（ISO 8601、常見日期格式或 Unix 秒數）依 chrono 格式輸出
+    Format(String),
+}
+
+impl TemplateFilter {
+    pub const SUPPORTED: [&'static str; 6] =
+        ["upper", "lower", "trim", "urlencode", "json", "format"];
+
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (name, argument) = match spec.split_once(':') {
+            Some((name, argument)) => (name.trim(), Some(argument)),
+            None => (spec.trim(), None),
+        };
+        match (name, argument) {
+            ("upper", None) => Ok(Self::Upper),
+            ("lower", None) => Ok(Self::Lower),
+            ("trim", None) => Ok(Self::Trim),
+            ("urlencode", None) => Ok(Self::UrlEncode),
+            ("json", None) => Ok(Self::Json),
+            ("format", Some(format)) if !format.is_empty() => Ok(Self::Format(format.to_string())),
+            _ => Err(EtlError::InvalidConfigValueError {
+                field: "template filter".to_string(),
+                value: spec.to_string(),
+                reason: format!(
+                    "Supported filters: {} (format requires a pattern, e.g. format:%Y-%m-%d)",
+                    Self::SUPPORTED.join(", ")
+                ),
+            }),
+        }
+    }
+
+    fn apply(&self, value: Value) -> Result<Value> {
+        Ok(match self {
+            Self::Upper => Value::String(to_text(&value).to_uppercase()),
+            Self::Lower => Value::String(to_text(&value).to_lowercase()),
+            Self::Trim => Value::String(to_text(&value).trim().to_string()),
+            Self::UrlEncode => Value::String(url_encode(&to_text(&value))),
+            Self::Json => Value::String(serde_json::to_string(&value)?),
+            Self::Format(format) => {
+                let time = parse_datetime(&value).ok_or_else(|| EtlError::ProcessingError {
+                    message: format!("Cannot format {} as a date", value),
+                })?;
+                Value::String(time.format(format).to_string())
+            }
+        })
+    }
+}
+
+/// 佔位符的鍵與過濾器，例如 `created_at|format:%Y-%m-%d|urlencode`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub key: String,
+    pub filters: Vec<TemplateFilter>,
+}
+
+impl Placeholder {
+    pub fn parse(inner: &str) -> Result<Self> {
+        let mut parts = inner.split('|');
+        let key = parts.next().unwrap_or_default().trim().to_string();
+        let filters = parts
+            .map(TemplateFilter::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { key, filters })
+    }
+
+    /// 依序套用過濾器並轉為要填入模板的文字
+    pub fn render(&self, value: &Value) -> Result<String> {
+        let mut value = value.clone();
+        for filter in &self.filters {
+            value = filter.apply(value)?;
+        }
+        Ok(to_text(&value))
+    }
+}
+
+pub fn placeholder_pattern() -> &'static regex::Regex {
+    static PATTERN: OnceLock<regex::Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| regex::Regex::new(r"\{\{([^}]+)\}\}").unwrap())
+}
+
+/// 以 `resolve` 取得每個 `{{key|filter...}}` 的值並套用過濾器；找不到值的佔位符保留原樣
+pub fn render_template<'a>(
+    template: &str,
+    resolve: impl Fn(&str) -> Option<&'a Value>,
+) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut last = 0;
+    for captures in placeholder_pattern().captures_iter(template) {
+        let whole = captures.get(0).unwrap();
+        rendered.push_str(&template[last..whole.start()]);
+        last = whole.end();
+
+        let placeholder = Placeholder::parse(&captures[1])?;
+        match resolve(&placeholder.key) {
+            Some(value) => rendered.push_str(&placeholder.render(value)?),
+            None => rendered.push_str(whole.as_str()),
+        }
+    }
+    rendered.push_str(&template[last..]);
+    Ok(rendered)
+}
+
+/// 檢查模板中的過濾器名稱與參數（設定驗證時使用）
+pub fn validate_template(template: &str, field: &str) -> Result<()> {
+    for captures in placeholder_pattern().captures_iter(template) {
+        Placeholder::parse(&captures[1]).map_err(|e| match e {
+            EtlError::InvalidConfigValueError { value, reason, .. } => {
+                EtlError::InvalidConfigValueError {
+                    field: field.to_string(),
+                    value,
+                    reason,
+                }
+            }
+            other => other,
+        })?;
+    }
+    Ok(())
+}
+
+/// 字串原樣輸出，其他值以 JSON 表示（與未使用過濾器時的替換結果相同）
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        other => serde_json::to_string(other)
+            .unwrap_or_default()
+            .trim_matches('"')
+            .to_string(),
+    }
+}
+
+/// RFC 3986 百分比編碼，只保留非保留字元（可用於路徑片段與查詢參數）
+pub(crate) fn url_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn parse_datetime(value: &Value) -> Option<NaiveDateTime> {
+    match value {
+        Value::Number(n) => n
+            .as_i64()
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+            .map(|time| time.naive_utc()),
+        Value::String(text) => {
+            let text = text.trim();
+            DateTime::parse_from_rfc3339(text)
+                .ok()
+                .map(|time| time.naive_utc())
+                .or_else(|| {
+                    DATETIME_FORMATS
+                        .iter()
+                        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                })
+                .or_else(|| {
+                    DATE_FORMATS.iter().find_map(|format| {
+                        NaiveDate::parse_from_str(text, format)
+                            .ok()
+                            .and_then(|date| date.and_hms_opt(0, 0, 0))
+                    })
+                })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_render_with_filters() {
+        let data: HashMap<String, Value> = HashMap::from([
+            ("name".to_string(), json!(" Ada Lovelace ")),
+            ("created".to_string(), json!("2024-03-05T10:20:30Z")),
+            ("tags".to_string(), json!({"a": [1, 2]})),
+        ]);
+        let rendered = render_template(
+            "/users/{{name|trim|urlencode}}?on={{created|format:%Y-%m-%d %H:%M}}&q={{name|trim|upper}}&t={{tags|json}}&x={{missing|upper}}",
+            |key| data.get(key),
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            r#"/users/Ada%20Lovelace?on=2024-03-05 10:20&q=ADA LOVELACE&t={"a":[1,2]}&x={{missing|upper}}"#
+        );
+
+        assert!(validate_template("{{name|shout}}", "source.headers.X").is_err());
+        assert!(validate_template("{{date|format:}}", "source.headers.X").is_err());
+        assert!(render_template("{{name|format:%Y}}", |key| data.get(key)).is_err());
+    }
+}
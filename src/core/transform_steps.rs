@@ -0,0 +1,140 @@
+use crate::core::Record;
+use crate::utils::error::{EtlError, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// 自訂轉換步驟：以名稱註冊後，由設定 `transform.operations.custom = ["my_step"]` 引用
+///
+/// 步驟在內建的逐筆轉換與驗證之後、彙總之前執行，一次取得整個 Pipeline 的記錄，
+/// 可修改、增加或移除記錄。閉包 `Fn(Vec<Record>) -> Result<Vec<Record>>` 也可直接註冊。
+pub trait TransformStep: Send + Sync {
+    fn apply(&self, records: Vec<Record>) -> Result<Vec<Record>>;
+}
+
+impl<F> TransformStep for F
+where
+    F: Fn(Vec<Record>) -> Result<Vec<Record>> + Send + Sync,
+{
+    fn apply(&self, records: Vec<Record>) -> Result<Vec<Record>> {
+        self(records)
+    }
+}
+
+type Registry = RwLock<HashMap<String, Arc<dyn TransformStep>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// 註冊自訂轉換步驟；同名時取代先前的步驟。須在載入並驗證設定之前呼叫
+pub fn register_transform_step(name: impl Into<String>, step: impl TransformStep + 'static) {
+    if let Ok(mut registry) = registry().write() {
+        registry.insert(name.into(), Arc::new(step));
+    }
+}
+
+/// 已註冊的步驟名稱（排序後）
+pub fn registered_transform_steps() -> Vec<String> {
+    let mut names: Vec<String> = registry()
+        .read()
+        .map(|registry| registry.keys().cloned().collect())
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// 依設定的順序取得步驟，名稱未註冊時返回設定錯誤
+pub fn resolve_transform_steps(
+    names: &[String],
+    field: &str,
+) -> Result<Vec<(String, Arc<dyn TransformStep>)>> {
+    let registry = registry().read().map_err(|_| EtlError::ProcessingError {
+        message: "Transform step registry is poisoned".to_string(),
+    })?;
+    names
+        .iter()
+        .map(|name| match registry.get(name) {
+            Some(step) => Ok((name.clone(), Arc::clone(step))),
+            None => Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: name.clone(),
+                reason: format!(
+                    "Transform step is not registered (registered: {})",
+                    match registry.len() {
+                        0 => "none".to_string(),
+                        _ => {
+                            let mut registered: Vec<&str> =
+                                registry.keys().map(String::as_str).collect();
+                            registered.sort();
+                            registered.join(", ")
+                        }
+                    }
+                ),
+            }),
+        })
+        .collect()
+}
+
+/// 依序執行步驟；步驟失敗時以 TransformationError 標示是哪一個步驟
+pub fn apply_transform_steps(
+    steps: &[(String, Arc<dyn TransformStep>)],
+    mut records: Vec<Record>,
+) -> Result<Vec<Record>> {
+    for (name, step) in steps {
+        records = step
+            .apply(records)
+            .map_err(|e| EtlError::TransformationError {
+                stage: format!("custom step '{}'", name),
+                details: e.to_string(),
+            })?;
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_registered_steps_run_in_order() {
+        register_transform_step("test_upper_name", |records: Vec<Record>| {
+            Ok(records
+                .into_iter()
+                .map(|mut record| {
+                    if let Some(name) = record.data.get("name").and_then(|v| v.as_str()) {
+                        let upper = name.to_uppercase();
+                        record.data.insert("name".to_string(), json!(upper));
+                    }
+                    record
+                })
+                .collect())
+        });
+        register_transform_step("test_drop_empty", |records: Vec<Record>| {
+            Ok(records
+                .into_iter()
+                .filter(|record| record.data.get("name") != Some(&json!("")))
+                .collect())
+        });
+
+        let names = vec!["test_upper_name".to_string(), "test_drop_empty".to_string()];
+        let steps = resolve_transform_steps(&names, "transform.operations.custom").unwrap();
+        let records = vec![
+            Record {
+                data: HashMap::from([("name".to_string(), json!("amy"))]),
+            },
+            Record {
+                data: HashMap::from([("name".to_string(), json!(""))]),
+            },
+        ];
+        let result = apply_transform_steps(&steps, records).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].data["name"], "AMY");
+
+        assert!(
+            resolve_transform_steps(&["missing".to_string()], "transform.operations.custom")
+                .is_err()
+        );
+    }
+}
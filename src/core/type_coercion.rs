@@ -0,0 +1,105 @@
+use crate::core::field_transforms::{cast_value, CastType};
+use crate::utils::error::{EtlError, Result};
+use serde_json::Value;
+
+/// 欄位映射型別轉換失敗時的處理方式（field_mapping 的 on_error）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoercionErrorPolicy {
+    /// 改為 null 並記錄警告（預設）
+    #[default]
+    Null,
+    /// 保留原始值並記錄警告
+    Keep,
+    /// 中止 Pipeline
+    Fail,
+}
+
+impl CoercionErrorPolicy {
+    pub const SUPPORTED: [&'static str; 3] = ["null", "keep", "fail"];
+
+    pub fn parse(value: &str, field: &str) -> Result<Self> {
+        match value {
+            "null" => Ok(Self::Null),
+            "keep" => Ok(Self::Keep),
+            "fail" => Ok(Self::Fail),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: field.to_string(),
+                value: other.to_string(),
+                reason: format!("Supported policies: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 寬鬆轉型：空字串視為 null，數字字串允許千分位逗號、底線與 "42.0" 這類整數值的小數
+///
+/// 陣列（例如 `items[*].price` 取出的值）逐一轉型；無法轉型時返回 None。
+pub fn coerce(value: &Value, cast: CastType, date_format: Option<&str>) -> Option<Value> {
+    match value {
+        Value::Null => Some(Value::Null),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| coerce(item, cast, date_format))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        Value::String(text) if text.trim().is_empty() && cast != CastType::String => {
+            Some(Value::Null)
+        }
+        Value::String(text) if matches!(cast, CastType::Int | CastType::Float) => {
+            let digits: String = text
+                .trim()
+                .chars()
+                .filter(|c| *c != ',' && *c != '_')
+                .collect();
+            cast_value(&Value::String(digits.clone()), cast, None).or_else(|| {
+                // 整數欄位接受小數部分為零的值，例如 "42.0"
+                let number = digits.parse::<f64>().ok()?;
+                cast_value(&serde_json::Number::from_f64(number)?.into(), cast, None)
+            })
+        }
+        other => cast_value(other, cast, date_format),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lenient_coercion() {
+        assert_eq!(
+            coerce(&json!(" 1,234 "), CastType::Int, None),
+            Some(json!(1234))
+        );
+        assert_eq!(coerce(&json!("42.0"), CastType::Int, None), Some(json!(42)));
+        assert_eq!(coerce(&json!("42.5"), CastType::Int, None), None);
+        assert_eq!(
+            coerce(&json!("1_000.5"), CastType::Float, None),
+            Some(json!(1000.5))
+        );
+        assert_eq!(coerce(&json!(""), CastType::Int, None), Some(Value::Null));
+        assert_eq!(
+            coerce(&json!("yes"), CastType::Bool, None),
+            Some(json!(true))
+        );
+        assert_eq!(
+            coerce(&json!(["1", "2"]), CastType::Int, None),
+            Some(json!([1, 2]))
+        );
+        assert_eq!(coerce(&json!(["1", "x"]), CastType::Int, None), None);
+        assert_eq!(
+            coerce(&json!("15/03/2024"), CastType::Date, Some("%d/%m/%Y")),
+            Some(json!("2024-03-15"))
+        );
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        assert_eq!(
+            CoercionErrorPolicy::parse("keep", "on_error").unwrap(),
+            CoercionErrorPolicy::Keep
+        );
+        assert!(CoercionErrorPolicy::parse("drop", "on_error").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// 警告代碼，供呼叫端以程式判斷
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningCode {
+    UnresolvedTemplate,
+    MissingField,
+    UnsupportedOutputFormat,
+    UnsupportedHttpMethod,
+    IgnoredFields,
+    DeadlineStop,
+    LookupMiss,
+    LookupFieldCollision,
+    InvalidRecord,
+    RecordCountOutOfRange,
+    SharedDataConflict,
+    ResponseLimitExceeded,
+    PaginationStopped,
+    RecordCallFailed,
+    NullRecordsSkipped,
+    Interrupted,
+    QualityRuleFailed,
+    RecordCountMismatch,
+    TypeCoercionFailed,
+    UnsaltedHash,
+}
+
+/// Pipeline 執行期間的結構化警告，相同代碼與訊息會合併計數
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warning {
+    pub code: WarningCode,
+    pub message: String,
+    pub count: usize,
+}
+
+/// 收集警告（可在 &self 方法中使用）
+#[derive(Debug, Default)]
+pub struct WarningCollector {
+    warnings: Mutex<Vec<Warning>>,
+}
+
+impl WarningCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 記錄警告，已存在相同代碼與訊息時只增加計數
+    pub fn add(&self, code: WarningCode, message: impl Into<String>) {
+        let message = message.into();
+        if let Ok(mut warnings) = self.warnings.lock() {
+            match warnings
+                .iter_mut()
+                .find(|w| w.code == code && w.message == message)
+            {
+                Some(existing) => existing.count += 1,
+                None => warnings.push(Warning {
+                    code,
+                    message,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    /// 取出並清空目前收集的警告
+    pub fn take(&self) -> Vec<Warning> {
+        self.warnings
+            .lock()
+            .map(|mut warnings| std::mem::take(&mut *warnings))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector_merges_duplicates() {
+        let collector = WarningCollector::new();
+        collector.add(WarningCode::MissingField, "Field 'email' not found");
+        collector.add(WarningCode::MissingField, "Field 'email' not found");
+        collector.add(WarningCode::UnsupportedOutputFormat, "parquet");
+
+        let warnings = collector.take();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].count, 2);
+        assert!(collector.take().is_empty());
+    }
+
+    #[test]
+    fn test_warning_code_serialization() {
+        assert_eq!(
+            serde_json::to_value(WarningCode::UnresolvedTemplate).unwrap(),
+            "unresolved_template"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::core::Storage;
+use crate::utils::error::{EtlError, Result};
+
+/// 版本模式最多嘗試的序號，避免存儲異常時無限迴圈
+const MAX_VERSIONS: u32 = 10_000;
+
+/// 輸出檔已存在時的處理方式（load.write_mode）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// 覆寫既有輸出（預設）
+    #[default]
+    Overwrite,
+    /// 輸出已存在時在擷取前失敗
+    ErrorIfExists,
+    /// 檔名加上遞增序號：output.zip、output_v2.zip、output_v3.zip…
+    Version,
+}
+
+impl WriteMode {
+    pub const SUPPORTED: [&'static str; 3] = ["overwrite", "error_if_exists", "version"];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "overwrite" => Ok(Self::Overwrite),
+            "error_if_exists" => Ok(Self::ErrorIfExists),
+            "version" => Ok(Self::Version),
+            other => Err(EtlError::InvalidConfigValueError {
+                field: "load.write_mode".to_string(),
+                value: other.to_string(),
+                reason: format!("Supported modes: {}", Self::SUPPORTED.join(", ")),
+            }),
+        }
+    }
+}
+
+/// 在副檔名前加上版本序號；檔名不以 `extension` 結尾時加在最後
+pub fn versioned_name(filename: &str, extension: &str, version: u32) -> String {
+    match filename
+        .strip_suffix(extension)
+        .filter(|_| !extension.is_empty())
+    {
+        Some(stem) => format!("{}_v{}{}", stem, version, extension),
+        None => format!("{}_v{}", filename, version),
+    }
+}
+
+/// 依寫入模式決定實際的輸出名稱
+pub async fn resolve_output_name<S: Storage>(
+    storage: &S,
+    filename: &str,
+    extension: &str,
+    mode: WriteMode,
+) -> Result<String> {
+    match mode {
+        WriteMode::Overwrite => Ok(filename.to_string()),
+        WriteMode::ErrorIfExists => {
+            if storage.exists(filename).await? {
+                return Err(EtlError::ProcessingError {
+                    message: format!(
+                        "Output '{}' already exists (load.write_mode = \"error_if_exists\")",
+                        filename
+                    ),
+                });
+            }
+            Ok(filename.to_string())
+        }
+        WriteMode::Version => {
+            if !storage.exists(filename).await? {
+                return Ok(filename.to_string());
+            }
+            for version in 2..=MAX_VERSIONS {
+                let candidate = versioned_name(filename, extension, version);
+                if !storage.exists(&candidate).await? {
+                    return Ok(candidate);
+                }
+            }
+            Err(EtlError::ProcessingError {
+                message: format!(
+                    "No free version found for output '{}' after {} attempts",
+                    filename, MAX_VERSIONS
+                ),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::cli::LocalStorage;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_resolve_output_name() {
+        let dir = TempDir::new().unwrap();
+        let storage = LocalStorage::new(dir.path().to_string_lossy().to_string());
+        let resolve = |mode| resolve_output_name(&storage, "posts.tar.gz", ".tar.gz", mode);
+
+        assert_eq!(resolve(WriteMode::Version).await.unwrap(), "posts.tar.gz");
+        storage.write_file("posts.tar.gz", b"v1").await.unwrap();
+        assert_eq!(
+            resolve(WriteMode::Version).await.unwrap(),
+            "posts_v2.tar.gz"
+        );
+        storage.write_file("posts_v2.tar.gz", b"v2").await.unwrap();
+        assert_eq!(
+            resolve(WriteMode::Version).await.unwrap(),
+            "posts_v3.tar.gz"
+        );
+
+        assert!(resolve(WriteMode::ErrorIfExists).await.is_err());
+        assert_eq!(resolve(WriteMode::Overwrite).await.unwrap(), "posts.tar.gz");
+        assert_eq!(versioned_name("posts", "", 2), "posts_v2");
+        assert!(WriteMode::parse("append").is_err());
+    }
+}
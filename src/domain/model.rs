@@ -1,3 +1,5 @@
+use crate::utils::error::{EtlError, Result};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -6,6 +8,49 @@ pub struct Record {
     pub data: HashMap<String, serde_json::Value>,
 }
 
+impl Record {
+    /// 以 serde 將記錄轉為使用者定義的結構；錯誤訊息包含缺少或型別不符的欄位路徑
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T> {
+        let value = serde_json::Value::Object(
+            self.data
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        );
+        serde_path_to_error::deserialize(value).map_err(|e| {
+            let path = e.path().to_string();
+            let message = if path == "." {
+                e.inner().to_string()
+            } else {
+                format!("field '{}': {}", path, e.inner())
+            };
+            EtlError::DataValidationError {
+                message: format!(
+                    "Cannot deserialize record into {}: {}",
+                    std::any::type_name::<T>(),
+                    message
+                ),
+            }
+        })
+    }
+}
+
+/// 將多筆記錄轉為 `Vec<T>`，錯誤訊息標示第幾筆記錄（從 0 起算）
+pub fn deserialize_records<T: DeserializeOwned>(records: &[Record]) -> Result<Vec<T>> {
+    records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            record.deserialize_into().map_err(|e| match e {
+                EtlError::DataValidationError { message } => EtlError::DataValidationError {
+                    message: format!("Record {}: {}", index, message),
+                },
+                other => other,
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct TransformResult {
     pub processed_records: Vec<Record>,
@@ -13,3 +58,66 @@ pub struct TransformResult {
     pub tsv_output: String,
     pub intermediate_data: Vec<Record>,
 }
+
+/// Pipeline 的執行階段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Extract,
+    Transform,
+    Load,
+}
+
+impl PipelineStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Extract => "extract",
+            Self::Transform => "transform",
+            Self::Load => "load",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        id: u64,
+        name: String,
+        email: Option<String>,
+    }
+
+    fn record(fields: serde_json::Value) -> Record {
+        Record {
+            data: serde_json::from_value(fields).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_into_user_struct() {
+        let users: Vec<User> = deserialize_records(&[
+            record(json!({"id": 1, "name": "Ada", "email": "ada@example.com", "extra": true})),
+            record(json!({"id": 2, "name": "Bob"})),
+        ])
+        .unwrap();
+        assert_eq!(users[0].name, "Ada");
+        assert_eq!(users[1].email, None);
+
+        let error = deserialize_records::<User>(&[
+            record(json!({"id": 1, "name": "Ada"})),
+            record(json!({"id": 2})),
+        ])
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("Record 1") && error.contains("missing field `name`"));
+
+        let error = record(json!({"id": "x", "name": "Ada"}))
+            .deserialize_into::<User>()
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("field 'id'"), "{}", error);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::domain::model::{Record, TransformResult};
-use crate::utils::error::Result;
+use crate::utils::error::{EtlError, Result};
 use async_trait::async_trait;
 
 pub trait Storage: Send + Sync {
@@ -9,12 +9,41 @@ pub trait Storage: Send + Sync {
         path: &str,
         data: &[u8],
     ) -> impl std::future::Future<Output = Result<()>> + Send;
+
+    /// 檢查檔案或目錄是否存在；預設以能否讀取判斷
+    fn exists(&self, path: &str) -> impl std::future::Future<Output = Result<bool>> + Send {
+        async move { Ok(self.read_file(path).await.is_ok()) }
+    }
+
+    /// 將檔案改名（覆蓋既有檔案）；預設以讀取後寫入實作，原檔需另外以 `remove` 刪除
+    fn rename(&self, from: &str, to: &str) -> impl std::future::Future<Output = Result<()>> + Send {
+        async move {
+            let data = self.read_file(from).await?;
+            self.write_file(to, &data).await?;
+            self.remove(from).await
+        }
+    }
+
+    /// 刪除檔案；不存在時視為成功。預設不支援刪除，直接返回成功
+    fn remove(&self, _path: &str) -> impl std::future::Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// 列出路徑以 prefix 開頭的檔案（相對於存儲根目錄、以 `/` 分隔並排序）；預設不支援列出
+    fn list(&self, prefix: &str) -> impl std::future::Future<Output = Result<Vec<String>>> + Send {
+        let message = format!("Listing '{}' is not supported by this storage", prefix);
+        async move { Err(EtlError::ProcessingError { message }) }
+    }
 }
 
 pub trait ConfigProvider: Send + Sync {
     fn api_endpoint(&self) -> &str;
     fn output_path(&self) -> &str;
     fn lookup_files(&self) -> &[String];
+    /// lookup 檔案與記錄關聯的欄位
+    fn lookup_key(&self) -> &str {
+        "id"
+    }
     fn concurrent_requests(&self) -> usize;
 }
 
@@ -23,4 +52,9 @@ pub trait Pipeline: Send + Sync {
     async fn extract(&self) -> Result<Vec<Record>>;
     async fn transform(&self, data: Vec<Record>) -> Result<TransformResult>;
     async fn load(&self, result: TransformResult) -> Result<String>;
+
+    /// 最近一次 load 寫入存儲的位元組數，供吞吐量報告使用；未回報時為 None
+    fn bytes_written(&self) -> Option<u64> {
+        None
+    }
 }
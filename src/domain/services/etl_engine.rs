@@ -1,10 +1,16 @@
+use crate::domain::model::{deserialize_records, PipelineStage, TransformResult};
 use crate::domain::ports::Pipeline;
+use crate::domain::services::stage_runner::run_stage;
+use crate::utils::budget::ExecutionBudget;
 use crate::utils::error::Result;
+use crate::utils::metrics::ThroughputReport;
 use crate::utils::monitor::SystemMonitor;
+use serde::de::DeserializeOwned;
 
 pub struct EtlEngine<P: Pipeline> {
     pipeline: P,
     monitor: SystemMonitor,
+    budget: Option<ExecutionBudget>,
 }
 
 impl<P: Pipeline> EtlEngine<P> {
@@ -12,6 +18,7 @@ impl<P: Pipeline> EtlEngine<P> {
         Self {
             pipeline,
             monitor: SystemMonitor::new(false),
+            budget: None,
         }
     }
 
@@ -19,48 +26,113 @@ impl<P: Pipeline> EtlEngine<P> {
         Self {
             pipeline,
             monitor: SystemMonitor::new(enable_monitoring),
+            budget: None,
+        }
+    }
+
+    /// 設定執行時間預算，於每個階段記錄剩餘時間
+    pub fn with_budget(mut self, budget: ExecutionBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    fn budget_checkpoint(&self, label: &str) {
+        if let Some(budget) = &self.budget {
+            budget.checkpoint(label);
         }
     }
 
     pub async fn run(&self) -> Result<String> {
         tracing::info!("Starting ETL process");
         self.monitor.log_stats("ETL Start");
+        self.budget_checkpoint("ETL Start");
 
         // Extract
         tracing::info!("Phase 1: Extracting data");
-        let start_time = std::time::Instant::now();
-        let raw_data = self.pipeline.extract().await?;
-        let extract_duration = start_time.elapsed();
+        let (raw_data, extract) = run_stage(
+            "ETL",
+            PipelineStage::Extract,
+            Some(&self.monitor),
+            self.pipeline.extract(),
+            Vec::len,
+        )
+        .await?;
         tracing::info!(
-            "✅ Extracted {} records in {:?}",
+            "✅ Extracted {} records in {}ms",
             raw_data.len(),
-            extract_duration
+            extract.duration_ms
         );
         self.monitor.log_stats("After Extract");
+        self.budget_checkpoint("After Extract");
 
         // Transform
         tracing::info!("Phase 2: Transforming data");
-        let start_time = std::time::Instant::now();
-        let transformed_result = self.pipeline.transform(raw_data).await?;
-        let transform_duration = start_time.elapsed();
+        let (transformed_result, transform) = run_stage(
+            "ETL",
+            PipelineStage::Transform,
+            Some(&self.monitor),
+            self.pipeline.transform(raw_data),
+            |result: &TransformResult| result.processed_records.len(),
+        )
+        .await?;
         tracing::info!(
-            "✅ Transformed {} records, {} intermediate records in {:?}",
+            "✅ Transformed {} records, {} intermediate records in {}ms",
             transformed_result.processed_records.len(),
             transformed_result.intermediate_data.len(),
-            transform_duration
+            transform.duration_ms
         );
         self.monitor.log_stats("After Transform");
+        self.budget_checkpoint("After Transform");
 
         // Load
         tracing::info!("Phase 3: Loading data");
-        let start_time = std::time::Instant::now();
-        let output_path = self.pipeline.load(transformed_result).await?;
-        let load_duration = start_time.elapsed();
-        tracing::info!("✅ Data loaded to: {} in {:?}", output_path, load_duration);
+        let processed_count = transform.records;
+        let (output_path, load) = run_stage(
+            "ETL",
+            PipelineStage::Load,
+            Some(&self.monitor),
+            self.pipeline.load(transformed_result),
+            |_| processed_count,
+        )
+        .await?;
+        tracing::info!(
+            "✅ Data loaded to: {} in {}ms",
+            output_path,
+            load.duration_ms
+        );
         self.monitor.log_stats("After Load");
+        self.budget_checkpoint("After Load");
+
+        // 吞吐量報告：寫入大小由 Pipeline 回報，S3、SFTP 等遠端輸出同樣適用
+        ThroughputReport::new(extract, transform, load, self.pipeline.bytes_written()).log("ETL");
 
         tracing::info!("🎉 ETL process completed successfully");
         self.monitor.log_final_stats();
         Ok(output_path)
     }
+
+    /// 執行 extract 與 transform，將處理後的記錄轉為 `Vec<T>` 返回（不執行 load）
+    pub async fn run_typed<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        tracing::info!("Starting typed ETL process");
+        let (raw_data, _) = run_stage(
+            "ETL",
+            PipelineStage::Extract,
+            Some(&self.monitor),
+            self.pipeline.extract(),
+            Vec::len,
+        )
+        .await?;
+        tracing::info!("✅ Extracted {} records", raw_data.len());
+        let (transformed_result, _) = run_stage(
+            "ETL",
+            PipelineStage::Transform,
+            Some(&self.monitor),
+            self.pipeline.transform(raw_data),
+            |result: &TransformResult| result.processed_records.len(),
+        )
+        .await?;
+        let records = deserialize_records(&transformed_result.processed_records)?;
+        tracing::info!("🎉 Deserialized {} typed records", records.len());
+        Ok(records)
+    }
 }
@@ -1 +1,2 @@
 pub mod etl_engine;
+pub mod stage_runner;
@@ -0,0 +1,63 @@
+use crate::domain::model::PipelineStage;
+use crate::utils::error::Result;
+use crate::utils::metrics::StageThroughput;
+use crate::utils::monitor::SystemMonitor;
+use std::future::Future;
+use std::time::Instant;
+
+/// 執行 Pipeline 的單一階段，單一 Pipeline 的 `EtlEngine` 與序列的 `PipelineSequence` 共用
+///
+/// 記錄耗時與 `records` 算出的筆數；失敗時在錯誤附上階段名稱，
+/// 完成後由監控器記錄 `{pipeline}.{stage}` 的記憶體用量。
+pub async fn run_stage<T>(
+    pipeline: &str,
+    stage: PipelineStage,
+    monitor: Option<&SystemMonitor>,
+    future: impl Future<Output = Result<T>>,
+    records: impl FnOnce(&T) -> usize,
+) -> Result<(T, StageThroughput)> {
+    let started = Instant::now();
+    let output = future.await.map_err(|e| e.at_stage(stage.as_str()))?;
+    let throughput = StageThroughput::new(records(&output), started.elapsed());
+    if let Some(monitor) = monitor {
+        monitor.record_stage(&format!("{}.{}", pipeline, stage.as_str()));
+    }
+    Ok((output, throughput))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::error::EtlError;
+
+    #[tokio::test]
+    async fn test_run_stage_counts_and_tags_errors() {
+        let (values, throughput) = run_stage(
+            "users",
+            PipelineStage::Extract,
+            None,
+            async { Ok(vec![1, 2, 3]) },
+            Vec::len,
+        )
+        .await
+        .unwrap();
+        assert_eq!(values, [1, 2, 3]);
+        assert_eq!(throughput.records, 3);
+
+        let error = run_stage(
+            "users",
+            PipelineStage::Load,
+            None,
+            async {
+                Err::<(), _>(EtlError::ProcessingError {
+                    message: "disk full".to_string(),
+                })
+            },
+            |_| 0,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.context().unwrap().stage.as_deref(), Some("load"));
+        assert!(matches!(error.root(), EtlError::ProcessingError { .. }));
+    }
+}
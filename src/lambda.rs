@@ -7,15 +7,25 @@ use aws_sdk_s3::Client as S3Client;
 #[cfg(feature = "lambda")]
 use lambda_runtime::{run, service_fn, Error, LambdaEvent};
 #[cfg(feature = "lambda")]
-use samll_etl::config::lambda::{LambdaConfig, S3Storage};
+use samll_etl::app::pipelines::sequence_runner::run_sequence_with_storage;
 #[cfg(feature = "lambda")]
-use samll_etl::core::{etl::EtlEngine, pipeline::SimplePipeline};
+use samll_etl::app::RunOptions;
+#[cfg(feature = "lambda")]
+use samll_etl::config::lambda::{parse_s3_uri, LambdaConfig, S3Storage};
+#[cfg(feature = "lambda")]
+use samll_etl::config::sequence_config::SequenceConfig;
+#[cfg(feature = "lambda")]
+use samll_etl::core::run_report::RunReport;
+#[cfg(feature = "lambda")]
+use samll_etl::core::{etl::EtlEngine, pipeline::SimplePipeline, Storage};
+#[cfg(feature = "lambda")]
+use samll_etl::utils::budget::{ExecutionBudget, ResourceUsage};
 #[cfg(feature = "lambda")]
 use samll_etl::utils::{logger, validation::Validate};
 #[cfg(feature = "lambda")]
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::Read;
+#[cfg(feature = "lambda")]
+use std::collections::HashMap;
 
 #[cfg(feature = "lambda")]
 #[derive(Debug, Deserialize)]
@@ -23,6 +33,19 @@ pub struct Request {
     pub api_endpoint: Option<String>,
     pub s3_bucket: Option<String>,
     pub s3_prefix: Option<String>,
+    /// 序列設定的 TOML 內容；優先於 sequence_config_s3 與 SEQUENCE_CONFIG
+    pub sequence_config: Option<String>,
+    /// 存放在 S3 的序列設定（s3://bucket/key）
+    pub sequence_config_s3: Option<String>,
+    /// 序列設定的 ${VAR} 變數，優先於環境變數
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    pub execution_id: Option<String>,
+    pub resume: Option<String>,
+    #[serde(default)]
+    pub only: Vec<String>,
+    #[serde(default)]
+    pub skip: Vec<String>,
 }
 
 #[cfg(feature = "lambda")]
@@ -31,29 +54,103 @@ pub struct Response {
     pub message: String,
     pub output_path: String,
     pub records_processed: usize,
+    pub resource_usage: ResourceUsage,
 }
 
+/// 序列的執行摘要：run_report.json 的內容加上輸出的 S3 位置
 #[cfg(feature = "lambda")]
-async fn function_handler(event: LambdaEvent<Request>) -> Result<Response, Error> {
-    tracing::info!("Starting ETL Lambda function");
-    tracing::debug!("Lambda event: {:?}", event.payload);
+#[derive(Debug, Serialize)]
+pub struct SequenceResponse {
+    #[serde(flatten)]
+    pub report: RunReport,
+    pub output_uris: Vec<String>,
+    pub resource_usage: ResourceUsage,
+}
+
+#[cfg(feature = "lambda")]
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum HandlerResponse {
+    Pipeline(Response),
+    Sequence(Box<SequenceResponse>),
+}
+
+/// 序列設定的來源
+#[cfg(feature = "lambda")]
+#[derive(Debug)]
+enum SequenceSource {
+    Inline(String),
+    S3(String),
+    /// 隨函數部署的設定檔（SEQUENCE_CONFIG，相對路徑以 LAMBDA_TASK_ROOT 為基準）
+    Embedded(std::path::PathBuf),
+}
+
+#[cfg(feature = "lambda")]
+impl SequenceSource {
+    /// 事件內容優先於部署時內嵌的設定；都沒有時執行單一 Pipeline
+    fn from_request(request: &Request) -> Option<Self> {
+        if let Some(content) = &request.sequence_config {
+            return Some(Self::Inline(content.clone()));
+        }
+        if let Some(uri) = &request.sequence_config_s3 {
+            return Some(Self::S3(uri.clone()));
+        }
+        let path = std::path::PathBuf::from(std::env::var("SEQUENCE_CONFIG").ok()?);
+        let root = std::env::var("LAMBDA_TASK_ROOT").unwrap_or_else(|_| ".".to_string());
+        Some(Self::Embedded(std::path::Path::new(&root).join(path)))
+    }
+
+    async fn load(
+        &self,
+        client: &S3Client,
+        variables: &HashMap<String, String>,
+    ) -> samll_etl::Result<SequenceConfig> {
+        match self {
+            Self::Inline(content) => {
+                SequenceConfig::from_toml_str_with_variables(content, variables)
+            }
+            Self::Embedded(path) => SequenceConfig::from_file_with_variables(path, variables),
+            Self::S3(uri) => {
+                let (bucket, key) = parse_s3_uri(uri)?;
+                let bytes = S3Storage::new(client.clone(), bucket)
+                    .read_file(&key)
+                    .await?;
+                let content =
+                    String::from_utf8(bytes).map_err(|e| samll_etl::EtlError::ConfigError {
+                        message: format!("Sequence config {} is not valid UTF-8: {}", uri, e),
+                    })?;
+                SequenceConfig::from_toml_str_with_variables(&content, variables)
+            }
+        }
+    }
+}
 
-    // 設置環境變量 (如果事件中有的話)
-    if let Some(endpoint) = &event.payload.api_endpoint {
+#[cfg(feature = "lambda")]
+async fn function_handler(event: LambdaEvent<Request>) -> Result<HandlerResponse, Error> {
+    match SequenceSource::from_request(&event.payload) {
+        Some(source) => sequence_handler(event, source)
+            .await
+            .map(|response| HandlerResponse::Sequence(Box::new(response))),
+        None => pipeline_handler(event).await.map(HandlerResponse::Pipeline),
+    }
+}
+
+/// 從事件設定 S3 位置後讀取並驗證 Lambda 配置
+#[cfg(feature = "lambda")]
+fn lambda_config(request: &Request) -> Result<LambdaConfig, Error> {
+    if let Some(endpoint) = &request.api_endpoint {
         std::env::set_var("API_ENDPOINT", endpoint);
     }
-    if let Some(bucket) = &event.payload.s3_bucket {
+    if let Some(bucket) = &request.s3_bucket {
         std::env::set_var("S3_BUCKET", bucket);
     }
-    if let Some(prefix) = &event.payload.s3_prefix {
+    if let Some(prefix) = &request.s3_prefix {
         std::env::set_var("S3_PREFIX", prefix);
     }
 
-    // 創建Lambda配置
     let lambda_config = LambdaConfig::from_env()
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
 
-    // 驗證配置
     lambda_config.validate().map_err(|e| {
         tracing::error!("Lambda configuration validation failed: {}", e);
         Box::new(e) as Box<dyn std::error::Error + Send + Sync>
@@ -65,23 +162,91 @@ async fn function_handler(event: LambdaEvent<Request>) -> Result<Response, Error
         lambda_config.s3_region,
         lambda_config.s3_prefix
     );
+    Ok(lambda_config)
+}
 
-    // 創建AWS配置和S3客戶端
+#[cfg(feature = "lambda")]
+async fn s3_client(region: &str) -> S3Client {
     let config = aws_config::load_defaults(BehaviorVersion::latest()).await;
-    let region = Region::new(lambda_config.s3_region.clone());
-    // let s3_client = S3Client::new(&config);
     let config = aws_sdk_s3::config::Builder::from(&config)
-        .region(region)
+        .region(Region::new(region.to_string()))
         .force_path_style(true)
         .build();
-    let s3_client = S3Client::from_conf(config);
+    S3Client::from_conf(config)
+}
+
+/// 執行序列設定：輸出寫到 S3，返回執行摘要（失敗時 status 為 failed，不視為呼叫錯誤）
+#[cfg(feature = "lambda")]
+async fn sequence_handler(
+    event: LambdaEvent<Request>,
+    source: SequenceSource,
+) -> Result<SequenceResponse, Error> {
+    tracing::info!("Starting sequence ETL Lambda function");
+    tracing::debug!("Sequence config source: {:?}", source);
+
+    let budget = ExecutionBudget::from_deadline_epoch_ms(event.context.deadline);
+    budget.checkpoint("Invocation Start");
+
+    let request = event.payload;
+    let lambda_config = lambda_config(&request)?;
+    let client = s3_client(&lambda_config.s3_region).await;
+    let config = source.load(&client, &request.variables).await?;
+
+    // Lambda 只有 /tmp 可寫入：序列狀態、進度檔與上下文暫存檔都放在這裡
+    std::env::set_current_dir(std::env::temp_dir())?;
+
+    let storage = S3Storage::new(client, lambda_config.s3_bucket.clone())
+        .with_prefix(&lambda_config.s3_prefix);
+    let options = RunOptions {
+        execution_id: request.execution_id,
+        resume: request.resume,
+        only: request.only,
+        skip: request.skip,
+        budget: Some(budget.clone()),
+        ..RunOptions::default()
+    };
+    let report = run_sequence_with_storage(config, options, |_| Ok(storage.clone())).await;
+
+    let response = SequenceResponse {
+        output_uris: report
+            .output_paths
+            .iter()
+            .map(|path| storage.uri(path))
+            .collect(),
+        report,
+        resource_usage: budget.resource_usage(lambda_memory_size_mb()),
+    };
+    log_resource_usage(&response.resource_usage);
+
+    match &response.report.error {
+        Some(error) => tracing::error!(
+            "Sequence ETL Lambda function failed ({}): {}",
+            response.report.execution_id,
+            error.message
+        ),
+        None => tracing::info!("Sequence ETL Lambda function completed successfully"),
+    }
+    Ok(response)
+}
+
+#[cfg(feature = "lambda")]
+async fn pipeline_handler(event: LambdaEvent<Request>) -> Result<Response, Error> {
+    tracing::info!("Starting ETL Lambda function");
+    tracing::debug!("Lambda event: {:?}", event.payload);
+
+    // 以呼叫截止時間建立執行預算
+    let budget = ExecutionBudget::from_deadline_epoch_ms(event.context.deadline);
+    budget.checkpoint("Invocation Start");
+
+    let lambda_config = lambda_config(&event.payload)?;
+    let s3_client = s3_client(&lambda_config.s3_region).await;
 
     // 創建存儲和管道
     let storage = S3Storage::new(s3_client, lambda_config.s3_bucket.clone());
     let pipeline = SimplePipeline::new(storage, lambda_config);
 
     // 運行ETL
-    let engine = EtlEngine::new(pipeline);
+    let engine = EtlEngine::new(pipeline).with_budget(budget.clone());
     let output_path = engine
         .run()
         .await
@@ -91,13 +256,37 @@ async fn function_handler(event: LambdaEvent<Request>) -> Result<Response, Error
         message: "ETL process completed successfully".to_string(),
         output_path: output_path.clone(),
         records_processed: 0, // TODO: 實際記錄處理數量
+        resource_usage: budget.resource_usage(lambda_memory_size_mb()),
     };
 
+    log_resource_usage(&response.resource_usage);
+
     tracing::info!("ETL Lambda function completed successfully");
     tracing::info!("Response: {:?}", response);
     Ok(response)
 }
 
+#[cfg(feature = "lambda")]
+fn log_resource_usage(usage: &ResourceUsage) {
+    tracing::info!(
+        "📊 Resource usage - duration: {}ms, billed: {}ms, peak memory: {:?}MB / {:?}MB, estimated: {:?} GB-s, remaining: {}ms",
+        usage.duration_ms,
+        usage.billed_duration_ms,
+        usage.peak_memory_mb,
+        usage.memory_size_mb,
+        usage.estimated_gb_seconds,
+        usage.remaining_ms
+    );
+}
+
+/// Lambda 配置的記憶體大小（MB）
+#[cfg(feature = "lambda")]
+fn lambda_memory_size_mb() -> Option<u64> {
+    std::env::var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE")
+        .ok()
+        .and_then(|value| value.parse().ok())
+}
+
 #[cfg(feature = "lambda")]
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -11,7 +11,11 @@ pub mod domain;
 pub use config::{cli::LocalStorage, CliConfig};
 
 #[cfg(feature = "lambda")]
-pub use config::lambda::{LambdaConfig, S3Storage};
+pub use config::lambda::LambdaConfig;
+#[cfg(feature = "s3")]
+pub use config::lambda::S3Storage;
 
-pub use core::{etl::EtlEngine, mvp_pipeline::MvpPipeline, pipeline::SimplePipeline};
+pub use core::{
+    etl::EtlEngine, etl::SequenceEngine, mvp_pipeline::MvpPipeline, pipeline::SimplePipeline,
+};
 pub use utils::error::{EtlError, Result};
@@ -1,13 +1,15 @@
 use clap::Parser;
+use samll_etl::core::run_report::RunReport;
 use samll_etl::utils::{logger, validation::Validate};
 use samll_etl::{CliConfig, EtlEngine, LocalStorage, SimplePipeline};
+use std::path::PathBuf;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = CliConfig::parse();
 
     // 初始化日誌
-    logger::init_cli_logger(config.verbose);
+    logger::init_logger(config.verbose, config.log_format);
 
     tracing::info!("Starting samll-etl CLI");
     if config.verbose {
@@ -27,6 +29,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("🔍 System monitoring enabled");
     }
 
+    // 執行報告預設寫在輸出目錄
+    let run_report_path = config
+        .run_report
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(&config.output_path).join("run_report.json"));
+    let execution_id = format!("run_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S"));
+    let started_at = std::time::Instant::now();
+
     // 創建存儲和管道
     let storage = LocalStorage::new(config.output_path.clone());
     let pipeline = SimplePipeline::new(storage, config);
@@ -34,7 +45,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 創建ETL引擎並運行
     let engine = EtlEngine::new_with_monitoring(pipeline, monitor_enabled);
 
-    match engine.run().await {
+    let outcome = engine.run().await;
+    let exit_code = outcome
+        .as_ref()
+        .err()
+        .map_or(0, |e| e.severity().exit_code());
+    let report = RunReport::for_pipeline(&execution_id, "samll-etl", outcome.as_deref(), exit_code)
+        .with_duration(started_at.elapsed());
+    match report.write(&run_report_path) {
+        Ok(()) => tracing::info!("🧾 Run report written to {}", run_report_path.display()),
+        Err(e) => tracing::warn!(
+            "⚠️ Failed to write run report {}: {}",
+            run_report_path.display(),
+            e
+        ),
+    }
+
+    match outcome {
         Ok(output_path) => {
             tracing::info!("✅ ETL process completed successfully!");
             tracing::info!("📁 Output saved to: {}", output_path);
@@ -44,9 +71,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => {
             // 記錄詳細錯誤信息
             tracing::error!(
-                "❌ ETL process failed: {} (Category: {:?}, Severity: {:?})",
+                "❌ ETL process failed: {} (Category: {}, Severity: {:?})",
                 e,
-                e.category(),
+                e.category().code(),
                 e.severity()
             );
             tracing::error!("💡 Recovery suggestion: {}", e.recovery_suggestion());
@@ -56,13 +83,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("💡 建議: {}", e.recovery_suggestion());
 
             // 根據錯誤嚴重程度決定退出碼
-            let exit_code = match e.severity() {
-                samll_etl::utils::error::ErrorSeverity::Low => 0, // 警告，但成功
-                samll_etl::utils::error::ErrorSeverity::Medium => 2, // 重試錯誤
-                samll_etl::utils::error::ErrorSeverity::High => 1, // 處理錯誤
-                samll_etl::utils::error::ErrorSeverity::Critical => 3, // 系統錯誤
-            };
-
             if exit_code > 0 {
                 std::process::exit(exit_code);
             }
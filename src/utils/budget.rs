@@ -0,0 +1,164 @@
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 預設保留的安全時間，剩餘時間低於此值時停止排程新的工作
+const DEFAULT_SAFETY_MARGIN: Duration = Duration::from_secs(10);
+
+/// 執行時間預算中的檢查點
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetCheckpoint {
+    pub label: String,
+    pub elapsed_ms: u64,
+    pub remaining_ms: u64,
+}
+
+/// 執行結束時的資源使用摘要
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUsage {
+    pub duration_ms: u64,
+    pub billed_duration_ms: u64,
+    pub remaining_ms: u64,
+    pub memory_size_mb: Option<u64>,
+    pub peak_memory_mb: Option<u64>,
+    pub estimated_gb_seconds: Option<f64>,
+    pub checkpoints: Vec<BudgetCheckpoint>,
+}
+
+/// 執行時間預算（例如 Lambda 的呼叫逾時），可在多個元件間共享
+#[derive(Debug, Clone)]
+pub struct ExecutionBudget {
+    started_at: Instant,
+    deadline: Instant,
+    safety_margin: Duration,
+    checkpoints: Arc<Mutex<Vec<BudgetCheckpoint>>>,
+}
+
+impl ExecutionBudget {
+    pub fn new(total: Duration) -> Self {
+        let started_at = Instant::now();
+        Self {
+            started_at,
+            deadline: started_at + total,
+            safety_margin: DEFAULT_SAFETY_MARGIN,
+            checkpoints: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// 從 Unix epoch 毫秒表示的截止時間建立（Lambda context.deadline）
+    pub fn from_deadline_epoch_ms(deadline_ms: u64) -> Self {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self::new(Duration::from_millis(deadline_ms.saturating_sub(now_ms)))
+    }
+
+    pub fn with_safety_margin(mut self, safety_margin: Duration) -> Self {
+        self.safety_margin = safety_margin;
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// 剩餘時間是否已低於安全邊界
+    pub fn is_near_deadline(&self) -> bool {
+        self.remaining() <= self.safety_margin
+    }
+
+    /// 記錄並輸出目前的剩餘時間
+    pub fn checkpoint(&self, label: &str) {
+        let checkpoint = BudgetCheckpoint {
+            label: label.to_string(),
+            elapsed_ms: self.elapsed().as_millis() as u64,
+            remaining_ms: self.remaining().as_millis() as u64,
+        };
+        tracing::info!(
+            "⏱️ {} - elapsed: {}ms, remaining budget: {}ms",
+            checkpoint.label,
+            checkpoint.elapsed_ms,
+            checkpoint.remaining_ms
+        );
+        if let Ok(mut checkpoints) = self.checkpoints.lock() {
+            checkpoints.push(checkpoint);
+        }
+    }
+
+    pub fn checkpoints(&self) -> Vec<BudgetCheckpoint> {
+        self.checkpoints
+            .lock()
+            .map(|checkpoints| checkpoints.clone())
+            .unwrap_or_default()
+    }
+
+    /// 產生資源使用摘要；memory_size_mb 為配置的記憶體（用於估算 GB-秒）
+    pub fn resource_usage(&self, memory_size_mb: Option<u64>) -> ResourceUsage {
+        let duration_ms = self.elapsed().as_millis() as u64;
+        // Lambda 以 1ms 為計費單位，至少 1ms
+        let billed_duration_ms = duration_ms.max(1);
+        let estimated_gb_seconds =
+            memory_size_mb.map(|mb| (mb as f64 / 1024.0) * (billed_duration_ms as f64 / 1000.0));
+
+        ResourceUsage {
+            duration_ms,
+            billed_duration_ms,
+            remaining_ms: self.remaining().as_millis() as u64,
+            memory_size_mb,
+            peak_memory_mb: read_peak_memory_mb(),
+            estimated_gb_seconds,
+            checkpoints: self.checkpoints(),
+        }
+    }
+}
+
+/// 讀取本行程的峰值常駐記憶體（Linux /proc/self/status 的 VmHWM）
+pub fn read_peak_memory_mb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find(|line| line.starts_with("VmHWM:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_deadline_with_margin() {
+        let budget = ExecutionBudget::new(Duration::from_secs(5));
+        assert!(budget.is_near_deadline());
+
+        let budget = ExecutionBudget::new(Duration::from_secs(60))
+            .with_safety_margin(Duration::from_secs(1));
+        assert!(!budget.is_near_deadline());
+    }
+
+    #[test]
+    fn test_resource_usage_records_checkpoints() {
+        let budget = ExecutionBudget::new(Duration::from_secs(60));
+        budget.checkpoint("After Extract");
+        budget.checkpoint("After Load");
+
+        let usage = budget.resource_usage(Some(1024));
+        assert_eq!(usage.checkpoints.len(), 2);
+        assert_eq!(usage.checkpoints[0].label, "After Extract");
+        assert!(usage.billed_duration_ms >= 1);
+        assert!(usage.estimated_gb_seconds.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_expired_deadline() {
+        let budget = ExecutionBudget::from_deadline_epoch_ms(0);
+        assert_eq!(budget.remaining(), Duration::ZERO);
+        assert!(budget.is_near_deadline());
+    }
+}
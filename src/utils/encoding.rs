@@ -0,0 +1,108 @@
+use crate::utils::error::{EtlError, Result};
+use encoding_rs::Encoding;
+
+/// 由名稱取得編碼（支援 WHATWG 標籤，例如 "utf-8"、"latin1"、"windows-1252"、"big5"）
+pub fn lookup(label: &str) -> Result<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| EtlError::InvalidConfigValueError {
+        field: "source.encoding.charset".to_string(),
+        value: label.to_string(),
+        reason: "Unknown character encoding".to_string(),
+    })
+}
+
+/// 從 Content-Type 標頭取出 charset，例如 "text/csv; charset=ISO-8859-1"
+pub fn charset_from_content_type(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// 將位元組依指定編碼轉為 UTF-8 字串
+///
+/// strict 模式遇到無效位元組序列時返回錯誤；否則以替換字元取代並記錄警告。
+pub fn decode_to_utf8(bytes: &[u8], encoding: &'static Encoding, strict: bool) -> Result<String> {
+    if strict {
+        return encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(|text| text.into_owned())
+            .ok_or_else(|| EtlError::DataValidationError {
+                message: format!(
+                    "Invalid {} byte sequence in source data (strict encoding enabled)",
+                    encoding.name()
+                ),
+            });
+    }
+
+    let (text, actual, had_errors) = encoding.decode(bytes);
+    if had_errors {
+        tracing::warn!(
+            "🔤 Invalid {} byte sequences replaced with U+FFFD",
+            actual.name()
+        );
+    }
+    Ok(text.into_owned())
+}
+
+/// 將 UTF-8 字串轉為指定編碼；遇到目標編碼無法表示的字元時返回錯誤
+///
+/// encoding_rs 的 UTF-16 編碼器輸出 UTF-8，因此 UTF-16LE/BE 自行轉換。
+pub fn encode_from_utf8(text: &str, encoding: &'static Encoding) -> Result<Vec<u8>> {
+    if encoding == encoding_rs::UTF_16LE {
+        return Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect());
+    }
+    if encoding == encoding_rs::UTF_16BE {
+        return Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect());
+    }
+
+    let (bytes, _, had_errors) = encoding.encode(text);
+    if had_errors {
+        let unmappable = text
+            .chars()
+            .find(|c| encoding.encode(c.encode_utf8(&mut [0; 4])).2)
+            .unwrap_or_default();
+        return Err(EtlError::DataValidationError {
+            message: format!(
+                "Character '{}' cannot be encoded as {}",
+                unmappable,
+                encoding.name()
+            ),
+        });
+    }
+    Ok(bytes.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_windows_1252() {
+        let encoding = lookup("windows-1252").unwrap();
+        // "café – 5€" in Windows-1252
+        let bytes = b"caf\xe9 \x96 5\x80";
+        assert_eq!(decode_to_utf8(bytes, encoding, true).unwrap(), "café – 5€");
+    }
+
+    #[test]
+    fn test_strict_utf8_rejects_invalid_bytes() {
+        let encoding = lookup("utf-8").unwrap();
+        assert!(decode_to_utf8(b"caf\xe9", encoding, true).is_err());
+        assert_eq!(
+            decode_to_utf8(b"caf\xe9", encoding, false).unwrap(),
+            "caf\u{FFFD}"
+        );
+        assert!(lookup("not-a-charset").is_err());
+    }
+
+    #[test]
+    fn test_charset_from_content_type() {
+        assert_eq!(
+            charset_from_content_type("application/json; charset=\"ISO-8859-1\"").as_deref(),
+            Some("ISO-8859-1")
+        );
+        assert_eq!(charset_from_content_type("application/json"), None);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::utils::error::{EtlError, Result};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+
+/// 加密檔的檔頭標記（含格式版本）
+const MAGIC_PREFIX: &[u8] = b"SETLENC";
+const MAGIC: &[u8] = b"SETLENC2";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// PBKDF2-HMAC-SHA256 的迭代次數（OWASP 建議值）
+const KDF_ITERATIONS: NonZeroU32 = NonZeroU32::new(600_000).unwrap();
+
+/// 預設讀取金鑰的環境變數
+pub const DEFAULT_KEY_ENV: &str = "ETL_STATE_KEY";
+
+/// 狀態檔（checkpoint、剩餘工作等）的加密器，使用 AES-256-GCM 同時提供機密性與完整性檢查
+///
+/// 金鑰由金鑰字串經 PBKDF2-HMAC-SHA256 與隨機 salt 衍生，salt 寫在檔頭；同一個加密器寫出的檔案共用
+/// 一個 salt，讀取其他 salt 的檔案時衍生一次後快取。檔案路徑作為附加驗證資料（AAD），避免不同狀態檔
+/// 被互相替換。
+#[derive(Clone)]
+pub struct StateCipher {
+    key_material: Arc<str>,
+    salt: [u8; SALT_LEN],
+    ciphers: Arc<Mutex<HashMap<[u8; SALT_LEN], Aes256Gcm>>>,
+    allow_plaintext: bool,
+}
+
+impl std::fmt::Debug for StateCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StateCipher").finish_non_exhaustive()
+    }
+}
+
+impl StateCipher {
+    /// 以任意長度的金鑰字串建立
+    pub fn from_key_material(key_material: &str) -> Result<Self> {
+        if key_material.is_empty() {
+            return Err(EtlError::MissingConfigError {
+                field: "global.state_encryption.key_env".to_string(),
+            });
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = Self {
+            key_material: Arc::from(key_material),
+            salt,
+            ciphers: Arc::new(Mutex::new(HashMap::new())),
+            allow_plaintext: false,
+        };
+        cipher.cipher_for(&salt);
+        Ok(cipher)
+    }
+
+    /// 從環境變數讀取金鑰
+    pub fn from_env(key_env: &str) -> Result<Self> {
+        let key_material = std::env::var(key_env).map_err(|_| EtlError::MissingConfigError {
+            field: format!("environment variable {}", key_env),
+        })?;
+        Self::from_key_material(&key_material)
+    }
+
+    /// 一次性遷移：允許讀取未加密的舊狀態檔（下次寫入時加密），預設視為完整性錯誤
+    pub fn allowing_plaintext(mut self) -> Self {
+        self.allow_plaintext = true;
+        self
+    }
+
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC_PREFIX)
+    }
+
+    /// 依 salt 衍生（或取出已衍生的）AES-256-GCM 加密器
+    fn cipher_for(&self, salt: &[u8; SALT_LEN]) -> Aes256Gcm {
+        let mut ciphers = self.ciphers.lock().unwrap_or_else(|e| e.into_inner());
+        ciphers
+            .entry(*salt)
+            .or_insert_with(|| {
+                let mut key = [0u8; 32];
+                ring::pbkdf2::derive(
+                    ring::pbkdf2::PBKDF2_HMAC_SHA256,
+                    KDF_ITERATIONS,
+                    salt,
+                    self.key_material.as_bytes(),
+                    &mut key,
+                );
+                Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+            })
+            .clone()
+    }
+
+    /// 加密狀態內容，輸出格式：MAGIC | salt | nonce | ciphertext+tag
+    pub fn seal(&self, plaintext: &[u8], path: &str) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher_for(&self.salt)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: path.as_bytes(),
+                },
+            )
+            .map_err(|_| EtlError::ProcessingError {
+                message: format!("Failed to encrypt state file '{}'", path),
+            })?;
+
+        let mut sealed = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&self.salt);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    /// 解密並驗證狀態內容；未加密的檔案只在遷移模式下接受，否則視為遭到替換
+    pub fn open(&self, data: &[u8], path: &str) -> Result<Vec<u8>> {
+        if !Self::is_encrypted(data) {
+            if !self.allow_plaintext {
+                return Err(integrity_error(path));
+            }
+            tracing::warn!(
+                "🔐 State file '{}' is not encrypted, it will be encrypted on next save",
+                path
+            );
+            return Ok(data.to_vec());
+        }
+
+        let Some(body) = data.strip_prefix(MAGIC) else {
+            return Err(integrity_error(path));
+        };
+        if body.len() < SALT_LEN + NONCE_LEN {
+            return Err(integrity_error(path));
+        }
+        let (salt, body) = body.split_at(SALT_LEN);
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        let salt: [u8; SALT_LEN] = salt.try_into().map_err(|_| integrity_error(path))?;
+        self.cipher_for(&salt)
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: path.as_bytes(),
+                },
+            )
+            .map_err(|_| integrity_error(path))
+    }
+}
+
+/// 依需要加密後寫入的內容
+pub fn seal_state(cipher: Option<&StateCipher>, plaintext: &[u8], path: &str) -> Result<Vec<u8>> {
+    match cipher {
+        Some(cipher) => cipher.seal(plaintext, path),
+        None => Ok(plaintext.to_vec()),
+    }
+}
+
+/// 讀取狀態內容，加密檔在沒有金鑰時視為錯誤
+pub fn open_state(cipher: Option<&StateCipher>, data: &[u8], path: &str) -> Result<Vec<u8>> {
+    match cipher {
+        Some(cipher) => cipher.open(data, path),
+        None if StateCipher::is_encrypted(data) => Err(EtlError::ConfigValidationError {
+            field: "global.state_encryption".to_string(),
+            message: format!(
+                "State file '{}' is encrypted but no encryption key is configured",
+                path
+            ),
+        }),
+        None => Ok(data.to_vec()),
+    }
+}
+
+fn integrity_error(path: &str) -> EtlError {
+    EtlError::DataValidationError {
+        message: format!(
+            "State file '{}' failed integrity check (wrong key or tampered content)",
+            path
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open_roundtrip() {
+        let cipher = StateCipher::from_key_material("secret").unwrap();
+        let sealed = cipher.seal(b"{\"token\":\"abc\"}", "a.json").unwrap();
+
+        assert!(StateCipher::is_encrypted(&sealed));
+        assert!(!sealed.windows(5).any(|w| w == b"token"));
+        assert_eq!(
+            cipher.open(&sealed, "a.json").unwrap(),
+            b"{\"token\":\"abc\"}"
+        );
+    }
+
+    #[test]
+    fn test_detects_tampering_wrong_key_and_path() {
+        let cipher = StateCipher::from_key_material("secret").unwrap();
+        let mut sealed = cipher.seal(b"state", "a.json").unwrap();
+
+        let other = StateCipher::from_key_material("other").unwrap();
+        assert!(other.open(&sealed, "a.json").is_err());
+        // 相同金鑰的另一個加密器（不同 salt）可以讀取
+        let same_key = StateCipher::from_key_material("secret").unwrap();
+        assert_eq!(same_key.open(&sealed, "a.json").unwrap(), b"state");
+        assert!(cipher.open(&sealed, "b.json").is_err());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0xff;
+        assert!(cipher.open(&sealed, "a.json").is_err());
+    }
+
+    #[test]
+    fn test_plaintext_handling() {
+        // 設定加密後，未加密的檔案視為遭到替換，只有遷移模式才接受
+        let cipher = StateCipher::from_key_material("secret").unwrap();
+        assert!(cipher.open(b"{}", "a.json").is_err());
+        let migrating = cipher.clone().allowing_plaintext();
+        assert_eq!(migrating.open(b"{}", "a.json").unwrap(), b"{}");
+
+        let sealed = cipher.seal(b"{}", "a.json").unwrap();
+        assert!(open_state(None, &sealed, "a.json").is_err());
+    }
+}
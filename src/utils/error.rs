@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -65,6 +66,9 @@ pub enum EtlError {
     #[error("Authentication failed: {details}")]
     AuthenticationError { details: String },
 
+    #[error("Response too large: {endpoint} exceeded {limit}")]
+    ResponseTooLarge { endpoint: String, limit: String },
+
     // Business logic errors
     #[error("Insufficient data: expected at least {expected} records, got {actual}")]
     InsufficientDataError { expected: usize, actual: usize },
@@ -86,6 +90,46 @@ pub enum EtlError {
     // Pipeline execution errors
     #[error("Pipeline execution failed: {0}")]
     PipelineExecution(String),
+
+    #[error("Execution interrupted: {details}")]
+    Interrupted { details: String },
+
+    // 附上發生位置的錯誤；分類、嚴重程度與建議都沿用原始錯誤
+    #[error("{context}: {source}")]
+    Context {
+        context: ErrorContext,
+        #[source]
+        source: Box<EtlError>,
+    },
+}
+
+/// 錯誤發生的位置，序列與單一 Pipeline 的 CLI、函式庫使用者都以此定位失敗
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorContext {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<String>,
+    /// extract、transform 或 load
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// 已遮蔽憑證的來源端點
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(pipeline) = &self.pipeline {
+            parts.push(format!("pipeline '{}'", pipeline));
+        }
+        if let Some(stage) = &self.stage {
+            parts.push(format!("{} stage", stage));
+        }
+        if let Some(endpoint) = &self.endpoint {
+            parts.push(format!("endpoint {}", endpoint));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, EtlError>;
@@ -98,6 +142,18 @@ pub enum ErrorSeverity {
     Critical, // System-level error, immediate attention required
 }
 
+impl ErrorSeverity {
+    /// CLI 的退出碼；單一 Pipeline 與序列共用同一套對應
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorSeverity::Low => 0,      // 警告，但成功
+            ErrorSeverity::Medium => 2,   // 重試錯誤
+            ErrorSeverity::High => 1,     // 處理錯誤
+            ErrorSeverity::Critical => 3, // 系統錯誤
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ErrorCategory {
     Configuration,
@@ -109,9 +165,101 @@ pub enum ErrorCategory {
     System,
 }
 
+impl ErrorCategory {
+    /// 穩定的分類代碼，供執行報告與外部系統比對
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorCategory::Configuration => "configuration",
+            ErrorCategory::Network => "network",
+            ErrorCategory::DataProcessing => "data_processing",
+            ErrorCategory::Infrastructure => "infrastructure",
+            ErrorCategory::Authentication => "authentication",
+            ErrorCategory::BusinessLogic => "business_logic",
+            ErrorCategory::System => "system",
+        }
+    }
+}
+
 impl EtlError {
+    /// 非 2xx 回應對應的錯誤：401/403 為認證錯誤，5xx 為（可重試的）服務無法使用，其餘為處理錯誤
+    pub fn http_status(status: u16, message: String) -> Self {
+        match status {
+            401 | 403 => EtlError::AuthenticationError { details: message },
+            500..=599 => EtlError::ServiceUnavailableError { service: message },
+            _ => EtlError::ProcessingError { message },
+        }
+    }
+
+    /// 是否為檔案不存在（含附上位置資訊後的錯誤），其他讀取錯誤不應視為「沒有資料」
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            EtlError::IoError(e) => e.kind() == std::io::ErrorKind::NotFound,
+            EtlError::Context { source, .. } => source.is_not_found(),
+            _ => false,
+        }
+    }
+
+    /// 附上失敗的 Pipeline；已有位置資訊時只補上缺少的欄位，內層較精確的資訊優先
+    pub fn in_pipeline(self, pipeline: impl Into<String>) -> Self {
+        self.with_context(|context| {
+            context.pipeline.get_or_insert_with(|| pipeline.into());
+        })
+    }
+
+    /// 附上失敗的階段（extract、transform 或 load）
+    pub fn at_stage(self, stage: impl Into<String>) -> Self {
+        self.with_context(|context| {
+            context.stage.get_or_insert_with(|| stage.into());
+        })
+    }
+
+    /// 附上來源端點；呼叫端需先遮蔽憑證
+    pub fn at_endpoint(self, endpoint: impl Into<String>) -> Self {
+        self.with_context(|context| {
+            context.endpoint.get_or_insert_with(|| endpoint.into());
+        })
+    }
+
+    fn with_context(self, update: impl FnOnce(&mut ErrorContext)) -> Self {
+        match self {
+            EtlError::Context {
+                mut context,
+                source,
+            } => {
+                update(&mut context);
+                EtlError::Context { context, source }
+            }
+            other => {
+                let mut context = ErrorContext::default();
+                update(&mut context);
+                EtlError::Context {
+                    context,
+                    source: Box::new(other),
+                }
+            }
+        }
+    }
+
+    /// 錯誤發生的位置；沒有附上時為 None
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            EtlError::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// 去掉位置資訊後的原始錯誤，判斷錯誤種類時應比對此值
+    pub fn root(&self) -> &EtlError {
+        match self {
+            EtlError::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
     pub fn severity(&self) -> ErrorSeverity {
         match self {
+            EtlError::Context { source, .. } => source.severity(),
+
             // Low severity - warnings
             EtlError::DataQualityError { .. } => ErrorSeverity::Low,
             EtlError::InsufficientDataError { .. } => ErrorSeverity::Low,
@@ -129,6 +277,7 @@ impl EtlError {
             EtlError::AuthenticationError { .. } => ErrorSeverity::High,
             EtlError::DataValidationError { .. } => ErrorSeverity::High,
             EtlError::TransformationError { .. } => ErrorSeverity::High,
+            EtlError::ResponseTooLarge { .. } => ErrorSeverity::High,
 
             // Critical severity - system errors
             EtlError::ResourceExhaustedError { .. } => ErrorSeverity::Critical,
@@ -142,6 +291,8 @@ impl EtlError {
 
     pub fn category(&self) -> ErrorCategory {
         match self {
+            EtlError::Context { source, .. } => source.category(),
+
             EtlError::ConfigValidationError { .. }
             | EtlError::MissingConfigError { .. }
             | EtlError::InvalidConfigValueError { .. }
@@ -150,7 +301,8 @@ impl EtlError {
             EtlError::ApiError { .. }
             | EtlError::TimeoutError { .. }
             | EtlError::RateLimitError { .. }
-            | EtlError::ServiceUnavailableError { .. } => ErrorCategory::Network,
+            | EtlError::ServiceUnavailableError { .. }
+            | EtlError::ResponseTooLarge { .. } => ErrorCategory::Network,
 
             EtlError::DataValidationError { .. }
             | EtlError::ProcessingError { .. }
@@ -170,12 +322,21 @@ impl EtlError {
 
             EtlError::ValidationError { .. } => ErrorCategory::DataProcessing,
             EtlError::PipelineExecution(_) => ErrorCategory::DataProcessing,
+            EtlError::Interrupted { .. } => ErrorCategory::System,
+        }
+    }
+
+    /// CLI 的退出碼：中斷時為 130，其餘依嚴重程度
+    pub fn exit_code(&self) -> i32 {
+        match self.root() {
+            EtlError::Interrupted { .. } => crate::utils::shutdown::INTERRUPTED_EXIT_CODE,
+            _ => self.severity().exit_code(),
         }
     }
 
     pub fn is_retryable(&self) -> bool {
         matches!(
-            self,
+            self.root(),
             EtlError::ApiError { .. }
                 | EtlError::TimeoutError { .. }
                 | EtlError::RateLimitError { .. }
@@ -185,7 +346,7 @@ impl EtlError {
     }
 
     pub fn recovery_suggestion(&self) -> &'static str {
-        match self {
+        match self.root() {
             EtlError::ConfigValidationError { .. } => "Check configuration values and restart",
             EtlError::MissingConfigError { .. } => "Set required configuration and restart",
             EtlError::InvalidConfigValueError { .. } => "Fix configuration value and restart",
@@ -194,18 +355,25 @@ impl EtlError {
             EtlError::TimeoutError { .. } => "Increase timeout values or check network latency",
             EtlError::RateLimitError { .. } => "Reduce request rate or implement backoff",
             EtlError::ServiceUnavailableError { .. } => "Wait for service to become available",
+            EtlError::ResponseTooLarge { .. } => {
+                "Narrow the request or raise extract.max_response_bytes / extract.max_records"
+            }
             EtlError::DataValidationError { .. } => "Check input data format and quality",
             EtlError::TransformationError { .. } => "Review data transformation logic",
             EtlError::ResourceExhaustedError { .. } => "Increase system resources or reduce load",
             EtlError::InsufficientDataError { .. } => "Check data source availability",
             EtlError::DataQualityError { .. } => "Review data quality rules and input data",
             EtlError::PipelineExecution(_) => "Check pipeline configuration and data dependencies",
+            EtlError::Interrupted { .. } => "Resume the execution with --resume",
             _ => "Check logs for detailed error information",
         }
     }
 
     pub fn user_friendly_message(&self) -> String {
         match self {
+            EtlError::Context { context, source } => {
+                format!("{}（{}）", source.user_friendly_message(), context)
+            }
             EtlError::ConfigValidationError { field, .. } => {
                 format!("配置參數 '{}' 驗證失敗", field)
             }
@@ -218,8 +386,55 @@ impl EtlError {
             }
             EtlError::DataValidationError { .. } => "數據驗證失敗，請檢查輸入數據格式".to_string(),
             EtlError::AuthenticationError { .. } => "認證失敗，請檢查API憑證".to_string(),
+            EtlError::ResponseTooLarge { endpoint, .. } => {
+                format!("'{}' 的回應超過設定的上限", endpoint)
+            }
             EtlError::PipelineExecution(msg) => format!("Pipeline執行失敗: {}", msg),
+            EtlError::Interrupted { .. } => "執行已中斷，已完成的部分已保存".to_string(),
             _ => "處理過程中發生錯誤".to_string(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_context_keeps_root_classification() {
+        let error = EtlError::TimeoutError {
+            operation: "GET /users".to_string(),
+            timeout_seconds: 30,
+        }
+        .at_endpoint("https://api.example.com/users")
+        .at_stage("extract")
+        .in_pipeline("users")
+        .in_pipeline("outer");
+
+        assert!(matches!(error.root(), EtlError::TimeoutError { .. }));
+        assert_eq!(error.category().code(), "network");
+        assert_eq!(error.severity(), ErrorSeverity::Medium);
+        assert!(error.is_retryable());
+        assert_eq!(error.exit_code(), 2);
+        assert_eq!(
+            error.context().unwrap(),
+            &ErrorContext {
+                pipeline: Some("users".to_string()),
+                stage: Some("extract".to_string()),
+                endpoint: Some("https://api.example.com/users".to_string()),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "pipeline 'users', extract stage, endpoint https://api.example.com/users: \
+             Network timeout: GET /users took longer than 30s"
+        );
+        // 原始錯誤可從 source 鏈取得
+        assert!(error
+            .source()
+            .unwrap()
+            .to_string()
+            .starts_with("Network timeout"));
+    }
+}
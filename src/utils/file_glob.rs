@@ -0,0 +1,111 @@
+use crate::utils::error::{EtlError, Result};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// 將 glob 轉為正規表示式：`*` 與 `?` 不跨目錄，`**/` 比對零或多層目錄
+fn glob_regex(pattern: &str) -> Result<Regex> {
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            other => regex.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).map_err(|e| EtlError::InvalidConfigValueError {
+        field: "source.files.pattern".to_string(),
+        value: pattern.to_string(),
+        reason: format!("Invalid glob pattern: {}", e),
+    })
+}
+
+/// 第一個含萬用字元的路徑段之前的目錄，從這裡開始走訪
+pub fn base_dir(pattern: &str) -> &str {
+    let wildcard = pattern.find(['*', '?']).unwrap_or(pattern.len());
+    match pattern[..wildcard].rfind('/') {
+        Some(0) => "/",
+        Some(index) => &pattern[..index],
+        None => ".",
+    }
+}
+
+/// 展開 glob（例如 `input/**/*.csv`），返回依路徑排序的檔案
+pub fn expand(pattern: &str) -> Result<Vec<PathBuf>> {
+    let regex = glob_regex(pattern)?;
+    let base = base_dir(pattern);
+    let mut matches = Vec::new();
+    let mut pending = vec![PathBuf::from(base)];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if regex.is_match(&normalize(&path, base)) {
+                matches.push(path);
+            }
+        }
+    }
+    matches.sort();
+    Ok(matches)
+}
+
+/// 以 `/` 分隔路徑，並去掉走訪時加上的 "./" 前綴，讓路徑與 glob 的寫法一致
+fn normalize(path: &Path, base: &str) -> String {
+    let text = path.to_string_lossy().replace('\\', "/");
+    if base == "." {
+        text.strip_prefix("./").unwrap_or(&text).to_string()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_expand_recursive_glob() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().to_string_lossy().replace('\\', "/");
+        for file in ["a.csv", "day1/b.csv", "day1/deep/c.csv", "day1/notes.txt"] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "id\n1\n").unwrap();
+        }
+
+        let names = |pattern: String| -> Vec<String> {
+            expand(&pattern)
+                .unwrap()
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(dir.path())
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+        assert_eq!(
+            names(format!("{}/**/*.csv", root)),
+            ["a.csv", "day1/b.csv", "day1/deep/c.csv"]
+        );
+        assert_eq!(names(format!("{}/day1/*.csv", root)), ["day1/b.csv"]);
+        assert_eq!(names(format!("{}/?.csv", root)), ["a.csv"]);
+        assert!(names(format!("{}/missing/*.csv", root)).is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+use crate::core::progress_file::{PipelineProgressStatus, PipelineStage};
+use crate::core::sequence_state::SequenceStatus;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 執行進度快照，用於心跳日誌與存活檔
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressSnapshot {
+    pub timestamp: String,
+    pub current_pipeline: Option<String>,
+    pub pipelines_completed: usize,
+    pub pipelines_total: usize,
+    pub records_processed: usize,
+    pub units_completed: usize,
+    pub units_total: usize,
+    pub elapsed_secs: u64,
+    pub records_per_sec: f64,
+    pub eta_secs: Option<u64>,
+}
+
+#[derive(Debug)]
+struct ProgressState {
+    started_at: Instant,
+    pipeline_started_at: Instant,
+    current_pipeline: Option<String>,
+    pipelines_completed: usize,
+    pipelines_total: usize,
+    records_processed: usize,
+    units_completed: usize,
+    units_total: usize,
+}
+
+/// 接收 ProgressTracker 轉送的執行事件，例如依事件重寫的進度檔
+pub trait ProgressSink: Send + Sync + std::fmt::Debug {
+    fn begin_pipeline(&self, name: &str);
+    fn stage_finished(&self, name: &str, stage: PipelineStage, records: usize);
+    fn finish_pipeline(&self, name: &str, status: PipelineProgressStatus);
+    fn fail_pipeline(&self, name: &str, error: &str);
+    fn finish(&self, status: SequenceStatus);
+}
+
+/// 追蹤序列執行進度（目前 Pipeline、已處理記錄數、工作單位）
+///
+/// 序列的進度事件都經由這裡：心跳與存活檔讀取快照，進度檔等 sink 收到轉送的事件。
+#[derive(Debug)]
+pub struct ProgressTracker {
+    state: Mutex<ProgressState>,
+    sink: Mutex<Option<Arc<dyn ProgressSink>>>,
+}
+
+impl ProgressTracker {
+    pub fn new(pipelines_total: usize) -> Self {
+        let now = Instant::now();
+        Self {
+            state: Mutex::new(ProgressState {
+                started_at: now,
+                pipeline_started_at: now,
+                current_pipeline: None,
+                pipelines_completed: 0,
+                pipelines_total,
+                records_processed: 0,
+                units_completed: 0,
+                units_total: 0,
+            }),
+            sink: Mutex::new(None),
+        }
+    }
+
+    /// 設定本次執行的事件接收端（每次執行開始時替換）
+    pub fn set_sink(&self, sink: Option<Arc<dyn ProgressSink>>) {
+        if let Ok(mut current) = self.sink.lock() {
+            *current = sink;
+        }
+    }
+
+    fn notify(&self, event: impl FnOnce(&dyn ProgressSink)) {
+        let sink = self.sink.lock().ok().and_then(|sink| sink.clone());
+        if let Some(sink) = sink {
+            event(sink.as_ref());
+        }
+    }
+
+    pub fn begin_pipeline(&self, name: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.current_pipeline = Some(name.to_string());
+            state.pipeline_started_at = Instant::now();
+            state.units_completed = 0;
+            state.units_total = 0;
+        }
+        self.notify(|sink| sink.begin_pipeline(name));
+    }
+
+    /// 階段完成；擷取的筆數計入已處理記錄數
+    pub fn stage_finished(&self, name: &str, stage: PipelineStage, records: usize) {
+        if stage == PipelineStage::Extract {
+            self.add_records(records);
+        }
+        self.notify(|sink| sink.stage_finished(name, stage, records));
+    }
+
+    /// Pipeline 完成或略過，計入已結束的 Pipeline 數
+    pub fn finish_pipeline(&self, name: &str, status: PipelineProgressStatus) {
+        if let Ok(mut state) = self.state.lock() {
+            state.pipelines_completed += 1;
+            state.current_pipeline = None;
+        }
+        self.notify(|sink| sink.finish_pipeline(name, status));
+    }
+
+    /// Pipeline 失敗或被中斷，不計入已結束的 Pipeline 數
+    pub fn fail_pipeline(&self, name: &str, error: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.current_pipeline = None;
+        }
+        self.notify(|sink| sink.fail_pipeline(name, error));
+    }
+
+    /// 序列結束
+    pub fn finish(&self, status: SequenceStatus) {
+        self.notify(|sink| sink.finish(status));
+    }
+
+    /// 累加已處理記錄數
+    pub fn add_records(&self, count: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            state.records_processed += count;
+        }
+    }
+
+    /// 更新目前 Pipeline 內的工作單位進度（例如參數化 API 呼叫）
+    pub fn set_units(&self, completed: usize, total: usize) {
+        if let Ok(mut state) = self.state.lock() {
+            state.units_completed = completed;
+            state.units_total = total;
+        }
+    }
+
+    pub fn snapshot(&self) -> Option<ProgressSnapshot> {
+        let state = self.state.lock().ok()?;
+        let elapsed = state.started_at.elapsed();
+        let records_per_sec = if elapsed.as_secs_f64() > 0.0 {
+            state.records_processed as f64 / elapsed.as_secs_f64()
+        } else {
+            0.0
+        };
+
+        // 優先以 Pipeline 內的工作單位估算，否則以已完成的 Pipeline 數估算
+        let eta_secs = if state.units_completed > 0 && state.units_total > state.units_completed {
+            let per_unit =
+                state.pipeline_started_at.elapsed().as_secs_f64() / state.units_completed as f64;
+            Some((per_unit * (state.units_total - state.units_completed) as f64) as u64)
+        } else if state.pipelines_completed > 0 {
+            let per_pipeline = elapsed.as_secs_f64() / state.pipelines_completed as f64;
+            let remaining = state
+                .pipelines_total
+                .saturating_sub(state.pipelines_completed);
+            Some((per_pipeline * remaining as f64) as u64)
+        } else {
+            None
+        };
+
+        Some(ProgressSnapshot {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            current_pipeline: state.current_pipeline.clone(),
+            pipelines_completed: state.pipelines_completed,
+            pipelines_total: state.pipelines_total,
+            records_processed: state.records_processed,
+            units_completed: state.units_completed,
+            units_total: state.units_total,
+            elapsed_secs: elapsed.as_secs(),
+            records_per_sec,
+            eta_secs,
+        })
+    }
+}
+
+/// 週期性輸出心跳並更新存活檔，Drop 時停止
+pub struct Heartbeat {
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl Heartbeat {
+    pub fn spawn(
+        tracker: Arc<ProgressTracker>,
+        interval: Duration,
+        liveness_file: Option<PathBuf>,
+    ) -> Self {
+        let handle = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await; // 第一次 tick 立即返回
+            loop {
+                ticker.tick().await;
+                let Some(snapshot) = tracker.snapshot() else {
+                    continue;
+                };
+                log_heartbeat(&snapshot);
+                if let Some(path) = &liveness_file {
+                    if let Err(e) = write_liveness_file(path, &snapshot) {
+                        tracing::warn!(
+                            "💓 Failed to update liveness file {}: {}",
+                            path.display(),
+                            e
+                        );
+                    }
+                }
+            }
+        });
+        Self { handle }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+fn log_heartbeat(snapshot: &ProgressSnapshot) {
+    let eta = snapshot
+        .eta_secs
+        .map(|secs| format!("{}s", secs))
+        .unwrap_or_else(|| "unknown".to_string());
+    tracing::info!(
+        "💓 Heartbeat: pipeline={} ({}/{} done), records={}, rate={:.1} rec/s, units={}/{}, elapsed={}s, ETA={}",
+        snapshot.current_pipeline.as_deref().unwrap_or("-"),
+        snapshot.pipelines_completed,
+        snapshot.pipelines_total,
+        snapshot.records_processed,
+        snapshot.records_per_sec,
+        snapshot.units_completed,
+        snapshot.units_total,
+        snapshot.elapsed_secs,
+        eta
+    );
+}
+
+/// 以暫存檔加改名的方式寫入，避免外部監控讀到半寫入的內容
+pub fn write_liveness_file(
+    path: &std::path::Path,
+    snapshot: &ProgressSnapshot,
+) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(snapshot)?)?;
+    std::fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_tracks_progress() {
+        let tracker = ProgressTracker::new(2);
+        tracker.begin_pipeline("users");
+        tracker.set_units(5, 10);
+        tracker.add_records(50);
+
+        let snapshot = tracker.snapshot().unwrap();
+        assert_eq!(snapshot.current_pipeline.as_deref(), Some("users"));
+        assert_eq!(snapshot.records_processed, 50);
+        assert!(snapshot.eta_secs.is_some());
+
+        tracker.finish_pipeline("users", PipelineProgressStatus::Completed);
+        let snapshot = tracker.snapshot().unwrap();
+        assert_eq!(snapshot.pipelines_completed, 1);
+        assert!(snapshot.current_pipeline.is_none());
+    }
+
+    #[test]
+    fn test_events_forwarded_to_progress_file() {
+        use crate::core::progress_file::{read_progress_file, ProgressFile};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("progress.json");
+        let tracker = ProgressTracker::new(2);
+        tracker.set_sink(Some(Arc::new(ProgressFile::new(
+            &path,
+            "exec_1",
+            &["users", "orders"],
+        ))));
+
+        tracker.begin_pipeline("users");
+        tracker.stage_finished("users", PipelineStage::Extract, 10);
+        tracker.finish_pipeline("users", PipelineProgressStatus::Completed);
+        tracker.begin_pipeline("orders");
+        tracker.fail_pipeline("orders", "boom");
+        tracker.finish(SequenceStatus::Failed);
+
+        // 心跳快照與進度檔來自同一組事件
+        let snapshot = tracker.snapshot().unwrap();
+        assert_eq!(snapshot.records_processed, 10);
+        assert_eq!(snapshot.pipelines_completed, 1);
+        let document = read_progress_file(&path).unwrap();
+        assert_eq!(document.status, SequenceStatus::Failed);
+        assert_eq!(document.pipelines[0].records_extracted, Some(10));
+        assert_eq!(document.pipelines[1].status, PipelineProgressStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_writes_liveness_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("liveness.json");
+        let tracker = Arc::new(ProgressTracker::new(1));
+        tracker.begin_pipeline("users");
+
+        let heartbeat = Heartbeat::spawn(
+            Arc::clone(&tracker),
+            Duration::from_millis(10),
+            Some(path.clone()),
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(heartbeat);
+
+        let content: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(content["current_pipeline"], "users");
+    }
+}
@@ -1,16 +1,95 @@
+use crate::utils::redact::redact_sensitive;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-pub fn init_cli_logger(verbose: bool) {
-    let filter = if verbose {
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("samll_etl=debug,info"))
+/// CLI 日誌格式：text 為給人看的精簡輸出，json 為每行一筆結構化記錄（供 ELK/Datadog 收集）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// 日誌輸出前先遮蔽敏感資料（憑證、敏感欄位的值）；每筆事件格式化完成後整行寫到 stdout
+/// （stdout 用於資料輸出時改寫到 stderr）
+#[derive(Default)]
+struct RedactingWriter {
+    buffer: Vec<u8>,
+    stderr: bool,
+}
+
+impl RedactingWriter {
+    fn stderr() -> Self {
+        Self {
+            buffer: Vec::new(),
+            stderr: true,
+        }
+    }
+}
+
+impl Write for RedactingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RedactingWriter {
+    fn drop(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let line = redact_sensitive(&String::from_utf8_lossy(&self.buffer));
+        let _ = if self.stderr {
+            std::io::stderr().lock().write_all(line.as_bytes())
+        } else {
+            std::io::stdout().lock().write_all(line.as_bytes())
+        };
+    }
+}
+
+fn default_filter(verbose: bool) -> EnvFilter {
+    let directives = if verbose {
+        "samll_etl=debug,info"
     } else {
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("samll_etl=info"))
+        "samll_etl=info"
     };
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(directives))
+}
+
+pub fn init_logger(verbose: bool, format: LogFormat) {
+    match format {
+        LogFormat::Text => init_cli_logger(verbose),
+        LogFormat::Json => init_json_logger(verbose),
+    }
+}
 
+/// 與 `init_logger` 相同，但日誌寫到 stderr，讓 stdout 只包含資料（transform 子命令）
+pub fn init_stderr_logger(verbose: bool, format: LogFormat) {
+    match format {
+        LogFormat::Text => init_text_logger(verbose, RedactingWriter::stderr),
+        LogFormat::Json => init_json_logger_with(verbose, RedactingWriter::stderr),
+    }
+}
+
+pub fn init_cli_logger(verbose: bool) {
+    init_text_logger(verbose, RedactingWriter::default)
+}
+
+fn init_text_logger(verbose: bool, writer: fn() -> RedactingWriter) {
     tracing_subscriber::registry()
-        .with(filter)
+        .with(default_filter(verbose))
         .with(
             tracing_subscriber::fmt::layer()
+                .with_writer(writer)
                 .with_target(false)
                 .with_thread_ids(false)
                 .with_file(false)
@@ -20,6 +99,30 @@ pub fn init_cli_logger(verbose: bool) {
         .init();
 }
 
+/// 結構化 JSON 日誌
+///
+/// 事件欄位攤平在最上層，所在 span 的欄位（execution_id、pipeline、stage）放在 `span`；
+/// span 結束時另輸出一筆記錄，包含該階段的 records 與 duration_ms。
+pub fn init_json_logger(verbose: bool) {
+    init_json_logger_with(verbose, RedactingWriter::default)
+}
+
+fn init_json_logger_with(verbose: bool, writer: fn() -> RedactingWriter) {
+    tracing_subscriber::registry()
+        .with(default_filter(verbose))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_span_events(FmtSpan::CLOSE)
+                .with_target(false),
+        )
+        .init();
+}
+
 pub fn init_lambda_logger() {
     let filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("samll_etl=info"));
@@ -28,6 +131,7 @@ pub fn init_lambda_logger() {
         .with(filter)
         .with(
             tracing_subscriber::fmt::layer()
+                .with_writer(RedactingWriter::default)
                 .with_target(false)
                 .with_thread_ids(false)
                 .with_file(false)
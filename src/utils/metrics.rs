@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// 單一階段的吞吐量
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StageThroughput {
+    pub records: usize,
+    pub duration_ms: u64,
+    pub records_per_sec: f64,
+    #[serde(skip)]
+    pub duration: Duration, // 未截斷的實際耗時，供其他速率計算使用
+}
+
+impl StageThroughput {
+    pub fn new(records: usize, duration: Duration) -> Self {
+        Self {
+            records,
+            duration_ms: duration.as_millis() as u64,
+            records_per_sec: per_second(records as f64, duration),
+            duration,
+        }
+    }
+}
+
+/// Pipeline 吞吐量報告（各階段 records/sec 與寫入 MB/s）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ThroughputReport {
+    pub extract: StageThroughput,
+    pub transform: StageThroughput,
+    pub load: StageThroughput,
+    pub bytes_written: Option<u64>,
+    pub write_mb_per_sec: Option<f64>,
+}
+
+impl ThroughputReport {
+    pub fn new(
+        extract: StageThroughput,
+        transform: StageThroughput,
+        load: StageThroughput,
+        bytes_written: Option<u64>,
+    ) -> Self {
+        let write_mb_per_sec =
+            bytes_written.map(|bytes| per_second(bytes as f64 / BYTES_PER_MB, load.duration));
+
+        Self {
+            extract,
+            transform,
+            load,
+            bytes_written,
+            write_mb_per_sec,
+        }
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+
+    pub fn log(&self, pipeline_name: &str) {
+        tracing::info!(
+            "📈 {}: Throughput - extract: {:.1} rec/s, transform: {:.1} rec/s, load: {:.1} rec/s, write: {}",
+            pipeline_name,
+            self.extract.records_per_sec,
+            self.transform.records_per_sec,
+            self.load.records_per_sec,
+            self.write_mb_per_sec
+                .map(|mb| format!("{:.2} MB/s", mb))
+                .unwrap_or_else(|| "n/a".to_string())
+        );
+    }
+}
+
+/// Pipeline 各階段指標：耗時、寫入位元組、HTTP 呼叫與重試次數
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StageMetrics {
+    pub extract_ms: u64,
+    pub transform_ms: u64,
+    pub load_ms: u64,
+    pub bytes_written: Option<u64>,
+    pub http_calls: u64,
+    pub http_retries: u64,
+}
+
+impl StageMetrics {
+    /// 由吞吐量報告與 Pipeline 回報的 metadata（bytes_written、http_calls、http_retries）組成
+    pub fn new(
+        throughput: &ThroughputReport,
+        metadata: &std::collections::HashMap<String, serde_json::Value>,
+    ) -> Self {
+        let count = |key: &str| metadata.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        Self {
+            extract_ms: throughput.extract.duration_ms,
+            transform_ms: throughput.transform.duration_ms,
+            load_ms: throughput.load.duration_ms,
+            bytes_written: throughput.bytes_written,
+            http_calls: count("http_calls"),
+            http_retries: count("http_retries"),
+        }
+    }
+
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+/// 計算每秒速率，避免除以零
+pub fn per_second(amount: f64, duration: Duration) -> f64 {
+    let secs = duration.as_secs_f64();
+    if secs > 0.0 {
+        amount / secs
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_throughput_rate() {
+        let stage = StageThroughput::new(500, Duration::from_millis(250));
+        assert_eq!(stage.duration_ms, 250);
+        assert!((stage.records_per_sec - 2000.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_zero_duration_does_not_divide_by_zero() {
+        let stage = StageThroughput::new(10, Duration::ZERO);
+        assert_eq!(stage.records_per_sec, 0.0);
+    }
+
+    #[test]
+    fn test_write_mb_per_sec() {
+        let report = ThroughputReport::new(
+            StageThroughput::default(),
+            StageThroughput::default(),
+            StageThroughput::new(1, Duration::from_secs(2)),
+            Some(4 * 1024 * 1024),
+        );
+        assert_eq!(report.write_mb_per_sec, Some(2.0));
+        assert_eq!(report.to_value()["bytes_written"], 4 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_write_mb_per_sec_uses_sub_millisecond_duration() {
+        // 寫入耗時不足 1ms 時不應被截斷為 0 而回報 0 MB/s
+        let report = ThroughputReport::new(
+            StageThroughput::default(),
+            StageThroughput::default(),
+            StageThroughput::new(1, Duration::from_micros(500)),
+            Some(1024 * 1024),
+        );
+        assert_eq!(report.load.duration_ms, 0);
+        assert_eq!(report.write_mb_per_sec, Some(2000.0));
+    }
+
+    #[test]
+    fn test_stage_metrics_from_throughput_and_metadata() {
+        let report = ThroughputReport::new(
+            StageThroughput::new(10, Duration::from_millis(120)),
+            StageThroughput::new(10, Duration::from_millis(30)),
+            StageThroughput::new(10, Duration::from_millis(50)),
+            Some(2048),
+        );
+        let metadata = std::collections::HashMap::from([
+            ("http_calls".to_string(), serde_json::json!(4)),
+            ("http_retries".to_string(), serde_json::json!(1)),
+        ]);
+
+        let metrics = StageMetrics::new(&report, &metadata);
+        assert_eq!(metrics.extract_ms, 120);
+        assert_eq!(metrics.transform_ms, 30);
+        assert_eq!(metrics.load_ms, 50);
+        assert_eq!(metrics.bytes_written, Some(2048));
+        assert_eq!(metrics.http_calls, 4);
+        assert_eq!(metrics.http_retries, 1);
+    }
+}
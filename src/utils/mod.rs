@@ -1,4 +1,16 @@
+pub mod budget;
+pub mod encoding;
+pub mod encryption;
 pub mod error;
+pub mod file_glob;
+pub mod heartbeat;
 pub mod logger;
+pub mod metrics;
 pub mod monitor;
+pub mod prometheus;
+pub mod rate_limiter;
+pub mod redact;
+pub mod schedule;
+pub mod shutdown;
 pub mod validation;
+pub mod xml;
@@ -1,9 +1,16 @@
 #[cfg(feature = "cli")]
-use std::sync::{Arc, Mutex};
+use crate::utils::prometheus;
 #[cfg(feature = "cli")]
-use std::time::{Duration, Instant};
+use std::sync::Mutex;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::Duration;
 #[cfg(feature = "cli")]
-use sysinfo::{Pid, RefreshKind, System};
+use std::time::Instant;
+#[cfg(feature = "cli")]
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
 
 #[cfg(feature = "cli")]
 #[derive(Debug, Clone)]
@@ -21,6 +28,7 @@ pub struct SystemMonitor {
     pid: Pid,
     start_time: Instant,
     peak_memory: Arc<Mutex<u64>>,
+    stage_memory: Mutex<Vec<(String, u64)>>, // 各階段結束時的記憶體（MB）
     enabled: bool,
 }
 
@@ -39,6 +47,7 @@ impl SystemMonitor {
             pid,
             start_time: Instant::now(),
             peak_memory: Arc::new(Mutex::new(0)),
+            stage_memory: Mutex::new(Vec::new()),
             enabled,
         }
     }
@@ -67,6 +76,15 @@ impl SystemMonitor {
         }
         let peak_memory = *peak;
 
+        // 同步更新 Prometheus 行程指標
+        let registry = prometheus::global();
+        registry.set_gauge(
+            prometheus::PROCESS_CPU_USAGE,
+            &[],
+            process.cpu_usage() as f64,
+        );
+        registry.set_gauge(prometheus::PROCESS_MEMORY, &[], process.memory() as f64);
+
         Some(SystemStats {
             cpu_usage: process.cpu_usage(),
             memory_usage_mb: memory_mb,
@@ -90,6 +108,28 @@ impl SystemMonitor {
         }
     }
 
+    /// 記錄階段結束時的記憶體用量，結束時列出用量最高的階段
+    pub fn record_stage(&self, stage: &str) {
+        if let Some(stats) = self.get_stats() {
+            tracing::debug!("📊 {} - Memory: {}MB", stage, stats.memory_usage_mb);
+            if let Ok(mut stage_memory) = self.stage_memory.lock() {
+                stage_memory.push((stage.to_string(), stats.memory_usage_mb));
+            }
+        }
+    }
+
+    /// 記憶體用量最高的階段（依用量由高到低）
+    pub fn top_stages(&self, limit: usize) -> Vec<(String, u64)> {
+        let mut stages = self
+            .stage_memory
+            .lock()
+            .map(|stages| stages.clone())
+            .unwrap_or_default();
+        stages.sort_by_key(|(_, memory_mb)| std::cmp::Reverse(*memory_mb));
+        stages.truncate(limit);
+        stages
+    }
+
     pub fn log_final_stats(&self) {
         if let Some(stats) = self.get_stats() {
             tracing::info!(
@@ -97,12 +137,40 @@ impl SystemMonitor {
                 stats.elapsed_time,
                 stats.peak_memory_mb
             );
+            let top_stages = self.top_stages(3);
+            if !top_stages.is_empty() {
+                tracing::info!(
+                    "📊 Highest memory by stage: {}",
+                    top_stages
+                        .iter()
+                        .map(|(stage, memory_mb)| format!("{} {}MB", stage, memory_mb))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
         }
     }
 
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
+
+    /// 啟動 Prometheus 指標端點；行程資源指標於每次抓取前刷新
+    #[cfg(feature = "metrics-server")]
+    pub async fn serve_metrics(
+        self: Arc<Self>,
+        address: &str,
+    ) -> std::io::Result<prometheus::MetricsServer> {
+        let server = prometheus::MetricsServer::bind(address, move || {
+            self.get_stats();
+        })
+        .await?;
+        tracing::info!(
+            "📡 Prometheus metrics available at http://{}/metrics",
+            server.local_addr()
+        );
+        Ok(server)
+    }
 }
 
 #[cfg(feature = "cli")]
@@ -126,7 +194,157 @@ impl SystemMonitor {
 
     pub fn log_final_stats(&self) {}
 
+    pub fn record_stage(&self, _stage: &str) {}
+
     pub fn is_enabled(&self) -> bool {
         false
     }
 }
+
+/// 目前行程的常駐記憶體（RSS，MB）；無法取得時返回 None
+#[cfg(feature = "cli")]
+pub fn process_memory_mb() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        false,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+    system
+        .process(pid)
+        .map(|process| process.memory() / 1024 / 1024)
+}
+
+#[cfg(not(feature = "cli"))]
+pub fn process_memory_mb() -> Option<u64> {
+    None
+}
+
+type MemorySampler = Arc<dyn Fn() -> Option<u64> + Send + Sync>;
+
+/// 記憶體背壓：RSS 超過上限時暫停擷取，等待記憶體回落（每個 Pipeline 一次，最多 `max_pause`，
+/// 仍超過上限則 Pipeline 失敗），並讓上下文提早將結果寫入暫存檔，避免大型資料集讓行程被 OOM 終止
+pub struct MemoryPressure {
+    limit_mb: u64,
+    max_pause: Duration,
+    poll_interval: Duration,
+    sampler: MemorySampler,
+    pauses: AtomicU64,
+}
+
+impl std::fmt::Debug for MemoryPressure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryPressure")
+            .field("limit_mb", &self.limit_mb)
+            .field("max_pause", &self.max_pause)
+            .field("pauses", &self.pauses())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MemoryPressure {
+    pub fn new(limit_mb: u64, max_pause: Duration) -> Self {
+        Self {
+            limit_mb,
+            max_pause,
+            poll_interval: Duration::from_millis(500),
+            sampler: Arc::new(process_memory_mb),
+            pauses: AtomicU64::new(0),
+        }
+    }
+
+    /// 以自訂方式取得記憶體用量（例如容器的 cgroup 用量）
+    pub fn with_sampler(
+        mut self,
+        sampler: impl Fn() -> Option<u64> + Send + Sync + 'static,
+        poll_interval: Duration,
+    ) -> Self {
+        self.sampler = Arc::new(sampler);
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn limit_mb(&self) -> u64 {
+        self.limit_mb
+    }
+
+    pub fn current_mb(&self) -> Option<u64> {
+        (self.sampler)()
+    }
+
+    pub fn is_over_limit(&self) -> bool {
+        self.current_mb()
+            .is_some_and(|memory_mb| memory_mb > self.limit_mb)
+    }
+
+    /// 已觸發背壓暫停的次數
+    pub fn pauses(&self) -> u64 {
+        self.pauses.load(Ordering::Relaxed)
+    }
+
+    /// 超過上限時暫停，直到記憶體回落或達到最長暫停時間；返回是否曾暫停
+    pub async fn wait_for_headroom(&self, label: &str) -> bool {
+        let Some(memory_mb) = self
+            .current_mb()
+            .filter(|memory_mb| *memory_mb > self.limit_mb)
+        else {
+            return false;
+        };
+        self.pauses.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            "⏸️ {}: Memory {}MB exceeds limit {}MB, pausing extraction",
+            label,
+            memory_mb,
+            self.limit_mb
+        );
+        let started = std::time::Instant::now();
+        while self.is_over_limit() {
+            if started.elapsed() >= self.max_pause {
+                tracing::warn!(
+                    "⚠️ {}: Memory still above {}MB after {:?}",
+                    label,
+                    self.limit_mb,
+                    self.max_pause
+                );
+                return true;
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+        tracing::info!(
+            "▶️ {}: Memory back under {}MB after {:?}, resuming extraction",
+            label,
+            self.limit_mb,
+            started.elapsed()
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pauses_until_memory_drops() {
+        let samples = Arc::new(AtomicU64::new(0));
+        let sampler_calls = Arc::clone(&samples);
+        // 前三次取樣超過上限，之後回落
+        let pressure = MemoryPressure::new(100, Duration::from_secs(5)).with_sampler(
+            move || {
+                let call = sampler_calls.fetch_add(1, Ordering::Relaxed);
+                Some(if call < 3 { 150 } else { 80 })
+            },
+            Duration::from_millis(1),
+        );
+
+        assert!(pressure.wait_for_headroom("test").await);
+        assert_eq!(pressure.pauses(), 1);
+        assert!(!pressure.wait_for_headroom("test").await);
+
+        let stuck = MemoryPressure::new(100, Duration::from_millis(5))
+            .with_sampler(|| Some(500), Duration::from_millis(1));
+        assert!(stuck.wait_for_headroom("test").await);
+        assert!(stuck.is_over_limit());
+    }
+}
@@ -0,0 +1,315 @@
+use crate::utils::redact::redact_sensitive;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+
+/// 記錄數計數器（依 pipeline 標籤區分）
+pub const RECORDS_EXTRACTED: &str = "etl_records_extracted_total";
+pub const RECORDS_TRANSFORMED: &str = "etl_records_transformed_total";
+pub const RECORDS_LOADED: &str = "etl_records_loaded_total";
+/// HTTP 請求計數、重試計數（OAuth2 刷新 token 後重送與 Pipeline 層級重試）與延遲分佈
+pub const HTTP_REQUESTS: &str = "etl_http_requests_total";
+pub const HTTP_RETRIES: &str = "etl_http_retries_total";
+pub const HTTP_REQUEST_DURATION: &str = "etl_http_request_duration_seconds";
+/// 行程資源（由 SystemMonitor 更新）
+pub const PROCESS_CPU_USAGE: &str = "etl_process_cpu_usage_percent";
+pub const PROCESS_MEMORY: &str = "etl_process_memory_bytes";
+
+/// 延遲直方圖的桶上限（秒）
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0];
+
+const HELP: [(&str, &str); 8] = [
+    (RECORDS_EXTRACTED, "Records extracted per pipeline"),
+    (RECORDS_TRANSFORMED, "Records transformed per pipeline"),
+    (RECORDS_LOADED, "Records loaded per pipeline"),
+    (HTTP_REQUESTS, "HTTP requests sent per pipeline and status"),
+    (
+        HTTP_RETRIES,
+        "Retries per pipeline (OAuth2 token refresh and pipeline retry attempts)",
+    ),
+    (HTTP_REQUEST_DURATION, "HTTP request latency in seconds"),
+    (PROCESS_CPU_USAGE, "CPU usage of the ETL process"),
+    (PROCESS_MEMORY, "Resident memory of the ETL process"),
+];
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            buckets: vec![0; LATENCY_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bucket, upper) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= upper {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    counters: BTreeMap<(String, Labels), f64>,
+    gauges: BTreeMap<(String, Labels), f64>,
+    histograms: BTreeMap<(String, Labels), Histogram>,
+}
+
+/// 以 Prometheus 文字格式輸出的指標登錄表
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    metrics: Mutex<Metrics>,
+}
+
+/// 全域指標登錄表，供各 Pipeline 與 SystemMonitor 共用
+pub fn global() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::default)
+}
+
+fn key(name: &str, labels: &[(&str, &str)]) -> (String, Labels) {
+    let labels = labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    (name.to_string(), labels)
+}
+
+impl MetricsRegistry {
+    pub fn inc_counter(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            *metrics.counters.entry(key(name, labels)).or_insert(0.0) += value;
+        }
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics.gauges.insert(key(name, labels), value);
+        }
+    }
+
+    pub fn observe(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics
+                .histograms
+                .entry(key(name, labels))
+                .or_insert_with(Histogram::new)
+                .observe(value);
+        }
+    }
+
+    /// 記錄單一 Pipeline 各階段處理的記錄數
+    pub fn record_stages(
+        &self,
+        pipeline: &str,
+        extracted: usize,
+        transformed: usize,
+        loaded: usize,
+    ) {
+        let labels = [("pipeline", pipeline)];
+        self.inc_counter(RECORDS_EXTRACTED, &labels, extracted as f64);
+        self.inc_counter(RECORDS_TRANSFORMED, &labels, transformed as f64);
+        self.inc_counter(RECORDS_LOADED, &labels, loaded as f64);
+    }
+
+    /// 輸出 Prometheus text exposition format (0.0.4)
+    pub fn render(&self) -> String {
+        let Ok(metrics) = self.metrics.lock() else {
+            return String::new();
+        };
+        let mut out = String::new();
+        let mut last_name = None;
+
+        for ((name, labels), value) in &metrics.counters {
+            write_header(&mut out, &mut last_name, name, "counter");
+            let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+        }
+        for ((name, labels), value) in &metrics.gauges {
+            write_header(&mut out, &mut last_name, name, "gauge");
+            let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+        }
+        for ((name, labels), histogram) in &metrics.histograms {
+            write_header(&mut out, &mut last_name, name, "histogram");
+            for (count, upper) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                let le = upper.to_string();
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some(&le)),
+                    count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{} {}",
+                name,
+                format_labels(labels, Some("+Inf")),
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{}_sum{} {}",
+                name,
+                format_labels(labels, None),
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "{}_count{} {}",
+                name,
+                format_labels(labels, None),
+                histogram.count
+            );
+        }
+        out
+    }
+}
+
+fn write_header<'a>(out: &mut String, last_name: &mut Option<&'a str>, name: &'a str, kind: &str) {
+    if *last_name == Some(name) {
+        return;
+    }
+    if let Some((_, help)) = HELP.iter().find(|(metric, _)| *metric == name) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+    }
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    *last_name = Some(name);
+}
+
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(&redact_sensitive(v))))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 在指定位址提供 GET /metrics，供 Prometheus 抓取；Drop 時停止
+#[cfg(feature = "metrics-server")]
+pub struct MetricsServer {
+    handle: tokio::task::JoinHandle<()>,
+    local_addr: std::net::SocketAddr,
+}
+
+#[cfg(feature = "metrics-server")]
+impl MetricsServer {
+    /// refresh 於每次抓取前呼叫，用於更新即時量測的 gauge
+    pub async fn bind<F>(address: &str, refresh: F) -> std::io::Result<Self>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        let local_addr = listener.local_addr()?;
+        let refresh = std::sync::Arc::new(refresh);
+        let handle = tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    continue;
+                };
+                let refresh = std::sync::Arc::clone(&refresh);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let Ok(n) = stream.read(&mut buf).await else {
+                        return;
+                    };
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+                    let (status, body) = if path == "/metrics" {
+                        refresh();
+                        ("200 OK", global().render())
+                    } else {
+                        ("404 Not Found", "Not Found\n".to_string())
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+        Ok(Self { handle, local_addr })
+    }
+
+    pub fn local_addr(&self) -> std::net::SocketAddr {
+        self.local_addr
+    }
+}
+
+#[cfg(feature = "metrics-server")]
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let registry = MetricsRegistry::default();
+        registry.record_stages("users", 10, 8, 8);
+        registry.inc_counter(
+            HTTP_REQUESTS,
+            &[("pipeline", "users"), ("status", "200")],
+            1.0,
+        );
+        registry.observe(HTTP_REQUEST_DURATION, &[("pipeline", "users")], 0.2);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE etl_records_extracted_total counter"));
+        assert!(text.contains("etl_records_extracted_total{pipeline=\"users\"} 10"));
+        assert!(text.contains("etl_http_requests_total{pipeline=\"users\",status=\"200\"} 1"));
+        assert!(text
+            .contains("etl_http_request_duration_seconds_bucket{pipeline=\"users\",le=\"0.1\"} 0"));
+        assert!(text.contains(
+            "etl_http_request_duration_seconds_bucket{pipeline=\"users\",le=\"0.25\"} 1"
+        ));
+        assert!(text.contains("etl_http_request_duration_seconds_count{pipeline=\"users\"} 1"));
+    }
+
+    #[cfg(feature = "metrics-server")]
+    #[tokio::test]
+    async fn test_metrics_server_serves_registry() {
+        global().record_stages("server_test", 3, 3, 3);
+        let server = MetricsServer::bind("127.0.0.1:0", || {}).await.unwrap();
+        let url = format!("http://{}/metrics", server.local_addr());
+        let body = reqwest::get(&url).await.unwrap().text().await.unwrap();
+        assert!(body.contains("etl_records_loaded_total{pipeline=\"server_test\"} 3"));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::config::sequence_config::RateLimitConfig;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Token bucket 限流器：以固定速率補充 token，最多累積 burst 個
+///
+/// 以 `Arc<RateLimiter>` 在多個 Pipeline 間共享，確保整個序列對上游 API 的總請求速率。
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            requests_per_second,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        Self::new(config.requests_per_second, config.burst())
+    }
+
+    /// 取得一個 token，不足時等待到補充完成
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.requests_per_second).min(self.burst);
+                state.last_refill = now;
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.requests_per_second)
+            };
+
+            tracing::debug!("🚦 Rate limit reached, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_burst_is_available_immediately() {
+        let limiter = RateLimiter::new(1.0, 3);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_waits_when_bucket_is_empty() {
+        let limiter = RateLimiter::new(20.0, 1);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        // 第一個立即取得，之後每個約需 50ms
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}
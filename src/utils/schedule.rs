@@ -0,0 +1,182 @@
+use crate::utils::error::{EtlError, Result};
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+
+/// 最多往後搜尋的分鐘數（約 4 年，涵蓋 2 月 29 日這類少見的排程）
+const MAX_SEARCH_MINUTES: i64 = 4 * 366 * 24 * 60;
+
+/// 五欄位 cron 表達式（分 時 日 月 星期），以 UTC 計算
+///
+/// 支援 `*`、`*/N`、`A-B`、`A-B/N` 與逗號清單；星期 0 與 7 都代表星期日。
+/// 日與星期同時限定時，符合任一即觸發（與標準 cron 相同）。
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(
+                expression,
+                "Expected 5 fields: minute hour day-of-month month day-of-week",
+            ));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, expression)?;
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(fields[0], 0, 59, expression)?,
+            hours: parse_field(fields[1], 0, 23, expression)?,
+            days_of_month: parse_field(fields[2], 1, 31, expression)?,
+            months: parse_field(fields[3], 1, 12, expression)?,
+            days_of_week,
+            day_of_month_restricted: fields[2] != "*",
+            day_of_week_restricted: fields[4] != "*",
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// 計算嚴格晚於 `after` 的下一個觸發時間（精確到分鐘）
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut candidate = after
+            .with_second(0)?
+            .with_nanosecond(0)?
+            .checked_add_signed(Duration::minutes(1))?;
+
+        for _ in 0..MAX_SEARCH_MINUTES {
+            if self.matches(&candidate) {
+                return Some(candidate);
+            }
+            candidate = candidate.checked_add_signed(Duration::minutes(1))?;
+        }
+        None
+    }
+
+    fn matches(&self, time: &DateTime<Utc>) -> bool {
+        let day_of_month = self.days_of_month[time.day() as usize];
+        let day_of_week = self.days_of_week[time.weekday().num_days_from_sunday() as usize];
+        let day_matches = match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        };
+
+        self.minutes[time.minute() as usize]
+            && self.hours[time.hour() as usize]
+            && self.months[time.month() as usize]
+            && day_matches
+    }
+}
+
+fn invalid(expression: &str, reason: &str) -> EtlError {
+    EtlError::InvalidConfigValueError {
+        field: "sequence.schedule".to_string(),
+        value: expression.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// 解析單一欄位，返回以數值為索引的允許表
+fn parse_field(field: &str, min: u32, max: u32, expression: &str) -> Result<Vec<bool>> {
+    let mut allowed = vec![false; max as usize + 1];
+    let parse_number = |value: &str| -> Result<u32> {
+        value
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (min..=max).contains(n))
+            .ok_or_else(|| {
+                invalid(
+                    expression,
+                    &format!("'{}' is out of range {}-{}", value, min, max),
+                )
+            })
+    };
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| invalid(expression, &format!("Invalid step '{}'", step)))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_number(start)?, parse_number(end)?)
+        } else {
+            let value = parse_number(range)?;
+            // "5/15" 表示從 5 開始每 15 個單位
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(invalid(expression, &format!("Invalid range '{}'", range)));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            allowed[value as usize] = true;
+        }
+    }
+    Ok(allowed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_after_every_six_hours() {
+        let schedule = CronSchedule::parse("0 */6 * * *").unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 6, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(now),
+            Some(Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap())
+        );
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 19, 30, 15).unwrap();
+        assert_eq!(
+            schedule.next_after(now),
+            Some(Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_day_of_week_and_lists() {
+        // 週一到週五 9:15 與 17:15
+        let schedule = CronSchedule::parse("15 9,17 * * 1-5").unwrap();
+        // 2024-03-02 是星期六
+        let now = Utc.with_ymd_and_hms(2024, 3, 2, 10, 0, 0).unwrap();
+        assert_eq!(
+            schedule.next_after(now),
+            Some(Utc.with_ymd_and_hms(2024, 3, 4, 9, 15, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 5-1 * * *").is_err());
+    }
+}
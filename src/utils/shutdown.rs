@@ -0,0 +1,82 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 因訊號中斷結束時的退出碼（與 shell 對 SIGINT 的慣例相同）
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// 收到 SIGINT/SIGTERM 後設定的停止旗標，可在多個元件間共享
+///
+/// Pipeline 在每次呼叫之間檢查旗標：進行中的請求會完成，已擷取的記錄照常輸出
+/// （標記為 partial），序列在下一個 Pipeline 前停止並保存狀態。
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownSignal {
+    requested: Arc<AtomicBool>,
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// 在背景監聽 Ctrl+C 與 SIGTERM：第一次設定停止旗標，第二次立即結束行程
+    pub fn listen(&self) {
+        let signal = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let name = wait_for_signal().await;
+                if signal.is_requested() {
+                    tracing::warn!("🛑 Received {} again, exiting immediately", name);
+                    std::process::exit(INTERRUPTED_EXIT_CODE);
+                }
+                tracing::warn!(
+                    "🛑 Received {}, finishing in-flight work and saving progress (send again to exit immediately)",
+                    name
+                );
+                signal.request();
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => tokio::select! {
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+            _ = terminate.recv() => "SIGTERM",
+        },
+        Err(_) => {
+            let _ = tokio::signal::ctrl_c().await;
+            "SIGINT"
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "Ctrl+C"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_is_shared_between_clones() {
+        let signal = ShutdownSignal::new();
+        let clone = signal.clone();
+        assert!(!clone.is_requested());
+        signal.request();
+        assert!(clone.is_requested());
+    }
+}
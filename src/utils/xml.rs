@@ -0,0 +1,228 @@
+use crate::utils::error::{EtlError, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+/// 屬性轉為欄位時使用的前綴，例如 `<user id="1">` 轉為 `{"@id": "1"}`
+pub const ATTRIBUTE_PREFIX: &str = "@";
+/// 同時有屬性或子元素時，元素文字內容使用的欄位名稱
+pub const TEXT_FIELD: &str = "#text";
+
+/// 將 XML 文件轉為 JSON 值，供 extract 階段沿用 JSON 的記錄與路徑映射處理
+///
+/// 轉換規則：
+/// - 只有文字的元素轉為字串，空元素轉為 null
+/// - 屬性轉為 `@屬性名` 欄位，文字內容（若同時有屬性或子元素）轉為 `#text`
+/// - 重複出現的同名子元素轉為陣列
+/// - 命名空間前綴會被移除，只保留本地名稱
+///
+/// 根元素本身不會成為欄位：若根元素只包含單一種子元素（例如
+/// `<users><user/><user/></users>`），則返回該子元素的值，使重複元素成為多筆記錄。
+pub fn parse_document(text: &str) -> Result<Value> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    // 每層尚未結束的元素：(名稱, 內容)
+    let mut stack: Vec<(String, ElementContent)> = Vec::new();
+    let mut root = None;
+
+    loop {
+        match reader.read_event().map_err(|e| xml_error(&reader, e))? {
+            Event::Start(start) => {
+                let content = ElementContent::from_start(&start)?;
+                stack.push((local_name(&start), content));
+            }
+            Event::Empty(start) => {
+                let value = ElementContent::from_start(&start)?.into_value();
+                attach(&mut stack, &mut root, local_name(&start), value)?;
+            }
+            Event::Text(text) => {
+                if let Some((_, content)) = stack.last_mut() {
+                    let unescaped = text.unescape().map_err(|e| xml_error(&reader, e))?;
+                    content.text.push_str(&unescaped);
+                }
+            }
+            Event::CData(cdata) => {
+                if let Some((_, content)) = stack.last_mut() {
+                    content
+                        .text
+                        .push_str(&String::from_utf8_lossy(&cdata.into_inner()));
+                }
+            }
+            Event::End(_) => {
+                let (name, content) = stack.pop().ok_or_else(|| EtlError::DataValidationError {
+                    message: "Unexpected closing tag in XML response".to_string(),
+                })?;
+                attach(&mut stack, &mut root, name, content.into_value())?;
+            }
+            Event::Eof => break,
+            // 宣告、註解與處理指令不影響資料
+            _ => {}
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(EtlError::DataValidationError {
+            message: format!("Unclosed XML element <{}>", stack[stack.len() - 1].0),
+        });
+    }
+    let root = root.ok_or_else(|| EtlError::DataValidationError {
+        message: "XML response has no root element".to_string(),
+    })?;
+
+    Ok(unwrap_single_child(root))
+}
+
+#[derive(Debug, Default)]
+struct ElementContent {
+    fields: Map<String, Value>,
+    text: String,
+}
+
+impl ElementContent {
+    fn from_start(start: &BytesStart) -> Result<Self> {
+        let mut content = Self::default();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|e| EtlError::DataValidationError {
+                message: format!("Invalid XML attribute: {}", e),
+            })?;
+            let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+            // xmlns 宣告不是資料
+            if attribute.key.as_ref() == b"xmlns" || attribute.key.as_ref().starts_with(b"xmlns:") {
+                continue;
+            }
+            let value = attribute
+                .unescape_value()
+                .map_err(|e| EtlError::DataValidationError {
+                    message: format!("Invalid XML attribute value: {}", e),
+                })?;
+            content.fields.insert(
+                format!("{}{}", ATTRIBUTE_PREFIX, key),
+                Value::String(value.into_owned()),
+            );
+        }
+        Ok(content)
+    }
+
+    fn add_child(&mut self, name: String, value: Value) {
+        match self.fields.get_mut(&name) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(existing) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                self.fields.insert(name, value);
+            }
+        }
+    }
+
+    fn into_value(mut self) -> Value {
+        let text = self.text.trim();
+        if self.fields.is_empty() {
+            return if text.is_empty() {
+                Value::Null
+            } else {
+                Value::String(text.to_string())
+            };
+        }
+        if !text.is_empty() {
+            self.fields
+                .insert(TEXT_FIELD.to_string(), Value::String(text.to_string()));
+        }
+        Value::Object(self.fields)
+    }
+}
+
+fn attach(
+    stack: &mut [(String, ElementContent)],
+    root: &mut Option<Value>,
+    name: String,
+    value: Value,
+) -> Result<()> {
+    match stack.last_mut() {
+        Some((_, parent)) => parent.add_child(name, value),
+        None if root.is_none() => *root = Some(value),
+        None => {
+            return Err(EtlError::DataValidationError {
+                message: format!("XML response has more than one root element (<{}>)", name),
+            })
+        }
+    }
+    Ok(())
+}
+
+/// 根元素只有單一種子元素（且沒有屬性與文字）、其值為物件或陣列時，以該值取代根元素
+fn unwrap_single_child(root: Value) -> Value {
+    match root {
+        Value::Object(fields) if fields.len() == 1 => {
+            let (key, value) = fields.into_iter().next().expect("one field");
+            let is_child_element = !key.starts_with(ATTRIBUTE_PREFIX) && key != TEXT_FIELD;
+            if is_child_element && (value.is_array() || value.is_object()) {
+                value
+            } else {
+                Value::Object(Map::from_iter([(key, value)]))
+            }
+        }
+        other => other,
+    }
+}
+
+fn local_name(start: &BytesStart) -> String {
+    String::from_utf8_lossy(start.local_name().as_ref()).into_owned()
+}
+
+fn xml_error(reader: &Reader<&[u8]>, error: impl std::fmt::Display) -> EtlError {
+    EtlError::DataValidationError {
+        message: format!(
+            "Invalid XML at position {}: {}",
+            reader.buffer_position(),
+            error
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_repeated_elements_become_records() {
+        let xml = r#"<?xml version="1.0"?>
+            <users>
+                <user id="1"><name>Alice</name><profile><city>Taipei</city></profile></user>
+                <user id="2"><name>Bob &amp; Co</name><profile><city/></profile></user>
+            </users>"#;
+        assert_eq!(
+            parse_document(xml).unwrap(),
+            json!([
+                {"@id": "1", "name": "Alice", "profile": {"city": "Taipei"}},
+                {"@id": "2", "name": "Bob & Co", "profile": {"city": null}}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_single_record_text_and_namespaces() {
+        let xml = r#"<ns:order xmlns:ns="urn:x" status="open">
+                <ns:id>7</ns:id><note lang="en"><![CDATA[a < b]]></note><total>12.5</total>
+            </ns:order>"#;
+        assert_eq!(
+            parse_document(xml).unwrap(),
+            json!({
+                "@status": "open",
+                "id": "7",
+                "note": {"@lang": "en", "#text": "a < b"},
+                "total": "12.5"
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_xml() {
+        assert!(parse_document("<users><user></users>").is_err());
+        assert!(parse_document("not xml").is_err());
+        assert!(parse_document("<a/><b/>").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path, EXECUTION_ID};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::utils::error::EtlError;
+use tempfile::TempDir;
+
+fn auth_config(output_path: &str, base_url: &str) -> String {
+    sequence_config([
+        api_pipeline(
+            "users",
+            &format!("{base_url}/users"),
+            output_path,
+            r#"source.auth = { type = "basic", username_env = "AUTH_TEST_USER", password_env = "AUTH_TEST_PASSWORD" }"#,
+        ),
+        api_pipeline(
+            "orders",
+            &format!("{base_url}/orders?page=1"),
+            output_path,
+            r#"source.auth = { type = "api_key", key_env = "AUTH_TEST_KEY" }"#,
+        ),
+        api_pipeline(
+            "invoices",
+            &format!("{base_url}/invoices"),
+            output_path,
+            r#"source.auth = { type = "api_key", key_env = "AUTH_TEST_KEY", placement = "query", name = "apikey" }"#,
+        ),
+    ])
+}
+
+/// 測試 basic 與 api_key 認證從環境變數讀取憑證，並放在標頭或查詢參數
+#[tokio::test]
+async fn test_basic_and_api_key_auth() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let users = server.mock(|when, then| {
+        // etl:s3cret
+        when.method(GET)
+            .path("/users")
+            .header("Authorization", "Basic ZXRsOnMzY3JldA==");
+        then.status(200).json_body(serde_json::json!([{"id": 1}]));
+    });
+    let orders = server.mock(|when, then| {
+        when.method(GET)
+            .path("/orders")
+            .query_param("page", "1")
+            .header("X-API-Key", "k-123");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 10}, {"id": 11}]));
+    });
+    let invoices = server.mock(|when, then| {
+        when.method(GET)
+            .path("/invoices")
+            .query_param("apikey", "k-123");
+        then.status(200).json_body(serde_json::json!([{"id": 20}]));
+    });
+
+    let config = SequenceConfig::from_toml_str(&auth_config(&output_path, &server.base_url()))?;
+    config.validate()?;
+
+    // 未設定環境變數時以設定錯誤失敗，不發送請求
+    let error = build_sequence(&config, EXECUTION_ID)
+        .execute_all()
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error.root(), EtlError::MissingConfigError { field } if field.contains("AUTH_TEST_USER"))
+    );
+    users.assert_hits(0);
+
+    std::env::set_var("AUTH_TEST_USER", "etl");
+    std::env::set_var("AUTH_TEST_PASSWORD", "s3cret");
+    std::env::set_var("AUTH_TEST_KEY", "k-123");
+    let results = build_sequence(&config, EXECUTION_ID).execute_all().await?;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[1].records.len(), 2);
+    users.assert();
+    orders.assert();
+    invoices.assert();
+
+    // 環境中的憑證值列為需遮蔽的值
+    let secrets = config.sensitive_values();
+    assert!(secrets.contains(&"s3cret".to_string()));
+    assert!(secrets.contains(&"k-123".to_string()));
+    Ok(())
+}
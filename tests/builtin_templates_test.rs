@@ -0,0 +1,58 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path, EXECUTION_ID};
+use httpmock::prelude::*;
+use tempfile::TempDir;
+
+/// 測試端點、標頭、payload 與檔名中的內建日期與執行佔位符
+#[tokio::test]
+async fn test_builtin_placeholders_in_requests_and_filenames() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let today = chrono::Utc::now();
+    let yesterday = (today - chrono::Duration::days(1))
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/reports")
+            .query_param("from", &yesterday)
+            .header("X-Run-Id", EXECUTION_ID)
+            .json_body(serde_json::json!({"day": today.format("%Y%m%d").to_string()}));
+        then.status(200).json_body(serde_json::json!([{"id": 1}]));
+    });
+
+    let results = run(&sequence_config([api_pipeline(
+        "reports",
+        &format!("{}?from={{{{yesterday}}}}", server.url("/reports")),
+        &output_path,
+        r#"
+[source]
+method = "POST"
+
+[source.headers]
+X-Run-Id = "{{execution_id}}"
+
+[source.payload]
+body = '{"day": "{{today:%Y%m%d}}"}'
+
+[load]
+filename_pattern = "reports_{{run_start:%Y-%m-%d}}"
+
+[load.compression]
+format = "none"
+"#,
+    )]))
+    .await?;
+
+    mock.assert();
+    assert_eq!(results[0].records.len(), 1);
+    assert!(temp_dir
+        .path()
+        .join(format!("reports_{}", today.format("%Y-%m-%d")))
+        .exists());
+    Ok(())
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn checkpoint_config(output_path: &str, server_address: &str) -> String {
+    sequence_config([api_pipeline(
+        "items",
+        &format!("http://{server_address}/items?since={{{{checkpoint.last_id}}}}"),
+        output_path,
+        r#"
+[checkpoint]
+watermarks = { last_id = "id" }
+initial_values = { last_id = 0 }
+"#,
+    )])
+}
+
+async fn run_once(config: &SequenceConfig, execution_id: &str) -> Result<usize> {
+    let results = build_sequence(config, execution_id).execute_all().await?;
+    Ok(results[0].records.len())
+}
+
+/// 測試 checkpoint：第二次執行只擷取 watermark 之後的資料
+#[tokio::test]
+async fn test_checkpoint_incremental_extraction() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+
+    let first_mock = server.mock(|when, then| {
+        when.method(GET).path("/items").query_param("since", "0");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 7}, {"id": 3}]));
+    });
+    let second_mock = server.mock(|when, then| {
+        when.method(GET).path("/items").query_param("since", "7");
+        then.status(200).json_body(serde_json::json!([{"id": 8}]));
+    });
+
+    let config = SequenceConfig::from_toml_str(&checkpoint_config(
+        &output_path,
+        &server.address().to_string(),
+    ))?;
+
+    assert_eq!(run_once(&config, "run_1").await?, 3);
+    first_mock.assert();
+
+    let state_file = temp_dir.path().join(".checkpoints/items.json");
+    let state: serde_json::Value = serde_json::from_slice(&std::fs::read(&state_file)?)?;
+    assert_eq!(state["values"]["last_id"], 7);
+    assert_eq!(state["execution_id"], "run_1");
+
+    assert_eq!(run_once(&config, "run_2").await?, 1);
+    second_mock.assert();
+
+    let state: serde_json::Value = serde_json::from_slice(&std::fs::read(&state_file)?)?;
+    assert_eq!(state["values"]["last_id"], 8);
+
+    Ok(())
+}
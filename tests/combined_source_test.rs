@@ -0,0 +1,92 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn combined_config(output_path: &str, server_url: &str, combined: &str) -> String {
+    sequence_config([
+        api_pipeline("crm", &format!("{server_url}/crm"), output_path, ""),
+        api_pipeline("shop", &format!("{server_url}/shop"), output_path, ""),
+        pipeline(
+            "contacts",
+            output_path,
+            &format!(
+                r#"
+dependencies = ["crm", "shop"]
+
+[source]
+type = "combined"
+
+[source.combined]
+{combined}
+"#
+            ),
+        ),
+    ])
+}
+
+/// 測試 source.combined：依序聯集上游 Pipeline 的輸出，依鍵去除重複並標記來源
+#[tokio::test]
+async fn test_combined_union_with_dedup() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/crm");
+        then.status(200).json_body(serde_json::json!([
+            {"email": "amy@example.com", "name": "Amy"},
+            {"email": "bob@example.com", "name": "Bob"}
+        ]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/shop");
+        then.status(200).json_body(serde_json::json!([
+            {"email": "amy@example.com", "name": "Amy Shopper"},
+            {"email": "cat@example.com", "name": "Cat"}
+        ]));
+    });
+
+    let results = run(&combined_config(
+        &output_path,
+        &server.url(""),
+        r#"pipelines = ["crm", "shop"]"#,
+    ))
+    .await?;
+    assert_eq!(results[2].records.len(), 4);
+
+    let results = run(&combined_config(
+        &output_path,
+        &server.url(""),
+        r#"pipelines = ["shop", "crm"]
+dedup_keys = ["email"]
+source_field = "_source""#,
+    ))
+    .await?;
+    let records = &results[2].records;
+    let tagged: Vec<(&str, &str)> = records
+        .iter()
+        .map(|r| {
+            (
+                r.data["name"].as_str().unwrap(),
+                r.data["_source"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        tagged,
+        [("Amy Shopper", "shop"), ("Cat", "shop"), ("Bob", "crm")]
+    );
+    assert_eq!(results[2].metadata["duplicates_removed"], 1);
+
+    // 上游 Pipeline 必須存在
+    let config = SequenceConfig::from_toml_str(&combined_config(
+        &output_path,
+        &server.url(""),
+        r#"pipelines = ["crm", "billing"]"#,
+    ))?;
+    assert!(config.validate().is_err());
+    Ok(())
+}
@@ -0,0 +1,125 @@
+//! 整合測試共用的序列設定範本與執行方式
+//!
+//! 各測試只寫出與預設值不同的部分：`api_pipeline("users", &endpoint, &output_path, r#"
+//! [load.compression]
+//! format = "none""#)`，再以 `sequence_config` 組成完整的序列設定。
+#![allow(dead_code)]
+
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::{
+    contextual_pipeline::SequenceAwarePipeline,
+    pipeline_sequence::{PipelineResult, PipelineSequence},
+};
+use samll_etl::LocalStorage;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// `run` 使用的執行 ID
+pub const EXECUTION_ID: &str = "test_run";
+
+/// 放進 TOML 字串的路徑（Windows 的反斜線會被當成跳脫字元）
+pub fn slash_path(path: &Path) -> String {
+    path.to_str().unwrap().replace('\\', "/")
+}
+
+/// GET `endpoint` 的 api 來源 Pipeline，其餘同 `pipeline`
+pub fn api_pipeline(name: &str, endpoint: &str, output_path: &str, overrides: &str) -> Table {
+    let source = format!("[source]\ntype = \"api\"\nendpoint = {:?}\n", endpoint);
+    let mut pipeline = pipeline(name, output_path, &source);
+    merge(&mut pipeline, parse(overrides));
+    pipeline
+}
+
+/// 輸出 JSON 到 `output_path` 的 Pipeline
+///
+/// `overrides` 是相對於該 Pipeline 的 TOML（`[source]`、`[load.compression]`、
+/// `[[quality.rules]]` 等），逐層合併到預設值上；來源不是 api 時由這裡指定 `source.type`。
+pub fn pipeline(name: &str, output_path: &str, overrides: &str) -> Table {
+    let mut pipeline = parse(&format!(
+        r#"
+name = {name:?}
+
+[source]
+
+[extract]
+
+[transform]
+
+[load]
+output_path = {output_path:?}
+output_formats = ["json"]
+"#
+    ));
+    merge(&mut pipeline, parse(overrides));
+    pipeline
+}
+
+/// 依序執行 `pipelines` 的序列設定
+pub fn sequence_config(pipelines: impl IntoIterator<Item = Table>) -> String {
+    sequence_config_with("", pipelines)
+}
+
+/// 同 `sequence_config`，`overrides` 為序列層級的 TOML（`[sequence]`、`[global]` 等）
+pub fn sequence_config_with(overrides: &str, pipelines: impl IntoIterator<Item = Table>) -> String {
+    let pipelines: Vec<Table> = pipelines.into_iter().collect();
+    let order: Vec<Value> = pipelines
+        .iter()
+        .map(|pipeline| pipeline["name"].clone())
+        .collect();
+
+    let mut config = parse(
+        r#"
+[sequence]
+name = "test-sequence"
+description = "Integration test"
+version = "1.0.0"
+"#,
+    );
+    config["sequence"]
+        .as_table_mut()
+        .unwrap()
+        .insert("execution_order".to_string(), Value::Array(order));
+    config.insert(
+        "pipelines".to_string(),
+        Value::Array(pipelines.into_iter().map(Value::Table).collect()),
+    );
+    merge(&mut config, parse(overrides));
+    toml::to_string(&config).unwrap()
+}
+
+/// 每個 Pipeline 以自己的 load.output_path 作為存儲根目錄
+pub fn build_sequence(config: &SequenceConfig, execution_id: &str) -> PipelineSequence {
+    let mut sequence = PipelineSequence::new(execution_id.to_string());
+    for pipeline_def in &config.pipelines {
+        sequence.add_pipeline(Box::new(SequenceAwarePipeline::new(
+            pipeline_def.name.clone(),
+            LocalStorage::new(pipeline_def.load.output_path.clone()),
+            pipeline_def.clone(),
+        )));
+    }
+    sequence
+}
+
+/// 解析、驗證並以 `EXECUTION_ID` 執行序列設定
+pub async fn run(config: &str) -> samll_etl::Result<Vec<PipelineResult>> {
+    let config = SequenceConfig::from_toml_str(config)?;
+    config.validate()?;
+    build_sequence(&config, EXECUTION_ID).execute_all().await
+}
+
+fn parse(content: &str) -> Table {
+    content
+        .parse()
+        .unwrap_or_else(|e| panic!("invalid TOML fixture: {}\n{}", e, content))
+}
+
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
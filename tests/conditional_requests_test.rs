@@ -0,0 +1,57 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::core::pipeline_sequence::SkipReason;
+use tempfile::TempDir;
+
+fn conditional_config(output_path: &str, endpoint: &str, on_not_modified: &str) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!("[source.conditional]\non_not_modified = \"{on_not_modified}\""),
+    )])
+}
+
+/// 測試第二次執行帶上 If-None-Match，收到 304 時沿用上次的回應或略過 Pipeline
+#[tokio::test]
+async fn test_not_modified_reuses_payload_or_skips() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let not_modified = server.mock(|when, then| {
+        when.method(GET)
+            .path("/users")
+            .header("If-None-Match", "\"v1\"");
+        then.status(304);
+    });
+    let first = server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .header("ETag", "\"v1\"")
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}]));
+    });
+
+    let config = conditional_config(&output_path, &server.url("/users"), "reuse");
+    let results = run(&config).await?;
+    assert_eq!(results[0].records.len(), 2);
+    first.assert();
+
+    let results = run(&config).await?;
+    not_modified.assert();
+    assert_eq!(results[0].skipped, None);
+    assert_eq!(results[0].records.len(), 2);
+    assert_eq!(results[0].records[1].data["id"], 2);
+
+    // skip 模式使用另一個端點，第二次執行收到 304 時略過整個 Pipeline
+    let skip_dir = TempDir::new()?;
+    let skip_path = slash_path(skip_dir.path());
+    let config = conditional_config(&skip_path, &server.url("/users"), "skip");
+    assert_eq!(run(&config).await?[0].records.len(), 2);
+    let results = run(&config).await?;
+    assert_eq!(results[0].skipped, Some(SkipReason::NotModified));
+    assert!(results[0].records.is_empty());
+    Ok(())
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn context_index_config(output_path: &str, server_address: &str, index: &str) -> String {
+    sequence_config([
+        api_pipeline(
+            "users",
+            &format!("http://{server_address}/users"),
+            output_path,
+            "",
+        ),
+        api_pipeline(
+            "orders",
+            &format!("http://{server_address}/orders"),
+            output_path,
+            "",
+        ),
+        api_pipeline(
+            "order_notifications",
+            &format!(
+                "http://{server_address}/notify/{{order_id}}?to={{{{lookup:users:id=link_user_id:email}}}}"
+            ),
+            output_path,
+            &format!("{index}\n\n[source.data_source]\nuse_previous_output = true"),
+        ),
+    ])
+}
+
+/// 測試 context_index：以 {{lookup:...}} 逐筆查找先前 Pipeline 的欄位
+#[tokio::test]
+async fn test_lookup_template_resolves_from_context_index() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "email": "alice@example.com"},
+            {"id": 2, "email": "bob@example.com"}
+        ]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(200).json_body(serde_json::json!([
+            {"order_id": 10, "link_user_id": 2}
+        ]));
+    });
+    let notify_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/notify/10")
+            .query_param("to", "bob@example.com");
+        then.status(200)
+            .json_body(serde_json::json!({"sent": true}));
+    });
+
+    let address = server.address().to_string();
+    let config = SequenceConfig::from_toml_str(&context_index_config(
+        &output_path,
+        &address,
+        r#"context_index = { pipeline = "users", key = "id" }"#,
+    ))?;
+    config.validate()?;
+
+    let results = build_sequence(&config, "context_index_run")
+        .execute_all()
+        .await?;
+
+    assert_eq!(results[2].records.len(), 1);
+    notify_mock.assert();
+
+    // 模板使用 lookup 卻未宣告 context_index 時，設定驗證失敗
+    let undeclared =
+        SequenceConfig::from_toml_str(&context_index_config(&output_path, &address, ""))?;
+    assert!(undeclared.validate().is_err());
+
+    Ok(())
+}
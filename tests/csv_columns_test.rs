@@ -0,0 +1,80 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use tempfile::TempDir;
+
+fn columns_config(output_path: &str, endpoint: &str, strict: bool) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[load]
+output_formats = ["csv", "tsv"]
+columns = ["name", "id", "email"]
+strict_columns = {strict}
+
+[load.compression]
+format = "none"
+"#
+        ),
+    )])
+}
+
+/// 測試 load.columns：固定欄位順序、缺值留空，strict 模式遇到未列出的欄位失敗
+#[tokio::test]
+async fn test_pinned_columns_and_strict_mode() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "name": "Alice", "internal": "x"},
+            {"id": 2, "name": "Bob", "email": "bob@example.com"}
+        ]));
+    });
+    let endpoint = server.url("/users");
+
+    run(&columns_config(&output_path, &endpoint, false)).await?;
+    let dir = temp_dir.path().join("users_output");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("output.csv"))?,
+        "name,id,email\nAlice,1,\nBob,2,bob@example.com"
+    );
+    assert!(std::fs::read_to_string(dir.join("output.tsv"))?.starts_with("name\tid\temail\n"));
+
+    let error = run(&columns_config(&output_path, &endpoint, true))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("internal"));
+    assert!(!error.to_string().contains("processed"));
+
+    Ok(())
+}
+
+/// 測試 strict 模式：記錄只含 load.columns 列出的欄位時通過，處理標記欄位不視為未知欄位
+#[tokio::test]
+async fn test_strict_columns_passes_for_listed_fields() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "name": "Alice"},
+            {"id": 2, "name": "Bob", "email": "bob@example.com"}
+        ]));
+    });
+
+    run(&columns_config(&output_path, &server.url("/users"), true)).await?;
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.path().join("users_output/output.csv"))?,
+        "name,id,email\nAlice,1,\nBob,2,bob@example.com"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn data_path_config(output_path: &str, endpoint: &str, data_path: &str) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[extract]
+data_path = "{data_path}"
+
+[extract.field_mapping]
+"profile.name" = "name"
+"#
+        ),
+    )])
+}
+
+/// 測試 extract.data_path 取出包在回應中的記錄陣列後再套用 field_mapping
+#[tokio::test]
+async fn test_data_path_unwraps_envelope() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!({
+            "data": {"items": [
+                {"id": 1, "profile": {"name": "Ann"}},
+                {"id": 2, "profile": {"name": "Bob"}}
+            ]},
+            "meta": {"total": 2}
+        }));
+    });
+
+    let results = run(&data_path_config(
+        &output_path,
+        &server.url("/users"),
+        "data.items",
+    ))
+    .await?;
+    let records = &results[0].records;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].data["id"], 2);
+    assert_eq!(records[1].data["name"], "Bob");
+    assert!(!records[0].data.contains_key("meta"));
+
+    // 找不到路徑時失敗，空路徑段落不通過設定檢查
+    assert!(run(&data_path_config(
+        &output_path,
+        &server.url("/users"),
+        "data.rows",
+    ))
+    .await
+    .is_err());
+    let config = SequenceConfig::from_toml_str(&data_path_config(
+        &output_path,
+        &server.url("/users"),
+        "data..items",
+    ))?;
+    assert!(config.validate().is_err());
+    Ok(())
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::dead_letter::DeadLetter;
+use tempfile::TempDir;
+
+fn dead_letter_config(output_path: &str, server_address: &str, dead_letter: &str) -> String {
+    sequence_config([
+        api_pipeline(
+            "users",
+            &format!("http://{server_address}/users"),
+            output_path,
+            "",
+        ),
+        api_pipeline(
+            "user_details",
+            &format!("http://{server_address}/users/{{id}}"),
+            output_path,
+            &format!(
+                r#"
+[source.data_source]
+use_previous_output = true
+
+[dead_letter]
+enabled = true
+{dead_letter}
+"#
+            ),
+        ),
+    ])
+}
+
+/// 測試 dead-letter：參數化呼叫失敗與範本缺值的記錄寫入 rejects 檔，Pipeline 仍完成
+#[tokio::test]
+async fn test_failed_parameterized_calls_written_to_rejects() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}, {"name": "no id"}]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/users/1");
+        then.status(200)
+            .json_body(serde_json::json!({"id": 1, "name": "Alice"}));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/users/2");
+        then.status(500);
+    });
+
+    let config = SequenceConfig::from_toml_str(&dead_letter_config(
+        &output_path,
+        &server.address().to_string(),
+        "",
+    ))?;
+    config.validate()?;
+
+    let results = build_sequence(&config, "dead_letter_run")
+        .execute_all()
+        .await?;
+
+    assert_eq!(results[1].records.len(), 1);
+    assert_eq!(results[1].metadata["rejected_records"], 2);
+
+    let rejects: Vec<DeadLetter> = serde_json::from_slice(&std::fs::read(
+        temp_dir.path().join("user_details_rejects.json"),
+    )?)?;
+    assert_eq!(rejects.len(), 2);
+    assert_eq!(rejects[0].stage, "extract");
+    assert!(rejects[0].reason.contains("500"));
+    assert_eq!(rejects[1].stage, "template");
+
+    Ok(())
+}
+
+/// 測試相對 output_path：rejects 檔寫在 output_path 底下，不會重複疊加路徑
+#[tokio::test]
+async fn test_rejects_path_relative_to_relative_output_path() -> Result<()> {
+    let temp_dir = TempDir::new_in(".")?;
+    let name = temp_dir.path().file_name().unwrap().to_str().unwrap();
+    let output_path = format!("./{}", name);
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([{"id": 1}]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/users/1");
+        then.status(500);
+    });
+
+    let config = SequenceConfig::from_toml_str(&dead_letter_config(
+        &output_path,
+        &server.address().to_string(),
+        r#"path = "rejects/{pipeline_name}.json""#,
+    ))?;
+    config.validate()?;
+
+    let results = build_sequence(&config, "dead_letter_relative_run")
+        .execute_all()
+        .await?;
+
+    let rejects_file = temp_dir.path().join("rejects/user_details.json");
+    let rejects: Vec<DeadLetter> = serde_json::from_slice(&std::fs::read(&rejects_file)?)?;
+    assert_eq!(rejects.len(), 1);
+    assert!(!temp_dir.path().join(name).exists());
+    assert_eq!(
+        results[1].metadata["rejects_path"],
+        format!("{}/rejects/user_details.json", output_path)
+    );
+
+    Ok(())
+}
+
+/// 測試單筆轉換逾時：卡住的記錄寫入 rejects 檔，其餘記錄照常輸出
+#[tokio::test]
+async fn test_record_timeout_routes_slow_record_to_rejects() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let huge = "word ".repeat(2_000_000);
+    server.mock(|when, then| {
+        when.method(GET).path("/posts");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "body": "short text"},
+            {"id": 2, "body": huge},
+        ]));
+    });
+
+    let config = SequenceConfig::from_toml_str(&sequence_config([api_pipeline(
+        "posts",
+        &server.url("/posts"),
+        &output_path,
+        r#"
+[transform]
+record_timeout_ms = 200
+
+[transform.field_transforms.body.regex_replace]
+pattern = '(\p{L}+)\s+'
+replacement = "$1-"
+"#,
+    )]))?;
+    config.validate()?;
+
+    let results = build_sequence(&config, "record_timeout_run")
+        .execute_all()
+        .await?;
+
+    assert_eq!(results[0].records.len(), 1);
+    assert_eq!(results[0].records[0].data["body"], "short-text");
+    let rejects: Vec<DeadLetter> =
+        serde_json::from_slice(&std::fs::read(temp_dir.path().join("posts_rejects.json"))?)?;
+    assert_eq!(rejects.len(), 1);
+    assert_eq!(rejects[0].stage, "timeout");
+    assert_eq!(rejects[0].record["id"], 2);
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::run_report::RunReport;
+use samll_etl::utils::error::EtlError;
+use tempfile::TempDir;
+
+/// 測試序列失敗時保留原始錯誤的分類，並附上 Pipeline、階段與遮蔽後的端點
+#[tokio::test]
+async fn test_sequence_error_keeps_category_and_context() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(503);
+    });
+
+    let config = SequenceConfig::from_toml_str(&sequence_config([api_pipeline(
+        "users",
+        &format!("{}?token=secret123", server.url("/users")),
+        &output_path,
+        "",
+    )]))?;
+    config.validate()?;
+
+    let pipeline_def = &config.pipelines[0];
+    let error = build_sequence(&config, "error_run")
+        .execute_all()
+        .await
+        .unwrap_err();
+
+    // 服務暫時無法使用仍歸類為可重試的網路錯誤，而不是籠統的 Pipeline 失敗
+    assert!(!matches!(error.root(), EtlError::Context { .. }));
+    assert_eq!(error.category().code(), "network");
+    assert!(error.is_retryable());
+    assert_eq!(error.exit_code(), 2);
+    let context = error.context().unwrap();
+    assert_eq!(context.pipeline.as_deref(), Some("users"));
+    assert_eq!(context.stage.as_deref(), Some("extract"));
+    let endpoint = context.endpoint.as_deref().unwrap();
+    assert!(endpoint.contains("/users") && !endpoint.contains("secret123"));
+
+    let order = [pipeline_def.name.clone()];
+    let report = RunReport::failed("error_run", &error, error.exit_code(), None, &order);
+    let run_error = report.error.unwrap();
+    assert_eq!(run_error.code, error.category().code());
+    assert_eq!(run_error.context.as_ref(), Some(context));
+    Ok(())
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use tempfile::TempDir;
+
+/// 測試 field_transforms：重新命名、轉型、預設值與正規表示式替換，轉型失敗依 on_invalid 處理
+#[tokio::test]
+async fn test_field_transforms_applied_before_output() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(200).json_body(serde_json::json!([
+            {"orderId": "1001", "amount": "$1,250.00", "paid": "yes", "ordered_at": "2024/05/01"},
+            {"orderId": "1002", "amount": "$80", "ordered_at": "2024-05-02T09:30:00Z"},
+            {"orderId": "n/a", "amount": "$5", "paid": "no", "ordered_at": "2024-05-03"}
+        ]));
+    });
+
+    let results = run(&sequence_config([api_pipeline(
+        "orders",
+        &server.url("/orders"),
+        &output_path,
+        r#"
+[transform.validation]
+on_invalid = "drop"
+
+[transform.field_transforms.orderId]
+rename = "order_id"
+cast = "int"
+
+[transform.field_transforms.amount]
+regex_replace = { pattern = "[$,]", replacement = "" }
+cast = "float"
+
+[transform.field_transforms.paid]
+default = false
+cast = "bool"
+
+[transform.field_transforms.ordered_at]
+cast = "date"
+
+[load]
+output_formats = ["csv"]
+"#,
+    )]))
+    .await?;
+
+    let records = &results[0].records;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].data["order_id"], 1001);
+    assert_eq!(records[0].data["amount"], 1250.0);
+    assert_eq!(records[0].data["paid"], true);
+    assert_eq!(records[0].data["ordered_at"], "2024-05-01");
+    assert_eq!(records[1].data["paid"], false);
+    assert_eq!(records[1].data["ordered_at"], "2024-05-02");
+    assert!(!records[0].data.contains_key("orderId"));
+    assert_eq!(results[0].warnings.len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use anyhow::Result;
+use common::{pipeline, run, sequence_config, slash_path};
+use tempfile::TempDir;
+
+/// 測試 files 來源：展開 glob、每個檔案各自解析並加上檔名欄位，並行讀取時仍依檔名排序
+#[tokio::test]
+async fn test_files_source_reads_matched_files() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = slash_path(temp_dir.path());
+    let input = temp_dir.path().join("input");
+    std::fs::create_dir_all(input.join("2024-01-02"))?;
+    std::fs::write(input.join("a.csv"), "id,name\n1,Alice\n2,Bob\n")?;
+    std::fs::write(input.join("2024-01-02/b.csv"), "id,name\n3,Carol\n")?;
+    std::fs::write(
+        input.join("2024-01-02/c.json"),
+        r#"{"id": "4", "name": "Dave"}"#,
+    )?;
+    std::fs::write(input.join("readme.txt"), "ignored")?;
+
+    run(&sequence_config([pipeline(
+        "drops",
+        &format!("{root}/output"),
+        &format!(
+            r#"
+[source]
+type = "files"
+
+[source.files]
+pattern = "{root}/input/**/*.csv"
+filename_field = "file"
+concurrency = 2
+
+[load]
+output_formats = ["csv"]
+columns = ["file", "id", "name"]
+
+[load.compression]
+format = "none"
+"#
+        ),
+    )]))
+    .await?;
+
+    let csv = std::fs::read_to_string(temp_dir.path().join("output/drops_output/output.csv"))?;
+    assert_eq!(
+        csv,
+        format!(
+            "file,id,name\n{root}/input/2024-01-02/b.csv,3,Carol\n{root}/input/a.csv,1,Alice\n{root}/input/a.csv,2,Bob"
+        )
+    );
+    Ok(())
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, sequence_config_with, slash_path};
+use httpmock::prelude::*;
+use samll_etl::app::pipelines::sequence_runner::SequenceRunner;
+use samll_etl::app::RunOptions;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn template_config(working_dir: &str, endpoint: &str, load: &str) -> String {
+    sequence_config_with(
+        &format!("sequence.name = \"nightly\"\nglobal.working_directory = \"{working_dir}\""),
+        [api_pipeline(
+            "orders",
+            endpoint,
+            &format!("{working_dir}/output"),
+            &format!(
+                r#"
+[load]
+output_formats = ["csv", "json"]
+{load}
+
+[load.compression]
+format = "none"
+"#
+            ),
+        )],
+    )
+}
+
+/// 測試 filename_pattern 與 file_pattern 的新變數，以及檔名衝突的驗證
+#[tokio::test]
+async fn test_filename_and_file_patterns() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let working_dir = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let orders = server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    });
+
+    let config = SequenceConfig::from_toml_str(&template_config(
+        &working_dir,
+        &server.url("/orders"),
+        r#"filename_pattern = "{sequence_name}_{pipeline_name}_{record_count}_{date}"
+file_pattern = "{pipeline_name}_{format}""#,
+    ))?;
+    config.validate()?;
+    let options = RunOptions {
+        execution_id: Some("template_run".to_string()),
+        ..RunOptions::default()
+    };
+    SequenceRunner::new(&config, &options)?.run().await?;
+    orders.assert();
+
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let output = temp_dir
+        .path()
+        .join(format!("output/nightly_orders_3_{}", date));
+    assert!(output.join("orders_csv.csv").exists());
+    assert!(output.join("orders_json.json").exists());
+    assert!(!output.join("output.csv").exists());
+
+    // 資料檔覆蓋 schema.json、metadata.json，或 filename_pattern 使用 {format} 時驗證失敗
+    for load in [
+        r#"file_pattern = "schema""#,
+        r#"file_pattern = "metadata""#,
+        r#"filename_pattern = "{pipeline_name}_{format}""#,
+    ] {
+        let config = SequenceConfig::from_toml_str(&template_config(
+            &working_dir,
+            &server.url("/orders"),
+            load,
+        ))?;
+        assert!(config.validate().is_err(), "{} should be rejected", load);
+    }
+    Ok(())
+}
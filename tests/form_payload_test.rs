@@ -0,0 +1,84 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path, EXECUTION_ID};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn payload_config(output_path: &str, endpoint: &str, payload: &str) -> String {
+    sequence_config([api_pipeline(
+        "legacy",
+        endpoint,
+        output_path,
+        &format!("[source]\nmethod = \"POST\"\n\n{payload}"),
+    )])
+}
+
+/// 測試 payload.form 送出 urlencoded 表單，payload.multipart 附上從存儲讀取的檔案
+#[tokio::test]
+async fn test_form_and_multipart_payloads() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let token = server.mock(|when, then| {
+        when.method(POST)
+            .path("/token")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(format!(
+                "grant_type=password&run={}&username=etl+user",
+                EXECUTION_ID
+            ));
+        then.status(200)
+            .json_body(serde_json::json!([{"access_token": "abc"}]));
+    });
+    let upload = server.mock(|when, then| {
+        when.method(POST)
+            .path("/upload")
+            .header_exists("Content-Type")
+            .body_contains("name=\"kind\"\r\n\r\ndaily")
+            .body_contains("name=\"file\"; filename=\"report.csv\"")
+            .body_contains("Content-Type: text/csv")
+            .body_contains("id,total\n1,42\n");
+        then.status(200)
+            .json_body(serde_json::json!([{"ok": true}]));
+    });
+
+    let results = run(&payload_config(
+        &output_path,
+        &server.url("/token"),
+        r#"[source.payload.form]
+grant_type = "password"
+username = "etl user"
+run = "{{execution_id}}""#,
+    ))
+    .await?;
+    token.assert();
+    assert_eq!(results[0].records[0].data["access_token"], "abc");
+
+    std::fs::create_dir_all(temp_dir.path().join("uploads"))?;
+    std::fs::write(
+        temp_dir.path().join("uploads/report.csv"),
+        "id,total\n1,42\n",
+    )?;
+    run(&payload_config(
+        &output_path,
+        &server.url("/upload"),
+        r#"[source.payload.multipart]
+fields = { kind = "daily" }
+files = [{ name = "file", path = "uploads/report.csv", content_type = "text/csv" }]"#,
+    ))
+    .await?;
+    upload.assert();
+
+    // body、form、multipart 只能擇一
+    let config = SequenceConfig::from_toml_str(&payload_config(
+        &output_path,
+        &server.url("/token"),
+        r#"[source.payload]
+body = "{}"
+form = { a = "b" }"#,
+    ))?;
+    assert!(config.validate().is_err());
+    Ok(())
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use anyhow::Result;
+use common::{build_sequence, slash_path};
+use httpmock::prelude::*;
+use samll_etl::app::pipelines::sequence_init::{ask, scaffold, InitAuth, CONFIG_FILE};
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+/// 測試 init 精靈的回答產生可直接執行的設定檔與目錄結構
+#[tokio::test]
+async fn test_init_scaffolds_runnable_sequence() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let project = temp_dir.path().join("project");
+    let output_path = slash_path(&project.join("data"));
+    let server = MockServer::start();
+    let api = server.mock(|when, then| {
+        when.method(GET)
+            .path("/v1/orders")
+            .header("X-API-Key", "init-wizard-key");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1, "total": 10}, {"id": 2, "total": 20}]));
+    });
+
+    let answers = format!(
+        "orders-daily\n{}\napi_key\njson, csv\n{}\n",
+        server.url("/v1/orders"),
+        output_path
+    );
+    let answers = ask(&mut answers.as_bytes(), &mut Vec::new())?;
+    assert_eq!(answers.auth, InitAuth::ApiKey);
+
+    let created = scaffold(&project, &answers, false)?;
+    assert_eq!(created.len(), 3);
+    assert!(project.join("data").is_dir());
+    assert_eq!(
+        std::fs::read_to_string(project.join(".env.example"))?,
+        "API_KEY=\n"
+    );
+    // 已存在的設定檔不會被覆寫
+    assert!(scaffold(&project, &answers, false).is_err());
+
+    let config = SequenceConfig::from_file(project.join(CONFIG_FILE))?;
+    config.validate()?;
+    let pipeline_def = &config.pipelines[0];
+    assert_eq!(pipeline_def.name, "orders");
+
+    std::env::set_var("API_KEY", "init-wizard-key");
+    let results = build_sequence(&config, "init_run").execute_all().await?;
+    api.assert();
+    assert_eq!(results[0].records.len(), 2);
+    Ok(())
+}
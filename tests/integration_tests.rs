@@ -1,4 +1,6 @@
 use httpmock::prelude::*;
+use samll_etl::core::Pipeline;
+use samll_etl::utils::logger::LogFormat;
 use samll_etl::{CliConfig, EtlEngine, LocalStorage, SimplePipeline};
 use tempfile::TempDir;
 
@@ -28,9 +30,12 @@ async fn test_end_to_end_etl_with_real_http() {
         api_endpoint: server.url("/products"),
         output_path: output_path.clone(),
         lookup_files: vec![],
+        lookup_key: "id".to_string(),
         concurrent_requests: 5,
         verbose: false,
+        log_format: LogFormat::Text,
         monitor: false,
+        run_report: None,
     };
 
     // Create storage and pipeline
@@ -94,9 +99,12 @@ async fn test_end_to_end_with_api_failure() {
         api_endpoint: server.url("/failed"),
         output_path: output_path.clone(),
         lookup_files: vec![],
+        lookup_key: "id".to_string(),
         concurrent_requests: 5,
         verbose: false,
+        log_format: LogFormat::Text,
         monitor: false,
+        run_report: None,
     };
 
     let storage = LocalStorage::new(output_path.clone());
@@ -136,9 +144,12 @@ async fn test_end_to_end_with_monitoring() {
         api_endpoint: server.url("/test"),
         output_path: output_path.clone(),
         lookup_files: vec![],
+        lookup_key: "id".to_string(),
         concurrent_requests: 5,
         verbose: true,
+        log_format: LogFormat::Text,
         monitor: true, // Enable monitoring
+        run_report: None,
     };
 
     let storage = LocalStorage::new(output_path.clone());
@@ -175,9 +186,12 @@ async fn test_intermediate_data_generation() {
         api_endpoint: server.url("/data"),
         output_path: output_path.clone(),
         lookup_files: vec![],
+        lookup_key: "id".to_string(),
         concurrent_requests: 5,
         verbose: false,
+        log_format: LogFormat::Text,
         monitor: false,
+        run_report: None,
     };
 
     let storage = LocalStorage::new(output_path.clone());
@@ -228,9 +242,12 @@ async fn test_concurrent_requests_parameter() {
         api_endpoint: server.url("/concurrent"),
         output_path: output_path.clone(),
         lookup_files: vec![],
+        lookup_key: "id".to_string(),
         concurrent_requests: 10, // Different value
         verbose: false,
+        log_format: LogFormat::Text,
         monitor: false,
+        run_report: None,
     };
 
     let storage = LocalStorage::new(output_path.clone());
@@ -241,3 +258,37 @@ async fn test_concurrent_requests_parameter() {
     assert!(result.is_ok());
     api_mock.assert();
 }
+
+#[tokio::test]
+async fn test_lookup_files_keep_existing_record_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_path = temp_dir.path().to_str().unwrap().to_string();
+    let lookup_path = temp_dir.path().join("products.csv");
+    std::fs::write(&lookup_path, "id,name,region\n1,Lookup A,APAC\n").unwrap();
+
+    let config = CliConfig {
+        api_endpoint: "http://localhost/unused".to_string(),
+        output_path: output_path.clone(),
+        lookup_files: vec![lookup_path.to_str().unwrap().to_string()],
+        lookup_key: "id".to_string(),
+        concurrent_requests: 5,
+        verbose: false,
+        log_format: LogFormat::Text,
+        monitor: false,
+        run_report: None,
+    };
+    let pipeline = SimplePipeline::new(LocalStorage::new(output_path), config);
+
+    let record = samll_etl::core::Record {
+        data: std::collections::HashMap::from([
+            ("id".to_string(), serde_json::json!(1)),
+            ("name".to_string(), serde_json::json!("Product A")),
+        ]),
+    };
+    let result = pipeline.transform(vec![record]).await.unwrap();
+
+    // 參照表的 name 與記錄欄位同名，不覆寫記錄的值；其他欄位照常加入
+    let enriched = &result.processed_records[0].data;
+    assert_eq!(enriched["name"], "Product A");
+    assert_eq!(enriched["region"], "APAC");
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::intermediate_output::IntermediateFormat;
+use samll_etl::LocalStorage;
+use tempfile::TempDir;
+
+fn pipeline(name: &str, address: &str, output_path: &str, intermediate: &str) -> toml::Table {
+    api_pipeline(
+        name,
+        &format!("http://{address}/{name}"),
+        output_path,
+        &format!(
+            r#"
+[transform.intermediate]
+conditions = {{ status = "active" }}
+{intermediate}
+
+[load.compression]
+format = "none"
+"#
+        ),
+    )
+}
+
+/// 測試中繼結果檔名、格式、關閉輸出，以及序列層級彙整
+#[tokio::test]
+async fn test_intermediate_naming_format_and_aggregate() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    for name in ["users", "orders"] {
+        server.mock(|when, then| {
+            when.method(GET).path(format!("/{}", name));
+            then.status(200).json_body(serde_json::json!([
+                {"id": 1, "status": "active"},
+                {"id": 2, "status": "inactive"}
+            ]));
+        });
+    }
+
+    let address = server.address().to_string();
+    let config = SequenceConfig::from_toml_str(&sequence_config([
+        pipeline(
+            "users",
+            &address,
+            &output_path,
+            r#"output_name = "active_users"
+output_format = "csv""#,
+        ),
+        pipeline("orders", &address, &output_path, "output = false"),
+    ]))?;
+    config.validate()?;
+
+    let aggregate_path = temp_dir.path().join("all_intermediate.jsonl");
+    build_sequence(&config, "intermediate_run")
+        .with_intermediate_aggregate(
+            LocalStorage::new(output_path.clone()),
+            "all_intermediate.jsonl",
+            IntermediateFormat::Jsonl,
+        )
+        .execute_all()
+        .await?;
+
+    let users_dir = temp_dir.path().join("users_output");
+    let csv = std::fs::read_to_string(users_dir.join("active_users.csv"))?;
+    assert_eq!(csv.lines().count(), 2);
+    assert!(csv.starts_with("id,"));
+    assert!(!users_dir.join("intermediate.json").exists());
+    let orders_dir = temp_dir.path().join("orders_output");
+    assert!(orders_dir.join("processed_data.json").exists());
+    assert!(!orders_dir.join("intermediate.json").exists());
+
+    let aggregate = std::fs::read_to_string(aggregate_path)?;
+    let pipelines: Vec<String> = aggregate
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["data"]["_pipeline"]
+                .to_string()
+        })
+        .collect();
+    assert_eq!(pipelines, vec!["\"users\"", "\"orders\""]);
+
+    Ok(())
+}
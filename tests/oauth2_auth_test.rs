@@ -0,0 +1,78 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn oauth2_config(output_path: &str, server_address: &str) -> String {
+    let auth = format!(
+        r#"
+[source.auth]
+type = "oauth2"
+token_url = "http://{server_address}/oauth/token"
+client_id = "etl_client"
+client_secret = "secret_123"
+"#
+    );
+    sequence_config([
+        api_pipeline(
+            "users",
+            &format!("http://{server_address}/users"),
+            output_path,
+            &format!("{auth}scopes = [\"read:users\"]"),
+        ),
+        api_pipeline(
+            "user_details",
+            &format!("http://{server_address}/users/{{id}}"),
+            output_path,
+            &format!("{auth}\n[source.data_source]\nuse_previous_output = true"),
+        ),
+    ])
+}
+
+/// 測試 OAuth2 認證：每個 Pipeline 只取得一次 token，並以 Bearer 標頭呼叫 API
+#[tokio::test]
+async fn test_oauth2_client_credentials_token_is_cached() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+
+    let token_mock = server.mock(|when, then| {
+        when.method(POST)
+            .path("/oauth/token")
+            .body_contains("grant_type=client_credentials")
+            .body_contains("client_id=etl_client");
+        then.status(200)
+            .json_body(serde_json::json!({"access_token": "token_abc", "expires_in": 3600}));
+    });
+    let users_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/users")
+            .header("Authorization", "Bearer token_abc");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    });
+    let details_mock = server.mock(|when, then| {
+        when.method(GET)
+            .path_matches(regex::Regex::new(r"^/users/\d+$").unwrap())
+            .header("Authorization", "Bearer token_abc");
+        then.status(200)
+            .json_body(serde_json::json!({"name": "user"}));
+    });
+
+    let config =
+        SequenceConfig::from_toml_str(&oauth2_config(&output_path, &server.address().to_string()))?;
+    config.validate()?;
+
+    let results = build_sequence(&config, "oauth2_run").execute_all().await?;
+
+    assert_eq!(results[1].records.len(), 3);
+    users_mock.assert_hits(1);
+    details_mock.assert_hits(3);
+    // 每個 Pipeline 各自取得一次 token，後續呼叫使用快取
+    token_mock.assert_hits(2);
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use std::io::Read;
+use tempfile::TempDir;
+
+fn compression_config(output_path: &str, endpoint: &str, format: &str, load: &str) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[load]
+output_formats = ["json", "csv"]
+{load}
+
+[load.compression]
+format = "{format}"
+"#
+        ),
+    )])
+}
+
+/// 測試 tar.gz 與未壓縮目錄輸出
+#[tokio::test]
+async fn test_tar_gz_and_directory_output() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1, "name": "Alice"}]));
+    });
+    let endpoint = server.url("/users");
+
+    let tar_path = run(&compression_config(&output_path, &endpoint, "tar.gz", ""))
+        .await?
+        .remove(0)
+        .output_path;
+    assert!(tar_path.ends_with("users_output.tar.gz"));
+    let file = std::fs::File::open(&tar_path)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut names = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content)?;
+        names.push(entry.path()?.display().to_string());
+    }
+    assert_eq!(names, vec!["processed_data.json", "output.csv"]);
+
+    let dir_path = run(&compression_config(&output_path, &endpoint, "none", ""))
+        .await?
+        .remove(0)
+        .output_path;
+    assert!(dir_path.ends_with("users_output"));
+    let csv = std::fs::read_to_string(temp_dir.path().join("users_output/output.csv"))?;
+    assert!(csv.contains("Alice"));
+
+    let invalid =
+        SequenceConfig::from_toml_str(&compression_config(&output_path, &endpoint, "rar", ""))?;
+    assert!(invalid.validate().is_err());
+
+    Ok(())
+}
+
+/// 測試交易式寫入：封裝輸出失敗時追加輸出也不變，成功後才一起出現
+#[tokio::test]
+async fn test_transactional_load_rolls_back_all_outputs() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1, "name": "Alice"}]));
+    });
+    let config = compression_config(
+        &output_path,
+        &server.url("/users"),
+        "none",
+        "transactional = true\nappend = { path = \"history.csv\" }",
+    );
+
+    // 目錄輸出的位置被同名檔案佔用，寫入失敗
+    std::fs::write(temp_dir.path().join("users_output"), "blocked")?;
+    assert!(run(&config).await.is_err());
+    let mut files: Vec<String> = std::fs::read_dir(temp_dir.path())?
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["users_output"]);
+
+    std::fs::remove_file(temp_dir.path().join("users_output"))?;
+    run(&config).await?;
+    assert!(std::fs::read_to_string(temp_dir.path().join("history.csv"))?.contains("Alice"));
+    assert!(temp_dir.path().join("users_output/output.csv").exists());
+    let mut files: Vec<String> = std::fs::read_dir(temp_dir.path().join("users_output"))?
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["output.csv", "processed_data.json"]);
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::output_encryption::decrypt_output;
+use std::io::Read;
+use tempfile::TempDir;
+
+fn encryption_config(output_path: &str, endpoint: &str, compression: &str) -> String {
+    sequence_config([api_pipeline(
+        "patients",
+        endpoint,
+        output_path,
+        &format!("[load]\noutput_formats = [\"csv\"]\n\n[load.compression]\n{compression}"),
+    )])
+}
+
+/// 測試 zip_aes 需要密碼才能讀取 ZIP 項目，aes_gcm 加密整個 tar.gz 並加上 .enc
+#[tokio::test]
+async fn test_encrypted_outputs() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let key_file = temp_dir.path().join("output.key");
+    std::fs::write(&key_file, "s3cret\n")?;
+    let key_file = slash_path(&key_file);
+    let server = MockServer::start();
+    let patients = server.mock(|when, then| {
+        when.method(GET).path("/patients");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1, "ssn": "123-45-6789"}]));
+    });
+
+    run(&encryption_config(
+        &output_path,
+        &server.url("/patients"),
+        &format!("[load.compression.encryption]\nkey_file = \"{key_file}\""),
+    ))
+    .await?;
+    let data = std::fs::read(temp_dir.path().join("patients_output.zip"))?;
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(data))?;
+    assert!(zip.by_name("output.csv").is_err());
+    let mut csv = String::new();
+    zip.by_name_decrypt("output.csv", b"s3cret")?
+        .read_to_string(&mut csv)?;
+    assert!(csv.contains("123-45-6789"));
+
+    run(&encryption_config(
+        &output_path,
+        &server.url("/patients"),
+        &format!(
+            "format = \"tar.gz\"\n\n[load.compression.encryption]\nmethod = \"aes_gcm\"\nkey_file = \"{key_file}\""
+        ),
+    ))
+    .await?;
+    assert!(!temp_dir.path().join("patients_output.tar.gz").exists());
+    let sealed = std::fs::read(temp_dir.path().join("patients_output.tar.gz.enc"))?;
+    assert!(decrypt_output("wrong", &sealed).is_err());
+    let archive = decrypt_output("s3cret", &sealed)?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive.as_slice()));
+    let names: Vec<String> = tar
+        .entries()?
+        .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+        .collect();
+    assert!(names.contains(&"output.csv".to_string()));
+    patients.assert_hits(2);
+
+    // zip_aes 只能用於 ZIP，金鑰來源需擇一
+    for compression in [
+        format!("format = \"none\"\n\n[load.compression.encryption]\nkey_file = \"{key_file}\""),
+        "[load.compression.encryption]\nmethod = \"aes_gcm\"".to_string(),
+    ] {
+        let config = SequenceConfig::from_toml_str(&encryption_config(
+            &output_path,
+            &server.url("/patients"),
+            &compression,
+        ))?;
+        assert!(config.validate().is_err());
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path, EXECUTION_ID};
+use httpmock::prelude::*;
+use samll_etl::core::output_manifest::OutputManifest;
+use std::io::Read;
+use tempfile::TempDir;
+
+fn manifest_config(output_path: &str, endpoint: &str, load: &str) -> String {
+    sequence_config([api_pipeline(
+        "orders",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[load]
+output_formats = ["csv", "json"]
+max_records_per_file = 2
+
+{load}
+"#
+        ),
+    )])
+}
+
+/// 測試 manifest.json 列出每個輸出檔的大小、SHA-256 與記錄數，放在輸出檔旁時另列出輸出檔本身
+#[tokio::test]
+async fn test_manifest_inside_and_alongside() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    });
+
+    run(&manifest_config(
+        &output_path,
+        &server.url("/orders"),
+        "[load.manifest]",
+    ))
+    .await?;
+    let data = std::fs::read(temp_dir.path().join("orders_output.zip"))?;
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(data))?;
+    let mut json = String::new();
+    zip.by_name("manifest.json")?.read_to_string(&mut json)?;
+    let manifest: OutputManifest = serde_json::from_str(&json)?;
+
+    assert_eq!(manifest.pipeline, "orders");
+    assert_eq!(manifest.execution_id, EXECUTION_ID);
+    assert!(manifest.output.is_none());
+    let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "output_0001.csv",
+            "output_0002.csv",
+            "processed_data_0001.json",
+            "processed_data_0002.json"
+        ]
+    );
+    assert_eq!(manifest.files[1].records, Some(1));
+    assert_eq!(manifest.total_records(), 6);
+    for file in &manifest.files {
+        let mut content = Vec::new();
+        zip.by_name(&file.path)?.read_to_end(&mut content)?;
+        assert!(file.verify(&content), "{} checksum mismatch", file.path);
+    }
+
+    run(&manifest_config(
+        &output_path,
+        &server.url("/orders"),
+        r#"[load.manifest]
+location = "alongside"
+
+[load.compression]
+format = "tar.gz""#,
+    ))
+    .await?;
+    let manifest: OutputManifest = serde_json::from_str(&std::fs::read_to_string(
+        temp_dir.path().join("orders_output.tar.gz.manifest.json"),
+    )?)?;
+    let output = manifest
+        .output
+        .expect("alongside manifest lists the archive");
+    assert_eq!(output.path, "orders_output.tar.gz");
+    assert!(output.verify(&std::fs::read(
+        temp_dir.path().join("orders_output.tar.gz")
+    )?));
+    assert_eq!(manifest.files.len(), 4);
+    Ok(())
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use tempfile::TempDir;
+
+fn partition_config(output_path: &str, endpoint: &str, load: &str, format: &str) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[load]
+output_formats = ["csv", "json"]
+{load}
+
+[load.compression]
+format = "{format}"
+"#
+        ),
+    )])
+}
+
+/// hive 或 flat 佈局，依 country 分區
+fn partitioned(layout: &str) -> String {
+    format!("partition_by = \"country\"\npartition_layout = \"{layout}\"")
+}
+
+/// 測試 partition_by：ZIP 內的 hive 目錄階層與未壓縮的 flat 檔案
+#[tokio::test]
+async fn test_partitioned_load_layouts() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "country": "US"},
+            {"id": 2, "country": "TW"},
+            {"id": 3, "country": "US"}
+        ]));
+    });
+    let endpoint = server.url("/users");
+
+    let zip_path = run(&partition_config(
+        &output_path,
+        &endpoint,
+        &partitioned("hive"),
+        "zip",
+    ))
+    .await?
+    .remove(0)
+    .output_path;
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(zip_path)?)?;
+    let mut names: Vec<String> = zip.file_names().map(|name| name.to_string()).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "country=TW/part-0.csv",
+            "country=TW/part-0.json",
+            "country=US/part-0.csv",
+            "country=US/part-0.json"
+        ]
+    );
+    let us_rows = std::io::read_to_string(zip.by_name("country=US/part-0.csv")?)?;
+    assert_eq!(us_rows.lines().count(), 3);
+
+    run(&partition_config(
+        &output_path,
+        &endpoint,
+        &partitioned("flat"),
+        "none",
+    ))
+    .await?;
+    let dir = temp_dir.path().join("users_output");
+    assert!(dir.join("output_TW.csv").exists());
+    let us: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(
+        dir.join("processed_data_US.json"),
+    )?)?;
+    assert_eq!(us.len(), 2);
+
+    Ok(())
+}
+
+/// 測試 max_records_per_file：未分區與 hive 分區輸出都依筆數上限編號分檔
+#[tokio::test]
+async fn test_max_records_per_file_splits_output() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "country": "US"},
+            {"id": 2, "country": "US"},
+            {"id": 3, "country": "US"},
+            {"id": 4, "country": "TW"},
+            {"id": 5, "country": "TW"}
+        ]));
+    });
+    let endpoint = server.url("/users");
+
+    let chunked = partition_config(&output_path, &endpoint, "max_records_per_file = 2", "zip");
+    let zip_path = run(&chunked).await?.remove(0).output_path;
+    let mut zip = zip::ZipArchive::new(std::fs::File::open(zip_path)?)?;
+    let mut names: Vec<String> = zip.file_names().map(|name| name.to_string()).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "output_0001.csv",
+            "output_0002.csv",
+            "output_0003.csv",
+            "processed_data_0001.json",
+            "processed_data_0002.json",
+            "processed_data_0003.json"
+        ]
+    );
+    let last: Vec<serde_json::Value> =
+        serde_json::from_reader(zip.by_name("processed_data_0003.json")?)?;
+    assert_eq!(last.len(), 1);
+
+    let load = format!("max_records_per_file = 2\n{}", partitioned("hive"));
+    let zip_path = run(&partition_config(&output_path, &endpoint, &load, "zip"))
+        .await?
+        .remove(0)
+        .output_path;
+    let zip = zip::ZipArchive::new(std::fs::File::open(zip_path)?)?;
+    let mut names: Vec<&str> = zip
+        .file_names()
+        .filter(|name| name.ends_with(".csv"))
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "country=TW/part-0001.csv",
+            "country=US/part-0001.csv",
+            "country=US/part-0002.csv"
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use tempfile::TempDir;
+
+fn join_config(output_path: &str, server_url: &str) -> String {
+    sequence_config([
+        api_pipeline("users", &format!("{server_url}/users"), output_path, ""),
+        api_pipeline("orders", &format!("{server_url}/orders"), output_path, ""),
+        pipeline(
+            "user_orders",
+            output_path,
+            r#"
+dependencies = ["users", "orders"]
+
+[source]
+type = "join"
+
+[source.join]
+left = "users"
+right = "orders"
+left_key = "id"
+right_key = "user_id"
+type = "left"
+"#,
+        ),
+    ])
+}
+
+/// 測試 source.join：依設定的鍵合併兩個上游 Pipeline 的輸出
+#[tokio::test]
+async fn test_left_join_of_two_pipelines() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "name": "Alice"},
+            {"id": 2, "name": "Bob"}
+        ]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(200).json_body(serde_json::json!([
+            {"order_id": "A", "user_id": 1, "total": 30},
+            {"order_id": "B", "user_id": 1, "total": 12}
+        ]));
+    });
+
+    let results = run(&join_config(&output_path, &server.base_url())).await?;
+
+    let joined = &results[2].records;
+    assert_eq!(joined.len(), 3);
+    assert_eq!(joined[0].data["name"], "Alice");
+    assert_eq!(joined[0].data["order_id"], "A");
+    assert_eq!(joined[1].data["total"], 12);
+    assert_eq!(joined[2].data["name"], "Bob");
+    assert!(!joined[2].data.contains_key("order_id"));
+    Ok(())
+}
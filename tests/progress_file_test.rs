@@ -0,0 +1,67 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::{
+    progress_file::{read_progress_file, PipelineProgressStatus, PipelineStage},
+    sequence_state::SequenceStatus,
+};
+use tempfile::TempDir;
+
+fn progress_config(output_path: &str, users: &str, orders: &str) -> String {
+    sequence_config([
+        api_pipeline("users", users, output_path, ""),
+        api_pipeline("orders", orders, output_path, ""),
+    ])
+}
+
+/// 測試進度檔：成功的 Pipeline 記錄各階段筆數，失敗的 Pipeline 保留所在階段與錯誤
+#[tokio::test]
+async fn test_progress_file_reflects_run() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    });
+    server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(404);
+    });
+
+    let config = SequenceConfig::from_toml_str(&progress_config(
+        &output_path,
+        &server.url("/users"),
+        &server.url("/orders"),
+    ))?;
+    config.validate()?;
+
+    let progress_path = temp_dir.path().join("progress.json");
+    let result = build_sequence(&config, "progress_run")
+        .with_progress_file(&progress_path)
+        .execute_all()
+        .await;
+    assert!(result.is_err());
+
+    let progress = read_progress_file(&progress_path)?;
+    assert_eq!(progress.execution_id, "progress_run");
+    assert_eq!(progress.status, SequenceStatus::Failed);
+    assert!(progress.current_pipeline.is_none());
+
+    let users = &progress.pipelines[0];
+    assert_eq!(users.status, PipelineProgressStatus::Completed);
+    assert_eq!(users.records_extracted, Some(3));
+    assert_eq!(users.records_loaded, Some(3));
+    assert!(users.started_at.is_some() && users.finished_at.is_some());
+
+    let orders = &progress.pipelines[1];
+    assert_eq!(orders.status, PipelineProgressStatus::Failed);
+    assert_eq!(orders.stage, Some(PipelineStage::Extract));
+    assert!(orders.error.as_deref().unwrap().contains("404"));
+
+    Ok(())
+}
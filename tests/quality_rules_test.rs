@@ -0,0 +1,71 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::quality_rules::QualityReport;
+use tempfile::TempDir;
+
+fn quality_config(output_path: &str, endpoint: &str, email_severity: &str) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[load.compression]
+format = "none"
+
+[[quality.rules]]
+check = "unique(id)"
+
+[[quality.rules]]
+name = "email coverage"
+check = "null_rate(email) < 0.05"
+severity = "{email_severity}"
+"#
+        ),
+    )])
+}
+
+/// 測試 error 等級規則失敗時中止並寫出報告；warn 等級只記錄在報告中
+#[tokio::test]
+async fn test_quality_rules_fail_or_warn_by_severity() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "email": "a@example.com"},
+            {"id": 2, "email": null}
+        ]));
+    });
+    let endpoint = server.url("/users");
+
+    let error = run(&quality_config(&output_path, &endpoint, "error"))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("email coverage"));
+    assert!(!temp_dir.path().join("users_output").exists());
+    let report: QualityReport = serde_json::from_slice(&std::fs::read(
+        temp_dir.path().join("users_quality_report.json"),
+    )?)?;
+    assert!(!report.passed);
+    assert_eq!(report.records, 2);
+    assert!(report.rules[0].passed);
+    assert_eq!(report.rules[1].value, serde_json::json!(0.5));
+
+    run(&quality_config(&output_path, &endpoint, "warn")).await?;
+    let report: QualityReport = serde_json::from_slice(&std::fs::read(
+        temp_dir.path().join("users_output/quality_report.json"),
+    )?)?;
+    assert!(report.passed);
+    assert!(!report.rules[1].passed);
+
+    let invalid =
+        quality_config(&output_path, &endpoint, "warn").replace("unique(id)", "unique(id) > 1");
+    assert!(SequenceConfig::from_toml_str(&invalid)?.validate().is_err());
+    Ok(())
+}
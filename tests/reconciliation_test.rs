@@ -0,0 +1,70 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::core::transform_steps::register_transform_step;
+use tempfile::TempDir;
+
+fn reconciliation_config(output_path: &str, endpoint: &str, transform: &str) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[load.compression]
+format = "none"
+
+[reconciliation]
+on_mismatch = "fail"
+{transform}"#
+        ),
+    )])
+}
+
+/// 測試已回報的丟棄不算差異，未回報的遺失記錄依 on_mismatch = "fail" 中止
+#[tokio::test]
+async fn test_reconciliation_catches_silent_drops() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "email": "a@example.com"},
+            {"id": 2, "email": null},
+            {"id": 3, "email": "c@example.com"}
+        ]));
+    });
+    let endpoint = server.url("/users");
+
+    let validated = r#"
+[transform.validation]
+required_fields = ["email"]
+on_invalid = "drop"
+"#;
+    let results = run(&reconciliation_config(&output_path, &endpoint, validated)).await?;
+    let reconciliation = &results[0].metadata["reconciliation"];
+    assert_eq!(reconciliation["extracted"], 3);
+    assert_eq!(reconciliation["transformed"], 2);
+    assert_eq!(reconciliation["transform_dropped"], 1);
+    assert_eq!(reconciliation["loaded"], 2);
+    assert_eq!(reconciliation["passed"], true);
+
+    register_transform_step("drop_last_user", |mut records: Vec<_>| {
+        records.pop();
+        Ok(records)
+    });
+    let lossy = r#"
+[transform.operations]
+custom = ["drop_last_user"]
+"#;
+    let error = run(&reconciliation_config(&output_path, &endpoint, lossy))
+        .await
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("extract -> transform: expected 3 records, got 2"));
+    Ok(())
+}
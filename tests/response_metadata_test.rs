@@ -0,0 +1,81 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use tempfile::TempDir;
+
+fn response_metadata_config(output_path: &str, base_url: &str, target: &str) -> String {
+    sequence_config([
+        api_pipeline("users", &format!("{base_url}/users"), output_path, ""),
+        api_pipeline(
+            "details",
+            &format!("{base_url}/users/{{id}}"),
+            output_path,
+            &format!(
+                r#"
+[source.data_source]
+use_previous_output = true
+from_pipeline = "users"
+
+[source.response_metadata]
+headers = ["ETag", "X-Request-Id"]
+target = "{target}"
+"#
+            ),
+        ),
+    ])
+}
+
+/// 測試參數化呼叫的狀態碼、標頭與延遲加到記錄欄位或 Pipeline metadata
+#[tokio::test]
+async fn test_response_metadata_on_records_and_metadata() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}]));
+    });
+    for id in [1, 2] {
+        server.mock(|when, then| {
+            when.method(GET).path(format!("/users/{}", id));
+            then.status(200)
+                .header("ETag", format!("\"v{}\"", id))
+                .header("X-Request-Id", format!("req-{}", id))
+                .json_body(serde_json::json!({"id": id, "name": format!("user {}", id)}));
+        });
+    }
+
+    let results = run(&response_metadata_config(
+        &output_path,
+        &server.base_url(),
+        "record",
+    ))
+    .await?;
+    let details = &results[1].records;
+    assert_eq!(details.len(), 2);
+    for record in details.iter() {
+        let id = record.data["id"].as_i64().unwrap();
+        assert_eq!(record.data["_http_status"], 200);
+        assert!(record.data["_http_latency_ms"].is_u64());
+        assert_eq!(record.data["_http_etag"], format!("\"v{}\"", id));
+        assert_eq!(record.data["_http_x_request_id"], format!("req-{}", id));
+    }
+
+    let results = run(&response_metadata_config(
+        &output_path,
+        &server.base_url(),
+        "metadata",
+    ))
+    .await?;
+    assert!(!results[1].records[0].data.contains_key("_http_status"));
+    let responses = results[1].metadata["responses"].as_array().unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["status"], 200);
+    assert_eq!(responses[0]["records"], 1);
+    assert_eq!(responses[0]["headers"]["x-request-id"], "req-1");
+    assert!(responses[0]["url"].as_str().unwrap().ends_with("/users/1"));
+    Ok(())
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use anyhow::Result;
+use common::{pipeline, sequence_config, slash_path};
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::{contextual_pipeline::SequenceAwarePipeline, Storage};
+use samll_etl::LocalStorage;
+use tempfile::TempDir;
+
+fn s3_config(output_path: &str, s3: &str) -> String {
+    sequence_config([pipeline(
+        "orders",
+        output_path,
+        &format!(
+            r#"
+extract.field_mapping = {{ "customer.id" = "customer_id" }}
+
+[source]
+type = "s3"
+
+[source.s3]
+bucket = "landing-zone"
+{s3}
+"#
+        ),
+    )])
+}
+
+/// 測試 S3 來源：列出前綴下的 CSV / JSON / NDJSON 物件，經欄位映射後加上物件鍵
+///
+/// 以本機存儲代替 bucket，驗證與 S3 存儲共用的列出與讀取流程。
+#[tokio::test]
+async fn test_s3_source_reads_objects_under_prefix() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let bucket = LocalStorage::new(temp_dir.path().join("bucket").display().to_string());
+    bucket
+        .write_file(
+            "landing/orders/2024-01-01.json",
+            br#"[{"id": 1, "customer": {"id": "c1"}}]"#,
+        )
+        .await?;
+    bucket
+        .write_file(
+            "landing/orders/2024-01-02.ndjson",
+            b"{\"id\": 2, \"customer\": {\"id\": \"c2\"}}\n{\"id\": 3}\n",
+        )
+        .await?;
+    bucket
+        .write_file("landing/orders/legacy.csv", b"id,total\n4,40\n")
+        .await?;
+    bucket.write_file("landing/orders/_SUCCESS", b"").await?;
+    bucket
+        .write_file("landing/refunds/r.json", br#"[{"id": 9}]"#)
+        .await?;
+
+    let config =
+        SequenceConfig::from_toml_str(&s3_config(&output_path, r#"prefix = "landing/orders/""#))?;
+    // 未以 --features s3 編譯時設定檢查會提示需要該功能
+    assert_eq!(config.validate().is_ok(), cfg!(feature = "s3"));
+
+    let definition = config.pipelines[0].clone();
+    let s3 = definition.source.s3.clone().unwrap();
+    let pipeline = SequenceAwarePipeline::new(
+        definition.name.clone(),
+        LocalStorage::new(output_path.clone()),
+        definition,
+    );
+    let records = pipeline.read_objects(&bucket, &s3).await?;
+
+    let summary: Vec<(String, String)> = records
+        .iter()
+        .map(|r| (r.data["id"].to_string(), r.data["source_key"].to_string()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("1", "\"landing/orders/2024-01-01.json\""),
+            ("2", "\"landing/orders/2024-01-02.ndjson\""),
+            ("3", "\"landing/orders/2024-01-02.ndjson\""),
+            ("\"4\"", "\"landing/orders/legacy.csv\""),
+        ]
+        .map(|(id, key)| (id.to_string(), key.to_string()))
+    );
+    assert_eq!(records[0].data["customer_id"], "c1");
+
+    // 不支援的格式不通過設定檢查
+    let config = SequenceConfig::from_toml_str(&s3_config(&output_path, r#"format = "parquet""#))?;
+    assert!(config.validate().is_err());
+    Ok(())
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, sequence_config_with, slash_path};
+use httpmock::prelude::*;
+use samll_etl::app::pipelines::sequence_backfill::{run_backfill, BackfillPlan};
+use samll_etl::app::pipelines::sequence_foreach::ForeachStatus;
+use samll_etl::app::pipelines::sequence_lint::lint_str;
+use samll_etl::app::RunOptions;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn backfill_config(working_dir: &str, base_url: &str) -> String {
+    sequence_config_with(
+        &format!("global.working_directory = \"{working_dir}\""),
+        [api_pipeline(
+            "orders",
+            &format!("{base_url}/orders?from={{{{window.start}}}}&to={{{{window.end}}}}"),
+            &format!("{working_dir}/output"),
+            r#"
+[load]
+filename_pattern = "orders_{{window.start}}_{{window.last}}"
+
+[load.compression]
+format = "none"
+"#,
+        )],
+    )
+}
+
+/// 測試依時間窗執行序列：時間窗提供給端點與檔名，並行執行後依日期彙整報告
+#[tokio::test]
+async fn test_backfill_runs_sequence_per_window() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let working_dir = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let first = server.mock(|when, then| {
+        when.method(GET)
+            .path("/orders")
+            .query_param("from", "2024-01-01")
+            .query_param("to", "2024-01-03");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}]));
+    });
+    let second = server.mock(|when, then| {
+        when.method(GET)
+            .path("/orders")
+            .query_param("from", "2024-01-03")
+            .query_param("to", "2024-01-05");
+        then.status(500);
+    });
+    let last = server.mock(|when, then| {
+        when.method(GET)
+            .path("/orders")
+            .query_param("from", "2024-01-05")
+            .query_param("to", "2024-01-06");
+        then.status(200).json_body(serde_json::json!([{"id": 3}]));
+    });
+
+    let content = backfill_config(&working_dir, &server.base_url());
+    let lint = lint_str("backfill.toml", &content);
+    assert!(lint
+        .diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.code != "unresolved_placeholder"));
+    let config = SequenceConfig::from_toml_str(&content)?;
+    config.validate()?;
+
+    // 失敗的時間窗不影響其他時間窗
+    let plan = BackfillPlan::parse("2024-01-01", "2024-01-05", "2d")?
+        .with_concurrency(2)
+        .with_continue_on_error(true);
+    let options = RunOptions {
+        execution_id: Some("q1".to_string()),
+        ..RunOptions::default()
+    };
+    let report = run_backfill(&config, &options, &plan).await?;
+
+    let ids: Vec<&str> = report
+        .windows
+        .iter()
+        .map(|window| window.execution_id.as_str())
+        .collect();
+    assert_eq!(ids, ["q1_2024-01-01", "q1_2024-01-03", "q1_2024-01-05"]);
+    assert_eq!(report.count(ForeachStatus::Succeeded), 2);
+    assert_eq!(report.windows[1].status, ForeachStatus::Failed);
+    assert_eq!(report.records, 3);
+    assert!(!report.all_succeeded());
+    assert!(temp_dir
+        .path()
+        .join("output/orders_2024-01-01_2024-01-02")
+        .exists());
+    assert!(temp_dir
+        .path()
+        .join("output/orders_2024-01-05_2024-01-05")
+        .exists());
+    first.assert();
+    second.assert();
+    last.assert();
+
+    // 預設在失敗後停止，之後的時間窗不執行
+    let plan = BackfillPlan::parse("2024-01-03", "2024-01-06", "2d")?;
+    let report = run_backfill(&config, &options, &plan).await?;
+    assert_eq!(report.windows.len(), 1);
+    assert_eq!(report.not_run, 1);
+    assert_eq!(report.count(ForeachStatus::Failed), 1);
+    last.assert_hits(1);
+
+    let report_path = temp_dir.path().join("reports/backfill.json");
+    report.write_json(&report_path)?;
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(report_path)?)?;
+    assert_eq!(json["step"], "2d");
+    assert_eq!(json["windows"][0]["item"]["start"], "2024-01-03");
+    Ok(())
+}
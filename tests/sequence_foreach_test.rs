@@ -0,0 +1,121 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, sequence_config_with, slash_path};
+use httpmock::prelude::*;
+use samll_etl::app::pipelines::sequence_foreach::{run_foreach, ForeachStatus};
+use samll_etl::app::RunOptions;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+fn foreach_config(working_dir: &str, base_url: &str, foreach: &str) -> String {
+    let output_path = format!("{working_dir}/output");
+    sequence_config_with(
+        &format!(
+            r#"
+[sequence.foreach]
+name = "tenant"
+key = "id"
+{foreach}
+
+[global]
+working_directory = "{working_dir}"
+"#
+        ),
+        [
+            api_pipeline("tenants", &format!("{base_url}/tenants"), &output_path, ""),
+            api_pipeline(
+                "orders",
+                &format!("{base_url}/tenants/{{{{tenant.id}}}}/orders"),
+                &output_path,
+                r#"
+[source.headers]
+X-Tenant = "{{tenant.name}}"
+
+[load]
+filename_pattern = "orders_{{tenant.id}}"
+
+[load.compression]
+format = "none"
+"#,
+            ),
+        ],
+    )
+}
+
+/// 測試以內嵌清單與上游 Pipeline 輸出逐一執行序列，項目提供給端點、標頭與檔名
+#[tokio::test]
+async fn test_foreach_runs_sequence_per_tenant() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let working_dir = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let tenants = server.mock(|when, then| {
+        when.method(GET).path("/tenants");
+        then.status(200).json_body(serde_json::json!([
+            {"id": "acme", "name": "Acme"},
+            {"id": "globex", "name": "Globex"}
+        ]));
+    });
+    let acme = server.mock(|when, then| {
+        when.method(GET)
+            .path("/tenants/acme/orders")
+            .header("X-Tenant", "Acme");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}]));
+    });
+    let globex = server.mock(|when, then| {
+        when.method(GET)
+            .path("/tenants/globex/orders")
+            .header("X-Tenant", "Globex");
+        then.status(500);
+    });
+
+    // 內嵌清單：globex 失敗後繼續，彙整報告列出兩個項目
+    let config = SequenceConfig::from_toml_str(&foreach_config(
+        &working_dir,
+        &server.base_url(),
+        r#"items = [{ id = "acme", name = "Acme" }, { id = "globex", name = "Globex" }]
+continue_on_error = true"#,
+    ))?;
+    config.validate()?;
+    let options = RunOptions {
+        execution_id: Some("tenants_run".to_string()),
+        skip: vec!["tenants".to_string()],
+        ..RunOptions::default()
+    };
+    let report = run_foreach(&config, &options).await?;
+
+    assert_eq!(report.entries.len(), 2);
+    assert_eq!(report.entries[0].execution_id, "tenants_run_acme");
+    assert_eq!(report.entries[0].status, ForeachStatus::Succeeded);
+    assert_eq!(report.entries[0].records, 2);
+    assert_eq!(report.entries[1].status, ForeachStatus::Failed);
+    assert!(!report.all_succeeded());
+    assert!(temp_dir.path().join("output/orders_acme").exists());
+    acme.assert();
+    globex.assert();
+    tenants.assert_hits(0);
+
+    // 上游 Pipeline：先執行 tenants 一次，以其輸出記錄為項目
+    let config = SequenceConfig::from_toml_str(&foreach_config(
+        &working_dir,
+        &server.base_url(),
+        r#"from_pipeline = "tenants""#,
+    ))?;
+    config.validate()?;
+    let report = run_foreach(
+        &config,
+        &RunOptions {
+            execution_id: Some("upstream_run".to_string()),
+            ..RunOptions::default()
+        },
+    )
+    .await?;
+    tenants.assert();
+    assert_eq!(report.entries.len(), 2);
+    assert_eq!(report.entries[0].item["name"], "Acme");
+    assert_eq!(report.entries[0].pipelines, 1);
+    assert_eq!(report.not_run, 0);
+    assert_eq!(report.count(ForeachStatus::Failed), 1);
+    Ok(())
+}
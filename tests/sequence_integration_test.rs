@@ -250,7 +250,7 @@ async fn test_pipeline_context_data_passing() -> Result<()> {
     );
 
     // 測試數據獲取
-    let retrieved_data = context.get_pipeline_data("previous_pipeline");
+    let retrieved_data = context.get_pipeline_data("previous_pipeline")?;
     assert!(retrieved_data.is_some());
     assert_eq!(retrieved_data.unwrap().len(), 1);
 
@@ -274,7 +274,7 @@ async fn test_pipeline_context_data_passing() -> Result<()> {
     );
     api_records.push(Record { data: api_data });
 
-    let merged = context.merge_with_previous("previous_pipeline", api_records);
+    let merged = context.merge_with_previous("previous_pipeline", api_records)?;
     assert!(!merged.is_empty());
     assert!(merged[0].data.contains_key("test_field"));
     assert!(merged[0].data.contains_key("api_field"));
@@ -388,6 +388,7 @@ output_formats = ["json"]
 }
 
 #[tokio::test]
+#[allow(deprecated)]
 async fn test_pipeline_sequence_metrics() -> Result<()> {
     let temp_dir = TempDir::new()?;
     let temp_path = temp_dir.path().to_str().unwrap();
@@ -435,6 +436,11 @@ async fn test_pipeline_sequence_metrics() -> Result<()> {
         assert!(result.duration.as_millis() > 0);
         assert!(!result.pipeline_name.is_empty());
         assert!(!result.output_path.is_empty());
+
+        // 驗證吞吐量報告
+        let throughput = result.metadata.get("throughput").unwrap();
+        assert!(throughput["extract"]["records_per_sec"].is_number());
+        assert!(throughput["bytes_written"].as_u64().unwrap() > 0);
     }
 
     // 測試執行摘要
@@ -443,6 +449,7 @@ async fn test_pipeline_sequence_metrics() -> Result<()> {
     assert!(summary.contains_key("total_pipelines"));
     assert!(summary.contains_key("total_records"));
     assert!(summary.contains_key("total_duration_ms"));
+    assert!(summary.contains_key("throughput"));
 
     Ok(())
 }
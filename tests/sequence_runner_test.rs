@@ -0,0 +1,211 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, sequence_config_with, slash_path};
+use httpmock::prelude::*;
+use samll_etl::app::{run_sequence, RunOptions};
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::run_report::{RunReport, RunStatus};
+use samll_etl::utils::budget::ExecutionBudget;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn runner_config(working_dir: &str, users: &str, orders: &str, orders_overrides: &str) -> String {
+    let output_path = format!("{working_dir}/output");
+    sequence_config_with(
+        &format!("sequence.name = \"runner-test\"\nglobal.working_directory = \"{working_dir}\""),
+        [
+            api_pipeline("users", users, &output_path, ""),
+            api_pipeline("orders", orders, &output_path, orders_overrides),
+        ],
+    )
+}
+
+/// 測試嵌入式 API：失敗時返回報告與退出碼，以相同 execution_id 續跑後成功
+#[tokio::test]
+async fn test_run_sequence_reports_failure_and_resumes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let working_dir = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let users = server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}]));
+    });
+    let mut orders = server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(404);
+    });
+    let config = SequenceConfig::from_toml_str(&runner_config(
+        &working_dir,
+        &server.url("/users"),
+        &server.url("/orders"),
+        "",
+    ))?;
+
+    let report_path = temp_dir.path().join("report.json");
+    let report = run_sequence(
+        config.clone(),
+        RunOptions {
+            execution_id: Some("embedded_run".to_string()),
+            run_report: Some(report_path.clone()),
+            ..RunOptions::default()
+        },
+    )
+    .await;
+
+    assert_eq!(report.execution_id, "embedded_run");
+    assert_eq!(report.sequence.as_deref(), Some("runner-test"));
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_ne!(report.exit_code, 0);
+    let statuses: Vec<&str> = report.pipelines.iter().map(|p| p.status.as_str()).collect();
+    assert_eq!(statuses, ["completed", "failed"]);
+    let written: RunReport = serde_json::from_slice(&std::fs::read(&report_path)?)?;
+    assert_eq!(written, report);
+
+    orders.delete();
+    server.mock(|when, then| {
+        when.method(GET).path("/orders");
+        then.status(200).json_body(serde_json::json!([{"id": 10}]));
+    });
+    let report = run_sequence(
+        config,
+        RunOptions {
+            resume: Some("embedded_run".to_string()),
+            ..RunOptions::default()
+        },
+    )
+    .await;
+
+    assert_eq!(report.status, RunStatus::Succeeded);
+    assert_eq!(report.exit_code, 0);
+    assert!(report.error.is_none());
+    // 續跑不會重新擷取已完成的 users
+    users.assert_hits(1);
+    // 未指定路徑時寫入工作目錄下的狀態目錄
+    assert!(temp_dir
+        .path()
+        .join(".sequence_state/embedded_run.run_report.json")
+        .exists());
+    Ok(())
+}
+
+/// 測試 only 篩選與設定驗證失敗
+#[tokio::test]
+async fn test_run_sequence_options() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let working_dir = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([{"id": 1}]));
+    });
+    let config = SequenceConfig::from_toml_str(&runner_config(
+        &working_dir,
+        &server.url("/users"),
+        &server.url("/orders"),
+        "",
+    ))?;
+
+    let report = run_sequence(
+        config.clone(),
+        RunOptions {
+            only: vec!["users".to_string()],
+            ..RunOptions::default()
+        },
+    )
+    .await;
+    assert_eq!(report.status, RunStatus::Succeeded);
+    assert!(report.execution_id.starts_with("seq_"));
+    assert_eq!(report.pipelines.len(), 1);
+    assert_eq!(report.pipelines[0].records, Some(1));
+
+    let mut invalid = config;
+    invalid.sequence.execution_order.push("missing".to_string());
+    let report = run_sequence(invalid, RunOptions::default()).await;
+    assert_eq!(report.status, RunStatus::Failed);
+    assert_eq!(report.error.unwrap().category, "Configuration");
+    Ok(())
+}
+
+/// 測試接近截止時間時保存剩餘的參數化呼叫，下一次執行接續處理並移除剩餘參數檔
+#[tokio::test]
+async fn test_run_sequence_continues_remaining_calls_after_deadline() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let working_dir = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let mut users = server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1}, {"id": 2}]));
+    });
+    let first = server.mock(|when, then| {
+        when.method(GET).path("/orders/1");
+        then.status(200)
+            .json_body(serde_json::json!([{"order": 10}]));
+    });
+    let second = server.mock(|when, then| {
+        when.method(GET).path("/orders/2");
+        then.status(200)
+            .json_body(serde_json::json!([{"order": 20}]));
+    });
+    let newer = server.mock(|when, then| {
+        when.method(GET).path("/orders/3");
+        then.status(200)
+            .json_body(serde_json::json!([{"order": 30}]));
+    });
+    let config = SequenceConfig::from_toml_str(&runner_config(
+        &working_dir,
+        &server.url("/users"),
+        &server.url("/orders/{id}"),
+        r#"
+[source.data_source]
+use_previous_output = true
+from_pipeline = "users"
+"#,
+    ))?;
+
+    // 預算已用完：orders 不發出任何呼叫，剩餘參數寫入 checkpoint
+    let report = run_sequence(
+        config.clone(),
+        RunOptions {
+            execution_id: Some("first_invocation".to_string()),
+            budget: Some(ExecutionBudget::new(Duration::ZERO)),
+            ..RunOptions::default()
+        },
+    )
+    .await;
+    assert_eq!(report.status, RunStatus::Deferred);
+    assert_eq!(report.pipelines[1].status, "deferred");
+    assert_eq!(report.pipelines[1].deferred_calls, Some(2));
+    assert_eq!(report.pipelines[1].records, Some(0));
+    let remaining_path = temp_dir
+        .path()
+        .join("output/.checkpoints/orders_remaining.json");
+    let remaining: serde_json::Value = serde_json::from_slice(&std::fs::read(&remaining_path)?)?;
+    assert_eq!(remaining["parameters"].as_array().unwrap().len(), 2);
+    first.assert_hits(0);
+
+    // 下一次執行先處理上次留下的參數，再處理上游這次的輸出（已留下的 id=2 不重複呼叫）
+    users.delete();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 2}, {"id": 3}]));
+    });
+    let report = run_sequence(
+        config,
+        RunOptions {
+            execution_id: Some("second_invocation".to_string()),
+            ..RunOptions::default()
+        },
+    )
+    .await;
+    assert_eq!(report.status, RunStatus::Succeeded);
+    assert_eq!(report.pipelines[1].records, Some(3));
+    first.assert_hits(1);
+    second.assert_hits(1);
+    newer.assert_hits(1);
+    assert!(!remaining_path.exists());
+    Ok(())
+}
@@ -161,7 +161,7 @@ output_formats = ["json"]
     }
 
     // 驗證詳細記錄包含正確的字段映射
-    for record in &results[1].records {
+    for record in results[1].records.iter() {
         assert!(
             record.data.contains_key("detail_user_id"),
             "Missing detail_user_id in record: {:?}",
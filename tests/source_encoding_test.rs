@@ -0,0 +1,43 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use tempfile::TempDir;
+
+fn encoding_config(output_path: &str, endpoint: &str, encoding: &str) -> String {
+    sequence_config([api_pipeline(
+        "products",
+        endpoint,
+        output_path,
+        &format!("[source.encoding]\n{encoding}"),
+    )])
+}
+
+/// 測試 Windows-1252 回應轉為 UTF-8，以及 strict 模式拒絕無效的 UTF-8
+#[tokio::test]
+async fn test_windows_1252_source_is_converted_to_utf8() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/products");
+        then.status(200)
+            .header("Content-Type", "application/json")
+            .body(b"[{\"id\": 1, \"name\": \"Caf\xe9 \x80\"}]");
+    });
+    let endpoint = server.url("/products");
+
+    let results = run(&encoding_config(
+        &output_path,
+        &endpoint,
+        r#"charset = "windows-1252""#,
+    ))
+    .await?;
+    assert_eq!(results[0].records[0].data["name"], "Café €");
+
+    let strict = run(&encoding_config(&output_path, &endpoint, "strict = true")).await;
+    assert!(strict.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::warnings::WarningCode;
+use tempfile::TempDir;
+
+fn typed_mapping_config(output_path: &str, endpoint: &str, on_error: &str) -> String {
+    sequence_config([api_pipeline(
+        "users",
+        endpoint,
+        output_path,
+        &format!(
+            r#"
+[extract.field_mapping]
+id = "user_id"
+"user.age" = {{ field = "age", type = "int", on_error = "{on_error}" }}
+"user.score" = {{ field = "score", type = "float" }}
+"user.active" = {{ field = "active", type = "bool" }}
+"#
+        ),
+    )])
+}
+
+/// 測試 field_mapping 指定型別時寬鬆轉型，無法轉型的值依 on_error 處理
+#[tokio::test]
+async fn test_typed_field_mapping() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200).json_body(serde_json::json!([
+            {"id": 1, "user": {"age": "42", "score": "1,234.5", "active": "yes"}},
+            {"id": 2, "user": {"age": "unknown", "score": 7, "active": true}}
+        ]));
+    });
+
+    let results = run(&typed_mapping_config(
+        &output_path,
+        &server.url("/users"),
+        "null",
+    ))
+    .await?;
+    let records = &results[0].records;
+    assert_eq!(records[0].data["user_id"], 1);
+    assert_eq!(records[0].data["age"], 42);
+    assert_eq!(records[0].data["score"], 1234.5);
+    assert_eq!(records[0].data["active"], true);
+    assert!(records[1].data["age"].is_null());
+    assert_eq!(records[1].data["score"], 7.0);
+    let warning = results[0]
+        .warnings
+        .iter()
+        .find(|w| w.code == WarningCode::TypeCoercionFailed)
+        .expect("coercion warning");
+    assert_eq!(warning.count, 1);
+
+    let results = run(&typed_mapping_config(
+        &output_path,
+        &server.url("/users"),
+        "keep",
+    ))
+    .await?;
+    assert_eq!(results[0].records[1].data["age"], "unknown");
+
+    assert!(run(&typed_mapping_config(
+        &output_path,
+        &server.url("/users"),
+        "fail",
+    ))
+    .await
+    .is_err());
+
+    // 不支援的型別或處理方式不通過設定檢查
+    let config = SequenceConfig::from_toml_str(&typed_mapping_config(
+        &output_path,
+        &server.url("/users"),
+        "skip",
+    ))?;
+    assert!(config.validate().is_err());
+    Ok(())
+}
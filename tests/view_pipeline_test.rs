@@ -0,0 +1,79 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, build_sequence, pipeline, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use samll_etl::core::sequence_state::SequenceStateStore;
+use tempfile::TempDir;
+
+fn view_config(output_path: &str, server_address: &str) -> String {
+    sequence_config([
+        api_pipeline(
+            "users",
+            &format!("http://{server_address}/users"),
+            &format!("{output_path}/api"),
+            "",
+        ),
+        pipeline(
+            "users_csv",
+            &format!("{output_path}/published"),
+            r#"
+dependencies = ["users"]
+
+[source]
+type = "view"
+
+[source.data_source]
+from_pipeline = "users"
+
+[load]
+output_formats = ["csv"]
+"#,
+        ),
+    ])
+}
+
+/// 測試 view：重用先前結果輸出新格式，續跑時只重新執行 view 而不重新呼叫 API
+#[tokio::test]
+async fn test_view_reloads_previous_results_without_refetching() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+    let users_mock = server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .json_body(serde_json::json!([{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}]));
+    });
+
+    let config =
+        SequenceConfig::from_toml_str(&view_config(&output_path, &server.address().to_string()))?;
+    config.validate()?;
+
+    let store = SequenceStateStore::new(temp_dir.path().join("state"));
+    let results = build_sequence(&config, "view_run")
+        .with_state_store(store.clone())
+        .execute_all()
+        .await?;
+    assert_eq!(results[1].records.len(), 2);
+    assert!(temp_dir
+        .path()
+        .join("published/users_csv_output.zip")
+        .exists());
+
+    // 續跑：users 已完成而被跳過，view 仍重新輸出
+    std::fs::remove_file(temp_dir.path().join("published/users_csv_output.zip"))?;
+    let results = build_sequence(&config, "ignored")
+        .with_state_store(store.clone())
+        .resume_from(store.load("view_run")?)
+        .execute_all()
+        .await?;
+    assert_eq!(results.len(), 2);
+    assert!(temp_dir
+        .path()
+        .join("published/users_csv_output.zip")
+        .exists());
+    users_mock.assert_hits(1);
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use anyhow::Result;
+use common::{api_pipeline, run, sequence_config, slash_path};
+use httpmock::prelude::*;
+use samll_etl::config::sequence_config::SequenceConfig;
+use tempfile::TempDir;
+
+/// 測試 source.response_format = "xml"：重複元素成為記錄，並沿用多階層路徑映射
+#[tokio::test]
+async fn test_xml_response_extracts_records() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let output_path = slash_path(temp_dir.path());
+    let server = MockServer::start();
+
+    server.mock(|when, then| {
+        when.method(GET).path("/users");
+        then.status(200)
+            .header("Content-Type", "application/xml")
+            .body(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<users>
+  <user id="1"><name>Alice</name><profile><city>Taipei</city></profile></user>
+  <user id="2"><name>Bob</name><profile><city>Tainan</city></profile></user>
+</users>"#,
+            );
+    });
+
+    let results = run(&sequence_config([api_pipeline(
+        "users",
+        &server.url("/users"),
+        &output_path,
+        r#"
+source.response_format = "xml"
+extract.field_mapping = { "@id" = "user_id", "profile.city" = "city" }
+"#,
+    )]))
+    .await?;
+
+    let records = &results[0].records;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].data["user_id"], "1");
+    assert_eq!(records[0].data["name"], "Alice");
+    assert_eq!(records[1].data["city"], "Tainan");
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_response_format_rejected() {
+    let config = SequenceConfig::from_toml_str(&sequence_config([api_pipeline(
+        "users",
+        "http://localhost/users",
+        "./output",
+        r#"source.response_format = "yaml""#,
+    )]))
+    .unwrap();
+    assert!(config.validate().is_err());
+}